@@ -1,7 +1,14 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::parse_size_arg;
+use crate::args::ClientCertificate;
 use crate::args::ConfigFlag;
 use crate::args::Flags;
+use crate::args::HostCaCertificate;
+use crate::args::IntegrityManifest;
+use crate::args::ModuleOverrides;
+use crate::args::NodeModulesLayout;
+use crate::args::NpmCopyStrategy;
 use crate::util::fs::canonicalize_path;
 use crate::util::path::specifier_parent;
 use crate::util::path::specifier_to_file_path;
@@ -17,6 +24,9 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::validate_child_permissions;
+use deno_runtime::permissions::ChildPermissionsArg;
+use deno_runtime::permissions::WorkerPermissionsConfig;
 use indexmap::IndexMap;
 use std::borrow::Cow;
 use std::collections::BTreeMap;
@@ -335,10 +345,15 @@ impl FilesConfig {
   /// Gets if the provided specifier is allowed based on the includes
   /// and excludes in the configuration file.
   pub fn matches_specifier(&self, specifier: &ModuleSpecifier) -> bool {
-    let file_path = match specifier_to_file_path(specifier) {
-      Ok(file_path) => file_path,
-      Err(_) => return false,
-    };
+    match specifier_to_file_path(specifier) {
+      Ok(file_path) => self.matches_path(&file_path),
+      Err(_) => false,
+    }
+  }
+
+  /// Gets if the provided path is allowed based on the includes
+  /// and excludes in the configuration file.
+  pub fn matches_path(&self, file_path: &Path) -> bool {
     // Skip files which is in the exclude list.
     if self.exclude.iter().any(|i| file_path.starts_with(i)) {
       return false;
@@ -612,6 +627,20 @@ pub struct BenchConfig {
   pub files: FilesConfig,
 }
 
+/// `watch` config representation.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct WatchConfig {
+  /// How long to wait for further changes before restarting, in
+  /// milliseconds.
+  pub debounce: Option<u64>,
+  pub clear_screen: Option<bool>,
+  /// Command to run before the watched operation restarts.
+  pub before_restart: Option<String>,
+  /// Command to run alongside the watched operation once it restarted.
+  pub after_restart: Option<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum LockConfig {
@@ -619,11 +648,267 @@ pub enum LockConfig {
   PathBuf(PathBuf),
 }
 
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedWorkersConfig {
+  permissions: Option<Value>,
+  presets: BTreeMap<String, Value>,
+  preload: Vec<String>,
+}
+
+impl SerializedWorkersConfig {
+  pub fn into_resolved(
+    self,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<WorkerPermissionsConfig, AnyError> {
+    let default = match self.permissions {
+      Some(value) => Some(
+        resolve_worker_permissions(value, config_file_specifier)
+          .context("Failed to parse \"workers.permissions\"")?,
+      ),
+      None => None,
+    };
+    let mut presets = HashMap::with_capacity(self.presets.len());
+    for (name, value) in self.presets {
+      if name == "inherit" || name == "none" {
+        bail!("Worker permissions preset name \"{}\" is reserved", name);
+      }
+      let permissions =
+        resolve_worker_permissions(value, config_file_specifier)
+          .with_context(|| format!("Failed to parse preset \"{name}\""))?;
+      presets.insert(name, permissions);
+    }
+    let config_dir = specifier_parent(config_file_specifier);
+    let preload = self
+      .preload
+      .iter()
+      .map(|specifier| {
+        config_dir.join(specifier).with_context(|| {
+          format!("Failed to resolve preload module \"{specifier}\"")
+        })
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(WorkerPermissionsConfig {
+      default,
+      presets,
+      preload,
+    })
+  }
+}
+
+/// Deserializes worker permissions, resolving any relative paths in the
+/// `read`, `write`, `ffi` and `unix` lists from the directory of the config
+/// file.
+fn resolve_worker_permissions(
+  mut value: Value,
+  config_file_specifier: &ModuleSpecifier,
+) -> Result<ChildPermissionsArg, AnyError> {
+  if let Value::Object(map) = &mut value {
+    let config_dir = specifier_parent(config_file_specifier);
+    for key in ["read", "write", "ffi", "unix"] {
+      if let Some(Value::Array(paths)) = map.get_mut(key) {
+        for path in paths.iter_mut() {
+          if let Value::String(p) = path {
+            let url = config_dir.join(p)?;
+            *p = specifier_to_file_path(&url)?.to_string_lossy().to_string();
+          }
+        }
+      }
+    }
+  }
+  let permissions: ChildPermissionsArg = serde_json::from_value(value)?;
+  validate_child_permissions(&permissions)?;
+  Ok(permissions)
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedWorkspaceConfig {
+  members: Vec<String>,
+}
+
+impl SerializedWorkspaceConfig {
+  pub fn into_resolved(
+    self,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<WorkspaceConfig, AnyError> {
+    let config_dir = specifier_parent(config_file_specifier);
+    let config_dir_path = specifier_to_file_path(&config_dir)?;
+    let mut members = Vec::with_capacity(self.members.len());
+    for member in self.members {
+      let member_path = specifier_to_file_path(&config_dir.join(&member)?)?;
+      if member_path == config_dir_path
+        || !member_path.starts_with(&config_dir_path)
+      {
+        bail!(
+          "Workspace member \"{}\" must be a sub directory of the workspace root",
+          member
+        );
+      }
+      members.push(member_path);
+    }
+    Ok(WorkspaceConfig { members })
+  }
+}
+
+/// The `workspace` section of a config file. Each member is the directory of
+/// a package with its own config file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WorkspaceConfig {
+  pub members: Vec<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedPublishConfig {
+  registry: Option<String>,
+  exclude: Vec<String>,
+}
+
+impl SerializedPublishConfig {
+  pub fn into_resolved(
+    self,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<PublishConfig, AnyError> {
+    let registry = match self.registry {
+      Some(registry) => Some(
+        // ensure there is a trailing slash for the directory
+        ModuleSpecifier::parse(&format!("{}/", registry.trim_end_matches('/')))
+          .with_context(|| format!("Invalid registry url \"{registry}\""))?,
+      ),
+      None => None,
+    };
+    let files = SerializedFilesConfig {
+      include: vec![],
+      exclude: self.exclude,
+    }
+    .into_resolved(config_file_specifier)?;
+    Ok(PublishConfig {
+      registry,
+      exclude: files.exclude,
+    })
+  }
+}
+
+/// The `publish` section of a config file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PublishConfig {
+  /// The registry `deno publish` uploads to.
+  pub registry: Option<ModuleSpecifier>,
+  /// Files of the package directory that aren't published.
+  pub exclude: Vec<PathBuf>,
+}
+
+/// The authentication of a module host in the `auth` section of a config
+/// file. Secrets are never part of the config file, only the names of the
+/// environment variables that hold them:
+///
+/// ```json
+/// {
+///   "auth": {
+///     "deno.example.com": { "tokenEnv": "EXAMPLE_TOKEN" },
+///     "git.example.com:8080": {
+///       "username": "ci",
+///       "passwordEnv": "GIT_PASSWORD"
+///     },
+///     "api.example.com": { "tokenEnv": "API_KEY", "header": "X-Api-Key" },
+///     "registry.example.com": {
+///       "cert": "./certs/client.pem",
+///       "key": "./certs/client.key",
+///       "ca": "./certs/internal-ca.pem"
+///     }
+///   }
+/// }
+/// ```
+///
+/// Hosts requiring mutual TLS get a client certificate and private key, and
+/// hosts signed by an internal certificate authority get a CA certificate
+/// that is only trusted for them. The paths are relative to the config file.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct HostAuthConfig {
+  /// Environment variable containing a bearer token.
+  pub token_env: Option<String>,
+  /// Header to send the token in instead of `Authorization: Bearer`.
+  pub header: Option<String>,
+  pub username: Option<String>,
+  /// Environment variable containing the password for basic auth.
+  pub password_env: Option<String>,
+  /// PEM encoded client certificate for mutual TLS.
+  pub cert: Option<PathBuf>,
+  /// PEM encoded private key of the client certificate.
+  pub key: Option<PathBuf>,
+  /// PEM encoded CA certificate trusted for the host.
+  pub ca: Option<PathBuf>,
+}
+
+impl HostAuthConfig {
+  fn validate(&self, host: &str) -> Result<(), AnyError> {
+    if host.is_empty() || host.contains('/') {
+      bail!(
+        "Invalid auth host \"{}\", expected a hostname with an optional port",
+        host
+      );
+    }
+    if self.cert.is_some() != self.key.is_some() {
+      bail!(
+        "Auth for \"{}\" must specify both \"cert\" and \"key\"",
+        host
+      );
+    }
+    match (&self.token_env, &self.username, &self.password_env) {
+      (Some(_), None, None) => Ok(()),
+      (None, Some(_), Some(_)) if self.header.is_none() => Ok(()),
+      (None, None, None)
+        if (self.cert.is_some() || self.ca.is_some())
+          && self.header.is_none() =>
+      {
+        Ok(())
+      }
+      _ => bail!(
+        "Auth for \"{}\" must either specify \"tokenEnv\" (with an optional \"header\"), \"username\" and \"passwordEnv\", a client \"cert\" and \"key\" or a \"ca\"",
+        host
+      ),
+    }
+  }
+}
+
+/// The `auth` section of a config file, keyed by lowercased host.
+pub type AuthConfig = BTreeMap<String, HostAuthConfig>;
+
+/// Returns the client certificates of the hosts in the `auth` configuration.
+pub fn to_client_certificates(config: &AuthConfig) -> Vec<ClientCertificate> {
+  config
+    .iter()
+    .filter_map(|(host, host_config)| {
+      Some(ClientCertificate {
+        host: host.to_string(),
+        cert_file: host_config.cert.clone()?,
+        key_file: host_config.key.clone()?,
+      })
+    })
+    .collect()
+}
+
+/// Returns the CA certificates of the hosts in the `auth` configuration.
+pub fn to_host_ca_certificates(config: &AuthConfig) -> Vec<HostCaCertificate> {
+  config
+    .iter()
+    .filter_map(|(host, host_config)| {
+      Some(HostCaCertificate {
+        host: host.to_string(),
+        ca_file: host_config.ca.clone()?,
+      })
+    })
+    .collect()
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFileJson {
   pub compiler_options: Option<Value>,
   pub import_map: Option<String>,
+  pub import_map_extends: Option<Value>,
   pub imports: Option<Value>,
   pub scopes: Option<Value>,
   pub lint: Option<Value>,
@@ -632,6 +917,21 @@ pub struct ConfigFileJson {
   pub test: Option<Value>,
   pub bench: Option<Value>,
   pub lock: Option<Value>,
+  pub workers: Option<Value>,
+  pub watch: Option<Value>,
+  pub name: Option<String>,
+  pub version: Option<String>,
+  pub exports: Option<Value>,
+  pub publish: Option<Value>,
+  pub workspace: Option<Value>,
+  pub auth: Option<Value>,
+  pub overrides: Option<Value>,
+  pub integrity: Option<Value>,
+  pub conditions: Option<Value>,
+  pub node_modules_layout: Option<Value>,
+  pub npm_cache_dir: Option<Value>,
+  pub npm_copy_strategy: Option<Value>,
+  pub npm_cache_max_size: Option<Value>,
 }
 
 #[derive(Clone, Debug)]
@@ -829,8 +1129,13 @@ impl ConfigFile {
     self.json.import_map.clone()
   }
 
+  /// Returns the import map embedded in the config file. `importMapExtends`
+  /// becomes the `extends` entry of the import map.
   pub fn to_import_map_value(&self) -> Value {
-    let mut value = serde_json::Map::with_capacity(2);
+    let mut value = serde_json::Map::with_capacity(3);
+    if let Some(extends) = &self.json.import_map_extends {
+      value.insert("extends".to_string(), extends.clone());
+    }
     if let Some(imports) = &self.json.imports {
       value.insert("imports".to_string(), imports.clone());
     }
@@ -841,7 +1146,9 @@ impl ConfigFile {
   }
 
   pub fn is_an_import_map(&self) -> bool {
-    self.json.imports.is_some() || self.json.scopes.is_some()
+    self.json.imports.is_some()
+      || self.json.scopes.is_some()
+      || self.json.import_map_extends.is_some()
   }
 
   pub fn to_fmt_config(&self) -> Result<Option<FmtConfig>, AnyError> {
@@ -978,6 +1285,238 @@ impl ConfigFile {
     Ok(tasks_config)
   }
 
+  pub fn to_worker_permissions_config(
+    &self,
+  ) -> Result<Option<WorkerPermissionsConfig>, AnyError> {
+    if let Some(config) = self.json.workers.clone() {
+      let workers_config: SerializedWorkersConfig =
+        serde_json::from_value(config)
+          .context("Failed to parse \"workers\" configuration")?;
+      Ok(Some(workers_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_watch_config(&self) -> Result<Option<WatchConfig>, AnyError> {
+    if let Some(config) = self.json.watch.clone() {
+      let watch_config: WatchConfig = serde_json::from_value(config)
+        .context("Failed to parse \"watch\" configuration")?;
+      for command in [&watch_config.before_restart, &watch_config.after_restart]
+        .into_iter()
+        .flatten()
+      {
+        deno_task_shell::parser::parse(command).with_context(|| {
+          format!("Failed to parse watch hook command '{command}'")
+        })?;
+      }
+      Ok(Some(watch_config))
+    } else {
+      Ok(None)
+    }
+  }
+
+  /// Returns the `exports` of the package, keyed by export name. A string is
+  /// the main export, which is named `"."`.
+  pub fn to_exports_config(
+    &self,
+  ) -> Result<Option<IndexMap<String, String>>, AnyError> {
+    let exports: IndexMap<String, String> = match self.json.exports.clone() {
+      Some(Value::String(path)) => IndexMap::from([(".".to_string(), path)]),
+      Some(config) => serde_json::from_value(config)
+        .context("Failed to parse \"exports\" configuration")?,
+      None => return Ok(None),
+    };
+    if exports.is_empty() {
+      bail!("The \"exports\" configuration must not be empty");
+    }
+    for (name, path) in &exports {
+      if name != "." && !name.starts_with("./") {
+        bail!(
+          "Export name \"{}\" must be \".\" or start with \"./\"",
+          name
+        );
+      }
+      if !path.starts_with("./") {
+        bail!(
+          "Export \"{}\" must be a path relative to the configuration file starting with \"./\", found \"{}\"",
+          name,
+          path
+        );
+      }
+    }
+    Ok(Some(exports))
+  }
+
+  pub fn to_publish_config(&self) -> Result<Option<PublishConfig>, AnyError> {
+    if let Some(config) = self.json.publish.clone() {
+      let publish_config: SerializedPublishConfig =
+        serde_json::from_value(config)
+          .context("Failed to parse \"publish\" configuration")?;
+      Ok(Some(publish_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_workspace_config(
+    &self,
+  ) -> Result<Option<WorkspaceConfig>, AnyError> {
+    if let Some(config) = self.json.workspace.clone() {
+      let workspace_config: SerializedWorkspaceConfig =
+        serde_json::from_value(config)
+          .context("Failed to parse \"workspace\" configuration")?;
+      Ok(Some(workspace_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_auth_config(&self) -> Result<Option<AuthConfig>, AnyError> {
+    if let Some(config) = self.json.auth.clone() {
+      let auth_config: AuthConfig = serde_json::from_value(config)
+        .context("Failed to parse \"auth\" configuration")?;
+      let mut resolved = AuthConfig::new();
+      for (host, mut host_config) in auth_config {
+        host_config.validate(&host)?;
+        for path in [
+          &mut host_config.cert,
+          &mut host_config.key,
+          &mut host_config.ca,
+        ]
+        .into_iter()
+        .flatten()
+        {
+          let config_dir = specifier_to_file_path(&self.specifier)?
+            .parent()
+            .unwrap()
+            .to_path_buf();
+          *path = config_dir.join(path.as_path());
+        }
+        resolved.insert(host.to_lowercase(), host_config);
+      }
+      Ok(Some(resolved))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_module_overrides(
+    &self,
+  ) -> Result<Option<ModuleOverrides>, AnyError> {
+    if let Some(config) = self.json.overrides.clone() {
+      let overrides: BTreeMap<String, String> = serde_json::from_value(config)
+        .context("Failed to parse \"overrides\" configuration")?;
+      Ok(Some(ModuleOverrides::new(overrides, &self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_integrity_manifest(
+    &self,
+  ) -> Result<Option<IntegrityManifest>, AnyError> {
+    if let Some(config) = self.json.integrity.clone() {
+      let integrity: BTreeMap<String, String> = serde_json::from_value(config)
+        .context("Failed to parse \"integrity\" configuration")?;
+      Ok(Some(IntegrityManifest::new(integrity)?))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_node_conditions(&self) -> Result<Option<Vec<String>>, AnyError> {
+    if let Some(config) = self.json.conditions.clone() {
+      let conditions: Vec<String> = serde_json::from_value(config)
+        .context("Failed to parse \"conditions\" configuration")?;
+      if conditions.iter().any(|condition| condition.is_empty()) {
+        bail!("Failed to parse \"conditions\" configuration: conditions can't be empty");
+      }
+      Ok(Some(conditions))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_node_modules_layout(
+    &self,
+  ) -> Result<Option<NodeModulesLayout>, AnyError> {
+    if let Some(config) = self.json.node_modules_layout.clone() {
+      let layout: String = serde_json::from_value(config)
+        .context("Failed to parse \"nodeModulesLayout\" configuration")?;
+      match NodeModulesLayout::parse(&layout) {
+        Some(layout) => Ok(Some(layout)),
+        None => bail!(
+          "Failed to parse \"nodeModulesLayout\" configuration: expected \"copy\" or \"linked\", found \"{}\"",
+          layout
+        ),
+      }
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_npm_copy_strategy(
+    &self,
+  ) -> Result<Option<NpmCopyStrategy>, AnyError> {
+    if let Some(config) = self.json.npm_copy_strategy.clone() {
+      let strategy: String = serde_json::from_value(config)
+        .context("Failed to parse \"npmCopyStrategy\" configuration")?;
+      match NpmCopyStrategy::parse(&strategy) {
+        Some(strategy) => Ok(Some(strategy)),
+        None => bail!(
+          "Failed to parse \"npmCopyStrategy\" configuration: expected \"hardlink\", \"reflink\", \"symlink\" or \"copy\", found \"{}\"",
+          strategy
+        ),
+      }
+    } else {
+      Ok(None)
+    }
+  }
+
+  /// Returns the maximum size of the npm cache in bytes, which is either a
+  /// number of bytes or a size like `"5GB"`.
+  pub fn to_npm_cache_max_size(&self) -> Result<Option<u64>, AnyError> {
+    match &self.json.npm_cache_max_size {
+      Some(Value::Number(number)) => match number.as_u64() {
+        Some(size) => Ok(Some(size)),
+        None => bail!(
+          "Failed to parse \"npmCacheMaxSize\" configuration: expected a positive number of bytes, found {}",
+          number
+        ),
+      },
+      Some(Value::String(size)) => match parse_size_arg(size) {
+        Ok(size) => Ok(Some(size)),
+        Err(err) => {
+          bail!("Failed to parse \"npmCacheMaxSize\" configuration: {}", err)
+        }
+      },
+      Some(_) => bail!(
+        "Failed to parse \"npmCacheMaxSize\" configuration: expected a number of bytes or a size like \"5GB\""
+      ),
+      None => Ok(None),
+    }
+  }
+
+  /// Returns the folder of the npm cache, relative paths being resolved from
+  /// the directory of the config file.
+  pub fn to_npm_cache_dir(&self) -> Result<Option<PathBuf>, AnyError> {
+    if let Some(config) = self.json.npm_cache_dir.clone() {
+      let dir: String = serde_json::from_value(config)
+        .context("Failed to parse \"npmCacheDir\" configuration")?;
+      if dir.is_empty() {
+        bail!("Failed to parse \"npmCacheDir\" configuration: the path can't be empty");
+      }
+      let config_dir = specifier_to_file_path(&self.specifier)?
+        .parent()
+        .unwrap()
+        .to_path_buf();
+      Ok(Some(config_dir.join(dir)))
+    } else {
+      Ok(None)
+    }
+  }
+
   pub fn to_lock_config(&self) -> Result<Option<LockConfig>, AnyError> {
     if let Some(config) = self.json.lock.clone() {
       let lock_config: LockConfig = serde_json::from_value(config)
@@ -1549,6 +2088,347 @@ mod tests {
     );
   }
 
+  #[test]
+  fn workers_config() {
+    let config_text = r#"{
+      "workers": {
+        "permissions": "none",
+        "presets": {
+          "plugin": {
+            "read": ["./plugins", "/data"],
+            "net": ["api.example.com"]
+          }
+        },
+        "preload": ["./setup.ts", "https://deno.land/x/polyfill.ts"]
+      }
+    }"#;
+    let config_dir = ModuleSpecifier::parse("file:///deno/").unwrap();
+    let config_specifier = config_dir.join("deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let config = config_file.to_worker_permissions_config().unwrap().unwrap();
+    assert_eq!(config.default, Some(ChildPermissionsArg::none()));
+    let expected: ChildPermissionsArg = serde_json::from_value(json!({
+      "read": ["/deno/plugins", "/data"],
+      "net": ["api.example.com"],
+    }))
+    .unwrap();
+    assert_eq!(config.presets.get("plugin"), Some(&expected));
+    assert_eq!(
+      config.preload,
+      vec![
+        config_dir.join("setup.ts").unwrap(),
+        ModuleSpecifier::parse("https://deno.land/x/polyfill.ts").unwrap(),
+      ]
+    );
+
+    let config_file = ConfigFile::new(
+      r#"{ "workers": { "presets": { "none": {} } } }"#,
+      &config_specifier,
+    )
+    .unwrap();
+    assert!(config_file.to_worker_permissions_config().is_err());
+
+    let config_file = ConfigFile::new(
+      r#"{ "workers": { "permissions": { "net": ["example.com:port"] } } }"#,
+      &config_specifier,
+    )
+    .unwrap();
+    assert!(config_file.to_worker_permissions_config().is_err());
+  }
+
+  #[test]
+  fn exports_and_publish_config() {
+    let config_text = r#"{
+      "name": "@scope/pkg",
+      "version": "1.0.0",
+      "exports": { ".": "./mod.ts", "./utils": "./utils.ts" },
+      "publish": {
+        "registry": "https://registry.example.com",
+        "exclude": ["./testdata"]
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert_eq!(config_file.json.name.as_deref(), Some("@scope/pkg"));
+    assert_eq!(config_file.json.version.as_deref(), Some("1.0.0"));
+    assert_eq!(
+      config_file.to_exports_config().unwrap(),
+      Some(IndexMap::from([
+        (".".to_string(), "./mod.ts".to_string()),
+        ("./utils".to_string(), "./utils.ts".to_string()),
+      ]))
+    );
+    assert_eq!(
+      config_file.to_publish_config().unwrap(),
+      Some(PublishConfig {
+        registry: Some(
+          ModuleSpecifier::parse("https://registry.example.com/").unwrap()
+        ),
+        exclude: vec![PathBuf::from("/deno/testdata")],
+      })
+    );
+
+    let config_file =
+      ConfigFile::new(r#"{ "exports": "./mod.ts" }"#, &config_specifier)
+        .unwrap();
+    assert_eq!(
+      config_file.to_exports_config().unwrap(),
+      Some(IndexMap::from([(".".to_string(), "./mod.ts".to_string())]))
+    );
+
+    for invalid in [
+      r#"{ "exports": {} }"#,
+      r#"{ "exports": "mod.ts" }"#,
+      r#"{ "exports": { "utils": "./utils.ts" } }"#,
+    ] {
+      let config_file = ConfigFile::new(invalid, &config_specifier).unwrap();
+      assert!(config_file.to_exports_config().is_err(), "{invalid}");
+    }
+  }
+
+  #[test]
+  fn watch_config() {
+    let config_text = r#"{
+      "watch": {
+        "debounce": 500,
+        "clearScreen": false,
+        "beforeRestart": "deno task codegen",
+        "afterRestart": "echo restarted"
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert_eq!(
+      config_file.to_watch_config().unwrap(),
+      Some(WatchConfig {
+        debounce: Some(500),
+        clear_screen: Some(false),
+        before_restart: Some("deno task codegen".to_string()),
+        after_restart: Some("echo restarted".to_string()),
+      })
+    );
+
+    let config_file =
+      ConfigFile::new(r#"{ "watch": { "delay": 500 } }"#, &config_specifier)
+        .unwrap();
+    assert!(config_file.to_watch_config().is_err());
+
+    let config_file = ConfigFile::new(
+      r#"{ "watch": { "beforeRestart": "echo 'unterminated" } }"#,
+      &config_specifier,
+    )
+    .unwrap();
+    assert!(config_file.to_watch_config().is_err());
+  }
+
+  #[test]
+  fn import_map_extends() {
+    let config_text = r#"{
+      "importMapExtends": ["https://example.com/org.json", "./base.json"],
+      "imports": { "std/": "https://deno.land/std@0.190.0/" }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.is_an_import_map());
+    assert_eq!(
+      config_file.to_import_map_value(),
+      json!({
+        "extends": ["https://example.com/org.json", "./base.json"],
+        "imports": { "std/": "https://deno.land/std@0.190.0/" },
+      })
+    );
+  }
+
+  #[test]
+  fn node_conditions() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(
+      r#"{ "conditions": ["react-server", "development"] }"#,
+      &config_specifier,
+    )
+    .unwrap();
+    assert_eq!(
+      config_file.to_node_conditions().unwrap(),
+      Some(vec!["react-server".to_string(), "development".to_string()])
+    );
+
+    let config_file =
+      ConfigFile::new(r#"{ "conditions": [""] }"#, &config_specifier).unwrap();
+    assert!(config_file.to_node_conditions().is_err());
+    let config_file =
+      ConfigFile::new(r#"{ "conditions": "react-server" }"#, &config_specifier)
+        .unwrap();
+    assert!(config_file.to_node_conditions().is_err());
+  }
+
+  #[test]
+  fn node_modules_layout() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(
+      r#"{ "nodeModulesLayout": "linked" }"#,
+      &config_specifier,
+    )
+    .unwrap();
+    assert_eq!(
+      config_file.to_node_modules_layout().unwrap(),
+      Some(NodeModulesLayout::Linked)
+    );
+    let config_file = ConfigFile::new(
+      r#"{ "nodeModulesLayout": "symlink" }"#,
+      &config_specifier,
+    )
+    .unwrap();
+    assert!(config_file.to_node_modules_layout().is_err());
+  }
+
+  #[test]
+  fn npm_copy_strategy() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file =
+      ConfigFile::new(r#"{ "npmCopyStrategy": "symlink" }"#, &config_specifier)
+        .unwrap();
+    assert_eq!(
+      config_file.to_npm_copy_strategy().unwrap(),
+      Some(NpmCopyStrategy::Symlink)
+    );
+    let config_file =
+      ConfigFile::new(r#"{ "npmCopyStrategy": "clone" }"#, &config_specifier)
+        .unwrap();
+    assert!(config_file.to_npm_copy_strategy().is_err());
+  }
+
+  #[test]
+  fn npm_cache_max_size() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file =
+      ConfigFile::new(r#"{ "npmCacheMaxSize": "5GB" }"#, &config_specifier)
+        .unwrap();
+    assert_eq!(
+      config_file.to_npm_cache_max_size().unwrap(),
+      Some(5 * 1024 * 1024 * 1024)
+    );
+    let config_file =
+      ConfigFile::new(r#"{ "npmCacheMaxSize": 1000 }"#, &config_specifier)
+        .unwrap();
+    assert_eq!(config_file.to_npm_cache_max_size().unwrap(), Some(1000));
+
+    for invalid in [r#""5 apples""#, "-1", "true"] {
+      let config_file = ConfigFile::new(
+        &format!(r#"{{ "npmCacheMaxSize": {invalid} }}"#),
+        &config_specifier,
+      )
+      .unwrap();
+      assert!(config_file.to_npm_cache_max_size().is_err());
+    }
+  }
+
+  #[test]
+  fn npm_cache_dir() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_dir = specifier_to_file_path(&config_specifier)
+      .unwrap()
+      .parent()
+      .unwrap()
+      .to_path_buf();
+    let config_file = ConfigFile::new(
+      r#"{ "npmCacheDir": "../cache/npm" }"#,
+      &config_specifier,
+    )
+    .unwrap();
+    assert_eq!(
+      config_file.to_npm_cache_dir().unwrap(),
+      Some(config_dir.join("../cache/npm"))
+    );
+
+    let config_file =
+      ConfigFile::new(r#"{ "npmCacheDir": "" }"#, &config_specifier).unwrap();
+    assert!(config_file.to_npm_cache_dir().is_err());
+    let config_file =
+      ConfigFile::new(r#"{ "npmCacheDir": 1 }"#, &config_specifier).unwrap();
+    assert!(config_file.to_npm_cache_dir().is_err());
+  }
+
+  #[test]
+  fn auth_config() {
+    let config_text = r#"{
+      "auth": {
+        "Deno.Example.com": { "tokenEnv": "EXAMPLE_TOKEN" },
+        "git.example.com:8080": {
+          "username": "ci",
+          "passwordEnv": "GIT_PASSWORD"
+        },
+        "api.example.com": { "tokenEnv": "API_KEY", "header": "X-Api-Key" },
+        "registry.example.com": {
+          "cert": "./certs/client.pem",
+          "key": "./certs/client.key"
+        },
+        "npm.example.com": { "ca": "./certs/internal-ca.pem" }
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let config = config_file.to_auth_config().unwrap().unwrap();
+    assert_eq!(
+      config.keys().collect::<Vec<_>>(),
+      vec![
+        "api.example.com",
+        "deno.example.com",
+        "git.example.com:8080",
+        "npm.example.com",
+        "registry.example.com"
+      ]
+    );
+    let config_dir = specifier_to_file_path(&config_specifier)
+      .unwrap()
+      .parent()
+      .unwrap()
+      .to_path_buf();
+    assert_eq!(
+      to_client_certificates(&config),
+      vec![ClientCertificate {
+        host: "registry.example.com".to_string(),
+        cert_file: config_dir.join("./certs/client.pem"),
+        key_file: config_dir.join("./certs/client.key"),
+      }]
+    );
+    assert_eq!(
+      to_host_ca_certificates(&config),
+      vec![HostCaCertificate {
+        host: "npm.example.com".to_string(),
+        ca_file: config_dir.join("./certs/internal-ca.pem"),
+      }]
+    );
+    assert_eq!(
+      config.get("git.example.com:8080"),
+      Some(&HostAuthConfig {
+        username: Some("ci".to_string()),
+        password_env: Some("GIT_PASSWORD".to_string()),
+        ..Default::default()
+      })
+    );
+
+    for invalid in [
+      r#"{ "auth": { "deno.land": {} } }"#,
+      r#"{ "auth": { "deno.land": { "username": "ci" } } }"#,
+      r#"{ "auth": { "deno.land": { "token": "secret" } } }"#,
+      r#"{ "auth": { "https://deno.land/": { "tokenEnv": "TOKEN" } } }"#,
+      r#"{ "auth": { "deno.land": { "cert": "./client.pem" } } }"#,
+      r#"{ "auth": { "deno.land": { "ca": "./ca.pem", "header": "X" } } }"#,
+    ] {
+      let config_file = ConfigFile::new(invalid, &config_specifier).unwrap();
+      assert!(config_file.to_auth_config().is_err(), "{invalid}");
+    }
+  }
+
   fn run_task_error_test(config_text: &str, expected_error: &str) {
     let config_dir = ModuleSpecifier::parse("file:///deno/").unwrap();
     let config_specifier = config_dir.join("tsconfig.json").unwrap();
@@ -0,0 +1,103 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+
+use crate::colors;
+
+/// Loads the variables of the given `.env` files into the environment of the
+/// process. Files given later take precedence over earlier ones. Variables
+/// that were set before any file was loaded are only overridden if
+/// `override_existing` is true.
+///
+/// This must be called before any other threads are spawned, because
+/// modifying the environment isn't thread safe.
+pub fn load_env_files(
+  env_files: &[PathBuf],
+  override_existing: bool,
+) -> Result<(), AnyError> {
+  let existing = std::env::vars_os()
+    .map(|(key, _)| key)
+    .collect::<HashSet<_>>();
+  for (key, value) in read_env_files(env_files)? {
+    if override_existing || !existing.contains(OsStr::new(&key)) {
+      std::env::set_var(key, value);
+    }
+  }
+  Ok(())
+}
+
+/// Reads the variables of the given `.env` files in order. Files that don't
+/// exist are skipped with a warning.
+fn read_env_files(
+  env_files: &[PathBuf],
+) -> Result<Vec<(String, String)>, AnyError> {
+  let mut vars = Vec::new();
+  for env_file in env_files {
+    let iter = match dotenvy::from_path_iter(env_file) {
+      Ok(iter) => iter,
+      Err(dotenvy::Error::Io(err))
+        if err.kind() == std::io::ErrorKind::NotFound =>
+      {
+        // The logger isn't initialized yet.
+        eprintln!(
+          "{}",
+          colors::yellow(format!(
+            "Warning: The env file '{}' was not found.",
+            env_file.display()
+          ))
+        );
+        continue;
+      }
+      Err(err) => return Err(env_file_error(env_file, err)),
+    };
+    for item in iter {
+      vars.push(item.map_err(|err| env_file_error(env_file, err))?);
+    }
+  }
+  Ok(vars)
+}
+
+fn env_file_error(env_file: &Path, err: dotenvy::Error) -> AnyError {
+  anyhow!("Failed to load env file '{}': {}", env_file.display(), err)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn read_env_files_in_order() {
+    let temp_dir = TempDir::new();
+    temp_dir.write(".env", "A=1\nB=\"two words\"\n# comment\n");
+    temp_dir.write(".env.local", "export B=2\n");
+    let vars = read_env_files(&[
+      temp_dir.path().join(".env"),
+      temp_dir.path().join("missing.env"),
+      temp_dir.path().join(".env.local"),
+    ])
+    .unwrap();
+    assert_eq!(
+      vars,
+      vec![
+        ("A".to_string(), "1".to_string()),
+        ("B".to_string(), "two words".to_string()),
+        ("B".to_string(), "2".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn read_env_files_invalid() {
+    let temp_dir = TempDir::new();
+    temp_dir.write(".env", "NOT VALID\n");
+    let err = read_env_files(&[temp_dir.path().join(".env")]).unwrap_err();
+    assert!(err.to_string().starts_with("Failed to load env file"));
+  }
+}
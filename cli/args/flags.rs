@@ -3,6 +3,7 @@
 use clap::value_parser;
 use clap::Arg;
 use clap::ArgAction;
+use clap::ArgGroup;
 use clap::ArgMatches;
 use clap::ColorChoice;
 use clap::Command;
@@ -20,6 +21,7 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use crate::util::fs::canonicalize_path;
 
@@ -43,11 +45,52 @@ pub struct BenchFlags {
 pub struct BundleFlags {
   pub source_file: String,
   pub out_file: Option<PathBuf>,
+  /// Write the bundle and a chunk for each dynamically imported module to
+  /// this directory.
+  pub out_dir: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CacheFlags {
   pub files: Vec<String>,
+  /// List the cached remote modules instead of caching files (`--list`).
+  pub list: Option<CacheListFlags>,
+  /// Output the listed modules or the module graph errors as JSON.
+  pub json: bool,
+  /// Output the integrity metadata of the cached remote modules
+  /// (`--print-integrity`).
+  pub print_integrity: bool,
+  /// Generate declaration files for the npm packages without types
+  /// (`--synth-npm-types`).
+  pub synth_npm_types: bool,
+  /// Remove the unused packages from the npm cache instead of caching files
+  /// (`--prune-npm`).
+  pub prune_npm: Option<CleanFlags>,
+  /// Write the cached remote modules and npm packages of the files to an
+  /// archive (`--export`).
+  pub export: Option<PathBuf>,
+  /// Extract an archive of `--export` into the cache instead of caching files
+  /// (`--import`).
+  pub import: Option<PathBuf>,
+  /// Check the npm packages in the cache against the digests of their files
+  /// instead of caching files (`--verify-npm`).
+  pub verify_npm: bool,
+  /// Audit the npm packages in the cache, including the integrity of the
+  /// tarballs they were extracted from (`--check-npm-integrity`).
+  pub check_npm_integrity: Option<CheckNpmIntegrityFlags>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CacheListFlags {
+  /// A host (eg. `deno.land`) or url pattern (eg.
+  /// `https://deno.land/std@*/http/*`) the listed modules must match.
+  pub filter: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CheckNpmIntegrityFlags {
+  /// Fix the issues instead of only reporting them (`--repair`).
+  pub repair: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -55,13 +98,52 @@ pub struct CheckFlags {
   pub files: Vec<String>,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CleanFlags {
+  pub dry_run: bool,
+  /// Only remove cache entries that weren't used for this long.
+  pub max_age: Option<Duration>,
+  /// Remove the least recently used cache entries until the caches are
+  /// smaller than this number of bytes.
+  pub max_size: Option<u64>,
+}
+
+/// The targets `deno compile --target` can compile an executable for.
+pub const COMPILE_TARGETS: &[&str] = &[
+  "x86_64-unknown-linux-gnu",
+  "aarch64-unknown-linux-gnu",
+  "x86_64-pc-windows-msvc",
+  "aarch64-pc-windows-msvc",
+  "x86_64-apple-darwin",
+  "aarch64-apple-darwin",
+];
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CompileFlags {
+  /// Empty for `--target list`, which doesn't take a script.
   pub source_file: String,
   pub output: Option<PathBuf>,
   pub args: Vec<String>,
   pub target: Option<String>,
   pub include: Vec<String>,
+  pub include_assets: Vec<PathBuf>,
+  pub no_compress: bool,
+  pub icon: Option<PathBuf>,
+  pub product_name: Option<String>,
+  pub file_version: Option<String>,
+  pub sign_cmd: Option<String>,
+  pub reproducible: bool,
+  pub no_terminal: bool,
+  pub log_file: Option<String>,
+}
+
+impl CompileFlags {
+  pub fn is_windows_target(&self) -> bool {
+    match &self.target {
+      Some(target) => target.contains("windows"),
+      None => cfg!(windows),
+    }
+  }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -101,9 +183,16 @@ pub struct DocFlags {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EvalFlags {
   pub print: bool,
+  /// The program, or `-` to read it from stdin.
   pub code: String,
 }
 
+impl EvalFlags {
+  pub fn is_stdin(&self) -> bool {
+    self.code == "-"
+  }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FmtFlags {
   pub check: bool,
@@ -142,6 +231,15 @@ pub struct UninstallFlags {
   pub root: Option<PathBuf>,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct JupyterFlags {
+  /// Install the kernelspec so Jupyter can start the kernel.
+  pub install: bool,
+  /// Run the kernel, which Jupyter does with the connection file.
+  pub kernel: bool,
+  pub conn_file: Option<PathBuf>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct LintFlags {
   pub files: FileFlags,
@@ -153,6 +251,34 @@ pub struct LintFlags {
   pub compact: bool,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LockFlags {
+  /// The entrypoints whose dependencies are kept when pruning or updating.
+  pub files: Vec<String>,
+  /// Check that every entry is still resolvable and matches its hash.
+  pub verify: bool,
+  /// Remove the entries that aren't reachable from the entrypoints.
+  pub prune: bool,
+  /// Dependencies to re-resolve: npm package names, `jsr:` packages or
+  /// remote url prefixes.
+  pub update: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PublishFlags {
+  /// The token to authenticate with, instead of the `auth` configuration or
+  /// `DENO_AUTH_TOKENS`.
+  pub token: Option<String>,
+  /// The registry to upload to, instead of the `publish` configuration.
+  pub registry: Option<String>,
+  /// Validate and build the package without uploading it.
+  pub dry_run: bool,
+  /// Publish even though the git repository has uncommitted changes.
+  pub allow_dirty: bool,
+  /// Attach a provenance statement of the GitHub Actions workflow.
+  pub provenance: bool,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ReplFlags {
   pub eval_files: Option<Vec<String>>,
@@ -171,6 +297,14 @@ impl RunFlags {
   }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServeFlags {
+  pub script: String,
+  pub port: u16,
+  pub host: String,
+  pub worker_count: Option<NonZeroUsize>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TaskFlags {
   pub cwd: Option<String>,
@@ -188,6 +322,28 @@ pub struct TestFlags {
   pub shuffle: Option<u64>,
   pub concurrent_jobs: Option<NonZeroUsize>,
   pub trace_ops: bool,
+  pub shard: Option<TestShard>,
+  pub reporter: TestReporterConfig,
+  pub timeout: Option<u64>,
+  pub isolate: bool,
+}
+
+/// The reporter of `deno test`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TestReporterConfig {
+  /// Human readable output.
+  #[default]
+  Pretty,
+  /// A JSON object per test event on stdout (`--reporter=json`).
+  Json,
+}
+
+/// One of the shards of `--shard`, eg. `2/8` is the second of eight shards.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TestShard {
+  /// The 1-based index of the shard.
+  pub index: usize,
+  pub count: usize,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -212,6 +368,7 @@ pub enum DenoSubcommand {
   Bundle(BundleFlags),
   Cache(CacheFlags),
   Check(CheckFlags),
+  Clean(CleanFlags),
   Compile(CompileFlags),
   Completions(CompletionsFlags),
   Coverage(CoverageFlags),
@@ -222,10 +379,14 @@ pub enum DenoSubcommand {
   Info(InfoFlags),
   Install(InstallFlags),
   Uninstall(UninstallFlags),
+  Jupyter(JupyterFlags),
   Lsp,
   Lint(LintFlags),
+  Lock(LockFlags),
+  Publish(PublishFlags),
   Repl(ReplFlags),
   Run(RunFlags),
+  Serve(ServeFlags),
   Task(TaskFlags),
   Test(TestFlags),
   Types,
@@ -261,6 +422,93 @@ impl Default for TypeCheckMode {
   }
 }
 
+/// The format of the diagnostics written to stderr.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum LogFormat {
+  /// Human readable text.
+  #[default]
+  Text,
+  /// A JSON object per line (`--log-format=json`).
+  Json,
+}
+
+/// How the packages of a local node_modules directory are created from the
+/// npm cache. Dependencies are symlinked in both cases.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NodeModulesLayout {
+  /// Copy the files of every package.
+  Copy,
+  /// Hard link the files of every package, so all projects share the copy
+  /// of the npm cache on disk. Changing a file in the node_modules
+  /// directory also changes it in the cache.
+  Linked,
+}
+
+impl NodeModulesLayout {
+  pub fn parse(value: &str) -> Option<Self> {
+    match value {
+      "copy" => Some(Self::Copy),
+      "linked" => Some(Self::Linked),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Copy => "copy",
+      Self::Linked => "linked",
+    }
+  }
+}
+
+impl Default for NodeModulesLayout {
+  fn default() -> Self {
+    Self::Copy
+  }
+}
+
+/// How the copies of a package in the npm cache are created, which are
+/// needed when a package is resolved with different peer dependencies. The
+/// files are copied instead when the strategy isn't supported.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NpmCopyStrategy {
+  /// Hard link the files, which only works on the same file system.
+  HardLink,
+  /// Clone the files with copy-on-write (ex. on btrfs, XFS or APFS).
+  Reflink,
+  /// Symlink the files.
+  Symlink,
+  /// Copy the files.
+  Copy,
+}
+
+impl NpmCopyStrategy {
+  pub fn parse(value: &str) -> Option<Self> {
+    match value {
+      "hardlink" => Some(Self::HardLink),
+      "reflink" => Some(Self::Reflink),
+      "symlink" => Some(Self::Symlink),
+      "copy" => Some(Self::Copy),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::HardLink => "hardlink",
+      Self::Reflink => "reflink",
+      Self::Symlink => "symlink",
+      Self::Copy => "copy",
+    }
+  }
+}
+
+impl Default for NpmCopyStrategy {
+  fn default() -> Self {
+    Self::HardLink
+  }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ConfigFlag {
   Discover,
@@ -274,6 +522,23 @@ impl Default for ConfigFlag {
   }
 }
 
+/// A client certificate presented to a host that requires mutual TLS.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClientCertificate {
+  /// The hostname with an optional port, eg. `registry.example.com:8443`.
+  pub host: String,
+  pub cert_file: PathBuf,
+  pub key_file: PathBuf,
+}
+
+/// A CA certificate trusted only when connecting to a host.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HostCaCertificate {
+  /// The hostname with an optional port, eg. `registry.example.com:8443`.
+  pub host: String,
+  pub ca_file: PathBuf,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum CaData {
   /// The string is a file path
@@ -299,6 +564,8 @@ pub struct Flags {
   pub allow_run: Option<Vec<String>>,
   pub allow_sys: Option<Vec<String>>,
   pub allow_write: Option<Vec<PathBuf>>,
+  pub allow_unix: Option<Vec<PathBuf>>,
+  pub allow_udp: Option<Vec<String>>,
   pub ca_stores: Option<Vec<String>>,
   pub ca_data: Option<CaData>,
   pub cache_blocklist: Vec<String>,
@@ -306,12 +573,34 @@ pub struct Flags {
   /// the language server is configured with an explicit cache option.
   pub cache_path: Option<PathBuf>,
   pub cached_only: bool,
+  pub client_certs: Vec<ClientCertificate>,
+  pub host_ca_certs: Vec<HostCaCertificate>,
   pub type_check_mode: TypeCheckMode,
   pub config_flag: ConfigFlag,
   pub node_modules_dir: Option<bool>,
+  /// Resolve npm packages from an existing node_modules directory created by
+  /// another package manager instead of managing one (`--byonm`).
+  pub byonm: bool,
+  /// How packages are created in the local node_modules directory
+  /// (`--node-modules-layout`).
+  pub node_modules_layout: Option<NodeModulesLayout>,
+  /// How the copies of packages in the npm cache are created
+  /// (`--npm-copy-strategy`).
+  pub npm_copy_strategy: Option<NpmCopyStrategy>,
+  /// The maximum number of npm package tarballs that are downloaded at once
+  /// (`--npm-concurrency`).
+  pub npm_concurrency: Option<usize>,
+  /// Custom conditions applied when resolving the `exports` of npm packages
+  /// (`--conditions`).
+  pub node_conditions: Vec<String>,
   pub coverage_dir: Option<String>,
   pub enable_testing_features: bool,
+  /// Files to load environment variables from before running (`--env`).
+  pub env_files: Vec<PathBuf>,
   pub ext: Option<String>,
+  /// Write a heap snapshot when close to running out of memory
+  /// (`--heap-snapshot-on-oom`).
+  pub heap_snapshot_on_oom: bool,
   pub ignore: Vec<PathBuf>,
   pub import_map_path: Option<String>,
   pub inspect_brk: Option<SocketAddr>,
@@ -321,17 +610,48 @@ pub struct Flags {
   pub lock_write: bool,
   pub lock: Option<PathBuf>,
   pub log_level: Option<Level>,
+  pub log_format: LogFormat,
+  /// Hide the progress of downloads and other operations
+  /// (`--quiet-progress`).
+  pub quiet_progress: bool,
+  /// Write the spans of the CLI phases in the Chrome trace event format to
+  /// this file (`--trace-out`).
+  pub trace_out: Option<PathBuf>,
+  /// The maximum number of redirects to follow when fetching a remote
+  /// module. `--no-redirects` sets it to zero.
+  pub max_redirects: Option<u32>,
   pub no_remote: bool,
+  /// Don't use or create V8 code caches of modules (`--no-code-cache`).
+  pub no_code_cache: bool,
+  /// Don't let `--env` files override environment variables that are
+  /// already set (`--no-env-override`).
+  pub no_env_override: bool,
   pub no_lock: bool,
   pub no_npm: bool,
   pub no_prompt: bool,
+  /// Only use cached remote modules and npm packages, never downloading
+  /// anything (`--offline`).
+  pub offline: bool,
+  pub permissions_policy: Option<PathBuf>,
+  /// Resolve remote modules to their copy in this vendor directory when they
+  /// have been vendored (`--prefer-vendor`).
+  pub prefer_vendor: Option<PathBuf>,
   pub reload: bool,
+  /// Revalidate cached remote modules that are stale according to their
+  /// cache headers (`--reload=stale`).
+  pub reload_stale: bool,
+  /// Revalidate all cached remote modules (`--revalidate`).
+  pub revalidate: bool,
+  pub sandbox: bool,
   pub seed: Option<u64>,
   pub unstable: bool,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub v8_flags: Vec<String>,
   pub version: bool,
   pub watch: Option<Vec<PathBuf>>,
+  /// Globs of paths whose changes are ignored in watch mode
+  /// (`--watch-exclude`).
+  pub watch_exclude: Vec<PathBuf>,
   pub no_clear_screen: bool,
 }
 
@@ -387,6 +707,17 @@ impl Flags {
       _ => {}
     }
 
+    match &self.allow_udp {
+      Some(udp_allowlist) if udp_allowlist.is_empty() => {
+        args.push("--allow-udp".to_string());
+      }
+      Some(udp_allowlist) => {
+        let s = format!("--allow-udp={}", udp_allowlist.join(","));
+        args.push(s);
+      }
+      _ => {}
+    }
+
     match &self.unsafely_ignore_certificate_errors {
       Some(ic_allowlist) if ic_allowlist.is_empty() => {
         args.push("--unsafely-ignore-certificate-errors".to_string());
@@ -445,10 +776,25 @@ impl Flags {
       _ => {}
     }
 
+    match &self.allow_unix {
+      Some(unix_allowlist) if unix_allowlist.is_empty() => {
+        args.push("--allow-unix".to_string());
+      }
+      Some(unix_allowlist) => {
+        let s = format!("--allow-unix={}", join_paths(unix_allowlist, ","));
+        args.push(s);
+      }
+      _ => {}
+    }
+
     if self.allow_hrtime {
       args.push("--allow-hrtime".to_string());
     }
 
+    if let Some(policy) = &self.permissions_policy {
+      args.push(format!("--permissions={}", policy.to_string_lossy()));
+    }
+
     args
   }
 
@@ -463,7 +809,7 @@ impl Flags {
     match &self.subcommand {
       Fmt(FmtFlags { files, .. }) => Some(files.include.clone()),
       Lint(LintFlags { files, .. }) => Some(files.include.clone()),
-      Run(RunFlags { script }) => {
+      Run(RunFlags { script }) | Serve(ServeFlags { script, .. }) => {
         if let Ok(module_specifier) = resolve_url_or_path(script, current_dir) {
           if module_specifier.scheme() == "file"
             || module_specifier.scheme() == "npm"
@@ -505,7 +851,7 @@ impl Flags {
     use DenoSubcommand::*;
 
     match &self.subcommand {
-      Run(RunFlags { script }) => {
+      Run(RunFlags { script }) | Serve(ServeFlags { script, .. }) => {
         let module_specifier = resolve_url_or_path(script, current_dir).ok()?;
         if module_specifier.scheme() == "file" {
           let p = module_specifier
@@ -527,11 +873,11 @@ impl Flags {
           .ok()
       }
       Task(_) | Check(_) | Coverage(_) | Cache(_) | Info(_) | Eval(_)
-      | Test(_) | Bench(_) | Repl(_) | Compile(_) => {
-        std::env::current_dir().ok()
-      }
-      Bundle(_) | Completions(_) | Doc(_) | Fmt(_) | Init(_) | Install(_)
-      | Uninstall(_) | Lsp | Lint(_) | Types | Upgrade(_) | Vendor(_) => None,
+      | Test(_) | Bench(_) | Repl(_) | Jupyter(_) | Compile(_) | Lock(_)
+      | Publish(_) => std::env::current_dir().ok(),
+      Bundle(_) | Clean(_) | Completions(_) | Doc(_) | Fmt(_) | Init(_)
+      | Install(_) | Uninstall(_) | Lsp | Lint(_) | Types | Upgrade(_)
+      | Vendor(_) => None,
     }
   }
 
@@ -545,6 +891,9 @@ impl Flags {
       || self.allow_run.is_some()
       || self.allow_sys.is_some()
       || self.allow_write.is_some()
+      || self.allow_unix.is_some()
+      || self.allow_udp.is_some()
+      || self.permissions_policy.is_some()
   }
 
   pub fn has_permission_in_argv(&self) -> bool {
@@ -558,6 +907,9 @@ impl Flags {
         || arg.starts_with("--allow-run")
         || arg.starts_with("--allow-sys")
         || arg.starts_with("--allow-write")
+        || arg.starts_with("--allow-unix")
+        || arg.starts_with("--allow-udp")
+        || arg.starts_with("--permissions")
     })
   }
 }
@@ -578,14 +930,25 @@ static ENV_VARIABLES_HELP: &str = r#"ENVIRONMENT VARIABLES:
                          History file is disabled when the value is empty
                          (defaults to $DENO_DIR/deno_history.txt)
     DENO_NO_PACKAGE_JSON Disables auto-resolution of package.json
+    DENO_NPM_AUTH_TOKENS Like DENO_AUTH_TOKENS, but for npm registries, taking
+                         precedence over the credentials in .npmrc files
+    DENO_NPM_CONCURRENCY Maximum number of npm packages to download at once
+                         (defaults to 16, see --npm-concurrency)
+    DENO_NPM_DIR         Set the npm cache directory
+                         (defaults to $DENO_DIR/npm)
+    DENO_NPM_CACHE_MAX_SIZE
+                         Maximum size of the npm cache (ex. 5GB), the least
+                         recently used packages are evicted when exceeded
     DENO_NO_PROMPT       Set to disable permission prompts on access
                          (alternative to passing --no-prompt on invocation)
     DENO_NO_UPDATE_CHECK Set to disable checking if a newer Deno version is
                          available
+    DENO_OFFLINE         Set to 1 to only use cached remote modules and npm
+                         packages (alternative to passing --offline)
     DENO_V8_FLAGS        Set V8 command line options
     DENO_JOBS            Number of parallel workers used for the --parallel
-                         flag with the test subcommand. Defaults to number
-                         of available CPUs.
+                         flag with the test and serve subcommands. Defaults
+                         to number of available CPUs.
     HTTP_PROXY           Proxy address for HTTP requests
                          (module downloads, fetch)
     HTTPS_PROXY          Proxy address for HTTPS requests
@@ -629,6 +992,10 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::error::Result<Flags> {
     flags.unstable = true;
   }
 
+  if matches.get_flag("offline") {
+    flags.offline = true;
+  }
+
   if matches.get_flag("quiet") {
     flags.log_level = Some(Level::Error);
   } else if let Some(log_level) = matches.get_one::<String>("log-level") {
@@ -639,12 +1006,24 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::error::Result<Flags> {
     };
   }
 
+  flags.trace_out = matches.remove_one::<PathBuf>("trace-out");
+  flags.quiet_progress = matches.get_flag("quiet-progress");
+
+  if let Some(log_format) = matches.get_one::<String>("log-format") {
+    flags.log_format = match log_format.as_str() {
+      "text" => LogFormat::Text,
+      "json" => LogFormat::Json,
+      _ => unreachable!(),
+    };
+  }
+
   if let Some((subcommand, mut m)) = matches.remove_subcommand() {
     match subcommand.as_str() {
       "bench" => bench_parse(&mut flags, &mut m),
       "bundle" => bundle_parse(&mut flags, &mut m),
       "cache" => cache_parse(&mut flags, &mut m),
       "check" => check_parse(&mut flags, &mut m),
+      "clean" => clean_parse(&mut flags, &mut m),
       "compile" => compile_parse(&mut flags, &mut m),
       "completions" => completions_parse(&mut flags, &mut m, app),
       "coverage" => coverage_parse(&mut flags, &mut m),
@@ -654,10 +1033,14 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::error::Result<Flags> {
       "init" => init_parse(&mut flags, &mut m),
       "info" => info_parse(&mut flags, &mut m),
       "install" => install_parse(&mut flags, &mut m),
+      "jupyter" => jupyter_parse(&mut flags, &mut m),
       "lint" => lint_parse(&mut flags, &mut m),
+      "lock" => lock_parse(&mut flags, &mut m),
       "lsp" => lsp_parse(&mut flags, &mut m),
+      "publish" => publish_parse(&mut flags, &mut m),
       "repl" => repl_parse(&mut flags, &mut m),
       "run" => run_parse(&mut flags, &mut m),
+      "serve" => serve_parse(&mut flags, &mut m),
       "task" => task_parse(&mut flags, &mut m),
       "test" => test_parse(&mut flags, &mut m),
       "types" => types_parse(&mut flags, &mut m),
@@ -690,6 +1073,8 @@ fn handle_repl_flags(flags: &mut Flags, repl_flags: ReplFlags) {
     flags.allow_sys = Some(vec![]);
     flags.allow_write = Some(vec![]);
     flags.allow_ffi = Some(vec![]);
+    flags.allow_unix = Some(vec![]);
+    flags.allow_udp = Some(vec![]);
     flags.allow_hrtime = true;
   }
   flags.subcommand = DenoSubcommand::Repl(repl_flags);
@@ -722,6 +1107,16 @@ fn clap_root() -> Command {
         .action(ArgAction::SetTrue)
         .global(true),
     )
+    .arg(
+      Arg::new("offline")
+        .long("offline")
+        .help(
+          "Only use cached remote modules and npm packages, failing instead \
+of downloading anything",
+        )
+        .action(ArgAction::SetTrue)
+        .global(true),
+    )
     .arg(
       Arg::new("log-level")
         .short('L')
@@ -731,6 +1126,39 @@ fn clap_root() -> Command {
         .value_parser(["debug", "info"])
         .global(true),
     )
+    .arg(
+      Arg::new("log-format")
+        .long("log-format")
+        .help(
+          "Output the diagnostics (download progress, module graph errors, \
+type errors, test events) as text or as JSON lines on stderr",
+        )
+        .value_parser(["text", "json"])
+        .global(true),
+    )
+    .arg(
+      Arg::new("quiet-progress")
+        .long("quiet-progress")
+        .help(
+          "Hide the progress of downloads and other operations, but not other \
+diagnostic output",
+        )
+        .action(ArgAction::SetTrue)
+        .global(true),
+    )
+    .arg(
+      Arg::new("trace-out")
+        .long("trace-out")
+        .value_name("FILE")
+        .help(
+          "Write a Chrome trace of the time spent building the module graph, \
+resolving and downloading npm packages, fetching, emitting and type checking \
+to a file, which can be opened in chrome://tracing or https://ui.perfetto.dev",
+        )
+        .value_parser(value_parser!(PathBuf))
+        .value_hint(ValueHint::FilePath)
+        .global(true),
+    )
     .arg(
       Arg::new("quiet")
         .short('q')
@@ -743,6 +1171,7 @@ fn clap_root() -> Command {
     .subcommand(bundle_subcommand())
     .subcommand(cache_subcommand())
     .subcommand(check_subcommand())
+    .subcommand(clean_subcommand())
     .subcommand(compile_subcommand())
     .subcommand(completions_subcommand())
     .subcommand(coverage_subcommand())
@@ -753,10 +1182,14 @@ fn clap_root() -> Command {
     .subcommand(info_subcommand())
     .subcommand(install_subcommand())
     .subcommand(uninstall_subcommand())
+    .subcommand(jupyter_subcommand())
     .subcommand(lsp_subcommand())
     .subcommand(lint_subcommand())
+    .subcommand(lock_subcommand())
+    .subcommand(publish_subcommand())
     .subcommand(repl_subcommand())
     .subcommand(run_subcommand())
+    .subcommand(serve_subcommand())
     .subcommand(task_subcommand())
     .subcommand(test_subcommand())
     .subcommand(types_subcommand())
@@ -769,6 +1202,7 @@ fn clap_root() -> Command {
 fn bench_subcommand() -> Command {
   runtime_args(Command::new("bench"), true, false)
     .arg(check_arg(true))
+    .arg(sandbox_arg())
     .arg(
       Arg::new("json")
         .long("json")
@@ -803,8 +1237,9 @@ fn bench_subcommand() -> Command {
         .help("Cache bench modules, but don't run benchmarks")
         .action(ArgAction::SetTrue),
     )
-    .arg(watch_arg(false))
+    .arg(watch_arg(true))
     .arg(no_clear_screen_arg())
+    .arg(watch_exclude_arg())
     .arg(script_arg().last(true))
     .about("Run benchmarks")
     .long_about(
@@ -824,7 +1259,6 @@ glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
 
 fn bundle_subcommand() -> Command {
   compile_args(Command::new("bundle"))
-    .hide(true)
     .arg(check_arg(true))
     .arg(
       Arg::new("source_file")
@@ -836,18 +1270,40 @@ fn bundle_subcommand() -> Command {
         .value_parser(value_parser!(PathBuf))
         .value_hint(ValueHint::FilePath),
     )
+    .arg(
+      Arg::new("outdir")
+        .long("outdir")
+        .help("Split the bundle at dynamic imports and write the chunks to this directory")
+        .value_parser(value_parser!(PathBuf))
+        .value_hint(ValueHint::DirPath)
+        .conflicts_with("out_file"),
+    )
     .arg(watch_arg(false))
     .arg(no_clear_screen_arg())
+    .arg(watch_exclude_arg())
     .arg(executable_ext_arg())
     .about("Bundle module and dependencies into single file")
     .long_about(
-      "Output a single JavaScript file with all dependencies.
+      "Output a single JavaScript module with all dependencies.
 
   deno bundle https://deno.land/std/examples/colors.ts colors.bundle.js
 
 If no output file is given, the output is written to standard output:
 
-  deno bundle https://deno.land/std/examples/colors.ts",
+  deno bundle https://deno.land/std/examples/colors.ts
+
+With --outdir, the bundle is split at dynamic imports. Every dynamically
+imported module is written to its own chunk next to the main bundle, which
+loads it on demand:
+
+  deno bundle --outdir dist main.ts
+
+Modules that are statically imported by several chunks are included in each
+of them.
+
+ES modules of npm packages are inlined into the bundle. Packages that are
+CommonJS modules can't be bundled and imports of Node built-in modules are
+kept as is.",
     )
 }
 
@@ -857,9 +1313,144 @@ fn cache_subcommand() -> Command {
     .arg(
       Arg::new("file")
         .num_args(1..)
-        .required(true)
+        .required_unless_present_any([
+          "list",
+          "prune-npm",
+          "import",
+          "verify-npm",
+          "check-npm-integrity",
+        ])
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("list")
+        .long("list")
+        .help("List the cached remote modules")
+        .conflicts_with("file")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("filter")
+        .long("filter")
+        .value_name("PATTERN")
+        .help("Only list modules of a host or matching a url pattern")
+        .requires("list"),
+    )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .help("Output the listed modules or the errors in JSON format")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("print-integrity")
+        .long("print-integrity")
+        .help("Output the integrity section of a config file for the remote modules")
+        .conflicts_with_all(["list", "json"])
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("synth-npm-types")
+        .long("synth-npm-types")
+        .help("Generate declaration files for npm packages that have no types")
+        .conflicts_with("list")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("prune-npm")
+        .long("prune-npm")
+        .help("Remove the npm packages that aren't used by the lockfile")
+        .conflicts_with_all(["file", "list", "json", "print-integrity"])
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("max-age")
+        .long("max-age")
+        .value_name("DURATION")
+        .help("Only prune packages not used for this long, eg. 30d or 12h")
+        .value_parser(parse_duration_arg)
+        .requires("prune-npm"),
+    )
+    .arg(
+      Arg::new("max-size")
+        .long("max-size")
+        .value_name("SIZE")
+        .help(
+          "Prune least recently used packages until below this size, eg. 5GB",
+        )
+        .value_parser(parse_size_arg)
+        .requires("prune-npm"),
+    )
+    .arg(
+      Arg::new("dry-run")
+        .long("dry-run")
+        .help("Show what would be pruned without removing anything")
+        .requires("prune-npm")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("export")
+        .long("export")
+        .value_name("FILE")
+        .help("Write the cached remote modules and npm packages to an archive")
+        .value_parser(value_parser!(PathBuf))
+        .conflicts_with_all(["list", "json", "print-integrity"])
         .value_hint(ValueHint::FilePath),
     )
+    .arg(
+      Arg::new("import")
+        .long("import")
+        .value_name("FILE")
+        .help("Extract an archive of --export into the cache")
+        .value_parser(value_parser!(PathBuf))
+        .conflicts_with_all([
+          "file",
+          "list",
+          "json",
+          "print-integrity",
+          "synth-npm-types",
+          "prune-npm",
+        ])
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("verify-npm")
+        .long("verify-npm")
+        .help("Check the cached npm packages for modified files")
+        .conflicts_with_all([
+          "file",
+          "list",
+          "json",
+          "print-integrity",
+          "synth-npm-types",
+          "prune-npm",
+          "import",
+        ])
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("check-npm-integrity")
+        .long("check-npm-integrity")
+        .help("Report modified npm packages and tarball integrity mismatches")
+        .conflicts_with_all([
+          "file",
+          "list",
+          "json",
+          "print-integrity",
+          "synth-npm-types",
+          "prune-npm",
+          "import",
+          "verify-npm",
+        ])
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("repair")
+        .long("repair")
+        .help("Fix the issues found by --check-npm-integrity")
+        .requires("check-npm-integrity")
+        .action(ArgAction::SetTrue),
+    )
     .about("Cache the dependencies")
     .long_about(
       "Cache and compile remote dependencies recursively.
@@ -870,7 +1461,64 @@ them in the local cache, without running any code:
   deno cache https://deno.land/std/http/file_server.ts
 
 Future runs of this module will trigger no downloads or compilation unless
---reload is specified.",
+--reload is specified.
+
+All the modules that can't be resolved or fetched are reported at once, along
+with the chain of imports leading to them. Use --json to output them as JSON:
+
+  deno cache --json main.ts
+
+Output the expected hashes of the remote modules, to add them to the
+\"integrity\" section of the config file so they can't change without being
+noticed:
+
+  deno cache --print-integrity main.ts
+
+Generate best-effort declaration files for the npm packages that ship no types
+and have no @types package, so that their exports are typed instead of the
+whole import being 'any'. The declarations are written next to the modules of
+the packages in the npm cache:
+
+  deno cache --synth-npm-types main.ts
+
+Remove the npm packages from the cache that aren't in the lockfile of the
+current project, optionally only the ones that weren't used in the last 30 days
+or the least recently used ones until the npm cache is smaller than 5GB:
+
+  deno cache --prune-npm
+  deno cache --prune-npm --max-age=30d --dry-run
+  deno cache --prune-npm --max-size=5GB
+
+Write the cached remote modules and npm packages of a module to an archive,
+and extract it into the cache of another machine, eg. one without network
+access:
+
+  deno cache --export=cache.tar.gz main.ts
+  deno cache --import=cache.tar.gz
+
+Check the files of the cached npm packages against the digests recorded when
+they were extracted. Files that were replaced are restored from the store of
+the npm cache and packages whose stored files were modified are removed, so
+that only they are downloaded again the next time they're used. Exits with
+code 1 when any package was modified:
+
+  deno cache --verify-npm
+
+Audit the cached npm packages without changing them. Besides the modified
+files, this reports the packages that were extracted from a tarball with
+another integrity than the one in the lockfile, or in the registry metadata for
+the packages that aren't in it. Exits with code 1 when there are any issues,
+which --repair fixes like --verify-npm, removing the mismatched packages:
+
+  deno cache --check-npm-integrity
+  deno cache --check-npm-integrity --repair
+
+List the cached remote modules with their url, local path, size, media type
+and fetch date, optionally only the ones of a host or matching a url pattern:
+
+  deno cache --list
+  deno cache --list --filter=deno.land
+  deno cache --list --filter='https://deno.land/std@*/http/*'",
     )
 }
 
@@ -898,19 +1546,79 @@ fn check_subcommand() -> Command {
         .required(true)
         .value_hint(ValueHint::FilePath),
     )
+    .arg(watch_arg(false))
+    .arg(no_clear_screen_arg())
+    .arg(watch_exclude_arg())
     .about("Type-check the dependencies")
     .long_about(
       "Download and type-check without execution.
 
   deno check https://deno.land/std/http/file_server.ts
 
+Type-check again whenever a local file changes:
+
+  deno check --watch main.ts
+
 Unless --reload is specified, this command will not re-download already cached dependencies.",
     )
 }
 
+fn clean_subcommand() -> Command {
+  Command::new("clean")
+    .about("Remove cached data from DENO_DIR")
+    .long_about(
+      "Remove the cached remote modules, emitted files, type checking caches
+and npm packages from DENO_DIR.
+
+Remove everything:
+
+  deno clean
+
+Remove the entries that weren't used in the last 30 days:
+
+  deno clean --max-age=30d
+
+Remove the least recently used entries until the caches are smaller than 5GB:
+
+  deno clean --max-size=5GB
+
+Show what would be removed without removing anything:
+
+  deno clean --max-age=2w --dry-run",
+    )
+    .arg(
+      Arg::new("max-age")
+        .long("max-age")
+        .value_name("DURATION")
+        .help("Only remove entries not used for this long, eg. 30d or 12h")
+        .value_parser(parse_duration_arg),
+    )
+    .arg(
+      Arg::new("max-size")
+        .long("max-size")
+        .value_name("SIZE")
+        .help(
+          "Remove least recently used entries until below this size, eg. 5GB",
+        )
+        .value_parser(parse_size_arg),
+    )
+    .arg(
+      Arg::new("dry-run")
+        .long("dry-run")
+        .help("Show what would be removed without removing anything")
+        .action(ArgAction::SetTrue),
+    )
+}
+
 fn compile_subcommand() -> Command {
   runtime_args(Command::new("compile"), true, false)
-    .arg(script_arg().required(true))
+    .arg(
+      script_arg()
+        .required_unless_present("target")
+        .required_if_eq_any(
+          COMPILE_TARGETS.iter().map(|target| ("target", *target)),
+        ),
+    )
     .arg(check_arg(true))
     .arg(
       Arg::new("include")
@@ -919,53 +1627,175 @@ fn compile_subcommand() -> Command {
         .long_help(
           "Includes an additional module in the compiled executable's module
     graph. Use this flag if a dynamically imported module or a web worker main
-    module fails to load in the executable. This flag can be passed multiple
-    times, to include multiple additional modules.",
+    module fails to load in the executable. A glob pattern of local paths, like
+    \"./plugins/**/*.ts\", includes all the modules it matches. This flag can be
+    passed multiple times, to include multiple additional modules.",
         )
         .action(ArgAction::Append)
         .value_hint(ValueHint::FilePath),
     )
     .arg(
-      Arg::new("output")
-        .long("output")
-        .short('o')
+      Arg::new("include-asset")
+        .long("include-asset")
+        .value_name("PATH")
+        .help("UNSTABLE: Additional data file or directory to embed")
+        .long_help(
+          "Embeds a data file, or every file of a directory, in the compiled
+    executable. The embedded files are imported at runtime from their original
+    file URL with a `text` or `bytes` import assertion. This flag can be passed
+    multiple times, to embed multiple files or directories.",
+        )
+        .action(ArgAction::Append)
+        .value_parser(value_parser!(PathBuf))
+        .value_hint(ValueHint::AnyPath),
+    )
+    .arg(
+      Arg::new("no-compress")
+        .long("no-compress")
+        .help("Store the modules uncompressed in the executable")
+        .long_help(
+          "Stores the modules uncompressed in the compiled executable. They're
+    compressed with zstd by default, which makes the executable smaller at the
+    cost of decompressing them on startup.",
+        )
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("icon")
+        .long("icon")
+        .value_name("PATH")
+        .help("Icon of the Windows executable, as an .ico file")
         .value_parser(value_parser!(PathBuf))
-        .help("Output file (defaults to $PWD/<inferred-name>)")
         .value_hint(ValueHint::FilePath),
     )
     .arg(
-      Arg::new("target")
-        .long("target")
-        .help("Target OS architecture")
-        .value_parser([
-          "x86_64-unknown-linux-gnu",
-          "x86_64-pc-windows-msvc",
-          "x86_64-apple-darwin",
-          "aarch64-apple-darwin",
-        ]),
+      Arg::new("product-name")
+        .long("product-name")
+        .value_name("NAME")
+        .help(
+          "Product name in the version information of the Windows executable",
+        ),
     )
-    .arg(executable_ext_arg())
-    .about("UNSTABLE: Compile the script into a self contained executable")
-    .long_about(
-      "UNSTABLE: Compiles the given script into a self contained executable.
-
-  deno compile -A https://deno.land/std/http/file_server.ts
-  deno compile --output color_util https://deno.land/std/examples/colors.ts
-
-Any flags passed which affect runtime behavior, such as '--unstable',
-'--allow-*', '--v8-flags', etc. are encoded into the output executable and
-used at runtime as if they were passed to a similar 'deno run' command.
-
-The executable name is inferred by default: Attempt to take the file stem of
+    .arg(
+      Arg::new("file-version")
+        .long("file-version")
+        .value_name("VERSION")
+        .help("File version of the Windows executable, eg. 1.2.3"),
+    )
+    .arg(
+      Arg::new("no-terminal")
+        .long("no-terminal")
+        .help("Hide the terminal window on Windows")
+        .long_help(
+          "Sets the subsystem of the Windows executable to GUI, so that no
+    terminal window is opened when it's started, eg. by double clicking it.
+    Use --log-file to keep its output.",
+        )
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("log-file")
+        .long("log-file")
+        .help("Redirect stdout and stderr of the executable to a file")
+        .long_help(
+          "Appends the stdout and stderr of the executable to the given file.
+    A relative path is resolved against the directory of the executable.",
+        )
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("reproducible")
+        .long("reproducible")
+        .help("Require a lockfile so that compiles are byte-identical")
+        .long_help(
+          "Requires a lockfile, which pins the remote modules and the npm
+    packages, so that compiling the same modules gives a byte-identical
+    executable. The paths of the local modules are embedded in the executable,
+    so compile from the same directory.",
+        )
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("sign-cmd")
+        .long("sign-cmd")
+        .value_name("COMMAND")
+        .help("Command to sign the executable with, run with its path")
+        .long_help(
+          "Signs the compiled executable by running the given command in the
+    shell, with the path of the executable as the last argument. The signature
+    of Windows executables is appended after the embedded modules, which keeps
+    the executable working after signing it with signtool.",
+        ),
+    )
+    .arg(
+      Arg::new("output")
+        .long("output")
+        .short('o')
+        .value_parser(value_parser!(PathBuf))
+        .help("Output file (defaults to $PWD/<inferred-name>)")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("target")
+        .long("target")
+        .help("Target OS architecture, or 'list' to print the targets")
+        .value_parser(
+          COMPILE_TARGETS
+            .iter()
+            .copied()
+            .chain(["list"])
+            .collect::<Vec<_>>(),
+        ),
+    )
+    .arg(executable_ext_arg())
+    .about("UNSTABLE: Compile the script into a self contained executable")
+    .long_about(
+      "UNSTABLE: Compiles the given script into a self contained executable.
+
+  deno compile -A https://deno.land/std/http/file_server.ts
+  deno compile --output color_util https://deno.land/std/examples/colors.ts
+
+Any flags passed which affect runtime behavior, such as '--unstable',
+'--allow-*', '--v8-flags', etc. are encoded into the output executable and
+used at runtime as if they were passed to a similar 'deno run' command.
+
+npm specifiers are supported: the resolved npm packages are embedded in the
+executable, either as a virtual node_modules directory with
+'--node-modules-dir' or as the packages of the global npm cache.
+
+The permissions granted with '--allow-*' are the most the executable gets:
+it doesn't prompt for other permissions, so it can't be run with broader ones.
+
+  deno compile --allow-net=api.example.com --allow-read=./data main.ts
+
+The executable name is inferred by default: Attempt to take the file stem of
 the URL path. The above example would become 'file_server'. If the file stem
 is something generic like 'main', 'mod', 'index' or 'cli', and the path has no
 parent, take the file name of the parent path. Otherwise settle with the
 generic name. If the resulting name has an '@...' suffix, strip it.
 
+Data files that aren't imported by the modules, such as templates or images,
+can be embedded with `--include-asset`. They keep their file URL, so they're
+read relative to the importing module:
+
+  const url = new URL(\"./page.html\", import.meta.url);
+  const { default: page } = await import(url.href, {
+    assert: { type: \"text\" },
+  });
+
 Cross-compiling to different target architectures is supported using the
 `--target` flag. On the first invocation with deno will download proper
 binary and cache it in $DENO_DIR. The aarch64-apple-darwin target is not
-supported in canary.
+supported in canary. Print the supported targets with:
+
+  deno compile --target list
+
+The icon and the version information shown by Windows for the executable are
+set with `--icon`, `--product-name` and `--file-version`:
+
+  deno compile --target x86_64-pc-windows-msvc --icon app.ico \\
+    --product-name \"My App\" --file-version 1.2.0 main.ts
 ",
     )
 }
@@ -1112,6 +1942,7 @@ Show documentation for runtime built-ins:
     )
     .arg(import_map_arg())
     .arg(reload_arg())
+    .arg(revalidate_arg())
     .arg(lock_arg())
     .arg(no_lock_arg())
     .arg(no_npm_arg())
@@ -1154,6 +1985,15 @@ To evaluate as TypeScript:
 
   deno eval --ext=ts \"const v: string = 'hello'; console.log(v)\"
 
+Read the program from stdin:
+
+  echo \"console.log(Deno.version)\" | deno eval -
+
+Remote modules and npm packages can be imported and are cached like for
+'deno run':
+
+  deno eval \"import chalk from 'npm:chalk@5'; console.log(chalk.blue('hi'))\"
+
 This command has implicit access to all permissions (--allow-all).",
     )
     .arg(check_arg(false))
@@ -1179,7 +2019,7 @@ This command has implicit access to all permissions (--allow-all).",
       Arg::new("code_arg")
         .num_args(1..)
         .action(ArgAction::Append)
-        .help("Code arg")
+        .help("Code arg, or '-' to read the code from stdin")
         .value_name("CODE_ARG")
         .required(true),
     )
@@ -1241,8 +2081,9 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
         .required(false)
         .value_hint(ValueHint::AnyPath),
     )
-    .arg(watch_arg(false))
+    .arg(watch_arg(true))
     .arg(no_clear_screen_arg())
+    .arg(watch_exclude_arg())
     .arg(
       Arg::new("use-tabs")
         .long("use-tabs")
@@ -1329,6 +2170,7 @@ TypeScript compiler cache: Subdirectory containing TS compiler output.",
     )
     .arg(Arg::new("file").required(false).value_hint(ValueHint::FilePath))
     .arg(reload_arg().requires("file"))
+    .arg(revalidate_arg().requires("file"))
     .arg(ca_file_arg())
     .arg(
       location_arg()
@@ -1430,6 +2272,52 @@ The installation root is determined, in order of precedence:
   - $HOME/.deno")
 }
 
+fn jupyter_subcommand() -> Command {
+  Command::new("jupyter")
+    .arg(
+      Arg::new("install")
+        .long("install")
+        .help("Installs kernelspec, requires 'jupyter' command to be available.")
+        .conflicts_with("kernel")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("kernel")
+        .long("kernel")
+        .help("Start the kernel")
+        .conflicts_with("install")
+        .requires("conn")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("conn")
+        .long("conn")
+        .help("Path to JSON file describing connection parameters, provided by Jupyter")
+        .value_parser(value_parser!(PathBuf))
+        .value_hint(ValueHint::FilePath)
+        .conflicts_with("install"),
+    )
+    .about("Deno kernel for Jupyter notebooks")
+    .long_about(
+      "Run a kernel for Jupyter notebooks, which evaluates the TypeScript and
+JavaScript cells of a notebook in one persistent session, like the REPL. The
+kernel runs with all permissions allowed.
+
+Install the kernel so Jupyter can find it:
+
+  deno jupyter --install
+
+Jupyter then starts the kernel itself with the connection file:
+
+  deno jupyter --kernel --conn connection.json
+
+Cells can display rich output: byte arrays containing an image are shown as
+that image, arrays of objects as a table, and objects implementing a
+[Symbol.for(\"Jupyter.display\")]() method returning a record of MIME types
+to data are displayed as that data.",
+    )
+}
+
 static LSP_HELP: &str = concat!(
   "The 'deno lsp' subcommand provides a way for code editors and IDEs to
 interact with Deno using the Language Server Protocol. Usually humans do not
@@ -1448,6 +2336,65 @@ fn lsp_subcommand() -> Command {
     .long_about(LSP_HELP)
 }
 
+fn lock_subcommand() -> Command {
+  Command::new("lock")
+    .about("Maintain the lock file")
+    .long_about(
+      "Maintain the lock file without editing or deleting it by hand.
+
+Check that every entry is still resolvable and matches its hash, using the
+cached remote modules unless --reload is specified:
+
+  deno lock --verify
+
+Remove the entries that aren't reachable from the entrypoints anymore:
+
+  deno lock --prune main.ts
+
+Resolve a dependency again, updating its entries. The dependency is an npm
+package name, a jsr: package or a remote url prefix:
+
+  deno lock --update chalk main.ts
+  deno lock --update https://deno.land/std@0.190.0/ main.ts",
+    )
+    .arg(
+      Arg::new("file")
+        .num_args(1..)
+        .required_unless_present("verify")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("verify")
+        .long("verify")
+        .help("Check every entry is resolvable and matches its hash")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("prune")
+        .long("prune")
+        .help("Remove the entries unreachable from the entrypoints")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("update")
+        .long("update")
+        .value_name("DEPENDENCY")
+        .help("Resolve a dependency again and update its entries")
+        .action(ArgAction::Append),
+    )
+    .group(
+      ArgGroup::new("action")
+        .args(["verify", "prune", "update"])
+        .required(true),
+    )
+    .arg(import_map_arg())
+    .arg(config_arg())
+    .arg(no_config_arg())
+    .arg(lock_arg())
+    .arg(reload_arg())
+    .arg(ca_file_arg())
+}
+
 fn lint_subcommand() -> Command {
   Command::new("lint")
     .about("Lint source files")
@@ -1552,6 +2499,73 @@ Ignore linting a file by adding an ignore comment at the top of the file:
     )
     .arg(watch_arg(false))
     .arg(no_clear_screen_arg())
+    .arg(watch_exclude_arg())
+}
+
+fn publish_subcommand() -> Command {
+  compile_args(Command::new("publish"))
+    .arg(check_arg(true))
+    .arg(
+      Arg::new("token")
+        .long("token")
+        .help("The token to authenticate to the registry with")
+        .value_name("TOKEN"),
+    )
+    .arg(
+      Arg::new("registry")
+        .long("registry")
+        .help("The url of the registry to publish to")
+        .value_name("URL")
+        .value_hint(ValueHint::Url),
+    )
+    .arg(
+      Arg::new("dry-run")
+        .long("dry-run")
+        .help("Validate and build the package without publishing it")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("allow-dirty")
+        .long("allow-dirty")
+        .help("Allow publishing with uncommitted changes")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("provenance")
+        .long("provenance")
+        .help(
+          "Attach a provenance statement when publishing from GitHub Actions",
+        )
+        .action(ArgAction::SetTrue),
+    )
+    .about("Publish the package of the current configuration file")
+    .long_about(
+      "Publish the package described by the \"name\", \"version\" and
+\"exports\" of the configuration file to a registry.
+
+  deno publish
+
+Before uploading, the package is validated: every export must exist, the
+exported modules are type-checked unless --no-check is specified, and the git
+repository must not have uncommitted changes unless --allow-dirty is specified.
+The files of the directory of the configuration file, except for the ones
+excluded in its \"publish\" configuration, are uploaded in a tarball along with
+a manifest of their checksums.
+
+The registry is the \"registry\" of the \"publish\" configuration unless
+--registry is specified, one of them is required. The token is taken from the
+\"auth\" configuration or DENO_AUTH_TOKENS for the host of the registry unless
+--token is specified.
+
+Validate the package and list its files without publishing it:
+
+  deno publish --dry-run
+
+In GitHub Actions, attach a provenance statement describing the workflow run,
+along with an OIDC token of the workflow to verify it with:
+
+  deno publish --provenance",
+    )
 }
 
 fn repl_subcommand() -> Command {
@@ -1585,7 +2599,11 @@ fn run_subcommand() -> Command {
         .conflicts_with("inspect-brk"),
     )
     .arg(no_clear_screen_arg())
+    .arg(watch_exclude_arg())
     .arg(executable_ext_arg())
+    .arg(sandbox_arg())
+    .arg(env_file_arg())
+    .arg(no_env_override_arg())
     .arg(
       script_arg()
         .required_unless_present("v8-flags")
@@ -1618,6 +2636,70 @@ Specifying the filename '-' to read the file from stdin.
     )
 }
 
+fn serve_subcommand() -> Command {
+  runtime_args(Command::new("serve"), true, true)
+    .arg(
+      Arg::new("port")
+        .long("port")
+        .help("The TCP port to serve on")
+        .default_value("8000")
+        .value_parser(value_parser!(u16)),
+    )
+    .arg(
+      Arg::new("host")
+        .long("host")
+        .help("The TCP address to serve on")
+        .default_value("0.0.0.0"),
+    )
+    .arg(
+      Arg::new("parallel")
+        .long("parallel")
+        .help("Run multiple server workers sharing the port. Parallelism defaults to the number of available CPUs or the value in the DENO_JOBS environment variable.")
+        .conflicts_with("watch")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(check_arg(false))
+    .arg(
+      watch_arg(true)
+        .conflicts_with("inspect")
+        .conflicts_with("inspect-wait")
+        .conflicts_with("inspect-brk"),
+    )
+    .arg(no_clear_screen_arg())
+    .arg(watch_exclude_arg())
+    .arg(executable_ext_arg())
+    .arg(
+      script_arg()
+        .required_unless_present("v8-flags")
+        .trailing_var_arg(true),
+    )
+    .about("Run a server")
+    .long_about(
+      "Run a server defined in a main module
+
+The main module must default export an object with a fetch handler, which
+is called with each request and returns the response:
+
+  export default {
+    fetch(req) {
+      return new Response(\"Hello world!\");
+    },
+  };
+
+The listener is owned by the CLI and configured with flags:
+
+  deno serve --allow-net --port 3000 main.ts
+
+Run a server worker per available CPU sharing the port (Linux only):
+
+  deno serve --unstable --allow-net --parallel main.ts
+
+In watch mode the server is closed and started again when files change:
+
+  deno serve --allow-net --watch main.ts",
+    )
+}
+
 fn task_subcommand() -> Command {
   Command::new("task")
     .allow_external_subcommands(true)
@@ -1630,6 +2712,8 @@ fn task_subcommand() -> Command {
         .help("Specify the directory to run the task in")
         .value_hint(ValueHint::DirPath),
     )
+    .arg(env_file_arg())
+    .arg(no_env_override_arg())
     .about("Run a task defined in the configuration file")
     .long_about(
       "Run a task defined in the configuration file
@@ -1641,6 +2725,7 @@ fn task_subcommand() -> Command {
 fn test_subcommand() -> Command {
   runtime_args(Command::new("test"), true, true)
     .arg(check_arg(true))
+    .arg(sandbox_arg())
     .arg(
       Arg::new("ignore")
         .long("ignore")
@@ -1700,6 +2785,58 @@ fn test_subcommand() -> Command {
         .require_equals(true)
         .value_parser(value_parser!(u64)),
     )
+    .arg(
+      Arg::new("isolate")
+        .long("isolate")
+        .help("Run each test file in a separate process")
+        .long_help(
+          "Runs each test file in a separate process, so that global state,
+changes to the environment and crashes, eg. of native code loaded with FFI,
+can't affect the other test files. Can be combined with --parallel.",
+        )
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("timeout")
+        .long("timeout")
+        .value_name("MS")
+        .help("Fail the tests that take longer than MS milliseconds")
+        .long_help(
+          "Fails the tests that take longer than MS milliseconds and reports the
+async ops that were still pending. The rest of the tests of the same module
+are cancelled, and the other modules are run as usual. The timeout option of
+Deno.test takes precedence.",
+        )
+        .require_equals(true)
+        .value_parser(value_parser!(u64).range(1..)),
+    )
+    .arg(
+      Arg::new("reporter")
+        .long("reporter")
+        .value_name("REPORTER")
+        .help("Select the reporter to use")
+        .long_help(
+          "Selects the reporter to use. The json reporter streams a JSON object
+per line to stdout for each test event (plan, test start, step, result with
+the error and its source mapped stack, output and summary), so that editors
+and dashboards can follow the progress without parsing the pretty output.",
+        )
+        .value_parser(["pretty", "json"]),
+    )
+    .arg(
+      Arg::new("shard")
+        .long("shard")
+        .value_name("INDEX/COUNT")
+        .help("Only run the test files of one shard, eg. --shard=2/8")
+        .long_help(
+          "Splits the test files into COUNT shards and only runs the ones of
+the shard INDEX, starting at 1. The files are assigned to the shards in sorted
+order, so the shards are disjoint and stable across runs, and running every
+shard runs every test file.",
+        )
+        .require_equals(true)
+        .value_parser(parse_shard_arg),
+    )
     .arg(
       Arg::new("coverage")
         .long("coverage")
@@ -1735,11 +2872,14 @@ fn test_subcommand() -> Command {
         .value_hint(ValueHint::AnyPath),
     )
     .arg(
-      watch_arg(false)
+      watch_arg(true)
         .conflicts_with("no-run")
         .conflicts_with("coverage"),
     )
     .arg(no_clear_screen_arg())
+    .arg(watch_exclude_arg())
+    .arg(env_file_arg())
+    .arg(no_env_override_arg())
     .arg(script_arg().last(true))
     .about("Run tests")
     .long_about(
@@ -1863,6 +3003,7 @@ Remote modules and multiple modules may also be specified:
     .arg(import_map_arg())
     .arg(lock_arg())
     .arg(reload_arg())
+    .arg(revalidate_arg())
     .arg(ca_file_arg())
 }
 
@@ -1874,55 +3015,70 @@ fn compile_args_without_check_args(app: Command) -> Command {
   app
     .arg(import_map_arg())
     .arg(no_remote_arg())
+    .arg(prefer_vendor_arg())
     .arg(no_npm_arg())
     .arg(local_npm_arg())
+    .arg(byonm_arg())
+    .arg(node_modules_layout_arg())
+    .arg(npm_copy_strategy_arg())
+    .arg(npm_concurrency_arg())
+    .arg(conditions_arg())
     .arg(config_arg())
     .arg(no_config_arg())
     .arg(reload_arg())
+    .arg(revalidate_arg())
+    .arg(max_redirects_arg())
+    .arg(no_redirects_arg())
     .arg(lock_arg())
     .arg(lock_write_arg())
     .arg(no_lock_arg())
     .arg(ca_file_arg())
+    .arg(client_cert_arg())
+    .arg(host_ca_arg())
 }
 
 static ALLOW_READ_HELP: &str = concat!(
-  "Allow file system read access. Optionally specify allowed paths.\n",
+  "Allow file system read access. Optionally specify allowed paths or globs.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-read\n",
-  "  --allow-read=\"/etc,/var/log.txt\""
+  "  --allow-read=\"/etc,/var/log.txt\"\n",
+  "  --allow-read=\"./data/**/*.json\""
 );
 
 static ALLOW_WRITE_HELP: &str = concat!(
-  "Allow file system write access. Optionally specify allowed paths.\n",
+  "Allow file system write access. Optionally specify allowed paths or globs.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-write\n",
-  "  --allow-write=\"/etc,/var/log.txt\""
+  "  --allow-write=\"/etc,/var/log.txt\"\n",
+  "  --allow-write=\"./data/**/*.json\""
 );
 
 static ALLOW_NET_HELP: &str = concat!(
-  "Allow network access. Optionally specify allowed IP addresses and host names, with ports as necessary.\n",
+  "Allow network access. Optionally specify allowed IP addresses, IP ranges in CIDR notation and host names, with ports as necessary.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-net\n",
-  "  --allow-net=\"localhost:8080,deno.land\""
+  "  --allow-net=\"localhost:8080,deno.land\"\n",
+  "  --allow-net=\"10.0.0.0/8,192.168.1.0/24:443\""
 );
 
 static ALLOW_ENV_HELP: &str = concat!(
-  "Allow access to system environment information. Optionally specify accessible environment variables.\n",
+  "Allow access to system environment information. Optionally specify accessible environment variables or globs.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-env\n",
-  "  --allow-env=\"PORT,HOME,PATH\""
+  "  --allow-env=\"PORT,HOME,PATH\"\n",
+  "  --allow-env=\"AWS_*,DENO_*\""
 );
 
 static ALLOW_SYS_HELP: &str = concat!(
@@ -1937,12 +3093,15 @@ static ALLOW_SYS_HELP: &str = concat!(
 
 static ALLOW_RUN_HELP: &str = concat!(
   "Allow running subprocesses. Optionally specify allowed runnable program names.\n",
+  "A program name may be followed by a colon and the arguments it is restricted to,\n",
+  "a trailing * allows any further arguments.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-run\n",
-  "  --allow-run=\"whoami,ps\""
+  "  --allow-run=\"whoami,ps\"\n",
+  "  --allow-run=\"git:status,git:log *\""
 );
 
 static ALLOW_FFI_HELP: &str = concat!(
@@ -1955,6 +3114,26 @@ static ALLOW_FFI_HELP: &str = concat!(
   "  --allow-ffi=\"./libfoo.so\""
 );
 
+static ALLOW_UNIX_HELP: &str = concat!(
+  "Allow listening on and connecting to unix sockets. Optionally specify allowed socket paths or directories.\n",
+  "Docs: https://deno.land/manual@v",
+  env!("CARGO_PKG_VERSION"),
+  "/basics/permissions\n",
+  "Examples:\n",
+  "  --allow-unix\n",
+  "  --allow-unix=\"/run/app.sock\""
+);
+
+static ALLOW_UDP_HELP: &str = concat!(
+  "Allow binding UDP sockets and sending datagrams. Optionally specify allowed IP addresses and host names, with ports as necessary.\n",
+  "Docs: https://deno.land/manual@v",
+  env!("CARGO_PKG_VERSION"),
+  "/basics/permissions\n",
+  "Examples:\n",
+  "  --allow-udp\n",
+  "  --allow-udp=\"1.1.1.1:53,0.0.0.0:5353\""
+);
+
 static ALLOW_HRTIME_HELP: &str = concat!(
   "Allow high-resolution time measurement. Note: this can enable timing attacks and fingerprinting.\n",
   "Docs: https://deno.land/manual@v",
@@ -1962,6 +3141,15 @@ static ALLOW_HRTIME_HELP: &str = concat!(
   "/basics/permissions\n"
 );
 
+static PERMISSIONS_POLICY_HELP: &str = concat!(
+  "Load allowed and denied permissions from a JSON policy file.\n",
+  "Docs: https://deno.land/manual@v",
+  env!("CARGO_PKG_VERSION"),
+  "/basics/permissions\n",
+  "Examples:\n",
+  "  --permissions=policy.json"
+);
+
 static ALLOW_ALL_HELP: &str = concat!(
   "Allow all permissions. Learn more about permissions in Deno:\n",
   "https://deno.land/manual@v",
@@ -2054,12 +3242,42 @@ fn permission_args(app: Command) -> Command {
         .value_parser(value_parser!(PathBuf))
         .value_hint(ValueHint::AnyPath),
     )
+    .arg(
+      Arg::new("allow-unix")
+        .long("allow-unix")
+        .num_args(0..)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .value_name("PATH")
+        .help(ALLOW_UNIX_HELP)
+        .value_parser(value_parser!(PathBuf))
+        .value_hint(ValueHint::AnyPath),
+    )
+    .arg(
+      Arg::new("allow-udp")
+        .long("allow-udp")
+        .num_args(0..)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .value_name("IP_OR_HOSTNAME")
+        .help(ALLOW_UDP_HELP)
+        .value_parser(flags_allow_net::validator),
+    )
     .arg(
       Arg::new("allow-hrtime")
         .long("allow-hrtime")
         .action(ArgAction::SetTrue)
         .help(ALLOW_HRTIME_HELP),
     )
+    .arg(
+      Arg::new("permissions")
+        .long("permissions")
+        .require_equals(true)
+        .value_name("FILE")
+        .help(PERMISSIONS_POLICY_HELP)
+        .value_parser(value_parser!(PathBuf))
+        .value_hint(ValueHint::FilePath),
+    )
     .arg(
       Arg::new("allow-all")
         .short('A')
@@ -2102,9 +3320,12 @@ fn runtime_args(
   };
   app
     .arg(cached_only_arg())
+    .arg(no_code_cache_arg())
     .arg(location_arg())
     .arg(v8_flags_arg())
     .arg(seed_arg())
+    .arg(max_heap_size_arg())
+    .arg(heap_snapshot_on_oom_arg())
     .arg(enable_testing_features_arg())
 }
 
@@ -2182,12 +3403,28 @@ fn reload_arg() -> Arg {
 --reload=npm:
   Reload all npm modules
 --reload=npm:chalk
-  Reload specific npm module",
+  Reload specific npm module
+--reload=stale
+  Revalidate cached remote modules whose cache headers mark them as stale,
+  only downloading the ones that changed",
     )
     .value_hint(ValueHint::FilePath)
     .value_parser(reload_arg_validate)
 }
 
+fn revalidate_arg() -> Arg {
+  Arg::new("revalidate")
+    .long("revalidate")
+    .action(ArgAction::SetTrue)
+    .conflicts_with("reload")
+    .help("Revalidate cached remote modules")
+    .long_help(
+      "Revalidate all cached remote modules using conditional requests \
+(If-None-Match/If-Modified-Since). Only modules that changed upstream \
+are downloaded again.",
+    )
+}
+
 fn ca_file_arg() -> Arg {
   Arg::new("cert")
     .long("cert")
@@ -2196,6 +3433,38 @@ fn ca_file_arg() -> Arg {
     .value_hint(ValueHint::FilePath)
 }
 
+fn client_cert_arg() -> Arg {
+  Arg::new("client-cert")
+    .long("client-cert")
+    .value_name("HOST=CERT_FILE,KEY_FILE")
+    .action(ArgAction::Append)
+    .value_parser(client_cert_arg_validate)
+    .help("Present a client certificate to a host requiring mutual TLS")
+    .long_help(
+      "Present a PEM encoded client certificate and private key when \
+fetching remote modules and npm packages from a host that requires mutual \
+TLS. Can be specified multiple times.
+  --client-cert=registry.example.com=./client.pem,./client.key",
+    )
+    .value_hint(ValueHint::Other)
+}
+
+fn host_ca_arg() -> Arg {
+  Arg::new("host-ca")
+    .long("host-ca")
+    .value_name("HOST=FILE")
+    .action(ArgAction::Append)
+    .value_parser(host_ca_arg_validate)
+    .help("Load certificate authority for a host from PEM encoded file")
+    .long_help(
+      "Load a PEM encoded certificate authority that is only trusted when \
+fetching remote modules and npm packages from the host, in addition to the \
+root certificates. Can be specified multiple times.
+  --host-ca=registry.example.com=./internal-ca.pem",
+    )
+    .value_hint(ValueHint::Other)
+}
+
 fn cached_only_arg() -> Arg {
   Arg::new("cached-only")
     .long("cached-only")
@@ -2203,6 +3472,18 @@ fn cached_only_arg() -> Arg {
     .help("Require that remote dependencies are already cached")
 }
 
+fn no_code_cache_arg() -> Arg {
+  Arg::new("no-code-cache")
+    .long("no-code-cache")
+    .action(ArgAction::SetTrue)
+    .help("Disable the V8 code cache of modules")
+    .long_help(
+      "Disable the V8 code cache of modules. By default, the code V8 compiles \
+for modules is cached in DENO_DIR, so unchanged modules don't need to be \
+compiled again on subsequent runs.",
+    )
+}
+
 /// Used for subcommands that operate on executable scripts only.
 /// `deno fmt` has its own `--ext` arg because its possible values differ.
 /// If --ext is not provided and the script doesn't have a file extension,
@@ -2262,7 +3543,34 @@ fn seed_arg() -> Arg {
     .value_parser(value_parser!(u64))
 }
 
-fn watch_arg(takes_files: bool) -> Arg {
+fn max_heap_size_arg() -> Arg {
+  Arg::new("max-heap-size")
+    .long("max-heap-size")
+    .value_name("MEGABYTES")
+    .help("Set the maximum size of the JavaScript heap in megabytes")
+    .long_help(
+      "Set the maximum size of the JavaScript heap in megabytes. The process \
+runs out of memory once the heap can't be shrunk below it anymore. This is a \
+shorthand for --v8-flags=--max-old-space-size=<MEGABYTES>.",
+    )
+    .value_parser(value_parser!(u64).range(1..))
+}
+
+fn heap_snapshot_on_oom_arg() -> Arg {
+  Arg::new("heap-snapshot-on-oom")
+    .long("heap-snapshot-on-oom")
+    .action(ArgAction::SetTrue)
+    .help(
+      "Write a heap snapshot when the process is about to run out of memory",
+    )
+    .long_help(
+      "Write a heap snapshot to the current directory the first time the \
+JavaScript heap gets close to its limit, before the process runs out of \
+memory. It can be loaded in the \"Memory\" tab of the Chrome DevTools.",
+    )
+}
+
+fn watch_arg(takes_files: bool) -> Arg {
   let arg = Arg::new("watch")
     .long("watch")
     .help("Watch for file changes and restart automatically");
@@ -2288,6 +3596,26 @@ Additional paths might be watched by passing them as arguments to this flag.",
   }
 }
 
+fn watch_exclude_arg() -> Arg {
+  Arg::new("watch-exclude")
+    .requires("watch")
+    .long("watch-exclude")
+    .value_name("FILES")
+    .num_args(0..)
+    .value_parser(value_parser!(PathBuf))
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .help("Exclude files or glob patterns from watch mode")
+    .long_help(
+      "Exclude files or glob patterns from watch mode. Changes to excluded \
+paths don't trigger a restart, which avoids restart loops when the watched \
+process generates files.
+
+  --watch-exclude=dist,\"**/*.generated.ts\"",
+    )
+    .value_hint(ValueHint::AnyPath)
+}
+
 fn no_clear_screen_arg() -> Arg {
   Arg::new("no-clear-screen")
     .requires("watch")
@@ -2296,6 +3624,52 @@ fn no_clear_screen_arg() -> Arg {
     .help("Do not clear terminal screen when under watch mode")
 }
 
+fn env_file_arg() -> Arg {
+  Arg::new("env")
+    .long("env")
+    .value_name("FILE")
+    .num_args(0..=1)
+    .require_equals(true)
+    .default_missing_value(".env")
+    .value_parser(value_parser!(PathBuf))
+    .action(ArgAction::Append)
+    .help("Load environment variables from a .env file")
+    .long_help(
+      "Load environment variables from a .env file before the program runs. \
+Defaults to '.env' in the current directory. Can be given multiple times, \
+in which case files given later take precedence over earlier ones. Variables \
+that are already set in the environment are overridden, unless \
+--no-env-override is given.",
+    )
+    .value_hint(ValueHint::FilePath)
+}
+
+fn no_env_override_arg() -> Arg {
+  Arg::new("no-env-override")
+    .long("no-env-override")
+    .requires("env")
+    .action(ArgAction::SetTrue)
+    .help("Don't override already set environment variables with --env files")
+}
+
+fn sandbox_arg() -> Arg {
+  Arg::new("sandbox")
+    .long("sandbox")
+    .action(ArgAction::SetTrue)
+    .help("Additionally sandbox the process on the OS level")
+    .long_help(
+      "Additionally sandbox the process on the OS level, aligned with the \
+permissions granted by flags, before the program runs. Uses a seccomp filter \
+and Landlock on Linux, a sandbox profile on macOS and a job object on \
+Windows. Permissions granted later on through prompts are still restricted \
+by the sandbox.
+
+Writing files is restricted to the paths of --allow-write, reading files only \
+when --allow-read lists paths. Paths that don't exist yet can't be created. \
+On Windows, only running subprocesses is restricted.",
+    )
+}
+
 fn no_check_arg() -> Arg {
   Arg::new("no-check")
     .num_args(0..=1)
@@ -2417,6 +3791,43 @@ fn no_remote_arg() -> Arg {
     .help("Do not resolve remote modules")
 }
 
+fn prefer_vendor_arg() -> Arg {
+  Arg::new("prefer-vendor")
+    .long("prefer-vendor")
+    .num_args(0..=1)
+    .value_parser(value_parser!(PathBuf))
+    .default_missing_value("vendor")
+    .require_equals(true)
+    .value_name("DIR")
+    .help("Use vendored copies of remote modules when available")
+    .long_help(
+      "Resolve remote modules to their copy in a directory created by `deno vendor` when they have been vendored, and fetch the remaining ones. Defaults to the \"vendor\" directory.
+  --prefer-vendor
+  --prefer-vendor=./third_party",
+    )
+    .value_hint(ValueHint::DirPath)
+}
+
+fn max_redirects_arg() -> Arg {
+  Arg::new("max-redirects")
+    .long("max-redirects")
+    .value_name("NUMBER")
+    .value_parser(value_parser!(u32))
+    .help("Maximum number of redirects to follow when fetching remote modules")
+    .long_help(
+      "Maximum number of redirects to follow when fetching a remote module. \
+Defaults to 10.",
+    )
+}
+
+fn no_redirects_arg() -> Arg {
+  Arg::new("no-redirects")
+    .long("no-redirects")
+    .action(ArgAction::SetTrue)
+    .conflicts_with("max-redirects")
+    .help("Error when fetching a remote module redirects")
+}
+
 fn no_npm_arg() -> Arg {
   Arg::new("no-npm")
     .long("no-npm")
@@ -2434,6 +3845,78 @@ fn local_npm_arg() -> Arg {
     .help("Creates a local node_modules folder")
 }
 
+fn byonm_arg() -> Arg {
+  Arg::new("byonm")
+    .long("byonm")
+    .action(ArgAction::SetTrue)
+    .conflicts_with_all(["no-npm", "node-modules-dir"])
+    .help("Resolve npm packages from an existing node_modules folder")
+    .long_help(
+      "Resolve npm: and bare specifiers against an existing node_modules \
+folder created by another package manager (ex. npm, pnpm or yarn) like \
+Node does. Deno does not install or modify any packages in this mode.",
+    )
+}
+
+fn node_modules_layout_arg() -> Arg {
+  Arg::new("node-modules-layout")
+    .long("node-modules-layout")
+    .require_equals(true)
+    .value_name("LAYOUT")
+    .value_parser(["copy", "linked"])
+    .help("How packages are created in a local node_modules folder")
+    .long_help(
+      "How packages are created in a local node_modules folder. \"copy\" \
+(the default) copies the files of every package from the npm cache, while \
+\"linked\" hard links them so all projects share a single copy on disk.",
+    )
+}
+
+fn npm_copy_strategy_arg() -> Arg {
+  Arg::new("npm-copy-strategy")
+    .long("npm-copy-strategy")
+    .require_equals(true)
+    .value_name("STRATEGY")
+    .value_parser(["hardlink", "reflink", "symlink", "copy"])
+    .help("How copies of packages in the npm cache are created")
+    .long_help(
+      "How the copies of packages in the npm cache are created, which are \
+needed when a package is resolved with different peer dependencies. \
+\"hardlink\" (the default) hard links the files, \"reflink\" clones them with \
+copy-on-write on file systems that support it (ex. btrfs or APFS), \
+\"symlink\" symlinks them and \"copy\" copies them. The files are copied \
+when the strategy isn't supported, ex. hard links across file systems.",
+    )
+}
+
+fn npm_concurrency_arg() -> Arg {
+  Arg::new("npm-concurrency")
+    .long("npm-concurrency")
+    .value_name("NUMBER")
+    .value_parser(value_parser!(u16).range(1..))
+    .help("Maximum number of npm packages to download at once")
+    .long_help(
+      "Maximum number of npm package tarballs to download at once. Defaults \
+to the DENO_NPM_CONCURRENCY environment variable or 16.",
+    )
+}
+
+fn conditions_arg() -> Arg {
+  Arg::new("conditions")
+    .long("conditions")
+    .num_args(1..)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .value_name("CONDITIONS")
+    .help("Custom conditions for resolving the exports of npm packages")
+    .long_help(
+      "Custom conditions matched in addition to the default ones when \
+resolving the \"exports\" and \"imports\" of npm packages.
+  --conditions=react-server,development",
+    )
+    .value_parser(conditions_arg_validate)
+}
+
 fn unsafely_ignore_certificate_errors_arg() -> Arg {
   Arg::new("unsafely-ignore-certificate-errors")
     .long("unsafely-ignore-certificate-errors")
@@ -2453,6 +3936,7 @@ fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   // NOTE: `deno bench` always uses `--no-prompt`, tests shouldn't ever do
   // interactive prompts, unless done by user code
   flags.no_prompt = true;
+  flags.sandbox = matches.get_flag("sandbox");
 
   let json = matches.get_flag("json");
 
@@ -2477,7 +3961,7 @@ fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
   let no_run = matches.get_flag("no-run");
 
-  watch_arg_parse(flags, matches, false);
+  watch_arg_parse(flags, matches, true);
   flags.subcommand = DenoSubcommand::Bench(BenchFlags {
     files: FileFlags { include, ignore },
     filter,
@@ -2500,6 +3984,10 @@ fn bundle_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     } else {
       None
     };
+  let out_dir = matches.remove_one::<PathBuf>("outdir");
+  if out_dir.is_some() {
+    flags.allow_write = Some(vec![]);
+  }
 
   watch_arg_parse(flags, matches, false);
   ext_arg_parse(flags, matches);
@@ -2507,13 +3995,51 @@ fn bundle_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.subcommand = DenoSubcommand::Bundle(BundleFlags {
     source_file,
     out_file,
+    out_dir,
   });
 }
 
 fn cache_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   compile_args_parse(flags, matches);
-  let files = matches.remove_many::<String>("file").unwrap().collect();
-  flags.subcommand = DenoSubcommand::Cache(CacheFlags { files });
+  let files = match matches.remove_many::<String>("file") {
+    Some(files) => files.collect(),
+    None => vec![],
+  };
+  let list = if matches.get_flag("list") {
+    Some(CacheListFlags {
+      filter: matches.remove_one::<String>("filter"),
+    })
+  } else {
+    None
+  };
+  let prune_npm = if matches.get_flag("prune-npm") {
+    Some(CleanFlags {
+      dry_run: matches.get_flag("dry-run"),
+      max_age: matches.remove_one::<Duration>("max-age"),
+      max_size: matches.remove_one::<u64>("max-size"),
+    })
+  } else {
+    None
+  };
+  let check_npm_integrity = if matches.get_flag("check-npm-integrity") {
+    Some(CheckNpmIntegrityFlags {
+      repair: matches.get_flag("repair"),
+    })
+  } else {
+    None
+  };
+  flags.subcommand = DenoSubcommand::Cache(CacheFlags {
+    files,
+    list,
+    json: matches.get_flag("json"),
+    print_integrity: matches.get_flag("print-integrity"),
+    synth_npm_types: matches.get_flag("synth-npm-types"),
+    prune_npm,
+    export: matches.remove_one::<PathBuf>("export"),
+    import: matches.remove_one::<PathBuf>("import"),
+    verify_npm: matches.get_flag("verify-npm"),
+    check_npm_integrity,
+  });
 }
 
 fn check_parse(flags: &mut Flags, matches: &mut ArgMatches) {
@@ -2523,22 +4049,36 @@ fn check_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if matches.get_flag("all") || matches.get_flag("remote") {
     flags.type_check_mode = TypeCheckMode::All;
   }
+  watch_arg_parse(flags, matches, false);
   flags.subcommand = DenoSubcommand::Check(CheckFlags { files });
 }
 
+fn clean_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Clean(CleanFlags {
+    dry_run: matches.get_flag("dry-run"),
+    max_age: matches.remove_one::<Duration>("max-age"),
+    max_size: matches.remove_one::<u64>("max-size"),
+  });
+}
+
 fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
   runtime_args_parse(flags, matches, true, false);
 
-  let mut script = matches.remove_many::<String>("script_arg").unwrap();
-  let source_file = script.next().unwrap();
-  let args = script.collect();
+  let (source_file, args) = match matches.remove_many::<String>("script_arg") {
+    Some(mut script) => (script.next().unwrap(), script.collect()),
+    None => (String::new(), vec![]),
+  };
   let output = matches.remove_one::<PathBuf>("output");
   let target = matches.remove_one::<String>("target");
   let include = match matches.remove_many::<String>("include") {
     Some(f) => f.collect(),
     None => vec![],
   };
+  let include_assets = match matches.remove_many::<PathBuf>("include-asset") {
+    Some(f) => f.collect(),
+    None => vec![],
+  };
   ext_arg_parse(flags, matches);
 
   flags.subcommand = DenoSubcommand::Compile(CompileFlags {
@@ -2547,6 +4087,15 @@ fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     args,
     target,
     include,
+    include_assets,
+    no_compress: matches.get_flag("no-compress"),
+    icon: matches.remove_one::<PathBuf>("icon"),
+    product_name: matches.remove_one::<String>("product-name"),
+    file_version: matches.remove_one::<String>("file-version"),
+    sign_cmd: matches.remove_one::<String>("sign-cmd"),
+    reproducible: matches.get_flag("reproducible"),
+    no_terminal: matches.get_flag("no-terminal"),
+    log_file: matches.remove_one::<String>("log-file"),
   });
 }
 
@@ -2613,6 +4162,7 @@ fn coverage_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 fn doc_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   import_map_arg_parse(flags, matches);
   reload_arg_parse(flags, matches);
+  revalidate_arg_parse(flags, matches);
   lock_arg_parse(flags, matches);
   no_lock_arg_parse(flags, matches);
   no_npm_arg_parse(flags, matches);
@@ -2648,6 +4198,8 @@ fn eval_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.allow_sys = Some(vec![]);
   flags.allow_write = Some(vec![]);
   flags.allow_ffi = Some(vec![]);
+  flags.allow_unix = Some(vec![]);
+  flags.allow_udp = Some(vec![]);
   flags.allow_hrtime = true;
 
   ext_arg_parse(flags, matches);
@@ -2676,7 +4228,7 @@ fn eval_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
 fn fmt_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   config_args_parse(flags, matches);
-  watch_arg_parse(flags, matches, false);
+  watch_arg_parse(flags, matches, true);
   ext_arg_parse(flags, matches);
 
   let include = match matches.remove_many::<PathBuf>("files") {
@@ -2715,6 +4267,7 @@ fn init_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
 fn info_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   reload_arg_parse(flags, matches);
+  revalidate_arg_parse(flags, matches);
   config_args_parse(flags, matches);
   import_map_arg_parse(flags, matches);
   location_arg_parse(flags, matches);
@@ -2804,6 +4357,53 @@ fn lint_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   });
 }
 
+fn jupyter_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let conn_file = matches.remove_one::<PathBuf>("conn");
+  let kernel = matches.get_flag("kernel");
+  let install = matches.get_flag("install");
+
+  flags.subcommand = DenoSubcommand::Jupyter(JupyterFlags {
+    install,
+    kernel,
+    conn_file,
+  });
+}
+
+fn lock_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  import_map_arg_parse(flags, matches);
+  config_args_parse(flags, matches);
+  lock_arg_parse(flags, matches);
+  reload_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
+  let files = match matches.remove_many::<String>("file") {
+    Some(files) => files.collect(),
+    None => vec![],
+  };
+  let update = match matches.remove_many::<String>("update") {
+    Some(update) => update.collect(),
+    None => vec![],
+  };
+  flags.subcommand = DenoSubcommand::Lock(LockFlags {
+    files,
+    verify: matches.get_flag("verify"),
+    prune: matches.get_flag("prune"),
+    update,
+  });
+}
+
+fn publish_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.type_check_mode = TypeCheckMode::Local;
+  compile_args_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Publish(PublishFlags {
+    token: matches.remove_one::<String>("token"),
+    registry: matches.remove_one::<String>("registry"),
+    dry_run: matches.get_flag("dry-run"),
+    allow_dirty: matches.get_flag("allow-dirty"),
+    provenance: matches.get_flag("provenance"),
+  });
+}
+
 fn repl_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   runtime_args_parse(flags, matches, true, true);
   unsafely_ignore_certificate_errors_parse(flags, matches);
@@ -2833,15 +4433,50 @@ fn run_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   ext_arg_parse(flags, matches);
 
   watch_arg_parse(flags, matches, true);
+  flags.sandbox = matches.get_flag("sandbox");
+  env_file_arg_parse(flags, matches);
   flags.subcommand = DenoSubcommand::Run(RunFlags { script });
 }
 
+fn serve_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let port = matches.remove_one::<u16>("port").unwrap();
+  let host = matches.remove_one::<String>("host").unwrap();
+  let worker_count = if matches.get_flag("parallel") {
+    if let Ok(value) = env::var("DENO_JOBS") {
+      value.parse::<NonZeroUsize>().ok()
+    } else {
+      std::thread::available_parallelism().ok()
+    }
+  } else {
+    None
+  };
+
+  runtime_args_parse(flags, matches, true, true);
+
+  let mut script_arg = matches.remove_many::<String>("script_arg").unwrap();
+
+  let script = script_arg.next().unwrap();
+  flags.argv.extend(script_arg);
+
+  ext_arg_parse(flags, matches);
+
+  watch_arg_parse(flags, matches, true);
+  flags.subcommand = DenoSubcommand::Serve(ServeFlags {
+    script,
+    port,
+    host,
+    worker_count,
+  });
+}
+
 fn task_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.config_flag = matches
     .remove_one::<String>("config")
     .map(ConfigFlag::Path)
     .unwrap_or(ConfigFlag::Discover);
 
+  env_file_arg_parse(flags, matches);
+
   let mut task_flags = TaskFlags {
     cwd: matches.remove_one::<String>("cwd"),
     task: None,
@@ -2868,6 +4503,7 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   // NOTE: `deno test` always uses `--no-prompt`, tests shouldn't ever do
   // interactive prompts, unless done by user code
   flags.no_prompt = true;
+  flags.sandbox = matches.get_flag("sandbox");
 
   let ignore = match matches.remove_many::<PathBuf>("ignore") {
     Some(f) => f.collect(),
@@ -2876,6 +4512,14 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
   let no_run = matches.get_flag("no-run");
   let trace_ops = matches.get_flag("trace-ops");
+  let shard = matches.remove_one::<TestShard>("shard");
+  let timeout = matches.remove_one::<u64>("timeout");
+  let isolate = matches.get_flag("isolate");
+  let reporter = match matches.remove_one::<String>("reporter").as_deref() {
+    Some("pretty") | None => TestReporterConfig::Pretty,
+    Some("json") => TestReporterConfig::Json,
+    _ => unreachable!(),
+  };
   let doc = matches.get_flag("doc");
   let allow_none = matches.get_flag("allow-none");
   let filter = matches.remove_one::<String>("filter");
@@ -2935,7 +4579,8 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   };
 
   flags.coverage_dir = matches.remove_one::<String>("coverage");
-  watch_arg_parse(flags, matches, false);
+  watch_arg_parse(flags, matches, true);
+  env_file_arg_parse(flags, matches);
   flags.subcommand = DenoSubcommand::Test(TestFlags {
     no_run,
     doc,
@@ -2946,6 +4591,10 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     allow_none,
     concurrent_jobs,
     trace_ops,
+    shard,
+    reporter,
+    timeout,
+    isolate,
   });
 }
 
@@ -2976,6 +4625,7 @@ fn vendor_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   import_map_arg_parse(flags, matches);
   lock_arg_parse(flags, matches);
   reload_arg_parse(flags, matches);
+  revalidate_arg_parse(flags, matches);
 
   flags.subcommand = DenoSubcommand::Vendor(VendorFlags {
     specifiers: matches
@@ -2999,12 +4649,22 @@ fn compile_args_without_check_parse(
 ) {
   import_map_arg_parse(flags, matches);
   no_remote_arg_parse(flags, matches);
+  prefer_vendor_arg_parse(flags, matches);
   no_npm_arg_parse(flags, matches);
   local_npm_args_parse(flags, matches);
+  byonm_arg_parse(flags, matches);
+  node_modules_layout_arg_parse(flags, matches);
+  npm_copy_strategy_arg_parse(flags, matches);
+  npm_concurrency_arg_parse(flags, matches);
+  conditions_arg_parse(flags, matches);
   config_args_parse(flags, matches);
   reload_arg_parse(flags, matches);
+  revalidate_arg_parse(flags, matches);
+  redirects_arg_parse(flags, matches);
   lock_args_parse(flags, matches);
   ca_file_arg_parse(flags, matches);
+  client_cert_arg_parse(flags, matches);
+  host_ca_arg_parse(flags, matches);
 }
 
 fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
@@ -3042,9 +4702,20 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     debug!("ffi allowlist: {:#?}", &flags.allow_ffi);
   }
 
+  if let Some(unix_wl) = matches.remove_many::<PathBuf>("allow-unix") {
+    flags.allow_unix = Some(unix_wl.collect());
+    debug!("unix allowlist: {:#?}", &flags.allow_unix);
+  }
+
+  if let Some(udp_wl) = matches.remove_many::<String>("allow-udp") {
+    let udp_allowlist = flags_allow_net::parse(udp_wl.collect()).unwrap();
+    flags.allow_udp = Some(udp_allowlist);
+  }
+
   if matches.get_flag("allow-hrtime") {
     flags.allow_hrtime = true;
   }
+  flags.permissions_policy = matches.remove_one::<PathBuf>("permissions");
   if matches.get_flag("allow-all") {
     flags.allow_all = true;
     flags.allow_read = Some(vec![]);
@@ -3054,6 +4725,8 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     flags.allow_write = Some(vec![]);
     flags.allow_sys = Some(vec![]);
     flags.allow_ffi = Some(vec![]);
+    flags.allow_unix = Some(vec![]);
+    flags.allow_udp = Some(vec![]);
     flags.allow_hrtime = true;
   }
   if matches.get_flag("no-prompt") {
@@ -3080,6 +4753,7 @@ fn runtime_args_parse(
 ) {
   compile_args_parse(flags, matches);
   cached_only_arg_parse(flags, matches);
+  no_code_cache_arg_parse(flags, matches);
   if include_perms {
     permission_args_parse(flags, matches);
   }
@@ -3089,6 +4763,8 @@ fn runtime_args_parse(
   location_arg_parse(flags, matches);
   v8_flags_arg_parse(flags, matches);
   seed_arg_parse(flags, matches);
+  max_heap_size_arg_parse(flags, matches);
+  heap_snapshot_on_oom_arg_parse(flags, matches);
   enable_testing_features_arg_parse(flags, matches);
 }
 
@@ -3129,7 +4805,14 @@ fn import_map_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
 fn reload_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if let Some(cache_bl) = matches.remove_many::<String>("reload") {
-    let raw_cache_blocklist: Vec<String> = cache_bl.collect();
+    let mut raw_cache_blocklist: Vec<String> = cache_bl.collect();
+    if raw_cache_blocklist.iter().any(|v| v == "stale") {
+      raw_cache_blocklist.retain(|v| v != "stale");
+      flags.reload_stale = true;
+      if raw_cache_blocklist.is_empty() {
+        return;
+      }
+    }
     if raw_cache_blocklist.is_empty() {
       flags.reload = true;
     } else {
@@ -3140,10 +4823,32 @@ fn reload_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   }
 }
 
+fn revalidate_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("revalidate") {
+    flags.revalidate = true;
+  }
+}
+
 fn ca_file_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.ca_data = matches.remove_one::<String>("cert").map(CaData::File);
 }
 
+fn client_cert_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(client_certs) =
+    matches.remove_many::<ClientCertificate>("client-cert")
+  {
+    flags.client_certs = client_certs.collect();
+  }
+}
+
+fn host_ca_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(host_ca_certs) =
+    matches.remove_many::<HostCaCertificate>("host-ca")
+  {
+    flags.host_ca_certs = host_ca_certs.collect();
+  }
+}
+
 fn enable_testing_features_arg_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
@@ -3159,10 +4864,23 @@ fn cached_only_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   }
 }
 
+fn no_code_cache_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("no-code-cache") {
+    flags.no_code_cache = true;
+  }
+}
+
 fn ext_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.ext = matches.remove_one::<String>("ext");
 }
 
+fn env_file_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(env_files) = matches.remove_many::<PathBuf>("env") {
+    flags.env_files = env_files.collect();
+  }
+  flags.no_env_override = matches.get_flag("no-env-override");
+}
+
 fn location_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.location = matches.remove_one::<Url>("location");
 }
@@ -3181,6 +4899,20 @@ fn seed_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   }
 }
 
+fn max_heap_size_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(max_heap_size) = matches.remove_one::<u64>("max-heap-size") {
+    flags
+      .v8_flags
+      .push(format!("--max-old-space-size={max_heap_size}"));
+  }
+}
+
+fn heap_snapshot_on_oom_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("heap-snapshot-on-oom") {
+    flags.heap_snapshot_on_oom = true;
+  }
+}
+
 fn no_check_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if let Some(cache_type) = matches.get_one::<String>("no-check") {
     match cache_type.as_str() {
@@ -3248,6 +4980,18 @@ fn no_remote_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   }
 }
 
+fn prefer_vendor_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.prefer_vendor = matches.remove_one::<PathBuf>("prefer-vendor");
+}
+
+fn redirects_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("no-redirects") {
+    flags.max_redirects = Some(0);
+  } else {
+    flags.max_redirects = matches.remove_one::<u32>("max-redirects");
+  }
+}
+
 fn no_npm_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if matches.get_flag("no-npm") {
     flags.no_npm = true;
@@ -3258,16 +5002,151 @@ fn local_npm_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.node_modules_dir = matches.remove_one::<bool>("node-modules-dir");
 }
 
+fn byonm_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("byonm") {
+    flags.byonm = true;
+  }
+}
+
+fn node_modules_layout_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.node_modules_layout = matches
+    .remove_one::<String>("node-modules-layout")
+    .map(|layout| NodeModulesLayout::parse(&layout).unwrap());
+}
+
+fn npm_copy_strategy_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.npm_copy_strategy = matches
+    .remove_one::<String>("npm-copy-strategy")
+    .map(|strategy| NpmCopyStrategy::parse(&strategy).unwrap());
+}
+
+fn npm_concurrency_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.npm_concurrency = matches
+    .remove_one::<u16>("npm-concurrency")
+    .map(usize::from);
+}
+
+fn conditions_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(conditions) = matches.remove_many::<String>("conditions") {
+    flags.node_conditions = conditions.collect();
+  }
+}
+
+fn conditions_arg_validate(condition: &str) -> Result<String, String> {
+  if condition.is_empty() {
+    return Err(String::from("Missing condition. Check for extra commas."));
+  }
+  Ok(condition.to_string())
+}
+
 fn reload_arg_validate(urlstr: &str) -> Result<String, String> {
   if urlstr.is_empty() {
     return Err(String::from("Missing url. Check for extra commas."));
   }
+  if urlstr == "stale" {
+    return Ok(urlstr.to_string());
+  }
   match Url::from_str(urlstr) {
     Ok(_) => Ok(urlstr.to_string()),
     Err(e) => Err(e.to_string()),
   }
 }
 
+fn client_cert_arg_validate(value: &str) -> Result<ClientCertificate, String> {
+  let invalid = || {
+    format!(
+      "Invalid client certificate \"{value}\". Expected HOST=CERT_FILE,KEY_FILE."
+    )
+  };
+  let (host, files) = value.split_once('=').ok_or_else(invalid)?;
+  let (cert_file, key_file) = files.split_once(',').ok_or_else(invalid)?;
+  if host.is_empty()
+    || host.contains('/')
+    || cert_file.is_empty()
+    || key_file.is_empty()
+  {
+    return Err(invalid());
+  }
+  Ok(ClientCertificate {
+    host: host.to_lowercase(),
+    cert_file: PathBuf::from(cert_file),
+    key_file: PathBuf::from(key_file),
+  })
+}
+
+fn host_ca_arg_validate(value: &str) -> Result<HostCaCertificate, String> {
+  match value.split_once('=') {
+    Some((host, ca_file))
+      if !host.is_empty() && !host.contains('/') && !ca_file.is_empty() =>
+    {
+      Ok(HostCaCertificate {
+        host: host.to_lowercase(),
+        ca_file: PathBuf::from(ca_file),
+      })
+    }
+    _ => Err(format!(
+      "Invalid host certificate authority \"{value}\". Expected HOST=FILE."
+    )),
+  }
+}
+
+/// Parses a duration like `45s`, `90m`, `12h`, `30d` or `2w`.
+fn parse_duration_arg(value: &str) -> Result<Duration, String> {
+  let invalid = || {
+    format!("Invalid duration \"{value}\". Expected a number followed by s, m, h, d or w, eg. 30d.")
+  };
+  let unit_index = match value.char_indices().last() {
+    Some((index, _)) => index,
+    None => return Err(invalid()),
+  };
+  let (amount, unit) = value.split_at(unit_index);
+  let amount = amount.parse::<u64>().map_err(|_| invalid())?;
+  let unit_secs = match unit {
+    "s" => 1,
+    "m" => 60,
+    "h" => 60 * 60,
+    "d" => 24 * 60 * 60,
+    "w" => 7 * 24 * 60 * 60,
+    _ => return Err(invalid()),
+  };
+  Ok(Duration::from_secs(amount * unit_secs))
+}
+
+/// Parses a test shard like `2/8`.
+fn parse_shard_arg(value: &str) -> Result<TestShard, String> {
+  let invalid = || {
+    format!("Invalid shard \"{value}\". Expected INDEX/COUNT with 1 <= INDEX <= COUNT, eg. 2/8.")
+  };
+  let (index, count) = value.split_once('/').ok_or_else(invalid)?;
+  let index = index.parse::<usize>().map_err(|_| invalid())?;
+  let count = count.parse::<usize>().map_err(|_| invalid())?;
+  if index == 0 || index > count {
+    return Err(invalid());
+  }
+  Ok(TestShard { index, count })
+}
+
+/// Parses a size in bytes like `500MB`, `1.5GB` or `1024`.
+pub fn parse_size_arg(value: &str) -> Result<u64, String> {
+  let invalid = || {
+    format!("Invalid size \"{value}\". Expected a number followed by an optional B, KB, MB, GB or TB, eg. 5GB.")
+  };
+  let number_end = value
+    .find(|c: char| !c.is_ascii_digit() && c != '.')
+    .unwrap_or(value.len());
+  let (amount, unit) = value.split_at(number_end);
+  let amount = amount.parse::<f64>().map_err(|_| invalid())?;
+  let exponent = match unit.to_uppercase().as_str() {
+    "" | "B" => 0,
+    "KB" => 1,
+    "MB" => 2,
+    "GB" => 3,
+    "TB" => 4,
+    _ => return Err(invalid()),
+  };
+  Ok((amount * 1024_f64.powi(exponent)) as u64)
+}
+
 fn watch_arg_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
@@ -3281,6 +5160,10 @@ fn watch_arg_parse(
     flags.watch = Some(vec![]);
   }
 
+  if let Some(f) = matches.remove_many::<PathBuf>("watch-exclude") {
+    flags.watch_exclude = f.collect();
+  }
+
   if matches.get_flag("no-clear-screen") {
     flags.no_clear_screen = true;
   }
@@ -3388,47 +5271,180 @@ mod tests {
   }
 
   #[test]
-  fn run_watch() {
-    let r = flags_from_vec(svec!["deno", "run", "--watch", "script.ts"]);
-    let flags = r.unwrap();
+  fn run_offline() {
+    let r = flags_from_vec(svec!["deno", "run", "--offline", "script.ts"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
         }),
-        watch: Some(vec![]),
+        offline: true,
         ..Flags::default()
       }
     );
+
+    // global flag
+    let r = flags_from_vec(svec!["deno", "--offline", "cache", "script.ts"]);
+    assert!(r.unwrap().offline);
   }
 
   #[test]
-  fn run_watch_with_external() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--watch=file1,file2", "script.ts"]);
-    let flags = r.unwrap();
+  fn run_sandbox() {
+    let r = flags_from_vec(svec!["deno", "run", "--sandbox", "script.ts"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
         }),
-        watch: Some(vec![PathBuf::from("file1"), PathBuf::from("file2")]),
+        sandbox: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn run_watch_with_no_clear_screen() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--watch",
-      "--no-clear-screen",
-      "script.ts"
-    ]);
+  fn test_and_bench_sandbox() {
+    let r = flags_from_vec(svec!["deno", "test", "--sandbox"]);
+    assert!(r.unwrap().sandbox);
+    let r = flags_from_vec(svec!["deno", "bench", "--sandbox"]);
+    assert!(r.unwrap().sandbox);
+  }
+
+  #[test]
+  fn run_watch() {
+    let r = flags_from_vec(svec!["deno", "run", "--watch", "script.ts"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        watch: Some(vec![]),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn serve() {
+    let r = flags_from_vec(svec!["deno", "serve", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags {
+          script: "main.ts".to_string(),
+          port: 8000,
+          host: "0.0.0.0".to_string(),
+          worker_count: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--port",
+      "3000",
+      "--host",
+      "127.0.0.1",
+      "--watch",
+      "main.ts",
+      "--flag"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags {
+          script: "main.ts".to_string(),
+          port: 3000,
+          host: "127.0.0.1".to_string(),
+          worker_count: None,
+        }),
+        argv: svec!["--flag"],
+        watch: Some(vec![]),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "serve", "--parallel", "main.ts"]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Serve(serve_flags) => {
+        assert!(serve_flags.worker_count.is_some());
+      }
+      _ => unreachable!(),
+    }
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--parallel",
+      "--watch",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_watch_with_external() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--watch=file1,file2", "script.ts"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        watch: Some(vec![PathBuf::from("file1"), PathBuf::from("file2")]),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_watch_with_excluded_paths() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--watch-exclude=dist,**/*.gen.ts",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        watch: Some(vec![]),
+        watch_exclude: vec![
+          PathBuf::from("dist"),
+          PathBuf::from("**/*.gen.ts")
+        ],
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--watch-exclude=dist", "script.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_watch_with_no_clear_screen() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
 
     let flags = r.unwrap();
     assert_eq!(
@@ -3551,6 +5567,8 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_unix: Some(vec![]),
+        allow_udp: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4135,111 +6153,167 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn check() {
-    let r = flags_from_vec(svec!["deno", "check", "script.ts"]);
+    let r = flags_from_vec(svec!["deno", "cache", "--json", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Check(CheckFlags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          list: None,
+          json: true,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
-        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
 
-    for all_flag in ["--remote", "--all"] {
-      let r = flags_from_vec(svec!["deno", "check", all_flag, "script.ts"]);
-      assert_eq!(
-        r.unwrap(),
-        Flags {
-          subcommand: DenoSubcommand::Check(CheckFlags {
-            files: svec!["script.ts"],
-          }),
-          type_check_mode: TypeCheckMode::All,
-          ..Flags::default()
-        }
-      );
-
-      let r = flags_from_vec(svec![
-        "deno",
-        "check",
-        all_flag,
-        "--no-remote",
-        "script.ts"
-      ]);
-      assert_eq!(
-        r.unwrap_err().kind(),
-        clap::error::ErrorKind::ArgumentConflict
-      );
-    }
-  }
-
-  #[test]
-  fn info() {
-    let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--print-integrity", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          list: None,
           json: false,
-          file: Some("script.ts".to_string()),
+          print_integrity: true,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
         ..Flags::default()
       }
     );
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--print-integrity",
+      "--json",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
 
-    let r = flags_from_vec(svec!["deno", "info", "--reload", "script.ts"]);
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--synth-npm-types", "main.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["main.ts"],
+          list: None,
           json: false,
-          file: Some("script.ts".to_string()),
+          print_integrity: false,
+          synth_npm_types: true,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
-        reload: true,
         ..Flags::default()
       }
     );
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--list", "--synth-npm-types"]);
+    assert!(r.is_err());
+  }
 
-    let r = flags_from_vec(svec!["deno", "info", "--json", "script.ts"]);
+  #[test]
+  fn cache_list() {
+    let r = flags_from_vec(svec!["deno", "cache", "--list"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: true,
-          file: Some("script.ts".to_string()),
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          list: Some(CacheListFlags { filter: None }),
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "info"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--list",
+      "--filter=deno.land",
+      "--json"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: false,
-          file: None
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          list: Some(CacheListFlags {
+            filter: Some("deno.land".to_string()),
+          }),
+          json: true,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "info", "--json"]);
+    let r = flags_from_vec(svec!["deno", "cache", "--list", "script.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "cache", "--filter=deno.land"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn cache_prune_npm() {
+    let r = flags_from_vec(svec!["deno", "cache", "--prune-npm"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: true,
-          file: None
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: Some(CleanFlags::default()),
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
         ..Flags::default()
       }
@@ -4247,84 +6321,712 @@ mod tests {
 
     let r = flags_from_vec(svec![
       "deno",
-      "info",
-      "--no-npm",
-      "--no-remote",
-      "--config",
-      "tsconfig.json"
+      "cache",
+      "--prune-npm",
+      "--max-age=30d",
+      "--max-size=5GB",
+      "--dry-run"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          list: None,
           json: false,
-          file: None
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: Some(CleanFlags {
+            dry_run: true,
+            max_age: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+            max_size: Some(5 * 1024 * 1024 * 1024),
+          }),
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
-        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
-        no_npm: true,
-        no_remote: true,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "cache", "--prune-npm", "main.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "cache", "--max-age=30d", "main.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn tsconfig() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "-c", "tsconfig.json", "script.ts"]);
+  fn cache_export_import() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--export=cache.tar.gz",
+      "main.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["main.ts"],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: Some(PathBuf::from("cache.tar.gz")),
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
-        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn eval() {
-    let r = flags_from_vec(svec!["deno", "eval", "'console.log(\"hello\")'"]);
+    let r = flags_from_vec(svec!["deno", "cache", "--import=cache.tar.gz"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Eval(EvalFlags {
-          print: false,
-          code: "'console.log(\"hello\")'".to_string(),
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: Some(PathBuf::from("cache.tar.gz")),
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
-        allow_net: Some(vec![]),
-        allow_env: Some(vec![]),
-        allow_run: Some(vec![]),
-        allow_read: Some(vec![]),
-        allow_sys: Some(vec![]),
-        allow_write: Some(vec![]),
-        allow_ffi: Some(vec![]),
-        allow_hrtime: true,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "cache", "--export=cache.tar.gz"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--import=cache.tar.gz",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn eval_p() {
-    let r = flags_from_vec(svec!["deno", "eval", "-p", "1+2"]);
+  fn cache_verify_npm() {
+    let r = flags_from_vec(svec!["deno", "cache", "--verify-npm"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Eval(EvalFlags {
-          print: true,
-          code: "1+2".to_string(),
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: true,
+          check_npm_integrity: None,
         }),
-        allow_net: Some(vec![]),
-        allow_env: Some(vec![]),
-        allow_run: Some(vec![]),
-        allow_read: Some(vec![]),
-        allow_sys: Some(vec![]),
-        allow_write: Some(vec![]),
-        allow_ffi: Some(vec![]),
-        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "cache", "--verify-npm", "main.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn cache_check_npm_integrity() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--check-npm-integrity",
+      "--repair"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: Some(CheckNpmIntegrityFlags { repair: true }),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "cache", "--repair"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--check-npm-integrity",
+      "--verify-npm"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn check() {
+    let r = flags_from_vec(svec!["deno", "check", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    for all_flag in ["--remote", "--all"] {
+      let r = flags_from_vec(svec!["deno", "check", all_flag, "script.ts"]);
+      assert_eq!(
+        r.unwrap(),
+        Flags {
+          subcommand: DenoSubcommand::Check(CheckFlags {
+            files: svec!["script.ts"],
+          }),
+          type_check_mode: TypeCheckMode::All,
+          ..Flags::default()
+        }
+      );
+
+      let r = flags_from_vec(svec![
+        "deno",
+        "check",
+        all_flag,
+        "--no-remote",
+        "script.ts"
+      ]);
+      assert_eq!(
+        r.unwrap_err().kind(),
+        clap::error::ErrorKind::ArgumentConflict
+      );
+    }
+  }
+
+  #[test]
+  fn run_redirects() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--max-redirects", "3", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        max_redirects: Some(3),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "run", "--no-redirects", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        max_redirects: Some(0),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--no-redirects",
+      "--max-redirects=3",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::error::ErrorKind::ArgumentConflict
+    );
+  }
+
+  #[test]
+  fn run_client_cert() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--client-cert=Registry.example.com=./client.pem,./client.key",
+      "--client-cert",
+      "git.example.com:8443=/certs/git.pem,/certs/git.key",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        client_certs: vec![
+          ClientCertificate {
+            host: "registry.example.com".to_string(),
+            cert_file: PathBuf::from("./client.pem"),
+            key_file: PathBuf::from("./client.key"),
+          },
+          ClientCertificate {
+            host: "git.example.com:8443".to_string(),
+            cert_file: PathBuf::from("/certs/git.pem"),
+            key_file: PathBuf::from("/certs/git.key"),
+          },
+        ],
+        ..Flags::default()
+      }
+    );
+
+    for invalid in [
+      "registry.example.com",
+      "registry.example.com=./client.pem",
+      "https://registry.example.com/=./client.pem,./client.key",
+      "=./client.pem,./client.key",
+    ] {
+      let r = flags_from_vec(svec![
+        "deno",
+        "run",
+        format!("--client-cert={invalid}"),
+        "script.ts"
+      ]);
+      assert!(r.is_err(), "{invalid}");
+    }
+  }
+
+  #[test]
+  fn run_host_ca() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--cert=./public-ca.pem",
+      "--host-ca=Registry.example.com=./internal-ca.pem",
+      "--host-ca",
+      "git.example.com:8443=/certs/git-ca.pem",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        ca_data: Some(CaData::File("./public-ca.pem".to_string())),
+        host_ca_certs: vec![
+          HostCaCertificate {
+            host: "registry.example.com".to_string(),
+            ca_file: PathBuf::from("./internal-ca.pem"),
+          },
+          HostCaCertificate {
+            host: "git.example.com:8443".to_string(),
+            ca_file: PathBuf::from("/certs/git-ca.pem"),
+          },
+        ],
+        ..Flags::default()
+      }
+    );
+
+    for invalid in ["registry.example.com", "registry.example.com=", "=ca.pem"]
+    {
+      let r = flags_from_vec(svec![
+        "deno",
+        "run",
+        format!("--host-ca={invalid}"),
+        "script.ts"
+      ]);
+      assert!(r.is_err(), "{invalid}");
+    }
+  }
+
+  #[test]
+  fn jupyter() {
+    let r = flags_from_vec(svec!["deno", "jupyter", "--install"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: true,
+          ..JupyterFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "jupyter",
+      "--kernel",
+      "--conn",
+      "path/to/conn/file"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: false,
+          kernel: true,
+          conn_file: Some(PathBuf::from("path/to/conn/file")),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    // the kernel needs a connection file
+    assert!(flags_from_vec(svec!["deno", "jupyter", "--kernel"]).is_err());
+    assert!(flags_from_vec(svec![
+      "deno",
+      "jupyter",
+      "--install",
+      "--kernel",
+      "--conn",
+      "path/to/conn/file"
+    ])
+    .is_err());
+  }
+
+  #[test]
+  fn publish() {
+    let r = flags_from_vec(svec!["deno", "publish"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Publish(PublishFlags::default()),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "publish",
+      "--no-check",
+      "--token",
+      "secret",
+      "--registry",
+      "https://registry.example.com",
+      "--dry-run",
+      "--allow-dirty",
+      "--provenance"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Publish(PublishFlags {
+          token: Some("secret".to_string()),
+          registry: Some("https://registry.example.com".to_string()),
+          dry_run: true,
+          allow_dirty: true,
+          provenance: true,
+        }),
+        type_check_mode: TypeCheckMode::None,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn lock() {
+    let r = flags_from_vec(svec!["deno", "lock", "--verify"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lock(LockFlags {
+          verify: true,
+          ..LockFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lock",
+      "--lock=deno.lock",
+      "--prune",
+      "main.ts",
+      "worker.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lock(LockFlags {
+          files: svec!["main.ts", "worker.ts"],
+          prune: true,
+          ..LockFlags::default()
+        }),
+        lock: Some(PathBuf::from("deno.lock")),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lock",
+      "--update",
+      "chalk",
+      "--update",
+      "jsr:@std/fs",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lock(LockFlags {
+          files: svec!["main.ts"],
+          update: svec!["chalk", "jsr:@std/fs"],
+          ..LockFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
+
+    // an action is required
+    assert!(flags_from_vec(svec!["deno", "lock", "main.ts"]).is_err());
+    // only one action at a time
+    assert!(flags_from_vec(svec![
+      "deno", "lock", "--verify", "--prune", "main.ts"
+    ])
+    .is_err());
+    // pruning needs the entrypoints
+    assert!(flags_from_vec(svec!["deno", "lock", "--prune"]).is_err());
+  }
+
+  #[test]
+  fn clean() {
+    let r = flags_from_vec(svec!["deno", "clean"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Clean(CleanFlags::default()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "clean",
+      "--max-age=30d",
+      "--max-size",
+      "1.5GB",
+      "--dry-run"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Clean(CleanFlags {
+          dry_run: true,
+          max_age: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+          max_size: Some(1024 * 1024 * 1536),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    assert_eq!(parse_duration_arg("45s"), Ok(Duration::from_secs(45)));
+    assert_eq!(parse_duration_arg("2w"), Ok(Duration::from_secs(1209600)));
+    assert!(parse_duration_arg("30").is_err());
+    assert!(parse_duration_arg("d").is_err());
+    assert!(parse_duration_arg("").is_err());
+    assert_eq!(parse_size_arg("1024"), Ok(1024));
+    assert_eq!(parse_size_arg("500mb"), Ok(500 * 1024 * 1024));
+    assert!(parse_size_arg("GB").is_err());
+    assert!(parse_size_arg("5PB").is_err());
+  }
+
+  #[test]
+  fn check_watch() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "check",
+      "--watch",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        watch: Some(vec![]),
+        no_clear_screen: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn info() {
+    let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: Some("script.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--reload", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: Some("script.ts".to_string()),
+        }),
+        reload: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--json", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: Some("script.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: None
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: None
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--no-npm",
+      "--no-remote",
+      "--config",
+      "tsconfig.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: None
+        }),
+        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
+        no_npm: true,
+        no_remote: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn tsconfig() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "-c", "tsconfig.json", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn eval() {
+    let r = flags_from_vec(svec!["deno", "eval", "'console.log(\"hello\")'"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: false,
+          code: "'console.log(\"hello\")'".to_string(),
+        }),
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_sys: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_ffi: Some(vec![]),
+        allow_unix: Some(vec![]),
+        allow_udp: Some(vec![]),
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn eval_stdin() {
+    let r = flags_from_vec(svec!["deno", "eval", "--ext=ts", "-"]);
+    let flags = r.unwrap();
+    assert_eq!(flags.ext, Some("ts".to_string()));
+    match flags.subcommand {
+      DenoSubcommand::Eval(eval_flags) => assert!(eval_flags.is_stdin()),
+      _ => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn eval_p() {
+    let r = flags_from_vec(svec!["deno", "eval", "-p", "1+2"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: true,
+          code: "1+2".to_string(),
+        }),
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_sys: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_ffi: Some(vec![]),
+        allow_unix: Some(vec![]),
+        allow_udp: Some(vec![]),
+        allow_hrtime: true,
         ..Flags::default()
       }
     );
@@ -4348,6 +7050,8 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_unix: Some(vec![]),
+        allow_udp: Some(vec![]),
         allow_hrtime: true,
         ext: Some("ts".to_string()),
         ..Flags::default()
@@ -4386,6 +7090,8 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_unix: Some(vec![]),
+        allow_udp: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4416,6 +7122,8 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_unix: Some(vec![]),
+        allow_udp: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4441,6 +7149,8 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_unix: Some(vec![]),
+        allow_udp: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4480,6 +7190,8 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_unix: Some(vec![]),
+        allow_udp: Some(vec![]),
         allow_hrtime: true,
         unsafely_ignore_certificate_errors: Some(vec![]),
         ..Flags::default()
@@ -4552,6 +7264,32 @@ mod tests {
     );
   }
 
+  #[test]
+  fn permissions_policy() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permissions=policy.json",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        permissions_policy: Some(PathBuf::from("policy.json")),
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        ..Flags::default()
+      }
+    );
+    assert!(flags.has_permission());
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--permissions=policy.json"]
+    );
+  }
+
   #[test]
   fn allow_write_allowlist() {
     use test_util::TempDir;
@@ -4576,6 +7314,49 @@ mod tests {
     );
   }
 
+  #[test]
+  fn allow_unix_allowlist() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-unix=/run/app.sock,./sockets",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        allow_unix: Some(vec![
+          PathBuf::from("/run/app.sock"),
+          PathBuf::from("./sockets")
+        ]),
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn allow_udp_allowlist() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-udp=1.1.1.1:53,0.0.0.0",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        allow_udp: Some(svec!["1.1.1.1:53", "0.0.0.0"]),
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn allow_net_allowlist() {
     let r = flags_from_vec(svec![
@@ -4660,6 +7441,35 @@ mod tests {
     );
   }
 
+  #[test]
+  fn allow_run_allowlist_args() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-run=git:status,git:log,ls",
+      "--allow-run=cargo:build --release,whoami",
+      "--allow-run=ps",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        allow_run: Some(svec![
+          "git:status",
+          "git:log",
+          "ls",
+          "cargo:build --release",
+          "whoami",
+          "ps"
+        ]),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn allow_sys_allowlist() {
     let r =
@@ -4786,10 +7596,76 @@ mod tests {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--reload=http://deno.land/a,,http://deno.land/b",
+      "--reload=http://deno.land/a,,http://deno.land/b",
+      "script.ts"
+    ]);
+    assert!(r.is_err(), "Should reject adjacent commas");
+  }
+
+  #[test]
+  fn reload_stale() {
+    let r = flags_from_vec(svec!["deno", "run", "--reload=stale", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        reload_stale: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--reload=stale,https://deno.land/std",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
+        }),
+        reload_stale: true,
+        cache_blocklist: svec!["https://deno.land/std"],
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn revalidate() {
+    let r = flags_from_vec(svec!["deno", "run", "--revalidate", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        revalidate: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--revalidate",
+      "--reload",
       "script.ts"
     ]);
-    assert!(r.is_err(), "Should reject adjacent commas");
+    assert!(r.is_err());
   }
 
   #[test]
@@ -4801,6 +7677,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          out_dir: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -4825,6 +7702,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: Some(PathBuf::from("bundle.js")),
+          out_dir: None,
         }),
         allow_write: Some(vec![]),
         no_remote: true,
@@ -4844,12 +7722,42 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: Some(PathBuf::from("bundle.js")),
+          out_dir: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        allow_write: Some(vec![]),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn bundle_with_outdir() {
+    let r =
+      flags_from_vec(svec!["deno", "bundle", "--outdir", "dist", "source.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          source_file: "source.ts".to_string(),
+          out_file: None,
+          out_dir: Some(PathBuf::from("dist")),
         }),
         type_check_mode: TypeCheckMode::Local,
         allow_write: Some(vec![]),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--outdir",
+      "dist",
+      "source.ts",
+      "bundle.js"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -4867,6 +7775,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          out_dir: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         lock_write: true,
@@ -4886,6 +7795,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          out_dir: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -4903,6 +7813,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "script.ts".to_string(),
           out_file: None,
+          out_dir: None,
         }),
         type_check_mode: TypeCheckMode::None,
         ..Flags::default()
@@ -4919,6 +7830,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          out_dir: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         watch: Some(vec![]),
@@ -4942,6 +7854,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          out_dir: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         watch: Some(vec![]),
@@ -5005,6 +7918,15 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -5044,6 +7966,15 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts", "script_two.ts"],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
         ..Flags::default()
       }
@@ -5066,6 +7997,74 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_env_file() {
+    let r = flags_from_vec(svec!["deno", "run", "--env", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        env_files: vec![PathBuf::from(".env")],
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--env=.env.defaults",
+      "--env=.env.local",
+      "--no-env-override",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        env_files: vec![
+          PathBuf::from(".env.defaults"),
+          PathBuf::from(".env.local")
+        ],
+        no_env_override: true,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--no-env-override", "script.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_heap_flags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--max-heap-size=512",
+      "--heap-snapshot-on-oom",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        v8_flags: svec!["--max-old-space-size=512"],
+        heap_snapshot_on_oom: true,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--max-heap-size=0", "script.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_seed_with_v8_flags() {
     let r = flags_from_vec(svec![
@@ -5182,6 +8181,49 @@ mod tests {
     );
   }
 
+  #[test]
+  fn log_format() {
+    let r = flags_from_vec(svec!["deno", "test", "--log-format=json"]);
+    let flags = r.unwrap();
+    assert_eq!(flags.log_format, LogFormat::Json);
+
+    let r =
+      flags_from_vec(svec!["deno", "--log-format", "text", "run", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        log_format: LogFormat::Text,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--log-format=yaml", "script.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn quiet_progress() {
+    let r = flags_from_vec(svec!["deno", "run", "--quiet-progress", "main.ts"]);
+    let flags = r.unwrap();
+    assert!(flags.quiet_progress);
+    assert_eq!(flags.log_level, None);
+  }
+
+  #[test]
+  fn trace_out() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--trace-out=trace.json",
+      "main.ts"
+    ]);
+    assert_eq!(r.unwrap().trace_out, Some(PathBuf::from("trace.json")));
+  }
+
   #[test]
   fn quiet() {
     let r = flags_from_vec(svec!["deno", "run", "-q", "script.ts"]);
@@ -5436,6 +8478,48 @@ mod tests {
     );
   }
 
+  #[test]
+  fn prefer_vendor() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--prefer-vendor", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        prefer_vendor: Some(PathBuf::from("vendor")),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--prefer-vendor=./third_party",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
+        }),
+        prefer_vendor: Some(PathBuf::from("./third_party")),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn no_npm() {
     let r = flags_from_vec(svec!["deno", "run", "--no-npm", "script.ts"]);
@@ -5461,7 +8545,137 @@ mod tests {
         subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
         }),
-        node_modules_dir: Some(true),
+        node_modules_dir: Some(true),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--node-modules-dir=false",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        node_modules_dir: Some(false),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn byonm() {
+    let r = flags_from_vec(svec!["deno", "run", "--byonm", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        byonm: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--byonm",
+      "--node-modules-dir",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn node_modules_layout() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--node-modules-dir",
+      "--node-modules-layout=linked",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        node_modules_dir: Some(true),
+        node_modules_layout: Some(NodeModulesLayout::Linked),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--node-modules-layout=symlink",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn npm_copy_strategy() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--npm-copy-strategy=reflink",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        npm_copy_strategy: Some(NpmCopyStrategy::Reflink),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--npm-copy-strategy=clone",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn npm_concurrency() {
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--npm-concurrency=4", "main.ts"]);
+    assert_eq!(r.unwrap().npm_concurrency, Some(4));
+
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--npm-concurrency=0", "main.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn node_conditions() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--conditions=react-server,development",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        node_conditions: svec!["react-server", "development"],
         ..Flags::default()
       }
     );
@@ -5469,31 +8683,38 @@ mod tests {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--node-modules-dir=false",
+      "--conditions=react-server,",
       "script.ts"
     ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn cached_only() {
+    let r = flags_from_vec(svec!["deno", "run", "--cached-only", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
         }),
-        node_modules_dir: Some(false),
+        cached_only: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn cached_only() {
-    let r = flags_from_vec(svec!["deno", "run", "--cached-only", "script.ts"]);
+  fn no_code_cache() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--no-code-cache", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
         }),
-        cached_only: true,
+        no_code_cache: true,
         ..Flags::default()
       }
     );
@@ -5693,6 +8914,10 @@ mod tests {
           shuffle: None,
           concurrent_jobs: None,
           trace_ops: true,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
         }),
         unstable: true,
         no_prompt: true,
@@ -5708,6 +8933,81 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_shard() {
+    let r = flags_from_vec(svec!["deno", "test", "--shard=2/8"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          shard: Some(TestShard { index: 2, count: 8 }),
+          ..TestFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    for value in ["0/8", "9/8", "2", "a/8", "2/"] {
+      let r = flags_from_vec(svec!["deno", "test", format!("--shard={value}")]);
+      assert!(r.is_err(), "{value}");
+    }
+  }
+
+  #[test]
+  fn test_reporter() {
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Json,
+          ..TestFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=junit"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_timeout() {
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=5000"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          timeout: Some(5000),
+          ..TestFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=0"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_isolate() {
+    let r = flags_from_vec(svec!["deno", "test", "--isolate", "--parallel"]);
+    let mut flags = r.unwrap();
+    match &mut flags.subcommand {
+      DenoSubcommand::Test(test_flags) => {
+        assert!(test_flags.isolate);
+        assert!(test_flags.concurrent_jobs.is_some());
+      }
+      _ => unreachable!(),
+    }
+  }
+
   #[test]
   fn run_with_cafile() {
     let r = flags_from_vec(svec![
@@ -5768,6 +9068,10 @@ mod tests {
           },
           concurrent_jobs: Some(NonZeroUsize::new(4).unwrap()),
           trace_ops: false,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
@@ -5798,6 +9102,10 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
@@ -5832,6 +9140,10 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5860,6 +9172,10 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
         }),
         no_prompt: true,
         watch: None,
@@ -5888,6 +9204,10 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5896,6 +9216,45 @@ mod tests {
       }
     );
   }
+  #[test]
+  fn test_watch_with_paths() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--watch=templates",
+      "--watch-exclude=dist",
+      "./"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![PathBuf::from("./")],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_ops: false,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        watch: Some(vec![PathBuf::from("templates")]),
+        watch_exclude: vec![PathBuf::from("dist")],
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn test_watch_explicit_cwd() {
     let r = flags_from_vec(svec!["deno", "test", "--watch", "./"]);
@@ -5915,6 +9274,10 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5944,6 +9307,10 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          shard: None,
+          reporter: TestReporterConfig::Pretty,
+          timeout: None,
+          isolate: false,
         }),
         watch: Some(vec![]),
         type_check_mode: TypeCheckMode::Local,
@@ -5969,6 +9336,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          out_dir: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ca_data: Some(CaData::File("example.crt".to_owned())),
@@ -6011,6 +9379,15 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts", "script_two.ts"],
+          list: None,
+          json: false,
+          print_integrity: false,
+          synth_npm_types: false,
+          prune_npm: None,
+          export: None,
+          import: None,
+          verify_npm: false,
+          check_npm_integrity: None,
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
@@ -6189,7 +9566,290 @@ mod tests {
           output: None,
           args: vec![],
           target: None,
-          include: vec![]
+          include: vec![],
+          include_assets: vec![],
+          no_compress: false,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: None,
+          reproducible: false,
+          no_terminal: false,
+          log_file: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_no_compress() {
+    let r =
+      flags_from_vec(svec!["deno", "compile", "--no-compress", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          include: vec![],
+          include_assets: vec![],
+          no_compress: true,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: None,
+          reproducible: false,
+          no_terminal: false,
+          log_file: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_windows_resources() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--icon",
+      "app.ico",
+      "--product-name",
+      "My App",
+      "--file-version=1.2.0",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          include: vec![],
+          include_assets: vec![],
+          no_compress: false,
+          icon: Some(PathBuf::from("app.ico")),
+          product_name: Some("My App".to_string()),
+          file_version: Some("1.2.0".to_string()),
+          sign_cmd: None,
+          reproducible: false,
+          no_terminal: false,
+          log_file: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_sign_cmd() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--sign-cmd",
+      "signtool sign /a",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          include: vec![],
+          include_assets: vec![],
+          no_compress: false,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: Some("signtool sign /a".to_string()),
+          reproducible: false,
+          no_terminal: false,
+          log_file: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_reproducible() {
+    let r =
+      flags_from_vec(svec!["deno", "compile", "--reproducible", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          include: vec![],
+          include_assets: vec![],
+          no_compress: false,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: None,
+          reproducible: true,
+          no_terminal: false,
+          log_file: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_no_terminal() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--no-terminal",
+      "--log-file",
+      "app.log",
+      "--target",
+      "x86_64-pc-windows-msvc",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: Some("x86_64-pc-windows-msvc".to_string()),
+          include: vec![],
+          include_assets: vec![],
+          no_compress: false,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: None,
+          reproducible: false,
+          no_terminal: true,
+          log_file: Some("app.log".to_string()),
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_target() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--target",
+      "aarch64-unknown-linux-gnu",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: Some("aarch64-unknown-linux-gnu".to_string()),
+          include: vec![],
+          include_assets: vec![],
+          no_compress: false,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: None,
+          reproducible: false,
+          no_terminal: false,
+          log_file: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "compile", "--target", "list"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: String::new(),
+          output: None,
+          args: vec![],
+          target: Some("list".to_string()),
+          include: vec![],
+          include_assets: vec![],
+          no_compress: false,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: None,
+          reproducible: false,
+          no_terminal: false,
+          log_file: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    // a script is required for the other targets
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--target",
+      "aarch64-pc-windows-msvc"
+    ]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "compile", "--target", "riscv64"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn compile_include_asset() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--include-asset",
+      "templates",
+      "--include-asset=schema.sql",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          include: vec![],
+          include_assets: vec![
+            PathBuf::from("templates"),
+            PathBuf::from("schema.sql")
+          ],
+          no_compress: false,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: None,
+          reproducible: false,
+          no_terminal: false,
+          log_file: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -6209,7 +9869,16 @@ mod tests {
           output: Some(PathBuf::from("colors")),
           args: svec!["foo", "bar"],
           target: None,
-          include: vec![]
+          include: vec![],
+          include_assets: vec![],
+          no_compress: false,
+          icon: None,
+          product_name: None,
+          file_version: None,
+          sign_cmd: None,
+          reproducible: false,
+          no_terminal: false,
+          log_file: None,
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -6379,6 +10048,23 @@ mod tests {
     );
   }
 
+  #[test]
+  fn task_subcommand_env_file() {
+    let r = flags_from_vec(svec!["deno", "task", "--env", "build", "hello"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+        }),
+        argv: svec!["hello"],
+        env_files: vec![PathBuf::from(".env")],
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn task_subcommand() {
     let r = flags_from_vec(svec!["deno", "task", "build", "hello", "world",]);
@@ -1,6 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use deno_core::url::Url;
+use deno_runtime::permissions::IpCidr;
 use std::net::IpAddr;
 use std::str::FromStr;
 
@@ -26,8 +27,20 @@ impl FromStr for BarePort {
   }
 }
 
+/// Returns true for valid IP address ranges in CIDR notation, optionally with
+/// a port (eg. "10.0.0.0/8" or "10.0.0.0/8:443").
+fn is_cidr(host_and_port: &str) -> bool {
+  matches!(IpCidr::parse_with_port(host_and_port), Some(Ok(_)))
+}
+
 pub fn validator(host_and_port: &str) -> Result<String, String> {
-  if Url::parse(&format!("internal://{host_and_port}")).is_ok()
+  if host_and_port.contains('/') {
+    if is_cidr(host_and_port) {
+      Ok(host_and_port.to_string())
+    } else {
+      Err(format!("Bad CIDR range: {host_and_port}"))
+    }
+  } else if Url::parse(&format!("internal://{host_and_port}")).is_ok()
     || host_and_port.parse::<IpAddr>().is_ok()
     || host_and_port.parse::<BarePort>().is_ok()
   {
@@ -43,7 +56,15 @@ pub fn validator(host_and_port: &str) -> Result<String, String> {
 pub fn parse(paths: Vec<String>) -> clap::error::Result<Vec<String>> {
   let mut out: Vec<String> = vec![];
   for host_and_port in paths.iter() {
-    if Url::parse(&format!("internal://{host_and_port}")).is_ok()
+    if host_and_port.contains('/') {
+      if !is_cidr(host_and_port) {
+        return Err(clap::Error::raw(
+          clap::error::ErrorKind::InvalidValue,
+          format!("Bad CIDR range: {host_and_port}"),
+        ));
+      }
+      out.push(host_and_port.to_owned())
+    } else if Url::parse(&format!("internal://{host_and_port}")).is_ok()
       || host_and_port.parse::<IpAddr>().is_ok()
     {
       out.push(host_and_port.to_owned())
@@ -182,6 +203,17 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn parse_net_args_cidr() {
+    let entries = svec!["10.0.0.0/8", "192.168.1.0/24:443", "fd00::/8"];
+    let expected = svec!["10.0.0.0/8", "192.168.1.0/24:443", "fd00::/8"];
+    let actual = parse(entries).unwrap();
+    assert_eq!(actual, expected);
+
+    assert!(parse(svec!["10.0.0.0/33"]).is_err());
+    assert!(parse(svec!["deno.land/8"]).is_err());
+  }
+
   #[test]
   fn parse_net_args_ipv6_error1() {
     let entries = svec![":::"];
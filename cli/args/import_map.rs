@@ -1,7 +1,13 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::futures::future::BoxFuture;
+use deno_core::futures::FutureExt;
 use deno_core::serde_json;
+use deno_core::serde_json::Map;
+use deno_core::serde_json::Value;
 use deno_core::url::Url;
 use deno_runtime::permissions::PermissionsContainer;
 use import_map::ImportMap;
@@ -17,28 +23,197 @@ pub async fn resolve_import_map_from_specifier(
   maybe_config_file: Option<&ConfigFile>,
   file_fetcher: &FileFetcher,
 ) -> Result<ImportMap, AnyError> {
-  let value: serde_json::Value = if specifier.scheme() == "data" {
-    serde_json::from_str(&get_source_from_data_url(specifier)?.0)?
+  let import_map_config = maybe_config_file
+    .as_ref()
+    .filter(|c| c.specifier == *specifier);
+  let value = match import_map_config {
+    Some(config) => config.to_import_map_value(),
+    None => fetch_import_map_value(specifier, file_fetcher).await?,
+  };
+  let value = resolve_extends(
+    specifier,
+    value,
+    file_fetcher,
+    &mut vec![specifier.clone()],
+  )
+  .await?;
+  import_map_from_value(specifier, value)
+}
+
+async fn fetch_import_map_value(
+  specifier: &Url,
+  file_fetcher: &FileFetcher,
+) -> Result<Value, AnyError> {
+  if specifier.scheme() == "data" {
+    Ok(serde_json::from_str(
+      &get_source_from_data_url(specifier)?.0,
+    )?)
   } else {
-    let import_map_config = maybe_config_file
-      .as_ref()
-      .filter(|c| c.specifier == *specifier);
-    match import_map_config {
-      Some(config) => config.to_import_map_value(),
-      None => {
-        let file = file_fetcher
-          .fetch(specifier, PermissionsContainer::allow_all())
-          .await?;
-        serde_json::from_str(&file.source)?
+    let file = file_fetcher
+      .fetch(specifier, PermissionsContainer::allow_all())
+      .await?;
+    Ok(serde_json::from_str(&file.source)?)
+  }
+}
+
+/// Resolves the `"extends"` entry of an import map, which is either a single
+/// import map or a list of import maps to inherit the mappings of, relative to
+/// the extending import map. The extended import maps are merged in order, so
+/// mappings of later import maps take precedence over earlier ones, and the
+/// mappings of the extending import map take precedence over all of them.
+/// Scopes present in several import maps are merged the same way.
+fn resolve_extends<'a>(
+  specifier: &'a Url,
+  mut value: Value,
+  file_fetcher: &'a FileFetcher,
+  chain: &'a mut Vec<Url>,
+) -> BoxFuture<'a, Result<Value, AnyError>> {
+  async move {
+    let extends = match value.as_object_mut().and_then(|m| m.remove("extends"))
+    {
+      Some(Value::String(extends)) => vec![Value::String(extends)],
+      Some(Value::Array(extends)) => extends,
+      Some(_) => bail!(invalid_extends_message(specifier)),
+      None => return Ok(value),
+    };
+
+    let mut merged = Value::Object(Map::new());
+    for extends in extends {
+      let extends = match extends {
+        Value::String(extends) => extends,
+        _ => bail!(invalid_extends_message(specifier)),
+      };
+      let extended_specifier =
+        deno_core::resolve_import(&extends, specifier.as_str())
+          .with_context(|| format!("Bad URL (\"{extends}\") for import map"))?;
+      if chain.contains(&extended_specifier) {
+        let cycle = chain
+          .iter()
+          .chain(std::iter::once(&extended_specifier))
+          .map(|s| s.as_str())
+          .collect::<Vec<_>>()
+          .join(" -> ");
+        bail!("Cyclic import map \"extends\": {}", cycle);
       }
+      let extended_value =
+        fetch_import_map_value(&extended_specifier, file_fetcher)
+          .await
+          .with_context(|| {
+            format!("Unable to load '{extended_specifier}' import map")
+          })?;
+      chain.push(extended_specifier.clone());
+      let mut extended_value = resolve_extends(
+        &extended_specifier,
+        extended_value,
+        file_fetcher,
+        chain,
+      )
+      .await?;
+      chain.pop();
+      // relative addresses are relative to the import map they're defined in
+      absolutize_import_map_value(&mut extended_value, &extended_specifier);
+      merge_import_map_value(&mut merged, extended_value);
+    }
+    merge_import_map_value(&mut merged, value);
+    Ok(merged)
+  }
+  .boxed()
+}
+
+fn invalid_extends_message(specifier: &Url) -> String {
+  format!(
+    "\"extends\" must be a string or an array of strings in import map \"{specifier}\"."
+  )
+}
+
+/// Merges the import map `other` into `base`, with the entries of `other`
+/// taking precedence.
+fn merge_import_map_value(base: &mut Value, other: Value) {
+  let (base, other) = match (base.as_object_mut(), other) {
+    (Some(base), Value::Object(other)) => (base, other),
+    (_, other) => {
+      *base = other;
+      return;
     }
   };
-  import_map_from_value(specifier, value)
+  for (key, value) in other {
+    match (base.get_mut(&key), value) {
+      (Some(Value::Object(imports)), Value::Object(other))
+        if key == "imports" =>
+      {
+        imports.extend(other);
+      }
+      (Some(Value::Object(scopes)), Value::Object(other))
+        if key == "scopes" =>
+      {
+        for (scope, value) in other {
+          match (scopes.get_mut(&scope), value) {
+            (Some(Value::Object(scope_imports)), Value::Object(other)) => {
+              scope_imports.extend(other);
+            }
+            (_, value) => {
+              scopes.insert(scope, value);
+            }
+          }
+        }
+      }
+      (_, value) => {
+        base.insert(key, value);
+      }
+    }
+  }
+}
+
+/// Resolves the relative addresses and scopes of an import map against its
+/// specifier, so that its entries keep their meaning when merged into an
+/// import map at a different location.
+fn absolutize_import_map_value(value: &mut Value, specifier: &Url) {
+  fn absolutize(address: &str, specifier: &Url) -> Option<String> {
+    if address.starts_with('/')
+      || address.starts_with("./")
+      || address.starts_with("../")
+    {
+      specifier.join(address).ok().map(String::from)
+    } else {
+      None
+    }
+  }
+
+  fn absolutize_specifier_map(map: &mut Map<String, Value>, specifier: &Url) {
+    let entries = std::mem::take(map);
+    for (key, mut value) in entries {
+      if let Value::String(address) = &mut value {
+        if let Some(resolved) = absolutize(address, specifier) {
+          *address = resolved;
+        }
+      }
+      let key = absolutize(&key, specifier).unwrap_or(key);
+      map.insert(key, value);
+    }
+  }
+
+  let value = match value.as_object_mut() {
+    Some(value) => value,
+    None => return,
+  };
+  if let Some(Value::Object(imports)) = value.get_mut("imports") {
+    absolutize_specifier_map(imports, specifier);
+  }
+  if let Some(Value::Object(scopes)) = value.get_mut("scopes") {
+    let entries = std::mem::take(scopes);
+    for (scope, mut scope_imports) in entries {
+      if let Value::Object(scope_imports) = &mut scope_imports {
+        absolutize_specifier_map(scope_imports, specifier);
+      }
+      let scope = absolutize(&scope, specifier).unwrap_or(scope);
+      scopes.insert(scope, scope_imports);
+    }
+  }
 }
 
 fn import_map_from_value(
   specifier: &Url,
-  json_value: serde_json::Value,
+  json_value: Value,
 ) -> Result<ImportMap, AnyError> {
   debug_assert!(
     !specifier.as_str().contains("../"),
@@ -62,3 +237,84 @@ fn print_import_map_diagnostics(diagnostics: &[ImportMapDiagnostic]) {
     );
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_core::serde_json::json;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn merge_import_maps() {
+    let mut base = json!({
+      "imports": {
+        "std/": "https://deno.land/std@0.180.0/",
+        "fmt": "https://deno.land/x/fmt@1.0.0/mod.ts",
+      },
+      "scopes": {
+        "https://deno.land/x/": { "a": "https://a.com/v1.ts" },
+      },
+    });
+    merge_import_map_value(
+      &mut base,
+      json!({
+        "imports": {
+          "std/": "https://deno.land/std@0.190.0/",
+        },
+        "scopes": {
+          "https://deno.land/x/": { "b": "https://b.com/v1.ts" },
+          "https://esm.sh/": { "c": "https://c.com/v1.ts" },
+        },
+      }),
+    );
+    assert_eq!(
+      base,
+      json!({
+        "imports": {
+          "std/": "https://deno.land/std@0.190.0/",
+          "fmt": "https://deno.land/x/fmt@1.0.0/mod.ts",
+        },
+        "scopes": {
+          "https://deno.land/x/": {
+            "a": "https://a.com/v1.ts",
+            "b": "https://b.com/v1.ts",
+          },
+          "https://esm.sh/": { "c": "https://c.com/v1.ts" },
+        },
+      })
+    );
+  }
+
+  #[test]
+  fn absolutize_import_map() {
+    let mut value = json!({
+      "imports": {
+        "utils/": "./utils/",
+        "./polyfill.ts": "../polyfills/mod.ts",
+        "std/": "https://deno.land/std@0.190.0/",
+        "lodash": "npm:lodash@4",
+      },
+      "scopes": {
+        "./vendor/": { "a": "/a.ts" },
+      },
+    });
+    absolutize_import_map_value(
+      &mut value,
+      &Url::parse("https://example.com/org/import_map.json").unwrap(),
+    );
+    assert_eq!(
+      value,
+      json!({
+        "imports": {
+          "utils/": "https://example.com/org/utils/",
+          "https://example.com/org/polyfill.ts": "https://example.com/polyfills/mod.ts",
+          "std/": "https://deno.land/std@0.190.0/",
+          "lodash": "npm:lodash@4",
+        },
+        "scopes": {
+          "https://example.com/org/vendor/": { "a": "https://example.com/a.ts" },
+        },
+      })
+    );
+  }
+}
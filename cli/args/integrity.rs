@@ -0,0 +1,234 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use ring::digest;
+
+/// Expected hashes of remote modules from the `integrity` section of a config
+/// file, using the format of subresource integrity metadata:
+///
+/// ```json
+/// {
+///   "integrity": {
+///     "https://deno.land/std@0.190.0/http/server.ts": "sha384-..."
+///   }
+/// }
+/// ```
+///
+/// A module whose contents don't match its hash fails to be fetched, whether
+/// it's downloaded or read from the cache, so remote code can't change
+/// without being noticed even when there is no lockfile. A value may contain
+/// several hashes separated by whitespace, in which case the ones of the
+/// strongest algorithm are used and any of them can match.
+#[derive(Clone, Debug, Default)]
+pub struct IntegrityManifest {
+  entries: HashMap<ModuleSpecifier, Vec<IntegrityHash>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum IntegrityAlgorithm {
+  Sha256,
+  Sha384,
+  Sha512,
+}
+
+#[derive(Clone, Debug)]
+struct IntegrityHash {
+  algorithm: IntegrityAlgorithm,
+  digest: Vec<u8>,
+}
+
+impl IntegrityAlgorithm {
+  fn parse(name: &str) -> Option<Self> {
+    match name {
+      "sha256" => Some(Self::Sha256),
+      "sha384" => Some(Self::Sha384),
+      "sha512" => Some(Self::Sha512),
+      _ => None,
+    }
+  }
+
+  fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+    let algorithm = match self {
+      Self::Sha256 => &digest::SHA256,
+      Self::Sha384 => &digest::SHA384,
+      Self::Sha512 => &digest::SHA512,
+    };
+    digest::digest(algorithm, bytes).as_ref().to_vec()
+  }
+}
+
+impl IntegrityHash {
+  fn parse(text: &str) -> Result<Self, AnyError> {
+    let (name, encoded_digest) = match text.split_once('-') {
+      Some(parts) => parts,
+      None => bail!("Expected <algorithm>-<base64 digest>, found \"{}\"", text),
+    };
+    let algorithm = match IntegrityAlgorithm::parse(name) {
+      Some(algorithm) => algorithm,
+      None => bail!(
+        "Unsupported algorithm \"{}\", expected sha256, sha384 or sha512",
+        name
+      ),
+    };
+    let digest = base64::decode(encoded_digest)
+      .with_context(|| format!("Invalid digest \"{encoded_digest}\""))?;
+    if digest.len() != algorithm.digest(&[]).len() {
+      bail!("Invalid {} digest \"{}\"", name, encoded_digest);
+    }
+    Ok(Self { algorithm, digest })
+  }
+}
+
+impl IntegrityManifest {
+  pub fn new(integrity: BTreeMap<String, String>) -> Result<Self, AnyError> {
+    let mut entries = HashMap::with_capacity(integrity.len());
+    for (key, value) in integrity {
+      let specifier = ModuleSpecifier::parse(&key)
+        .with_context(|| format!("Invalid integrity entry \"{key}\""))?;
+      if !matches!(specifier.scheme(), "http" | "https") {
+        bail!(
+          "Invalid integrity entry \"{}\", only remote modules can be checked",
+          key
+        );
+      }
+      let hashes = value
+        .split_whitespace()
+        .map(IntegrityHash::parse)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid integrity of \"{key}\""))?;
+      if hashes.is_empty() {
+        bail!("Invalid integrity of \"{}\", expected a hash", key);
+      }
+      entries.insert(specifier, hashes);
+    }
+    Ok(Self { entries })
+  }
+
+  pub fn contains(&self, specifier: &ModuleSpecifier) -> bool {
+    self.entries.contains_key(specifier)
+  }
+
+  /// Errors when the contents of the module don't match its expected hash.
+  /// Modules without an entry are not checked.
+  pub fn check(
+    &self,
+    specifier: &ModuleSpecifier,
+    bytes: &[u8],
+  ) -> Result<(), AnyError> {
+    let hashes = match self.entries.get(specifier) {
+      Some(hashes) => hashes,
+      None => return Ok(()),
+    };
+    let algorithm = hashes.iter().map(|hash| hash.algorithm).max().unwrap();
+    let actual_digest = algorithm.digest(bytes);
+    let is_match = hashes
+      .iter()
+      .filter(|hash| hash.algorithm == algorithm)
+      .any(|hash| hash.digest == actual_digest);
+    if !is_match {
+      bail!(
+        "The integrity check of \"{}\" failed, its contents changed.\n  Expected: {}\n  Actual: {}",
+        specifier,
+        hashes
+          .iter()
+          .filter(|hash| hash.algorithm == algorithm)
+          .map(|hash| format_hash(hash.algorithm, &hash.digest))
+          .collect::<Vec<_>>()
+          .join(" "),
+        format_hash(algorithm, &actual_digest),
+      );
+    }
+    Ok(())
+  }
+}
+
+/// Computes the integrity metadata of the contents of a module, as used in
+/// the `integrity` section of a config file.
+pub fn integrity_for_bytes(bytes: &[u8]) -> String {
+  let algorithm = IntegrityAlgorithm::Sha384;
+  format_hash(algorithm, &algorithm.digest(bytes))
+}
+
+fn format_hash(algorithm: IntegrityAlgorithm, digest: &[u8]) -> String {
+  let name = match algorithm {
+    IntegrityAlgorithm::Sha256 => "sha256",
+    IntegrityAlgorithm::Sha384 => "sha384",
+    IntegrityAlgorithm::Sha512 => "sha512",
+  };
+  format!("{}-{}", name, base64::encode(digest))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn integrity_check() {
+    let bytes = b"export const a = 1;";
+    let integrity = integrity_for_bytes(bytes);
+    assert!(integrity.starts_with("sha384-"));
+    let sha256 = format_hash(
+      IntegrityAlgorithm::Sha256,
+      digest::digest(&digest::SHA256, b"other").as_ref(),
+    );
+    let manifest = IntegrityManifest::new(BTreeMap::from([
+      ("https://deno.land/x/a.ts".to_string(), integrity.clone()),
+      // the strongest algorithm is used
+      (
+        "https://deno.land/x/b.ts".to_string(),
+        format!("{sha256} {integrity}"),
+      ),
+    ]))
+    .unwrap();
+    let specifier = |s: &str| ModuleSpecifier::parse(s).unwrap();
+
+    assert!(manifest
+      .check(&specifier("https://deno.land/x/a.ts"), bytes)
+      .is_ok());
+    assert!(manifest
+      .check(&specifier("https://deno.land/x/b.ts"), bytes)
+      .is_ok());
+    let err = manifest
+      .check(
+        &specifier("https://deno.land/x/a.ts"),
+        b"export const a = 2;",
+      )
+      .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      format!(
+        "The integrity check of \"https://deno.land/x/a.ts\" failed, its contents changed.\n  Expected: {}\n  Actual: {}",
+        integrity,
+        integrity_for_bytes(b"export const a = 2;"),
+      )
+    );
+    // not in the manifest
+    assert!(manifest
+      .check(&specifier("https://deno.land/x/c.ts"), b"")
+      .is_ok());
+  }
+
+  #[test]
+  fn integrity_manifest_invalid() {
+    let new = |key: &str, value: &str| {
+      IntegrityManifest::new(BTreeMap::from([(
+        key.to_string(),
+        value.to_string(),
+      )]))
+    };
+    let integrity = integrity_for_bytes(b"");
+    assert!(new("https://deno.land/x/a.ts", &integrity).is_ok());
+    assert!(new("./a.ts", &integrity).is_err());
+    assert!(new("file:///a.ts", &integrity).is_err());
+    assert!(new("https://deno.land/x/a.ts", "").is_err());
+    assert!(new("https://deno.land/x/a.ts", "md5-AAAA").is_err());
+    assert!(new("https://deno.land/x/a.ts", "sha384-AAAA").is_err());
+    assert!(new("https://deno.land/x/a.ts", "sha384-!!").is_err());
+  }
+}
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -10,15 +11,16 @@ use deno_core::error::AnyError;
 use deno_core::futures::stream::FuturesOrdered;
 use deno_core::futures::StreamExt;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
 use deno_npm::registry::NpmRegistryApi;
 use deno_npm::resolution::SerializedNpmResolutionSnapshot;
 use deno_npm::resolution::SerializedNpmResolutionSnapshotPackage;
-use deno_npm::resolution::ValidSerializedNpmResolutionSnapshot;
 use deno_npm::NpmPackageId;
 use deno_semver::npm::NpmPackageReq;
 
 use crate::args::config_file::LockConfig;
 use crate::args::ConfigFile;
+use crate::cache::FastInsecureHasher;
 use crate::npm::CliNpmRegistryApi;
 use crate::Flags;
 
@@ -74,10 +76,30 @@ pub fn discover(
   Ok(Some(lockfile))
 }
 
+/// Gets a hash of the npm section of the lockfile, which is all the
+/// information the resolved npm snapshot of the lockfile depends on.
+pub fn npm_lockfile_hash(lockfile: &Lockfile, api: &CliNpmRegistryApi) -> u64 {
+  let mut hasher = FastInsecureHasher::new();
+  hasher.write_str(api.base_url().as_str());
+  for (key, value) in &lockfile.content.npm.specifiers {
+    hasher.write_str(key).write_str(value);
+  }
+  for (key, package) in &lockfile.content.npm.packages {
+    hasher.write_str(key).write_str(&package.integrity);
+    hasher.write_u64(package.dependencies.len() as u64);
+    for (name, id) in &package.dependencies {
+      hasher.write_str(name).write_str(id);
+    }
+  }
+  hasher.finish()
+}
+
+/// Resolves the npm snapshot of the lockfile. The snapshot still needs to be
+/// validated with `SerializedNpmResolutionSnapshot::into_valid`.
 pub async fn snapshot_from_lockfile(
   lockfile: Arc<Mutex<Lockfile>>,
   api: &CliNpmRegistryApi,
-) -> Result<ValidSerializedNpmResolutionSnapshot, AnyError> {
+) -> Result<SerializedNpmResolutionSnapshot, AnyError> {
   let (root_packages, mut packages) = {
     let lockfile = lockfile.lock();
 
@@ -154,10 +176,31 @@ pub async fn snapshot_from_lockfile(
   // clear the memory cache to reduce memory usage
   api.clear_memory_cache();
 
-  SerializedNpmResolutionSnapshot {
+  Ok(SerializedNpmResolutionSnapshot {
     packages,
     root_packages,
-  }
-  .into_valid()
-  .context("The lockfile is corrupt. You can recreate it with --lock-write")
+  })
+}
+
+/// Returns the remote section of a lockfile, which maps the URLs of remote
+/// modules to their checksums. `deno_lockfile` doesn't expose the section, so
+/// it's read from the serialized content.
+pub fn lockfile_remote_entries(
+  lockfile: &Lockfile,
+) -> BTreeMap<String, String> {
+  let mut content = match serde_json::to_value(&lockfile.content) {
+    Ok(content) => content,
+    Err(_) => return BTreeMap::new(),
+  };
+  serde_json::from_value(content["remote"].take()).unwrap_or_default()
+}
+
+/// Replaces the remote section of a lockfile.
+pub fn set_lockfile_remote_entries(
+  lockfile: &mut Lockfile,
+  remote: BTreeMap<String, String>,
+) {
+  let mut content = serde_json::to_value(&lockfile.content).unwrap();
+  content["remote"] = serde_json::to_value(remote).unwrap();
+  lockfile.content = serde_json::from_value(content).unwrap();
 }
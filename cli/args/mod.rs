@@ -1,15 +1,23 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 mod config_file;
+mod env_file;
 mod flags;
 mod flags_allow_net;
 mod import_map;
+mod integrity;
 mod lockfile;
+mod module_overrides;
 pub mod package_json;
+mod permissions_policy;
+mod prefer_vendor;
+mod workspace;
 
 pub use self::import_map::resolve_import_map_from_specifier;
+use self::lockfile::npm_lockfile_hash;
 use self::lockfile::snapshot_from_lockfile;
 use self::package_json::PackageJsonDeps;
+use self::permissions_policy::PermissionsPolicy;
 use ::import_map::ImportMap;
 use deno_core::resolve_url_or_path;
 use deno_npm::resolution::ValidSerializedNpmResolutionSnapshot;
@@ -17,23 +25,42 @@ use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_semver::npm::NpmPackageReqReference;
 use indexmap::IndexMap;
 
+pub use config_file::to_client_certificates;
+pub use config_file::to_host_ca_certificates;
+pub use config_file::AuthConfig;
 pub use config_file::BenchConfig;
 pub use config_file::CompilerOptions;
 pub use config_file::ConfigFile;
 pub use config_file::EmitConfigOptions;
 pub use config_file::FilesConfig;
 pub use config_file::FmtOptionsConfig;
+pub use config_file::HostAuthConfig;
 pub use config_file::JsxImportSourceConfig;
 pub use config_file::LintRulesConfig;
 pub use config_file::ProseWrap;
+pub use config_file::PublishConfig;
 pub use config_file::TsConfig;
 pub use config_file::TsConfigForEmit;
 pub use config_file::TsConfigType;
 pub use config_file::TsTypeLib;
+pub use config_file::WatchConfig;
+pub use env_file::load_env_files;
 pub use flags::*;
+pub use integrity::integrity_for_bytes;
+pub use integrity::IntegrityManifest;
+pub use lockfile::lockfile_remote_entries;
+pub use lockfile::set_lockfile_remote_entries;
 pub use lockfile::Lockfile;
 pub use lockfile::LockfileError;
+pub use module_overrides::check_or_insert_locked_override;
+pub use module_overrides::ModuleOverrides;
 pub use package_json::PackageJsonDepsProvider;
+pub use prefer_vendor::PreferVendor;
+pub use workspace::workspace_member_matches_path;
+pub use workspace::workspace_member_matches_specifier;
+pub use workspace::workspace_options_for_path;
+pub use workspace::Workspace;
+pub use workspace::WorkspaceMemberOptions;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::anyhow;
@@ -52,7 +79,11 @@ use deno_runtime::deno_tls::rustls_native_certs::load_native_certs;
 use deno_runtime::deno_tls::rustls_pemfile;
 use deno_runtime::deno_tls::webpki_roots;
 use deno_runtime::inspector_server::InspectorServer;
+use deno_runtime::permissions::narrow_to_inherited_permissions;
 use deno_runtime::permissions::PermissionsOptions;
+use deno_runtime::permissions::WorkerPermissionsConfig;
+use deno_runtime::permissions::INHERITED_PERMISSIONS_ENV_VAR_NAME;
+use deno_runtime::sandbox::SandboxOptions;
 use once_cell::sync::Lazy;
 use once_cell::sync::OnceCell;
 use std::collections::HashMap;
@@ -67,9 +98,11 @@ use std::sync::Arc;
 use thiserror::Error;
 
 use crate::cache::DenoDir;
+use crate::cache::NpmSnapshotCache;
 use crate::file_fetcher::FileFetcher;
 use crate::npm::CliNpmRegistryApi;
 use crate::npm::NpmProcessState;
+use crate::npm::DEFAULT_NPM_DOWNLOAD_CONCURRENCY;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
 use crate::version;
 
@@ -93,14 +126,29 @@ pub enum CacheSetting {
   ReloadSome(Vec<String>),
   /// The usability of a cached value is determined by analyzing the cached
   /// headers and other metadata associated with a cached response, reloading
-  /// any cached "non-fresh" cached responses.
+  /// any cached "non-fresh" cached responses.  Stale responses are
+  /// revalidated with a conditional request.  This is the equivalent of
+  /// `--reload=stale` in the CLI.
   RespectHeaders,
+  /// All cached remote files are revalidated with a conditional request and
+  /// only downloaded again when they changed.  This is the equivalent of
+  /// `--revalidate` in the CLI.
+  Revalidate,
   /// The cached source files should be used for local modules.  This is the
   /// default behavior of the CLI.
   Use,
+  /// Like `Only`, but for everything that would otherwise be downloaded:
+  /// remote modules, npm package information and npm tarballs. This is the
+  /// equivalent of `--offline` in the CLI.
+  Offline,
 }
 
 impl CacheSetting {
+  /// Whether only cached resources can be used, erroring for the others.
+  pub fn is_cached_only(&self) -> bool {
+    matches!(self, CacheSetting::Only | CacheSetting::Offline)
+  }
+
   pub fn should_use_for_npm_package(&self, package_name: &str) -> bool {
     match self {
       CacheSetting::ReloadAll => false,
@@ -119,9 +167,67 @@ impl CacheSetting {
   }
 }
 
+/// Error of resources that aren't cached in offline mode (`--offline`). The
+/// errors of several resources are combined to report all of them at once.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NotCachedError {
+  resources: Vec<String>,
+}
+
+impl NotCachedError {
+  pub fn new(resource: impl Into<String>) -> Self {
+    Self {
+      resources: vec![resource.into()],
+    }
+  }
+
+  /// Combines the errors into one listing all their resources, if there are
+  /// any errors.
+  pub fn combine(
+    errors: impl IntoIterator<Item = NotCachedError>,
+  ) -> Option<Self> {
+    let mut resources: Vec<String> = Vec::new();
+    for error in errors {
+      for resource in error.resources {
+        if !resources.contains(&resource) {
+          resources.push(resource);
+        }
+      }
+    }
+    if resources.is_empty() {
+      None
+    } else {
+      Some(Self { resources })
+    }
+  }
+}
+
+impl std::fmt::Display for NotCachedError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if let [resource] = self.resources.as_slice() {
+      return write!(
+        f,
+        "Not cached, offline mode: \"{resource}\" is not in the cache and can't be downloaded."
+      );
+    }
+    write!(
+      f,
+      "Not cached, offline mode: {} resources are not in the cache and can't be downloaded:",
+      self.resources.len()
+    )?;
+    for resource in &self.resources {
+      write!(f, "\n  {resource}")?;
+    }
+    Ok(())
+  }
+}
+
+impl std::error::Error for NotCachedError {}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BenchOptions {
   pub files: FilesConfig,
+  pub workspace_members: Vec<WorkspaceMemberOptions<()>>,
   pub filter: Option<String>,
   pub json: bool,
   pub no_run: bool,
@@ -138,6 +244,7 @@ impl BenchOptions {
         maybe_bench_config.map(|c| c.files),
         Some(bench_flags.files),
       ),
+      workspace_members: Vec::new(),
       filter: bench_flags.filter,
       json: bench_flags.json,
       no_run: bench_flags.no_run,
@@ -151,6 +258,7 @@ pub struct FmtOptions {
   pub check: bool,
   pub options: FmtOptionsConfig,
   pub files: FilesConfig,
+  pub workspace_members: Vec<WorkspaceMemberOptions<FmtOptionsConfig>>,
 }
 
 impl FmtOptions {
@@ -183,6 +291,7 @@ impl FmtOptions {
         maybe_config_files,
         maybe_fmt_flags.map(|f| f.files),
       ),
+      workspace_members: Vec::new(),
     })
   }
 }
@@ -231,6 +340,7 @@ fn resolve_fmt_options(
 #[derive(Clone)]
 pub struct TestOptions {
   pub files: FilesConfig,
+  pub workspace_members: Vec<WorkspaceMemberOptions<()>>,
   pub doc: bool,
   pub no_run: bool,
   pub fail_fast: Option<NonZeroUsize>,
@@ -239,6 +349,10 @@ pub struct TestOptions {
   pub shuffle: Option<u64>,
   pub concurrent_jobs: NonZeroUsize,
   pub trace_ops: bool,
+  pub shard: Option<TestShard>,
+  pub reporter: TestReporterConfig,
+  pub timeout: Option<u64>,
+  pub isolate: bool,
 }
 
 impl TestOptions {
@@ -253,6 +367,7 @@ impl TestOptions {
         maybe_test_config.map(|c| c.files),
         Some(test_flags.files),
       ),
+      workspace_members: Vec::new(),
       allow_none: test_flags.allow_none,
       concurrent_jobs: test_flags
         .concurrent_jobs
@@ -263,6 +378,10 @@ impl TestOptions {
       no_run: test_flags.no_run,
       shuffle: test_flags.shuffle,
       trace_ops: test_flags.trace_ops,
+      shard: test_flags.shard,
+      reporter: test_flags.reporter,
+      timeout: test_flags.timeout,
+      isolate: test_flags.isolate,
     })
   }
 }
@@ -279,6 +398,7 @@ pub enum LintReporterKind {
 pub struct LintOptions {
   pub rules: LintRulesConfig,
   pub files: FilesConfig,
+  pub workspace_members: Vec<WorkspaceMemberOptions<LintRulesConfig>>,
   pub is_stdin: bool,
   pub reporter_kind: LintReporterKind,
 }
@@ -354,6 +474,7 @@ impl LintOptions {
         maybe_rules_include,
         maybe_rules_exclude,
       ),
+      workspace_members: Vec::new(),
     })
   }
 }
@@ -566,8 +687,11 @@ pub struct CliOptions {
   initial_cwd: PathBuf,
   maybe_node_modules_folder: Option<PathBuf>,
   maybe_config_file: Option<ConfigFile>,
+  maybe_workspace: Option<Workspace>,
   maybe_package_json: Option<PackageJson>,
   maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
+  maybe_permissions_policy: Option<PermissionsPolicy>,
+  maybe_inherited_permissions: Option<PermissionsOptions>,
   overrides: CliOptionOverrides,
 }
 
@@ -576,6 +700,7 @@ impl CliOptions {
     flags: Flags,
     initial_cwd: PathBuf,
     maybe_config_file: Option<ConfigFile>,
+    maybe_workspace: Option<Workspace>,
     maybe_lockfile: Option<Lockfile>,
     maybe_package_json: Option<PackageJson>,
   ) -> Result<Self, AnyError> {
@@ -597,18 +722,30 @@ impl CliOptions {
     let maybe_node_modules_folder = resolve_local_node_modules_folder(
       &initial_cwd,
       &flags,
-      maybe_config_file.as_ref(),
+      maybe_workspace
+        .as_ref()
+        .map(|w| w.root())
+        .or(maybe_config_file.as_ref()),
       maybe_package_json.as_ref(),
     )
     .with_context(|| "Resolving node_modules folder.")?;
+    let maybe_permissions_policy = flags
+      .permissions_policy
+      .as_ref()
+      .map(|path| PermissionsPolicy::read(&initial_cwd.join(path)))
+      .transpose()?;
+    let maybe_inherited_permissions = inherited_permissions_from_env()?;
 
     Ok(Self {
       flags,
       initial_cwd,
       maybe_config_file,
+      maybe_workspace,
       maybe_lockfile,
       maybe_package_json,
       maybe_node_modules_folder,
+      maybe_permissions_policy,
+      maybe_inherited_permissions,
       overrides: Default::default(),
     })
   }
@@ -617,6 +754,15 @@ impl CliOptions {
     let initial_cwd =
       std::env::current_dir().with_context(|| "Failed getting cwd.")?;
     let maybe_config_file = ConfigFile::discover(&flags, &initial_cwd)?;
+    let maybe_workspace = match &maybe_config_file {
+      Some(config_file) => Workspace::discover(config_file)?,
+      None => None,
+    };
+    // workspace members share the lockfile and npm resolution of the root
+    let maybe_shared_config_file = maybe_workspace
+      .as_ref()
+      .map(|w| w.root())
+      .or(maybe_config_file.as_ref());
 
     let mut maybe_package_json = None;
     if flags.config_flag == ConfigFlag::Disabled
@@ -624,7 +770,7 @@ impl CliOptions {
       || has_flag_env_var("DENO_NO_PACKAGE_JSON")
     {
       log::debug!("package.json auto-discovery is disabled")
-    } else if let Some(config_file) = &maybe_config_file {
+    } else if let Some(config_file) = maybe_shared_config_file {
       let specifier = config_file.specifier.clone();
       if specifier.scheme() == "file" {
         let maybe_stop_at = specifier
@@ -640,12 +786,12 @@ impl CliOptions {
       maybe_package_json = discover_package_json(&flags, None, &initial_cwd)?;
     }
 
-    let maybe_lock_file =
-      lockfile::discover(&flags, maybe_config_file.as_ref())?;
+    let maybe_lock_file = lockfile::discover(&flags, maybe_shared_config_file)?;
     Self::new(
       flags,
       initial_cwd,
       maybe_config_file,
+      maybe_workspace,
       maybe_lock_file,
       maybe_package_json,
     )
@@ -676,20 +822,93 @@ impl CliOptions {
     }
   }
 
+  pub fn max_redirects(&self) -> Option<u32> {
+    self.flags.max_redirects
+  }
+
+  /// Whether nothing may be downloaded (`--offline` or `DENO_OFFLINE=1`).
+  pub fn is_offline(&self) -> bool {
+    self.flags.offline || has_flag_env_var("DENO_OFFLINE")
+  }
+
+  /// The maximum number of npm package tarballs that are downloaded at once.
+  /// `--npm-concurrency` takes precedence over `DENO_NPM_CONCURRENCY`.
+  pub fn npm_download_concurrency(&self) -> usize {
+    if let Some(concurrency) = self.flags.npm_concurrency {
+      return concurrency;
+    }
+    match env::var("DENO_NPM_CONCURRENCY") {
+      Ok(value) => match value.parse::<usize>() {
+        Ok(concurrency) if concurrency > 0 => concurrency,
+        _ => {
+          log::warn!(
+            "{} Ignoring invalid DENO_NPM_CONCURRENCY '{}', expected a \
+positive number.",
+            colors::yellow("Warning"),
+            value
+          );
+          DEFAULT_NPM_DOWNLOAD_CONCURRENCY
+        }
+      },
+      Err(_) => DEFAULT_NPM_DOWNLOAD_CONCURRENCY,
+    }
+  }
+
+  /// The maximum size of the npm cache, from the config file or otherwise
+  /// `DENO_NPM_CACHE_MAX_SIZE`.
+  pub fn npm_cache_max_size(&self) -> Result<Option<u64>, AnyError> {
+    if let Some(config_file) = self.shared_config_file() {
+      if let Some(max_size) = config_file.to_npm_cache_max_size()? {
+        return Ok(Some(max_size));
+      }
+    }
+    match env::var("DENO_NPM_CACHE_MAX_SIZE") {
+      Ok(value) => match parse_size_arg(&value) {
+        Ok(max_size) => Ok(Some(max_size)),
+        Err(err) => {
+          log::warn!(
+            "{} Ignoring invalid DENO_NPM_CACHE_MAX_SIZE: {}",
+            colors::yellow("Warning"),
+            err
+          );
+          Ok(None)
+        }
+      },
+      Err(_) => Ok(None),
+    }
+  }
+
   pub fn cache_setting(&self) -> CacheSetting {
-    if self.flags.cached_only {
+    if self.is_offline() {
+      CacheSetting::Offline
+    } else if self.flags.cached_only {
       CacheSetting::Only
     } else if !self.flags.cache_blocklist.is_empty() {
       CacheSetting::ReloadSome(self.flags.cache_blocklist.clone())
     } else if self.flags.reload {
       CacheSetting::ReloadAll
+    } else if self.flags.revalidate {
+      CacheSetting::Revalidate
+    } else if self.flags.reload_stale {
+      CacheSetting::RespectHeaders
     } else {
       CacheSetting::Use
     }
   }
 
+  /// Resolves DENO_DIR, with the npm cache in the `npmCacheDir` of the
+  /// config file when there is one, which takes precedence over the
+  /// `DENO_NPM_DIR` environment variable.
   pub fn resolve_deno_dir(&self) -> Result<DenoDir, AnyError> {
-    Ok(DenoDir::new(self.maybe_custom_root())?)
+    let deno_dir = DenoDir::new(self.maybe_custom_root())?;
+    let maybe_npm_cache_dir = match self.shared_config_file() {
+      Some(config_file) => config_file.to_npm_cache_dir()?,
+      None => None,
+    };
+    Ok(match maybe_npm_cache_dir {
+      Some(npm_cache_dir) => deno_dir.with_npm_folder_path(npm_cache_dir),
+      None => deno_dir,
+    })
   }
 
   /// Based on an optional command line import map path and an optional
@@ -702,7 +921,7 @@ impl CliOptions {
       Some(maybe_path) => Ok(maybe_path),
       None => resolve_import_map_specifier(
         self.flags.import_map_path.as_deref(),
-        self.maybe_config_file.as_ref(),
+        self.shared_config_file(),
         &self.initial_cwd,
       ),
     }
@@ -718,7 +937,7 @@ impl CliOptions {
     };
     resolve_import_map_from_specifier(
       &import_map_specifier,
-      self.maybe_config_file().as_ref(),
+      self.shared_config_file(),
       file_fetcher,
     )
     .await
@@ -742,10 +961,14 @@ impl CliOptions {
         resolve_url_or_path("./$deno$eval", self.initial_cwd())
           .map_err(AnyError::from)
       }
-      DenoSubcommand::Repl(_) => {
+      DenoSubcommand::Repl(_) | DenoSubcommand::Jupyter(_) => {
         resolve_url_or_path("./$deno$repl.ts", self.initial_cwd())
           .map_err(AnyError::from)
       }
+      DenoSubcommand::Serve(serve_flags) => {
+        resolve_url_or_path(&serve_flags.script, self.initial_cwd())
+          .map_err(AnyError::from)
+      }
       DenoSubcommand::Run(run_flags) => {
         if run_flags.is_stdin() {
           std::env::current_dir()
@@ -799,6 +1022,7 @@ impl CliOptions {
   pub async fn resolve_npm_resolution_snapshot(
     &self,
     api: &CliNpmRegistryApi,
+    snapshot_cache: &NpmSnapshotCache,
   ) -> Result<Option<ValidSerializedNpmResolutionSnapshot>, AnyError> {
     if let Some(state) = &*NPM_PROCESS_STATE {
       // TODO(bartlomieju): remove this clone
@@ -807,22 +1031,66 @@ impl CliOptions {
 
     if let Some(lockfile) = self.maybe_lockfile() {
       if !lockfile.lock().overwrite {
-        return Ok(Some(
-          snapshot_from_lockfile(lockfile.clone(), api)
-            .await
-            .with_context(|| {
-              format!(
-                "failed reading lockfile '{}'",
-                lockfile.lock().filename.display()
-              )
-            })?,
-        ));
+        let (lockfile_key, lockfile_hash) = {
+          let lockfile = lockfile.lock();
+          (
+            lockfile.filename.to_string_lossy().to_string(),
+            npm_lockfile_hash(&lockfile, api),
+          )
+        };
+        // the resolved snapshot only changes with the npm section of the
+        // lockfile, so it's reused on warm runs unless npm is reloaded
+        let use_snapshot_cache = self.should_use_npm_snapshot_cache();
+        if use_snapshot_cache {
+          if let Some(snapshot) =
+            snapshot_cache.get(&lockfile_key, lockfile_hash)
+          {
+            if let Ok(snapshot) = snapshot.into_valid() {
+              return Ok(Some(snapshot));
+            }
+          }
+        }
+        let snapshot = snapshot_from_lockfile(lockfile.clone(), api)
+          .await
+          .with_context(|| {
+            format!(
+              "failed reading lockfile '{}'",
+              lockfile.lock().filename.display()
+            )
+          })?;
+        if use_snapshot_cache {
+          // a corrupt snapshot is resolved again when reading it from the
+          // cache fails to validate it
+          snapshot_cache.set(&lockfile_key, lockfile_hash, &snapshot);
+        }
+        let snapshot = snapshot
+          .into_valid()
+          .context(
+            "The lockfile is corrupt. You can recreate it with --lock-write",
+          )
+          .with_context(|| {
+            format!(
+              "failed reading lockfile '{}'",
+              lockfile.lock().filename.display()
+            )
+          })?;
+        return Ok(Some(snapshot));
       }
     }
 
     Ok(None)
   }
 
+  fn should_use_npm_snapshot_cache(&self) -> bool {
+    match self.cache_setting() {
+      CacheSetting::ReloadAll => false,
+      CacheSetting::ReloadSome(list) => {
+        !list.iter().any(|i| i.starts_with("npm:"))
+      }
+      _ => true,
+    }
+  }
+
   // If the main module should be treated as being in an npm package.
   // This is triggered via a secret environment variable which is used
   // for functionality like child_process.fork. Users should NOT depend
@@ -837,13 +1105,62 @@ impl CliOptions {
   }
 
   pub fn has_node_modules_dir(&self) -> bool {
-    self.maybe_node_modules_folder.is_some()
+    self.maybe_node_modules_folder.is_some() || self.byonm()
+  }
+
+  /// Whether npm packages are resolved from an existing node_modules
+  /// directory that deno doesn't manage (`--byonm`).
+  pub fn byonm(&self) -> bool {
+    self.flags.byonm
+  }
+
+  /// The node_modules directory next to the package.json (or in the current
+  /// directory) that packages are resolved from when using `--byonm`.
+  pub fn byonm_node_modules_dir_path(&self) -> Option<PathBuf> {
+    if !self.byonm() {
+      return None;
+    }
+    let root_dir = match &self.maybe_package_json {
+      Some(package_json) => package_json.path.parent().unwrap(),
+      None => self.initial_cwd(),
+    };
+    Some(root_dir.join("node_modules"))
   }
 
   pub fn node_modules_dir_path(&self) -> Option<PathBuf> {
     self.maybe_node_modules_folder.clone()
   }
 
+  /// Return how the copies of packages in the npm cache are created.
+  /// `--npm-copy-strategy` takes precedence over the config file.
+  pub fn resolve_npm_copy_strategy(&self) -> Result<NpmCopyStrategy, AnyError> {
+    if let Some(strategy) = self.flags.npm_copy_strategy {
+      return Ok(strategy);
+    }
+    match self.shared_config_file() {
+      Some(config_file) => {
+        Ok(config_file.to_npm_copy_strategy()?.unwrap_or_default())
+      }
+      None => Ok(NpmCopyStrategy::default()),
+    }
+  }
+
+  /// Return how packages are created in the local node_modules directory.
+  /// `--node-modules-layout` takes precedence over the config file.
+  pub fn resolve_node_modules_layout(
+    &self,
+  ) -> Result<NodeModulesLayout, AnyError> {
+    if let Some(layout) = self.flags.node_modules_layout {
+      return Ok(layout);
+    }
+    match self.shared_config_file() {
+      Some(config_file) => {
+        Ok(config_file.to_node_modules_layout()?.unwrap_or_default())
+      }
+      None => Ok(NodeModulesLayout::default()),
+    }
+  }
+
   pub fn node_modules_dir_specifier(&self) -> Option<ModuleSpecifier> {
     self
       .maybe_node_modules_folder
@@ -897,6 +1214,99 @@ impl CliOptions {
     }
   }
 
+  pub fn resolve_watch_config(&self) -> Result<WatchConfig, AnyError> {
+    match &self.maybe_config_file {
+      Some(config_file) => {
+        Ok(config_file.to_watch_config()?.unwrap_or_default())
+      }
+      None => Ok(Default::default()),
+    }
+  }
+
+  /// Return the `auth` configuration for module hosts. Workspace members use
+  /// the configuration of the workspace root.
+  pub fn resolve_auth_config(&self) -> Result<Option<AuthConfig>, AnyError> {
+    match self.shared_config_file() {
+      Some(config_file) => config_file.to_auth_config(),
+      None => Ok(None),
+    }
+  }
+
+  /// Return the client certificates to present to hosts requiring mutual TLS.
+  /// Certificates passed with `--client-cert` take precedence over the ones
+  /// of the `auth` configuration.
+  pub fn resolve_client_certificates(
+    &self,
+  ) -> Result<Vec<ClientCertificate>, AnyError> {
+    let mut client_certs = self.flags.client_certs.clone();
+    if let Some(auth_config) = self.resolve_auth_config()? {
+      client_certs.extend(to_client_certificates(&auth_config));
+    }
+    Ok(client_certs)
+  }
+
+  /// Return the CA certificates that are only trusted for a host.
+  /// Certificates passed with `--host-ca` take precedence over the ones of the
+  /// `auth` configuration.
+  pub fn resolve_host_ca_certificates(
+    &self,
+  ) -> Result<Vec<HostCaCertificate>, AnyError> {
+    let mut host_ca_certs = self.flags.host_ca_certs.clone();
+    if let Some(auth_config) = self.resolve_auth_config()? {
+      host_ca_certs.extend(to_host_ca_certificates(&auth_config));
+    }
+    Ok(host_ca_certs)
+  }
+
+  /// Return the overrides of remote modules. Workspace members use the
+  /// overrides of the workspace root.
+  pub fn resolve_module_overrides(
+    &self,
+  ) -> Result<Option<ModuleOverrides>, AnyError> {
+    match self.shared_config_file() {
+      Some(config_file) => config_file.to_module_overrides(),
+      None => Ok(None),
+    }
+  }
+
+  /// Return the expected hashes of remote modules. Workspace members use the
+  /// ones of the workspace root.
+  pub fn resolve_integrity_manifest(
+    &self,
+  ) -> Result<Option<IntegrityManifest>, AnyError> {
+    match self.shared_config_file() {
+      Some(config_file) => config_file.to_integrity_manifest(),
+      None => Ok(None),
+    }
+  }
+
+  /// Return the custom conditions used when resolving the exports of npm
+  /// packages. `--conditions` takes precedence over the config file.
+  pub fn resolve_node_conditions(&self) -> Result<Vec<String>, AnyError> {
+    if !self.flags.node_conditions.is_empty() {
+      return Ok(self.flags.node_conditions.clone());
+    }
+    match self.shared_config_file() {
+      Some(config_file) => {
+        Ok(config_file.to_node_conditions()?.unwrap_or_default())
+      }
+      None => Ok(Vec::new()),
+    }
+  }
+
+  /// Return the vendor directory to resolve remote modules from when they
+  /// have been vendored (`--prefer-vendor`).
+  pub fn resolve_prefer_vendor(
+    &self,
+  ) -> Result<Option<PreferVendor>, AnyError> {
+    match &self.flags.prefer_vendor {
+      Some(vendor_dir) => PreferVendor::from_dir(&normalize_path(
+        self.initial_cwd.join(vendor_dir),
+      )),
+      None => Ok(None),
+    }
+  }
+
   /// Return the JSX import source configuration.
   pub fn to_maybe_jsx_import_source_config(
     &self,
@@ -921,16 +1331,38 @@ impl CliOptions {
     &self.maybe_config_file
   }
 
+  /// The config file that provides the import map, lockfile and npm
+  /// resolution. For workspace members this is the workspace root.
+  fn shared_config_file(&self) -> Option<&ConfigFile> {
+    self
+      .maybe_workspace
+      .as_ref()
+      .map(|w| w.root())
+      .or(self.maybe_config_file.as_ref())
+  }
+
+  /// Returns the workspace when the command is run from its root, in which
+  /// case the configuration of each member applies to the member's files.
+  fn maybe_workspace_root(&self) -> Option<&Workspace> {
+    let config_file = self.maybe_config_file.as_ref()?;
+    self
+      .maybe_workspace
+      .as_ref()
+      .filter(|w| w.is_root(config_file))
+  }
+
   pub fn maybe_package_json(&self) -> &Option<PackageJson> {
     &self.maybe_package_json
   }
 
   pub fn maybe_package_json_deps(&self) -> Option<PackageJsonDeps> {
-    if matches!(
+    // don't have any package json dependencies for deno task with no args
+    let is_task_without_args = matches!(
       self.flags.subcommand,
       DenoSubcommand::Task(TaskFlags { task: None, .. })
-    ) {
-      // don't have any package json dependencies for deno task with no args
+    );
+    // with byonm the dependencies are installed by another package manager
+    if is_task_without_args || self.byonm() {
       None
     } else {
       self
@@ -949,7 +1381,24 @@ impl CliOptions {
     } else {
       None
     };
-    FmtOptions::resolve(maybe_fmt_config, Some(fmt_flags))
+    let mut fmt_options =
+      FmtOptions::resolve(maybe_fmt_config, Some(fmt_flags.clone()))?;
+    if let Some(workspace) = self.maybe_workspace_root() {
+      // members without a `fmt` configuration use the one of the root, while
+      // flags take precedence over the configuration of each member
+      fmt_options.workspace_members = workspace.resolve_member_options(
+        &fmt_options.options,
+        |config_file| {
+          Ok(config_file.to_fmt_config()?.map(|c| {
+            (
+              c.files,
+              resolve_fmt_options(Some(&fmt_flags), Some(c.options)),
+            )
+          }))
+        },
+      )?;
+    }
+    Ok(fmt_options)
   }
 
   pub fn resolve_lint_options(
@@ -961,7 +1410,31 @@ impl CliOptions {
     } else {
       None
     };
-    LintOptions::resolve(maybe_lint_config, Some(lint_flags))
+    let mut lint_options =
+      LintOptions::resolve(maybe_lint_config, Some(lint_flags.clone()))?;
+    if let Some(workspace) = self.maybe_workspace_root() {
+      // members without a `lint` configuration use the one of the root, while
+      // flags take precedence over the configuration of each member
+      let rules_from_flags = |maybe_rules| {
+        resolve_lint_rules_options(
+          maybe_rules,
+          lint_flags.maybe_rules_tags.clone(),
+          lint_flags.maybe_rules_include.clone(),
+          lint_flags.maybe_rules_exclude.clone(),
+        )
+      };
+      lint_options.workspace_members = workspace.resolve_member_options(
+        &lint_options.rules,
+        |config_file| {
+          Ok(
+            config_file
+              .to_lint_config()?
+              .map(|c| (c.files, rules_from_flags(Some(c.rules)))),
+          )
+        },
+      )?;
+    }
+    Ok(lint_options)
   }
 
   pub fn resolve_test_options(
@@ -973,7 +1446,15 @@ impl CliOptions {
     } else {
       None
     };
-    TestOptions::resolve(maybe_test_config, Some(test_flags))
+    let mut test_options =
+      TestOptions::resolve(maybe_test_config, Some(test_flags))?;
+    if let Some(workspace) = self.maybe_workspace_root() {
+      test_options.workspace_members = workspace
+        .resolve_member_options(&(), |config_file| {
+          Ok(config_file.to_test_config()?.map(|c| (c.files, ())))
+        })?;
+    }
+    Ok(test_options)
   }
 
   pub fn resolve_bench_options(
@@ -986,7 +1467,15 @@ impl CliOptions {
     } else {
       None
     };
-    BenchOptions::resolve(maybe_bench_config, Some(bench_flags))
+    let mut bench_options =
+      BenchOptions::resolve(maybe_bench_config, Some(bench_flags))?;
+    if let Some(workspace) = self.maybe_workspace_root() {
+      bench_options.workspace_members = workspace
+        .resolve_member_options(&(), |config_file| {
+          Ok(config_file.to_bench_config()?.map(|c| (c.files, ())))
+        })?;
+    }
+    Ok(bench_options)
   }
 
   /// Vector of user script CLI arguments.
@@ -1093,8 +1582,48 @@ impl CliOptions {
     self.flags.no_npm
   }
 
-  pub fn permissions_options(&self) -> PermissionsOptions {
-    PermissionsOptions {
+  pub fn worker_permissions_config(
+    &self,
+  ) -> Result<WorkerPermissionsConfig, AnyError> {
+    match &self.maybe_config_file {
+      Some(config_file) => Ok(
+        config_file
+          .to_worker_permissions_config()?
+          .unwrap_or_default(),
+      ),
+      None => Ok(Default::default()),
+    }
+  }
+
+  /// Options of the OS sandbox to apply before running the program, if
+  /// requested. The files Deno itself keeps using while the program runs
+  /// stay accessible.
+  pub fn sandbox_options(
+    &self,
+    deno_dir: &DenoDir,
+  ) -> Result<Option<SandboxOptions>, AnyError> {
+    if !self.flags.sandbox {
+      return Ok(None);
+    }
+    let mut options =
+      SandboxOptions::from_permissions(&self.permissions_options()?);
+    for path in deno_dir.cache_folder_paths() {
+      options.allow_runtime_path(path);
+    }
+    if let Some(node_modules_dir) = self.node_modules_dir_path() {
+      options.allow_runtime_path(node_modules_dir);
+    }
+    if let Some(lockfile) = self.maybe_lockfile() {
+      options.allow_runtime_path(lockfile.lock().filename.clone());
+    }
+    if let Some(coverage_dir) = self.coverage_dir() {
+      options.allow_runtime_path(PathBuf::from(coverage_dir));
+    }
+    Ok(Some(options))
+  }
+
+  pub fn permissions_options(&self) -> Result<PermissionsOptions, AnyError> {
+    let mut options = PermissionsOptions {
       allow_env: self.flags.allow_env.clone(),
       allow_hrtime: self.flags.allow_hrtime,
       allow_net: self.flags.allow_net.clone(),
@@ -1103,8 +1632,25 @@ impl CliOptions {
       allow_run: self.flags.allow_run.clone(),
       allow_sys: self.flags.allow_sys.clone(),
       allow_write: self.flags.allow_write.clone(),
+      allow_unix: self.flags.allow_unix.clone(),
+      allow_udp: self.flags.allow_udp.clone(),
       prompt: !self.no_prompt(),
+      ..Default::default()
+    };
+    if let Some(policy) = &self.maybe_permissions_policy {
+      policy.apply(&mut options);
     }
+    match &self.maybe_inherited_permissions {
+      Some(inherited) => narrow_to_inherited_permissions(&options, inherited)
+        .context(
+          "Permissions exceed the ones inherited from the parent process.",
+        ),
+      None => Ok(options),
+    }
+  }
+
+  pub fn no_code_cache(&self) -> bool {
+    self.flags.no_code_cache
   }
 
   pub fn reload_flag(&self) -> bool {
@@ -1115,6 +1661,10 @@ impl CliOptions {
     self.flags.seed
   }
 
+  pub fn heap_snapshot_on_oom(&self) -> bool {
+    self.flags.heap_snapshot_on_oom
+  }
+
   pub fn sub_command(&self) -> &DenoSubcommand {
     &self.flags.subcommand
   }
@@ -1138,6 +1688,36 @@ impl CliOptions {
   pub fn watch_paths(&self) -> &Option<Vec<PathBuf>> {
     &self.flags.watch
   }
+
+  /// The paths passed to `--watch`, resolved against the initial working
+  /// directory.
+  pub fn extra_watch_paths(&self) -> Vec<PathBuf> {
+    self
+      .flags
+      .watch
+      .iter()
+      .flatten()
+      .map(|path| self.resolve_watch_path(path))
+      .collect()
+  }
+
+  /// The globs passed to `--watch-exclude`, resolved against the initial
+  /// working directory.
+  pub fn watch_exclude(&self) -> Vec<PathBuf> {
+    self
+      .flags
+      .watch_exclude
+      .iter()
+      .map(|path| self.resolve_watch_path(path))
+      .collect()
+  }
+
+  fn resolve_watch_path(&self, path: &Path) -> PathBuf {
+    // The watcher reports canonicalized paths.
+    let path = self.initial_cwd.join(path);
+    canonicalize_path_maybe_not_exists(&path)
+      .unwrap_or_else(|_| normalize_path(path))
+  }
 }
 
 /// Resolves the path to use for a local node_modules folder.
@@ -1147,7 +1727,7 @@ fn resolve_local_node_modules_folder(
   maybe_config_file: Option<&ConfigFile>,
   maybe_package_json: Option<&PackageJson>,
 ) -> Result<Option<PathBuf>, AnyError> {
-  let path = if flags.node_modules_dir == Some(false) {
+  let path = if flags.node_modules_dir == Some(false) || flags.byonm {
     return Ok(None);
   } else if let Some(state) = &*NPM_PROCESS_STATE {
     return Ok(state.local_node_modules_path.as_ref().map(PathBuf::from));
@@ -1295,6 +1875,20 @@ fn resolve_files(
 }
 
 /// Resolves the no_prompt value based on the cli flags and environment.
+/// Reads the permissions a parent `deno` process passed down to this one, if
+/// any. See `deno_runtime::permissions::INHERITED_PERMISSIONS_ENV_VAR_NAME`.
+pub fn inherited_permissions_from_env(
+) -> Result<Option<PermissionsOptions>, AnyError> {
+  match std::env::var(INHERITED_PERMISSIONS_ENV_VAR_NAME) {
+    Ok(value) => serde_json::from_str(&value).map(Some).with_context(|| {
+      format!(
+        "Invalid {INHERITED_PERMISSIONS_ENV_VAR_NAME} environment variable."
+      )
+    }),
+    Err(_) => Ok(None),
+  }
+}
+
 pub fn resolve_no_prompt(flags: &Flags) -> bool {
   flags.no_prompt || has_flag_env_var("DENO_NO_PROMPT")
 }
@@ -1471,4 +2065,27 @@ mod test {
     let resolver = StorageKeyResolver::empty();
     assert_eq!(resolver.resolve_storage_key(&specifier), None);
   }
+
+  #[test]
+  fn not_cached_error_combine() {
+    assert!(NotCachedError::combine(Vec::new()).is_none());
+    let err = NotCachedError::combine(vec![NotCachedError::new(
+      "https://deno.land/x/a.ts",
+    )])
+    .unwrap();
+    assert_eq!(
+      err.to_string(),
+      "Not cached, offline mode: \"https://deno.land/x/a.ts\" is not in the cache and can't be downloaded."
+    );
+    let err = NotCachedError::combine(vec![
+      NotCachedError::new("https://deno.land/x/a.ts"),
+      NotCachedError::new("npm:chalk"),
+      NotCachedError::new("https://deno.land/x/a.ts"),
+    ])
+    .unwrap();
+    assert_eq!(
+      err.to_string(),
+      "Not cached, offline mode: 2 resources are not in the cache and can't be downloaded:\n  https://deno.land/x/a.ts\n  npm:chalk"
+    );
+  }
 }
@@ -0,0 +1,303 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use deno_semver::Version;
+use deno_semver::VersionReq;
+
+use super::lockfile_remote_entries;
+use super::set_lockfile_remote_entries;
+use super::Lockfile;
+
+/// Prefix of the lockfile entries that record the applied overrides.
+const LOCKFILE_KEY_PREFIX: &str = "override:";
+
+/// Overrides of remote modules from the `overrides` section of a config
+/// file. They are applied at resolution time, so every importer of an
+/// overridden module uses the replacement:
+///
+/// ```json
+/// {
+///   "overrides": {
+///     "https://deno.land/std@0.190.0/http/server.ts": "./patches/server.ts",
+///     "https://deno.land/x/oak@^12.0.0/": "https://deno.land/x/oak@v12.6.0/"
+///   }
+/// }
+/// ```
+///
+/// Keys ending with a slash override every module below them. The version of
+/// a key (after the `@`) can be a version range, which matches the versions
+/// of the imported urls (eg. `v12.5.1` matches `^12.0.0`). Local paths are
+/// resolved from the directory of the config file.
+#[derive(Clone, Debug, Default)]
+pub struct ModuleOverrides {
+  entries: Vec<ModuleOverride>,
+}
+
+#[derive(Clone, Debug)]
+struct ModuleOverride {
+  /// The part of the key before the version segment, or the whole key.
+  prefix: String,
+  version: Option<OverrideVersion>,
+  /// The part of the key after the version segment.
+  rest: String,
+  target: ModuleSpecifier,
+}
+
+#[derive(Clone, Debug)]
+struct OverrideVersion {
+  text: String,
+  maybe_req: Option<VersionReq>,
+}
+
+impl ModuleOverride {
+  fn is_dir(&self) -> bool {
+    if self.version.is_some() {
+      self.rest.ends_with('/')
+    } else {
+      self.prefix.ends_with('/')
+    }
+  }
+
+  fn key_len(&self) -> usize {
+    self.prefix.len()
+      + self.version.as_ref().map(|v| v.text.len()).unwrap_or(0)
+      + self.rest.len()
+  }
+
+  /// Returns the part of the specifier after the matched key.
+  fn match_specifier<'a>(&self, specifier: &'a str) -> Option<&'a str> {
+    let after_prefix = specifier.strip_prefix(self.prefix.as_str())?;
+    let remaining = match &self.version {
+      Some(version) => {
+        let end = after_prefix.find('/').unwrap_or(after_prefix.len());
+        let (specifier_version, rest) = after_prefix.split_at(end);
+        if !version.matches(specifier_version) {
+          return None;
+        }
+        rest.strip_prefix(self.rest.as_str())?
+      }
+      None => after_prefix,
+    };
+    if remaining.is_empty() || self.is_dir() {
+      Some(remaining)
+    } else {
+      None
+    }
+  }
+}
+
+impl OverrideVersion {
+  fn matches(&self, version_text: &str) -> bool {
+    if self.text == version_text {
+      return true;
+    }
+    match &self.maybe_req {
+      Some(req) => {
+        let version_text =
+          version_text.strip_prefix('v').unwrap_or(version_text);
+        Version::parse_standard(version_text)
+          .map(|version| req.matches(&version))
+          .unwrap_or(false)
+      }
+      None => false,
+    }
+  }
+}
+
+impl ModuleOverrides {
+  pub fn new(
+    overrides: BTreeMap<String, String>,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<Self, AnyError> {
+    let mut entries = Vec::with_capacity(overrides.len());
+    for (key, value) in overrides {
+      let key_url = ModuleSpecifier::parse(&key)
+        .with_context(|| format!("Invalid override \"{key}\""))?;
+      if !matches!(key_url.scheme(), "http" | "https") {
+        bail!(
+          "Invalid override \"{}\", only remote modules can be overridden",
+          key
+        );
+      }
+      let target = config_file_specifier
+        .join(&value)
+        .with_context(|| format!("Invalid target of override \"{key}\""))?;
+      let (prefix, version, rest) = split_version(&key);
+      let entry = ModuleOverride {
+        prefix: prefix.to_string(),
+        version: version.map(|text| OverrideVersion {
+          text: text.to_string(),
+          maybe_req: VersionReq::parse_from_specifier(
+            text.strip_prefix('v').unwrap_or(text),
+          )
+          .ok(),
+        }),
+        rest: rest.to_string(),
+        target,
+      };
+      if entry.is_dir() && !entry.target.path().ends_with('/') {
+        bail!(
+          "Invalid target of override \"{}\", the target of a directory must end with a slash",
+          key
+        );
+      }
+      entries.push(entry);
+    }
+    // the most specific override wins
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.key_len()));
+    Ok(Self { entries })
+  }
+
+  /// Returns the replacement of the specifier, if it is overridden.
+  pub fn resolve(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Result<Option<ModuleSpecifier>, AnyError> {
+    if !matches!(specifier.scheme(), "http" | "https") {
+      return Ok(None);
+    }
+    for entry in &self.entries {
+      if let Some(remaining) = entry.match_specifier(specifier.as_str()) {
+        if remaining.is_empty() {
+          return Ok(Some(entry.target.clone()));
+        }
+        return Ok(Some(entry.target.join(remaining)?));
+      }
+    }
+    Ok(None)
+  }
+}
+
+/// Splits a key into the part before the version, the version and the part
+/// after the version, eg. `https://deno.land/x/oak@^12.0.0/mod.ts` into
+/// `https://deno.land/x/oak@`, `^12.0.0` and `/mod.ts`.
+fn split_version(key: &str) -> (&str, Option<&str>, &str) {
+  match key.rfind('@') {
+    Some(index) => {
+      let (prefix, after) = key.split_at(index + 1);
+      let end = after.find('/').unwrap_or(after.len());
+      let (version, rest) = after.split_at(end);
+      if version.is_empty() {
+        (key, None, "")
+      } else {
+        (prefix, Some(version), rest)
+      }
+    }
+    None => (key, None, ""),
+  }
+}
+
+/// Records an applied override in the lockfile, erroring when the lockfile
+/// contains a different target for it.
+pub fn check_or_insert_locked_override(
+  lockfile: &mut Lockfile,
+  specifier: &ModuleSpecifier,
+  target: &ModuleSpecifier,
+) -> Result<(), AnyError> {
+  let key = format!("{LOCKFILE_KEY_PREFIX}{specifier}");
+  let mut remote = lockfile_remote_entries(lockfile);
+  match remote.get(&key) {
+    Some(locked) if locked == target.as_str() => Ok(()),
+    Some(locked) if !lockfile.overwrite => bail!(
+      "The override of \"{}\" resolved to \"{}\", but the lock file contains \"{}\". Run with --lock-write to update the lock file.\n  Lock file: {}",
+      specifier,
+      target,
+      locked,
+      lockfile.filename.display(),
+    ),
+    _ => {
+      remote.insert(key, target.to_string());
+      set_lockfile_remote_entries(lockfile, remote);
+      lockfile.has_content_changed = true;
+      Ok(())
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn resolve(overrides: &ModuleOverrides, specifier: &str) -> Option<String> {
+    overrides
+      .resolve(&ModuleSpecifier::parse(specifier).unwrap())
+      .unwrap()
+      .map(|s| s.to_string())
+  }
+
+  #[test]
+  fn module_overrides() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///project/deno.json").unwrap();
+    let overrides = ModuleOverrides::new(
+      BTreeMap::from([
+        (
+          "https://deno.land/std@0.190.0/http/server.ts".to_string(),
+          "./patches/server.ts".to_string(),
+        ),
+        (
+          "https://deno.land/x/oak@^12.0.0/".to_string(),
+          "https://deno.land/x/oak@v12.6.0/".to_string(),
+        ),
+        (
+          "https://deno.land/x/oak@^12.0.0/router.ts".to_string(),
+          "./patches/router.ts".to_string(),
+        ),
+        (
+          "https://example.com/lib/".to_string(),
+          "./vendor/lib/".to_string(),
+        ),
+      ]),
+      &config_specifier,
+    )
+    .unwrap();
+
+    assert_eq!(
+      resolve(&overrides, "https://deno.land/std@0.190.0/http/server.ts"),
+      Some("file:///project/patches/server.ts".to_string())
+    );
+    assert_eq!(
+      resolve(&overrides, "https://deno.land/std@0.190.0/http/mod.ts"),
+      None
+    );
+    assert_eq!(
+      resolve(&overrides, "https://deno.land/x/oak@v12.5.1/mod.ts"),
+      Some("https://deno.land/x/oak@v12.6.0/mod.ts".to_string())
+    );
+    assert_eq!(
+      resolve(&overrides, "https://deno.land/x/oak@v12.5.1/router.ts"),
+      Some("file:///project/patches/router.ts".to_string())
+    );
+    assert_eq!(
+      resolve(&overrides, "https://deno.land/x/oak@v11.1.0/mod.ts"),
+      None
+    );
+    assert_eq!(
+      resolve(&overrides, "https://example.com/lib/a/b.ts"),
+      Some("file:///project/vendor/lib/a/b.ts".to_string())
+    );
+    assert_eq!(resolve(&overrides, "file:///project/mod.ts"), None);
+  }
+
+  #[test]
+  fn module_overrides_invalid() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///project/deno.json").unwrap();
+    let new = |key: &str, value: &str| {
+      ModuleOverrides::new(
+        BTreeMap::from([(key.to_string(), value.to_string())]),
+        &config_specifier,
+      )
+    };
+    assert!(new("./mod.ts", "./other.ts").is_err());
+    assert!(new("npm:chalk", "./chalk.ts").is_err());
+    assert!(new("https://example.com/lib/", "./vendor/lib").is_err());
+    assert!(new("https://example.com/lib/", "./vendor/lib/").is_ok());
+  }
+}
@@ -0,0 +1,266 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::normalize_path;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_runtime::permissions::PermissionsOptions;
+
+use super::flags_allow_net;
+
+/// A permission value in the `allow` section of a policy file. `true` grants
+/// the permission entirely while a list grants only the listed entries.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(untagged)]
+enum PolicyAllowValue {
+  All(bool),
+  List(Vec<String>),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct PolicyAllowSection {
+  env: Option<PolicyAllowValue>,
+  ffi: Option<PolicyAllowValue>,
+  hrtime: bool,
+  net: Option<PolicyAllowValue>,
+  read: Option<PolicyAllowValue>,
+  run: Option<PolicyAllowValue>,
+  sys: Option<PolicyAllowValue>,
+  udp: Option<PolicyAllowValue>,
+  unix: Option<PolicyAllowValue>,
+  write: Option<PolicyAllowValue>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct PolicyDenySection {
+  env: Vec<String>,
+  ffi: Vec<String>,
+  net: Vec<String>,
+  read: Vec<String>,
+  run: Vec<String>,
+  sys: Vec<String>,
+  udp: Vec<String>,
+  unix: Vec<String>,
+  write: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct PermissionsPolicyJson {
+  allow: PolicyAllowSection,
+  deny: PolicyDenySection,
+}
+
+/// A permissions policy file passed via `--permissions=policy.json`. It
+/// describes the full set of allowed and denied permissions so that they can
+/// be kept in a single reviewable file instead of long lists of flags:
+///
+/// ```json
+/// {
+///   "allow": {
+///     "read": ["./data"],
+///     "net": ["deno.land", "api.example.com:443"],
+///     "env": ["HOME", "PORT"],
+///     "run": ["git"],
+///     "hrtime": false
+///   },
+///   "deny": {
+///     "read": ["./data/secrets"],
+///     "env": ["AWS_SECRET_ACCESS_KEY"]
+///   }
+/// }
+/// ```
+///
+/// Relative paths are resolved from the directory containing the policy file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PermissionsPolicy {
+  /// Directory that relative paths in the policy are resolved against.
+  base_dir: PathBuf,
+  json: PermissionsPolicyJson,
+}
+
+impl PermissionsPolicy {
+  pub fn read(policy_path: &Path) -> Result<Self, AnyError> {
+    let text = std::fs::read_to_string(policy_path).map_err(|err| {
+      anyhow!(
+        "Error reading permissions policy file {}: {}",
+        policy_path.display(),
+        err
+      )
+    })?;
+    let base_dir = policy_path
+      .parent()
+      .map(|p| p.to_path_buf())
+      .unwrap_or_default();
+    Self::new(&text, base_dir).map_err(|err| {
+      anyhow!(
+        "Invalid permissions policy file {}: {}",
+        policy_path.display(),
+        err
+      )
+    })
+  }
+
+  pub fn new(text: &str, base_dir: PathBuf) -> Result<Self, AnyError> {
+    let value =
+      match jsonc_parser::parse_to_serde_value(text, &Default::default())? {
+        Some(value) if value.is_object() => value,
+        Some(_) => bail!("the policy should be an object"),
+        None => serde_json::json!({}),
+      };
+    let mut json: PermissionsPolicyJson = serde_json::from_value(value)?;
+    // expand bare ports (eg. ":8080") the same way `--allow-net` does
+    if let Some(PolicyAllowValue::List(list)) = &mut json.allow.net {
+      *list = flags_allow_net::parse(std::mem::take(list))?;
+    }
+    if let Some(PolicyAllowValue::List(list)) = &mut json.allow.udp {
+      *list = flags_allow_net::parse(std::mem::take(list))?;
+    }
+    json.deny.net = flags_allow_net::parse(std::mem::take(&mut json.deny.net))?;
+    json.deny.udp = flags_allow_net::parse(std::mem::take(&mut json.deny.udp))?;
+    Ok(Self { base_dir, json })
+  }
+
+  /// Merges the policy into the provided options. Permissions granted by the
+  /// policy are added to the ones already granted (eg. by flags).
+  pub fn apply(&self, options: &mut PermissionsOptions) {
+    let allow = &self.json.allow;
+    let deny = &self.json.deny;
+    let to_path = |s: &String| normalize_path(self.base_dir.join(s));
+    let to_string = |s: &String| s.to_string();
+
+    merge_allow(&mut options.allow_env, &allow.env, to_string);
+    merge_allow(&mut options.allow_ffi, &allow.ffi, to_path);
+    merge_allow(&mut options.allow_read, &allow.read, to_path);
+    merge_allow(&mut options.allow_run, &allow.run, to_string);
+    merge_allow(&mut options.allow_sys, &allow.sys, to_string);
+    merge_allow(&mut options.allow_unix, &allow.unix, to_path);
+    merge_allow(&mut options.allow_write, &allow.write, to_path);
+    merge_allow(&mut options.allow_net, &allow.net, to_string);
+    merge_allow(&mut options.allow_udp, &allow.udp, to_string);
+    options.allow_hrtime |= allow.hrtime;
+
+    merge_deny(&mut options.deny_env, &deny.env, to_string);
+    merge_deny(&mut options.deny_ffi, &deny.ffi, to_path);
+    merge_deny(&mut options.deny_net, &deny.net, to_string);
+    merge_deny(&mut options.deny_udp, &deny.udp, to_string);
+    merge_deny(&mut options.deny_read, &deny.read, to_path);
+    merge_deny(&mut options.deny_run, &deny.run, to_string);
+    merge_deny(&mut options.deny_sys, &deny.sys, to_string);
+    merge_deny(&mut options.deny_unix, &deny.unix, to_path);
+    merge_deny(&mut options.deny_write, &deny.write, to_path);
+  }
+}
+
+fn merge_allow<T>(
+  current: &mut Option<Vec<T>>,
+  value: &Option<PolicyAllowValue>,
+  map: impl Fn(&String) -> T,
+) {
+  match value {
+    Some(PolicyAllowValue::All(true)) => {
+      *current = Some(vec![]);
+    }
+    Some(PolicyAllowValue::List(list)) if !list.is_empty() => {
+      match current {
+        // already granted entirely
+        Some(current) if current.is_empty() => {}
+        Some(current) => current.extend(list.iter().map(map)),
+        None => *current = Some(list.iter().map(map).collect()),
+      }
+    }
+    Some(PolicyAllowValue::All(false)) | Some(PolicyAllowValue::List(_)) => {}
+    None => {}
+  }
+}
+
+fn merge_deny<T>(
+  current: &mut Option<Vec<T>>,
+  list: &[String],
+  map: impl Fn(&String) -> T,
+) {
+  if list.is_empty() {
+    return;
+  }
+  current
+    .get_or_insert_with(Vec::new)
+    .extend(list.iter().map(map));
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn policy_apply() {
+    let policy = PermissionsPolicy::new(
+      r#"{
+        // comments are allowed
+        "allow": {
+          "read": ["./data"],
+          "write": true,
+          "net": ["deno.land", ":8080"],
+          "env": ["HOME"],
+          "hrtime": true
+        },
+        "deny": {
+          "read": ["./data/secrets"],
+          "env": ["AWS_SECRET_ACCESS_KEY"]
+        }
+      }"#,
+      PathBuf::from("/policy"),
+    )
+    .unwrap();
+    let mut options = PermissionsOptions {
+      allow_env: Some(vec!["PORT".to_string()]),
+      ..Default::default()
+    };
+    policy.apply(&mut options);
+    assert_eq!(
+      options,
+      PermissionsOptions {
+        allow_env: Some(vec!["PORT".to_string(), "HOME".to_string()]),
+        allow_hrtime: true,
+        allow_net: Some(vec![
+          "deno.land".to_string(),
+          "0.0.0.0:8080".to_string(),
+          "127.0.0.1:8080".to_string(),
+          "localhost:8080".to_string(),
+        ]),
+        allow_read: Some(vec![PathBuf::from("/policy/data")]),
+        allow_write: Some(vec![]),
+        deny_env: Some(vec!["AWS_SECRET_ACCESS_KEY".to_string()]),
+        deny_read: Some(vec![PathBuf::from("/policy/data/secrets")]),
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn policy_invalid() {
+    assert!(PermissionsPolicy::new("[]", PathBuf::new()).is_err());
+    assert!(PermissionsPolicy::new(
+      r#"{ "allow": { "foo": true } }"#,
+      PathBuf::new()
+    )
+    .is_err());
+    assert!(PermissionsPolicy::new(
+      r#"{ "deny": { "read": true } }"#,
+      PathBuf::new()
+    )
+    .is_err());
+    assert!(PermissionsPolicy::new(
+      r#"{ "allow": { "net": ["bad host:port"] } }"#,
+      PathBuf::new()
+    )
+    .is_err());
+  }
+}
@@ -0,0 +1,128 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use import_map::ImportMap;
+
+/// Resolves remote modules to their copy in a directory created by
+/// `deno vendor` (`--prefer-vendor`), using the import map that was
+/// generated along with it. Modules that are not vendored keep resolving to
+/// their remote specifier, so they are fetched and cached as usual. This
+/// allows vendoring the dependencies of a project incrementally without
+/// switching to the vendored import map.
+#[derive(Debug)]
+pub struct PreferVendor {
+  import_map: ImportMap,
+}
+
+impl PreferVendor {
+  /// Reads the `import_map.json` of the vendor directory. Returns `None`
+  /// when the directory doesn't exist yet, in which case every module is
+  /// remote.
+  pub fn from_dir(vendor_dir: &Path) -> Result<Option<Self>, AnyError> {
+    if !vendor_dir.is_dir() {
+      log::debug!(
+        "Vendor directory {} does not exist, using remote modules.",
+        vendor_dir.display()
+      );
+      return Ok(None);
+    }
+    let import_map_path = vendor_dir.join("import_map.json");
+    let text = std::fs::read_to_string(&import_map_path).with_context(|| {
+      format!(
+        "Could not read the import map of vendor directory \"{}\". Run `deno vendor` to create it.",
+        vendor_dir.display()
+      )
+    })?;
+    let import_map_specifier =
+      ModuleSpecifier::from_file_path(&import_map_path).map_err(|_| {
+        anyhow!("Invalid vendor directory \"{}\".", vendor_dir.display())
+      })?;
+    let result = import_map::parse_from_json(&import_map_specifier, &text)
+      .with_context(|| {
+        format!("Invalid vendor import map \"{import_map_specifier}\"")
+      })?;
+    Ok(Some(Self {
+      import_map: result.import_map,
+    }))
+  }
+
+  /// Returns the vendored copy of the remote module, if it has been
+  /// vendored.
+  pub fn resolve(
+    &self,
+    specifier: &ModuleSpecifier,
+    referrer: &ModuleSpecifier,
+  ) -> Option<ModuleSpecifier> {
+    if !matches!(specifier.scheme(), "http" | "https") {
+      return None;
+    }
+    let local_specifier =
+      self.import_map.resolve(specifier.as_str(), referrer).ok()?;
+    if local_specifier.scheme() != "file" {
+      return None;
+    }
+    let local_path = local_specifier.to_file_path().ok()?;
+    if local_path.is_file() {
+      Some(local_specifier)
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use test_util::TempDir;
+
+  #[test]
+  fn prefer_vendor_resolve() {
+    let temp_dir = TempDir::new();
+    let vendor_dir = temp_dir.path().join("vendor");
+    assert!(PreferVendor::from_dir(&vendor_dir).unwrap().is_none());
+
+    temp_dir.create_dir_all("vendor");
+    assert!(PreferVendor::from_dir(&vendor_dir).is_err());
+
+    temp_dir.write(
+      "vendor/import_map.json",
+      r#"{
+        "imports": {
+          "https://deno.land/": "./deno.land/",
+          "https://esm.sh/preact": "./esm.sh/preact.js"
+        }
+      }"#,
+    );
+    temp_dir.create_dir_all("vendor/deno.land/std@0.190.0/http");
+    temp_dir.write("vendor/deno.land/std@0.190.0/http/server.ts", "");
+    temp_dir.create_dir_all("vendor/esm.sh");
+    temp_dir.write("vendor/esm.sh/preact.js", "");
+    let prefer_vendor = PreferVendor::from_dir(&vendor_dir).unwrap().unwrap();
+    let referrer =
+      ModuleSpecifier::from_file_path(temp_dir.path().join("main.ts")).unwrap();
+    let resolve = |specifier: &str| {
+      prefer_vendor
+        .resolve(&ModuleSpecifier::parse(specifier).unwrap(), &referrer)
+        .map(|s| s.to_file_path().unwrap())
+    };
+
+    assert_eq!(
+      resolve("https://deno.land/std@0.190.0/http/server.ts"),
+      Some(vendor_dir.join("deno.land/std@0.190.0/http/server.ts"))
+    );
+    assert_eq!(
+      resolve("https://esm.sh/preact"),
+      Some(vendor_dir.join("esm.sh/preact.js"))
+    );
+    // not vendored yet
+    assert_eq!(resolve("https://deno.land/std@0.190.0/http/mod.ts"), None);
+    assert_eq!(resolve("https://example.com/mod.ts"), None);
+    assert_eq!(resolve("file:///project/mod.ts"), None);
+  }
+}
@@ -0,0 +1,326 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use deno_runtime::colors;
+
+use super::ConfigFile;
+use super::FilesConfig;
+use crate::util::path::specifier_to_file_path;
+
+/// A workspace described by the `workspace` section of a root config file:
+///
+/// ```json
+/// {
+///   "imports": { "std/": "https://deno.land/std@0.190.0/" },
+///   "workspace": {
+///     "members": ["./packages/core", "./packages/cli"]
+///   }
+/// }
+/// ```
+///
+/// Every member directory has its own `deno.json` or `deno.jsonc`. Members
+/// share the import map, lockfile and npm resolution of the root, while the
+/// `fmt`, `lint`, `test` and `bench` configuration of a member applies to the
+/// files inside of its directory.
+#[derive(Clone, Debug)]
+pub struct Workspace {
+  root: ConfigFile,
+  members: Vec<WorkspaceMember>,
+}
+
+#[derive(Clone, Debug)]
+struct WorkspaceMember {
+  dir: PathBuf,
+  config_file: ConfigFile,
+}
+
+impl Workspace {
+  /// Discovers the workspace the provided config file belongs to, either as
+  /// its root or as one of its members.
+  pub fn discover(config_file: &ConfigFile) -> Result<Option<Self>, AnyError> {
+    if config_file.specifier.scheme() != "file" {
+      return Ok(None);
+    }
+    if config_file.to_workspace_config()?.is_some() {
+      return Self::from_root(config_file.clone()).map(Some);
+    }
+
+    // walk up looking for a root that lists this config file as a member
+    let config_dir = config_file_dir(config_file)?;
+    let mut checked = HashSet::new();
+    let mut maybe_start = config_dir.parent().map(|p| p.to_path_buf());
+    while let Some(start) = maybe_start {
+      let root = match ConfigFile::discover_from(&start, &mut checked)? {
+        Some(root) => root,
+        None => break,
+      };
+      if root.json.workspace.is_some() {
+        let workspace = Self::from_root(root)?;
+        if workspace.members.iter().any(|m| m.dir == config_dir) {
+          return Ok(Some(workspace));
+        }
+        return Ok(None);
+      }
+      maybe_start = config_file_dir(&root)?.parent().map(|p| p.to_path_buf());
+    }
+    Ok(None)
+  }
+
+  fn from_root(root: ConfigFile) -> Result<Self, AnyError> {
+    let workspace_config = root.to_workspace_config()?.unwrap_or_default();
+    let mut members: Vec<WorkspaceMember> =
+      Vec::with_capacity(workspace_config.members.len());
+    for dir in workspace_config.members {
+      let config_file = match read_member_config_file(&dir)? {
+        Some(config_file) => config_file,
+        None => bail!(
+          "Could not find a deno.json or deno.jsonc file in workspace member \"{}\".",
+          dir.display()
+        ),
+      };
+      if config_file.json.workspace.is_some() {
+        bail!(
+          "Workspace member \"{}\" cannot define its own workspace.",
+          config_file.specifier
+        );
+      }
+      if config_file.is_an_import_map()
+        || config_file.to_import_map_path().is_some()
+      {
+        log::warn!(
+          "{} the import map of workspace member \"{}\" is ignored. Members use the import map of the workspace root \"{}\".",
+          colors::yellow("Warning"),
+          config_file.specifier,
+          root.specifier,
+        );
+      }
+      // use the canonicalized path of the config file so that the member
+      // can be matched against discovered config files
+      let dir = config_file_dir(&config_file)?;
+      if members.iter().any(|m| m.dir == dir) {
+        bail!("Duplicate workspace member \"{}\".", dir.display());
+      }
+      members.push(WorkspaceMember { dir, config_file });
+    }
+    Ok(Self { root, members })
+  }
+
+  /// The config file that defines the workspace.
+  pub fn root(&self) -> &ConfigFile {
+    &self.root
+  }
+
+  pub fn is_root(&self, config_file: &ConfigFile) -> bool {
+    self.root.specifier == config_file.specifier
+  }
+
+  /// Resolves per member options for a subcommand. Members that don't
+  /// configure the subcommand fall back to the default options.
+  pub fn resolve_member_options<T: Clone>(
+    &self,
+    default_options: &T,
+    resolve: impl Fn(&ConfigFile) -> Result<Option<(FilesConfig, T)>, AnyError>,
+  ) -> Result<Vec<WorkspaceMemberOptions<T>>, AnyError> {
+    self
+      .members
+      .iter()
+      .map(|member| {
+        let (files, options) = resolve(&member.config_file)?
+          .unwrap_or_else(|| (Default::default(), default_options.clone()));
+        Ok(WorkspaceMemberOptions {
+          dir: member.dir.clone(),
+          files,
+          options,
+        })
+      })
+      .collect()
+  }
+}
+
+/// The options of a subcommand for the files inside of a workspace member.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WorkspaceMemberOptions<T> {
+  pub dir: PathBuf,
+  pub files: FilesConfig,
+  pub options: T,
+}
+
+impl<T> WorkspaceMemberOptions<T> {
+  pub fn map_options<R>(
+    &self,
+    f: impl Fn(&T) -> R,
+  ) -> WorkspaceMemberOptions<R> {
+    WorkspaceMemberOptions {
+      dir: self.dir.clone(),
+      files: self.files.clone(),
+      options: f(&self.options),
+    }
+  }
+}
+
+/// Finds the workspace member the path belongs to, if any.
+pub fn find_workspace_member<'a, T>(
+  members: &'a [WorkspaceMemberOptions<T>],
+  path: &Path,
+) -> Option<&'a WorkspaceMemberOptions<T>> {
+  members
+    .iter()
+    .filter(|member| path.starts_with(&member.dir))
+    .max_by_key(|member| member.dir.components().count())
+}
+
+/// Returns the options that apply to the path, which are the ones of the
+/// workspace member it belongs to or otherwise the root options.
+pub fn workspace_options_for_path<'a, T>(
+  members: &'a [WorkspaceMemberOptions<T>],
+  root_options: &'a T,
+  path: &Path,
+) -> &'a T {
+  match find_workspace_member(members, path) {
+    Some(member) => &member.options,
+    None => root_options,
+  }
+}
+
+/// Returns true if the path is not excluded by the configuration of the
+/// workspace member it belongs to.
+pub fn workspace_member_matches_path<T>(
+  members: &[WorkspaceMemberOptions<T>],
+  path: &Path,
+) -> bool {
+  match find_workspace_member(members, path) {
+    Some(member) => member.files.matches_path(path),
+    None => true,
+  }
+}
+
+/// Returns true if the specifier is not excluded by the configuration of the
+/// workspace member it belongs to.
+pub fn workspace_member_matches_specifier<T>(
+  members: &[WorkspaceMemberOptions<T>],
+  specifier: &ModuleSpecifier,
+) -> bool {
+  match specifier_to_file_path(specifier) {
+    Ok(path) => workspace_member_matches_path(members, &path),
+    // remote modules are never part of a member
+    Err(_) => true,
+  }
+}
+
+fn config_file_dir(config_file: &ConfigFile) -> Result<PathBuf, AnyError> {
+  let config_path = specifier_to_file_path(&config_file.specifier)?;
+  Ok(config_path.parent().unwrap().to_path_buf())
+}
+
+fn read_member_config_file(dir: &Path) -> Result<Option<ConfigFile>, AnyError> {
+  for config_filename in ["deno.json", "deno.jsonc"] {
+    let path = dir.join(config_filename);
+    if path.exists() {
+      return ConfigFile::read(&path).map(Some);
+    }
+  }
+  Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use test_util::TempDir;
+
+  #[test]
+  fn discover_workspace() {
+    let temp_dir = TempDir::new();
+    temp_dir.write(
+      "deno.json",
+      r#"{ "workspace": { "members": ["./a", "./b"] } }"#,
+    );
+    temp_dir.create_dir_all("a");
+    temp_dir.write("a/deno.json", r#"{ "fmt": { "lineWidth": 100 } }"#);
+    temp_dir.create_dir_all("b");
+    temp_dir.write("b/deno.jsonc", "{}");
+    temp_dir.create_dir_all("c");
+    temp_dir.write("c/deno.json", "{}");
+
+    let root_dir = temp_dir.path().canonicalize().unwrap();
+    let root = ConfigFile::read(&root_dir.join("deno.json")).unwrap();
+    let workspace = Workspace::discover(&root).unwrap().unwrap();
+    assert!(workspace.is_root(&root));
+    let member_dirs = workspace
+      .members
+      .iter()
+      .map(|m| m.dir.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(member_dirs, vec![root_dir.join("a"), root_dir.join("b")]);
+
+    let member = ConfigFile::read(&root_dir.join("a/deno.json")).unwrap();
+    let workspace = Workspace::discover(&member).unwrap().unwrap();
+    assert_eq!(workspace.root().specifier, root.specifier);
+    assert!(!workspace.is_root(&member));
+
+    // not listed as a member
+    let other = ConfigFile::read(&root_dir.join("c/deno.json")).unwrap();
+    assert!(Workspace::discover(&other).unwrap().is_none());
+  }
+
+  #[test]
+  fn discover_workspace_invalid_members() {
+    let temp_dir = TempDir::new();
+    temp_dir.write(
+      "deno.json",
+      r#"{ "workspace": { "members": ["./missing"] } }"#,
+    );
+    let root_dir = temp_dir.path().canonicalize().unwrap();
+    let root = ConfigFile::read(&root_dir.join("deno.json")).unwrap();
+    assert!(Workspace::discover(&root).is_err());
+
+    temp_dir.write(
+      "deno.json",
+      r#"{ "workspace": { "members": ["../outside"] } }"#,
+    );
+    let root = ConfigFile::read(&root_dir.join("deno.json")).unwrap();
+    assert!(Workspace::discover(&root).is_err());
+  }
+
+  #[test]
+  fn member_options_for_path() {
+    let members = vec![
+      WorkspaceMemberOptions {
+        dir: PathBuf::from("/ws/a"),
+        files: FilesConfig {
+          include: vec![],
+          exclude: vec![PathBuf::from("/ws/a/gen")],
+        },
+        options: "a",
+      },
+      WorkspaceMemberOptions {
+        dir: PathBuf::from("/ws/a/nested"),
+        files: Default::default(),
+        options: "nested",
+      },
+    ];
+    let options_for = |path: &str| {
+      *workspace_options_for_path(&members, &"root", Path::new(path))
+    };
+    assert_eq!(options_for("/ws/mod.ts"), "root");
+    assert_eq!(options_for("/ws/a/mod.ts"), "a");
+    assert_eq!(options_for("/ws/a/nested/mod.ts"), "nested");
+    assert_eq!(options_for("/ws/ab/mod.ts"), "root");
+
+    let matches =
+      |path: &str| workspace_member_matches_path(&members, Path::new(path));
+    assert!(matches("/ws/mod.ts"));
+    assert!(matches("/ws/a/mod.ts"));
+    assert!(!matches("/ws/a/gen/out.ts"));
+    assert!(workspace_member_matches_specifier(
+      &members,
+      &ModuleSpecifier::parse("https://deno.land/x/mod.ts").unwrap()
+    ));
+  }
+}
@@ -1,14 +1,24 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::AuthConfig;
 use deno_core::ModuleSpecifier;
 use log::debug;
 use log::error;
+use log::warn;
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AuthTokenData {
   Bearer(String),
-  Basic { username: String, password: String },
+  Basic {
+    username: String,
+    password: String,
+  },
+  /// A token sent in a custom header instead of `Authorization`.
+  Header {
+    name: String,
+    value: String,
+  },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,6 +35,22 @@ impl fmt::Display for AuthToken {
         let credentials = format!("{username}:{password}");
         write!(f, "Basic {}", base64::encode(credentials))
       }
+      AuthTokenData::Header { value, .. } => write!(f, "{value}"),
+    }
+  }
+}
+
+impl AuthToken {
+  pub fn new(host: String, token: AuthTokenData) -> Self {
+    Self { host, token }
+  }
+
+  /// The name of the header the token is sent in, if it is not the
+  /// `Authorization` header.
+  pub fn header_name(&self) -> Option<&str> {
+    match &self.token {
+      AuthTokenData::Header { name, .. } => Some(name),
+      _ => None,
     }
   }
 }
@@ -72,6 +98,64 @@ impl AuthTokens {
     Self(tokens)
   }
 
+  /// Create a set of tokens from the `auth` section of a config file, reading
+  /// the referenced secrets with `get_env`.  Hosts whose environment variables
+  /// are not set are skipped.
+  pub fn from_config(
+    config: &AuthConfig,
+    get_env: impl Fn(&str) -> Option<String>,
+  ) -> Self {
+    let mut tokens = Vec::with_capacity(config.len());
+    for (host, host_config) in config {
+      let env_var_name = match host_config
+        .token_env
+        .as_ref()
+        .or(host_config.password_env.as_ref())
+      {
+        Some(env_var_name) => env_var_name,
+        // only authenticates with a client certificate
+        None => continue,
+      };
+      let secret = match get_env(env_var_name) {
+        Some(secret) => secret,
+        None => {
+          warn!(
+            "Auth environment variable \"{}\" for \"{}\" is not set.",
+            env_var_name, host
+          );
+          continue;
+        }
+      };
+      let token = match (&host_config.username, &host_config.header) {
+        (Some(username), _) => AuthTokenData::Basic {
+          username: username.to_string(),
+          password: secret,
+        },
+        (None, Some(name)) => AuthTokenData::Header {
+          name: name.to_string(),
+          value: secret,
+        },
+        (None, None) => AuthTokenData::Bearer(secret),
+      };
+      tokens.push(AuthToken {
+        host: host.to_string(),
+        token,
+      });
+    }
+    // prefer the most specific host when several of them match
+    tokens.sort_by(|a, b| b.host.len().cmp(&a.host.len()));
+    debug!(
+      "Parsed {} auth token(s) from the config file.",
+      tokens.len()
+    );
+    Self(tokens)
+  }
+
+  /// Adds the provided tokens, giving precedence to the existing ones.
+  pub fn extend(&mut self, other: AuthTokens) {
+    self.0.extend(other.0);
+  }
+
   /// Attempt to match the provided specifier to the tokens in the set.  The
   /// matching occurs from the right of the hostname plus port, irrespective of
   /// scheme.  For example `https://www.deno.land:8080/` would match a token
@@ -180,4 +264,69 @@ mod tests {
     let fixture = resolve_url("https://deno.land:8080/x/mod.ts").unwrap();
     assert_eq!(auth_tokens.get(&fixture), None);
   }
+
+  #[test]
+  fn test_auth_tokens_from_config() {
+    use crate::args::HostAuthConfig;
+
+    let mut config = AuthConfig::new();
+    config.insert(
+      "example.com".to_string(),
+      HostAuthConfig {
+        token_env: Some("EXAMPLE_TOKEN".to_string()),
+        ..Default::default()
+      },
+    );
+    config.insert(
+      "api.example.com".to_string(),
+      HostAuthConfig {
+        token_env: Some("API_KEY".to_string()),
+        header: Some("X-Api-Key".to_string()),
+        ..Default::default()
+      },
+    );
+    config.insert(
+      "git.example.com:8080".to_string(),
+      HostAuthConfig {
+        username: Some("ci".to_string()),
+        password_env: Some("GIT_PASSWORD".to_string()),
+        ..Default::default()
+      },
+    );
+    config.insert(
+      "deno.land".to_string(),
+      HostAuthConfig {
+        token_env: Some("MISSING".to_string()),
+        ..Default::default()
+      },
+    );
+    let auth_tokens = AuthTokens::from_config(&config, |name| match name {
+      "EXAMPLE_TOKEN" => Some("abc123".to_string()),
+      "API_KEY" => Some("def456".to_string()),
+      "GIT_PASSWORD" => Some("pass".to_string()),
+      _ => None,
+    });
+
+    let fixture = resolve_url("https://www.example.com/x/mod.ts").unwrap();
+    let token = auth_tokens.get(&fixture).unwrap();
+    assert_eq!(token.to_string(), "Bearer abc123");
+    assert_eq!(token.header_name(), None);
+    let fixture = resolve_url("https://api.example.com/x/mod.ts").unwrap();
+    let token = auth_tokens.get(&fixture).unwrap();
+    assert_eq!(token.to_string(), "def456");
+    assert_eq!(token.header_name(), Some("X-Api-Key"));
+    let fixture = resolve_url("http://git.example.com:8080/mod.ts").unwrap();
+    assert_eq!(
+      auth_tokens.get(&fixture).unwrap().to_string(),
+      "Basic Y2k6cGFzcw=="
+    );
+    let fixture = resolve_url("https://deno.land/x/mod.ts").unwrap();
+    assert_eq!(auth_tokens.get(&fixture), None);
+
+    // tokens from the environment take precedence
+    let mut env_tokens = AuthTokens::new(Some("xyz@example.com".to_string()));
+    env_tokens.extend(auth_tokens);
+    let fixture = resolve_url("https://example.com/x/mod.ts").unwrap();
+    assert_eq!(env_tokens.get(&fixture).unwrap().to_string(), "Bearer xyz");
+  }
 }
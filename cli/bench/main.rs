@@ -101,6 +101,15 @@ const EXEC_TIME_BENCHMARKS: &[(&str, &[&str], Option<i32>)] = &[
     ],
     None,
   ),
+  (
+    "workers_transfer",
+    &[
+      "run",
+      "--allow-read",
+      "cli/tests/testdata/workers/bench_transfer.ts",
+    ],
+    None,
+  ),
   (
     "text_decoder",
     &["run", "cli/tests/testdata/benches/text_decoder_perf.js"],
@@ -7,8 +7,12 @@ use once_cell::sync::OnceCell;
 use super::cache_db::CacheDB;
 use super::cache_db::CacheDBConfiguration;
 use super::check::TYPE_CHECK_CACHE_DB;
+use super::code_cache::CODE_CACHE_DB;
 use super::incremental::INCREMENTAL_CACHE_DB;
+use super::module_graph::MODULE_GRAPH_CACHE_DB;
 use super::node::NODE_ANALYSIS_CACHE_DB;
+use super::npm_index::NPM_INDEX_DB;
+use super::npm_snapshot::NPM_SNAPSHOT_CACHE_DB;
 use super::parsed_source::PARSED_SOURCE_CACHE_DB;
 use super::DenoDir;
 
@@ -19,6 +23,10 @@ pub struct Caches {
   dep_analysis_db: OnceCell<CacheDB>,
   node_analysis_db: OnceCell<CacheDB>,
   type_checking_cache_db: OnceCell<CacheDB>,
+  code_cache_db: OnceCell<CacheDB>,
+  npm_snapshot_db: OnceCell<CacheDB>,
+  npm_index_db: OnceCell<CacheDB>,
+  module_graph_db: OnceCell<CacheDB>,
 }
 
 impl Caches {
@@ -30,6 +38,10 @@ impl Caches {
       dep_analysis_db: Default::default(),
       node_analysis_db: Default::default(),
       type_checking_cache_db: Default::default(),
+      code_cache_db: Default::default(),
+      npm_snapshot_db: Default::default(),
+      npm_index_db: Default::default(),
+      module_graph_db: Default::default(),
     }
   }
 
@@ -37,9 +49,18 @@ impl Caches {
     cell: &OnceCell<CacheDB>,
     config: &'static CacheDBConfiguration,
     path: PathBuf,
+  ) -> CacheDB {
+    Self::make_db_with_version(cell, config, path, crate::version::deno())
+  }
+
+  fn make_db_with_version(
+    cell: &OnceCell<CacheDB>,
+    config: &'static CacheDBConfiguration,
+    path: PathBuf,
+    version: &'static str,
   ) -> CacheDB {
     cell
-      .get_or_init(|| CacheDB::from_path(config, path, crate::version::deno()))
+      .get_or_init(|| CacheDB::from_path(config, path, version))
       .clone()
   }
 
@@ -82,4 +103,38 @@ impl Caches {
       self.dir.type_checking_cache_db_file_path(),
     )
   }
+
+  pub fn code_cache_db(&self) -> CacheDB {
+    Self::make_db_with_version(
+      &self.code_cache_db,
+      &CODE_CACHE_DB,
+      self.dir.code_cache_db_file_path(),
+      // code caches can only be used by the V8 version that created them
+      deno_core::v8::V8::get_version(),
+    )
+  }
+
+  pub fn npm_snapshot_db(&self) -> CacheDB {
+    Self::make_db(
+      &self.npm_snapshot_db,
+      &NPM_SNAPSHOT_CACHE_DB,
+      self.dir.npm_snapshot_db_file_path(),
+    )
+  }
+
+  pub fn npm_index_db(&self) -> CacheDB {
+    Self::make_db(
+      &self.npm_index_db,
+      &NPM_INDEX_DB,
+      self.dir.npm_index_db_file_path(),
+    )
+  }
+
+  pub fn module_graph_db(&self) -> CacheDB {
+    Self::make_db(
+      &self.module_graph_db,
+      &MODULE_GRAPH_CACHE_DB,
+      self.dir.module_graph_db_file_path(),
+    )
+  }
 }
@@ -0,0 +1,146 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_runtime::deno_webstorage::rusqlite::params;
+
+use super::cache_db::CacheDB;
+use super::cache_db::CacheDBConfiguration;
+use super::cache_db::CacheFailure;
+
+pub static CODE_CACHE_DB: CacheDBConfiguration = CacheDBConfiguration {
+  table_initializer: "CREATE TABLE IF NOT EXISTS codecache (
+      specifier TEXT PRIMARY KEY,
+      source_hash TEXT NOT NULL,
+      data BLOB NOT NULL
+    );",
+  on_version_change: "DELETE FROM codecache;",
+  preheat_queries: &[],
+  // If the cache fails, just ignore all caching attempts
+  on_failure: CacheFailure::Blackhole,
+};
+
+/// Cache of the code caches V8 creates for modules, which allow it to skip
+/// parsing and compiling the modules on subsequent runs.
+#[derive(Clone)]
+pub struct CodeCache {
+  inner: CodeCacheInner,
+}
+
+impl CodeCache {
+  pub fn new(db: CacheDB) -> Self {
+    Self {
+      inner: CodeCacheInner::new(db),
+    }
+  }
+
+  fn ensure_ok<T: Default>(res: Result<T, AnyError>) -> T {
+    match res {
+      Ok(x) => x,
+      Err(err) => {
+        // should never error here, but if it ever does don't fail
+        if cfg!(debug_assertions) {
+          panic!("Error using code cache: {err:#}");
+        } else {
+          log::debug!("Error using code cache: {:#}", err);
+        }
+        T::default()
+      }
+    }
+  }
+
+  pub fn get_sync(
+    &self,
+    specifier: &str,
+    expected_source_hash: u64,
+  ) -> Option<Vec<u8>> {
+    Self::ensure_ok(self.inner.get(specifier, expected_source_hash))
+  }
+
+  pub fn set_sync(&self, specifier: &str, source_hash: u64, data: &[u8]) {
+    Self::ensure_ok(self.inner.set(specifier, source_hash, data))
+  }
+}
+
+#[derive(Clone)]
+struct CodeCacheInner {
+  conn: CacheDB,
+}
+
+impl CodeCacheInner {
+  pub fn new(conn: CacheDB) -> Self {
+    Self { conn }
+  }
+
+  pub fn get(
+    &self,
+    specifier: &str,
+    expected_source_hash: u64,
+  ) -> Result<Option<Vec<u8>>, AnyError> {
+    let query = "
+      SELECT
+        data
+      FROM
+        codecache
+      WHERE
+        specifier=?1
+        AND source_hash=?2
+      LIMIT 1";
+    let res = self.conn.query_row(
+      query,
+      params![specifier, &expected_source_hash.to_string()],
+      |row| {
+        let data: Vec<u8> = row.get(0)?;
+        Ok(data)
+      },
+    )?;
+    Ok(res)
+  }
+
+  pub fn set(
+    &self,
+    specifier: &str,
+    source_hash: u64,
+    data: &[u8],
+  ) -> Result<(), AnyError> {
+    let sql = "
+      INSERT OR REPLACE INTO
+        codecache (specifier, source_hash, data)
+      VALUES
+        (?1, ?2, ?3)";
+    self
+      .conn
+      .execute(sql, params![specifier, &source_hash.to_string(), data])?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn code_cache_general_use() {
+    let conn = CacheDB::in_memory(&CODE_CACHE_DB, "1.0.0");
+    let cache = CodeCacheInner::new(conn);
+
+    assert!(cache.get("file:///a.js", 1).unwrap().is_none());
+    cache.set("file:///a.js", 1, &[1, 2, 3]).unwrap();
+    assert!(cache.get("file:///a.js", 2).unwrap().is_none()); // different hash
+    assert_eq!(cache.get("file:///a.js", 1).unwrap(), Some(vec![1, 2, 3]));
+
+    // replacing with a new source
+    cache.set("file:///a.js", 2, &[4, 5]).unwrap();
+    assert!(cache.get("file:///a.js", 1).unwrap().is_none());
+    assert_eq!(cache.get("file:///a.js", 2).unwrap(), Some(vec![4, 5]));
+
+    // recreating with same v8 version should still have it
+    let conn = cache.conn.recreate_with_version("1.0.0");
+    let cache = CodeCacheInner::new(conn);
+    assert_eq!(cache.get("file:///a.js", 2).unwrap(), Some(vec![4, 5]));
+
+    // now changing the v8 version should clear it
+    let conn = cache.conn.recreate_with_version("2.0.0");
+    let cache = CodeCacheInner::new(conn);
+    assert!(cache.get("file:///a.js", 2).unwrap().is_none());
+  }
+}
@@ -12,6 +12,9 @@ pub struct DenoDir {
   /// Example: /Users/rld/.deno/
   /// Note: This is not exposed in order to encourage using re-usable methods.
   root: PathBuf,
+  /// The folder of the npm cache when it's not in the root directory, eg.
+  /// because it's on another volume.
+  npm_root: Option<PathBuf>,
   /// Used by TsCompiler to cache compiler output.
   pub gen_cache: DiskCache,
 }
@@ -38,9 +41,16 @@ impl DenoDir {
     };
     assert!(root.is_absolute());
     let gen_path = root.join("gen");
+    let npm_root = match env::var_os("DENO_NPM_DIR") {
+      Some(npm_root) if !npm_root.is_empty() => {
+        Some(std::env::current_dir()?.join(npm_root))
+      }
+      _ => None,
+    };
 
     let deno_dir = Self {
       root,
+      npm_root,
       gen_cache: DiskCache::new(&gen_path),
     };
     deno_dir.gen_cache.ensure_dir_exists(&gen_path)?;
@@ -48,6 +58,14 @@ impl DenoDir {
     Ok(deno_dir)
   }
 
+  /// The folders the caches are stored in, which have to stay accessible
+  /// when sandboxing the process.
+  pub fn cache_folder_paths(&self) -> Vec<PathBuf> {
+    let mut paths = vec![self.root.clone()];
+    paths.extend(self.npm_root.clone());
+    paths
+  }
+
   /// The root directory of the DENO_DIR for display purposes only.
   pub fn root_path_for_display(&self) -> std::path::Display {
     self.root.display()
@@ -83,6 +101,24 @@ impl DenoDir {
     self.root.join("check_cache_v1")
   }
 
+  /// Path for the V8 code cache of modules.
+  pub fn code_cache_db_file_path(&self) -> PathBuf {
+    // bump this version name to invalidate the entire cache
+    self.root.join("v8_code_cache_v1")
+  }
+
+  /// Path for caching the npm resolution snapshots of lockfiles.
+  pub fn npm_snapshot_db_file_path(&self) -> PathBuf {
+    // bump this version name to invalidate the entire cache
+    self.root.join("npm_snapshot_cache_v1")
+  }
+
+  /// Path for caching the resolutions and redirects of module graphs.
+  pub fn module_graph_db_file_path(&self) -> PathBuf {
+    // bump this version name to invalidate the entire cache
+    self.root.join("module_graph_cache_v1")
+  }
+
   /// Path to the registries cache, used for the lps.
   pub fn registries_folder_path(&self) -> PathBuf {
     self.root.join("registries")
@@ -105,8 +141,27 @@ impl DenoDir {
   }
 
   /// Folder used for the npm cache.
+  /// Can be overridden by setting `DENO_NPM_DIR` environment variable.
   pub fn npm_folder_path(&self) -> PathBuf {
-    self.root.join("npm")
+    match &self.npm_root {
+      Some(npm_root) => npm_root.clone(),
+      None => self.root.join("npm"),
+    }
+  }
+
+  /// Path for the index of the package folders of the npm cache, which is
+  /// stored along with them in case the npm cache is outside of DENO_DIR.
+  pub fn npm_index_db_file_path(&self) -> PathBuf {
+    // bump this version name to invalidate the entire cache
+    self.npm_folder_path().join(".deno_index_v1")
+  }
+
+  /// Uses the provided absolute path as the folder of the npm cache, eg. the
+  /// `npmCacheDir` of the config file.
+  pub fn with_npm_folder_path(mut self, npm_root: PathBuf) -> Self {
+    debug_assert!(npm_root.is_absolute());
+    self.npm_root = Some(npm_root);
+    self
   }
 
   /// Path used for the REPL history file.
@@ -129,6 +184,11 @@ impl DenoDir {
   }
 }
 
+/// Gets the home directory of the user.
+pub fn home_dir() -> Option<PathBuf> {
+  dirs::home_dir()
+}
+
 /// To avoid the poorly managed dirs crate
 #[cfg(not(windows))]
 mod dirs {
@@ -1,6 +1,9 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::anyhow;
@@ -12,17 +15,54 @@ use serde::Serialize;
 use super::DiskCache;
 use super::FastInsecureHasher;
 
+static SOURCE_MAP_PREFIX: &str =
+  "//# sourceMappingURL=data:application/json;base64,";
+
 #[derive(Debug, Deserialize, Serialize)]
 struct EmitMetadata {
   pub source_hash: String,
-  pub emit_hash: String,
+}
+
+/// Hit rates of the emit cache in this process.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EmitCacheStats {
+  pub hits: usize,
+  /// Hits on emits that were stored for a different module, for example an
+  /// identical dependency of another project.
+  pub shared_hits: usize,
+  pub misses: usize,
+}
+
+impl EmitCacheStats {
+  pub fn hit_rate(&self) -> f64 {
+    let total = self.hits + self.misses;
+    if total == 0 {
+      0.0
+    } else {
+      self.hits as f64 / total as f64
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+struct EmitCacheCounters {
+  hits: AtomicUsize,
+  shared_hits: AtomicUsize,
+  misses: AtomicUsize,
 }
 
 /// The cache that stores previously emitted files.
+///
+/// Emits are content addressed: they are stored in the `emit` folder keyed
+/// by the hash of the source and emit options, so identical modules (for
+/// example the same dependency used by several projects on the machine) are
+/// only transpiled once. Alongside, a small `.meta` file per module records
+/// the source hash of its last emit so the emit can be found by specifier.
 #[derive(Clone)]
 pub struct EmitCache {
   disk_cache: DiskCache,
   cli_version: &'static str,
+  counters: Arc<EmitCacheCounters>,
 }
 
 impl EmitCache {
@@ -30,56 +70,74 @@ impl EmitCache {
     Self {
       disk_cache,
       cli_version: crate::version::deno(),
+      counters: Default::default(),
     }
   }
 
   /// Gets the emitted code with embedded sourcemap from the cache.
   ///
-  /// The expected source hash is used in order to verify
-  /// that you're getting a value from the cache that is
-  /// for the provided source.
+  /// The source hash is the hash of the source and the emit options, so
+  /// an emit stored for any module with the same source is returned.
   ///
-  /// Cached emits from previous CLI releases will not be returned
-  /// or emits that do not match the source.
+  /// Cached emits from previous CLI releases will not be returned.
   pub fn get_emit_code(
     &self,
     specifier: &ModuleSpecifier,
-    expected_source_hash: u64,
+    source_hash: u64,
   ) -> Option<String> {
-    let meta_filename = self.get_meta_filename(specifier)?;
-    let emit_filename = self.get_emit_filename(specifier)?;
-
-    // load and verify the meta data file is for this source and CLI version
-    let bytes = self.disk_cache.get(&meta_filename).ok()?;
-    let meta: EmitMetadata = serde_json::from_slice(&bytes).ok()?;
-    if meta.source_hash != expected_source_hash.to_string() {
-      return None;
-    }
+    let emit_filename = self.get_emit_filename(source_hash);
+    let emit_text = match self.disk_cache.get(&emit_filename) {
+      Ok(emit_bytes) => String::from_utf8(emit_bytes).ok(),
+      Err(_) => None,
+    };
+    let emit_text = match emit_text {
+      Some(emit_text) => emit_text,
+      None => {
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+        return None;
+      }
+    };
+    self.counters.hits.fetch_add(1, Ordering::Relaxed);
 
-    // load and verify the emit is for the meta data
-    let emit_bytes = self.disk_cache.get(&emit_filename).ok()?;
-    if meta.emit_hash != compute_emit_hash(&emit_bytes, self.cli_version) {
-      return None;
+    // the inline source map points at the module the emit was stored for
+    let emit_text = match source_map_with_specifier(&emit_text, specifier) {
+      Some(emit_text) => {
+        self.counters.shared_hits.fetch_add(1, Ordering::Relaxed);
+        emit_text
+      }
+      None => emit_text,
+    };
+    if self.get_meta_source_hash(specifier) != Some(source_hash) {
+      if let Err(err) = self.set_meta(specifier, source_hash) {
+        log::debug!("Error saving emit metadata ({}): {}", specifier, err);
+      }
     }
-
-    // everything looks good, return it
-    let emit_text = String::from_utf8(emit_bytes).ok()?;
     Some(emit_text)
   }
 
-  /// Gets the filepath which stores the emit.
+  /// Gets the filepath which stores the last emit of the specifier.
   pub fn get_emit_filepath(
     &self,
     specifier: &ModuleSpecifier,
   ) -> Option<PathBuf> {
+    let source_hash = self.get_meta_source_hash(specifier)?;
     Some(
       self
         .disk_cache
         .location
-        .join(self.get_emit_filename(specifier)?),
+        .join(self.get_emit_filename(source_hash)),
     )
   }
 
+  /// Gets the hit rates of the cache in this process.
+  pub fn stats(&self) -> EmitCacheStats {
+    EmitCacheStats {
+      hits: self.counters.hits.load(Ordering::Relaxed),
+      shared_hits: self.counters.shared_hits.load(Ordering::Relaxed),
+      misses: self.counters.misses.load(Ordering::Relaxed),
+    }
+  }
+
   /// Sets the emit code in the cache.
   pub fn set_emit_code(
     &self,
@@ -102,26 +160,38 @@ impl EmitCache {
     specifier: &ModuleSpecifier,
     source_hash: u64,
     code: &str,
+  ) -> Result<(), AnyError> {
+    // save the emit source, which is written atomically so readers never
+    // see a partial emit
+    self
+      .disk_cache
+      .set(&self.get_emit_filename(source_hash), code.as_bytes())?;
+
+    // save the metadata
+    self.set_meta(specifier, source_hash)
+  }
+
+  fn get_meta_source_hash(&self, specifier: &ModuleSpecifier) -> Option<u64> {
+    let meta_filename = self.get_meta_filename(specifier)?;
+    let bytes = self.disk_cache.get(&meta_filename).ok()?;
+    let meta: EmitMetadata = serde_json::from_slice(&bytes).ok()?;
+    meta.source_hash.parse().ok()
+  }
+
+  fn set_meta(
+    &self,
+    specifier: &ModuleSpecifier,
+    source_hash: u64,
   ) -> Result<(), AnyError> {
     let meta_filename = self
       .get_meta_filename(specifier)
       .ok_or_else(|| anyhow!("Could not get meta filename."))?;
-    let emit_filename = self
-      .get_emit_filename(specifier)
-      .ok_or_else(|| anyhow!("Could not get emit filename."))?;
-
-    // save the metadata
     let metadata = EmitMetadata {
       source_hash: source_hash.to_string(),
-      emit_hash: compute_emit_hash(code.as_bytes(), self.cli_version),
     };
     self
       .disk_cache
       .set(&meta_filename, &serde_json::to_vec(&metadata)?)?;
-
-    // save the emit source
-    self.disk_cache.set(&emit_filename, code.as_bytes())?;
-
     Ok(())
   }
 
@@ -131,23 +201,42 @@ impl EmitCache {
       .get_cache_filename_with_extension(specifier, "meta")
   }
 
-  fn get_emit_filename(&self, specifier: &ModuleSpecifier) -> Option<PathBuf> {
-    self
-      .disk_cache
-      .get_cache_filename_with_extension(specifier, "js")
+  fn get_emit_filename(&self, source_hash: u64) -> PathBuf {
+    // emit should not be re-used between cli versions
+    let hash = FastInsecureHasher::new()
+      .write_u64(source_hash)
+      .write(self.cli_version.as_bytes())
+      .finish();
+    let hash = format!("{hash:016x}");
+    PathBuf::from("emit")
+      .join(&hash[..2])
+      .join(format!("{hash}.js"))
   }
 }
 
-fn compute_emit_hash(bytes: &[u8], cli_version: &str) -> String {
-  // it's ok to use an insecure hash here because
-  // if someone can change the emit source then they
-  // can also change the version hash
-  FastInsecureHasher::new()
-    .write(bytes)
-    // emit should not be re-used between cli versions
-    .write(cli_version.as_bytes())
-    .finish()
-    .to_string()
+/// Returns the code with the `sources` of its inline source map replaced
+/// by the specifier, when the emit was stored for a different module.
+fn source_map_with_specifier(
+  code: &str,
+  specifier: &ModuleSpecifier,
+) -> Option<String> {
+  let line_start = code.rfind('\n').map(|i| i + 1).unwrap_or(0);
+  let encoded = code[line_start..].strip_prefix(SOURCE_MAP_PREFIX)?;
+  let decoded = base64::decode(encoded.trim_end()).ok()?;
+  let mut source_map: serde_json::Value =
+    serde_json::from_slice(&decoded).ok()?;
+  let sources = source_map.get_mut("sources")?.as_array_mut()?;
+  if sources.len() == 1 && sources[0].as_str() == Some(specifier.as_str()) {
+    return None;
+  }
+  *sources = vec![serde_json::Value::String(specifier.to_string())];
+  let source_map = serde_json::to_vec(&source_map).ok()?;
+  Some(format!(
+    "{}{}{}",
+    &code[..line_start],
+    SOURCE_MAP_PREFIX,
+    base64::encode(source_map)
+  ))
 }
 
 #[cfg(test)]
@@ -163,6 +252,7 @@ mod test {
     let cache = EmitCache {
       disk_cache: disk_cache.clone(),
       cli_version: "1.0.0",
+      counters: Default::default(),
     };
 
     let specifier1 =
@@ -172,6 +262,7 @@ mod test {
       ModuleSpecifier::from_file_path(temp_dir.path().join("file2.ts"))
         .unwrap();
     assert_eq!(cache.get_emit_code(&specifier1, 1), None);
+    assert_eq!(cache.get_emit_filepath(&specifier1), None);
     let emit_code1 = "text1".to_string();
     let emit_code2 = "text2".to_string();
     cache.set_emit_code(&specifier1, 10, &emit_code1);
@@ -184,11 +275,16 @@ mod test {
       Some(emit_code1.clone()),
     );
     assert_eq!(cache.get_emit_code(&specifier2, 2), Some(emit_code2));
+    assert_eq!(
+      cache.get_emit_filepath(&specifier1),
+      Some(temp_dir.path().join(cache.get_emit_filename(10)))
+    );
 
     // try changing the cli version (should not load previous ones)
     let cache = EmitCache {
       disk_cache: disk_cache.clone(),
       cli_version: "2.0.0",
+      counters: Default::default(),
     };
     assert_eq!(cache.get_emit_code(&specifier1, 10), None);
     cache.set_emit_code(&specifier1, 5, &emit_code1);
@@ -197,13 +293,81 @@ mod test {
     let cache = EmitCache {
       disk_cache,
       cli_version: "2.0.0",
+      counters: Default::default(),
     };
-    assert_eq!(cache.get_emit_code(&specifier1, 5), Some(emit_code1));
+    assert_eq!(
+      cache.get_emit_code(&specifier1, 5),
+      Some(emit_code1.clone())
+    );
 
     // adding when already exists should not cause issue
     let emit_code3 = "asdf".to_string();
     cache.set_emit_code(&specifier1, 20, &emit_code3);
-    assert_eq!(cache.get_emit_code(&specifier1, 5), None);
     assert_eq!(cache.get_emit_code(&specifier1, 20), Some(emit_code3));
+    // the previous emit is still available for modules with that source
+    assert_eq!(cache.get_emit_code(&specifier2, 5), Some(emit_code1));
+    assert_eq!(
+      cache.get_emit_filepath(&specifier2),
+      Some(temp_dir.path().join(cache.get_emit_filename(5)))
+    );
+    assert_eq!(
+      cache.stats(),
+      EmitCacheStats {
+        hits: 3,
+        shared_hits: 0,
+        misses: 0,
+      }
+    );
+  }
+
+  #[test]
+  pub fn emit_cache_shared_source_map() {
+    let temp_dir = TempDir::new();
+    let cache = EmitCache {
+      disk_cache: DiskCache::new(temp_dir.path()),
+      cli_version: "1.0.0",
+      counters: Default::default(),
+    };
+    let specifier1 = ModuleSpecifier::parse("file:///a/mod.ts").unwrap();
+    let specifier2 = ModuleSpecifier::parse("file:///b/mod.ts").unwrap();
+    let source_map = |specifier: &ModuleSpecifier| {
+      format!(
+        "{}{}",
+        SOURCE_MAP_PREFIX,
+        base64::encode(format!(
+          r#"{{"version":3,"sources":["{specifier}"],"mappings":""}}"#
+        ))
+      )
+    };
+    let emit_code1 = format!("export {{}};\n{}", source_map(&specifier1));
+    cache.set_emit_code(&specifier1, 1, &emit_code1);
+
+    assert_eq!(cache.get_emit_code(&specifier1, 1), Some(emit_code1));
+    let emit_code2 = cache.get_emit_code(&specifier2, 1).unwrap();
+    let decoded = base64::decode(
+      emit_code2
+        .strip_prefix("export {};\n")
+        .unwrap()
+        .strip_prefix(SOURCE_MAP_PREFIX)
+        .unwrap(),
+    )
+    .unwrap();
+    let source_map: serde_json::Value =
+      serde_json::from_slice(&decoded).unwrap();
+    assert_eq!(
+      source_map["sources"],
+      serde_json::json!(["file:///b/mod.ts"])
+    );
+    assert_eq!(cache.get_emit_code(&specifier2, 2), None);
+    let stats = cache.stats();
+    assert_eq!(
+      stats,
+      EmitCacheStats {
+        hits: 2,
+        shared_hits: 1,
+        misses: 1,
+      }
+    );
+    assert!((stats.hit_rate() - 2.0 / 3.0).abs() < f64::EPSILON);
   }
 }
@@ -2,6 +2,7 @@
 
 use crate::errors::get_error_class_name;
 use crate::file_fetcher::FileFetcher;
+use crate::util::sync::PriorityTaskQueue;
 
 use deno_core::futures;
 use deno_core::futures::FutureExt;
@@ -11,36 +12,58 @@ use deno_graph::source::LoadFuture;
 use deno_graph::source::LoadResponse;
 use deno_graph::source::Loader;
 use deno_runtime::permissions::PermissionsContainer;
+use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 mod cache_db;
 mod caches;
 mod check;
+mod code_cache;
 mod common;
 mod deno_dir;
 mod disk_cache;
 mod emit;
 mod http_cache;
 mod incremental;
+mod module_graph;
 mod node;
+mod npm_index;
+mod npm_snapshot;
 mod parsed_source;
 
 pub use caches::Caches;
 pub use check::TypeCheckCache;
+pub use code_cache::CodeCache;
 pub use common::FastInsecureHasher;
+pub use deno_dir::home_dir;
 pub use deno_dir::DenoDir;
 pub use disk_cache::DiskCache;
 pub use emit::EmitCache;
 pub use http_cache::CachedUrlMetadata;
 pub use http_cache::HttpCache;
 pub use incremental::IncrementalCache;
+pub use module_graph::ModuleGraphCache;
+pub use module_graph::ModuleGraphResolutions;
 pub use node::NodeAnalysisCache;
+pub use npm_index::NpmCacheIndex;
+pub use npm_index::NpmIndexEntry;
+pub use npm_snapshot::NpmSnapshotCache;
 pub use parsed_source::ParsedSourceCache;
 
 /// Permissions used to save a file in the disk caches.
 pub const CACHE_PERM: u32 = 0o644;
 
+/// The maximum number of remote modules that are loaded at the same time when
+/// building module graphs.
+const MAX_CONCURRENT_REMOTE_LOADS: usize = 50;
+
+/// Queue of the remote module loads. Static imports are loaded before dynamic
+/// imports and otherwise the modules are loaded in the order they were found,
+/// which loads the shallower modules of the graph first.
+static REMOTE_LOAD_QUEUE: Lazy<PriorityTaskQueue> =
+  Lazy::new(|| PriorityTaskQueue::new(MAX_CONCURRENT_REMOTE_LOADS));
+
 /// A "wrapper" for the FileFetcher and DiskCache for the Deno CLI that provides
 /// a concise interface to the DENO_DIR when building module graphs.
 pub struct FetchCacher {
@@ -50,6 +73,7 @@ pub struct FetchCacher {
   permissions: PermissionsContainer,
   cache_info_enabled: bool,
   maybe_local_node_modules_url: Option<ModuleSpecifier>,
+  is_byonm: bool,
 }
 
 impl FetchCacher {
@@ -59,6 +83,7 @@ impl FetchCacher {
     file_header_overrides: HashMap<ModuleSpecifier, HashMap<String, String>>,
     permissions: PermissionsContainer,
     maybe_local_node_modules_url: Option<ModuleSpecifier>,
+    is_byonm: bool,
   ) -> Self {
     Self {
       emit_cache,
@@ -67,6 +92,7 @@ impl FetchCacher {
       permissions,
       cache_info_enabled: false,
       maybe_local_node_modules_url,
+      is_byonm,
     }
   }
 
@@ -102,8 +128,21 @@ impl Loader for FetchCacher {
   fn load(
     &mut self,
     specifier: &ModuleSpecifier,
-    _is_dynamic: bool,
+    is_dynamic: bool,
   ) -> LoadFuture {
+    if self.is_byonm
+      && specifier.scheme() == "file"
+      && specifier.path().contains("/node_modules/")
+    {
+      // packages in a node_modules directory not managed by deno are
+      // loaded by the node resolution at runtime
+      return Box::pin(futures::future::ready(Ok(Some(
+        LoadResponse::External {
+          specifier: specifier.clone(),
+        },
+      ))));
+    }
+
     if let Some(node_modules_url) = self.maybe_local_node_modules_url.as_ref() {
       // The specifier might be in a completely different symlinked tree than
       // what the resolved node_modules_url is in (ex. `/my-project-1/node_modules`
@@ -126,7 +165,14 @@ impl Loader for FetchCacher {
     let file_header_overrides = self.file_header_overrides.clone();
     let specifier = specifier.clone();
 
+    let is_remote = matches!(specifier.scheme(), "http" | "https" | "jsr");
+
     async move {
+      let _permit = if is_remote {
+        Some(REMOTE_LOAD_QUEUE.acquire(u8::from(is_dynamic)).await)
+      } else {
+        None
+      };
       file_fetcher
         .fetch(&specifier, permissions)
         .await
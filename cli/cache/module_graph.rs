@@ -0,0 +1,222 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
+use deno_runtime::deno_webstorage::rusqlite::params;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::cache_db::CacheDB;
+use super::cache_db::CacheDBConfiguration;
+use super::cache_db::CacheFailure;
+
+pub static MODULE_GRAPH_CACHE_DB: CacheDBConfiguration = CacheDBConfiguration {
+  table_initializer: "CREATE TABLE IF NOT EXISTS modulegraphcache (
+      roots TEXT PRIMARY KEY,
+      config_hash TEXT NOT NULL,
+      data BLOB NOT NULL
+    );",
+  on_version_change: "DELETE FROM modulegraphcache;",
+  preheat_queries: &[],
+  // If the cache fails, just ignore all caching attempts
+  on_failure: CacheFailure::Blackhole,
+};
+
+/// The resolved specifiers of the imports of the modules of a module graph
+/// and the redirects of its remote modules.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleGraphResolutions {
+  /// The resolved specifiers keyed by referrer and then by the specifier
+  /// text of the import.
+  pub resolutions: BTreeMap<ModuleSpecifier, BTreeMap<String, ModuleSpecifier>>,
+  /// The redirected specifiers mapped to the specifiers they redirect to.
+  pub redirects: BTreeMap<ModuleSpecifier, ModuleSpecifier>,
+}
+
+impl ModuleGraphResolutions {
+  pub fn get(
+    &self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+  ) -> Option<&ModuleSpecifier> {
+    self.resolutions.get(referrer)?.get(specifier)
+  }
+
+  pub fn insert(
+    &mut self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+    resolved: ModuleSpecifier,
+  ) {
+    self
+      .resolutions
+      .entry(referrer.clone())
+      .or_default()
+      .insert(specifier.to_string(), resolved);
+  }
+}
+
+/// Cache of the resolutions and redirects of the module graphs built from
+/// a set of roots, which allows building them again on subsequent runs
+/// without resolving each import or looking up each redirect.
+#[derive(Clone)]
+pub struct ModuleGraphCache {
+  inner: ModuleGraphCacheInner,
+}
+
+impl ModuleGraphCache {
+  pub fn new(db: CacheDB) -> Self {
+    Self {
+      inner: ModuleGraphCacheInner::new(db),
+    }
+  }
+
+  fn ensure_ok<T: Default>(res: Result<T, AnyError>) -> T {
+    match res {
+      Ok(x) => x,
+      Err(err) => {
+        // should never error here, but if it ever does don't fail
+        if cfg!(debug_assertions) {
+          panic!("Error using module graph cache: {err:#}");
+        } else {
+          log::debug!("Error using module graph cache: {:#}", err);
+        }
+        T::default()
+      }
+    }
+  }
+
+  pub fn get(
+    &self,
+    roots: &str,
+    expected_config_hash: u64,
+  ) -> Option<ModuleGraphResolutions> {
+    let data = Self::ensure_ok(self.inner.get(roots, expected_config_hash))?;
+    // resolutions that can't be read are treated as not being cached
+    match deserialize_resolutions(&data) {
+      Ok(resolutions) => Some(resolutions),
+      Err(err) => {
+        log::debug!("Error reading cached module graph: {:#}", err);
+        None
+      }
+    }
+  }
+
+  pub fn set(
+    &self,
+    roots: &str,
+    config_hash: u64,
+    resolutions: &ModuleGraphResolutions,
+  ) {
+    Self::ensure_ok(
+      serialize_resolutions(resolutions)
+        .and_then(|data| self.inner.set(roots, config_hash, &data)),
+    )
+  }
+}
+
+fn serialize_resolutions(
+  resolutions: &ModuleGraphResolutions,
+) -> Result<Vec<u8>, AnyError> {
+  let bytes = serde_json::to_vec(resolutions)?;
+  Ok(zstd::bulk::compress(&bytes, 3)?)
+}
+
+fn deserialize_resolutions(
+  data: &[u8],
+) -> Result<ModuleGraphResolutions, AnyError> {
+  let bytes = zstd::stream::decode_all(data)?;
+  Ok(serde_json::from_slice(&bytes)?)
+}
+
+#[derive(Clone)]
+struct ModuleGraphCacheInner {
+  conn: CacheDB,
+}
+
+impl ModuleGraphCacheInner {
+  pub fn new(conn: CacheDB) -> Self {
+    Self { conn }
+  }
+
+  pub fn get(
+    &self,
+    roots: &str,
+    expected_config_hash: u64,
+  ) -> Result<Option<Vec<u8>>, AnyError> {
+    let query = "
+      SELECT
+        data
+      FROM
+        modulegraphcache
+      WHERE
+        roots=?1
+        AND config_hash=?2
+      LIMIT 1";
+    let res = self.conn.query_row(
+      query,
+      params![roots, &expected_config_hash.to_string()],
+      |row| {
+        let data: Vec<u8> = row.get(0)?;
+        Ok(data)
+      },
+    )?;
+    Ok(res)
+  }
+
+  pub fn set(
+    &self,
+    roots: &str,
+    config_hash: u64,
+    data: &[u8],
+  ) -> Result<(), AnyError> {
+    let sql = "
+      INSERT OR REPLACE INTO
+        modulegraphcache (roots, config_hash, data)
+      VALUES
+        (?1, ?2, ?3)";
+    self
+      .conn
+      .execute(sql, params![roots, &config_hash.to_string(), data])?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn module_graph_cache_general_use() {
+    let conn = CacheDB::in_memory(&MODULE_GRAPH_CACHE_DB, "1.0.0");
+    let cache = ModuleGraphCache::new(conn);
+    let referrer = ModuleSpecifier::parse("file:///main.ts").unwrap();
+    let redirected = ModuleSpecifier::parse("https://deno.land/x/a").unwrap();
+    let target =
+      ModuleSpecifier::parse("https://deno.land/x/a@1.0.0/mod.ts").unwrap();
+    let mut resolutions = ModuleGraphResolutions::default();
+    resolutions.insert("a", &referrer, redirected.clone());
+    resolutions.redirects.insert(redirected.clone(), target);
+    assert_eq!(resolutions.get("a", &referrer), Some(&redirected));
+    assert_eq!(resolutions.get("b", &referrer), None);
+
+    assert!(cache.get("file:///main.ts", 1).is_none());
+    cache.set("file:///main.ts", 1, &resolutions);
+    assert!(cache.get("file:///main.ts", 2).is_none()); // different hash
+    assert!(cache.get("file:///other.ts", 1).is_none());
+    assert_eq!(cache.get("file:///main.ts", 1), Some(resolutions.clone()));
+
+    // a changed configuration replaces the previous resolutions
+    cache.set("file:///main.ts", 2, &resolutions);
+    assert!(cache.get("file:///main.ts", 1).is_none());
+    assert!(cache.get("file:///main.ts", 2).is_some());
+
+    // changing the cli version should clear it
+    let conn = cache.inner.conn.recreate_with_version("2.0.0");
+    let cache = ModuleGraphCache::new(conn);
+    assert!(cache.get("file:///main.ts", 2).is_none());
+  }
+}
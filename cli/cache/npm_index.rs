@@ -0,0 +1,122 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_runtime::deno_webstorage::rusqlite::params;
+
+use super::cache_db::CacheDB;
+use super::cache_db::CacheDBConfiguration;
+use super::cache_db::CacheFailure;
+
+pub static NPM_INDEX_DB: CacheDBConfiguration = CacheDBConfiguration {
+  table_initializer: "CREATE TABLE IF NOT EXISTS npmpackages (
+      folder TEXT PRIMARY KEY,
+      size INTEGER NOT NULL,
+      last_used INTEGER NOT NULL
+    );",
+  on_version_change: "DELETE FROM npmpackages;",
+  preheat_queries: &[],
+  // the index is rebuilt from the npm cache when it's empty
+  on_failure: CacheFailure::InMemory,
+};
+
+/// A package version folder of the npm cache in the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpmIndexEntry {
+  /// The path of the folder relative to the npm cache with forward slashes.
+  pub folder: String,
+  pub size: u64,
+  /// The last use in seconds since the unix epoch.
+  pub last_used: u64,
+}
+
+/// Index of the size and the last use of the package version folders of the
+/// npm cache, so that the least recently used ones can be evicted without
+/// reading every folder on each run.
+#[derive(Clone)]
+pub struct NpmCacheIndex {
+  conn: CacheDB,
+}
+
+impl NpmCacheIndex {
+  pub fn new(conn: CacheDB) -> Self {
+    Self { conn }
+  }
+
+  #[cfg(test)]
+  pub fn in_memory() -> Self {
+    Self::new(CacheDB::in_memory(&NPM_INDEX_DB, "1.0.0"))
+  }
+
+  pub fn entries(&self) -> Result<Vec<NpmIndexEntry>, AnyError> {
+    self.conn.with_connection(|conn| {
+      let mut stmt = conn
+        .prepare_cached("SELECT folder, size, last_used FROM npmpackages")?;
+      let rows = stmt.query_map([], |row| {
+        Ok(NpmIndexEntry {
+          folder: row.get(0)?,
+          size: row.get::<_, i64>(1)? as u64,
+          last_used: row.get::<_, i64>(2)? as u64,
+        })
+      })?;
+      let mut entries = Vec::new();
+      for row in rows {
+        entries.push(row?);
+      }
+      Ok(entries)
+    })
+  }
+
+  pub fn set(&self, entry: &NpmIndexEntry) -> Result<(), AnyError> {
+    let sql = "
+      INSERT OR REPLACE INTO
+        npmpackages (folder, size, last_used)
+      VALUES
+        (?1, ?2, ?3)";
+    self.conn.execute(
+      sql,
+      params![entry.folder, entry.size as i64, entry.last_used as i64],
+    )?;
+    Ok(())
+  }
+
+  pub fn remove(&self, folder: &str) -> Result<(), AnyError> {
+    let sql = "DELETE FROM npmpackages WHERE folder=?1";
+    self.conn.execute(sql, params![folder])?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn npm_index_general_use() {
+    let index = NpmCacheIndex::in_memory();
+    assert_eq!(index.entries().unwrap(), vec![]);
+
+    let entry = NpmIndexEntry {
+      folder: "registry.npmjs.org/chalk/5.0.1".to_string(),
+      size: 41_000,
+      last_used: 1_700_000_000,
+    };
+    index.set(&entry).unwrap();
+    assert_eq!(index.entries().unwrap(), vec![entry.clone()]);
+
+    let used_entry = NpmIndexEntry {
+      last_used: 1_700_000_100,
+      ..entry.clone()
+    };
+    index.set(&used_entry).unwrap();
+    assert_eq!(index.entries().unwrap(), vec![used_entry]);
+
+    index.remove(&entry.folder).unwrap();
+    assert_eq!(index.entries().unwrap(), vec![]);
+
+    // changing the cli version should clear it
+    index.set(&entry).unwrap();
+    let conn = index.conn.recreate_with_version("2.0.0");
+    let index = NpmCacheIndex::new(conn);
+    assert_eq!(index.entries().unwrap(), vec![]);
+  }
+}
@@ -0,0 +1,197 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_npm::resolution::SerializedNpmResolutionSnapshot;
+use deno_runtime::deno_webstorage::rusqlite::params;
+
+use super::cache_db::CacheDB;
+use super::cache_db::CacheDBConfiguration;
+use super::cache_db::CacheFailure;
+
+pub static NPM_SNAPSHOT_CACHE_DB: CacheDBConfiguration = CacheDBConfiguration {
+  table_initializer: "CREATE TABLE IF NOT EXISTS npmsnapshotcache (
+      lockfile TEXT PRIMARY KEY,
+      lockfile_hash TEXT NOT NULL,
+      data BLOB NOT NULL
+    );",
+  on_version_change: "DELETE FROM npmsnapshotcache;",
+  preheat_queries: &[],
+  // If the cache fails, just ignore all caching attempts
+  on_failure: CacheFailure::Blackhole,
+};
+
+/// Cache of the npm resolution snapshots resolved from the npm section of
+/// lockfiles, which allows skipping the loading of the package information
+/// of every locked package on subsequent runs.
+#[derive(Clone)]
+pub struct NpmSnapshotCache {
+  inner: NpmSnapshotCacheInner,
+}
+
+impl NpmSnapshotCache {
+  pub fn new(db: CacheDB) -> Self {
+    Self {
+      inner: NpmSnapshotCacheInner::new(db),
+    }
+  }
+
+  fn ensure_ok<T: Default>(res: Result<T, AnyError>) -> T {
+    match res {
+      Ok(x) => x,
+      Err(err) => {
+        // should never error here, but if it ever does don't fail
+        if cfg!(debug_assertions) {
+          panic!("Error using npm snapshot cache: {err:#}");
+        } else {
+          log::debug!("Error using npm snapshot cache: {:#}", err);
+        }
+        T::default()
+      }
+    }
+  }
+
+  pub fn get(
+    &self,
+    lockfile: &str,
+    expected_lockfile_hash: u64,
+  ) -> Option<SerializedNpmResolutionSnapshot> {
+    let data =
+      Self::ensure_ok(self.inner.get(lockfile, expected_lockfile_hash))?;
+    // a snapshot that can't be read is treated as not being cached
+    match deserialize_snapshot(&data) {
+      Ok(snapshot) => Some(snapshot),
+      Err(err) => {
+        log::debug!("Error reading cached npm snapshot: {:#}", err);
+        None
+      }
+    }
+  }
+
+  pub fn set(
+    &self,
+    lockfile: &str,
+    lockfile_hash: u64,
+    snapshot: &SerializedNpmResolutionSnapshot,
+  ) {
+    Self::ensure_ok(
+      serialize_snapshot(snapshot)
+        .and_then(|data| self.inner.set(lockfile, lockfile_hash, &data)),
+    )
+  }
+}
+
+fn serialize_snapshot(
+  snapshot: &SerializedNpmResolutionSnapshot,
+) -> Result<Vec<u8>, AnyError> {
+  let bytes = serde_json::to_vec(snapshot)?;
+  Ok(zstd::bulk::compress(&bytes, 3)?)
+}
+
+fn deserialize_snapshot(
+  data: &[u8],
+) -> Result<SerializedNpmResolutionSnapshot, AnyError> {
+  let bytes = zstd::stream::decode_all(data)?;
+  Ok(serde_json::from_slice(&bytes)?)
+}
+
+#[derive(Clone)]
+struct NpmSnapshotCacheInner {
+  conn: CacheDB,
+}
+
+impl NpmSnapshotCacheInner {
+  pub fn new(conn: CacheDB) -> Self {
+    Self { conn }
+  }
+
+  pub fn get(
+    &self,
+    lockfile: &str,
+    expected_lockfile_hash: u64,
+  ) -> Result<Option<Vec<u8>>, AnyError> {
+    let query = "
+      SELECT
+        data
+      FROM
+        npmsnapshotcache
+      WHERE
+        lockfile=?1
+        AND lockfile_hash=?2
+      LIMIT 1";
+    let res = self.conn.query_row(
+      query,
+      params![lockfile, &expected_lockfile_hash.to_string()],
+      |row| {
+        let data: Vec<u8> = row.get(0)?;
+        Ok(data)
+      },
+    )?;
+    Ok(res)
+  }
+
+  pub fn set(
+    &self,
+    lockfile: &str,
+    lockfile_hash: u64,
+    data: &[u8],
+  ) -> Result<(), AnyError> {
+    let sql = "
+      INSERT OR REPLACE INTO
+        npmsnapshotcache (lockfile, lockfile_hash, data)
+      VALUES
+        (?1, ?2, ?3)";
+    self
+      .conn
+      .execute(sql, params![lockfile, &lockfile_hash.to_string(), data])?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use deno_npm::resolution::SerializedNpmResolutionSnapshotPackage;
+  use deno_npm::NpmPackageId;
+  use deno_semver::npm::NpmPackageReq;
+
+  use super::*;
+
+  #[test]
+  pub fn npm_snapshot_cache_general_use() {
+    let conn = CacheDB::in_memory(&NPM_SNAPSHOT_CACHE_DB, "1.0.0");
+    let cache = NpmSnapshotCache::new(conn);
+    let pkg_id = NpmPackageId::from_serialized("chalk@5.0.0").unwrap();
+    let snapshot = SerializedNpmResolutionSnapshot {
+      root_packages: [(
+        NpmPackageReq::from_str("chalk@5").unwrap(),
+        pkg_id.clone(),
+      )]
+      .into_iter()
+      .collect(),
+      packages: vec![SerializedNpmResolutionSnapshotPackage {
+        pkg_id,
+        dist: Default::default(),
+        dependencies: Default::default(),
+      }],
+    };
+
+    assert!(cache.get("/deno.lock", 1).is_none());
+    cache.set("/deno.lock", 1, &snapshot);
+    assert!(cache.get("/deno.lock", 2).is_none()); // different hash
+    assert!(cache.get("/other/deno.lock", 1).is_none());
+    let cached = cache.get("/deno.lock", 1).unwrap();
+    assert_eq!(cached.root_packages, snapshot.root_packages);
+    assert_eq!(cached.packages.len(), 1);
+    assert_eq!(cached.packages[0].pkg_id, snapshot.packages[0].pkg_id);
+
+    // a changed lockfile replaces the previous snapshot
+    cache.set("/deno.lock", 2, &snapshot);
+    assert!(cache.get("/deno.lock", 1).is_none());
+    assert!(cache.get("/deno.lock", 2).is_some());
+
+    // changing the cli version should clear it
+    let conn = cache.inner.conn.recreate_with_version("2.0.0");
+    let cache = NpmSnapshotCache::new(conn);
+    assert!(cache.get("/deno.lock", 2).is_none());
+  }
+}
@@ -3,8 +3,10 @@
 use crate::cache::EmitCache;
 use crate::cache::FastInsecureHasher;
 use crate::cache::ParsedSourceCache;
+use crate::util::trace;
 
 use deno_core::error::AnyError;
+use deno_core::serde_json::json;
 use deno_core::ModuleCode;
 use deno_core::ModuleSpecifier;
 use deno_graph::MediaType;
@@ -67,9 +69,10 @@ impl Emitter {
   pub fn maybed_cached_emit(
     &self,
     specifier: &ModuleSpecifier,
+    media_type: MediaType,
     source: &str,
   ) -> Option<String> {
-    let source_hash = self.get_source_hash(source);
+    let source_hash = self.get_source_hash(media_type, source);
     self.emit_cache.get_emit_code(specifier, source_hash)
   }
 
@@ -79,13 +82,18 @@ impl Emitter {
     media_type: MediaType,
     source: &Arc<str>,
   ) -> Result<ModuleCode, AnyError> {
-    let source_hash = self.get_source_hash(source);
+    let source_hash = self.get_source_hash(media_type, source);
 
     if let Some(emit_code) =
       self.emit_cache.get_emit_code(specifier, source_hash)
     {
       Ok(emit_code.into())
     } else {
+      let _span = trace::span_with_args(
+        "emit",
+        "emit module",
+        || json!({ "specifier": specifier.as_str() }),
+      );
       // this will use a cached version if it exists
       let parsed_source = self.parsed_source_cache.get_or_parse_module(
         specifier,
@@ -103,13 +111,30 @@ impl Emitter {
     }
   }
 
-  /// A hashing function that takes the source code and uses the global emit
-  /// options then generates a string hash which can be stored to
-  /// determine if the cached emit is valid or not.
-  fn get_source_hash(&self, source_text: &str) -> u64 {
+  /// A hashing function that takes the source code and media type and uses
+  /// the global emit options then generates a hash which is used as the key
+  /// of the emit in the cache. It doesn't depend on the specifier, so modules
+  /// with the same source share their emit.
+  fn get_source_hash(&self, media_type: MediaType, source_text: &str) -> u64 {
     FastInsecureHasher::new()
       .write_str(source_text)
+      .write_str(media_type.as_ts_extension())
       .write_u64(self.emit_options_hash)
       .finish()
   }
 }
+
+impl Drop for Emitter {
+  fn drop(&mut self) {
+    let stats = self.emit_cache.stats();
+    if stats.hits + stats.misses > 0 {
+      log::debug!(
+        "Emit cache: {} hits ({} shared with other modules), {} misses, {:.1}% hit rate",
+        stats.hits,
+        stats.shared_hits,
+        stats.misses,
+        stats.hit_rate() * 100.0,
+      );
+    }
+  }
+}
@@ -16,6 +16,9 @@ use deno_graph::ModuleGraphError;
 use deno_graph::ResolutionError;
 use import_map::ImportMapError;
 
+use crate::args::NotCachedError;
+use crate::graph_util::GraphErrors;
+
 fn get_import_map_error_class(_: &ImportMapError) -> &'static str {
   "URIError"
 }
@@ -64,6 +67,8 @@ pub fn get_error_class_name(e: &AnyError) -> &'static str {
       e.downcast_ref::<ResolutionError>()
         .map(get_resolution_error_class)
     })
+    .or_else(|| e.downcast_ref::<GraphErrors>().map(|e| e.class()))
+    .or_else(|| e.downcast_ref::<NotCachedError>().map(|_| "NotCached"))
     .unwrap_or_else(|| {
       if cfg!(debug) {
         log::warn!(
@@ -8,11 +8,16 @@ use crate::args::Lockfile;
 use crate::args::PackageJsonDepsProvider;
 use crate::args::StorageKeyResolver;
 use crate::args::TsConfigType;
+use crate::auth_tokens::AuthTokens;
 use crate::cache::Caches;
+use crate::cache::CodeCache;
 use crate::cache::DenoDir;
 use crate::cache::EmitCache;
 use crate::cache::HttpCache;
+use crate::cache::ModuleGraphCache;
 use crate::cache::NodeAnalysisCache;
+use crate::cache::NpmCacheIndex;
+use crate::cache::NpmSnapshotCache;
 use crate::cache::ParsedSourceCache;
 use crate::emit::Emitter;
 use crate::file_fetcher::FileFetcher;
@@ -25,12 +30,15 @@ use crate::module_loader::ModuleLoadPreparer;
 use crate::module_loader::NpmModuleLoader;
 use crate::node::CliCjsEsmCodeAnalyzer;
 use crate::node::CliNodeCodeTranslator;
+use crate::npm::create_byonm_npm_fs_resolver;
 use crate::npm::create_npm_fs_resolver;
 use crate::npm::CliNpmRegistryApi;
 use crate::npm::CliNpmResolver;
 use crate::npm::NpmCache;
+use crate::npm::NpmRc;
 use crate::npm::NpmResolution;
 use crate::npm::PackageJsonDepsInstaller;
+use crate::resolver::ByonmResolver;
 use crate::resolver::CliGraphResolver;
 use crate::standalone::DenoCompileBinaryWriter;
 use crate::tools::check::TypeChecker;
@@ -41,6 +49,7 @@ use crate::watcher::FileWatcherReporter;
 use crate::worker::CliMainWorkerFactory;
 use crate::worker::CliMainWorkerOptions;
 use crate::worker::HasNodeSpecifierChecker;
+use crate::worker::ServeOptions;
 
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
@@ -131,6 +140,7 @@ impl<T> Deferred<T> {
 struct CliFactoryServices {
   dir: Deferred<DenoDir>,
   caches: Deferred<Arc<Caches>>,
+  code_cache: Deferred<Option<Arc<CodeCache>>>,
   file_fetcher: Deferred<Arc<FileFetcher>>,
   http_client: Deferred<Arc<HttpClient>>,
   emit_cache: Deferred<EmitCache>,
@@ -193,9 +203,12 @@ impl CliFactory {
       let caches = Arc::new(Caches::new(self.deno_dir()?.clone()));
       // Warm up the caches we know we'll likely need based on the CLI mode
       match self.options.sub_command() {
-        DenoSubcommand::Run(_) => {
+        DenoSubcommand::Run(_) | DenoSubcommand::Serve(_) => {
           _ = caches.dep_analysis_db();
           _ = caches.node_analysis_db();
+          if !self.options.no_code_cache() {
+            _ = caches.code_cache_db();
+          }
         }
         DenoSubcommand::Check(_) => {
           _ = caches.dep_analysis_db();
@@ -208,6 +221,17 @@ impl CliFactory {
     })
   }
 
+  pub fn code_cache(&self) -> Result<&Option<Arc<CodeCache>>, AnyError> {
+    self.services.code_cache.get_or_try_init(|| {
+      if self.options.no_code_cache() {
+        return Ok(None);
+      }
+      Ok(Some(Arc::new(CodeCache::new(
+        self.caches()?.code_cache_db(),
+      ))))
+    })
+  }
+
   pub fn blob_store(&self) -> &BlobStore {
     self.services.blob_store.get_or_init(BlobStore::default)
   }
@@ -226,25 +250,43 @@ impl CliFactory {
       .get_or_init(|| ProgressBar::new(ProgressBarStyle::TextOnly))
   }
 
-  pub fn http_client(&self) -> &Arc<HttpClient> {
-    self.services.http_client.get_or_init(|| {
-      Arc::new(HttpClient::new(
+  pub fn http_client(&self) -> Result<&Arc<HttpClient>, AnyError> {
+    self.services.http_client.get_or_try_init(|| {
+      let mut http_client = HttpClient::new(
         Some(self.root_cert_store_provider().clone()),
         self.options.unsafely_ignore_certificate_errors().clone(),
-      ))
+      );
+      http_client
+        .set_client_certificates(self.options.resolve_client_certificates()?);
+      http_client
+        .set_host_ca_certificates(self.options.resolve_host_ca_certificates()?);
+      Ok(Arc::new(http_client))
     })
   }
 
   pub fn file_fetcher(&self) -> Result<&Arc<FileFetcher>, AnyError> {
     self.services.file_fetcher.get_or_try_init(|| {
-      Ok(Arc::new(FileFetcher::new(
+      let mut file_fetcher = FileFetcher::new(
         HttpCache::new(&self.deno_dir()?.deps_folder_path()),
         self.options.cache_setting(),
         !self.options.no_remote(),
-        self.http_client().clone(),
+        self.http_client()?.clone(),
         self.blob_store().clone(),
         Some(self.text_only_progress_bar().clone()),
-      )))
+      );
+      file_fetcher.set_lockfile(self.maybe_lockfile().clone());
+      file_fetcher
+        .set_integrity_manifest(self.options.resolve_integrity_manifest()?);
+      if let Some(max_redirects) = self.options.max_redirects() {
+        file_fetcher.set_max_redirects(max_redirects);
+      }
+      if let Some(auth_config) = self.options.resolve_auth_config()? {
+        file_fetcher
+          .add_auth_tokens(AuthTokens::from_config(&auth_config, |name| {
+            std::env::var(name).ok()
+          }));
+      }
+      Ok(Arc::new(file_fetcher))
     })
   }
 
@@ -261,12 +303,22 @@ impl CliFactory {
 
   pub fn npm_cache(&self) -> Result<&Arc<NpmCache>, AnyError> {
     self.services.npm_cache.get_or_try_init(|| {
-      Ok(Arc::new(NpmCache::new(
+      let npm_cache = NpmCache::new(
         self.deno_dir()?.npm_folder_path(),
         self.options.cache_setting(),
-        self.http_client().clone(),
+        self.http_client()?.clone(),
         self.text_only_progress_bar().clone(),
-      )))
+        Arc::new(NpmRc::discover(self.options.initial_cwd())),
+        self.options.npm_download_concurrency(),
+        self.options.resolve_npm_copy_strategy()?,
+      );
+      Ok(Arc::new(match self.options.npm_cache_max_size()? {
+        Some(max_size) => npm_cache.with_size_budget(
+          max_size,
+          NpmCacheIndex::new(self.caches()?.npm_index_db()),
+        ),
+        None => npm_cache,
+      }))
     })
   }
 
@@ -275,7 +327,7 @@ impl CliFactory {
       Ok(Arc::new(CliNpmRegistryApi::new(
         CliNpmRegistryApi::default_url().to_owned(),
         self.npm_cache()?.clone(),
-        self.http_client().clone(),
+        self.http_client()?.clone(),
         self.text_only_progress_bar().clone(),
       )))
     })
@@ -291,7 +343,10 @@ impl CliFactory {
           npm_api.clone(),
           self
             .options
-            .resolve_npm_resolution_snapshot(npm_api)
+            .resolve_npm_resolution_snapshot(
+              npm_api,
+              &NpmSnapshotCache::new(self.caches()?.npm_snapshot_db()),
+            )
             .await?,
           self.maybe_lockfile().as_ref().cloned(),
         )))
@@ -306,14 +361,20 @@ impl CliFactory {
       .get_or_try_init_async(async {
         let npm_resolution = self.npm_resolution().await?;
         let fs = self.fs().clone();
-        let npm_fs_resolver = create_npm_fs_resolver(
-          fs.clone(),
-          self.npm_cache()?.clone(),
-          self.text_only_progress_bar(),
-          CliNpmRegistryApi::default_url().to_owned(),
-          npm_resolution.clone(),
-          self.options.node_modules_dir_path(),
-        );
+        let npm_fs_resolver = match self.options.byonm_node_modules_dir_path() {
+          Some(root_node_modules_path) => {
+            create_byonm_npm_fs_resolver(fs.clone(), root_node_modules_path)
+          }
+          None => create_npm_fs_resolver(
+            fs.clone(),
+            self.npm_cache()?.clone(),
+            self.text_only_progress_bar(),
+            CliNpmRegistryApi::default_url().to_owned(),
+            npm_resolution.clone(),
+            self.options.node_modules_dir_path(),
+            self.options.resolve_node_modules_layout()?,
+          ),
+        };
         Ok(Arc::new(CliNpmResolver::new(
           fs.clone(),
           npm_resolution.clone(),
@@ -374,11 +435,22 @@ impl CliFactory {
         Ok(Arc::new(CliGraphResolver::new(
           self.options.to_maybe_jsx_import_source_config(),
           self.maybe_import_map().await?.clone(),
+          self.options.resolve_module_overrides()?,
+          self.options.resolve_prefer_vendor()?,
+          self.maybe_lockfile().clone(),
           self.options.no_npm(),
           self.npm_api()?.clone(),
           self.npm_resolution().await?.clone(),
           self.package_json_deps_provider().clone(),
           self.package_json_deps_installer().await?.clone(),
+          if self.options.byonm() {
+            Some(ByonmResolver::new(
+              self.npm_resolver().await?.clone(),
+              self.node_resolver().await?.clone(),
+            ))
+          } else {
+            None
+          },
         )))
       })
       .await
@@ -448,6 +520,7 @@ impl CliFactory {
         Ok(Arc::new(NodeResolver::new(
           self.fs().clone(),
           self.npm_resolver().await?.clone(),
+          self.options.resolve_node_conditions()?,
         )))
       })
       .await
@@ -485,6 +558,7 @@ impl CliFactory {
           self.options.clone(),
           self.node_resolver().await?.clone(),
           self.npm_resolver().await?.clone(),
+          self.text_only_progress_bar().clone(),
         )))
       })
       .await
@@ -506,6 +580,7 @@ impl CliFactory {
           self.emit_cache()?.clone(),
           self.file_fetcher()?.clone(),
           self.type_checker().await?.clone(),
+          ModuleGraphCache::new(self.caches()?.module_graph_db()),
         )))
       })
       .await
@@ -553,7 +628,7 @@ impl CliFactory {
   ) -> Result<DenoCompileBinaryWriter, AnyError> {
     Ok(DenoCompileBinaryWriter::new(
       self.file_fetcher()?,
-      self.http_client(),
+      self.http_client()?,
       self.deno_dir()?,
       self.npm_api()?,
       self.npm_cache()?,
@@ -572,6 +647,8 @@ impl CliFactory {
     let graph_container = self.graph_container().clone();
     let module_load_preparer = self.module_load_preparer().await?.clone();
     let parsed_source_cache = self.parsed_source_cache()?.clone();
+    let file_fetcher = self.file_fetcher()?.clone();
+    let maybe_code_cache = self.code_cache()?.clone();
     let resolver = self.resolver().await?.clone();
     let blob_store = self.blob_store().clone();
     let cjs_resolutions = self.cjs_resolutions().clone();
@@ -596,6 +673,8 @@ impl CliFactory {
           graph_container.clone(),
           module_load_preparer.clone(),
           parsed_source_cache.clone(),
+          file_fetcher.clone(),
+          maybe_code_cache.clone(),
           resolver.clone(),
           NpmModuleLoader::new(
             cjs_resolutions.clone(),
@@ -629,6 +708,8 @@ impl CliFactory {
         self.graph_container().clone(),
         self.module_load_preparer().await?.clone(),
         self.parsed_source_cache()?.clone(),
+        self.file_fetcher()?.clone(),
+        self.code_cache()?.clone(),
         self.resolver().await?.clone(),
         NpmModuleLoader::new(
           self.cjs_resolutions().clone(),
@@ -662,6 +743,7 @@ impl CliFactory {
       is_inspecting: self.options.is_inspecting(),
       is_npm_main: self.options.is_npm_main(),
       location: self.options.location_flag().clone(),
+      node_conditions: self.options.resolve_node_conditions()?,
       maybe_binary_npm_command_name: {
         let mut maybe_binary_command_name = None;
         if let DenoSubcommand::Run(flags) = self.options.sub_command() {
@@ -675,12 +757,28 @@ impl CliFactory {
         maybe_binary_command_name
       },
       origin_data_folder_path: Some(self.deno_dir()?.origin_data_folder_path()),
+      sandbox_options: self.options.sandbox_options(self.deno_dir()?)?,
       seed: self.options.seed(),
+      heap_snapshot_on_oom: self.options.heap_snapshot_on_oom(),
+      serve_options: match self.options.sub_command() {
+        DenoSubcommand::Serve(serve_flags) => Some(ServeOptions {
+          port: serve_flags.port,
+          hostname: serve_flags.host.clone(),
+          reuse_port: serve_flags
+            .worker_count
+            .map(|count| count.get() > 1)
+            .unwrap_or(false),
+        }),
+        _ => None,
+      },
       unsafely_ignore_certificate_errors: self
         .options
         .unsafely_ignore_certificate_errors()
         .clone(),
       unstable: self.options.unstable(),
+      worker_permissions_config: Arc::new(
+        self.options.worker_permissions_config()?,
+      ),
     })
   }
 }
@@ -1,6 +1,10 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::lockfile_remote_entries;
 use crate::args::CacheSetting;
+use crate::args::IntegrityManifest;
+use crate::args::Lockfile;
+use crate::args::NotCachedError;
 use crate::auth_tokens::AuthToken;
 use crate::auth_tokens::AuthTokens;
 use crate::cache::HttpCache;
@@ -10,12 +14,16 @@ use crate::http_util::resolve_redirect_from_response;
 use crate::http_util::CacheSemantics;
 use crate::http_util::HeadersMap;
 use crate::http_util::HttpClient;
+use crate::jsr;
+use crate::jsr::JsrPackageReqReference;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::UpdateGuard;
 use crate::util::text_encoding;
+use crate::util::trace;
 
 use data_url::DataUrl;
 use deno_ast::MediaType;
+use deno_core::anyhow::Context;
 use deno_core::error::custom_error;
 use deno_core::error::generic_error;
 use deno_core::error::uri_error;
@@ -23,11 +31,14 @@ use deno_core::error::AnyError;
 use deno_core::futures;
 use deno_core::futures::future::FutureExt;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json::json;
 use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
+use deno_runtime::deno_fetch::reqwest::header::HeaderName;
 use deno_runtime::deno_fetch::reqwest::header::HeaderValue;
 use deno_runtime::deno_fetch::reqwest::header::ACCEPT;
 use deno_runtime::deno_fetch::reqwest::header::AUTHORIZATION;
+use deno_runtime::deno_fetch::reqwest::header::IF_MODIFIED_SINCE;
 use deno_runtime::deno_fetch::reqwest::header::IF_NONE_MATCH;
 use deno_runtime::deno_fetch::reqwest::StatusCode;
 use deno_runtime::deno_web::BlobStore;
@@ -44,8 +55,8 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::SystemTime;
 
-pub const SUPPORTED_SCHEMES: [&str; 5] =
-  ["data", "blob", "file", "http", "https"];
+pub const SUPPORTED_SCHEMES: [&str; 6] =
+  ["data", "blob", "file", "http", "https", "jsr"];
 
 /// A structure representing a source file.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -92,8 +103,8 @@ fn fetch_local(specifier: &ModuleSpecifier) -> Result<File, AnyError> {
   })?;
   let bytes = fs::read(&local)?;
   let charset = text_encoding::detect_charset(&bytes).to_string();
-  let source = get_source_from_bytes(bytes, Some(charset))?;
   let media_type = MediaType::from_specifier(specifier);
+  let source = get_file_source_from_bytes(bytes, Some(charset), media_type)?;
 
   Ok(File {
     local,
@@ -135,6 +146,32 @@ pub fn get_source_from_bytes(
   Ok(source)
 }
 
+/// Decodes the source of a fetched file. Files that can't be modules are
+/// only imported as assets (eg. an image imported with a `bytes` import
+/// assertion) and don't need to be valid text, so they're decoded lossily
+/// instead of failing. Their bytes are read from [`File::local`] instead.
+fn get_file_source_from_bytes(
+  bytes: Vec<u8>,
+  maybe_charset: Option<String>,
+  media_type: MediaType,
+) -> Result<String, AnyError> {
+  if !matches!(media_type, MediaType::Unknown | MediaType::Wasm) {
+    return get_source_from_bytes(bytes, maybe_charset);
+  }
+  let maybe_source = match &maybe_charset {
+    Some(charset) => text_encoding::convert_to_utf8(&bytes, charset)
+      .ok()
+      .map(|source| source.into_owned()),
+    None => std::str::from_utf8(&bytes)
+      .ok()
+      .map(|source| source.to_string()),
+  };
+  Ok(match maybe_source {
+    Some(source) => source,
+    None => String::from_utf8_lossy(&bytes).into_owned(),
+  })
+}
+
 /// Return a validated scheme for a given module specifier.
 fn get_validated_scheme(
   specifier: &ModuleSpecifier,
@@ -181,7 +218,10 @@ pub struct FileFetcher {
   http_client: Arc<HttpClient>,
   blob_store: BlobStore,
   download_log_level: log::Level,
+  maybe_integrity: Option<IntegrityManifest>,
+  maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
   progress_bar: Option<ProgressBar>,
+  redirect_limit: i64,
 }
 
 impl FileFetcher {
@@ -202,10 +242,39 @@ impl FileFetcher {
       http_client,
       blob_store,
       download_log_level: log::Level::Info,
+      maybe_integrity: None,
+      maybe_lockfile: None,
       progress_bar,
+      redirect_limit: 10,
     }
   }
 
+  /// Adds auth tokens, eg. from the `auth` section of a config file. Tokens
+  /// from `DENO_AUTH_TOKENS` take precedence.
+  pub fn add_auth_tokens(&mut self, auth_tokens: AuthTokens) {
+    self.auth_tokens.extend(auth_tokens);
+  }
+
+  /// Sets the lockfile used to pin the resolution of `jsr:` specifiers.
+  pub fn set_lockfile(&mut self, maybe_lockfile: Option<Arc<Mutex<Lockfile>>>) {
+    self.maybe_lockfile = maybe_lockfile;
+  }
+
+  /// Sets the expected hashes of remote modules, which are checked whenever
+  /// they're fetched.
+  pub fn set_integrity_manifest(
+    &mut self,
+    maybe_integrity: Option<IntegrityManifest>,
+  ) {
+    self.maybe_integrity = maybe_integrity;
+  }
+
+  /// Sets the maximum number of redirects to follow when fetching a remote
+  /// module, where zero disallows redirects.
+  pub fn set_max_redirects(&mut self, max_redirects: u32) {
+    self.redirect_limit = i64::from(max_redirects);
+  }
+
   /// Sets the log level to use when outputting the download message.
   pub fn set_download_log_level(&mut self, level: log::Level) {
     self.download_log_level = level;
@@ -228,7 +297,7 @@ impl FileFetcher {
     let maybe_content_type = headers.get("content-type");
     let (media_type, maybe_charset) =
       map_content_type(specifier, maybe_content_type);
-    let source = get_source_from_bytes(bytes, maybe_charset)?;
+    let source = get_file_source_from_bytes(bytes, maybe_charset, media_type)?;
     let maybe_types = match media_type {
       MediaType::JavaScript
       | MediaType::Cjs
@@ -257,7 +326,7 @@ impl FileFetcher {
   ) -> Result<Option<File>, AnyError> {
     debug!("FileFetcher::fetch_cached - specifier: {}", specifier);
     if redirect_limit < 0 {
-      return Err(custom_error("Http", "Too many redirects."));
+      return Err(self.too_many_redirects_error(specifier));
     }
 
     let (mut source_file, headers, _) = match self.http_cache.get(specifier) {
@@ -296,13 +365,8 @@ impl FileFetcher {
       Err(err) => return Err(err),
     }
 
-    if self.cache_setting == CacheSetting::Only {
-      return Err(custom_error(
-        "NotCached",
-        format!(
-          "Specifier not found in cache: \"{specifier}\", --cached-only is specified."
-        ),
-      ));
+    if self.cache_setting.is_cached_only() {
+      return Err(self.not_cached_error(specifier));
     }
 
     let (source, content_type) = get_source_from_data_url(specifier)?;
@@ -343,13 +407,8 @@ impl FileFetcher {
       Err(err) => return Err(err),
     }
 
-    if self.cache_setting == CacheSetting::Only {
-      return Err(custom_error(
-        "NotCached",
-        format!(
-          "Specifier not found in cache: \"{specifier}\", --cached-only is specified."
-        ),
-      ));
+    if self.cache_setting.is_cached_only() {
+      return Err(self.not_cached_error(specifier));
     }
 
     let blob = {
@@ -408,7 +467,7 @@ impl FileFetcher {
   ) -> Pin<Box<dyn Future<Output = Result<File, AnyError>> + Send>> {
     debug!("FileFetcher::fetch_remote() - specifier: {}", specifier);
     if redirect_limit < 0 {
-      return futures::future::err(custom_error("Http", "Too many redirects."))
+      return futures::future::err(self.too_many_redirects_error(specifier))
         .boxed();
     }
 
@@ -416,6 +475,15 @@ impl FileFetcher {
       return futures::future::err(err).boxed();
     }
 
+    if specifier.scheme() == "jsr" {
+      return self.fetch_jsr(
+        specifier,
+        permissions,
+        redirect_limit,
+        maybe_accept,
+      );
+    }
+
     if self.should_use_cache(specifier) {
       match self.fetch_cached(specifier, redirect_limit) {
         Ok(Some(file)) => {
@@ -428,14 +496,8 @@ impl FileFetcher {
       }
     }
 
-    if self.cache_setting == CacheSetting::Only {
-      return futures::future::err(custom_error(
-        "NotCached",
-        format!(
-          "Specifier not found in cache: \"{specifier}\", --cached-only is specified."
-        ),
-      ))
-      .boxed();
+    if self.cache_setting.is_cached_only() {
+      return futures::future::err(self.not_cached_error(specifier)).boxed();
     }
 
     let mut maybe_progress_guard = None;
@@ -450,9 +512,13 @@ impl FileFetcher {
       );
     }
 
-    let maybe_etag = match self.http_cache.get(specifier) {
-      Ok((_, headers, _)) => headers.get("etag").cloned(),
-      _ => None,
+    let (maybe_etag, maybe_last_modified) = match self.http_cache.get(specifier)
+    {
+      Ok((_, headers, _)) => (
+        headers.get("etag").cloned(),
+        headers.get("last-modified").cloned(),
+      ),
+      _ => (None, None),
     };
     let maybe_auth_token = self.auth_tokens.get(specifier);
     let specifier = specifier.clone();
@@ -460,12 +526,18 @@ impl FileFetcher {
     let file_fetcher = self.clone();
     // A single pass of fetch either yields code or yields a redirect.
     async move {
+      let _span = trace::span_with_args(
+        "cache",
+        "fetch remote module",
+        || json!({ "specifier": specifier.as_str() }),
+      );
       let result = match fetch_once(
         &client,
         FetchOnceArgs {
           url: specifier.clone(),
           maybe_accept: maybe_accept.clone(),
           maybe_etag,
+          maybe_last_modified,
           maybe_auth_token,
           maybe_progress_guard: maybe_progress_guard.as_ref(),
         },
@@ -477,6 +549,7 @@ impl FileFetcher {
           Ok(file)
         }
         FetchOnceResult::Redirect(redirect_url, headers) => {
+          file_fetcher.warn_locked_module_redirect(&specifier, &redirect_url);
           file_fetcher.http_cache.set(&specifier, headers, &[])?;
           file_fetcher
             .fetch_remote(
@@ -502,11 +575,135 @@ impl FileFetcher {
     .boxed()
   }
 
+  /// Fetches the module a `jsr:` specifier resolves to. The resolution is
+  /// pinned by the lockfile and cached as a redirect, so that it is reused on
+  /// subsequent runs and by the language server.
+  fn fetch_jsr(
+    &self,
+    specifier: &ModuleSpecifier,
+    permissions: PermissionsContainer,
+    redirect_limit: i64,
+    maybe_accept: Option<String>,
+  ) -> Pin<Box<dyn Future<Output = Result<File, AnyError>> + Send>> {
+    let specifier = specifier.clone();
+    let maybe_module_url = self
+      .maybe_lockfile
+      .as_ref()
+      .and_then(|l| jsr::get_locked_module_url(&l.lock(), &specifier))
+      .or_else(|| {
+        if !self.should_use_cache(&specifier) {
+          return None;
+        }
+        let (_, headers, _) = self.http_cache.get(&specifier).ok()?;
+        ModuleSpecifier::parse(headers.get("location")?).ok()
+      });
+    let resolve_future = match maybe_module_url {
+      Some(module_url) => futures::future::ok(module_url).boxed(),
+      None if self.cache_setting.is_cached_only() => {
+        return futures::future::err(self.not_cached_error(&specifier)).boxed();
+      }
+      None => self.resolve_jsr_specifier(&specifier, permissions.clone()),
+    };
+    let file_fetcher = self.clone();
+    async move {
+      let module_url = resolve_future.await?;
+      if let Some(lockfile) = &file_fetcher.maybe_lockfile {
+        jsr::insert_locked_module_url(
+          &mut lockfile.lock(),
+          &specifier,
+          &module_url,
+        );
+      }
+      let mut headers = HashMap::new();
+      headers.insert("location".to_string(), module_url.to_string());
+      file_fetcher.http_cache.set(&specifier, headers, &[])?;
+      // the resolution is stored like a redirect, but isn't one
+      let fetch_future = file_fetcher.fetch_remote(
+        &module_url,
+        permissions,
+        redirect_limit,
+        maybe_accept,
+      );
+      fetch_future.await
+    }
+    .boxed()
+  }
+
+  /// Resolves a `jsr:` specifier to the url of the module on the registry,
+  /// using the highest version of the package that matches the version
+  /// requirement.
+  fn resolve_jsr_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+    permissions: PermissionsContainer,
+  ) -> Pin<Box<dyn Future<Output = Result<ModuleSpecifier, AnyError>> + Send>>
+  {
+    let specifier = specifier.clone();
+    let file_fetcher = self.clone();
+    async move {
+      let req_ref = JsrPackageReqReference::from_specifier(&specifier)?;
+      let package_url = jsr::package_url(&req_ref.name)?;
+      let info_future = file_fetcher.fetch_remote(
+        &package_url.join("meta.json")?,
+        permissions.clone(),
+        file_fetcher.redirect_limit,
+        None,
+      );
+      let info = info_future.await?;
+      let version = jsr::resolve_version(&req_ref, &info.source)?;
+      let version_info_future = file_fetcher.fetch_remote(
+        &package_url.join(&format!("{version}_meta.json"))?,
+        permissions,
+        file_fetcher.redirect_limit,
+        None,
+      );
+      let version_info = version_info_future.await?;
+      let module_url =
+        jsr::resolve_module_url(&req_ref, &version, &version_info.source)?;
+      debug!("Resolved {} to {}", specifier, module_url);
+      Ok(module_url)
+    }
+    .boxed()
+  }
+
+  fn too_many_redirects_error(&self, specifier: &ModuleSpecifier) -> AnyError {
+    if self.redirect_limit == 0 {
+      custom_error(
+        "Http",
+        format!("Redirects are not allowed (--no-redirects), but a remote module redirected to \"{specifier}\"."),
+      )
+    } else {
+      custom_error("Http", "Too many redirects.")
+    }
+  }
+
+  /// Warns when a module that is in the lockfile now redirects, because the
+  /// lockfile no longer describes the source that gets loaded for it.
+  fn warn_locked_module_redirect(
+    &self,
+    specifier: &ModuleSpecifier,
+    redirect_url: &ModuleSpecifier,
+  ) {
+    let lockfile = match &self.maybe_lockfile {
+      Some(lockfile) => lockfile.lock(),
+      None => return,
+    };
+    if lockfile_remote_entries(&lockfile).contains_key(specifier.as_str()) {
+      log::warn!(
+        "{} \"{}\" is in the lock file, but now redirects to \"{}\".\n  Lock file: {}",
+        colors::yellow("Warning"),
+        specifier,
+        redirect_url,
+        lockfile.filename.display(),
+      );
+    }
+  }
+
   /// Returns if the cache should be used for a given specifier.
   fn should_use_cache(&self, specifier: &ModuleSpecifier) -> bool {
     match &self.cache_setting {
-      CacheSetting::ReloadAll => false,
-      CacheSetting::Use | CacheSetting::Only => true,
+      CacheSetting::ReloadAll | CacheSetting::Revalidate => false,
+      CacheSetting::Use | CacheSetting::Only | CacheSetting::Offline => true,
       CacheSetting::RespectHeaders => {
         if let Ok((_, headers, cache_time)) = self.http_cache.get(specifier) {
           let cache_semantics =
@@ -583,17 +780,53 @@ impl FileFetcher {
         .fetch_remote(
           specifier,
           permissions,
-          10,
+          self.redirect_limit,
           maybe_accept.map(String::from),
         )
         .await;
       if let Ok(file) = &result {
+        self.check_integrity(specifier, file)?;
         self.cache.insert(specifier.clone(), file.clone());
       }
       result
     }
   }
 
+  fn not_cached_error(&self, specifier: &ModuleSpecifier) -> AnyError {
+    if self.cache_setting == CacheSetting::Offline {
+      NotCachedError::new(specifier.to_string()).into()
+    } else {
+      custom_error(
+        "NotCached",
+        format!(
+          "Specifier not found in cache: \"{specifier}\", --cached-only is specified."
+        ),
+      )
+    }
+  }
+
+  /// Checks the contents of a remote file against the integrity manifest,
+  /// which may have an entry for the requested or the redirected specifier.
+  fn check_integrity(
+    &self,
+    specifier: &ModuleSpecifier,
+    file: &File,
+  ) -> Result<(), AnyError> {
+    let integrity = match &self.maybe_integrity {
+      Some(integrity) => integrity,
+      None => return Ok(()),
+    };
+    if !integrity.contains(specifier) && !integrity.contains(&file.specifier) {
+      return Ok(());
+    }
+    // the source may have been decoded, so check the cached bytes
+    let bytes = fs::read(&file.local).with_context(|| {
+      format!("Reading the cached contents of \"{}\"", file.specifier)
+    })?;
+    integrity.check(specifier, &bytes)?;
+    integrity.check(&file.specifier, &bytes)
+  }
+
   pub fn get_local_path(&self, specifier: &ModuleSpecifier) -> Option<PathBuf> {
     // TODO(@kitsonk) fix when deno_graph does not query cache for synthetic
     // modules
@@ -647,6 +880,7 @@ struct FetchOnceArgs<'a> {
   pub url: Url,
   pub maybe_accept: Option<String>,
   pub maybe_etag: Option<String>,
+  pub maybe_last_modified: Option<String>,
   pub maybe_auth_token: Option<AuthToken>,
   pub maybe_progress_guard: Option<&'a UpdateGuard>,
 }
@@ -666,9 +900,17 @@ async fn fetch_once<'a>(
     let if_none_match_val = HeaderValue::from_str(&etag)?;
     request = request.header(IF_NONE_MATCH, if_none_match_val);
   }
+  if let Some(last_modified) = args.maybe_last_modified {
+    let if_modified_since_val = HeaderValue::from_str(&last_modified)?;
+    request = request.header(IF_MODIFIED_SINCE, if_modified_since_val);
+  }
   if let Some(auth_token) = args.maybe_auth_token {
     let authorization_val = HeaderValue::from_str(&auth_token.to_string())?;
-    request = request.header(AUTHORIZATION, authorization_val);
+    request = match auth_token.header_name() {
+      Some(name) => request
+        .header(HeaderName::from_bytes(name.as_bytes())?, authorization_val),
+      None => request.header(AUTHORIZATION, authorization_val),
+    };
   }
   if let Some(accept) = args.maybe_accept {
     let accepts_val = HeaderValue::from_str(&accept)?;
@@ -735,6 +977,7 @@ async fn fetch_once<'a>(
 
 #[cfg(test)]
 mod tests {
+  use crate::args::integrity_for_bytes;
   use crate::cache::CachedUrlMetadata;
   use crate::http_util::HttpClient;
   use crate::version;
@@ -746,6 +989,7 @@ mod tests {
   use deno_runtime::deno_fetch::create_http_client;
   use deno_runtime::deno_web::Blob;
   use deno_runtime::deno_web::InMemoryBlobPart;
+  use std::collections::BTreeMap;
   use std::fs::read;
   use test_util::TempDir;
 
@@ -840,6 +1084,7 @@ mod tests {
       ("file:///a/b/c.ts", true, "file"),
       ("file:///C:/a/b/c.ts", true, "file"),
       ("data:,some%20text", true, "data"),
+      ("jsr:@std/path@^0.220.0/join", true, "jsr"),
       ("ftp://a/b/c.ts", false, ""),
       ("mailto:dino@deno.land", false, ""),
     ];
@@ -1469,6 +1714,30 @@ mod tests {
     assert!(result.is_err());
   }
 
+  #[tokio::test]
+  async fn test_fetcher_no_redirects() {
+    let _http_server_guard = test_util::http_server();
+    let (mut file_fetcher, _) = setup(CacheSetting::Use, None);
+    file_fetcher.set_max_redirects(0);
+    let specifier =
+      resolve_url("http://localhost:4546/subdir/redirects/redirect1.js")
+        .unwrap();
+
+    let err = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await
+      .unwrap_err();
+    assert!(err.to_string().contains("--no-redirects"));
+
+    let specifier =
+      resolve_url("http://localhost:4545/subdir/redirects/redirect1.js")
+        .unwrap();
+    let result = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_ok());
+  }
+
   #[tokio::test]
   async fn test_fetch_same_host_redirect() {
     let _http_server_guard = test_util::http_server();
@@ -1541,6 +1810,69 @@ mod tests {
     assert_eq!(err.to_string(), "A remote specifier was requested: \"http://localhost:4545/run/002_hello.ts\", but --no-remote is specified.");
   }
 
+  #[tokio::test]
+  async fn test_fetch_integrity() {
+    let (mut file_fetcher, temp_dir) = setup(CacheSetting::Only, None);
+    let specifier = resolve_url("https://deno.land/x/mod.ts").unwrap();
+    let source = b"export const a = 1;";
+    file_fetcher
+      .http_cache
+      .set(&specifier, HashMap::new(), source)
+      .unwrap();
+    let new_manifest = |integrity: String| {
+      IntegrityManifest::new(BTreeMap::from([(
+        specifier.to_string(),
+        integrity,
+      )]))
+      .unwrap()
+    };
+
+    file_fetcher
+      .set_integrity_manifest(Some(new_manifest(integrity_for_bytes(source))));
+    let result = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_ok());
+
+    // a different fetcher, as the file is cached in memory
+    let (mut file_fetcher, _temp_dir) =
+      setup(CacheSetting::Only, Some(temp_dir));
+    file_fetcher.set_integrity_manifest(Some(new_manifest(
+      integrity_for_bytes(b"export const a = 2;"),
+    )));
+    let err = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await
+      .unwrap_err();
+    assert!(err.to_string().starts_with(
+      "The integrity check of \"https://deno.land/x/mod.ts\" failed"
+    ));
+  }
+
+  #[tokio::test]
+  async fn test_fetch_offline() {
+    let (file_fetcher, _temp_dir) = setup(CacheSetting::Offline, None);
+    let specifier = resolve_url("https://deno.land/x/mod.ts").unwrap();
+    let err = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await
+      .unwrap_err();
+    assert!(err.downcast_ref::<NotCachedError>().is_some());
+    assert_eq!(
+      err.to_string(),
+      "Not cached, offline mode: \"https://deno.land/x/mod.ts\" is not in the cache and can't be downloaded."
+    );
+
+    file_fetcher
+      .http_cache
+      .set(&specifier, HashMap::new(), b"export const a = 1;")
+      .unwrap();
+    let result = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_ok());
+  }
+
   #[tokio::test]
   async fn test_fetch_cache_only() {
     let _http_server_guard = test_util::http_server();
@@ -1606,6 +1938,30 @@ mod tests {
     assert_eq!(&*file.source, r#"console.log("goodbye deno");"#);
   }
 
+  #[tokio::test]
+  async fn test_fetch_local_binary_asset() {
+    let (file_fetcher, temp_dir) = setup(CacheSetting::Use, None);
+    let bytes = vec![0x89, b'P', b'N', b'G', 0xff];
+    let fixture_path = temp_dir.path().join("image.png");
+    fs::write(&fixture_path, &bytes).unwrap();
+    let specifier = ModuleSpecifier::from_file_path(&fixture_path).unwrap();
+    let file = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await
+      .unwrap();
+    assert_eq!(&*file.source, "\u{fffd}PNG\u{fffd}");
+    assert_eq!(fs::read(&file.local).unwrap(), bytes);
+
+    // modules still need to be valid text
+    let fixture_path = temp_dir.path().join("mod.ts");
+    fs::write(&fixture_path, &bytes).unwrap();
+    let specifier = ModuleSpecifier::from_file_path(&fixture_path).unwrap();
+    let result = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_err());
+  }
+
   #[tokio::test]
   async fn test_respect_cache_revalidates() {
     let _g = test_util::http_server();
@@ -1660,6 +2016,32 @@ mod tests {
     assert_eq!(first, second);
   }
 
+  #[tokio::test]
+  async fn test_revalidate_ignores_fresh_cache() {
+    let _g = test_util::http_server();
+    let temp_dir = TempDir::new();
+    let (file_fetcher, _) = setup(CacheSetting::Use, Some(temp_dir.clone()));
+    let specifier =
+      ModuleSpecifier::parse("http://localhost:4545/dynamic_cache").unwrap();
+    let result = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_ok());
+    let file = result.unwrap();
+    let first = file.source;
+
+    let (file_fetcher, _) =
+      setup(CacheSetting::Revalidate, Some(temp_dir.clone()));
+    let result = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await;
+    assert!(result.is_ok());
+    let file = result.unwrap();
+    let second = file.source;
+
+    assert_ne!(first, second);
+  }
+
   #[tokio::test]
   async fn test_fetch_local_utf_16be() {
     let expected = String::from_utf8(
@@ -1746,7 +2128,7 @@ mod tests {
 
   fn create_test_client() -> HttpClient {
     HttpClient::from_client(
-      create_http_client("test_client", None, vec![], None, None, None)
+      create_http_client("test_client", None, vec![], None, None, None, None)
         .unwrap(),
     )
   }
@@ -1763,6 +2145,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -1791,6 +2174,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -1820,6 +2204,7 @@ mod tests {
         url: url.clone(),
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -1843,6 +2228,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: Some("33a64df551425fcc55e".to_string()),
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -1864,6 +2250,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -1895,6 +2282,7 @@ mod tests {
         url,
         maybe_accept: Some("application/json".to_string()),
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -1922,6 +2310,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -1954,6 +2343,7 @@ mod tests {
         None,
         None,
         None,
+        None,
       )
       .unwrap(),
     );
@@ -1963,6 +2353,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -1991,6 +2382,7 @@ mod tests {
         None,
         None,
         None,
+        None,
       )
       .unwrap(),
     );
@@ -2001,6 +2393,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -2046,6 +2439,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -2079,6 +2473,7 @@ mod tests {
         None,
         None,
         None,
+        None,
       )
       .unwrap(),
     );
@@ -2088,6 +2483,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -2124,6 +2520,7 @@ mod tests {
         None,
         None,
         None,
+        None,
       )
       .unwrap(),
     );
@@ -2133,6 +2530,7 @@ mod tests {
         url: url.clone(),
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -2157,6 +2555,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: Some("33a64df551425fcc55e".to_string()),
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -2186,6 +2585,7 @@ mod tests {
         None,
         None,
         None,
+        None,
       )
       .unwrap(),
     );
@@ -2195,6 +2595,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
@@ -2226,6 +2627,7 @@ mod tests {
         url,
         maybe_accept: None,
         maybe_etag: None,
+        maybe_last_modified: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
       },
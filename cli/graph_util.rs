@@ -2,26 +2,33 @@
 
 use crate::args::CliOptions;
 use crate::args::Lockfile;
+use crate::args::NotCachedError;
 use crate::args::TsTypeLib;
 use crate::args::TypeCheckMode;
 use crate::cache;
+use crate::cache::FastInsecureHasher;
+use crate::cache::ModuleGraphCache;
 use crate::cache::ParsedSourceCache;
 use crate::colors;
 use crate::errors::get_error_class_name;
 use crate::file_fetcher::FileFetcher;
 use crate::npm::CliNpmResolver;
+use crate::resolver::CachedGraphResolver;
 use crate::resolver::CliGraphResolver;
 use crate::tools::check;
 use crate::tools::check::TypeChecker;
+use crate::util::trace;
 
-use deno_core::anyhow::bail;
-use deno_core::error::custom_error;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
 use deno_core::parking_lot::RwLock;
+use deno_core::serde::Serialize;
+use deno_core::serde_json::json;
 use deno_core::ModuleSpecifier;
 use deno_core::TaskQueue;
 use deno_core::TaskQueuePermit;
+use deno_graph::source::CacheInfo;
+use deno_graph::source::LoadFuture;
 use deno_graph::source::Loader;
 use deno_graph::Module;
 use deno_graph::ModuleError;
@@ -32,8 +39,12 @@ use deno_graph::SpecifierError;
 use deno_runtime::deno_node;
 use deno_runtime::permissions::PermissionsContainer;
 use import_map::ImportMapError;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt;
 use std::sync::Arc;
 
 #[derive(Clone, Copy)]
@@ -66,6 +77,9 @@ pub fn graph_valid_with_cli_options(
 /// so. Returns `Err(_)` if there is a known module graph or resolution
 /// error statically reachable from `roots`.
 ///
+/// All the errors are collected and returned at once as [`GraphErrors`],
+/// each with the chain of imports that leads to it from the roots.
+///
 /// It is preferable to use this over using deno_graph's API directly
 /// because it will have enhanced error message information specifically
 /// for the CLI.
@@ -74,7 +88,8 @@ pub fn graph_valid(
   roots: &[ModuleSpecifier],
   options: GraphValidOptions,
 ) -> Result<(), AnyError> {
-  let mut errors = graph
+  let mut not_cached_errors = Vec::new();
+  let errors = graph
     .walk(
       roots,
       deno_graph::WalkOptions {
@@ -84,29 +99,48 @@ pub fn graph_valid(
       },
     )
     .errors()
-    .flat_map(|error| {
+    .filter_map(|error| {
+      // assets are loaded when they're imported instead of by the graph
+      if let ModuleGraphError::ModuleError(
+        ModuleError::UnsupportedImportAssertionType { kind, .. },
+      ) = &error
+      {
+        if is_asset_assert_type(kind) {
+          return None;
+        }
+      }
+      // the modules that aren't cached in offline mode are reported at once
+      if let ModuleGraphError::ModuleError(ModuleError::LoadingErr(_, _, err)) =
+        &error
+      {
+        if let Some(err) = err.downcast_ref::<NotCachedError>() {
+          not_cached_errors.push(err.clone());
+          return None;
+        }
+      }
+
       let is_root = match &error {
         ModuleGraphError::ResolutionError(_) => false,
         ModuleGraphError::ModuleError(error) => {
           roots.contains(error.specifier())
         }
       };
-      let mut message = if let ModuleGraphError::ResolutionError(err) = &error {
+      let message = if let ModuleGraphError::ResolutionError(err) = &error {
         enhanced_resolution_error_message(err)
       } else {
         format!("{error}")
       };
 
-      if let Some(range) = error.maybe_range() {
-        if !is_root && !range.specifier.as_str().contains("/$deno$eval") {
-          message.push_str(&format!(
-            "\n    at {}:{}:{}",
-            colors::cyan(range.specifier.as_str()),
-            colors::yellow(&(range.start.line + 1).to_string()),
-            colors::yellow(&(range.start.character + 1).to_string())
-          ));
-        }
-      }
+      let maybe_location = error
+        .maybe_range()
+        .filter(|range| {
+          !is_root && !range.specifier.as_str().contains("/$deno$eval")
+        })
+        .map(|range| GraphErrorLocation {
+          specifier: range.specifier.clone(),
+          line: range.start.line + 1,
+          column: range.start.character + 1,
+        });
 
       if options.is_vendoring {
         // warn about failing dynamic imports when vendoring, but don't fail completely
@@ -114,7 +148,10 @@ pub fn graph_valid(
           error,
           ModuleGraphError::ModuleError(ModuleError::MissingDynamic(_, _))
         ) {
-          log::warn!("Ignoring: {:#}", message);
+          let location = maybe_location
+            .map(|location| location.to_string())
+            .unwrap_or_default();
+          log::warn!("Ignoring: {:#}{}", message, location);
           return None;
         }
 
@@ -130,15 +167,211 @@ pub fn graph_valid(
         }
       }
 
-      Some(custom_error(get_error_class_name(&error.into()), message))
-    });
-  if let Some(error) = errors.next() {
-    Err(error)
-  } else {
+      Some(GraphError {
+        class: get_error_class_name(&error.into()),
+        message,
+        location: maybe_location,
+        import_chain: Vec::new(),
+      })
+    })
+    .collect::<Vec<_>>();
+  if let Some(err) = NotCachedError::combine(not_cached_errors) {
+    return Err(err.into());
+  }
+  if errors.is_empty() {
+    return Ok(());
+  }
+
+  let importers = get_importers(graph, roots, options.is_vendoring);
+  let errors = errors
+    .into_iter()
+    .map(|mut error| {
+      if let Some(location) = &error.location {
+        error.import_chain = get_import_chain(&importers, &location.specifier);
+      }
+      error
+    })
+    .collect();
+  Err(GraphErrors { errors }.into())
+}
+
+/// Gets if the import assertion type is one of an asset, which is loaded as
+/// is instead of as a module by deno_graph (eg.
+/// `import text from "./data.txt" assert { type: "text" }`).
+pub fn is_asset_assert_type(assert_type: &str) -> bool {
+  matches!(assert_type, "text" | "bytes")
+}
+
+/// Gets the assets imported by the modules of the graph.
+pub fn graph_assets(graph: &ModuleGraph) -> BTreeSet<ModuleSpecifier> {
+  graph
+    .modules()
+    .filter_map(|module| module.esm())
+    .flat_map(|module| module.dependencies.values())
+    .filter(|dep| {
+      dep
+        .maybe_assert_type
+        .as_deref()
+        .map(is_asset_assert_type)
+        .unwrap_or(false)
+    })
+    .filter_map(|dep| dep.maybe_code.maybe_specifier())
+    .cloned()
+    .collect()
+}
+
+/// A module graph or resolution error found by [`graph_valid`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphError {
+  /// The JavaScript class of the error (eg. `TypeError`).
+  class: &'static str,
+  message: String,
+  /// Where the failing import is. Not set for errors of the roots.
+  location: Option<GraphErrorLocation>,
+  /// The modules importing each other from one of the roots to the module
+  /// containing the failing import (inclusive).
+  import_chain: Vec<ModuleSpecifier>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct GraphErrorLocation {
+  specifier: ModuleSpecifier,
+  /// One based line number.
+  line: usize,
+  /// One based column number.
+  column: usize,
+}
+
+impl fmt::Display for GraphErrorLocation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "\n    at {}:{}:{}",
+      colors::cyan(self.specifier.as_str()),
+      colors::yellow(&self.line.to_string()),
+      colors::yellow(&self.column.to_string())
+    )
+  }
+}
+
+impl fmt::Display for GraphError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)?;
+    if let Some(location) = &self.location {
+      write!(f, "{location}")?;
+    }
+    // the last module of the chain is already in the location
+    for importer in self.import_chain.iter().rev().skip(1) {
+      write!(f, "\n    imported by {}", colors::cyan(importer.as_str()))?;
+    }
     Ok(())
   }
 }
 
+/// All the module graph and resolution errors found by [`graph_valid`].
+#[derive(Debug)]
+pub struct GraphErrors {
+  errors: Vec<GraphError>,
+}
+
+impl GraphErrors {
+  pub fn errors(&self) -> &[GraphError] {
+    &self.errors
+  }
+
+  /// The class of the first error, which is used for the whole error when
+  /// it's surfaced to JavaScript (eg. by a dynamic import).
+  pub fn class(&self) -> &'static str {
+    self.errors[0].class
+  }
+}
+
+impl std::error::Error for GraphErrors {}
+
+impl fmt::Display for GraphErrors {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, error) in self.errors.iter().enumerate() {
+      if i > 0 {
+        write!(f, "\n\n")?;
+      }
+      write!(f, "{error}")?;
+    }
+    if self.errors.len() > 1 {
+      write!(f, "\n\nFound {} errors.", self.errors.len())?;
+    }
+    Ok(())
+  }
+}
+
+/// Maps each module reachable from the roots to the module it was first
+/// imported by, walking the graph breadth first so the import chains
+/// derived from it are the shortest ones.
+fn get_importers(
+  graph: &ModuleGraph,
+  roots: &[ModuleSpecifier],
+  follow_dynamic: bool,
+) -> HashMap<ModuleSpecifier, ModuleSpecifier> {
+  let mut importers = HashMap::new();
+  let mut seen = HashSet::new();
+  let mut pending = VecDeque::new();
+  for root in roots {
+    let root = graph.resolve(root);
+    if seen.insert(root.clone()) {
+      pending.push_back(root);
+    }
+  }
+  while let Some(specifier) = pending.pop_front() {
+    let module = match graph.get(&specifier) {
+      Some(Module::Esm(module)) => module,
+      _ => continue,
+    };
+    let dependencies = module
+      .dependencies
+      .values()
+      .filter(|dep| follow_dynamic || !dep.is_dynamic)
+      .flat_map(|dep| {
+        [
+          dep.maybe_code.maybe_specifier(),
+          dep.maybe_type.maybe_specifier(),
+        ]
+      })
+      .chain(
+        module
+          .maybe_types_dependency
+          .as_ref()
+          .map(|dep| dep.dependency.maybe_specifier()),
+      )
+      .flatten();
+    for dependency in dependencies {
+      let dependency = graph.resolve(dependency);
+      if seen.insert(dependency.clone()) {
+        importers.insert(dependency.clone(), specifier.clone());
+        pending.push_back(dependency);
+      }
+    }
+  }
+  importers
+}
+
+fn get_import_chain(
+  importers: &HashMap<ModuleSpecifier, ModuleSpecifier>,
+  specifier: &ModuleSpecifier,
+) -> Vec<ModuleSpecifier> {
+  let mut chain = vec![specifier.clone()];
+  let mut current = specifier;
+  while let Some(importer) = importers.get(current) {
+    // guard against cycles, which shouldn't happen for a breadth first walk
+    if chain.contains(importer) {
+      break;
+    }
+    chain.push(importer.clone());
+    current = importer;
+  }
+  chain.reverse();
+  chain
+}
+
 /// Checks the lockfile against the graph and and exits on errors.
 pub fn graph_lock_or_exit(graph: &ModuleGraph, lockfile: &mut Lockfile) {
   for module in graph.modules() {
@@ -172,6 +405,7 @@ pub struct ModuleGraphBuilder {
   emit_cache: cache::EmitCache,
   file_fetcher: Arc<FileFetcher>,
   type_checker: Arc<TypeChecker>,
+  module_graph_cache: ModuleGraphCache,
 }
 
 impl ModuleGraphBuilder {
@@ -185,6 +419,7 @@ impl ModuleGraphBuilder {
     emit_cache: cache::EmitCache,
     file_fetcher: Arc<FileFetcher>,
     type_checker: Arc<TypeChecker>,
+    module_graph_cache: ModuleGraphCache,
   ) -> Self {
     Self {
       options,
@@ -195,6 +430,7 @@ impl ModuleGraphBuilder {
       emit_cache,
       file_fetcher,
       type_checker,
+      module_graph_cache,
     }
   }
 
@@ -296,7 +532,26 @@ impl ModuleGraphBuilder {
     loader: &mut dyn deno_graph::source::Loader,
     options: deno_graph::BuildOptions<'a>,
   ) -> Result<(), AnyError> {
-    graph.build(roots, loader, options).await;
+    {
+      let _span = trace::span_with_args("graph", "build graph", || {
+        json!({ "roots": roots })
+      });
+      match self.resolutions_cache_key(graph, &roots, &options) {
+        Some((key, config_hash)) => {
+          self
+            .build_graph_with_cached_resolutions(
+              graph,
+              roots,
+              loader,
+              options,
+              &key,
+              config_hash,
+            )
+            .await
+        }
+        None => graph.build(roots, loader, options).await,
+      }
+    }
 
     // ensure that the top level package.json is installed if a
     // specifier was matched in the package.json
@@ -312,6 +567,78 @@ impl ModuleGraphBuilder {
     Ok(())
   }
 
+  /// Gets the key and the configuration hash that the resolutions of a graph
+  /// built from the roots are cached with, which is `None` when they can't
+  /// be cached.
+  fn resolutions_cache_key(
+    &self,
+    graph: &ModuleGraph,
+    roots: &[ModuleSpecifier],
+    options: &deno_graph::BuildOptions,
+  ) -> Option<(String, u64)> {
+    // modules already in the graph aren't resolved again, and dynamic
+    // imports are loaded with the permissions of their importer, which
+    // following the cached redirects would skip checking
+    if !graph.roots.is_empty() || options.is_dynamic {
+      return None;
+    }
+    let resolutions_hash = self.resolver.resolutions_cache_hash()?;
+    let mut hasher = FastInsecureHasher::new();
+    hasher.write_u64(resolutions_hash);
+    if let Some(config_file) = self.options.maybe_config_file() {
+      hasher.write_str(config_file.specifier.as_str());
+    }
+    let key = roots
+      .iter()
+      .map(|root| root.as_str())
+      .collect::<Vec<_>>()
+      .join("\n");
+    Some((key, hasher.finish()))
+  }
+
+  /// Builds the graph resolving the imports and following the redirects
+  /// cached by a previous build of it, then caches the ones of this build.
+  async fn build_graph_with_cached_resolutions<'a>(
+    &self,
+    graph: &mut ModuleGraph,
+    roots: Vec<ModuleSpecifier>,
+    loader: &mut dyn Loader,
+    options: deno_graph::BuildOptions<'a>,
+    key: &str,
+    config_hash: u64,
+  ) {
+    let cached = self
+      .module_graph_cache
+      .get(key, config_hash)
+      .unwrap_or_default();
+    let resolver = CachedGraphResolver::new(&self.resolver, cached.clone());
+    let mut loader = CachedRedirectsLoader {
+      loader,
+      // the redirects may have changed when reloading
+      redirects: if self.options.reload_flag() {
+        Default::default()
+      } else {
+        cached.redirects.clone()
+      },
+    };
+    graph
+      .build(
+        roots,
+        &mut loader,
+        deno_graph::BuildOptions {
+          resolver: Some(&resolver),
+          ..options
+        },
+      )
+      .await;
+
+    let mut resolved = resolver.into_resolved();
+    resolved.redirects = graph.redirects.clone();
+    if resolved != cached {
+      self.module_graph_cache.set(key, config_hash, &resolved);
+    }
+  }
+
   /// Creates the default loader used for creating a graph.
   pub fn create_graph_loader(&self) -> cache::FetchCacher {
     self.create_fetch_cacher(PermissionsContainer::allow_all())
@@ -327,6 +654,7 @@ impl ModuleGraphBuilder {
       self.options.resolve_file_header_overrides(),
       permissions,
       self.options.node_modules_dir_specifier(),
+      self.options.byonm(),
     )
   }
 
@@ -339,21 +667,27 @@ impl ModuleGraphBuilder {
   }
 }
 
-pub fn error_for_any_npm_specifier(
-  graph: &ModuleGraph,
-) -> Result<(), AnyError> {
-  for module in graph.modules() {
-    match module {
-      Module::Npm(module) => {
-        bail!("npm specifiers have not yet been implemented for this subcommand (https://github.com/denoland/deno/issues/15960). Found: {}", module.specifier)
-      }
-      Module::Node(module) => {
-        bail!("Node specifiers have not yet been implemented for this subcommand (https://github.com/denoland/deno/issues/15960). Found: node:{}", module.module_name)
-      }
-      Module::Esm(_) | Module::Json(_) | Module::External(_) => {}
-    }
+/// Loader that loads the redirected modules from the specifiers they were
+/// redirected to in a previous build of the graph, which skips looking up
+/// each redirect again.
+struct CachedRedirectsLoader<'a> {
+  loader: &'a mut dyn Loader,
+  redirects: BTreeMap<ModuleSpecifier, ModuleSpecifier>,
+}
+
+impl<'a> Loader for CachedRedirectsLoader<'a> {
+  fn get_cache_info(&self, specifier: &ModuleSpecifier) -> Option<CacheInfo> {
+    self.loader.get_cache_info(specifier)
+  }
+
+  fn load(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    is_dynamic: bool,
+  ) -> LoadFuture {
+    let specifier = self.redirects.get(specifier).unwrap_or(specifier);
+    self.loader.load(specifier, is_dynamic)
   }
-  Ok(())
 }
 
 /// Adds more explanatory information to a resolution error.
@@ -420,6 +754,45 @@ impl ModuleGraphContainer {
     self.graph_data.write().graph = Default::default();
   }
 
+  /// Removes the changed modules and all the modules depending on them from
+  /// the graph and from what's type checked. The rest of the graph is kept,
+  /// so it doesn't need to be fetched, analyzed and checked again.
+  ///
+  /// Returns the invalidated modules or `None` when a changed module is not
+  /// in the graph, in which case nothing is invalidated.
+  pub fn invalidate(
+    &self,
+    changed: &[ModuleSpecifier],
+  ) -> Option<Vec<ModuleSpecifier>> {
+    let mut data = self.graph_data.write();
+    let dirty = dirty_subgraph(&data.graph, changed)?;
+    let graph = {
+      let clean_modules = data
+        .graph
+        .modules()
+        .map(|module| module.specifier())
+        .filter(|specifier| !dirty.contains(*specifier))
+        .cloned()
+        .collect::<Vec<_>>();
+      let mut graph = data.graph.segment(&clean_modules);
+      graph.roots = data
+        .graph
+        .roots
+        .iter()
+        .filter(|root| !dirty.contains(&data.graph.resolve(root)))
+        .cloned()
+        .collect();
+      graph
+    };
+    data.graph = Arc::new(graph);
+    for checked_lib_set in data.checked_libs.values_mut() {
+      checked_lib_set.retain(|specifier| !dirty.contains(specifier));
+    }
+    let mut dirty = dirty.into_iter().collect::<Vec<_>>();
+    dirty.sort();
+    Some(dirty)
+  }
+
   /// Acquires a permit to modify the module graph without other code
   /// having the chance to modify it. In the meantime, other code may
   /// still read from the existing module graph.
@@ -477,6 +850,50 @@ impl ModuleGraphContainer {
   }
 }
 
+/// Gets the changed modules along with all the modules that depend on them,
+/// directly or indirectly. Returns `None` if a changed module isn't in the
+/// graph.
+fn dirty_subgraph(
+  graph: &ModuleGraph,
+  changed: &[ModuleSpecifier],
+) -> Option<HashSet<ModuleSpecifier>> {
+  let mut dependents: HashMap<ModuleSpecifier, Vec<&ModuleSpecifier>> =
+    HashMap::new();
+  for module in graph.modules() {
+    if let Some(module) = module.esm() {
+      for dep in module.dependencies.values() {
+        for dep_specifier in
+          [dep.get_code(), dep.get_type()].into_iter().flatten()
+        {
+          dependents
+            .entry(graph.resolve(dep_specifier))
+            .or_default()
+            .push(&module.specifier);
+        }
+      }
+    }
+  }
+
+  let mut pending = Vec::with_capacity(changed.len());
+  for specifier in changed {
+    let specifier = graph.resolve(specifier);
+    graph.get(&specifier)?;
+    pending.push(specifier);
+  }
+  let mut dirty = HashSet::new();
+  while let Some(specifier) = pending.pop() {
+    if let Some(specifiers) = dependents.get(&specifier) {
+      for dependent in specifiers {
+        if !dirty.contains(*dependent) {
+          pending.push((*dependent).clone());
+        }
+      }
+    }
+    dirty.insert(specifier);
+  }
+  Some(dirty)
+}
+
 /// A permit for updating the module graph. When complete and
 /// everything looks fine, calling `.commit()` will store the
 /// new graph in the ModuleGraphContainer.
@@ -512,7 +929,9 @@ mod test {
   use deno_graph::ResolutionError;
   use deno_graph::SpecifierError;
 
+  use crate::args::TsTypeLib;
   use crate::graph_util::get_resolution_error_bare_node_specifier;
+  use crate::graph_util::ModuleGraphContainer;
 
   #[test]
   fn import_map_node_resolution_error() {
@@ -552,4 +971,66 @@ mod test {
       assert_eq!(get_resolution_error_bare_node_specifier(&err), output,);
     }
   }
+
+  #[tokio::test]
+  async fn invalidate_dirty_subgraph() {
+    let module = |specifier: &str, content: &str| {
+      (
+        specifier.to_string(),
+        deno_graph::source::Source::Module {
+          specifier: specifier.to_string(),
+          content: content.to_string(),
+          maybe_headers: None,
+        },
+      )
+    };
+    let mut loader = deno_graph::source::MemoryLoader::new(
+      vec![
+        module("file:///main.ts", "import './a.ts'; import './b.ts';"),
+        module("file:///a.ts", "import './c.ts';"),
+        module("file:///b.ts", "export {};"),
+        module("file:///c.ts", "export {};"),
+        module("file:///other.ts", "import './b.ts';"),
+      ],
+      Vec::new(),
+    );
+    let main = ModuleSpecifier::parse("file:///main.ts").unwrap();
+    let other = ModuleSpecifier::parse("file:///other.ts").unwrap();
+    let container = ModuleGraphContainer::default();
+    {
+      let mut permit = container.acquire_update_permit().await;
+      permit
+        .graph_mut()
+        .build(
+          vec![main.clone(), other.clone()],
+          &mut loader,
+          Default::default(),
+        )
+        .await;
+      permit.commit();
+    }
+    container.set_type_checked(&[main.clone()], TsTypeLib::DenoWindow);
+    container.set_type_checked(&[other.clone()], TsTypeLib::DenoWindow);
+
+    let changed = ModuleSpecifier::parse("file:///c.ts").unwrap();
+    let invalidated = container.invalidate(&[changed]).unwrap();
+    assert_eq!(
+      invalidated.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+      vec!["file:///a.ts", "file:///c.ts", "file:///main.ts"]
+    );
+    let graph = container.graph();
+    let mut modules = graph
+      .modules()
+      .map(|m| m.specifier().as_str())
+      .collect::<Vec<_>>();
+    modules.sort();
+    assert_eq!(modules, vec!["file:///b.ts", "file:///other.ts"]);
+    assert_eq!(graph.roots, vec![other.clone()]);
+    assert!(!container.is_type_checked(&[main], TsTypeLib::DenoWindow));
+    assert!(container.is_type_checked(&[other], TsTypeLib::DenoWindow));
+
+    // a module that isn't in the graph can't be invalidated
+    let unknown = ModuleSpecifier::parse("file:///unknown.ts").unwrap();
+    assert!(container.invalidate(&[unknown]).is_none());
+  }
 }
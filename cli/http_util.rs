@@ -1,4 +1,8 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use crate::args::ClientCertificate;
+use crate::args::HostCaCertificate;
+use crate::auth_tokens::AuthToken;
+use crate::colors;
 use crate::util::progress_bar::UpdateGuard;
 use crate::version::get_user_agent;
 
@@ -6,21 +10,39 @@ use cache_control::Cachability;
 use cache_control::CacheControl;
 use chrono::DateTime;
 use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
 use deno_core::error::custom_error;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::StreamExt;
+use deno_core::parking_lot::Mutex;
 use deno_core::url::Url;
 use deno_runtime::deno_fetch::create_http_client;
 use deno_runtime::deno_fetch::reqwest;
+use deno_runtime::deno_fetch::reqwest::header::HeaderMap;
+use deno_runtime::deno_fetch::reqwest::header::HeaderName;
+use deno_runtime::deno_fetch::reqwest::header::HeaderValue;
+use deno_runtime::deno_fetch::reqwest::header::AUTHORIZATION;
+use deno_runtime::deno_fetch::reqwest::header::CONTENT_RANGE;
+use deno_runtime::deno_fetch::reqwest::header::ETAG;
+use deno_runtime::deno_fetch::reqwest::header::IF_RANGE;
 use deno_runtime::deno_fetch::reqwest::header::LOCATION;
+use deno_runtime::deno_fetch::reqwest::header::RANGE;
 use deno_runtime::deno_fetch::reqwest::Response;
+use deno_runtime::deno_fetch::reqwest::StatusCode;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::SystemTime;
 
+/// How often a download is retried after it failed with a network error or
+/// a server error.
+const DOWNLOAD_RETRIES: u32 = 4;
+/// The delay before the first retry, which doubles with every retry.
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_millis(250);
+
 /// Construct the next uri based on base uri and location header fragment
 /// See <https://tools.ietf.org/html/rfc3986#section-4.2>
 fn resolve_url_from_location(base_url: &Url, location: &str) -> Url {
@@ -70,6 +92,10 @@ pub fn resolve_redirect_from_response(
 // Vec<(String, String)>
 pub type HeadersMap = HashMap<String, String>;
 
+/// The hosts of the client certificate and CA certificate a client with host
+/// specific TLS settings uses.
+type HostClientKey = (Option<String>, Option<String>);
+
 /// A structure used to determine if a entity in the http cache can be used.
 ///
 /// This is heavily influenced by
@@ -222,6 +248,10 @@ pub struct HttpClient {
   root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
   unsafely_ignore_certificate_errors: Option<Vec<String>>,
   cell: once_cell::sync::OnceCell<reqwest::Client>,
+  client_certs: Vec<ClientCertificate>,
+  host_ca_certs: Vec<HostCaCertificate>,
+  /// Clients with host specific TLS settings.
+  host_clients: Mutex<HashMap<HostClientKey, reqwest::Client>>,
 }
 
 impl std::fmt::Debug for HttpClient {
@@ -231,6 +261,8 @@ impl std::fmt::Debug for HttpClient {
         "unsafely_ignore_certificate_errors",
         &self.unsafely_ignore_certificate_errors,
       )
+      .field("client_certs", &self.client_certs)
+      .field("host_ca_certs", &self.host_ca_certs)
       .finish()
   }
 }
@@ -244,6 +276,9 @@ impl HttpClient {
       root_cert_store_provider,
       unsafely_ignore_certificate_errors,
       cell: Default::default(),
+      client_certs: Default::default(),
+      host_ca_certs: Default::default(),
+      host_clients: Default::default(),
     }
   }
 
@@ -253,25 +288,107 @@ impl HttpClient {
       root_cert_store_provider: Default::default(),
       unsafely_ignore_certificate_errors: Default::default(),
       cell: Default::default(),
+      client_certs: Default::default(),
+      host_ca_certs: Default::default(),
+      host_clients: Default::default(),
     };
     result.cell.set(client).unwrap();
     result
   }
 
+  /// Sets the client certificates presented to hosts that require mutual
+  /// TLS. The first certificate of a host takes precedence.
+  pub fn set_client_certificates(
+    &mut self,
+    mut client_certs: Vec<ClientCertificate>,
+  ) {
+    // prefer the most specific host when several of them match
+    client_certs.sort_by(|a, b| b.host.len().cmp(&a.host.len()));
+    self.client_certs = client_certs;
+  }
+
+  /// Sets the CA certificates trusted in addition to the root certificates
+  /// when connecting to a host. The first certificate of a host takes
+  /// precedence.
+  pub fn set_host_ca_certificates(
+    &mut self,
+    mut host_ca_certs: Vec<HostCaCertificate>,
+  ) {
+    // prefer the most specific host when several of them match
+    host_ca_certs.sort_by(|a, b| b.host.len().cmp(&a.host.len()));
+    self.host_ca_certs = host_ca_certs;
+  }
+
+  fn create_client(
+    &self,
+    maybe_client_cert: Option<&ClientCertificate>,
+    maybe_host_ca_cert: Option<&HostCaCertificate>,
+  ) -> Result<reqwest::Client, AnyError> {
+    let client_cert_chain_and_key = match maybe_client_cert {
+      Some(client_cert) => {
+        let read = |path: &Path| {
+          std::fs::read_to_string(path).with_context(|| {
+            format!(
+              "Failed to read client certificate for \"{}\": {}",
+              client_cert.host,
+              path.display()
+            )
+          })
+        };
+        Some((read(&client_cert.cert_file)?, read(&client_cert.key_file)?))
+      }
+      None => None,
+    };
+    let ca_certs = match maybe_host_ca_cert {
+      Some(host_ca_cert) => {
+        vec![std::fs::read(&host_ca_cert.ca_file).with_context(|| {
+          format!(
+            "Failed to read CA certificate for \"{}\": {}",
+            host_ca_cert.host,
+            host_ca_cert.ca_file.display()
+          )
+        })?]
+      }
+      None => vec![],
+    };
+    create_http_client(
+      get_user_agent(),
+      match &self.root_cert_store_provider {
+        Some(provider) => Some(provider.get_or_try_init()?.clone()),
+        None => None,
+      },
+      ca_certs,
+      None,
+      self.unsafely_ignore_certificate_errors.clone(),
+      client_cert_chain_and_key,
+      None,
+    )
+  }
+
   fn client(&self) -> Result<&reqwest::Client, AnyError> {
-    self.cell.get_or_try_init(|| {
-      create_http_client(
-        get_user_agent(),
-        match &self.root_cert_store_provider {
-          Some(provider) => Some(provider.get_or_try_init()?.clone()),
-          None => None,
-        },
-        vec![],
-        None,
-        self.unsafely_ignore_certificate_errors.clone(),
-        None,
-      )
-    })
+    self.cell.get_or_try_init(|| self.create_client(None, None))
+  }
+
+  /// Returns the client for requests to the url, which uses the TLS settings
+  /// of its host if there are any.
+  fn client_for_url(&self, url: &Url) -> Result<reqwest::Client, AnyError> {
+    let maybe_client_cert = find_for_host(&self.client_certs, url, |c| &c.host);
+    let maybe_host_ca_cert =
+      find_for_host(&self.host_ca_certs, url, |c| &c.host);
+    if maybe_client_cert.is_none() && maybe_host_ca_cert.is_none() {
+      return Ok(self.client()?.clone());
+    }
+    let key = (
+      maybe_client_cert.map(|c| c.host.clone()),
+      maybe_host_ca_cert.map(|c| c.host.clone()),
+    );
+    let mut clients = self.host_clients.lock();
+    if let Some(client) = clients.get(&key) {
+      return Ok(client.clone());
+    }
+    let client = self.create_client(maybe_client_cert, maybe_host_ca_cert)?;
+    clients.insert(key, client.clone());
+    Ok(client)
   }
 
   /// Do a GET request without following redirects.
@@ -279,7 +396,17 @@ impl HttpClient {
     &self,
     url: U,
   ) -> Result<reqwest::RequestBuilder, AnyError> {
-    Ok(self.client()?.get(url))
+    let url = url.into_url()?;
+    Ok(self.client_for_url(&url)?.get(url))
+  }
+
+  /// Do a POST request.
+  pub fn post<U: reqwest::IntoUrl>(
+    &self,
+    url: U,
+  ) -> Result<reqwest::RequestBuilder, AnyError> {
+    let url = url.into_url()?;
+    Ok(self.client_for_url(&url)?.post(url))
   }
 
   pub async fn download_text<U: reqwest::IntoUrl>(
@@ -294,7 +421,7 @@ impl HttpClient {
     &self,
     url: U,
   ) -> Result<Vec<u8>, AnyError> {
-    let maybe_bytes = self.inner_download(url, None).await?;
+    let maybe_bytes = self.inner_download(url, None, None).await?;
     match maybe_bytes {
       Some(bytes) => Ok(bytes),
       None => Err(custom_error("Http", "Not found.")),
@@ -306,15 +433,147 @@ impl HttpClient {
     url: U,
     progress_guard: &UpdateGuard,
   ) -> Result<Option<Vec<u8>>, AnyError> {
-    self.inner_download(url, Some(progress_guard)).await
+    self.inner_download(url, None, Some(progress_guard)).await
+  }
+
+  /// Downloads like `download_with_progress`, authenticating with the token
+  /// if there is one. The token is not sent to the other origins the request
+  /// is redirected to.
+  pub async fn download_with_progress_and_auth<U: reqwest::IntoUrl>(
+    &self,
+    url: U,
+    maybe_auth_token: Option<&AuthToken>,
+    progress_guard: &UpdateGuard,
+  ) -> Result<Option<Vec<u8>>, AnyError> {
+    self
+      .inner_download(url, maybe_auth_token, Some(progress_guard))
+      .await
+  }
+
+  /// Downloads like `download_with_progress_and_auth`, but retries with an
+  /// exponential backoff when the download fails with a network error or a
+  /// server error. The retries resume the download from the received bytes
+  /// with a range request when the server supports it.
+  pub async fn download_with_retries(
+    &self,
+    url: Url,
+    maybe_auth_token: Option<&AuthToken>,
+    progress_guard: &UpdateGuard,
+  ) -> Result<Option<Vec<u8>>, AnyError> {
+    let mut state = PartialDownload::default();
+    let mut delay = DOWNLOAD_RETRY_DELAY;
+    let mut retries = 0;
+    loop {
+      let result = self
+        .download_attempt(&url, maybe_auth_token, progress_guard, &mut state)
+        .await;
+      match result {
+        Ok(true) => return Ok(Some(state.data)),
+        Ok(false) => return Ok(None),
+        Err(DownloadError::Retryable(err)) if retries < DOWNLOAD_RETRIES => {
+          log::warn!(
+            "{} Downloading {} failed, retrying in {}ms. {:#}",
+            colors::yellow("Warning"),
+            url,
+            delay.as_millis(),
+            err
+          );
+          tokio::time::sleep(delay).await;
+          delay *= 2;
+          retries += 1;
+        }
+        Err(DownloadError::Retryable(err) | DownloadError::Fatal(err)) => {
+          return Err(err)
+        }
+      }
+    }
+  }
+
+  /// Downloads the rest of the partial download, returning `false` when the
+  /// url wasn't found.
+  async fn download_attempt(
+    &self,
+    url: &Url,
+    maybe_auth_token: Option<&AuthToken>,
+    progress_guard: &UpdateGuard,
+    state: &mut PartialDownload,
+  ) -> Result<bool, DownloadError> {
+    let mut headers = HeaderMap::new();
+    if !state.data.is_empty() {
+      let range = format!("bytes={}-", state.data.len());
+      headers.insert(RANGE, HeaderValue::from_str(&range).unwrap());
+      // only resume when the resource didn't change in the meantime
+      if let Some(etag) = &state.maybe_etag {
+        headers.insert(IF_RANGE, etag.clone());
+      }
+    }
+    let response = self
+      .get_redirected_response_with_headers(
+        url.clone(),
+        maybe_auth_token,
+        &headers,
+      )
+      .await
+      .map_err(DownloadError::Retryable)?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND && state.data.is_empty() {
+      return Ok(false);
+    } else if status == StatusCode::RANGE_NOT_SATISFIABLE {
+      // start over, eg. when the resource became smaller
+      state.data.clear();
+      state.maybe_etag = None;
+      return Err(DownloadError::Retryable(generic_error(format!(
+        "Bad response: {status:?}"
+      ))));
+    }
+    let resumes = status == StatusCode::PARTIAL_CONTENT
+      && content_range_start(&response) == Some(state.data.len() as u64);
+    if !resumes {
+      if !status.is_success() || status == StatusCode::PARTIAL_CONTENT {
+        let err = generic_error(format!("Bad response: {status:?}"));
+        return Err(
+          if status.is_server_error()
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::REQUEST_TIMEOUT
+          {
+            DownloadError::Retryable(err)
+          } else {
+            DownloadError::Fatal(err)
+          },
+        );
+      }
+      // the server sent the whole resource
+      state.data.clear();
+      state.maybe_etag = response.headers().get(ETAG).cloned();
+    }
+
+    if let Some(content_length) = response.content_length() {
+      progress_guard.set_total_size(state.data.len() as u64 + content_length);
+    }
+    let mut stream = response.bytes_stream();
+    while let Some(item) = stream.next().await {
+      // keep the received bytes for resuming
+      let bytes = item.map_err(|err| DownloadError::Retryable(err.into()))?;
+      state.data.extend(bytes.into_iter());
+      progress_guard.set_position(state.data.len() as u64);
+    }
+    Ok(true)
   }
 
   async fn inner_download<U: reqwest::IntoUrl>(
     &self,
     url: U,
+    maybe_auth_token: Option<&AuthToken>,
     progress_guard: Option<&UpdateGuard>,
   ) -> Result<Option<Vec<u8>>, AnyError> {
-    let response = self.get_redirected_response(url).await?;
+    let response = self
+      .get_redirected_response_with_headers(
+        url,
+        maybe_auth_token,
+        &HeaderMap::new(),
+      )
+      .await?;
 
     if response.status() == 404 {
       return Ok(None);
@@ -339,15 +598,41 @@ impl HttpClient {
   pub async fn get_redirected_response<U: reqwest::IntoUrl>(
     &self,
     url: U,
+  ) -> Result<Response, AnyError> {
+    self
+      .get_redirected_response_with_headers(url, None, &HeaderMap::new())
+      .await
+  }
+
+  async fn get_redirected_response_with_headers<U: reqwest::IntoUrl>(
+    &self,
+    url: U,
+    maybe_auth_token: Option<&AuthToken>,
+    headers: &HeaderMap,
   ) -> Result<Response, AnyError> {
     let mut url = url.into_url()?;
-    let mut response = self.get_no_redirect(url.clone())?.send().await?;
+    let origin = url.origin();
+    let get = |url: &Url| -> Result<reqwest::RequestBuilder, AnyError> {
+      let request = self.get_no_redirect(url.clone())?.headers(headers.clone());
+      match maybe_auth_token {
+        Some(auth_token) if url.origin() == origin => {
+          let value = HeaderValue::from_str(&auth_token.to_string())?;
+          Ok(match auth_token.header_name() {
+            Some(name) => {
+              request.header(HeaderName::from_bytes(name.as_bytes())?, value)
+            }
+            None => request.header(AUTHORIZATION, value),
+          })
+        }
+        _ => Ok(request),
+      }
+    };
+    let mut response = get(&url)?.send().await?;
     let status = response.status();
     if status.is_redirection() {
       for _ in 0..5 {
         let new_url = resolve_redirect_from_response(&url, &response)?;
-        let new_response =
-          self.get_no_redirect(new_url.clone())?.send().await?;
+        let new_response = get(&new_url)?.send().await?;
         let status = new_response.status();
         if status.is_redirection() {
           response = new_response;
@@ -363,6 +648,48 @@ impl HttpClient {
   }
 }
 
+/// The bytes received so far by `HttpClient::download_with_retries`.
+#[derive(Default)]
+struct PartialDownload {
+  data: Vec<u8>,
+  /// The entity tag of the resource, which ensures the received bytes and the
+  /// rest of them are of the same version of the resource.
+  maybe_etag: Option<HeaderValue>,
+}
+
+enum DownloadError {
+  /// A network error or a server error, which may not happen again.
+  Retryable(AnyError),
+  Fatal(AnyError),
+}
+
+/// Gets the first byte position of the `Content-Range` of a partial response,
+/// eg. `bytes 1000-1999/2000`.
+fn content_range_start(response: &Response) -> Option<u64> {
+  let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+  let range = value.strip_prefix("bytes ")?;
+  let (start, _) = range.split_once('-')?;
+  start.trim().parse().ok()
+}
+
+/// Finds the entry for the hostname plus port of the url, also matching the
+/// subdomains of the hosts of the entries.
+fn find_for_host<'a, T>(
+  entries: &'a [T],
+  url: &Url,
+  get_host: impl Fn(&T) -> &str,
+) -> Option<&'a T> {
+  let host = url.host_str()?.to_lowercase();
+  let host = match url.port() {
+    Some(port) => format!("{host}:{port}"),
+    None => host,
+  };
+  entries.iter().find(|entry| {
+    let entry_host = get_host(entry);
+    host == entry_host || host.ends_with(&format!(".{entry_host}"))
+  })
+}
+
 pub async fn get_response_body_with_progress(
   response: reqwest::Response,
   progress_guard: Option<&UpdateGuard>,
@@ -411,6 +738,133 @@ mod test {
     assert_eq!(err.to_string(), "Too many redirects.");
   }
 
+  #[tokio::test]
+  async fn test_http_client_concurrent_requests_to_origin() {
+    let _http_server_guard = test_util::http_server();
+    let client = HttpClient::new(None, None);
+
+    // concurrent requests to the same origin aren't serialized
+    let start = std::time::Instant::now();
+    let downloads =
+      (0..4).map(|_| client.download("http://localhost:4545/sleep/1000"));
+    for result in deno_core::futures::future::join_all(downloads).await {
+      result.unwrap();
+    }
+    assert!(start.elapsed() < std::time::Duration::from_millis(3000));
+  }
+
+  /// Serves a download that is interrupted halfway on the first request and
+  /// the rest of it on the second request, returning the second request.
+  fn serve_interrupted_download(
+    listener: std::net::TcpListener,
+    body: &'static [u8],
+  ) -> std::thread::JoinHandle<String> {
+    use std::io::Read;
+    use std::io::Write;
+    std::thread::spawn(move || {
+      let half = body.len() / 2;
+      let mut requests = Vec::new();
+      for (index, stream) in listener.incoming().take(2).enumerate() {
+        let mut stream = stream.unwrap();
+        let mut buf = [0; 4096];
+        let len = stream.read(&mut buf).unwrap();
+        requests.push(String::from_utf8_lossy(&buf[..len]).to_string());
+        if index == 0 {
+          let head = format!(
+            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\netag: \"v1\"\r\n\r\n",
+            body.len()
+          );
+          stream.write_all(head.as_bytes()).unwrap();
+          stream.write_all(&body[..half]).unwrap();
+          // closing the connection interrupts the download
+        } else {
+          let head = format!(
+            "HTTP/1.1 206 Partial Content\r\ncontent-length: {}\r\ncontent-range: bytes {}-{}/{}\r\nconnection: close\r\n\r\n",
+            body.len() - half,
+            half,
+            body.len() - 1,
+            body.len()
+          );
+          stream.write_all(head.as_bytes()).unwrap();
+          stream.write_all(&body[half..]).unwrap();
+        }
+      }
+      requests.pop().unwrap()
+    })
+  }
+
+  #[tokio::test]
+  async fn test_http_client_resumes_interrupted_download() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = Url::parse(&format!(
+      "http://{}/package.tgz",
+      listener.local_addr().unwrap()
+    ))
+    .unwrap();
+    let body: &'static [u8] = b"0123456789abcdefghij";
+    let server = serve_interrupted_download(listener, body);
+
+    let client = HttpClient::new(None, None);
+    let progress_bar = crate::util::progress_bar::ProgressBar::new(
+      crate::util::progress_bar::ProgressBarStyle::TextOnly,
+    );
+    let guard = progress_bar.update("");
+    let data = client
+      .download_with_retries(url, None, &guard)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(data, body);
+    let second_request = server.join().unwrap().to_lowercase();
+    assert!(
+      second_request.contains("range: bytes=10-"),
+      "{second_request}"
+    );
+    assert!(
+      second_request.contains("if-range: \"v1\""),
+      "{second_request}"
+    );
+  }
+
+  #[test]
+  fn test_http_client_find_for_host() {
+    let mut client = HttpClient::new(None, None);
+    let client_cert = |host: &str, name: &str| ClientCertificate {
+      host: host.to_string(),
+      cert_file: format!("/certs/{name}.pem").into(),
+      key_file: format!("/certs/{name}.key").into(),
+    };
+    client.set_client_certificates(vec![
+      client_cert("example.com", "example"),
+      client_cert("registry.example.com", "registry"),
+      client_cert("git.example.com:8443", "git"),
+    ]);
+    let find = |url: &str| {
+      find_for_host(&client.client_certs, &Url::parse(url).unwrap(), |c| {
+        &c.host
+      })
+      .map(|client_cert| client_cert.cert_file.clone())
+    };
+    assert_eq!(
+      find("https://registry.example.com/mod.ts"),
+      Some("/certs/registry.pem".into())
+    );
+    assert_eq!(
+      find("https://npm.Example.com/pkg.tgz"),
+      Some("/certs/example.pem".into())
+    );
+    assert_eq!(
+      find("https://git.example.com:8443/mod.ts"),
+      Some("/certs/git.pem".into())
+    );
+    assert_eq!(
+      find("https://git.example.com/mod.ts"),
+      Some("/certs/example.pem".into())
+    );
+    assert_eq!(find("https://badexample.com/mod.ts"), None);
+    assert_eq!(find("https://deno.land/x/mod.ts"), None);
+  }
+
   #[test]
   fn test_resolve_url_from_location_full_1() {
     let url = "http://deno.land".parse::<Url>().unwrap();
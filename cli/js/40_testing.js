@@ -2,6 +2,7 @@
 
 const core = globalThis.Deno.core;
 const ops = core.ops;
+const internals = globalThis.__bootstrap.internals;
 import { setExitHandler } from "ext:runtime/30_os.js";
 import { Console } from "ext:deno_console/01_console.js";
 import { serializePermissions } from "ext:runtime/10_permissions.js";
@@ -30,6 +31,29 @@ const {
   TypeError,
 } = primordials;
 
+// Describes the async ops that haven't completed, for the failure of a test
+// that timed out. Unlike the op sanitizer, this includes the ops started
+// before the test.
+function getPendingOpDetails() {
+  const { ops: opMetrics } = core.metrics();
+  const details = [];
+  for (const key in opMetrics) {
+    if (!ObjectHasOwn(opMetrics, key)) {
+      continue;
+    }
+    const count = opMetrics[key].opsDispatchedAsync -
+      opMetrics[key].opsCompletedAsync;
+    if (count > 0) {
+      const [name] = OP_DETAILS[key] || [key];
+      ArrayPrototypePush(
+        details,
+        `${count} async operation${count === 1 ? "" : "s"} to ${name}`,
+      );
+    }
+  }
+  return details;
+}
+
 const opSanitizerDelayResolveQueue = [];
 let hasSetOpSanitizerDelayMacrotask = false;
 
@@ -128,8 +152,8 @@ const OP_DETAILS = {
   "op_ws_close": ["close a WebSocket", "awaiting until the `close` event is emitted on a `WebSocket`, or the `WebSocketStream#closed` promise resolves"],
   "op_ws_create": ["create a WebSocket", "awaiting until the `open` event is emitted on a `WebSocket`, or the result of a `WebSocketStream#connection` promise"],
   "op_ws_next_event": ["receive the next message on a WebSocket", "closing a `WebSocket` or `WebSocketStream`"],
-  "op_ws_send_text": ["send a message on a WebSocket", "closing a `WebSocket` or `WebSocketStream`"],
-  "op_ws_send_binary": ["send a message on a WebSocket", "closing a `WebSocket` or `WebSocketStream`"],
+  "op_ws_send_text_async": ["send a message on a WebSocketStream", "closing a `WebSocketStream`"],
+  "op_ws_send_binary_async": ["send a message on a WebSocketStream", "closing a `WebSocketStream`"],
   "op_ws_send_ping": ["send a message on a WebSocket", "closing a `WebSocket` or `WebSocketStream`"],
   "op_ws_send_pong": ["send a message on a WebSocket", "closing a `WebSocket` or `WebSocketStream`"],
 };
@@ -1118,3 +1142,4 @@ function wrapTest(desc) {
 import { denoNs } from "ext:runtime/90_deno_ns.js";
 denoNs.bench = bench;
 denoNs.test = test;
+internals.getPendingOpDetails = getPendingOpDetails;
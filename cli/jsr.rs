@@ -0,0 +1,317 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_core::ModuleSpecifier;
+use deno_semver::Version;
+use deno_semver::VersionReq;
+use once_cell::sync::Lazy;
+
+use crate::args::lockfile_remote_entries;
+use crate::args::set_lockfile_remote_entries;
+use crate::args::Lockfile;
+
+static JSR_URL: Lazy<Url> = Lazy::new(|| {
+  let env_var_name = "JSR_URL";
+  if let Ok(registry_url) = std::env::var(env_var_name) {
+    // ensure there is a trailing slash for the directory
+    let registry_url = format!("{}/", registry_url.trim_end_matches('/'));
+    match Url::parse(&registry_url) {
+      Ok(url) => {
+        return url;
+      }
+      Err(err) => {
+        log::debug!("Invalid {} environment variable: {:#}", env_var_name, err);
+      }
+    }
+  }
+
+  Url::parse("https://jsr.io/").unwrap()
+});
+
+/// The url of the jsr registry, which can be overridden with the `JSR_URL`
+/// environment variable.
+pub fn jsr_url() -> &'static Url {
+  &JSR_URL
+}
+
+/// A reference to a module of a jsr package, eg.
+/// `jsr:@std/path@^0.220.0/join`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JsrPackageReqReference {
+  /// The scoped package name, eg. `@std/path`.
+  pub name: String,
+  pub version_req: Option<VersionReq>,
+  /// The export of the package, eg. `join`. `None` refers to the main
+  /// export (`.`).
+  pub sub_path: Option<String>,
+}
+
+impl JsrPackageReqReference {
+  pub fn from_specifier(specifier: &ModuleSpecifier) -> Result<Self, AnyError> {
+    Self::from_str(specifier.as_str())
+  }
+
+  #[allow(clippy::should_implement_trait)]
+  pub fn from_str(specifier: &str) -> Result<Self, AnyError> {
+    let invalid = || {
+      anyhow!("Invalid jsr specifier \"{}\". Expected a scoped package name like \"jsr:@scope/name\".", specifier)
+    };
+    let text = match specifier.strip_prefix("jsr:") {
+      Some(text) => text.trim_start_matches('/'),
+      None => return Err(invalid()),
+    };
+    let (scope, rest) = match text.strip_prefix('@') {
+      Some(text) => text.split_once('/').ok_or_else(invalid)?,
+      None => return Err(invalid()),
+    };
+    let name_end = rest.find(['@', '/']).unwrap_or(rest.len());
+    let (name, rest) = rest.split_at(name_end);
+    if scope.is_empty() || name.is_empty() {
+      return Err(invalid());
+    }
+    let (version_req, rest) = match rest.strip_prefix('@') {
+      Some(rest) => {
+        let req_end = rest.find('/').unwrap_or(rest.len());
+        let (version_req, rest) = rest.split_at(req_end);
+        let version_req = VersionReq::parse_from_specifier(version_req)
+          .with_context(|| {
+            format!("Invalid version requirement in \"{specifier}\"")
+          })?;
+        (Some(version_req), rest)
+      }
+      None => (None, rest),
+    };
+    let sub_path = rest
+      .strip_prefix('/')
+      .filter(|s| !s.is_empty())
+      .map(|s| s.to_string());
+    Ok(Self {
+      name: format!("@{scope}/{name}"),
+      version_req,
+      sub_path,
+    })
+  }
+}
+
+/// Returns the module a `jsr:` specifier was resolved to according to the
+/// lockfile, so that the resolution is stable across machines.
+pub fn get_locked_module_url(
+  lockfile: &Lockfile,
+  specifier: &ModuleSpecifier,
+) -> Option<ModuleSpecifier> {
+  if lockfile.overwrite {
+    return None;
+  }
+  let remote = lockfile_remote_entries(lockfile);
+  let url = remote.get(specifier.as_str())?;
+  ModuleSpecifier::parse(url).ok()
+}
+
+/// Records the module a `jsr:` specifier was resolved to in the lockfile.
+pub fn insert_locked_module_url(
+  lockfile: &mut Lockfile,
+  specifier: &ModuleSpecifier,
+  module_url: &ModuleSpecifier,
+) {
+  let mut remote = lockfile_remote_entries(lockfile);
+  let previous = remote.insert(specifier.to_string(), module_url.to_string());
+  if previous.as_deref() != Some(module_url.as_str()) {
+    set_lockfile_remote_entries(lockfile, remote);
+    lockfile.has_content_changed = true;
+  }
+}
+
+/// The `meta.json` of a package.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JsrPackageInfo {
+  latest: Option<String>,
+  versions: HashMap<String, JsrPackageInfoVersion>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JsrPackageInfoVersion {
+  yanked: bool,
+}
+
+/// The `<version>_meta.json` of a package.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JsrPackageVersionInfo {
+  exports: HashMap<String, String>,
+}
+
+/// The url of the directory of a package on the registry.
+pub fn package_url(name: &str) -> Result<Url, AnyError> {
+  Ok(jsr_url().join(&format!("{name}/"))?)
+}
+
+/// Resolves the version of the package to use from the text of its
+/// `meta.json`.
+pub fn resolve_version(
+  req_ref: &JsrPackageReqReference,
+  info_text: &str,
+) -> Result<Version, AnyError> {
+  let info: JsrPackageInfo = serde_json::from_str(info_text)
+    .with_context(|| format!("Invalid jsr package info of {}", req_ref.name))?;
+  match select_version(&info, req_ref.version_req.as_ref()) {
+    Some(version) => Ok(version),
+    None => bail!(
+      "Could not find a version of jsr package {} matching \"{}\". Try running with --reload={} if it was published recently.",
+      req_ref.name,
+      req_ref
+        .version_req
+        .as_ref()
+        .map(|r| r.version_text())
+        .unwrap_or("*"),
+      jsr_url().as_str().trim_end_matches('/'),
+    ),
+  }
+}
+
+/// Resolves the url of the module the reference points to from the text of
+/// the `<version>_meta.json` of the package.
+pub fn resolve_module_url(
+  req_ref: &JsrPackageReqReference,
+  version: &Version,
+  version_info_text: &str,
+) -> Result<ModuleSpecifier, AnyError> {
+  let version_info: JsrPackageVersionInfo =
+    serde_json::from_str(version_info_text).with_context(|| {
+      format!("Invalid jsr package info of {}@{}", req_ref.name, version)
+    })?;
+  let export_name = match &req_ref.sub_path {
+    Some(sub_path) => format!("./{sub_path}"),
+    None => ".".to_string(),
+  };
+  let export = match version_info.exports.get(&export_name) {
+    Some(export) => export,
+    None => bail!(
+      "jsr package {}@{} has no export named \"{}\".",
+      req_ref.name,
+      version,
+      export_name
+    ),
+  };
+  Ok(
+    package_url(&req_ref.name)?
+      .join(&format!("{version}/"))?
+      .join(export.trim_start_matches("./"))?,
+  )
+}
+
+/// Selects the highest version matching the version requirement, ignoring
+/// yanked versions. Without a requirement the latest version is used.
+fn select_version(
+  info: &JsrPackageInfo,
+  version_req: Option<&VersionReq>,
+) -> Option<Version> {
+  if version_req.is_none() {
+    if let Some(latest) = &info.latest {
+      let is_yanked =
+        info.versions.get(latest).map(|v| v.yanked).unwrap_or(false);
+      if !is_yanked {
+        if let Ok(version) = Version::parse_standard(latest) {
+          return Some(version);
+        }
+      }
+    }
+  }
+  info
+    .versions
+    .iter()
+    .filter(|(_, info)| !info.yanked)
+    .filter_map(|(version, _)| Version::parse_standard(version).ok())
+    .filter(|version| match version_req {
+      Some(version_req) => version_req.matches(version),
+      None => version.pre.is_empty(),
+    })
+    .max()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_core::serde_json::json;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn parse_req_reference() {
+    let req_ref =
+      JsrPackageReqReference::from_str("jsr:@std/path@^0.220.0/posix/join")
+        .unwrap();
+    assert_eq!(req_ref.name, "@std/path");
+    assert_eq!(req_ref.version_req.unwrap().version_text(), "^0.220.0");
+    assert_eq!(req_ref.sub_path.as_deref(), Some("posix/join"));
+
+    let req_ref = JsrPackageReqReference::from_str("jsr:/@std/path").unwrap();
+    assert_eq!(req_ref.name, "@std/path");
+    assert_eq!(req_ref.version_req, None);
+    assert_eq!(req_ref.sub_path, None);
+
+    let req_ref = JsrPackageReqReference::from_str("jsr:@std/path/").unwrap();
+    assert_eq!(req_ref.sub_path, None);
+
+    assert!(JsrPackageReqReference::from_str("jsr:path").is_err());
+    assert!(JsrPackageReqReference::from_str("jsr:@std").is_err());
+    assert!(JsrPackageReqReference::from_str("jsr:@std/").is_err());
+    assert!(JsrPackageReqReference::from_str("jsr:@std/path@%").is_err());
+    assert!(JsrPackageReqReference::from_str("npm:@std/path").is_err());
+  }
+
+  #[test]
+  fn select_package_version() {
+    let info: JsrPackageInfo = serde_json::from_value(json!({
+      "latest": "1.1.0",
+      "versions": {
+        "0.9.0": {},
+        "1.0.0": {},
+        "1.1.0": {},
+        "1.2.0": { "yanked": true },
+        "2.0.0-rc.1": {},
+      }
+    }))
+    .unwrap();
+    let select = |req: Option<&str>| {
+      let req = req.map(|r| VersionReq::parse_from_specifier(r).unwrap());
+      select_version(&info, req.as_ref()).map(|v| v.to_string())
+    };
+    assert_eq!(select(None), Some("1.1.0".to_string()));
+    assert_eq!(select(Some("^1.0.0")), Some("1.1.0".to_string()));
+    assert_eq!(select(Some("~1.0.0")), Some("1.0.0".to_string()));
+    assert_eq!(select(Some("0.9")), Some("0.9.0".to_string()));
+    assert_eq!(select(Some("2.0.0-rc.1")), Some("2.0.0-rc.1".to_string()));
+    assert_eq!(select(Some("^3")), None);
+  }
+
+  #[test]
+  fn resolve_module_url_from_exports() {
+    let version_info = r#"{
+      "exports": { ".": "./mod.ts", "./join": "./posix/join.ts" }
+    }"#;
+    let version = Version::parse_standard("0.220.1").unwrap();
+    let resolve = |specifier: &str| {
+      let req_ref = JsrPackageReqReference::from_str(specifier).unwrap();
+      resolve_module_url(&req_ref, &version, version_info)
+        .map(|url| url.to_string())
+    };
+    assert_eq!(
+      resolve("jsr:@std/path@^0.220.0").unwrap(),
+      "https://jsr.io/@std/path/0.220.1/mod.ts"
+    );
+    assert_eq!(
+      resolve("jsr:@std/path@^0.220.0/join").unwrap(),
+      "https://jsr.io/@std/path/0.220.1/posix/join.ts"
+    );
+    assert!(resolve("jsr:@std/path@^0.220.0/other").is_err());
+  }
+}
@@ -331,6 +331,11 @@ pub struct WorkspaceSettings {
   #[serde(default)]
   pub suggest: CompletionSettings,
 
+  /// A flag that indicates if declaration files should be synthesized for the
+  /// npm packages without types when caching dependencies.
+  #[serde(default)]
+  pub synth_npm_types: bool,
+
   /// Testing settings for the workspace.
   #[serde(default)]
   pub testing: TestingSettings,
@@ -364,6 +369,7 @@ impl Default for WorkspaceSettings {
       lint: true,
       document_preload_limit: default_document_preload_limit(),
       suggest: Default::default(),
+      synth_npm_types: false,
       testing: Default::default(),
       tls_certificate: None,
       unsafely_ignore_certificate_errors: None,
@@ -770,6 +776,7 @@ mod tests {
             hosts: HashMap::new(),
           }
         },
+        synth_npm_types: false,
         testing: TestingSettings {
           args: vec!["--allow-all".to_string(), "--no-check".to_string()],
           enable: true
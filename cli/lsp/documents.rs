@@ -1215,6 +1215,10 @@ impl Documents {
     let maybe_jsx_config = options
       .maybe_config_file
       .and_then(|cf| cf.to_maybe_jsx_import_source_config());
+    // invalid overrides are surfaced by the cli
+    let maybe_module_overrides = options
+      .maybe_config_file
+      .and_then(|cf| cf.to_module_overrides().ok().flatten());
     let new_resolver_config_hash = calculate_resolver_config_hash(
       &options.enabled_urls,
       options.document_preload_limit,
@@ -1232,11 +1236,15 @@ impl Documents {
     self.resolver = Arc::new(CliGraphResolver::new(
       maybe_jsx_config,
       options.maybe_import_map,
+      maybe_module_overrides,
+      None,
+      None,
       false,
       options.npm_registry_api,
       options.npm_resolution,
       deps_provider,
       deps_installer,
+      None,
     ));
     self.imports = Arc::new(
       if let Some(Ok(imports)) =
@@ -77,7 +77,10 @@ use crate::args::ConfigFile;
 use crate::args::Flags;
 use crate::args::FmtOptions;
 use crate::args::LintOptions;
+use crate::args::NodeModulesLayout;
+use crate::args::NpmCopyStrategy;
 use crate::args::TsConfig;
+use crate::auth_tokens::AuthTokens;
 use crate::cache::DenoDir;
 use crate::cache::HttpCache;
 use crate::factory::CliFactory;
@@ -86,10 +89,13 @@ use crate::graph_util;
 use crate::http_util::HttpClient;
 use crate::lsp::urls::LspUrlKind;
 use crate::npm::create_npm_fs_resolver;
+use crate::npm::synthesize_npm_types;
 use crate::npm::CliNpmRegistryApi;
 use crate::npm::CliNpmResolver;
 use crate::npm::NpmCache;
+use crate::npm::NpmRc;
 use crate::npm::NpmResolution;
+use crate::npm::DEFAULT_NPM_DOWNLOAD_CONCURRENCY;
 use crate::tools::fmt::format_file;
 use crate::tools::fmt::format_parsed_source;
 use crate::util::fs::remove_dir_all_if_exists;
@@ -192,6 +198,7 @@ impl LanguageServer {
       cli_options: CliOptions,
       roots: Vec<ModuleSpecifier>,
       open_docs: Vec<Document>,
+      synth_npm_types: bool,
     ) -> Result<(), AnyError> {
       let open_docs = open_docs
         .into_iter()
@@ -216,6 +223,11 @@ impl LanguageServer {
           check_js: false,
         },
       )?;
+      if synth_npm_types {
+        let npm_resolver = factory.npm_resolver().await?;
+        npm_resolver.resolve_pending().await?;
+        synthesize_npm_types(npm_resolver)?;
+      }
       Ok(())
     }
 
@@ -241,8 +253,15 @@ impl LanguageServer {
           let cli_options = result.cli_options;
           let roots = result.roots;
           let open_docs = result.open_docs;
+          let synth_npm_types = result.synth_npm_types;
           let handle = spawn(async move {
-            create_graph_for_caching(cli_options, roots, open_docs).await
+            create_graph_for_caching(
+              cli_options,
+              roots,
+              open_docs,
+              synth_npm_types,
+            )
+            .await
           });
           if let Err(err) = handle.await.unwrap() {
             self
@@ -450,6 +469,13 @@ fn create_lsp_structs(
     CacheSetting::Only,
     http_client.clone(),
     progress_bar.clone(),
+    Arc::new(
+      std::env::current_dir()
+        .map(|cwd| NpmRc::discover(&cwd))
+        .unwrap_or_default(),
+    ),
+    DEFAULT_NPM_DOWNLOAD_CONCURRENCY,
+    NpmCopyStrategy::default(),
   ));
   let api = Arc::new(CliNpmRegistryApi::new(
     registry_url.clone(),
@@ -467,6 +493,7 @@ fn create_lsp_structs(
     registry_url.clone(),
     resolution.clone(),
     None,
+    NodeModulesLayout::default(),
   );
   (
     api,
@@ -725,11 +752,20 @@ impl Inner {
         self.npm_api.base_url().clone(),
         npm_resolution,
         None,
+        NodeModulesLayout::default(),
       ),
       None,
     ));
-    let node_resolver =
-      Arc::new(NodeResolver::new(node_fs, npm_resolver.clone()));
+    let node_conditions = self
+      .maybe_config_file
+      .as_ref()
+      .and_then(|config_file| config_file.to_node_conditions().ok().flatten())
+      .unwrap_or_default();
+    let node_resolver = Arc::new(NodeResolver::new(
+      node_fs,
+      npm_resolver.clone(),
+      node_conditions,
+    ));
     Arc::new(StateSnapshot {
       assets: self.assets.snapshot(),
       cache_metadata: self.cache_metadata.clone(),
@@ -875,6 +911,20 @@ impl Inner {
       None,
     );
     file_fetcher.set_download_log_level(super::logging::lsp_log_level());
+    if let Some(config_file) = &self.maybe_config_file {
+      match config_file.to_auth_config() {
+        Ok(Some(auth_config)) => {
+          file_fetcher
+            .add_auth_tokens(AuthTokens::from_config(&auth_config, |name| {
+              std::env::var(name).ok()
+            }));
+        }
+        Ok(None) => {}
+        Err(err) => {
+          lsp_warn!("  Error resolving auth configuration: {:#}", err);
+        }
+      }
+    }
     file_fetcher
   }
 
@@ -3117,6 +3167,7 @@ struct PrepareCacheResult {
   cli_options: CliOptions,
   roots: Vec<ModuleSpecifier>,
   open_docs: Vec<Document>,
+  synth_npm_types: bool,
   mark: PerformanceMark,
 }
 
@@ -3157,6 +3208,8 @@ impl Inner {
       },
       std::env::current_dir().with_context(|| "Failed getting cwd.")?,
       self.maybe_config_file.clone(),
+      // the lsp does not support workspaces yet
+      None,
       // TODO(#16510): add support for lockfile
       None,
       // TODO(bartlomieju): handle package.json dependencies here
@@ -3169,6 +3222,7 @@ impl Inner {
       cli_options,
       open_docs,
       roots,
+      synth_npm_types: self.config.workspace_settings().synth_npm_types,
       mark,
     }))
   }
@@ -298,6 +298,7 @@ pub fn get_repl_workspace_settings() -> WorkspaceSettings {
     tls_certificate: None,
     unsafely_ignore_certificate_errors: None,
     unstable: false,
+    synth_npm_types: false,
     suggest: CompletionSettings {
       complete_function_calls: false,
       names: false,
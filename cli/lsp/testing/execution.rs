@@ -225,7 +225,7 @@ impl TestRun {
     // `PermissionsContainer` - otherwise granting/revoking permissions in one
     // file would have impact on other files, which is undesirable.
     let permissions =
-      Permissions::from_options(&factory.cli_options().permissions_options())?;
+      Permissions::from_options(&factory.cli_options().permissions_options()?)?;
     test::check_specifiers(
       factory.cli_options(),
       factory.file_fetcher()?,
@@ -304,6 +304,7 @@ impl TestRun {
               filter,
               shuffle: None,
               trace_ops: false,
+              timeout: None,
             },
           ))
         };
@@ -11,6 +11,7 @@ mod file_fetcher;
 mod graph_util;
 mod http_util;
 mod js;
+mod jsr;
 mod lsp;
 mod module_loader;
 mod napi;
@@ -44,6 +45,7 @@ use args::CliOptions;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
+use deno_core::serde_json::json;
 use deno_runtime::colors;
 use deno_runtime::fmt_errors::format_js_error;
 use deno_runtime::tokio_util::create_and_run_current_thread;
@@ -77,22 +79,38 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       tools::run::eval_command(flags, eval_flags).await
     }
     DenoSubcommand::Cache(cache_flags) => {
-      let factory = CliFactory::from_flags(flags).await?;
-      let module_load_preparer = factory.module_load_preparer().await?;
-      let emitter = factory.emitter()?;
-      let graph_container = factory.graph_container();
-      module_load_preparer
-        .load_and_type_check_files(&cache_flags.files)
-        .await?;
-      emitter.cache_module_emits(&graph_container.graph())?;
-      Ok(0)
+      if let Some(list_flags) = cache_flags.list {
+        tools::cache::list_cached_modules(flags, list_flags, cache_flags.json)
+          .await?;
+        Ok(0)
+      } else if let Some(prune_flags) = cache_flags.prune_npm {
+        tools::clean::prune_npm_cache(flags, prune_flags).await?;
+        Ok(0)
+      } else if let Some(archive_path) = cache_flags.import {
+        tools::cache::import_cache(flags, archive_path).await?;
+        Ok(0)
+      } else if cache_flags.verify_npm {
+        tools::cache::verify_npm_cache(flags, None).await
+      } else if let Some(integrity_flags) = cache_flags.check_npm_integrity {
+        tools::cache::verify_npm_cache(flags, Some(integrity_flags)).await
+      } else {
+        tools::cache::cache_files(flags, cache_flags).await
+      }
     }
     DenoSubcommand::Check(check_flags) => {
-      let factory = CliFactory::from_flags(flags).await?;
-      let module_load_preparer = factory.module_load_preparer().await?;
-      module_load_preparer
-        .load_and_type_check_files(&check_flags.files)
-        .await?;
+      if flags.watch.is_some() {
+        tools::check::check_watch(flags, check_flags).await?;
+      } else {
+        let factory = CliFactory::from_flags(flags).await?;
+        let module_load_preparer = factory.module_load_preparer().await?;
+        module_load_preparer
+          .load_and_type_check_files(&check_flags.files)
+          .await?;
+      }
+      Ok(0)
+    }
+    DenoSubcommand::Clean(clean_flags) => {
+      tools::clean::clean(flags, clean_flags).await?;
       Ok(0)
     }
     DenoSubcommand::Compile(compile_flags) => {
@@ -139,6 +157,18 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       }
       Ok(0)
     }
+    DenoSubcommand::Lock(lock_flags) => {
+      tools::lock::lock(flags, lock_flags).await?;
+      Ok(0)
+    }
+    DenoSubcommand::Jupyter(jupyter_flags) => {
+      tools::jupyter::kernel(flags, jupyter_flags).await?;
+      Ok(0)
+    }
+    DenoSubcommand::Publish(publish_flags) => {
+      tools::publish::publish(flags, publish_flags).await?;
+      Ok(0)
+    }
     DenoSubcommand::Repl(repl_flags) => {
       tools::repl::run(flags, repl_flags).await
     }
@@ -149,6 +179,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
         tools::run::run_script(flags).await
       }
     }
+    DenoSubcommand::Serve(serve_flags) => {
+      tools::serve::serve(flags, serve_flags).await
+    }
     DenoSubcommand::Task(task_flags) => {
       tools::task::execute_script(flags, task_flags).await
     }
@@ -166,7 +199,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       let cli_options = CliOptions::from_flags(flags)?;
       let test_options = cli_options.resolve_test_options(test_flags)?;
 
-      if cli_options.watch_paths().is_some() {
+      if cli_options.watch_paths().is_some()
+        && !tools::test::is_isolated_test_process()
+      {
         tools::test::run_tests_with_watch(cli_options, test_options).await?;
       } else {
         tools::test::run_tests(cli_options, test_options).await?;
@@ -232,6 +267,25 @@ fn unwrap_or_exit<T>(result: Result<T, AnyError>) -> T {
         error_code = 10;
       }
 
+      if util::logger::is_json_format() {
+        let data = if let Some(e) = error.downcast_ref::<tsc::Diagnostics>() {
+          json!({ "diagnostics": e })
+        } else if let Some(e) = error.downcast_ref::<graph_util::GraphErrors>()
+        {
+          json!({ "graphErrors": e.errors() })
+        } else {
+          json!({})
+        };
+        eprintln!(
+          "{}",
+          util::logger::json_error_line(
+            error_string.trim_start_matches("error: "),
+            data
+          )
+        );
+        std::process::exit(error_code);
+      }
+
       eprintln!(
         "{}: {}",
         colors::red_bold("error"),
@@ -281,6 +335,13 @@ pub fn main() {
       Err(err) => unwrap_or_exit(Err(AnyError::from(err))),
     };
 
+    if !flags.env_files.is_empty() {
+      unwrap_or_exit(args::load_env_files(
+        &flags.env_files,
+        !flags.no_env_override,
+      ));
+    }
+
     let default_v8_flags = match flags.subcommand {
       // Using same default as VSCode:
       // https://github.com/microsoft/vscode/blob/48d4ba271686e8072fc6674137415bc80d936bc7/extensions/typescript-language-features/src/configuration/configuration.ts#L213-L214
@@ -289,7 +350,13 @@ pub fn main() {
     };
     init_v8_flags(&default_v8_flags, &flags.v8_flags, get_v8_flags_from_env());
 
-    util::logger::init(flags.log_level);
+    util::logger::init(flags.log_level, flags.log_format);
+    util::progress_bar::set_quiet(flags.quiet_progress);
+    if let Some(trace_out) = &flags.trace_out {
+      unwrap_or_exit(util::trace::init(trace_out));
+    }
+
+    let _span = util::trace::span("cli", "run subcommand");
 
     run_subcommand(flags).await
   };
@@ -4,8 +4,11 @@ use crate::args::CliOptions;
 use crate::args::DenoSubcommand;
 use crate::args::TsTypeLib;
 use crate::args::TypeCheckMode;
+use crate::cache::CodeCache;
+use crate::cache::FastInsecureHasher;
 use crate::cache::ParsedSourceCache;
 use crate::emit::Emitter;
+use crate::file_fetcher::FileFetcher;
 use crate::graph_util::graph_lock_or_exit;
 use crate::graph_util::graph_valid_with_cli_options;
 use crate::graph_util::ModuleGraphBuilder;
@@ -33,6 +36,7 @@ use deno_core::parking_lot::Mutex;
 use deno_core::resolve_url;
 use deno_core::resolve_url_or_path;
 use deno_core::ModuleCode;
+use deno_core::ModuleCodeCache;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSource;
 use deno_core::ModuleSpecifier;
@@ -311,6 +315,10 @@ struct SharedCliModuleLoaderState {
   graph_container: Arc<ModuleGraphContainer>,
   module_load_preparer: Arc<ModuleLoadPreparer>,
   prepared_module_loader: PreparedModuleLoader,
+  file_fetcher: Arc<FileFetcher>,
+  maybe_code_cache: Option<Arc<CodeCache>>,
+  // cached hash of the v8 flags, which code caches depend on
+  v8_flags_hash: u64,
   resolver: Arc<CliGraphResolver>,
   npm_module_loader: NpmModuleLoader,
 }
@@ -320,12 +328,15 @@ pub struct CliModuleLoaderFactory {
 }
 
 impl CliModuleLoaderFactory {
+  #[allow(clippy::too_many_arguments)]
   pub fn new(
     options: &CliOptions,
     emitter: Arc<Emitter>,
     graph_container: Arc<ModuleGraphContainer>,
     module_load_preparer: Arc<ModuleLoadPreparer>,
     parsed_source_cache: Arc<ParsedSourceCache>,
+    file_fetcher: Arc<FileFetcher>,
+    maybe_code_cache: Option<Arc<CodeCache>>,
     resolver: Arc<CliGraphResolver>,
     npm_module_loader: NpmModuleLoader,
   ) -> Self {
@@ -334,7 +345,10 @@ impl CliModuleLoaderFactory {
         lib_window: options.ts_type_lib_window(),
         lib_worker: options.ts_type_lib_worker(),
         is_inspecting: options.is_inspecting(),
-        is_repl: matches!(options.sub_command(), DenoSubcommand::Repl(_)),
+        is_repl: matches!(
+          options.sub_command(),
+          DenoSubcommand::Repl(_) | DenoSubcommand::Jupyter(_)
+        ),
         prepared_module_loader: PreparedModuleLoader {
           emitter,
           graph_container: graph_container.clone(),
@@ -342,6 +356,11 @@ impl CliModuleLoaderFactory {
         },
         graph_container,
         module_load_preparer,
+        file_fetcher,
+        maybe_code_cache,
+        v8_flags_hash: FastInsecureHasher::new()
+          .write_hashable(options.v8_flags())
+          .finish(),
         resolver,
         npm_module_loader,
       }),
@@ -440,15 +459,33 @@ impl CliModuleLoader {
       // because we don't need it
       code_without_source_map(code_source.code)
     };
-    Ok(ModuleSource::new_with_redirect(
-      match code_source.media_type {
-        MediaType::Json => ModuleType::Json,
-        _ => ModuleType::JavaScript,
-      },
+    let module_type = match code_source.media_type {
+      MediaType::Json => ModuleType::Json,
+      _ => ModuleType::JavaScript,
+    };
+    let maybe_code_cache = match (&self.shared.maybe_code_cache, module_type) {
+      (Some(code_cache), ModuleType::JavaScript) => {
+        let hash = FastInsecureHasher::new()
+          .write(code.as_bytes())
+          .write_u64(self.shared.v8_flags_hash)
+          .finish();
+        Some(ModuleCodeCache {
+          hash,
+          data: code_cache.get_sync(code_source.found_url.as_str(), hash),
+        })
+      }
+      _ => None,
+    };
+    let module_source = ModuleSource::new_with_redirect(
+      module_type,
       code,
       specifier,
       &code_source.found_url,
-    ))
+    );
+    Ok(match maybe_code_cache {
+      Some(code_cache) => module_source.with_code_cache(code_cache),
+      None => module_source,
+    })
   }
 }
 
@@ -501,9 +538,10 @@ impl ModuleLoader for CliModuleLoader {
             }
             Some(Module::Esm(module)) => Ok(module.specifier.clone()),
             Some(Module::Json(module)) => Ok(module.specifier.clone()),
-            Some(Module::External(module)) => {
-              Ok(node::resolve_specifier_into_node_modules(&module.specifier))
-            }
+            Some(Module::External(module)) => self
+              .shared
+              .npm_module_loader
+              .resolve_external(&module.specifier),
             None => Ok(specifier.clone()),
           };
         }
@@ -572,6 +610,42 @@ impl ModuleLoader for CliModuleLoader {
     )))
   }
 
+  fn load_asset(
+    &self,
+    specifier: &ModuleSpecifier,
+    _maybe_referrer: Option<&ModuleSpecifier>,
+    is_dynamic: bool,
+    module_type: ModuleType,
+  ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
+    // assets aren't part of the module graph, so they're fetched here
+    let specifier = specifier.clone();
+    let file_fetcher = self.shared.file_fetcher.clone();
+    let permissions = if is_dynamic {
+      self.dynamic_permissions.clone()
+    } else {
+      self.root_permissions.clone()
+    };
+    async move {
+      let file = file_fetcher.fetch(&specifier, permissions).await?;
+      match module_type {
+        ModuleType::Bytes => {
+          // the source may have been decoded lossily, so read the original
+          // bytes
+          let bytes = std::fs::read(&file.local)
+            .unwrap_or_else(|_| file.source.as_bytes().to_vec());
+          Ok(ModuleSource::new_bytes(bytes, &specifier, &file.specifier))
+        }
+        _ => Ok(ModuleSource::new_with_redirect(
+          module_type,
+          file.source.into(),
+          &specifier,
+          &file.specifier,
+        )),
+      }
+    }
+    .boxed_local()
+  }
+
   fn prepare_load(
     &self,
     _op_state: Rc<RefCell<OpState>>,
@@ -602,6 +676,17 @@ impl ModuleLoader for CliModuleLoader {
     }
     .boxed_local()
   }
+
+  fn code_cache_ready(
+    &self,
+    specifier: &ModuleSpecifier,
+    hash: u64,
+    code_cache: &[u8],
+  ) {
+    if let Some(cache) = &self.shared.maybe_code_cache {
+      cache.set_sync(specifier.as_str(), hash, code_cache);
+    }
+  }
 }
 
 struct CliSourceMapGetter {
@@ -725,6 +810,26 @@ impl NpmModuleLoader {
       .with_context(|| format!("Could not resolve '{reference}'."))
   }
 
+  /// Resolves a module of a node_modules directory that is external to the
+  /// module graph, remembering if it's CommonJS so it gets translated.
+  pub fn resolve_external(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let specifier = node::resolve_specifier_into_node_modules(specifier);
+    if !self.node_resolver.in_npm_package(&specifier) {
+      return Ok(specifier);
+    }
+    self
+      .handle_node_resolve_result(
+        self
+          .node_resolver
+          .url_to_node_resolution(specifier.clone())
+          .map(Some),
+      )
+      .with_context(|| format!("Could not resolve '{specifier}'."))
+  }
+
   pub fn maybe_prepare_load(
     &self,
     specifier: &ModuleSpecifier,
@@ -825,4 +930,8 @@ impl CjsResolutionStore {
   pub fn insert(&self, specifier: ModuleSpecifier) {
     self.0.lock().insert(specifier);
   }
+
+  pub fn remove(&self, specifier: &ModuleSpecifier) {
+    self.0.lock().remove(specifier);
+  }
 }
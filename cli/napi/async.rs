@@ -61,19 +61,29 @@ fn napi_queue_async_work(env_ptr: *mut Env, work: napi_async_work) -> Result {
   Ok(())
 }
 
-// TODO: Custom async operations.
+// Async hooks aren't supported, so an async context is only a placeholder
+// that can be passed to `napi_make_callback` and `napi_async_destroy`.
 
 #[napi_sym::napi_sym]
 fn napi_async_init(
-  _env: *mut Env,
+  env: *mut Env,
   _async_resource: napi_value,
   _async_resource_name: napi_value,
-  _result: *mut *mut (),
+  result: *mut *mut (),
 ) -> Result {
-  todo!()
+  let _env: &mut Env = env.as_mut().ok_or(Error::InvalidArg)?;
+  if result.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  *result = std::ptr::NonNull::dangling().as_ptr();
+  Ok(())
 }
 
 #[napi_sym::napi_sym]
-fn napi_async_destroy(_env: *mut Env, _async_context: *mut ()) -> Result {
-  todo!()
+fn napi_async_destroy(env: *mut Env, async_context: *mut ()) -> Result {
+  let _env: &mut Env = env.as_mut().ok_or(Error::InvalidArg)?;
+  if async_context.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  Ok(())
 }
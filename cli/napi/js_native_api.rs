@@ -929,10 +929,7 @@ fn napi_make_callback(
     check_arg!(env, argv);
   }
 
-  if !async_context.is_null() {
-    log::info!("napi_make_callback: async_context is not supported");
-  }
-
+  // `async_context` is ignored, see `napi_async_init`.
   let recv = napi_value_unchecked(recv);
   let func = napi_value_unchecked(func);
 
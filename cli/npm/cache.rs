@@ -1,10 +1,14 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
@@ -12,21 +16,44 @@ use deno_core::anyhow::Context;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json::json;
 use deno_core::url::Url;
 use deno_npm::registry::NpmPackageVersionDistInfo;
 use deno_npm::NpmPackageCacheFolderId;
 use deno_semver::npm::NpmPackageNv;
 use deno_semver::Version;
 use once_cell::sync::Lazy;
+use tokio::sync::Semaphore;
 
 use crate::args::CacheSetting;
+use crate::args::NotCachedError;
+use crate::args::NpmCopyStrategy;
+use crate::auth_tokens::AuthToken;
+use crate::auth_tokens::AuthTokens;
+use crate::cache::NpmCacheIndex;
+use crate::cache::NpmIndexEntry;
+use crate::colors;
 use crate::http_util::HttpClient;
+use crate::util::display::human_size;
 use crate::util::fs::canonicalize_path;
+use crate::util::fs::copy_dir_recursive;
+use crate::util::fs::dir_size;
 use crate::util::fs::hard_link_dir_recursive;
+use crate::util::fs::reflink_dir_recursive;
+use crate::util::fs::symlink_files_recursive;
+use crate::util::fs::unlinked_files_size;
 use crate::util::path::root_url_to_safe_local_dirname;
 use crate::util::progress_bar::ProgressBar;
+use crate::util::trace;
 
+use super::store::ContentStore;
+use super::store::CONTENT_STORE_FOLDER_NAME;
 use super::tarball::verify_and_extract_tarball;
+use super::NpmRc;
+
+/// The maximum number of package tarballs that are downloaded at once, unless
+/// configured otherwise.
+pub const DEFAULT_NPM_DOWNLOAD_CONCURRENCY: usize = 16;
 
 static SHOULD_SYNC_DOWNLOAD: Lazy<bool> =
   Lazy::new(|| std::env::var("DENO_UNSTABLE_NPM_SYNC_DOWNLOAD").is_ok());
@@ -40,7 +67,23 @@ pub fn should_sync_download() -> bool {
 }
 
 const NPM_PACKAGE_SYNC_LOCK_FILENAME: &str = ".deno_sync_lock";
+/// A file whose modification time is the last time the package was used,
+/// which `deno clean` and `deno cache --prune-npm` use to find unused
+/// packages since the access times of files are often not updated.
+const NPM_PACKAGE_LAST_USED_FILENAME: &str = ".deno_last_used";
+/// How often the last use of a package is recorded at most.
+const NPM_PACKAGE_LAST_USED_INTERVAL: Duration =
+  Duration::from_secs(24 * 60 * 60);
+
+/// How long to wait for another process that sets up the same package folder
+/// before giving up.
+const NPM_PACKAGE_FOLDER_LOCK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
 
+/// Sets up the package folder with the action while holding an advisory lock
+/// of the folder, so that processes setting up the same folder wait for each
+/// other. The action is skipped when another process set up the folder while
+/// waiting and a folder that was left behind partially set up is removed
+/// first.
 pub fn with_folder_sync_lock(
   package: &NpmPackageNv,
   output_folder: &Path,
@@ -48,21 +91,29 @@ pub fn with_folder_sync_lock(
 ) -> Result<(), AnyError> {
   fn inner(
     output_folder: &Path,
+    waited: bool,
     action: impl FnOnce() -> Result<(), AnyError>,
   ) -> Result<(), AnyError> {
+    // This sync lock file is a way to ensure that partially created
+    // npm package directories aren't considered valid. It's only deleted
+    // once the action succeeded, so when it still exists while holding the
+    // folder lock, the process that set up the folder didn't finish.
+    let sync_lock_path = output_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME);
+    if sync_lock_path.exists() {
+      fs::remove_dir_all(output_folder).with_context(|| {
+        format!(
+          "Error removing partially set up '{}'.",
+          output_folder.display()
+        )
+      })?;
+    } else if waited && output_folder.exists() {
+      // set up by the process that held the lock
+      return Ok(());
+    }
     fs::create_dir_all(output_folder).with_context(|| {
       format!("Error creating '{}'.", output_folder.display())
     })?;
 
-    // This sync lock file is a way to ensure that partially created
-    // npm package directories aren't considered valid. This could maybe
-    // be a bit smarter in the future to not bother extracting here
-    // if another process has taken the lock in the past X seconds and
-    // wait for the other process to finish (it could try to create the
-    // file with `create_new(true)` then if it exists, check the metadata
-    // then wait until the other process finishes with a timeout), but
-    // for now this is good enough.
-    let sync_lock_path = output_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME);
     match fs::OpenOptions::new()
       .write(true)
       .create(true)
@@ -87,7 +138,9 @@ pub fn with_folder_sync_lock(
     }
   }
 
-  match inner(output_folder, action) {
+  // held until the folder is set up or cleaned up
+  let folder_lock = lock_package_folder(package, output_folder)?;
+  match inner(output_folder, folder_lock.waited, action) {
     Ok(()) => Ok(()),
     Err(err) => {
       if let Err(remove_err) = fs::remove_dir_all(output_folder) {
@@ -112,15 +165,110 @@ pub fn with_folder_sync_lock(
   }
 }
 
+/// Path of the file that is locked while setting up the package folder, which
+/// is next to the folder so that it outlives removing the folder.
+fn package_folder_lock_path(package_folder: &Path) -> PathBuf {
+  let mut file_name = package_folder
+    .file_name()
+    .map(|name| name.to_os_string())
+    .unwrap_or_default();
+  file_name.push(".lock");
+  package_folder.with_file_name(file_name)
+}
+
+/// An advisory lock of a package folder (`flock` or `LockFileEx`), which is
+/// released when it's dropped or when the process crashes.
+struct PackageFolderLock {
+  /// `None` when the file system doesn't support locking.
+  _maybe_file: Option<fs::File>,
+  /// Whether another process held the lock first.
+  waited: bool,
+}
+
+/// Waits until this process holds the exclusive lock of the package folder.
+fn lock_package_folder(
+  package: &NpmPackageNv,
+  package_folder: &Path,
+) -> Result<PackageFolderLock, AnyError> {
+  use fs3::FileExt;
+
+  let lock_path = package_folder_lock_path(package_folder);
+  if let Some(parent) = lock_path.parent() {
+    fs::create_dir_all(parent)
+      .with_context(|| format!("Error creating '{}'.", parent.display()))?;
+  }
+  let file = fs::OpenOptions::new()
+    .read(true)
+    .write(true)
+    .create(true)
+    .open(&lock_path)
+    .with_context(|| {
+      format!("Error opening package lock file '{}'.", lock_path.display())
+    })?;
+
+  let start = Instant::now();
+  let mut waited = false;
+  let mut logged_wait = false;
+  loop {
+    match file.try_lock_exclusive() {
+      Ok(()) => {
+        return Ok(PackageFolderLock {
+          _maybe_file: Some(file),
+          waited,
+        })
+      }
+      Err(err) if err.kind() == fs3::lock_contended_error().kind() => {
+        waited = true;
+        if start.elapsed() > NPM_PACKAGE_FOLDER_LOCK_TIMEOUT {
+          bail!(
+            concat!(
+              "Timed out waiting for another process setting up the npm ",
+              "package {} at '{}'. If no other process is running, delete ",
+              "'{}' and try again."
+            ),
+            package,
+            package_folder.display(),
+            lock_path.display(),
+          );
+        }
+        if !logged_wait && start.elapsed() > Duration::from_secs(1) {
+          log::info!(
+            "Waiting for another process setting up npm package {}...",
+            package
+          );
+          logged_wait = true;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+      }
+      Err(err) => {
+        // eg. the file system doesn't support locks, so proceed without
+        // synchronizing with other processes like before
+        log::debug!(
+          "Failed locking '{}', continuing without it. {:#}",
+          lock_path.display(),
+          err
+        );
+        return Ok(PackageFolderLock {
+          _maybe_file: None,
+          waited,
+        });
+      }
+    }
+  }
+}
+
 #[derive(Clone, Debug)]
 pub struct ReadonlyNpmCache {
   root_dir: PathBuf,
   // cached url representation of the root directory
   root_dir_url: Url,
+  /// Stores the packages of scopes with their own registry in the folder of
+  /// that registry instead of the one of the provided registry url.
+  npmrc: Arc<NpmRc>,
 }
 
 impl ReadonlyNpmCache {
-  pub fn new(root_dir: PathBuf) -> Self {
+  pub fn new(root_dir: PathBuf, npmrc: Arc<NpmRc>) -> Self {
     fn try_get_canonicalized_root_dir(
       root_dir: &Path,
     ) -> Result<PathBuf, AnyError> {
@@ -138,6 +286,7 @@ impl ReadonlyNpmCache {
     Self {
       root_dir,
       root_dir_url,
+      npmrc,
     }
   }
 
@@ -170,6 +319,7 @@ impl ReadonlyNpmCache {
   }
 
   pub fn package_name_folder(&self, name: &str, registry_url: &Url) -> PathBuf {
+    let registry_url = self.npmrc.registry_url_for_package(name, registry_url);
     let mut dir = self.registry_folder(registry_url);
     if name.to_lowercase() != name {
       let encoded_name = mixed_case_package_name_encode(name);
@@ -191,14 +341,39 @@ impl ReadonlyNpmCache {
       .join(root_url_to_safe_local_dirname(registry_url))
   }
 
+  /// Gets the url of the registry the package is downloaded from, which
+  /// is the registry url unless the `.npmrc` configures one for its scope.
+  pub fn registry_url_for_package<'a>(
+    &'a self,
+    name: &str,
+    registry_url: &'a Url,
+  ) -> &'a Url {
+    self.npmrc.registry_url_for_package(name, registry_url)
+  }
+
+  /// Gets the folders of the registry url and the registries of scopes.
+  pub fn registry_folders(&self, registry_url: &Url) -> Vec<PathBuf> {
+    std::iter::once(registry_url)
+      .chain(self.npmrc.scope_registries.values())
+      .map(|url| self.registry_folder(url))
+      .collect()
+  }
+
   pub fn resolve_package_folder_id_from_specifier(
     &self,
     specifier: &ModuleSpecifier,
     registry_url: &Url,
   ) -> Result<NpmPackageCacheFolderId, AnyError> {
-    match self
-      .maybe_resolve_package_folder_id_from_specifier(specifier, registry_url)
-    {
+    // the scope registries first in case they're below the registry url
+    let maybe_id = self
+      .npmrc
+      .scope_registries
+      .values()
+      .chain(std::iter::once(registry_url))
+      .find_map(|url| {
+        self.maybe_resolve_package_folder_id_from_specifier(specifier, url)
+      });
+    match maybe_id {
       Some(id) => Ok(id),
       None => bail!("could not find npm package for '{}'", specifier),
     }
@@ -274,6 +449,11 @@ impl ReadonlyNpmCache {
   pub fn get_cache_location(&self) -> PathBuf {
     self.root_dir.clone()
   }
+
+  /// Gets the store of the files that the package folders link to.
+  pub fn content_store(&self) -> ContentStore {
+    ContentStore::new(&self.root_dir)
+  }
 }
 
 /// Stores a single copy of npm packages in a cache.
@@ -282,7 +462,16 @@ pub struct NpmCache {
   readonly: ReadonlyNpmCache,
   cache_setting: CacheSetting,
   http_client: Arc<HttpClient>,
+  /// The tokens of `DENO_NPM_AUTH_TOKENS`, which take precedence over the
+  /// credentials in the `.npmrc`.
+  auth_tokens: AuthTokens,
   progress_bar: ProgressBar,
+  /// Limits how many tarballs are downloaded and extracted at once.
+  download_permits: Semaphore,
+  copy_strategy: NpmCopyStrategy,
+  maybe_size_budget: Option<NpmCacheSizeBudget>,
+  /// The package folders used by this run, which are never evicted.
+  used_package_folders: Mutex<HashSet<PathBuf>>,
   /// ensures a package is only downloaded once per run
   previously_reloaded_packages: Mutex<HashSet<NpmPackageNv>>,
 }
@@ -293,16 +482,35 @@ impl NpmCache {
     cache_setting: CacheSetting,
     http_client: Arc<HttpClient>,
     progress_bar: ProgressBar,
+    npmrc: Arc<NpmRc>,
+    download_concurrency: usize,
+    copy_strategy: NpmCopyStrategy,
   ) -> Self {
     Self {
-      readonly: ReadonlyNpmCache::new(cache_dir_path),
+      readonly: ReadonlyNpmCache::new(cache_dir_path, npmrc),
       cache_setting,
       http_client,
+      auth_tokens: AuthTokens::new(std::env::var("DENO_NPM_AUTH_TOKENS").ok()),
       progress_bar,
+      download_permits: Semaphore::new(download_concurrency),
+      copy_strategy,
+      maybe_size_budget: None,
+      used_package_folders: Default::default(),
       previously_reloaded_packages: Default::default(),
     }
   }
 
+  /// Evicts the least recently used package versions at the end of caching
+  /// packages while the npm cache is larger than the maximum size.
+  pub fn with_size_budget(
+    mut self,
+    max_size: u64,
+    index: NpmCacheIndex,
+  ) -> Self {
+    self.maybe_size_budget = Some(NpmCacheSizeBudget { max_size, index });
+    self
+  }
+
   pub fn as_readonly(&self) -> ReadonlyNpmCache {
     self.readonly.clone()
   }
@@ -337,6 +545,11 @@ impl NpmCache {
     dist: &NpmPackageVersionDistInfo,
     registry_url: &Url,
   ) -> Result<(), AnyError> {
+    let _span = trace::span_with_args(
+      "npm",
+      "ensure package",
+      || json!({ "package": package.to_string() }),
+    );
     self
       .ensure_package_inner(package, dist, registry_url)
       .await
@@ -352,13 +565,20 @@ impl NpmCache {
     let package_folder = self
       .readonly
       .package_folder_for_name_and_version(package, registry_url);
+    self
+      .used_package_folders
+      .lock()
+      .insert(package_folder.clone());
     if self.should_use_global_cache_for_package(package)
       && package_folder.exists()
       // if this file exists, then the package didn't successfully extract
       // the first time, or another process is currently extracting the zip file
       && !package_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME).exists()
     {
+      mark_package_used(&package_folder);
       return Ok(());
+    } else if self.cache_setting == CacheSetting::Offline {
+      return Err(NotCachedError::new(format!("npm:{package}")).into());
     } else if self.cache_setting == CacheSetting::Only {
       return Err(custom_error(
         "NotCached",
@@ -370,15 +590,25 @@ impl NpmCache {
       );
     }
 
+    // the progress counts the package as pending while it waits for a slot
+    let queued_guard = self.progress_bar.queue();
+    let _permit = self.download_permits.acquire().await?;
     let guard = self.progress_bar.update(&dist.tarball);
+    drop(queued_guard);
+    let tarball_url = Url::parse(&dist.tarball)?;
+    let maybe_auth_token = self.auth_token_for_url(&tarball_url);
     let maybe_bytes = self
       .http_client
-      .download_with_progress(&dist.tarball, &guard)
+      .download_with_retries(tarball_url, maybe_auth_token.as_ref(), &guard)
       .await?;
     match maybe_bytes {
-      Some(bytes) => {
-        verify_and_extract_tarball(package, &bytes, dist, &package_folder)
-      }
+      Some(bytes) => verify_and_extract_tarball(
+        package,
+        &bytes,
+        dist,
+        &package_folder,
+        &self.readonly.content_store(),
+      ),
       None => {
         bail!("Could not find npm package tarball at: {}", dist.tarball);
       }
@@ -387,8 +617,8 @@ impl NpmCache {
 
   /// Ensures a copy of the package exists in the global cache.
   ///
-  /// This assumes that the original package folder being hard linked
-  /// from exists before this is called.
+  /// This assumes that the original package folder being copied from exists
+  /// before this is called.
   pub fn ensure_copy_package(
     &self,
     folder_id: &NpmPackageCacheFolderId,
@@ -397,6 +627,10 @@ impl NpmCache {
     assert_ne!(folder_id.copy_index, 0);
     let package_folder =
       self.readonly.package_folder_for_id(folder_id, registry_url);
+    self
+      .used_package_folders
+      .lock()
+      .insert(package_folder.clone());
 
     if package_folder.exists()
       // if this file exists, then the package didn't successfully extract
@@ -411,7 +645,11 @@ impl NpmCache {
       .readonly
       .package_folder_for_name_and_version(&folder_id.nv, registry_url);
     with_folder_sync_lock(&folder_id.nv, &package_folder, || {
-      hard_link_dir_recursive(&original_package_folder, &package_folder)
+      copy_package_folder(
+        self.copy_strategy,
+        &original_package_folder,
+        &package_folder,
+      )
     })?;
     Ok(())
   }
@@ -424,6 +662,51 @@ impl NpmCache {
     self.readonly.package_folder_for_id(id, registry_url)
   }
 
+  /// Removes the least recently used package versions that weren't used by
+  /// this run until the npm cache is smaller than its maximum size, if it has
+  /// one. Failing to do so doesn't fail the run.
+  pub fn evict_least_recently_used(&self) {
+    if let Err(err) = self.evict_least_recently_used_inner() {
+      log::warn!(
+        "{} Failed evicting npm packages: {:#}",
+        colors::yellow("Warning"),
+        err
+      );
+    }
+  }
+
+  fn evict_least_recently_used_inner(&self) -> Result<(), AnyError> {
+    let budget = match &self.maybe_size_budget {
+      Some(budget) => budget,
+      None => return Ok(()),
+    };
+    let used_package_folders = self.used_package_folders.lock().clone();
+    let evictions = budget.update_index(
+      &self.readonly.root_dir,
+      &used_package_folders,
+      SystemTime::now(),
+    )?;
+    if evictions.is_empty() {
+      return Ok(());
+    }
+    let mut evicted_size = 0;
+    for (entry, folder) in &evictions {
+      remove_evicted_folder(folder)?;
+      budget.index.remove(&entry.folder)?;
+      evicted_size += entry.size;
+    }
+    evicted_size += self.readonly.content_store().remove_unused()?;
+    log::info!(
+      "{} {} npm package{} ({}) to keep the npm cache below {}",
+      colors::green("Evicted"),
+      evictions.len(),
+      if evictions.len() == 1 { "" } else { "s" },
+      human_size(evicted_size as f64),
+      human_size(budget.max_size as f64),
+    );
+    Ok(())
+  }
+
   pub fn package_folder_for_name_and_version(
     &self,
     package: &NpmPackageNv,
@@ -442,6 +725,31 @@ impl NpmCache {
     self.readonly.registry_folder(registry_url)
   }
 
+  pub fn registry_url_for_package<'a>(
+    &'a self,
+    name: &str,
+    registry_url: &'a Url,
+  ) -> &'a Url {
+    self.readonly.registry_url_for_package(name, registry_url)
+  }
+
+  pub fn registry_folders(&self, registry_url: &Url) -> Vec<PathBuf> {
+    self.readonly.registry_folders(registry_url)
+  }
+
+  pub fn content_store(&self) -> ContentStore {
+    self.readonly.content_store()
+  }
+
+  /// Gets the credentials for requests to the url of a registry, eg. for
+  /// package information or tarballs.
+  pub fn auth_token_for_url(&self, url: &Url) -> Option<AuthToken> {
+    self
+      .auth_tokens
+      .get(url)
+      .or_else(|| self.readonly.npmrc.auth_token_for_url(url))
+  }
+
   pub fn resolve_package_folder_id_from_specifier(
     &self,
     specifier: &ModuleSpecifier,
@@ -453,6 +761,246 @@ impl NpmCache {
   }
 }
 
+/// Reads the folders of the packages in the npm cache, which are stored in
+/// `<registry>/<name>/<version>`, where the name may be scoped.
+pub fn read_npm_package_folders(
+  npm_folder: &Path,
+) -> Result<Vec<PathBuf>, AnyError> {
+  let mut package_folders = Vec::new();
+  for registry_folder in read_sub_folders(npm_folder)? {
+    // the stored files are removed once no package links to them
+    if registry_folder.ends_with(CONTENT_STORE_FOLDER_NAME) {
+      continue;
+    }
+    for folder in read_sub_folders(&registry_folder)? {
+      let is_scope = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().starts_with('@'))
+        .unwrap_or(false);
+      if is_scope {
+        package_folders.extend(read_sub_folders(&folder)?);
+      } else {
+        package_folders.push(folder);
+      }
+    }
+  }
+  package_folders.sort();
+  Ok(package_folders)
+}
+
+fn read_sub_folders(folder: &Path) -> Result<Vec<PathBuf>, AnyError> {
+  let mut folders = Vec::new();
+  if !folder.is_dir() {
+    return Ok(folders);
+  }
+  for entry in fs::read_dir(folder)? {
+    let entry = entry?;
+    if entry.file_type()?.is_dir() {
+      folders.push(entry.path());
+    }
+  }
+  Ok(folders)
+}
+
+/// The maximum size of the npm cache along with the index of its package
+/// version folders (`npmCacheMaxSize`).
+struct NpmCacheSizeBudget {
+  max_size: u64,
+  index: NpmCacheIndex,
+}
+
+impl std::fmt::Debug for NpmCacheSizeBudget {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("NpmCacheSizeBudget")
+      .field("max_size", &self.max_size)
+      .finish()
+  }
+}
+
+impl NpmCacheSizeBudget {
+  /// Brings the index in line with the package version folders in the npm
+  /// cache and returns the entries to evict, least recently used first. Only
+  /// the folders that aren't in the index yet are read.
+  ///
+  /// The files of the folders are mostly hard links to the content store, so
+  /// the size of the cache is the size of the store plus the files of the
+  /// folders that aren't linked anywhere else, which are also the only ones
+  /// evicting a folder is sure to free.
+  fn update_index(
+    &self,
+    npm_folder: &Path,
+    used_package_folders: &HashSet<PathBuf>,
+    now: SystemTime,
+  ) -> Result<Vec<(NpmIndexEntry, PathBuf)>, AnyError> {
+    let now_secs = now
+      .duration_since(SystemTime::UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or(0);
+    let mut indexed = self
+      .index
+      .entries()?
+      .into_iter()
+      .map(|entry| (entry.folder.clone(), entry))
+      .collect::<HashMap<_, _>>();
+    let content_store_folder = npm_folder.join(CONTENT_STORE_FOLDER_NAME);
+    let mut total_size = if content_store_folder.is_dir() {
+      dir_size(&content_store_folder)?
+    } else {
+      0
+    };
+    let mut candidates = Vec::new();
+    for package_folder in read_npm_package_folders(npm_folder)? {
+      for version_folder in read_sub_folders(&package_folder)? {
+        // partially set up or being set up by another process
+        if version_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME).exists() {
+          continue;
+        }
+        let key = index_key(npm_folder, &version_folder);
+        let is_used = used_package_folders.contains(&version_folder);
+        let entry = match indexed.remove(&key) {
+          Some(mut entry) => {
+            if is_used
+              && now_secs.saturating_sub(entry.last_used)
+                >= NPM_PACKAGE_LAST_USED_INTERVAL.as_secs()
+            {
+              entry.last_used = now_secs;
+              self.index.set(&entry)?;
+            }
+            entry
+          }
+          None => {
+            let entry = NpmIndexEntry {
+              folder: key,
+              size: unlinked_files_size(&version_folder)?,
+              last_used: if is_used {
+                now_secs
+              } else {
+                read_last_used(&version_folder)
+              },
+            };
+            self.index.set(&entry)?;
+            entry
+          }
+        };
+        total_size += entry.size;
+        if !is_used {
+          candidates.push((entry, version_folder));
+        }
+      }
+    }
+    // the folders that were removed since, eg. by `deno clean`
+    for folder in indexed.keys() {
+      self.index.remove(folder)?;
+    }
+
+    candidates.sort_by(|a, b| {
+      a.0
+        .last_used
+        .cmp(&b.0.last_used)
+        .then(a.0.folder.cmp(&b.0.folder))
+    });
+    let mut evictions = Vec::new();
+    for candidate in candidates {
+      if total_size <= self.max_size {
+        break;
+      }
+      total_size -= candidate.0.size;
+      evictions.push(candidate);
+    }
+    Ok(evictions)
+  }
+}
+
+fn index_key(npm_folder: &Path, version_folder: &Path) -> String {
+  let relative_path = version_folder
+    .strip_prefix(npm_folder)
+    .unwrap_or(version_folder);
+  relative_path
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// Reads the last use of a package version folder that isn't in the index
+/// yet, in seconds since the unix epoch.
+fn read_last_used(version_folder: &Path) -> u64 {
+  fs::metadata(version_folder.join(NPM_PACKAGE_LAST_USED_FILENAME))
+    .or_else(|_| fs::metadata(version_folder))
+    .and_then(|metadata| metadata.modified())
+    .ok()
+    .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+    .map(|duration| duration.as_secs())
+    .unwrap_or(0)
+}
+
+/// Removes an evicted package version folder, which may have been removed by
+/// another process in the meantime.
+fn remove_evicted_folder(folder: &Path) -> Result<(), AnyError> {
+  match fs::remove_dir_all(folder) {
+    Ok(()) => Ok(()),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    Err(err) => Err(err)
+      .with_context(|| format!("Failed removing '{}'.", folder.display())),
+  }
+}
+
+/// Records that the package in the folder was used, which is best effort
+/// since the cache may be read only.
+fn mark_package_used(package_folder: &Path) {
+  let path = package_folder.join(NPM_PACKAGE_LAST_USED_FILENAME);
+  let is_recent = fs::metadata(&path)
+    .and_then(|metadata| metadata.modified())
+    .ok()
+    .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+    .map(|age| age < NPM_PACKAGE_LAST_USED_INTERVAL)
+    .unwrap_or(false);
+  if !is_recent {
+    if let Err(err) = fs::write(&path, "") {
+      log::debug!("Failed marking '{}' as used: {:#}", path.display(), err);
+    }
+  }
+}
+
+/// Creates a copy of a package folder with the strategy. When that fails, ex.
+/// hard links across file systems or reflinks on a file system without
+/// copy-on-write, the files are copied instead.
+fn copy_package_folder(
+  strategy: NpmCopyStrategy,
+  from: &Path,
+  to: &Path,
+) -> Result<(), AnyError> {
+  let result = match strategy {
+    NpmCopyStrategy::HardLink => hard_link_dir_recursive(from, to),
+    NpmCopyStrategy::Reflink => reflink_dir_recursive(from, to),
+    NpmCopyStrategy::Symlink => symlink_files_recursive(from, to),
+    NpmCopyStrategy::Copy => return copy_dir_recursive(from, to),
+  };
+  if let Err(err) = result {
+    log::debug!(
+      "Failed to {} '{}', copying the files instead: {:#}",
+      strategy.as_str(),
+      from.display(),
+      err
+    );
+    // copying over the links that were created would change the files of
+    // the original folder
+    for entry in fs::read_dir(to)? {
+      let entry = entry?;
+      if entry.file_name() == NPM_PACKAGE_SYNC_LOCK_FILENAME {
+        continue;
+      }
+      if entry.file_type()?.is_dir() {
+        fs::remove_dir_all(entry.path())?;
+      } else {
+        fs::remove_file(entry.path())?;
+      }
+    }
+    copy_dir_recursive(from, to)?;
+  }
+  Ok(())
+}
+
 pub fn mixed_case_package_name_encode(name: &str) -> String {
   // use base32 encoding because it's reversable and the character set
   // only includes the characters within 0-9 and A-Z so it can be lower cased
@@ -474,14 +1022,263 @@ mod test {
   use deno_semver::npm::NpmPackageNv;
   use deno_semver::Version;
 
+  use std::collections::HashSet;
+  use std::sync::atomic::AtomicUsize;
+  use std::sync::atomic::Ordering;
+  use std::sync::Arc;
+  use std::time::Duration;
+  use std::time::SystemTime;
+
+  use super::copy_package_folder;
+  use super::lock_package_folder;
+  use super::with_folder_sync_lock;
+  use super::NpmCacheSizeBudget;
   use super::ReadonlyNpmCache;
+  use super::NPM_PACKAGE_SYNC_LOCK_FILENAME;
+  use crate::args::NpmCopyStrategy;
+  use crate::cache::NpmCacheIndex;
+  use crate::cache::NpmIndexEntry;
   use crate::npm::cache::NpmPackageCacheFolderId;
+  use crate::npm::NpmRc;
+
+  fn chalk_nv() -> NpmPackageNv {
+    NpmPackageNv {
+      name: "chalk".to_string(),
+      version: Version::parse_from_npm("5.0.1").unwrap(),
+    }
+  }
+
+  #[test]
+  fn folder_sync_lock_waits_for_other_lock_holder() {
+    let temp_dir = test_util::TempDir::new();
+    let package_folder = temp_dir.path().join("chalk").join("5.0.1");
+    let folder_lock =
+      lock_package_folder(&chalk_nv(), &package_folder).unwrap();
+    assert!(!folder_lock.waited);
+
+    let action_count = Arc::new(AtomicUsize::new(0));
+    let handle = std::thread::spawn({
+      let package_folder = package_folder.clone();
+      let action_count = action_count.clone();
+      move || {
+        with_folder_sync_lock(&chalk_nv(), &package_folder, || {
+          action_count.fetch_add(1, Ordering::SeqCst);
+          Ok(())
+        })
+      }
+    });
+    // set up the folder while the other thread waits for the lock
+    std::thread::sleep(Duration::from_millis(100));
+    std::fs::create_dir_all(&package_folder).unwrap();
+    std::fs::write(package_folder.join("package.json"), "{}").unwrap();
+    drop(folder_lock);
+
+    handle.join().unwrap().unwrap();
+    assert_eq!(action_count.load(Ordering::SeqCst), 0);
+    assert!(package_folder.join("package.json").exists());
+  }
+
+  #[test]
+  fn folder_sync_lock_sets_up_partial_folder_again() {
+    let temp_dir = test_util::TempDir::new();
+    let package_folder = temp_dir.path().join("chalk").join("5.0.1");
+    std::fs::create_dir_all(&package_folder).unwrap();
+    std::fs::write(package_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME), "")
+      .unwrap();
+    std::fs::write(package_folder.join("partial.js"), "").unwrap();
+
+    with_folder_sync_lock(&chalk_nv(), &package_folder, || {
+      std::fs::write(package_folder.join("package.json"), "{}")?;
+      Ok(())
+    })
+    .unwrap();
+    assert!(package_folder.join("package.json").exists());
+    assert!(!package_folder.join("partial.js").exists());
+    assert!(!package_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME).exists());
+
+    // reloading sets up an existing folder again
+    let action_count = AtomicUsize::new(0);
+    with_folder_sync_lock(&chalk_nv(), &package_folder, || {
+      action_count.fetch_add(1, Ordering::SeqCst);
+      Ok(())
+    })
+    .unwrap();
+    assert_eq!(action_count.load(Ordering::SeqCst), 1);
+
+    // failing removes the folder
+    let result = with_folder_sync_lock(&chalk_nv(), &package_folder, || {
+      deno_core::anyhow::bail!("failed")
+    });
+    assert_eq!(result.unwrap_err().to_string(), "failed");
+    assert!(!package_folder.exists());
+  }
+
+  #[test]
+  fn copies_package_folder_with_each_strategy() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("original/lib");
+    temp_dir.write("original/package.json", "{}");
+    temp_dir.write("original/lib/index.js", "export {};");
+    let original_folder = temp_dir.path().join("original");
+    for strategy in [
+      NpmCopyStrategy::HardLink,
+      NpmCopyStrategy::Reflink,
+      NpmCopyStrategy::Symlink,
+      NpmCopyStrategy::Copy,
+    ] {
+      let copy_folder = temp_dir.path().join(strategy.as_str());
+      copy_package_folder(strategy, &original_folder, &copy_folder).unwrap();
+      assert_eq!(
+        temp_dir.read_to_string(format!("{}/package.json", strategy.as_str())),
+        "{}"
+      );
+      assert_eq!(
+        temp_dir.read_to_string(format!("{}/lib/index.js", strategy.as_str())),
+        "export {};"
+      );
+    }
+  }
+
+  #[test]
+  fn copies_package_folder_when_strategy_fails() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("original/lib");
+    temp_dir.write("original/lib/index.js", "export {};");
+    temp_dir.write("original/package.json", "{}");
+    // left behind by a failed attempt, so the symlink can't be created
+    temp_dir.create_dir_all("copy");
+    temp_dir.write(format!("copy/{NPM_PACKAGE_SYNC_LOCK_FILENAME}"), "");
+    temp_dir.write("copy/package.json", "partial");
+    copy_package_folder(
+      NpmCopyStrategy::Symlink,
+      &temp_dir.path().join("original"),
+      &temp_dir.path().join("copy"),
+    )
+    .unwrap();
+    assert_eq!(temp_dir.read_to_string("copy/package.json"), "{}");
+    assert_eq!(temp_dir.read_to_string("copy/lib/index.js"), "export {};");
+    assert!(!temp_dir.path().join("copy/package.json").is_symlink());
+    assert!(temp_dir
+      .path()
+      .join(format!("copy/{NPM_PACKAGE_SYNC_LOCK_FILENAME}"))
+      .exists());
+    assert_eq!(temp_dir.read_to_string("original/package.json"), "{}");
+  }
+
+  #[test]
+  fn evicts_least_recently_used_packages() {
+    let temp_dir = test_util::TempDir::new();
+    for folder in [
+      "registry.npmjs.org/a/1.0.0",
+      "registry.npmjs.org/b/1.0.0",
+      "registry.npmjs.org/c/1.0.0",
+      "registry.npmjs.org/@scope/d/1.0.0",
+      "_content/sha512/ab",
+    ] {
+      temp_dir.create_dir_all(folder);
+    }
+    temp_dir.write("registry.npmjs.org/@scope/d/1.0.0/index.js", "a");
+    // a stored file is only counted once and not as part of the folder
+    temp_dir.create_dir_all("registry.npmjs.org/e/1.0.0");
+    temp_dir.write("_content/sha512/ab/cdef", "bbbb");
+    std::fs::hard_link(
+      temp_dir.path().join("_content/sha512/ab/cdef"),
+      temp_dir.path().join("registry.npmjs.org/e/1.0.0/index.js"),
+    )
+    .unwrap();
+    let npm_folder = temp_dir.path().to_path_buf();
+    let index = NpmCacheIndex::in_memory();
+    for (folder, last_used) in [
+      ("registry.npmjs.org/a/1.0.0", 100),
+      ("registry.npmjs.org/b/1.0.0", 50),
+      ("registry.npmjs.org/c/1.0.0", 10),
+      // removed by another process
+      ("registry.npmjs.org/gone/1.0.0", 0),
+    ] {
+      index
+        .set(&NpmIndexEntry {
+          folder: folder.to_string(),
+          size: 100,
+          last_used,
+        })
+        .unwrap();
+    }
+    let budget = NpmCacheSizeBudget {
+      max_size: 150,
+      index: index.clone(),
+    };
+    let used_folder = npm_folder.join("registry.npmjs.org/c/1.0.0");
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let evictions = budget
+      .update_index(&npm_folder, &HashSet::from([used_folder]), now)
+      .unwrap();
+    assert_eq!(
+      evictions
+        .iter()
+        .map(|(entry, _)| entry.folder.as_str())
+        .collect::<Vec<_>>(),
+      vec!["registry.npmjs.org/b/1.0.0", "registry.npmjs.org/a/1.0.0"]
+    );
+
+    let mut entries = index.entries().unwrap();
+    entries.sort_by(|a, b| a.folder.cmp(&b.folder));
+    assert_eq!(
+      entries
+        .iter()
+        .map(|entry| (entry.folder.as_str(), entry.size))
+        .collect::<Vec<_>>(),
+      vec![
+        ("registry.npmjs.org/@scope/d/1.0.0", 1),
+        ("registry.npmjs.org/a/1.0.0", 100),
+        ("registry.npmjs.org/b/1.0.0", 100),
+        ("registry.npmjs.org/c/1.0.0", 100),
+        ("registry.npmjs.org/e/1.0.0", if cfg!(unix) { 0 } else { 4 }),
+      ]
+    );
+    // the used package is recorded as used now
+    assert_eq!(entries[3].last_used, 1_000_000);
+  }
+
+  #[test]
+  fn package_folder_of_scope_registry() {
+    let temp_dir = test_util::TempDir::new();
+    let npmrc = NpmRc::parse("@company:registry=https://npm.company.com/");
+    let cache =
+      ReadonlyNpmCache::new(temp_dir.path().to_path_buf(), Arc::new(npmrc));
+    let registry_url = Url::parse("https://registry.npmjs.org/").unwrap();
+    let root_dir = cache.root_dir.clone();
+
+    let nv = NpmPackageNv {
+      name: "@company/utils".to_string(),
+      version: Version::parse_from_npm("1.0.0").unwrap(),
+    };
+    let folder = cache.package_folder_for_name_and_version(&nv, &registry_url);
+    assert_eq!(
+      folder,
+      root_dir
+        .join("npm.company.com")
+        .join("@company")
+        .join("utils")
+        .join("1.0.0"),
+    );
+    assert_eq!(
+      cache.package_name_folder("chalk", &registry_url),
+      root_dir.join("registry.npmjs.org").join("chalk"),
+    );
+
+    let specifier = Url::from_file_path(folder.join("index.js")).unwrap();
+    let folder_id = cache
+      .resolve_package_folder_id_from_specifier(&specifier, &registry_url)
+      .unwrap();
+    assert_eq!(folder_id.nv, nv);
+    assert_eq!(folder_id.copy_index, 0);
+  }
 
   #[test]
   fn should_get_package_folder() {
     let deno_dir = crate::cache::DenoDir::new(None).unwrap();
     let root_dir = deno_dir.npm_folder_path();
-    let cache = ReadonlyNpmCache::new(root_dir.clone());
+    let cache = ReadonlyNpmCache::new(root_dir.clone(), Default::default());
     let registry_url = Url::parse("https://registry.npmjs.org/").unwrap();
 
     assert_eq!(
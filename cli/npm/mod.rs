@@ -2,16 +2,28 @@
 
 mod cache;
 mod installer;
+mod npmrc;
 mod registry;
 mod resolution;
 mod resolvers;
+mod store;
 mod tarball;
+mod types;
 
+pub use cache::read_npm_package_folders;
 pub use cache::should_sync_download;
 pub use cache::NpmCache;
+pub use cache::DEFAULT_NPM_DOWNLOAD_CONCURRENCY;
 pub use installer::PackageJsonDepsInstaller;
+pub use npmrc::NpmRc;
 pub use registry::CliNpmRegistryApi;
 pub use resolution::NpmResolution;
+pub use resolvers::create_byonm_npm_fs_resolver;
 pub use resolvers::create_npm_fs_resolver;
 pub use resolvers::CliNpmResolver;
 pub use resolvers::NpmProcessState;
+pub use store::ContentStore;
+pub use store::PackageIntegrity;
+pub use store::PackageVerification;
+pub use store::CONTENT_STORE_FOLDER_NAME;
+pub use types::synthesize_npm_types;
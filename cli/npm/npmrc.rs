@@ -0,0 +1,353 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use deno_core::url::Url;
+
+use crate::auth_tokens::AuthToken;
+use crate::auth_tokens::AuthTokenData;
+use crate::cache::home_dir;
+
+const NPMRC_FILENAME: &str = ".npmrc";
+
+/// The registries of package scopes and the credentials of registries
+/// configured in `.npmrc` files, eg.
+/// `@company:registry=https://npm.company.com/` and
+/// `//npm.company.com/:_authToken=${NPM_TOKEN}`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NpmRc {
+  /// The registry urls keyed by the scope, including its `@`.
+  pub scope_registries: HashMap<String, Url>,
+  /// The credentials keyed by the registry url without its scheme, eg.
+  /// `//npm.company.com/`.
+  pub registry_auths: HashMap<String, AuthTokenData>,
+}
+
+impl NpmRc {
+  /// Reads the `.npmrc` of the user and the closest one of the directory or
+  /// its ancestors, where the latter takes precedence like it does for npm.
+  pub fn discover(dir: &Path) -> Self {
+    let mut npmrc = Self::default();
+    let maybe_user_path = home_dir().map(|home| home.join(NPMRC_FILENAME));
+    if let Some(user_path) = &maybe_user_path {
+      npmrc.merge(Self::read(user_path));
+    }
+    let maybe_project_path = dir
+      .ancestors()
+      .map(|dir| dir.join(NPMRC_FILENAME))
+      .find(|path| path.is_file());
+    if let Some(project_path) = maybe_project_path {
+      if Some(&project_path) != maybe_user_path.as_ref() {
+        npmrc.merge(Self::read(&project_path));
+      }
+    }
+    npmrc
+  }
+
+  fn read(path: &Path) -> Self {
+    match std::fs::read_to_string(path) {
+      Ok(text) => {
+        log::debug!("Reading npm config at {}", path.display());
+        Self::parse(&text)
+      }
+      Err(_) => Self::default(),
+    }
+  }
+
+  /// Parses the scope registries of the `.npmrc` text, ignoring the other
+  /// settings. Environment variables are substituted in the values like
+  /// `${NPM_REGISTRY}`.
+  pub fn parse(text: &str) -> Self {
+    let mut npmrc = Self::default();
+    let mut usernames = HashMap::new();
+    let mut passwords = HashMap::new();
+    for line in text.lines() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+        continue;
+      }
+      let (key, value) = match line.split_once('=') {
+        Some((key, value)) => (key.trim(), value.trim()),
+        None => continue,
+      };
+      let value = substitute_env_vars(value.trim_matches('"'));
+      if key.starts_with("//") {
+        let (registry, setting) = match key.rsplit_once(':') {
+          Some(parts) => parts,
+          None => continue,
+        };
+        let registry = registry.to_string();
+        match setting {
+          "_authToken" => {
+            npmrc
+              .registry_auths
+              .insert(registry, AuthTokenData::Bearer(value));
+          }
+          "_auth" => match decode_basic_auth(&value) {
+            Some((username, password)) => {
+              npmrc
+                .registry_auths
+                .insert(registry, AuthTokenData::Basic { username, password });
+            }
+            None => {
+              log::debug!("Invalid _auth of {} in .npmrc", registry);
+            }
+          },
+          "username" => {
+            usernames.insert(registry, value);
+          }
+          "_password" => {
+            passwords.insert(registry, value);
+          }
+          _ => {}
+        }
+        continue;
+      }
+      let scope = match key.strip_suffix(":registry") {
+        Some(scope) if scope.starts_with('@') && scope.len() > 1 => scope,
+        _ => continue,
+      };
+      // ensure there is a trailing slash for the directory
+      let url = format!("{}/", value.trim_end_matches('/'));
+      match Url::parse(&url) {
+        Ok(url) => {
+          npmrc.scope_registries.insert(scope.to_string(), url);
+        }
+        Err(err) => {
+          log::debug!("Invalid registry of {} in .npmrc: {:#}", scope, err);
+        }
+      }
+    }
+    // the password is base64 encoded
+    for (registry, username) in usernames {
+      let maybe_password = passwords
+        .remove(&registry)
+        .and_then(|password| base64::decode(password).ok())
+        .and_then(|password| String::from_utf8(password).ok());
+      match maybe_password {
+        Some(password) => {
+          npmrc
+            .registry_auths
+            .insert(registry, AuthTokenData::Basic { username, password });
+        }
+        None => {
+          log::debug!("Missing or invalid _password of {} in .npmrc", registry);
+        }
+      }
+    }
+    npmrc
+  }
+
+  fn merge(&mut self, other: Self) {
+    self.scope_registries.extend(other.scope_registries);
+    self.registry_auths.extend(other.registry_auths);
+  }
+
+  /// Gets the credentials of the registry the url belongs to, which are the
+  /// ones of the longest registry url the url starts with.
+  pub fn auth_token_for_url(&self, url: &Url) -> Option<AuthToken> {
+    let host = url.host_str()?;
+    let url_without_scheme = match url.port() {
+      Some(port) => format!("//{}:{}{}", host, port, url.path()),
+      None => format!("//{}{}", host, url.path()),
+    };
+    self
+      .registry_auths
+      .iter()
+      .filter(|(registry, _)| {
+        let registry = format!("{}/", registry.trim_end_matches('/'));
+        url_without_scheme.starts_with(&registry)
+      })
+      .max_by_key(|(registry, _)| registry.len())
+      .map(|(registry, token)| AuthToken::new(registry.clone(), token.clone()))
+  }
+
+  /// Gets the url of the registry the package is published to, which is
+  /// the registry of its scope or otherwise the default registry.
+  pub fn registry_url_for_package<'a>(
+    &'a self,
+    name: &str,
+    default_url: &'a Url,
+  ) -> &'a Url {
+    let maybe_scope = name
+      .split_once('/')
+      .map(|(scope, _)| scope)
+      .filter(|scope| scope.starts_with('@'));
+    maybe_scope
+      .and_then(|scope| self.scope_registries.get(scope))
+      .unwrap_or(default_url)
+  }
+}
+
+/// Decodes the base64 encoded `username:password` of `_auth`.
+fn decode_basic_auth(value: &str) -> Option<(String, String)> {
+  let credentials = String::from_utf8(base64::decode(value).ok()?).ok()?;
+  let (username, password) = credentials.split_once(':')?;
+  Some((username.to_string(), password.to_string()))
+}
+
+fn substitute_env_vars(value: &str) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut rest = value;
+  while let Some(start) = rest.find("${") {
+    let end = match rest[start..].find('}') {
+      Some(end) => start + end,
+      None => break,
+    };
+    result.push_str(&rest[..start]);
+    let name = &rest[start + 2..end];
+    result.push_str(&std::env::var(name).unwrap_or_default());
+    rest = &rest[end + 1..];
+  }
+  result.push_str(rest);
+  result
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn parse_scope_registries() {
+    let npmrc = NpmRc::parse(
+      r#"
+# comment
+; another comment
+registry=https://registry.example.com/
+@company:registry=https://npm.company.com
+@other:registry = "https://npm.other.com/packages/"
+//npm.company.com/:_authToken=secret
+@invalid:registry=not a url
+@:registry=https://npm.empty.com
+"#,
+    );
+    assert_eq!(
+      npmrc.scope_registries,
+      HashMap::from([
+        (
+          "@company".to_string(),
+          Url::parse("https://npm.company.com/").unwrap()
+        ),
+        (
+          "@other".to_string(),
+          Url::parse("https://npm.other.com/packages/").unwrap()
+        ),
+      ])
+    );
+  }
+
+  #[test]
+  fn parse_registry_auths() {
+    std::env::set_var("DENO_TEST_NPMRC_TOKEN", "secret");
+    let npmrc = NpmRc::parse(
+      r#"
+//npm.company.com/:_authToken=${DENO_TEST_NPMRC_TOKEN}
+//npm.other.com/packages/:_auth=dXNlcjpwYXNz
+//localhost:4873/:username=user
+//localhost:4873/:_password=cGFzcw==
+//npm.invalid.com/:_auth=invalid
+"#,
+    );
+    assert_eq!(
+      npmrc.registry_auths,
+      HashMap::from([
+        (
+          "//npm.company.com/".to_string(),
+          AuthTokenData::Bearer("secret".to_string())
+        ),
+        (
+          "//npm.other.com/packages/".to_string(),
+          AuthTokenData::Basic {
+            username: "user".to_string(),
+            password: "pass".to_string(),
+          }
+        ),
+        (
+          "//localhost:4873/".to_string(),
+          AuthTokenData::Basic {
+            username: "user".to_string(),
+            password: "pass".to_string(),
+          }
+        ),
+      ])
+    );
+  }
+
+  #[test]
+  fn auth_token_for_url() {
+    let npmrc = NpmRc::parse(
+      r#"
+//npm.company.com/:_authToken=company
+//npm.company.com/private/:_authToken=private
+//localhost:4873:_authToken=local
+"#,
+    );
+    let token = |url: &str| {
+      npmrc
+        .auth_token_for_url(&Url::parse(url).unwrap())
+        .map(|token| token.to_string())
+    };
+    assert_eq!(
+      token("https://npm.company.com/@company/utils/-/utils-1.0.0.tgz"),
+      Some("Bearer company".to_string())
+    );
+    assert_eq!(
+      token("https://npm.company.com/private/@company%2futils"),
+      Some("Bearer private".to_string())
+    );
+    assert_eq!(
+      token("http://localhost:4873/chalk"),
+      Some("Bearer local".to_string())
+    );
+    assert_eq!(token("https://npm.company.com.evil.com/chalk"), None);
+    assert_eq!(token("https://registry.npmjs.org/chalk"), None);
+  }
+
+  #[test]
+  fn registry_url_for_package() {
+    let npmrc = NpmRc::parse("@company:registry=https://npm.company.com/");
+    let default_url = Url::parse("https://registry.npmjs.org/").unwrap();
+    assert_eq!(
+      npmrc.registry_url_for_package("@company/utils", &default_url),
+      &Url::parse("https://npm.company.com/").unwrap()
+    );
+    assert_eq!(
+      npmrc.registry_url_for_package("@types/node", &default_url),
+      &default_url
+    );
+    assert_eq!(
+      npmrc.registry_url_for_package("chalk", &default_url),
+      &default_url
+    );
+  }
+
+  #[test]
+  fn discover_closest_npmrc() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("project/sub");
+    temp_dir.write(
+      "project/.npmrc",
+      "@company:registry=https://npm.company.com/",
+    );
+    let npmrc = NpmRc::discover(&temp_dir.path().join("project").join("sub"));
+    assert_eq!(
+      npmrc.scope_registries.get("@company"),
+      Some(&Url::parse("https://npm.company.com/").unwrap())
+    );
+  }
+
+  #[test]
+  fn substitutes_env_vars() {
+    std::env::set_var("DENO_TEST_NPMRC_HOST", "npm.company.com");
+    assert_eq!(
+      substitute_env_vars("https://${DENO_TEST_NPMRC_HOST}/npm/"),
+      "https://npm.company.com/npm/"
+    );
+    assert_eq!(
+      substitute_env_vars("https://${UNCLOSED"),
+      "https://${UNCLOSED"
+    );
+  }
+}
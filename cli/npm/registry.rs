@@ -14,17 +14,24 @@ use deno_core::error::custom_error;
 use deno_core::error::AnyError;
 use deno_core::futures::future::BoxFuture;
 use deno_core::futures::future::Shared;
+use deno_core::futures::stream::FuturesUnordered;
 use deno_core::futures::FutureExt;
+use deno_core::futures::StreamExt;
 use deno_core::parking_lot::Mutex;
 use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_core::TaskQueue;
 use deno_npm::registry::NpmPackageInfo;
+use deno_npm::registry::NpmPackageVersionInfo;
 use deno_npm::registry::NpmRegistryApi;
 use deno_npm::registry::NpmRegistryPackageInfoLoadError;
+use deno_semver::npm::NpmPackageNv;
+use deno_semver::Version;
+use deno_semver::VersionReq;
 use once_cell::sync::Lazy;
 
 use crate::args::CacheSetting;
+use crate::args::NotCachedError;
 use crate::cache::CACHE_PERM;
 use crate::http_util::HttpClient;
 use crate::util::fs::atomic_write_file;
@@ -108,7 +115,7 @@ impl CliNpmRegistryApi {
     // is disabled or if we're already reloading
     if matches!(
       self.inner().cache.cache_setting(),
-      CacheSetting::Only | CacheSetting::ReloadAll
+      CacheSetting::Only | CacheSetting::Offline | CacheSetting::ReloadAll
     ) {
       return false;
     }
@@ -120,6 +127,25 @@ impl CliNpmRegistryApi {
     }
   }
 
+  /// Starts loading the package information of the dependency tree of the
+  /// package version in the background. This way the npm resolution that
+  /// happens after building the module graph finds it in the memory cache
+  /// instead of loading it one level of the tree at a time.
+  pub fn prefetch_dependencies(&self, nv: &NpmPackageNv) {
+    let inner = match &self.0 {
+      Some(inner) => inner.clone(),
+      None => return,
+    };
+    // keep the output deterministic when downloading synchronously
+    if should_sync_download() {
+      return;
+    }
+    let nv = nv.clone();
+    deno_core::task::spawn(async move {
+      inner.prefetch_dependencies(&nv).await;
+    });
+  }
+
   fn inner(&self) -> &Arc<CliNpmRegistryApiInner> {
     // this panicking indicates a bug in the code where this
     // wasn't initialized
@@ -244,6 +270,40 @@ impl CliNpmRegistryApiInner {
     }
   }
 
+  async fn prefetch_dependencies(self: &Arc<Self>, nv: &NpmPackageNv) {
+    // the dependencies of a package are only prefetched for one of its
+    // versions, which is good enough to warm up the cache
+    let mut seen_names = HashSet::from([nv.name.clone()]);
+    let mut pending = FuturesUnordered::new();
+    pending
+      .push(self.prefetch_package(nv.name.clone(), nv.version.to_string()));
+    while let Some(dependencies) = pending.next().await {
+      for (name, version_req) in dependencies {
+        if seen_names.insert(name.clone()) {
+          pending.push(self.prefetch_package(name, version_req));
+        }
+      }
+    }
+  }
+
+  /// Loads the package information, returning the dependencies of the
+  /// version that matches the version requirement.
+  async fn prefetch_package(
+    self: &Arc<Self>,
+    name: String,
+    version_req: String,
+  ) -> Vec<(String, String)> {
+    match self.maybe_package_info(&name).await {
+      Ok(Some(info)) => get_version_dependencies(&info, &version_req),
+      Ok(None) => Vec::new(),
+      Err(err) => {
+        // the npm resolution will surface the error
+        log::debug!("Failed prefetching npm package {}: {:#}", name, err);
+        Vec::new()
+      }
+    }
+  }
+
   fn force_reload(&self) -> bool {
     self.force_reload_flag.is_raised()
   }
@@ -320,6 +380,9 @@ impl CliNpmRegistryApiInner {
     &self,
     name: &str,
   ) -> Result<Option<NpmPackageInfo>, AnyError> {
+    if *self.cache.cache_setting() == CacheSetting::Offline {
+      return Err(NotCachedError::new(format!("npm:{name}")).into());
+    }
     self
       .load_package_info_from_registry_inner(name)
       .await
@@ -348,9 +411,14 @@ impl CliNpmRegistryApiInner {
     let package_url = self.get_package_url(name);
     let guard = self.progress_bar.update(package_url.as_str());
 
+    let maybe_auth_token = self.cache.auth_token_for_url(&package_url);
     let maybe_bytes = self
       .http_client
-      .download_with_progress(package_url, &guard)
+      .download_with_progress_and_auth(
+        package_url,
+        maybe_auth_token.as_ref(),
+        &guard,
+      )
       .await?;
     match maybe_bytes {
       Some(bytes) => {
@@ -363,7 +431,11 @@ impl CliNpmRegistryApiInner {
   }
 
   fn get_package_url(&self, name: &str) -> Url {
-    self.base_url.join(name).unwrap()
+    self
+      .cache
+      .registry_url_for_package(name, &self.base_url)
+      .join(name)
+      .unwrap()
   }
 
   fn get_package_file_cache_path(&self, name: &str) -> PathBuf {
@@ -387,3 +459,62 @@ impl CliNpmRegistryApiInner {
     }
   }
 }
+
+/// Gets the dependencies of the highest version of the package that matches
+/// the version requirement as pairs of package name and version requirement.
+fn get_version_dependencies(
+  info: &NpmPackageInfo,
+  version_req: &str,
+) -> Vec<(String, String)> {
+  let version_info = match select_version(info, version_req) {
+    Some(version_info) => version_info,
+    None => return Vec::new(),
+  };
+  version_info
+    .dependencies
+    .iter()
+    .chain(version_info.peer_dependencies.iter())
+    .map(
+      |(name, version_req)| match version_req.strip_prefix("npm:") {
+        // aliased dependency (ex. `"alias": "npm:package@^1.0.0"`)
+        Some(alias) => split_alias(alias),
+        None => (name.clone(), version_req.clone()),
+      },
+    )
+    .collect()
+}
+
+fn select_version<'a>(
+  info: &'a NpmPackageInfo,
+  version_req: &str,
+) -> Option<&'a NpmPackageVersionInfo> {
+  if let Ok(version) = Version::parse_from_npm(version_req) {
+    if let Some(version_info) = info.versions.get(&version) {
+      return Some(version_info);
+    }
+  }
+  if let Some(version) = info.dist_tags.get(version_req) {
+    return info.versions.get(version);
+  }
+  let version_req = VersionReq::parse_from_npm(version_req).ok()?;
+  info
+    .versions
+    .iter()
+    .filter(|(version, _)| version_req.matches(version))
+    .max_by(|(a, _), (b, _)| a.cmp(b))
+    .map(|(_, version_info)| version_info)
+}
+
+/// Splits `package@^1.0.0` or `@scope/package@^1.0.0` into the package name
+/// and version requirement.
+fn split_alias(alias: &str) -> (String, String) {
+  // skip the `@` of the scope
+  let start = usize::from(alias.starts_with('@'));
+  match alias[start..].find('@') {
+    Some(index) => {
+      let index = start + index;
+      (alias[..index].to_string(), alias[index + 1..].to_string())
+    }
+    None => (alias.to_string(), "*".to_string()),
+  }
+}
@@ -30,6 +30,7 @@ use deno_semver::npm::NpmPackageReqReference;
 use deno_semver::VersionReq;
 
 use crate::args::Lockfile;
+use crate::util::trace;
 
 use super::registry::CliNpmRegistryApi;
 
@@ -272,6 +273,7 @@ async fn add_package_reqs_to_snapshot(
   maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
   get_new_snapshot: impl Fn() -> NpmResolutionSnapshot,
 ) -> Result<NpmResolutionSnapshot, AnyError> {
+  let _span = trace::span("npm", "resolve npm packages");
   let snapshot = get_new_snapshot();
   if !snapshot.has_pending()
     && package_reqs
@@ -0,0 +1,231 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Code for resolving packages from a node_modules directory that is not
+//! managed by Deno ("bring your own node_modules").
+
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use deno_npm::NpmPackageId;
+use deno_runtime::deno_fs::FileSystem;
+use deno_runtime::deno_node::NodePermissions;
+use deno_runtime::deno_node::NodeResolutionMode;
+use deno_runtime::deno_node::PackageJson;
+
+use super::common::types_package_name;
+use super::common::NpmPackageFsResolver;
+
+/// Resolves packages from existing node_modules directories created by
+/// another package manager (npm, pnpm, yarn), looking them up in the
+/// node_modules directories of the ancestors of the referrer like Node does.
+/// Nothing is downloaded or written to the node_modules directories.
+#[derive(Debug)]
+pub struct ByonmNpmPackageResolver {
+  fs: Arc<dyn FileSystem>,
+  root_node_modules_path: PathBuf,
+  root_node_modules_url: Url,
+}
+
+impl ByonmNpmPackageResolver {
+  pub fn new(fs: Arc<dyn FileSystem>, root_node_modules_path: PathBuf) -> Self {
+    Self {
+      fs,
+      root_node_modules_url: Url::from_directory_path(&root_node_modules_path)
+        .unwrap(),
+      root_node_modules_path,
+    }
+  }
+}
+
+#[async_trait]
+impl NpmPackageFsResolver for ByonmNpmPackageResolver {
+  fn root_dir_url(&self) -> &Url {
+    &self.root_node_modules_url
+  }
+
+  fn node_modules_path(&self) -> Option<PathBuf> {
+    Some(self.root_node_modules_path.clone())
+  }
+
+  fn in_npm_package(&self, specifier: &ModuleSpecifier) -> bool {
+    specifier.scheme() == "file" && specifier.path().contains("/node_modules/")
+  }
+
+  fn package_folder(&self, id: &NpmPackageId) -> Result<PathBuf, AnyError> {
+    bail!(
+      "Could not find package '{}', npm packages are resolved from the node_modules directory with --byonm.",
+      id.as_serialized()
+    )
+  }
+
+  fn resolve_package_folder_from_package(
+    &self,
+    name: &str,
+    referrer: &ModuleSpecifier,
+    mode: NodeResolutionMode,
+  ) -> Result<PathBuf, AnyError> {
+    let referrer_path = match referrer.to_file_path() {
+      Ok(path) => path,
+      Err(()) => bail!(
+        "could not find package '{}' from referrer '{}'.",
+        name,
+        referrer
+      ),
+    };
+    // use the real path so the dependencies of packages in symlinked
+    // layouts (ex. pnpm) are found next to them
+    let referrer_path = self
+      .fs
+      .realpath_sync(&referrer_path)
+      .map(deno_core::strip_unc_prefix)
+      .unwrap_or(referrer_path);
+    for ancestor in referrer_path.ancestors().skip(1) {
+      if ancestor.file_name().map(|n| n == "node_modules") == Some(true) {
+        continue;
+      }
+      let node_modules_folder = ancestor.join("node_modules");
+      let sub_dir = join_package_name(&node_modules_folder, name);
+      if self.fs.is_dir(&sub_dir) {
+        // if doing types resolution, only resolve the package if it specifies a types property
+        if mode.is_types() && !name.starts_with("@types/") {
+          let package_json = PackageJson::load_skip_read_permission(
+            &*self.fs,
+            sub_dir.join("package.json"),
+          )?;
+          if package_json.types.is_some() {
+            return Ok(sub_dir);
+          }
+        } else {
+          return Ok(sub_dir);
+        }
+      }
+
+      // if doing type resolution, check for the existence of a @types package
+      if mode.is_types() && !name.starts_with("@types/") {
+        let sub_dir =
+          join_package_name(&node_modules_folder, &types_package_name(name));
+        if self.fs.is_dir(&sub_dir) {
+          return Ok(sub_dir);
+        }
+      }
+    }
+
+    bail!(
+      "could not find package '{}' from referrer '{}'. Make sure it was installed in a node_modules directory.",
+      name,
+      referrer
+    );
+  }
+
+  fn resolve_package_folder_from_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Result<PathBuf, AnyError> {
+    let path = match specifier.to_file_path() {
+      Ok(path) => path,
+      Err(()) => bail!("could not find npm package for '{}'", specifier),
+    };
+    match package_root_in_node_modules(&path) {
+      Some(package_root) => Ok(package_root),
+      None => bail!("could not find npm package for '{}'", specifier),
+    }
+  }
+
+  async fn cache_packages(&self) -> Result<(), AnyError> {
+    // the packages are installed by another package manager
+    Ok(())
+  }
+
+  fn ensure_read_permission(
+    &self,
+    permissions: &dyn NodePermissions,
+    path: &Path,
+  ) -> Result<(), AnyError> {
+    // allow reading the files of packages
+    if package_root_in_node_modules(path).is_some()
+      && path
+        .components()
+        .all(|c| !matches!(c, Component::ParentDir))
+    {
+      return Ok(());
+    }
+
+    permissions.check_read(path)
+  }
+}
+
+/// Gets the root folder of the package the path is in, which is the folder
+/// (or scoped folder) after the last node_modules folder of the path.
+fn package_root_in_node_modules(path: &Path) -> Option<PathBuf> {
+  let components = path.components().collect::<Vec<_>>();
+  let node_modules_index = components
+    .iter()
+    .rposition(|c| c.as_os_str() == "node_modules")?;
+  let name = components.get(node_modules_index + 1)?;
+  let end_index = if name.as_os_str().to_string_lossy().starts_with('@') {
+    node_modules_index + 2
+  } else {
+    node_modules_index + 1
+  };
+  if end_index >= components.len() {
+    return None;
+  }
+  Some(components[..=end_index].iter().collect())
+}
+
+fn join_package_name(path: &Path, package_name: &str) -> PathBuf {
+  let mut path = path.to_path_buf();
+  // ensure backslashes are used on windows
+  for part in package_name.split('/') {
+    path = path.join(part);
+  }
+  path
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_package_root_in_node_modules() {
+    let root = |path: &str| {
+      package_root_in_node_modules(Path::new(path))
+        .map(|p| p.to_string_lossy().to_string())
+    };
+    assert_eq!(
+      root("/project/node_modules/chalk/source/index.js"),
+      Some("/project/node_modules/chalk".to_string())
+    );
+    assert_eq!(
+      root("/project/node_modules/@scope/pkg/index.js"),
+      Some("/project/node_modules/@scope/pkg".to_string())
+    );
+    assert_eq!(
+      root("/project/node_modules/a/node_modules/b/lib/index.js"),
+      Some("/project/node_modules/a/node_modules/b".to_string())
+    );
+    assert_eq!(
+      root(
+        "/project/node_modules/.pnpm/chalk@5.0.0/node_modules/chalk/index.js"
+      ),
+      Some(
+        "/project/node_modules/.pnpm/chalk@5.0.0/node_modules/chalk"
+          .to_string()
+      )
+    );
+    assert_eq!(
+      root("/project/node_modules/chalk"),
+      Some("/project/node_modules/chalk".to_string())
+    );
+    assert_eq!(root("/project/node_modules/@scope"), None);
+    assert_eq!(root("/project/node_modules"), None);
+    assert_eq!(root("/project/main.ts"), None);
+  }
+}
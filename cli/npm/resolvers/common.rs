@@ -17,6 +17,7 @@ use deno_runtime::deno_fs::FileSystem;
 use deno_runtime::deno_node::NodePermissions;
 use deno_runtime::deno_node::NodeResolutionMode;
 
+use crate::args::NotCachedError;
 use crate::npm::cache::should_sync_download;
 use crate::npm::NpmCache;
 
@@ -29,6 +30,13 @@ pub trait NpmPackageFsResolver: Send + Sync {
   /// The local node_modules folder if it is applicable to the implementation.
   fn node_modules_path(&self) -> Option<PathBuf>;
 
+  /// Gets if the provided specifier is in an npm package.
+  fn in_npm_package(&self, specifier: &ModuleSpecifier) -> bool {
+    let root_dir_url = self.root_dir_url();
+    debug_assert!(root_dir_url.as_str().ends_with('/'));
+    specifier.as_ref().starts_with(root_dir_url.as_str())
+  }
+
   fn package_folder(
     &self,
     package_id: &NpmPackageId,
@@ -84,11 +92,23 @@ pub async fn cache_packages(
     }
   }
   let results = futures::future::join_all(handles).await;
+  let mut not_cached_errors = Vec::new();
   for result in results {
-    // surface the first error
-    result??;
+    if let Err(err) = result? {
+      // report all the packages that aren't cached in offline mode at once,
+      // otherwise surface the first error
+      match err.downcast_ref::<NotCachedError>() {
+        Some(not_cached_error) => {
+          not_cached_errors.push(not_cached_error.clone())
+        }
+        None => return Err(err),
+      }
+    }
+  }
+  match NotCachedError::combine(not_cached_errors) {
+    Some(err) => Err(err.into()),
+    None => Ok(()),
   }
-  Ok(())
 }
 
 pub fn ensure_registry_read_permission(
@@ -133,7 +133,13 @@ impl NpmPackageFsResolver for GlobalNpmPackageResolver {
     permissions: &dyn NodePermissions,
     path: &Path,
   ) -> Result<(), AnyError> {
-    let registry_path = self.cache.registry_folder(&self.registry_url);
+    // the packages of scopes with their own registry are in its folder
+    let registry_path = self
+      .cache
+      .registry_folders(&self.registry_url)
+      .into_iter()
+      .find(|folder| path.starts_with(folder))
+      .unwrap_or_else(|| self.cache.registry_folder(&self.registry_url));
     ensure_registry_read_permission(&self.fs, permissions, &registry_path, path)
   }
 }
@@ -158,5 +164,6 @@ async fn cache_packages_in_resolver(
     )?;
   }
 
+  resolver.cache.evict_least_recently_used();
   Ok(())
 }
@@ -30,6 +30,7 @@ use deno_runtime::deno_node::NodePermissions;
 use deno_runtime::deno_node::NodeResolutionMode;
 use deno_runtime::deno_node::PackageJson;
 
+use crate::args::NodeModulesLayout;
 use crate::npm::cache::mixed_case_package_name_encode;
 use crate::npm::cache::should_sync_download;
 use crate::npm::resolution::NpmResolution;
@@ -52,6 +53,7 @@ pub struct LocalNpmPackageResolver {
   registry_url: Url,
   root_node_modules_path: PathBuf,
   root_node_modules_url: Url,
+  layout: NodeModulesLayout,
 }
 
 impl LocalNpmPackageResolver {
@@ -61,6 +63,7 @@ impl LocalNpmPackageResolver {
     progress_bar: ProgressBar,
     registry_url: Url,
     node_modules_folder: PathBuf,
+    layout: NodeModulesLayout,
     resolution: Arc<NpmResolution>,
   ) -> Self {
     Self {
@@ -72,6 +75,7 @@ impl LocalNpmPackageResolver {
       root_node_modules_url: Url::from_directory_path(&node_modules_folder)
         .unwrap(),
       root_node_modules_path: node_modules_folder,
+      layout,
     }
   }
 
@@ -205,8 +209,11 @@ impl NpmPackageFsResolver for LocalNpmPackageResolver {
       &self.progress_bar,
       &self.registry_url,
       &self.root_node_modules_path,
+      self.layout,
     )
-    .await
+    .await?;
+    self.cache.evict_least_recently_used();
+    Ok(())
   }
 
   fn ensure_read_permission(
@@ -230,6 +237,7 @@ async fn sync_resolution_with_fs(
   progress_bar: &ProgressBar,
   registry_url: &Url,
   root_node_modules_dir_path: &Path,
+  layout: NodeModulesLayout,
 ) -> Result<(), AnyError> {
   if snapshot.is_empty() {
     return Ok(()); // don't create the directory
@@ -251,7 +259,7 @@ async fn sync_resolution_with_fs(
 
   // 1. Write all the packages out the .deno directory.
   //
-  // Copy (hardlink in future) <global_registry_cache>/<package_id>/ to
+  // Copy or hard link <global_registry_cache>/<package_id>/ to
   // node_modules/.deno/<package_folder_id_folder_name>/node_modules/<package_name>
   let sync_download = should_sync_download();
   let mut package_partitions = snapshot.all_packages_partitioned();
@@ -272,7 +280,7 @@ async fn sync_resolution_with_fs(
     if !cache
       .cache_setting()
       .should_use_for_npm_package(&package.pkg_id.nv.name)
-      || !initialized_file.exists()
+      || !is_initialized(&initialized_file, layout)
     {
       let pb = progress_bar.clone();
       let cache = cache.clone();
@@ -289,16 +297,40 @@ async fn sync_resolution_with_fs(
         let sub_node_modules = folder_path.join("node_modules");
         let package_path =
           join_package_name(&sub_node_modules, &package.pkg_id.nv.name);
+        if package_path.exists() {
+          // start over so no files of another layout remain, as copying
+          // over a hard link would change the file in the cache
+          fs::remove_dir_all(&package_path).with_context(|| {
+            format!("Removing '{}'", package_path.display())
+          })?;
+        }
         fs::create_dir_all(&package_path)
           .with_context(|| format!("Creating '{}'", folder_path.display()))?;
         let cache_folder = cache.package_folder_for_name_and_version(
           &package.pkg_id.nv,
           &registry_url,
         );
-        // for now copy, but in the future consider hard linking
-        copy_dir_recursive(&cache_folder, &package_path)?;
+        match layout {
+          NodeModulesLayout::Copy => {
+            copy_dir_recursive(&cache_folder, &package_path)?
+          }
+          NodeModulesLayout::Linked => {
+            if let Err(err) =
+              hard_link_dir_recursive(&cache_folder, &package_path)
+            {
+              // ex. the cache is on a different device
+              log::debug!(
+                "Failed hard linking {}, copying instead: {:#}",
+                package.pkg_id.nv,
+                err
+              );
+              copy_dir_recursive(&cache_folder, &package_path)?;
+            }
+          }
+        }
         // write out a file that indicates this folder has been initialized
-        fs::write(initialized_file, "")?;
+        // with the layout, so changing the layout recreates it
+        fs::write(initialized_file, layout.as_str())?;
         // finally stop showing the progress bar
         drop(pb_guard); // explicit for clarity
         Ok(())
@@ -410,6 +442,15 @@ async fn sync_resolution_with_fs(
   Ok(())
 }
 
+fn is_initialized(initialized_file: &Path, layout: NodeModulesLayout) -> bool {
+  match fs::read_to_string(initialized_file) {
+    // folders initialized before layouts existed have an empty file
+    Ok(text) if text.is_empty() => layout == NodeModulesLayout::Copy,
+    Ok(text) => text == layout.as_str(),
+    Err(_) => false,
+  }
+}
+
 fn get_package_folder_id_folder_name(
   folder_id: &NpmPackageCacheFolderId,
 ) -> String {
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+mod byonm;
 mod common;
 mod global;
 mod local;
@@ -8,6 +9,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use byonm::ByonmNpmPackageResolver;
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
@@ -22,16 +24,19 @@ use deno_runtime::deno_fs::FileSystem;
 use deno_runtime::deno_node::NodePermissions;
 use deno_runtime::deno_node::NodeResolutionMode;
 use deno_runtime::deno_node::NpmResolver;
+use deno_runtime::deno_node::PackageJson;
 use deno_runtime::deno_node::PathClean;
 use deno_semver::npm::NpmPackageNv;
 use deno_semver::npm::NpmPackageNvReference;
 use deno_semver::npm::NpmPackageReq;
 use deno_semver::npm::NpmPackageReqReference;
+use deno_semver::Version;
 use global::GlobalNpmPackageResolver;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::args::Lockfile;
+use crate::args::NodeModulesLayout;
 use crate::util::fs::canonicalize_path_maybe_not_exists_with_fs;
 use crate::util::progress_bar::ProgressBar;
 
@@ -133,6 +138,47 @@ impl CliNpmResolver {
     Ok(path)
   }
 
+  /// Resolves the folder of a package from a node_modules directory that deno
+  /// doesn't manage (`--byonm`), ensuring the installed version satisfies the
+  /// version requirement.
+  pub fn resolve_byonm_pkg_folder_from_pkg_req(
+    &self,
+    req: &NpmPackageReq,
+    referrer: &ModuleSpecifier,
+  ) -> Result<PathBuf, AnyError> {
+    let package_folder = self.fs_resolver.resolve_package_folder_from_package(
+      &req.name,
+      referrer,
+      NodeResolutionMode::Execution,
+    )?;
+    let version_req = match &req.version_req {
+      Some(version_req) => version_req,
+      None => return Ok(package_folder),
+    };
+    let package_json = PackageJson::load_skip_read_permission(
+      &*self.fs,
+      package_folder.join("package.json"),
+    )?;
+    let version = package_json
+      .version
+      .as_deref()
+      .and_then(|version| Version::parse_from_npm(version).ok());
+    match version {
+      Some(version) if version_req.matches(&version) => Ok(package_folder),
+      Some(version) => bail!(
+        "Could not resolve '{}', version {} of the package is installed at '{}'. Update the package with your package manager.",
+        req,
+        version,
+        package_folder.display(),
+      ),
+      None => bail!(
+        "Could not resolve '{}', the version of the package installed at '{}' is unknown.",
+        req,
+        package_folder.display(),
+      ),
+    }
+  }
+
   /// Attempts to get the package size in bytes.
   pub fn package_size(
     &self,
@@ -144,9 +190,7 @@ impl CliNpmResolver {
 
   /// Gets if the provided specifier is in an npm package.
   pub fn in_npm_package(&self, specifier: &ModuleSpecifier) -> bool {
-    let root_dir_url = self.fs_resolver.root_dir_url();
-    debug_assert!(root_dir_url.as_str().ends_with('/'));
-    specifier.as_ref().starts_with(root_dir_url.as_str())
+    self.fs_resolver.in_npm_package(specifier)
   }
 
   /// If the resolver has resolved any npm packages.
@@ -289,6 +333,7 @@ pub fn create_npm_fs_resolver(
   registry_url: Url,
   resolution: Arc<NpmResolution>,
   maybe_node_modules_path: Option<PathBuf>,
+  node_modules_layout: NodeModulesLayout,
 ) -> Arc<dyn NpmPackageFsResolver> {
   match maybe_node_modules_path {
     Some(node_modules_folder) => Arc::new(LocalNpmPackageResolver::new(
@@ -297,6 +342,7 @@ pub fn create_npm_fs_resolver(
       progress_bar.clone(),
       registry_url,
       node_modules_folder,
+      node_modules_layout,
       resolution,
     )),
     None => Arc::new(GlobalNpmPackageResolver::new(
@@ -308,6 +354,15 @@ pub fn create_npm_fs_resolver(
   }
 }
 
+/// Creates a resolver that uses the packages of an existing node_modules
+/// directory instead of installing them.
+pub fn create_byonm_npm_fs_resolver(
+  fs: Arc<dyn FileSystem>,
+  root_node_modules_path: PathBuf,
+) -> Arc<dyn NpmPackageFsResolver> {
+  Arc::new(ByonmNpmPackageResolver::new(fs, root_node_modules_path))
+}
+
 fn path_to_specifier(path: &Path) -> Result<ModuleSpecifier, AnyError> {
   match ModuleSpecifier::from_file_path(path.to_path_buf().clean()) {
     Ok(specifier) => Ok(specifier),
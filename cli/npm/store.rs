@@ -0,0 +1,479 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The content addressable store of the files of npm packages. Every
+//! extracted file is stored once under the SHA-512 digest of its contents
+//! and the version folders of the packages consist of hard links to the
+//! stored files, along with a manifest of the digests of their files that
+//! `deno cache --verify-npm` checks the folders against.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use ring::digest::SHA512;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::util::fs::atomic_write_file;
+
+/// The folder of the store in the npm cache, which can't be confused with a
+/// registry folder since hosts can't start with an underscore.
+pub const CONTENT_STORE_FOLDER_NAME: &str = "_content";
+/// The manifest of the files of a package version folder.
+const PACKAGE_INTEGRITY_FILENAME: &str = ".deno_integrity.json";
+/// Files of a package folder that aren't part of the package.
+const UNTRACKED_FILE_NAMES: [&str; 3] = [
+  PACKAGE_INTEGRITY_FILENAME,
+  ".deno_last_used",
+  ".deno_sync_lock",
+];
+
+/// The SHA-512 digests of the files of a package version folder, keyed by
+/// their path relative to the folder with forward slashes.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageIntegrity {
+  pub files: BTreeMap<String, String>,
+  /// The integrity of the tarball the files were extracted from, eg.
+  /// `sha512-...`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub tarball_integrity: Option<String>,
+}
+
+impl PackageIntegrity {
+  pub fn read(package_folder: &Path) -> Result<Option<Self>, AnyError> {
+    let path = package_folder.join(PACKAGE_INTEGRITY_FILENAME);
+    let text = match fs::read_to_string(&path) {
+      Ok(text) => text,
+      Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+      Err(err) => return Err(err.into()),
+    };
+    let integrity = serde_json::from_str(&text)
+      .with_context(|| format!("Failed parsing '{}'.", path.display()))?;
+    Ok(Some(integrity))
+  }
+
+  pub fn write(&self, package_folder: &Path) -> Result<(), AnyError> {
+    let path = package_folder.join(PACKAGE_INTEGRITY_FILENAME);
+    fs::write(&path, serde_json::to_string_pretty(self)?)
+      .with_context(|| format!("Failed writing '{}'.", path.display()))?;
+    Ok(())
+  }
+}
+
+/// The result of verifying a package version folder.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PackageVerification {
+  /// All the files match their digests.
+  Valid,
+  /// The folder has no manifest, because it was set up before the store
+  /// existed.
+  Unverifiable,
+  /// The files that don't match, which weren't repaired.
+  Modified(Vec<String>),
+  /// The files that didn't match were restored from the store.
+  Healed(Vec<String>),
+  /// The files didn't match and the store has no intact copy of them, so the
+  /// folder needs to be set up again.
+  Corrupted(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ContentStore {
+  folder: PathBuf,
+}
+
+impl ContentStore {
+  pub fn new(npm_root_dir: &Path) -> Self {
+    Self {
+      folder: npm_root_dir.join(CONTENT_STORE_FOLDER_NAME),
+    }
+  }
+
+  fn file_path(&self, digest: &str) -> PathBuf {
+    // spread the files over sub folders, since some file systems are slow
+    // with many files in one folder
+    self.folder.join("sha512").join(&digest[..2]).join(digest)
+  }
+
+  /// Stores the contents unless they're stored already, returning their
+  /// digest.
+  pub fn add(&self, data: &[u8], executable: bool) -> Result<String, AnyError> {
+    let digest = digest_bytes(data);
+    let path = self.file_path(&digest);
+    if !path.exists() {
+      fs::create_dir_all(path.parent().unwrap())?;
+      let mode = if executable { 0o755 } else { 0o644 };
+      // written atomically, because other processes may link it right away
+      atomic_write_file(&path, data, mode)
+        .with_context(|| format!("Failed writing '{}'.", path.display()))?;
+    } else if executable {
+      set_executable(&path)?;
+    }
+    Ok(digest)
+  }
+
+  /// Hard links the stored file to the path, or copies it when the file
+  /// system doesn't support hard links.
+  pub fn link(&self, digest: &str, path: &Path) -> Result<(), AnyError> {
+    let stored_path = self.file_path(digest);
+    match fs::remove_file(path) {
+      Ok(()) => {}
+      Err(err) if err.kind() == ErrorKind::NotFound => {}
+      Err(err) => return Err(err.into()),
+    }
+    if let Err(err) = fs::hard_link(&stored_path, path) {
+      log::debug!(
+        "Failed hard linking '{}', copying it instead. {:#}",
+        stored_path.display(),
+        err
+      );
+      fs::copy(&stored_path, path).with_context(|| {
+        format!(
+          "Failed copying '{}' to '{}'.",
+          stored_path.display(),
+          path.display()
+        )
+      })?;
+    }
+    Ok(())
+  }
+
+  /// Checks the files of the package folder against its manifest. When
+  /// repairing, the files that don't match are restored from the store and
+  /// the stored files that don't match their digest are removed as well, so
+  /// that they're stored again the next time a package with them is
+  /// extracted.
+  pub fn verify_package_folder(
+    &self,
+    package_folder: &Path,
+    repair: bool,
+  ) -> Result<PackageVerification, AnyError> {
+    let integrity = match PackageIntegrity::read(package_folder)? {
+      Some(integrity) => integrity,
+      None => return Ok(PackageVerification::Unverifiable),
+    };
+    let mut mismatches = Vec::new();
+    for (relative_path, digest) in &integrity.files {
+      let path = package_folder.join(relative_path);
+      if digest_file(&path)?.as_deref() != Some(digest.as_str()) {
+        mismatches.push((relative_path.clone(), Some(digest.as_str())));
+      }
+    }
+    for relative_path in read_untracked_files(package_folder, &integrity)? {
+      // eg. a file added by someone tampering with the package
+      if repair {
+        fs::remove_file(package_folder.join(&relative_path))?;
+      }
+      mismatches.push((relative_path, None));
+    }
+    if mismatches.is_empty() {
+      return Ok(PackageVerification::Valid);
+    } else if !repair {
+      let relative_paths =
+        mismatches.into_iter().map(|(path, _)| path).collect();
+      return Ok(PackageVerification::Modified(relative_paths));
+    }
+
+    let mut is_corrupted = false;
+    for (relative_path, maybe_digest) in &mismatches {
+      let digest = match maybe_digest {
+        Some(digest) => *digest,
+        None => continue,
+      };
+      let stored_path = self.file_path(digest);
+      if digest_file(&stored_path)?.as_deref() == Some(digest) {
+        self.link(digest, &package_folder.join(relative_path))?;
+      } else {
+        is_corrupted = true;
+        match fs::remove_file(&stored_path) {
+          Ok(()) => {}
+          Err(err) if err.kind() == ErrorKind::NotFound => {}
+          Err(err) => return Err(err.into()),
+        }
+      }
+    }
+    let relative_paths = mismatches.into_iter().map(|(path, _)| path).collect();
+    if is_corrupted {
+      Ok(PackageVerification::Corrupted(relative_paths))
+    } else {
+      Ok(PackageVerification::Healed(relative_paths))
+    }
+  }
+
+  /// Removes the stored files that no package folder links to anymore,
+  /// returning their total size. The links of files are only counted on
+  /// unix, so nothing is removed elsewhere.
+  pub fn remove_unused(&self) -> Result<u64, AnyError> {
+    let mut removed_size = 0;
+    let files_folder = self.folder.join("sha512");
+    if !files_folder.is_dir() {
+      return Ok(removed_size);
+    }
+    for sub_folder in fs::read_dir(&files_folder)? {
+      let sub_folder = sub_folder?.path();
+      for entry in fs::read_dir(&sub_folder)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if link_count(&metadata) == Some(1) {
+          match fs::remove_file(entry.path()) {
+            Ok(()) => removed_size += metadata.len(),
+            // removed by another process in the meantime
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+          }
+        }
+      }
+      if fs::read_dir(&sub_folder)?.next().is_none() {
+        let _ = fs::remove_dir(&sub_folder);
+      }
+    }
+    Ok(removed_size)
+  }
+}
+
+#[cfg(unix)]
+fn link_count(metadata: &fs::Metadata) -> Option<u64> {
+  use std::os::unix::fs::MetadataExt;
+  Some(metadata.nlink())
+}
+
+#[cfg(not(unix))]
+fn link_count(_metadata: &fs::Metadata) -> Option<u64> {
+  None
+}
+
+fn digest_bytes(data: &[u8]) -> String {
+  let digest = ring::digest::digest(&SHA512, data);
+  digest
+    .as_ref()
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect()
+}
+
+fn digest_file(path: &Path) -> Result<Option<String>, AnyError> {
+  match fs::read(path) {
+    Ok(data) => Ok(Some(digest_bytes(&data))),
+    Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+    Err(err) => {
+      Err(err).with_context(|| format!("Failed reading '{}'.", path.display()))
+    }
+  }
+}
+
+/// Reads the files of the package folder that aren't in its manifest.
+fn read_untracked_files(
+  package_folder: &Path,
+  integrity: &PackageIntegrity,
+) -> Result<Vec<String>, AnyError> {
+  let mut untracked_files = Vec::new();
+  let mut pending = vec![(package_folder.to_path_buf(), String::new())];
+  while let Some((folder, prefix)) = pending.pop() {
+    for entry in fs::read_dir(&folder)? {
+      let entry = entry?;
+      let name = entry.file_name().to_string_lossy().to_string();
+      let relative_path = format!("{prefix}{name}");
+      if entry.file_type()?.is_dir() {
+        pending.push((entry.path(), format!("{relative_path}/")));
+      } else {
+        let is_ignored =
+          prefix.is_empty() && UNTRACKED_FILE_NAMES.contains(&&*name);
+        if !(is_ignored || integrity.files.contains_key(&relative_path)) {
+          untracked_files.push(relative_path);
+        }
+      }
+    }
+  }
+  untracked_files.sort();
+  Ok(untracked_files)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), AnyError> {
+  use std::os::unix::fs::PermissionsExt;
+  let mut permissions = fs::metadata(path)?.permissions();
+  if permissions.mode() & 0o111 == 0 {
+    permissions.set_mode(permissions.mode() | 0o111);
+    fs::set_permissions(path, permissions)?;
+  }
+  Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), AnyError> {
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn set_up_package(
+    store: &ContentStore,
+    package_folder: &Path,
+    files: &[(&str, &str)],
+  ) {
+    let mut integrity = PackageIntegrity::default();
+    for (relative_path, text) in files {
+      let path = package_folder.join(relative_path);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      let digest = store.add(text.as_bytes(), false).unwrap();
+      store.link(&digest, &path).unwrap();
+      integrity.files.insert(relative_path.to_string(), digest);
+    }
+    integrity.write(package_folder).unwrap();
+  }
+
+  #[test]
+  fn stores_files_once() {
+    let temp_dir = test_util::TempDir::new();
+    let store = ContentStore::new(temp_dir.path());
+    let package_folder = temp_dir.path().join("registry.npmjs.org/a/1.0.0");
+    set_up_package(
+      &store,
+      &package_folder,
+      &[
+        ("index.js", "module.exports = 1;"),
+        ("lib/b.js", "module.exports = 1;"),
+      ],
+    );
+    let digest = digest_bytes(b"module.exports = 1;");
+    assert_eq!(
+      fs::read_dir(store.file_path(&digest).parent().unwrap())
+        .unwrap()
+        .count(),
+      1
+    );
+    assert_eq!(
+      PackageIntegrity::read(&package_folder).unwrap().unwrap(),
+      PackageIntegrity {
+        files: BTreeMap::from([
+          ("index.js".to_string(), digest.clone()),
+          ("lib/b.js".to_string(), digest),
+        ]),
+        tarball_integrity: None,
+      }
+    );
+    assert_eq!(
+      store.verify_package_folder(&package_folder, true).unwrap(),
+      PackageVerification::Valid
+    );
+    assert_eq!(
+      store
+        .verify_package_folder(
+          &temp_dir.path().join("registry.npmjs.org"),
+          true
+        )
+        .unwrap(),
+      PackageVerification::Unverifiable
+    );
+  }
+
+  #[test]
+  fn heals_replaced_files() {
+    let temp_dir = test_util::TempDir::new();
+    let store = ContentStore::new(temp_dir.path());
+    let package_folder = temp_dir.path().join("registry.npmjs.org/a/1.0.0");
+    set_up_package(
+      &store,
+      &package_folder,
+      &[("index.js", "export default 1;"), ("package.json", "{}")],
+    );
+    // replaced instead of modified in place, so the stored file is intact
+    fs::remove_file(package_folder.join("index.js")).unwrap();
+    fs::write(package_folder.join("index.js"), "export default 2;").unwrap();
+    fs::remove_file(package_folder.join("package.json")).unwrap();
+    fs::write(package_folder.join("extra.js"), "").unwrap();
+    assert_eq!(
+      store.verify_package_folder(&package_folder, true).unwrap(),
+      PackageVerification::Healed(vec![
+        "index.js".to_string(),
+        "package.json".to_string(),
+        "extra.js".to_string(),
+      ])
+    );
+    assert_eq!(
+      fs::read_to_string(package_folder.join("index.js")).unwrap(),
+      "export default 1;"
+    );
+    assert!(package_folder.join("package.json").exists());
+    assert!(!package_folder.join("extra.js").exists());
+    assert_eq!(
+      store.verify_package_folder(&package_folder, true).unwrap(),
+      PackageVerification::Valid
+    );
+  }
+
+  #[test]
+  fn reports_modified_files_without_repairing() {
+    let temp_dir = test_util::TempDir::new();
+    let store = ContentStore::new(temp_dir.path());
+    let package_folder = temp_dir.path().join("registry.npmjs.org/a/1.0.0");
+    set_up_package(&store, &package_folder, &[("index.js", "export {};")]);
+    fs::remove_file(package_folder.join("index.js")).unwrap();
+    fs::write(package_folder.join("index.js"), "tampered").unwrap();
+    fs::write(package_folder.join("extra.js"), "").unwrap();
+    let verification = store.verify_package_folder(&package_folder, false);
+    assert_eq!(
+      verification.unwrap(),
+      PackageVerification::Modified(vec![
+        "index.js".to_string(),
+        "extra.js".to_string(),
+      ])
+    );
+    assert_eq!(
+      fs::read_to_string(package_folder.join("index.js")).unwrap(),
+      "tampered"
+    );
+    assert!(package_folder.join("extra.js").exists());
+  }
+
+  #[test]
+  fn detects_corrupted_stored_files() {
+    let temp_dir = test_util::TempDir::new();
+    let store = ContentStore::new(temp_dir.path());
+    let package_folder = temp_dir.path().join("registry.npmjs.org/a/1.0.0");
+    set_up_package(&store, &package_folder, &[("index.js", "export {};")]);
+    let digest = digest_bytes(b"export {};");
+    // modifying the hard link modifies the stored file
+    fs::write(package_folder.join("index.js"), "tampered").unwrap();
+    assert_eq!(
+      store.verify_package_folder(&package_folder, true).unwrap(),
+      PackageVerification::Corrupted(vec!["index.js".to_string()])
+    );
+    assert!(!store.file_path(&digest).exists());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn removes_unused_files() {
+    let temp_dir = test_util::TempDir::new();
+    let store = ContentStore::new(temp_dir.path());
+    let package_folder = temp_dir.path().join("registry.npmjs.org/a/1.0.0");
+    set_up_package(&store, &package_folder, &[("index.js", "export {};")]);
+    let unused_digest = store.add(b"unused", false).unwrap();
+    assert_eq!(store.remove_unused().unwrap(), 6);
+    assert!(!store.file_path(&unused_digest).exists());
+    assert!(store.file_path(&digest_bytes(b"export {};")).exists());
+
+    fs::remove_dir_all(&package_folder).unwrap();
+    assert_eq!(store.remove_unused().unwrap(), 10);
+    assert_eq!(
+      fs::read_dir(
+        temp_dir
+          .path()
+          .join(CONTENT_STORE_FOLDER_NAME)
+          .join("sha512")
+      )
+      .unwrap()
+      .count(),
+      0
+    );
+  }
+}
@@ -2,6 +2,7 @@
 
 use std::collections::HashSet;
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -14,17 +15,20 @@ use tar::Archive;
 use tar::EntryType;
 
 use super::cache::with_folder_sync_lock;
+use super::store::ContentStore;
+use super::store::PackageIntegrity;
 
 pub fn verify_and_extract_tarball(
   package: &NpmPackageNv,
   data: &[u8],
   dist_info: &NpmPackageVersionDistInfo,
   output_folder: &Path,
+  store: &ContentStore,
 ) -> Result<(), AnyError> {
   verify_tarball_integrity(package, data, &dist_info.integrity())?;
 
   with_folder_sync_lock(package, output_folder, || {
-    extract_tarball(data, output_folder)
+    extract_tarball(data, &dist_info.integrity(), output_folder, store)
   })
 }
 
@@ -69,14 +73,24 @@ fn verify_tarball_integrity(
   Ok(())
 }
 
-fn extract_tarball(data: &[u8], output_folder: &Path) -> Result<(), AnyError> {
+/// Extracts the files of the tarball into the store and hard links them
+/// into the output folder, along with a manifest of their digests and the
+/// integrity of the tarball.
+fn extract_tarball(
+  data: &[u8],
+  tarball_integrity: &str,
+  output_folder: &Path,
+  store: &ContentStore,
+) -> Result<(), AnyError> {
   fs::create_dir_all(output_folder)?;
   let output_folder = fs::canonicalize(output_folder)?;
   let tar = GzDecoder::new(data);
   let mut archive = Archive::new(tar);
-  archive.set_overwrite(true);
-  archive.set_preserve_permissions(true);
   let mut created_dirs = HashSet::new();
+  let mut integrity = PackageIntegrity {
+    tarball_integrity: Some(tarball_integrity.to_string()),
+    ..Default::default()
+  };
 
   for entry in archive.entries()? {
     let mut entry = entry?;
@@ -91,7 +105,7 @@ fn extract_tarball(data: &[u8], output_folder: &Path) -> Result<(), AnyError> {
 
     // skip the first component which will be either "package" or the name of the package
     let relative_path = path.components().skip(1).collect::<PathBuf>();
-    let absolute_path = output_folder.join(relative_path);
+    let absolute_path = output_folder.join(&relative_path);
     let dir_path = if entry_type == EntryType::Directory {
       absolute_path.as_path()
     } else {
@@ -107,17 +121,28 @@ fn extract_tarball(data: &[u8], output_folder: &Path) -> Result<(), AnyError> {
         )
       }
     }
-    if entry.header().entry_type() == EntryType::Regular {
-      entry.unpack(&absolute_path)?;
+    if entry_type == EntryType::Regular {
+      let executable = entry.header().mode()? & 0o111 != 0;
+      let mut data = Vec::with_capacity(entry.size() as usize);
+      entry.read_to_end(&mut data)?;
+      let digest = store.add(&data, executable)?;
+      store.link(&digest, &absolute_path)?;
+      let relative_path = relative_path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+      integrity.files.insert(relative_path, digest);
     }
   }
-  Ok(())
+  integrity.write(&output_folder)
 }
 
 #[cfg(test)]
 mod test {
   use deno_semver::Version;
 
+  use super::super::store::PackageVerification;
   use super::*;
 
   #[test]
@@ -162,4 +187,47 @@ mod test {
     )
     .is_ok());
   }
+
+  fn create_tarball(files: &[(&str, &str)]) -> Vec<u8> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    let mut builder =
+      tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, text) in files {
+      let mut header = tar::Header::new_gnu();
+      header.set_size(text.len() as u64);
+      header.set_mode(0o644);
+      header.set_cksum();
+      builder
+        .append_data(&mut header, path, text.as_bytes())
+        .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+  }
+
+  #[test]
+  fn extracts_into_store() {
+    let temp_dir = test_util::TempDir::new();
+    let store = ContentStore::new(temp_dir.path());
+    let data = create_tarball(&[
+      ("package/package.json", "{}"),
+      ("package/lib/index.js", "export {};"),
+    ]);
+    let output_folder = temp_dir.path().join("registry.npmjs.org/a/1.0.0");
+    extract_tarball(&data, "sha512-abc", &output_folder, &store).unwrap();
+    assert_eq!(
+      temp_dir.read_to_string("registry.npmjs.org/a/1.0.0/lib/index.js"),
+      "export {};"
+    );
+    let integrity = PackageIntegrity::read(&output_folder).unwrap().unwrap();
+    assert_eq!(
+      integrity.files.keys().collect::<Vec<_>>(),
+      vec!["lib/index.js", "package.json"]
+    );
+    assert_eq!(integrity.tarball_integrity.as_deref(), Some("sha512-abc"));
+    assert_eq!(
+      store.verify_package_folder(&output_folder, true).unwrap(),
+      PackageVerification::Valid
+    );
+  }
 }
@@ -0,0 +1,512 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_ast::swc::ast::Decl;
+use deno_ast::swc::ast::ExportSpecifier;
+use deno_ast::swc::ast::ModuleDecl;
+use deno_ast::swc::ast::ModuleExportName;
+use deno_ast::swc::ast::ModuleItem;
+use deno_ast::swc::ast::ObjectPatProp;
+use deno_ast::swc::ast::Param;
+use deno_ast::swc::ast::Pat;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParseParams;
+use deno_ast::SourceTextInfo;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_semver::npm::NpmPackageNv;
+use walkdir::WalkDir;
+
+use super::CliNpmResolver;
+
+/// The file in a package folder that lists the declaration files which were
+/// synthesized for the package.
+const SYNTHESIZED_TYPES_MARKER: &str = ".deno_synthesized_types";
+
+const DECLARATION_HEADER: &str =
+  "// Synthesized by Deno because this package has no types. The exports are
+// typed as `any`, so these declarations only describe what the modules export.
+";
+
+/// Writes declaration files next to the modules of the npm packages in the
+/// resolution that don't ship types and have no `@types` package, so that
+/// importing them is typed by their exports instead of being `any`. Returns
+/// the packages types were synthesized for.
+pub fn synthesize_npm_types(
+  npm_resolver: &CliNpmResolver,
+) -> Result<Vec<NpmPackageNv>, AnyError> {
+  let packages = npm_resolver.snapshot().all_packages();
+  let package_names = packages
+    .iter()
+    .map(|package| package.pkg_id.nv.name.as_str())
+    .collect::<HashSet<_>>();
+  let mut synthesized = Vec::new();
+  for package in &packages {
+    let nv = &package.pkg_id.nv;
+    if nv.name.starts_with("@types/")
+      || package_names.contains(types_package_name(&nv.name).as_str())
+    {
+      continue;
+    }
+    let package_folder =
+      npm_resolver.resolve_pkg_folder_from_pkg_id(&package.pkg_id)?;
+    // packages for other systems aren't in the cache
+    if !package_folder.exists() {
+      continue;
+    }
+    if synthesize_package_types(&package_folder)? {
+      synthesized.push(nv.clone());
+    }
+  }
+  Ok(synthesized)
+}
+
+/// The name of the DefinitelyTyped package of a package, eg.
+/// `@types/scope__name` for `@scope/name`.
+fn types_package_name(name: &str) -> String {
+  match name.strip_prefix('@') {
+    Some(scoped_name) => format!("@types/{}", scoped_name.replace('/', "__")),
+    None => format!("@types/{name}"),
+  }
+}
+
+/// Synthesizes the declaration files of the modules of a package, unless it
+/// has types or they were synthesized before.
+fn synthesize_package_types(package_folder: &Path) -> Result<bool, AnyError> {
+  if package_folder.join(SYNTHESIZED_TYPES_MARKER).exists() {
+    return Ok(false);
+  }
+  let package_json =
+    std::fs::read_to_string(package_folder.join("package.json"))
+      .ok()
+      .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+      .unwrap_or(Value::Null);
+  if package_json.get("types").is_some()
+    || package_json.get("typings").is_some()
+    || package_json.get("exports").map(has_types_condition) == Some(true)
+  {
+    return Ok(false);
+  }
+  let is_esm_package =
+    package_json.get("type").and_then(|t| t.as_str()) == Some("module");
+
+  let mut modules = Vec::new();
+  let walker = WalkDir::new(package_folder)
+    .into_iter()
+    .filter_entry(|entry| entry.file_name() != "node_modules");
+  for entry in walker.filter_map(|entry| entry.ok()) {
+    if !entry.file_type().is_file() {
+      continue;
+    }
+    let path = entry.path();
+    let file_name = entry.file_name().to_string_lossy();
+    if file_name.ends_with(".d.ts")
+      || file_name.ends_with(".d.mts")
+      || file_name.ends_with(".d.cts")
+    {
+      // the package ships types
+      return Ok(false);
+    }
+    if let Some(declaration_path) = declaration_path(path) {
+      modules.push((path.to_path_buf(), declaration_path));
+    }
+  }
+
+  let mut declaration_files = Vec::new();
+  for (path, declaration_path) in modules {
+    let is_esm = match path.extension().and_then(|ext| ext.to_str()) {
+      Some("mjs") => true,
+      Some("cjs") => false,
+      _ => is_esm_package,
+    };
+    let source = match std::fs::read_to_string(&path) {
+      Ok(source) => source,
+      Err(_) => continue,
+    };
+    match module_declaration(&path, source, is_esm) {
+      Ok(declaration) => {
+        std::fs::write(&declaration_path, declaration)?;
+        declaration_files.push(declaration_path);
+      }
+      Err(err) => {
+        // best effort, the module stays untyped
+        log::debug!(
+          "Failed to synthesize types for '{}': {:#}",
+          path.display(),
+          err
+        );
+      }
+    }
+  }
+  let marker_text = declaration_files
+    .iter()
+    .filter_map(|path| path.strip_prefix(package_folder).ok())
+    .map(|path| format!("{}\n", path.display()))
+    .collect::<String>();
+  std::fs::write(package_folder.join(SYNTHESIZED_TYPES_MARKER), marker_text)?;
+  Ok(!declaration_files.is_empty())
+}
+
+/// Whether the `exports` of a package.json have a `types` condition.
+fn has_types_condition(exports: &Value) -> bool {
+  match exports {
+    Value::Object(map) => map
+      .iter()
+      .any(|(key, value)| key == "types" || has_types_condition(value)),
+    Value::Array(values) => values.iter().any(has_types_condition),
+    _ => false,
+  }
+}
+
+/// The declaration file TypeScript looks for next to a JavaScript module.
+fn declaration_path(path: &Path) -> Option<PathBuf> {
+  let declaration_ext = match path.extension()?.to_str()? {
+    "js" => "d.ts",
+    "mjs" => "d.mts",
+    "cjs" => "d.cts",
+    _ => return None,
+  };
+  Some(path.with_extension(declaration_ext))
+}
+
+/// Creates the declarations of the exports of a module.
+fn module_declaration(
+  path: &Path,
+  source: String,
+  is_esm: bool,
+) -> Result<String, AnyError> {
+  let specifier = ModuleSpecifier::from_file_path(path)
+    .map_err(|_| deno_core::anyhow::anyhow!("Invalid path"))?;
+  let params = ParseParams {
+    specifier: specifier.to_string(),
+    text_info: SourceTextInfo::from_string(source),
+    media_type: MediaType::from_path(path),
+    capture_tokens: true,
+    scope_analysis: false,
+    maybe_syntax: None,
+  };
+  let mut text = DECLARATION_HEADER.to_string();
+  if is_esm {
+    let parsed_source = deno_ast::parse_module(params)?;
+    text.push_str(&esm_declarations(&parsed_source.module().body));
+  } else {
+    let analysis = deno_ast::parse_script(params)?.analyze_cjs();
+    for reexport in &analysis.reexports {
+      text.push_str(&format!("export * from {};\n", quote(reexport)));
+    }
+    // the default export is `module.exports`
+    text.push_str("declare const _default: any;\nexport default _default;\n");
+    for name in &analysis.exports {
+      if name != "default" && is_identifier(name) {
+        text.push_str(&format!("export declare const {name}: any;\n"));
+      }
+    }
+  }
+  Ok(text)
+}
+
+fn esm_declarations(body: &[ModuleItem]) -> String {
+  let mut text = String::new();
+  let mut declared_locals = HashSet::new();
+  for item in body {
+    let module_decl = match item {
+      ModuleItem::ModuleDecl(module_decl) => module_decl,
+      ModuleItem::Stmt(_) => continue,
+    };
+    match module_decl {
+      ModuleDecl::ExportDecl(export_decl) => match &export_decl.decl {
+        Decl::Fn(fn_decl) => text.push_str(&format!(
+          "export declare function {}({}): any;\n",
+          fn_decl.ident.sym,
+          params_declaration(&fn_decl.function.params)
+        )),
+        Decl::Class(class_decl) => text.push_str(&format!(
+          "export declare class {} {{\n  constructor(...args: any[]);\n  [key: string]: any;\n}}\n",
+          class_decl.ident.sym
+        )),
+        Decl::Var(var_decl) => {
+          for declarator in &var_decl.decls {
+            let mut names = Vec::new();
+            binding_names(&declarator.name, &mut names);
+            for name in names {
+              text.push_str(&format!("export declare const {name}: any;\n"));
+            }
+          }
+        }
+        _ => {}
+      },
+      ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_) => {
+        text
+          .push_str("declare const _default: any;\nexport default _default;\n");
+      }
+      ModuleDecl::ExportNamed(named_export) => {
+        for specifier in &named_export.specifiers {
+          match (specifier, &named_export.src) {
+            (ExportSpecifier::Namespace(namespace), Some(src)) => {
+              text.push_str(&format!(
+                "export * as {} from {};\n",
+                export_name(&namespace.name),
+                quote(&src.value)
+              ));
+            }
+            (ExportSpecifier::Named(named), Some(src)) => {
+              let orig = export_name(&named.orig);
+              let exported = named.exported.as_ref().map(export_name);
+              text.push_str(&format!(
+                "export {{ {}{} }} from {};\n",
+                orig,
+                exported.map(|name| format!(" as {name}")).unwrap_or_default(),
+                quote(&src.value)
+              ));
+            }
+            (ExportSpecifier::Named(named), None) => {
+              let orig = match &named.orig {
+                ModuleExportName::Ident(ident) => ident.sym.to_string(),
+                ModuleExportName::Str(_) => continue,
+              };
+              let exported = named
+                .exported
+                .as_ref()
+                .map(export_name)
+                .unwrap_or_else(|| orig.clone());
+              if declared_locals.insert(orig.clone()) {
+                text.push_str(&format!("declare const {orig}: any;\n"));
+              }
+              text.push_str(&format!("export {{ {orig} as {exported} }};\n"));
+            }
+            _ => {}
+          }
+        }
+      }
+      ModuleDecl::ExportAll(export_all) => {
+        text.push_str(&format!(
+          "export * from {};\n",
+          quote(&export_all.src.value)
+        ));
+      }
+      _ => {}
+    }
+  }
+  text
+}
+
+fn params_declaration(params: &[Param]) -> String {
+  params
+    .iter()
+    .enumerate()
+    .map(|(i, param)| match &param.pat {
+      Pat::Ident(ident) => format!("{}?: any", ident.id.sym),
+      Pat::Assign(assign) => match &*assign.left {
+        Pat::Ident(ident) => format!("{}?: any", ident.id.sym),
+        _ => format!("arg{i}?: any"),
+      },
+      Pat::Rest(rest) => match &*rest.arg {
+        Pat::Ident(ident) => format!("...{}: any[]", ident.id.sym),
+        _ => "...args: any[]".to_string(),
+      },
+      _ => format!("arg{i}?: any"),
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+fn binding_names(pat: &Pat, names: &mut Vec<String>) {
+  match pat {
+    Pat::Ident(ident) => names.push(ident.id.sym.to_string()),
+    Pat::Array(array) => {
+      for elem in array.elems.iter().flatten() {
+        binding_names(elem, names);
+      }
+    }
+    Pat::Object(object) => {
+      for prop in &object.props {
+        match prop {
+          ObjectPatProp::KeyValue(key_value) => {
+            binding_names(&key_value.value, names)
+          }
+          ObjectPatProp::Assign(assign) => {
+            names.push(assign.key.sym.to_string())
+          }
+          ObjectPatProp::Rest(rest) => binding_names(&rest.arg, names),
+        }
+      }
+    }
+    Pat::Assign(assign) => binding_names(&assign.left, names),
+    Pat::Rest(rest) => binding_names(&rest.arg, names),
+    _ => {}
+  }
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+  match name {
+    ModuleExportName::Ident(ident) => ident.sym.to_string(),
+    ModuleExportName::Str(str) => quote(&str.value),
+  }
+}
+
+fn quote(text: &str) -> String {
+  serde_json::to_string(text).unwrap()
+}
+
+fn is_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+  match chars.next() {
+    Some(c) if c.is_alphabetic() || c == '_' || c == '$' => {}
+    _ => return false,
+  }
+  chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+    && !matches!(
+      name,
+      "break"
+        | "case"
+        | "catch"
+        | "class"
+        | "const"
+        | "continue"
+        | "debugger"
+        | "delete"
+        | "do"
+        | "else"
+        | "enum"
+        | "export"
+        | "extends"
+        | "false"
+        | "finally"
+        | "for"
+        | "function"
+        | "if"
+        | "import"
+        | "in"
+        | "instanceof"
+        | "new"
+        | "null"
+        | "return"
+        | "super"
+        | "switch"
+        | "this"
+        | "throw"
+        | "true"
+        | "try"
+        | "typeof"
+        | "var"
+        | "void"
+        | "while"
+        | "with"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use test_util::TempDir;
+
+  #[test]
+  fn esm_module_declaration() {
+    let declaration = module_declaration(
+      Path::new("/pkg/index.mjs"),
+      r#"
+import { helper } from "./helper.mjs";
+export function add(a, b = 1, ...rest) { return a + b; }
+export class Client {}
+export const { x, y: [z] } = helper(), version = "1.0.0";
+const local = 1;
+export { local, local as renamed };
+export { default as helper, other } from "./helper.mjs";
+export * from "./more.mjs";
+export default function () {}
+"#
+      .to_string(),
+      true,
+    )
+    .unwrap();
+    assert_eq!(
+      declaration.strip_prefix(DECLARATION_HEADER).unwrap(),
+      r#"export declare function add(a?: any, b?: any, ...rest: any[]): any;
+export declare class Client {
+  constructor(...args: any[]);
+  [key: string]: any;
+}
+export declare const x: any;
+export declare const z: any;
+export declare const version: any;
+declare const local: any;
+export { local as local };
+export { local as renamed };
+export { default as helper } from "./helper.mjs";
+export { other } from "./helper.mjs";
+export * from "./more.mjs";
+declare const _default: any;
+export default _default;
+"#
+    );
+  }
+
+  #[test]
+  fn cjs_module_declaration() {
+    let declaration = module_declaration(
+      Path::new("/pkg/index.js"),
+      r#"
+exports.add = function (a, b) { return a + b; };
+exports["not-an-identifier"] = 1;
+module.exports.delete = 2;
+module.exports = require("./other.js");
+"#
+      .to_string(),
+      false,
+    )
+    .unwrap();
+    assert_eq!(
+      declaration.strip_prefix(DECLARATION_HEADER).unwrap(),
+      r#"export * from "./other.js";
+declare const _default: any;
+export default _default;
+export declare const add: any;
+"#
+    );
+  }
+
+  #[test]
+  fn synthesize_types_of_package() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("untyped/lib");
+    temp_dir.write("untyped/package.json", r#"{ "main": "./lib/index.js" }"#);
+    temp_dir.write("untyped/lib/index.js", "exports.value = 1;");
+    temp_dir.write("untyped/lib/esm.mjs", "export const value = 1;");
+    let package_folder = temp_dir.path().join("untyped");
+    assert!(synthesize_package_types(&package_folder).unwrap());
+    assert!(package_folder.join("lib/index.d.ts").exists());
+    assert!(package_folder.join("lib/esm.d.mts").exists());
+    assert!(package_folder.join(SYNTHESIZED_TYPES_MARKER).exists());
+    // already synthesized
+    assert!(!synthesize_package_types(&package_folder).unwrap());
+
+    temp_dir.create_dir_all("typed");
+    temp_dir.write(
+      "typed/package.json",
+      r#"{ "exports": { ".": { "types": "./index.d.ts", "default": "./index.js" } } }"#,
+    );
+    temp_dir.write("typed/index.js", "exports.value = 1;");
+    let package_folder = temp_dir.path().join("typed");
+    assert!(!synthesize_package_types(&package_folder).unwrap());
+    assert!(!package_folder.join("index.d.ts").exists());
+
+    temp_dir.create_dir_all("shipped");
+    temp_dir.write("shipped/package.json", "{}");
+    temp_dir.write("shipped/index.js", "exports.value = 1;");
+    temp_dir.write("shipped/index.d.ts", "export const value: number;");
+    let package_folder = temp_dir.path().join("shipped");
+    assert!(!synthesize_package_types(&package_folder).unwrap());
+  }
+
+  #[test]
+  fn types_package_names() {
+    assert_eq!(types_package_name("chalk"), "@types/chalk");
+    assert_eq!(types_package_name("@scope/name"), "@types/scope__name");
+  }
+}
@@ -105,6 +105,7 @@ struct TestInfo<'s> {
   #[serde(default)]
   only: bool,
   location: TestLocation,
+  timeout: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -116,13 +117,18 @@ struct TestRegisterResult {
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
+/// Gets a test id that's unique in this process.
+pub(crate) fn next_test_id() -> usize {
+  NEXT_ID.fetch_add(1, Ordering::SeqCst)
+}
+
 #[op(v8)]
 fn op_register_test<'a>(
   scope: &mut v8::HandleScope<'a>,
   state: &mut OpState,
   info: TestInfo<'a>,
 ) -> Result<TestRegisterResult, AnyError> {
-  let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+  let id = next_test_id();
   let origin = state.borrow::<ModuleSpecifier>().to_string();
   let description = TestDescription {
     id,
@@ -131,6 +137,7 @@ fn op_register_test<'a>(
     only: info.only,
     origin: origin.clone(),
     location: info.location,
+    timeout: info.timeout,
   };
   let function: v8::Local<v8::Function> = info.function.v8_value.try_into()?;
   let function = v8::Global::new(scope, function);
@@ -172,7 +179,7 @@ fn op_register_test_step(
   state: &mut OpState,
   info: TestStepInfo,
 ) -> Result<TestRegisterResult, AnyError> {
-  let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+  let id = next_test_id();
   let origin = state.borrow::<ModuleSpecifier>().to_string();
   let description = TestStepDescription {
     id,
@@ -1,10 +1,12 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
 use deno_core::futures::future;
 use deno_core::futures::future::LocalBoxFuture;
 use deno_core::futures::FutureExt;
+use deno_core::parking_lot::Mutex;
 use deno_core::ModuleSpecifier;
 use deno_core::TaskQueue;
 use deno_graph::source::NpmPackageReqResolution;
@@ -14,14 +16,25 @@ use deno_graph::source::UnknownBuiltInNodeModuleError;
 use deno_graph::source::DEFAULT_JSX_IMPORT_SOURCE_MODULE;
 use deno_npm::registry::NpmRegistryApi;
 use deno_runtime::deno_node::is_builtin_node_module;
+use deno_runtime::deno_node::NodeResolutionMode;
+use deno_runtime::deno_node::NodeResolver;
+use deno_runtime::permissions::PermissionsContainer;
 use deno_semver::npm::NpmPackageReq;
+use deno_semver::npm::NpmPackageReqReference;
 use import_map::ImportMap;
 use std::sync::Arc;
 
+use crate::args::check_or_insert_locked_override;
 use crate::args::package_json::PackageJsonDeps;
 use crate::args::JsxImportSourceConfig;
+use crate::args::Lockfile;
+use crate::args::ModuleOverrides;
 use crate::args::PackageJsonDepsProvider;
+use crate::args::PreferVendor;
+use crate::cache::FastInsecureHasher;
+use crate::cache::ModuleGraphResolutions;
 use crate::npm::CliNpmRegistryApi;
+use crate::npm::CliNpmResolver;
 use crate::npm::NpmResolution;
 use crate::npm::PackageJsonDepsInstaller;
 use crate::util::sync::AtomicFlag;
@@ -95,6 +108,59 @@ impl MappedSpecifierResolver {
   }
 }
 
+/// Resolves npm specifiers and bare specifiers against an existing
+/// node_modules directory that deno doesn't manage (`--byonm`).
+#[derive(Debug)]
+pub struct ByonmResolver {
+  npm_resolver: Arc<CliNpmResolver>,
+  node_resolver: Arc<NodeResolver>,
+}
+
+impl ByonmResolver {
+  pub fn new(
+    npm_resolver: Arc<CliNpmResolver>,
+    node_resolver: Arc<NodeResolver>,
+  ) -> Self {
+    Self {
+      npm_resolver,
+      node_resolver,
+    }
+  }
+
+  fn resolve_npm_req_ref(
+    &self,
+    req_ref: &NpmPackageReqReference,
+    referrer: &ModuleSpecifier,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    // ensure the installed package satisfies the version requirement
+    self
+      .npm_resolver
+      .resolve_byonm_pkg_folder_from_pkg_req(&req_ref.req, referrer)?;
+    let specifier = match &req_ref.sub_path {
+      Some(sub_path) => format!("{}/{}", req_ref.req.name, sub_path),
+      None => req_ref.req.name.clone(),
+    };
+    self.resolve_bare_specifier(&specifier, referrer)
+  }
+
+  fn resolve_bare_specifier(
+    &self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let resolution = self.node_resolver.resolve(
+      specifier,
+      referrer,
+      NodeResolutionMode::Execution,
+      &PermissionsContainer::allow_all(),
+    )?;
+    match resolution {
+      Some(resolution) => Ok(resolution.into_url()),
+      None => bail!("Could not resolve '{}' from '{}'.", specifier, referrer),
+    }
+  }
+}
+
 /// A resolver that takes care of resolution, taking into account loaded
 /// import map, JSX settings.
 #[derive(Debug)]
@@ -102,12 +168,16 @@ pub struct CliGraphResolver {
   mapped_specifier_resolver: MappedSpecifierResolver,
   maybe_default_jsx_import_source: Option<String>,
   maybe_jsx_import_source_module: Option<String>,
+  maybe_module_overrides: Option<ModuleOverrides>,
+  maybe_prefer_vendor: Option<PreferVendor>,
+  maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
   no_npm: bool,
   npm_registry_api: Arc<CliNpmRegistryApi>,
   npm_resolution: Arc<NpmResolution>,
   package_json_deps_installer: Arc<PackageJsonDepsInstaller>,
   found_package_json_dep_flag: Arc<AtomicFlag>,
   sync_download_queue: Option<Arc<TaskQueue>>,
+  maybe_byonm_resolver: Option<ByonmResolver>,
 }
 
 impl Default for CliGraphResolver {
@@ -127,25 +197,34 @@ impl Default for CliGraphResolver {
       },
       maybe_default_jsx_import_source: Default::default(),
       maybe_jsx_import_source_module: Default::default(),
+      maybe_module_overrides: Default::default(),
+      maybe_prefer_vendor: Default::default(),
+      maybe_lockfile: Default::default(),
       no_npm: false,
       npm_registry_api,
       npm_resolution,
       package_json_deps_installer: Default::default(),
       found_package_json_dep_flag: Default::default(),
       sync_download_queue: Self::create_sync_download_queue(),
+      maybe_byonm_resolver: None,
     }
   }
 }
 
 impl CliGraphResolver {
+  #[allow(clippy::too_many_arguments)]
   pub fn new(
     maybe_jsx_import_source_config: Option<JsxImportSourceConfig>,
     maybe_import_map: Option<Arc<ImportMap>>,
+    maybe_module_overrides: Option<ModuleOverrides>,
+    maybe_prefer_vendor: Option<PreferVendor>,
+    maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
     no_npm: bool,
     npm_registry_api: Arc<CliNpmRegistryApi>,
     npm_resolution: Arc<NpmResolution>,
     package_json_deps_provider: Arc<PackageJsonDepsProvider>,
     package_json_deps_installer: Arc<PackageJsonDepsInstaller>,
+    maybe_byonm_resolver: Option<ByonmResolver>,
   ) -> Self {
     Self {
       mapped_specifier_resolver: MappedSpecifierResolver {
@@ -157,12 +236,16 @@ impl CliGraphResolver {
         .and_then(|c| c.default_specifier.clone()),
       maybe_jsx_import_source_module: maybe_jsx_import_source_config
         .map(|c| c.module),
+      maybe_module_overrides,
+      maybe_prefer_vendor,
+      maybe_lockfile,
       no_npm,
       npm_registry_api,
       npm_resolution,
       package_json_deps_installer,
       found_package_json_dep_flag: Default::default(),
       sync_download_queue: Self::create_sync_download_queue(),
+      maybe_byonm_resolver,
     }
   }
 
@@ -182,6 +265,75 @@ impl CliGraphResolver {
     self
   }
 
+  /// Replaces the resolved specifier when it is overridden in the config
+  /// file, recording the override in the lockfile.
+  fn apply_module_overrides(
+    &self,
+    specifier: ModuleSpecifier,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let overrides = match &self.maybe_module_overrides {
+      Some(overrides) => overrides,
+      None => return Ok(specifier),
+    };
+    match overrides.resolve(&specifier)? {
+      Some(target) => {
+        if let Some(lockfile) = &self.maybe_lockfile {
+          check_or_insert_locked_override(
+            &mut lockfile.lock(),
+            &specifier,
+            &target,
+          )?;
+        }
+        log::debug!("Overriding {} with {}", specifier, target);
+        Ok(target)
+      }
+      None => Ok(specifier),
+    }
+  }
+
+  /// Replaces a remote specifier with its copy in the vendor directory when
+  /// resolving with `--prefer-vendor` and the module has been vendored.
+  fn apply_prefer_vendor(
+    &self,
+    specifier: ModuleSpecifier,
+    referrer: &ModuleSpecifier,
+  ) -> ModuleSpecifier {
+    let prefer_vendor = match &self.maybe_prefer_vendor {
+      Some(prefer_vendor) => prefer_vendor,
+      None => return specifier,
+    };
+    match prefer_vendor.resolve(&specifier, referrer) {
+      Some(local_specifier) => {
+        log::debug!("Using vendored {} for {}", local_specifier, specifier);
+        local_specifier
+      }
+      None => specifier,
+    }
+  }
+
+  /// Gets a hash of the configuration the resolutions depend on, which is
+  /// `None` when they can't be cached because they also depend on the file
+  /// system (eg. `--prefer-vendor`), the lockfile or the package.json.
+  pub fn resolutions_cache_hash(&self) -> Option<u64> {
+    if self.maybe_byonm_resolver.is_some()
+      || self.maybe_prefer_vendor.is_some()
+      || self.maybe_module_overrides.is_some()
+      || self
+        .mapped_specifier_resolver
+        .package_json_deps_provider
+        .deps()
+        .is_some()
+    {
+      return None;
+    }
+    let mut hasher = FastInsecureHasher::new();
+    if let Some(import_map) = &self.mapped_specifier_resolver.maybe_import_map {
+      hasher.write_str(import_map.base_url().as_str());
+      hasher.write_str(&import_map.to_json());
+    }
+    Some(hasher.finish())
+  }
+
   pub async fn top_level_package_json_install_if_necessary(
     &self,
   ) -> Result<(), AnyError> {
@@ -213,20 +365,93 @@ impl Resolver for CliGraphResolver {
     referrer: &ModuleSpecifier,
   ) -> Result<ModuleSpecifier, AnyError> {
     use MappedResolution::*;
-    match self
+    let specifier = match self
       .mapped_specifier_resolver
       .resolve(specifier, referrer)?
     {
-      ImportMap(specifier) => Ok(specifier),
+      ImportMap(specifier) => specifier,
       PackageJson(specifier) => {
         // found a specifier in the package.json, so mark that
         // we need to do an "npm install" later
         self.found_package_json_dep_flag.raise();
-        Ok(specifier)
+        specifier
+      }
+      None => match deno_graph::resolve_import(specifier, referrer) {
+        Ok(resolved) => resolved,
+        Err(err) => {
+          // bare specifiers are resolved from node_modules like node does
+          if let Some(byonm_resolver) = &self.maybe_byonm_resolver {
+            if let Ok(resolved) =
+              byonm_resolver.resolve_bare_specifier(specifier, referrer)
+            {
+              return Ok(resolved);
+            }
+          }
+          return Err(err.into());
+        }
+      },
+    };
+    if let Some(byonm_resolver) = &self.maybe_byonm_resolver {
+      if let Ok(req_ref) = NpmPackageReqReference::from_specifier(&specifier) {
+        return byonm_resolver.resolve_npm_req_ref(&req_ref, referrer);
       }
-      None => deno_graph::resolve_import(specifier, referrer)
-        .map_err(|err| err.into()),
     }
+    let specifier = self.apply_module_overrides(specifier)?;
+    Ok(self.apply_prefer_vendor(specifier, referrer))
+  }
+}
+
+/// Resolver of a module graph that reuses the resolutions cached by a
+/// previous build of the graph, recording every resolution it makes so they
+/// can be cached for the next build.
+#[derive(Debug)]
+pub struct CachedGraphResolver<'a> {
+  resolver: &'a CliGraphResolver,
+  cached: ModuleGraphResolutions,
+  resolved: Mutex<ModuleGraphResolutions>,
+}
+
+impl<'a> CachedGraphResolver<'a> {
+  pub fn new(
+    resolver: &'a CliGraphResolver,
+    cached: ModuleGraphResolutions,
+  ) -> Self {
+    Self {
+      resolver,
+      cached,
+      resolved: Default::default(),
+    }
+  }
+
+  /// The resolutions made while building the graph.
+  pub fn into_resolved(self) -> ModuleGraphResolutions {
+    self.resolved.into_inner()
+  }
+}
+
+impl<'a> Resolver for CachedGraphResolver<'a> {
+  fn default_jsx_import_source(&self) -> Option<String> {
+    self.resolver.default_jsx_import_source()
+  }
+
+  fn jsx_import_source_module(&self) -> &str {
+    self.resolver.jsx_import_source_module()
+  }
+
+  fn resolve(
+    &self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let resolved = match self.cached.get(specifier, referrer) {
+      Some(resolved) => resolved.clone(),
+      None => self.resolver.resolve(specifier, referrer)?,
+    };
+    self
+      .resolved
+      .lock()
+      .insert(specifier, referrer, resolved.clone());
+    Ok(resolved)
   }
 }
 
@@ -309,11 +534,23 @@ impl NpmResolver for CliGraphResolver {
       ));
     }
 
+    // packages from the lockfile are already resolved along with their
+    // dependencies
+    let is_resolved = self
+      .npm_resolution
+      .resolve_pkg_id_from_pkg_req(package_req)
+      .is_ok();
     let result = self
       .npm_resolution
       .resolve_package_req_as_pending(package_req);
     match result {
-      Ok(nv) => NpmPackageReqResolution::Ok(nv),
+      Ok(nv) => {
+        if !is_resolved {
+          // load the dependency tree while the graph is still being built
+          self.npm_registry_api.prefetch_dependencies(&nv);
+        }
+        NpmPackageReqResolution::Ok(nv)
+      }
       Err(err) => {
         if self.npm_registry_api.mark_force_reload() {
           log::debug!("Restarting npm specifier resolution to check for new registry information. Error: {:#}", err);
@@ -391,4 +628,32 @@ mod test {
     // non-existent bare specifier
     assert_eq!(resolve("non-existent", &deps).unwrap(), None);
   }
+
+  #[test]
+  fn test_cached_graph_resolver() {
+    let cli_resolver = CliGraphResolver::default();
+    let referrer = ModuleSpecifier::parse("file:///project/main.ts").unwrap();
+    let cached_specifier =
+      ModuleSpecifier::parse("https://deno.land/x/a/mod.ts").unwrap();
+    let mut cached = ModuleGraphResolutions::default();
+    cached.insert("a", &referrer, cached_specifier.clone());
+
+    let resolver = CachedGraphResolver::new(&cli_resolver, cached);
+    // cached resolutions are reused
+    assert_eq!(resolver.resolve("a", &referrer).unwrap(), cached_specifier);
+    // others are resolved
+    let resolved = resolver.resolve("./b.ts", &referrer).unwrap();
+    assert_eq!(resolved.as_str(), "file:///project/b.ts");
+    // errors aren't recorded
+    assert!(resolver.resolve("c", &referrer).is_err());
+
+    let resolved = resolver.into_resolved();
+    assert_eq!(resolved.get("a", &referrer), Some(&cached_specifier));
+    assert_eq!(
+      resolved.get("./b.ts", &referrer).map(|s| s.as_str()),
+      Some("file:///project/b.ts")
+    );
+    assert_eq!(resolved.get("c", &referrer), None);
+    assert_eq!(resolved.resolutions.len(), 1);
+  }
 }
@@ -1,6 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::env::current_exe;
 use std::io::Read;
 use std::io::Seek;
@@ -10,15 +11,18 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::futures::io::AllowStdIo;
+use deno_core::futures::io::Cursor;
 use deno_core::futures::AsyncReadExt;
 use deno_core::futures::AsyncSeekExt;
 use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_npm::registry::PackageDepNpmSchemeValueParseError;
 use deno_npm::resolution::SerializedNpmResolutionSnapshot;
+use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::permissions::PermissionsOptions;
 use deno_semver::npm::NpmPackageReq;
 use deno_semver::npm::NpmVersionReqSpecifierParseError;
@@ -33,12 +37,14 @@ use crate::args::CliOptions;
 use crate::args::CompileFlags;
 use crate::args::PackageJsonDepsProvider;
 use crate::cache::DenoDir;
+use crate::cache::CACHE_PERM;
 use crate::file_fetcher::FileFetcher;
 use crate::http_util::HttpClient;
 use crate::npm::CliNpmRegistryApi;
 use crate::npm::CliNpmResolver;
 use crate::npm::NpmCache;
 use crate::npm::NpmResolution;
+use crate::util::fs::atomic_write_file;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 
@@ -46,8 +52,16 @@ use super::virtual_fs::FileBackedVfs;
 use super::virtual_fs::VfsBuilder;
 use super::virtual_fs::VfsRoot;
 use super::virtual_fs::VirtualDirectory;
+use super::windows_resources::patch_windows_resources;
+use super::windows_resources::set_windows_gui_subsystem;
+use super::windows_resources::strip_windows_signature;
+use super::windows_resources::windows_signature_pos;
+use super::windows_resources::WindowsResources;
 
 const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
+/// The magic number zstd frames start with, which tells a compressed eszip
+/// apart from an uncompressed one (starting with `ESZIP_V2`).
+const ZSTD_MAGIC: &[u8; 4] = &[0x28, 0xb5, 0x2f, 0xfd];
 
 #[derive(Serialize, Deserialize)]
 enum SerializablePackageJsonDepValueParseError {
@@ -128,6 +142,7 @@ pub struct Metadata {
   pub argv: Vec<String>,
   pub unstable: bool,
   pub seed: Option<u64>,
+  pub heap_snapshot_on_oom: bool,
   pub permissions: PermissionsOptions,
   pub location: Option<Url>,
   pub v8_flags: Vec<String>,
@@ -139,17 +154,22 @@ pub struct Metadata {
   pub entrypoint: ModuleSpecifier,
   /// Whether this uses a node_modules directory (true) or the global cache (false).
   pub node_modules_dir: bool,
+  /// Custom conditions used when resolving the exports of npm packages.
+  pub node_conditions: Vec<String>,
   pub npm_snapshot: Option<SerializedNpmResolutionSnapshot>,
   pub package_json_deps: Option<SerializablePackageJsonDeps>,
+  /// Files imported with a `text` or `bytes` import assertion and the files
+  /// of `--include-asset`, which aren't stored in the eszip.
+  pub assets: BTreeMap<ModuleSpecifier, Vec<u8>>,
+  /// The file of `--log-file` that stdout and stderr are redirected to,
+  /// relative to the directory of the executable.
+  pub log_file: Option<String>,
 }
 
 pub fn load_npm_vfs(root_dir_path: PathBuf) -> Result<FileBackedVfs, AnyError> {
   let file_path = current_exe().unwrap();
   let mut file = std::fs::File::open(file_path)?;
-  file.seek(SeekFrom::End(-(TRAILER_SIZE as i64)))?;
-  let mut trailer = [0; TRAILER_SIZE];
-  file.read_exact(&mut trailer)?;
-  let trailer = Trailer::parse(&trailer)?.unwrap();
+  let trailer = read_trailer(&mut file)?.unwrap();
   file.seek(SeekFrom::Start(trailer.npm_vfs_pos))?;
   let mut vfs_data = vec![0; trailer.npm_vfs_len() as usize];
   file.read_exact(&mut vfs_data)?;
@@ -177,18 +197,32 @@ fn write_binary_bytes(
   eszip: eszip::EszipV2,
   npm_vfs: Option<&VirtualDirectory>,
   npm_files: &Vec<Vec<u8>>,
+  compress: bool,
 ) -> Result<(), AnyError> {
-  let metadata = serde_json::to_string(metadata)?.as_bytes().to_vec();
+  let metadata =
+    serde_json::to_string(&sort_object_keys(serde_json::to_value(metadata)?))?
+      .as_bytes()
+      .to_vec();
   let npm_vfs = serde_json::to_string(&npm_vfs)?.as_bytes().to_vec();
-  let eszip_archive = eszip.into_bytes();
+  let eszip_archive = if compress {
+    zstd::bulk::compress(&eszip.into_bytes(), 3)?
+  } else {
+    eszip.into_bytes()
+  };
 
   writer.write_all(&original_bin)?;
   writer.write_all(&eszip_archive)?;
   writer.write_all(&metadata)?;
   writer.write_all(&npm_vfs)?;
+  let mut len = original_bin.len() + eszip_archive.len();
+  len += metadata.len() + npm_vfs.len();
   for file in npm_files {
     writer.write_all(file)?;
+    len += file.len();
   }
+  // align the end of the file to 64 bits, which is where signtool appends the
+  // signature of Windows executables, so the trailer stays right before it
+  writer.write_all(&vec![0; (8 - len % 8) % 8])?;
 
   // write the trailer, which includes the positions
   // of the data blocks in the file
@@ -209,57 +243,99 @@ fn write_binary_bytes(
   Ok(())
 }
 
+/// Sorts the keys of the JSON objects, since some of the serialized maps are
+/// hash maps, which would make the output differ between two compiles.
+fn sort_object_keys(value: serde_json::Value) -> serde_json::Value {
+  match value {
+    serde_json::Value::Object(map) => {
+      let mut entries = map.into_iter().collect::<Vec<_>>();
+      entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+      serde_json::Value::Object(
+        entries
+          .into_iter()
+          .map(|(key, value)| (key, sort_object_keys(value)))
+          .collect(),
+      )
+    }
+    serde_json::Value::Array(items) => serde_json::Value::Array(
+      items.into_iter().map(sort_object_keys).collect(),
+    ),
+    value => value,
+  }
+}
+
 pub fn is_standalone_binary(exe_path: &Path) -> bool {
   let Ok(mut output_file) = std::fs::File::open(exe_path) else {
     return false;
   };
-  if output_file
-    .seek(SeekFrom::End(-(TRAILER_SIZE as i64)))
-    .is_err()
-  {
-    // This seek may fail because the file is too small to possibly be
-    // `deno compile` output.
-    return false;
+  matches!(read_trailer(&mut output_file), Ok(Some(_)))
+}
+
+/// Reads the trailer at the end of the file, or right before the signature
+/// when a Windows executable was signed after `deno compile`.
+fn read_trailer(file: &mut std::fs::File) -> Result<Option<Trailer>, AnyError> {
+  let file_len = file.seek(SeekFrom::End(0))?;
+  let mut ends = vec![file_len];
+  let mut header = vec![0; file_len.min(4096) as usize];
+  file.seek(SeekFrom::Start(0))?;
+  file.read_exact(&mut header)?;
+  if let Some(signature_pos) = windows_signature_pos(&header) {
+    ends.push(signature_pos);
   }
-  let mut trailer = [0; TRAILER_SIZE];
-  if output_file.read_exact(&mut trailer).is_err() {
-    return false;
-  };
-  let (magic_trailer, _) = trailer.split_at(8);
-  magic_trailer == MAGIC_TRAILER
+
+  for end in ends {
+    // the file may be too small to possibly be `deno compile` output
+    if end < TRAILER_SIZE as u64 {
+      continue;
+    }
+    file.seek(SeekFrom::Start(end - TRAILER_SIZE as u64))?;
+    let mut trailer = [0; TRAILER_SIZE];
+    file.read_exact(&mut trailer)?;
+    if let Some(trailer) = Trailer::parse(&trailer)? {
+      return Ok(Some(trailer));
+    }
+  }
+  Ok(None)
 }
 
 /// This function will try to run this binary as a standalone binary
 /// produced by `deno compile`. It determines if this is a standalone
 /// binary by skipping over the trailer width at the end of the file,
-/// then checking for the magic trailer string `d3n0l4nd`. If found,
-/// the bundle is executed. If not, this function exits with `Ok(None)`.
+/// or before its signature, then checking for the magic trailer string
+/// `d3n0l4nd`. If found, the bundle is executed. If not, this function
+/// exits with `Ok(None)`.
 pub async fn extract_standalone(
   exe_path: &Path,
   cli_args: Vec<String>,
 ) -> Result<Option<(Metadata, eszip::EszipV2)>, AnyError> {
-  let file = std::fs::File::open(exe_path)?;
-
-  let mut bufreader =
-    deno_core::futures::io::BufReader::new(AllowStdIo::new(file));
-
-  let _trailer_pos = bufreader
-    .seek(SeekFrom::End(-(TRAILER_SIZE as i64)))
-    .await?;
-  let mut trailer = [0; TRAILER_SIZE];
-  bufreader.read_exact(&mut trailer).await?;
-  let trailer = match Trailer::parse(&trailer)? {
+  let mut file = std::fs::File::open(exe_path)?;
+  let trailer = match read_trailer(&mut file)? {
     None => return Ok(None),
     Some(trailer) => trailer,
   };
 
+  let mut bufreader =
+    deno_core::futures::io::BufReader::new(AllowStdIo::new(file));
+
   bufreader.seek(SeekFrom::Start(trailer.eszip_pos)).await?;
 
-  let (eszip, loader) = eszip::EszipV2::parse(bufreader)
+  let mut eszip_archive = vec![0; trailer.eszip_len() as usize];
+  bufreader
+    .read_exact(&mut eszip_archive)
     .await
-    .context("Failed to parse eszip header")?;
+    .context("Failed to read eszip from the current executable")?;
+  if eszip_archive.starts_with(ZSTD_MAGIC) {
+    eszip_archive = zstd::stream::decode_all(eszip_archive.as_slice())
+      .context("Failed to decompress eszip")?;
+  }
 
-  let mut bufreader = loader.await.context("Failed to parse eszip archive")?;
+  let (eszip, loader) = eszip::EszipV2::parse(
+    deno_core::futures::io::BufReader::new(Cursor::new(eszip_archive)),
+  )
+  .await
+  .context("Failed to parse eszip header")?;
+
+  loader.await.context("Failed to parse eszip archive")?;
 
   bufreader
     .seek(SeekFrom::Start(trailer.metadata_pos))
@@ -310,6 +386,10 @@ impl Trailer {
     }))
   }
 
+  pub fn eszip_len(&self) -> u64 {
+    self.metadata_pos - self.eszip_pos
+  }
+
   pub fn metadata_len(&self) -> u64 {
     self.npm_vfs_pos - self.metadata_pos
   }
@@ -372,23 +452,39 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     }
   }
 
+  #[allow(clippy::too_many_arguments)]
   pub async fn write_bin(
     &self,
     writer: &mut impl Write,
     eszip: eszip::EszipV2,
+    assets: &BTreeSet<ModuleSpecifier>,
     module_specifier: &ModuleSpecifier,
     compile_flags: &CompileFlags,
+    windows_resources: Option<&WindowsResources>,
     cli_options: &CliOptions,
   ) -> Result<(), AnyError> {
     // Select base binary based on target
-    let original_binary =
+    let mut original_binary =
       self.get_base_binary(compile_flags.target.clone()).await?;
+    if compile_flags.is_windows_target() {
+      // the signature of the base binary doesn't cover what's appended to it,
+      // and it would hide the signature of the compiled executable
+      original_binary = strip_windows_signature(original_binary)?;
+    }
+    if let Some(windows_resources) = windows_resources {
+      original_binary =
+        patch_windows_resources(original_binary, windows_resources)?;
+    }
+    if compile_flags.no_terminal {
+      original_binary = set_windows_gui_subsystem(original_binary)?;
+    }
 
     self
       .write_standalone_binary(
         writer,
         original_binary,
         eszip,
+        assets,
         module_specifier,
         cli_options,
         compile_flags,
@@ -400,12 +496,18 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     &self,
     target: Option<String>,
   ) -> Result<Vec<u8>, AnyError> {
-    if target.is_none() {
-      let path = std::env::current_exe()?;
-      return Ok(std::fs::read(path)?);
+    let target = match target {
+      Some(target) if target != env!("TARGET") => target,
+      // the current executable is the base binary of its own target
+      _ => {
+        let path = std::env::current_exe()?;
+        return Ok(std::fs::read(path)?);
+      }
+    };
+    if crate::version::is_canary() && target == "aarch64-apple-darwin" {
+      bail!("Canary builds are not available for {}", target);
     }
 
-    let target = target.unwrap_or_else(|| env!("TARGET").to_string());
     let binary_name = format!("deno-{target}.zip");
 
     let binary_path_suffix = if crate::version::is_canary() {
@@ -461,17 +563,20 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     std::fs::create_dir_all(output_directory)?;
     let output_path = output_directory.join(binary_path_suffix);
     std::fs::create_dir_all(output_path.parent().unwrap())?;
-    tokio::fs::write(output_path, bytes).await?;
+    // write it atomically so an interrupted download isn't cached
+    atomic_write_file(&output_path, bytes, CACHE_PERM)?;
     Ok(())
   }
 
   /// This functions creates a standalone deno binary by appending a bundle
   /// and magic trailer to the currently executing binary.
+  #[allow(clippy::too_many_arguments)]
   async fn write_standalone_binary(
     &self,
     writer: &mut impl Write,
     original_bin: Vec<u8>,
     eszip: eszip::EszipV2,
+    assets: &BTreeSet<ModuleSpecifier>,
     entrypoint: &ModuleSpecifier,
     cli_options: &CliOptions,
     compile_flags: &CompileFlags,
@@ -490,18 +595,39 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       .map(|import_map| (import_map.base_url().clone(), import_map.to_json()));
     let (npm_snapshot, npm_vfs, npm_files) = if self.resolution.has_packages() {
       let (root_dir, files) = self.build_vfs()?.into_dir_and_files();
-      let snapshot = self.resolution.serialized_snapshot();
+      let mut snapshot = self.resolution.serialized_snapshot();
+      snapshot
+        .packages
+        .sort_by_cached_key(|package| package.pkg_id.as_serialized());
       (Some(snapshot), Some(root_dir), files)
     } else {
       (None, None, Vec::new())
     };
+    let mut asset_bytes = BTreeMap::new();
+    for specifier in assets {
+      // read local files as is, since they may not be text
+      let path = match specifier.to_file_path() {
+        Ok(path) => path,
+        Err(()) => {
+          self
+            .file_fetcher
+            .fetch(specifier, PermissionsContainer::allow_all())
+            .await?
+            .local
+        }
+      };
+      let bytes = std::fs::read(&path)
+        .with_context(|| format!("Reading asset: {specifier}"))?;
+      asset_bytes.insert(specifier.clone(), bytes);
+    }
 
     let metadata = Metadata {
       argv: compile_flags.args.clone(),
       unstable: cli_options.unstable(),
       seed: cli_options.seed(),
+      heap_snapshot_on_oom: cli_options.heap_snapshot_on_oom(),
       location: cli_options.location_flag().clone(),
-      permissions: cli_options.permissions_options(),
+      permissions: cli_options.permissions_options()?,
       v8_flags: cli_options.v8_flags().clone(),
       unsafely_ignore_certificate_errors: cli_options
         .unsafely_ignore_certificate_errors()
@@ -512,11 +638,14 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       entrypoint: entrypoint.clone(),
       maybe_import_map,
       node_modules_dir: self.npm_resolver.node_modules_path().is_some(),
+      node_conditions: cli_options.resolve_node_conditions()?,
       npm_snapshot,
       package_json_deps: self
         .package_json_deps_provider
         .deps()
         .map(|deps| SerializablePackageJsonDeps::from_deps(deps.clone())),
+      assets: asset_bytes,
+      log_file: compile_flags.log_file.clone(),
     };
 
     write_binary_bytes(
@@ -526,6 +655,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       eszip,
       npm_vfs.as_ref(),
       &npm_files,
+      !compile_flags.no_compress,
     )
   }
 
@@ -539,11 +669,22 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       // but also don't make this dependent on the registry url
       let registry_url = self.npm_api.base_url();
       let root_path = self.npm_cache.registry_folder(registry_url);
-      let mut builder = VfsBuilder::new(root_path);
-      for package in self.resolution.all_packages() {
+      let mut builder = VfsBuilder::new(root_path.clone());
+      let mut packages = self.resolution.all_packages();
+      packages.sort_by_cached_key(|package| package.pkg_id.as_serialized());
+      for package in packages {
         let folder = self
           .npm_resolver
           .resolve_pkg_folder_from_pkg_id(&package.pkg_id)?;
+        if !folder.starts_with(&root_path) {
+          bail!(
+            concat!(
+              "Compiling npm package {} from the registry of its scope in ",
+              ".npmrc requires a node_modules folder. Use --node-modules-dir."
+            ),
+            package.pkg_id.nv,
+          );
+        }
         builder.add_dir_recursive(&folder)?;
       }
       // overwrite the root directory's name to obscure the user's registry url
@@ -1,9 +1,12 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::get_root_cert_store;
+use crate::args::inherited_permissions_from_env;
 use crate::args::npm_pkg_req_ref_to_binary_command;
 use crate::args::CaData;
 use crate::args::CacheSetting;
+use crate::args::NodeModulesLayout;
+use crate::args::NpmCopyStrategy;
 use crate::args::PackageJsonDepsProvider;
 use crate::args::StorageKeyResolver;
 use crate::cache::Caches;
@@ -19,6 +22,7 @@ use crate::npm::CliNpmRegistryApi;
 use crate::npm::CliNpmResolver;
 use crate::npm::NpmCache;
 use crate::npm::NpmResolution;
+use crate::npm::DEFAULT_NPM_DOWNLOAD_CONCURRENCY;
 use crate::resolver::MappedSpecifierResolver;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
@@ -44,10 +48,16 @@ use deno_runtime::deno_node::NodeResolver;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use deno_runtime::deno_web::BlobStore;
+use deno_runtime::permissions::narrow_to_inherited_permissions;
+use deno_runtime::permissions::set_prompter;
+use deno_runtime::permissions::DenyPrompter;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
+use deno_runtime::permissions::PermissionsOptions;
 use deno_semver::npm::NpmPackageReqReference;
 use import_map::parse_from_json;
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -55,10 +65,12 @@ use std::sync::Arc;
 mod binary;
 mod file_system;
 mod virtual_fs;
+mod windows_resources;
 
 pub use binary::extract_standalone;
 pub use binary::is_standalone_binary;
 pub use binary::DenoCompileBinaryWriter;
+pub use windows_resources::WindowsResources;
 
 use self::binary::load_npm_vfs;
 use self::binary::Metadata;
@@ -66,6 +78,7 @@ use self::file_system::DenoCompileFileSystem;
 
 struct SharedModuleLoaderState {
   eszip: eszip::EszipV2,
+  assets: BTreeMap<ModuleSpecifier, Vec<u8>>,
   mapped_specifier_resolver: MappedSpecifierResolver,
   npm_module_loader: Arc<NpmModuleLoader>,
 }
@@ -212,6 +225,47 @@ impl ModuleLoader for EmbeddedModuleLoader {
     }
     .boxed_local()
   }
+
+  fn load_asset(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    _maybe_referrer: Option<&ModuleSpecifier>,
+    _is_dynamic: bool,
+    module_type: ModuleType,
+  ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
+    let result = match self.shared.assets.get(module_specifier) {
+      Some(bytes) => Ok(match module_type {
+        ModuleType::Bytes => deno_core::ModuleSource::new_bytes(
+          bytes.clone(),
+          module_specifier,
+          module_specifier,
+        ),
+        _ => deno_core::ModuleSource::new(
+          module_type,
+          String::from_utf8_lossy(bytes).into_owned().into(),
+          module_specifier,
+        ),
+      }),
+      None => Err(type_error(format!(
+        "Module not found: {}",
+        module_specifier
+      ))),
+    };
+    Box::pin(deno_core::futures::future::ready(result))
+  }
+}
+
+fn grants_any_permission(options: &PermissionsOptions) -> bool {
+  options.allow_hrtime
+    || options.allow_env.is_some()
+    || options.allow_net.is_some()
+    || options.allow_udp.is_some()
+    || options.allow_ffi.is_some()
+    || options.allow_read.is_some()
+    || options.allow_run.is_some()
+    || options.allow_sys.is_some()
+    || options.allow_write.is_some()
+    || options.allow_unix.is_some()
 }
 
 struct StandaloneModuleLoaderFactory {
@@ -273,6 +327,46 @@ impl RootCertStoreProvider for StandaloneRootCertStoreProvider {
   }
 }
 
+/// Redirects stdout and stderr to the file of `--log-file`, which is where
+/// the output of an executable compiled with `--no-terminal` can be found.
+fn redirect_stdio(log_file_path: &Path) -> Result<(), AnyError> {
+  let file = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(log_file_path)
+    .with_context(|| {
+      format!("Opening log file: {}", log_file_path.display())
+    })?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::io::AsRawFd;
+
+    for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+      // SAFETY: both file descriptors are open for the duration of the call
+      if unsafe { libc::dup2(file.as_raw_fd(), fd) } == -1 {
+        return Err(std::io::Error::last_os_error().into());
+      }
+    }
+  }
+  #[cfg(windows)]
+  {
+    use std::os::windows::io::IntoRawHandle;
+    use winapi::um::processenv::SetStdHandle;
+    use winapi::um::winbase::STD_ERROR_HANDLE;
+    use winapi::um::winbase::STD_OUTPUT_HANDLE;
+
+    // the handle is shared by stdout and stderr, so it's never closed
+    let handle = file.into_raw_handle();
+    for std_handle in [STD_OUTPUT_HANDLE, STD_ERROR_HANDLE] {
+      // SAFETY: winapi calls
+      if unsafe { SetStdHandle(std_handle, handle as _) } == 0 {
+        return Err(std::io::Error::last_os_error().into());
+      }
+    }
+  }
+  Ok(())
+}
+
 pub async fn run(
   eszip: eszip::EszipV2,
   metadata: Metadata,
@@ -281,6 +375,9 @@ pub async fn run(
   let current_exe_path = std::env::current_exe().unwrap();
   let current_exe_name =
     current_exe_path.file_name().unwrap().to_string_lossy();
+  if let Some(log_file) = &metadata.log_file {
+    redirect_stdio(&current_exe_path.parent().unwrap().join(log_file))?;
+  }
   let dir = DenoDir::new(None)?;
   let root_cert_store_provider = Arc::new(StandaloneRootCertStoreProvider {
     ca_stores: metadata.ca_stores,
@@ -303,6 +400,10 @@ pub async fn run(
     CacheSetting::Use,
     http_client.clone(),
     progress_bar.clone(),
+    // all the packages are stored in the folder of the dummy registry
+    Default::default(),
+    DEFAULT_NPM_DOWNLOAD_CONCURRENCY,
+    NpmCopyStrategy::default(),
   ));
   let npm_api = Arc::new(CliNpmRegistryApi::new(
     npm_registry_url.clone(),
@@ -350,6 +451,7 @@ pub async fn run(
     npm_registry_url,
     npm_resolution.clone(),
     node_modules_path,
+    NodeModulesLayout::default(),
   );
   let npm_resolver = Arc::new(CliNpmResolver::new(
     fs.clone(),
@@ -357,8 +459,11 @@ pub async fn run(
     npm_fs_resolver,
     None,
   ));
-  let node_resolver =
-    Arc::new(NodeResolver::new(fs.clone(), npm_resolver.clone()));
+  let node_resolver = Arc::new(NodeResolver::new(
+    fs.clone(),
+    npm_resolver.clone(),
+    metadata.node_conditions.clone(),
+  ));
   let cjs_resolutions = Arc::new(CjsResolutionStore::default());
   let cache_db = Caches::new(dir.clone());
   let node_analysis_cache = NodeAnalysisCache::new(cache_db.node_analysis_db());
@@ -380,6 +485,7 @@ pub async fn run(
   let module_loader_factory = StandaloneModuleLoaderFactory {
     shared: Arc::new(SharedModuleLoaderState {
       eszip,
+      assets: metadata.assets,
       mapped_specifier_resolver: MappedSpecifierResolver::new(
         maybe_import_map.clone(),
         package_json_deps_provider.clone(),
@@ -393,9 +499,20 @@ pub async fn run(
     }),
   };
 
-  let permissions = PermissionsContainer::new(Permissions::from_options(
-    &metadata.permissions,
-  )?);
+  let mut permissions_options = match inherited_permissions_from_env()? {
+    Some(inherited) => {
+      narrow_to_inherited_permissions(&metadata.permissions, &inherited)?
+    }
+    None => metadata.permissions.clone(),
+  };
+  // the permissions granted when compiling are the most the executable gets,
+  // so it doesn't prompt for more and denies permission requests
+  if grants_any_permission(&metadata.permissions) {
+    permissions_options.prompt = false;
+    set_prompter(Box::new(DenyPrompter));
+  }
+  let permissions =
+    PermissionsContainer::new(Permissions::from_options(&permissions_options)?);
   let worker_factory = CliMainWorkerFactory::new(
     StorageKeyResolver::empty(),
     npm_resolver.clone(),
@@ -417,16 +534,21 @@ pub async fn run(
       is_inspecting: false,
       is_npm_main: main_module.scheme() == "npm",
       location: metadata.location,
+      node_conditions: metadata.node_conditions,
       maybe_binary_npm_command_name: NpmPackageReqReference::from_specifier(
         main_module,
       )
       .ok()
       .map(|req_ref| npm_pkg_req_ref_to_binary_command(&req_ref)),
       origin_data_folder_path: None,
+      sandbox_options: None,
       seed: metadata.seed,
+      heap_snapshot_on_oom: metadata.heap_snapshot_on_oom,
+      serve_options: None,
       unsafely_ignore_certificate_errors: metadata
         .unsafely_ignore_certificate_errors,
       unstable: metadata.unstable,
+      worker_permissions_config: Default::default(),
     },
   );
 
@@ -59,11 +59,13 @@ impl VfsBuilder {
 
   pub fn add_dir_recursive(&mut self, path: &Path) -> Result<(), AnyError> {
     self.add_dir(path);
-    let read_dir = std::fs::read_dir(path)
-      .with_context(|| format!("Reading {}", path.display()))?;
+    let mut entries = std::fs::read_dir(path)
+      .with_context(|| format!("Reading {}", path.display()))?
+      .collect::<Result<Vec<_>, _>>()?;
+    // add the files in the same order on every run
+    entries.sort_by_key(|entry| entry.file_name());
 
-    for entry in read_dir {
-      let entry = entry?;
+    for entry in entries {
       let file_type = entry.file_type()?;
       let path = entry.path();
 
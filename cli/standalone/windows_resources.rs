@@ -0,0 +1,898 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Patches the resources of the Windows executables written by
+//! `deno compile`, so that they get their own icon and version information,
+//! and their subsystem for `--no-terminal`.
+
+use std::collections::BTreeMap;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+
+use crate::args::CompileFlags;
+
+const RT_ICON: u16 = 3;
+const RT_GROUP_ICON: u16 = 14;
+const RT_VERSION: u16 = 16;
+const LANG_EN_US: u16 = 0x0409;
+
+const RESOURCE_DIRECTORY_INDEX: usize = 2;
+const SECURITY_DIRECTORY_INDEX: usize = 4;
+const SECTION_HEADER_SIZE: usize = 40;
+const SUBDIRECTORY_FLAG: u32 = 0x8000_0000;
+const IMAGE_SUBSYSTEM_WINDOWS_GUI: u16 = 2;
+
+/// The resources to set on a Windows executable.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WindowsResources {
+  /// The contents of an `.ico` file.
+  pub icon: Option<Vec<u8>>,
+  pub product_name: Option<String>,
+  pub file_version: Option<[u16; 4]>,
+}
+
+impl WindowsResources {
+  /// Gets the resources of `--icon`, `--product-name` and `--file-version`,
+  /// or `None` when none of them were passed.
+  pub fn from_flags(
+    compile_flags: &CompileFlags,
+  ) -> Result<Option<Self>, AnyError> {
+    if compile_flags.icon.is_none()
+      && compile_flags.product_name.is_none()
+      && compile_flags.file_version.is_none()
+    {
+      return Ok(None);
+    }
+    if !compile_flags.is_windows_target() {
+      bail!(
+        "--icon, --product-name and --file-version are only supported when compiling for Windows."
+      );
+    }
+
+    let icon = match &compile_flags.icon {
+      Some(path) => {
+        let icon = std::fs::read(path)
+          .with_context(|| format!("Reading icon: {}", path.display()))?;
+        // fail early rather than when writing the executable
+        read_icon(&icon)
+          .with_context(|| format!("Invalid icon: {}", path.display()))?;
+        Some(icon)
+      }
+      None => None,
+    };
+    let file_version = match &compile_flags.file_version {
+      Some(version) => Some(parse_file_version(version)?),
+      None => None,
+    };
+    Ok(Some(Self {
+      icon,
+      product_name: compile_flags.product_name.clone(),
+      file_version,
+    }))
+  }
+}
+
+/// Parses a file version of one to four numbers, eg. `1.2.3`.
+fn parse_file_version(text: &str) -> Result<[u16; 4], AnyError> {
+  let mut version = [0; 4];
+  let parts = text.split('.').collect::<Vec<_>>();
+  if parts.len() > 4 {
+    bail!(
+      "Invalid file version '{}': expected at most 4 numbers.",
+      text
+    );
+  }
+  for (i, part) in parts.into_iter().enumerate() {
+    version[i] = part.parse().with_context(|| {
+      format!("Invalid file version '{text}': expected numbers up to 65535.")
+    })?;
+  }
+  Ok(version)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum ResourceName {
+  // named entries have to come before the ids in a resource directory
+  Name(Vec<u16>),
+  Id(u16),
+}
+
+/// The resources of a type by name and language.
+type ResourceEntries = BTreeMap<ResourceName, BTreeMap<ResourceName, Vec<u8>>>;
+
+/// The resources by type, name and language.
+type ResourceTree = BTreeMap<ResourceName, ResourceEntries>;
+
+struct Section {
+  virtual_size: u32,
+  virtual_address: u32,
+  raw_size: u32,
+  raw_pos: u32,
+}
+
+struct PeHeaders {
+  coff_header_pos: usize,
+  optional_header_pos: usize,
+  data_directories_pos: usize,
+  section_table_pos: usize,
+  sections: Vec<Section>,
+}
+
+impl PeHeaders {
+  pub fn parse(binary: &[u8]) -> Result<Self, AnyError> {
+    if binary.get(0..2) != Some(b"MZ") {
+      bail!("Missing DOS header.");
+    }
+    let pe_header_pos = read_u32(binary, 0x3c)? as usize;
+    if binary.get(pe_header_pos..pe_header_pos + 4) != Some(b"PE\0\0") {
+      bail!("Missing PE header.");
+    }
+    let coff_header_pos = pe_header_pos + 4;
+    let number_of_sections = read_u16(binary, coff_header_pos + 2)? as usize;
+    let optional_header_size = read_u16(binary, coff_header_pos + 16)?;
+    let optional_header_pos = coff_header_pos + 20;
+    let data_directories_pos = match read_u16(binary, optional_header_pos)? {
+      // PE32
+      0x10b => optional_header_pos + 96,
+      // PE32+
+      0x20b => optional_header_pos + 112,
+      magic => bail!("Unknown optional header magic: {:#x}", magic),
+    };
+    let section_table_pos = optional_header_pos + optional_header_size as usize;
+    let mut sections = Vec::with_capacity(number_of_sections);
+    for i in 0..number_of_sections {
+      let pos = section_table_pos + i * SECTION_HEADER_SIZE;
+      sections.push(Section {
+        virtual_size: read_u32(binary, pos + 8)?,
+        virtual_address: read_u32(binary, pos + 12)?,
+        raw_size: read_u32(binary, pos + 16)?,
+        raw_pos: read_u32(binary, pos + 20)?,
+      });
+    }
+    Ok(Self {
+      coff_header_pos,
+      optional_header_pos,
+      data_directories_pos,
+      section_table_pos,
+      sections,
+    })
+  }
+
+  pub fn data_directory(
+    &self,
+    binary: &[u8],
+    index: usize,
+  ) -> Result<(u32, u32), AnyError> {
+    let pos = self.data_directories_pos + index * 8;
+    Ok((read_u32(binary, pos)?, read_u32(binary, pos + 4)?))
+  }
+
+  pub fn rva_to_pos(&self, rva: u32) -> Result<usize, AnyError> {
+    self
+      .sections
+      .iter()
+      .find(|section| {
+        rva >= section.virtual_address
+          && rva - section.virtual_address
+            < section.virtual_size.max(section.raw_size)
+      })
+      .map(|section| (rva - section.virtual_address + section.raw_pos) as usize)
+      .with_context(|| format!("Address outside of the sections: {rva:#x}"))
+  }
+}
+
+/// Gets the position of the signature in a Windows executable from its
+/// headers, which signtool appends to the end of the file.
+pub fn windows_signature_pos(header: &[u8]) -> Option<u64> {
+  let headers = PeHeaders::parse(header).ok()?;
+  match headers
+    .data_directory(header, SECURITY_DIRECTORY_INDEX)
+    .ok()?
+  {
+    (_, 0) => None,
+    (pos, _) => Some(pos as u64),
+  }
+}
+
+/// Removes the signature of a Windows executable.
+pub fn strip_windows_signature(
+  mut binary: Vec<u8>,
+) -> Result<Vec<u8>, AnyError> {
+  let headers = PeHeaders::parse(&binary)
+    .context("Failed to parse the Windows executable")?;
+  let directory_pos =
+    headers.data_directories_pos + SECURITY_DIRECTORY_INDEX * 8;
+  let (pos, len) = headers.data_directory(&binary, SECURITY_DIRECTORY_INDEX)?;
+  if len != 0 {
+    binary.truncate(pos as usize);
+    write_u32(&mut binary, directory_pos, 0);
+    write_u32(&mut binary, directory_pos + 4, 0);
+  }
+  Ok(binary)
+}
+
+/// Sets the subsystem of a Windows executable to GUI, so that Windows doesn't
+/// open a console window when it's started.
+pub fn set_windows_gui_subsystem(
+  mut binary: Vec<u8>,
+) -> Result<Vec<u8>, AnyError> {
+  let headers = PeHeaders::parse(&binary)
+    .context("Failed to parse the Windows executable")?;
+  write_u16(
+    &mut binary,
+    headers.optional_header_pos + 68,
+    IMAGE_SUBSYSTEM_WINDOWS_GUI,
+  );
+  Ok(binary)
+}
+
+/// Sets the resources on the given Windows executable.
+///
+/// The resources are written to a new section at the end of the image, since
+/// the existing resource section rarely has room for them. Anything after the
+/// sections, such as the signature that isn't valid anymore, is dropped.
+pub fn patch_windows_resources(
+  mut binary: Vec<u8>,
+  resources: &WindowsResources,
+) -> Result<Vec<u8>, AnyError> {
+  let headers = PeHeaders::parse(&binary)
+    .context("Failed to parse the Windows executable")?;
+  let mut tree = read_resource_tree(&binary, &headers)
+    .context("Failed to read the resources of the Windows executable")?;
+
+  if let Some(icon) = &resources.icon {
+    let (group, images) = read_icon(icon)?;
+    tree.insert(ResourceName::Id(RT_ICON), images);
+    tree.insert(ResourceName::Id(RT_GROUP_ICON), single_resource(group));
+  }
+  if resources.product_name.is_some() || resources.file_version.is_some() {
+    let version_info = version_info(
+      resources.product_name.as_deref(),
+      resources.file_version.unwrap_or_default(),
+    );
+    tree.insert(ResourceName::Id(RT_VERSION), single_resource(version_info));
+  }
+
+  let optional_header_pos = headers.optional_header_pos;
+  let section_alignment = read_u32(&binary, optional_header_pos + 32)?;
+  let file_alignment = read_u32(&binary, optional_header_pos + 36)?;
+  let size_of_headers = read_u32(&binary, optional_header_pos + 60)?;
+  let section_header_pos =
+    headers.section_table_pos + headers.sections.len() * SECTION_HEADER_SIZE;
+  let section_header_end = section_header_pos + SECTION_HEADER_SIZE;
+  if section_header_end > size_of_headers as usize
+    || binary[section_header_pos..section_header_end]
+      .iter()
+      .any(|byte| *byte != 0)
+  {
+    bail!("The headers of the Windows executable have no room for a section.");
+  }
+
+  let image_end = headers
+    .sections
+    .iter()
+    .map(|section| section.virtual_address + section.virtual_size)
+    .max()
+    .unwrap_or(size_of_headers);
+  let raw_end = headers
+    .sections
+    .iter()
+    .map(|section| section.raw_pos + section.raw_size)
+    .max()
+    .unwrap_or(size_of_headers);
+  let virtual_address = align(image_end, section_alignment);
+  let raw_pos = align(raw_end, file_alignment);
+  let data = write_resource_tree(&tree, virtual_address);
+  let data_len = data.len() as u32;
+  let raw_size = align(data_len, file_alignment);
+
+  binary.resize(raw_pos as usize, 0);
+  binary.extend(data);
+  binary.resize((raw_pos + raw_size) as usize, 0);
+
+  let section_header = &mut binary[section_header_pos..section_header_end];
+  section_header[0..8].copy_from_slice(b".rsrc1\0\0");
+  write_u32(section_header, 8, data_len);
+  write_u32(section_header, 12, virtual_address);
+  write_u32(section_header, 16, raw_size);
+  write_u32(section_header, 20, raw_pos);
+  // initialized data, readable
+  write_u32(section_header, 36, 0x4000_0040);
+  write_u16(
+    &mut binary,
+    headers.coff_header_pos + 2,
+    headers.sections.len() as u16 + 1,
+  );
+  // size of image
+  write_u32(
+    &mut binary,
+    optional_header_pos + 56,
+    align(virtual_address + data_len, section_alignment),
+  );
+  // the checksum isn't verified for executables, so clear it
+  write_u32(&mut binary, optional_header_pos + 64, 0);
+  let resource_directory_pos =
+    headers.data_directories_pos + RESOURCE_DIRECTORY_INDEX * 8;
+  write_u32(&mut binary, resource_directory_pos, virtual_address);
+  write_u32(&mut binary, resource_directory_pos + 4, data_len);
+  let security_directory_pos =
+    headers.data_directories_pos + SECURITY_DIRECTORY_INDEX * 8;
+  write_u32(&mut binary, security_directory_pos, 0);
+  write_u32(&mut binary, security_directory_pos + 4, 0);
+
+  Ok(binary)
+}
+
+fn single_resource(data: Vec<u8>) -> ResourceEntries {
+  BTreeMap::from([(
+    ResourceName::Id(1),
+    BTreeMap::from([(ResourceName::Id(LANG_EN_US), data)]),
+  )])
+}
+
+/// Reads an `.ico` file into the data of an icon group resource and the
+/// icon resources of its images.
+fn read_icon(icon: &[u8]) -> Result<(Vec<u8>, ResourceEntries), AnyError> {
+  if read_u16(icon, 0)? != 0 || read_u16(icon, 2)? != 1 {
+    bail!("Not an .ico file.");
+  }
+  let count = read_u16(icon, 4)?;
+  if count == 0 {
+    bail!("The icon has no images.");
+  }
+  let mut group = icon[0..6].to_vec();
+  let mut images = BTreeMap::new();
+  for id in 1..=count {
+    let entry_pos = 6 + (id as usize - 1) * 16;
+    let size = read_u32(icon, entry_pos + 8)? as usize;
+    let pos = read_u32(icon, entry_pos + 12)? as usize;
+    let image = icon
+      .get(pos..pos + size)
+      .context("An image of the icon is out of bounds.")?;
+    // the entries of the group are the ones of the file with the id of the
+    // image instead of its position
+    group.extend(&icon[entry_pos..entry_pos + 12]);
+    group.extend(id.to_le_bytes());
+    images.insert(
+      ResourceName::Id(id),
+      BTreeMap::from([(ResourceName::Id(LANG_EN_US), image.to_vec())]),
+    );
+  }
+  Ok((group, images))
+}
+
+/// Creates a `VS_VERSIONINFO` resource.
+fn version_info(product_name: Option<&str>, file_version: [u16; 4]) -> Vec<u8> {
+  let version_text = file_version.map(|part| part.to_string()).join(".");
+  let mut strings = vec![
+    ("FileVersion", version_text.as_str()),
+    ("ProductVersion", version_text.as_str()),
+  ];
+  if let Some(product_name) = product_name {
+    strings.push(("FileDescription", product_name));
+    strings.push(("ProductName", product_name));
+  }
+  let strings = strings
+    .into_iter()
+    .map(|(key, value)| {
+      let value = to_utf16_bytes(value);
+      // the length of text values is in words
+      version_block(key, &value, value.len() as u16 / 2, true, &[])
+    })
+    .collect::<Vec<_>>();
+  // US English, Unicode
+  let string_table = version_block("040904b0", &[], 0, true, &strings);
+  let string_file_info =
+    version_block("StringFileInfo", &[], 0, true, &[string_table]);
+  let translation =
+    version_block("Translation", &[0x09, 0x04, 0xb0, 0x04], 4, false, &[]);
+  let var_file_info =
+    version_block("VarFileInfo", &[], 0, true, &[translation]);
+
+  let version_ms = ((file_version[0] as u32) << 16) | file_version[1] as u32;
+  let version_ls = ((file_version[2] as u32) << 16) | file_version[3] as u32;
+  let fixed_file_info = [
+    // signature and struct version
+    0xfeef_04bd,
+    0x0001_0000,
+    version_ms,
+    version_ls,
+    version_ms,
+    version_ls,
+    // file flags mask and flags
+    0x3f,
+    0,
+    // VOS_NT_WINDOWS32
+    0x0004_0004,
+    // VFT_APP
+    1,
+    // file subtype and date
+    0,
+    0,
+    0,
+  ]
+  .iter()
+  .flat_map(|value: &u32| value.to_le_bytes())
+  .collect::<Vec<_>>();
+  version_block(
+    "VS_VERSION_INFO",
+    &fixed_file_info,
+    fixed_file_info.len() as u16,
+    false,
+    &[string_file_info, var_file_info],
+  )
+}
+
+/// Writes a block of a version resource, whose key, value and children are
+/// aligned to 32 bits.
+fn version_block(
+  key: &str,
+  value: &[u8],
+  value_len: u16,
+  is_text: bool,
+  children: &[Vec<u8>],
+) -> Vec<u8> {
+  let mut block = vec![0; 6];
+  block.extend(to_utf16_bytes(key));
+  pad_to_u32(&mut block);
+  block.extend(value);
+  for child in children {
+    pad_to_u32(&mut block);
+    block.extend(child);
+  }
+  let block_len = block.len() as u16;
+  write_u16(&mut block, 0, block_len);
+  write_u16(&mut block, 2, value_len);
+  write_u16(&mut block, 4, is_text as u16);
+  block
+}
+
+fn read_resource_tree(
+  binary: &[u8],
+  headers: &PeHeaders,
+) -> Result<ResourceTree, AnyError> {
+  let (resource_rva, _) =
+    headers.data_directory(binary, RESOURCE_DIRECTORY_INDEX)?;
+  let mut tree = ResourceTree::new();
+  if resource_rva == 0 {
+    return Ok(tree);
+  }
+  let base_pos = headers.rva_to_pos(resource_rva)?;
+  for (type_name, names_offset) in
+    read_resource_directory(binary, base_pos, 0, true)?
+  {
+    let names = tree.entry(type_name).or_default();
+    for (name, languages_offset) in
+      read_resource_directory(binary, base_pos, names_offset, true)?
+    {
+      let languages = names.entry(name).or_default();
+      for (language, data_entry_offset) in
+        read_resource_directory(binary, base_pos, languages_offset, false)?
+      {
+        let data_entry_pos = base_pos + data_entry_offset as usize;
+        let data_pos = headers.rva_to_pos(read_u32(binary, data_entry_pos)?)?;
+        let data_len = read_u32(binary, data_entry_pos + 4)? as usize;
+        let data = binary
+          .get(data_pos..data_pos + data_len)
+          .context("A resource is out of bounds.")?;
+        languages.insert(language, data.to_vec());
+      }
+    }
+  }
+  Ok(tree)
+}
+
+/// Reads the entries of a resource directory, with the offsets of their
+/// subdirectories or data entries.
+fn read_resource_directory(
+  binary: &[u8],
+  base_pos: usize,
+  offset: u32,
+  has_subdirectories: bool,
+) -> Result<Vec<(ResourceName, u32)>, AnyError> {
+  let pos = base_pos + offset as usize;
+  let entry_count =
+    read_u16(binary, pos + 12)? as usize + read_u16(binary, pos + 14)? as usize;
+  let mut entries = Vec::with_capacity(entry_count);
+  for i in 0..entry_count {
+    let entry_pos = pos + 16 + i * 8;
+    let name = read_u32(binary, entry_pos)?;
+    let name = if name & SUBDIRECTORY_FLAG != 0 {
+      let name_pos = base_pos + (name & !SUBDIRECTORY_FLAG) as usize;
+      let name_len = read_u16(binary, name_pos)? as usize;
+      let name = (0..name_len)
+        .map(|i| read_u16(binary, name_pos + 2 + i * 2))
+        .collect::<Result<Vec<_>, _>>()?;
+      ResourceName::Name(name)
+    } else {
+      ResourceName::Id(name as u16)
+    };
+    let data_offset = read_u32(binary, entry_pos + 4)?;
+    if (data_offset & SUBDIRECTORY_FLAG != 0) != has_subdirectories {
+      bail!("Unexpected nesting of the resource directories.");
+    }
+    entries.push((name, data_offset & !SUBDIRECTORY_FLAG));
+  }
+  Ok(entries)
+}
+
+/// Writes the resource tree for a section at the given virtual address:
+/// the directories, then the data entries, the names and the data.
+fn write_resource_tree(tree: &ResourceTree, virtual_address: u32) -> Vec<u8> {
+  fn write_directory<'a>(
+    data: &mut Vec<u8>,
+    names: impl Iterator<Item = &'a ResourceName>,
+    named_entries: &mut Vec<(usize, &'a [u16])>,
+  ) -> Vec<usize> {
+    let names = names.collect::<Vec<_>>();
+    let named_count = names
+      .iter()
+      .filter(|name| matches!(name, ResourceName::Name(_)))
+      .count();
+    let pos = data.len();
+    data.resize(pos + 16 + names.len() * 8, 0);
+    write_u16(data, pos + 12, named_count as u16);
+    write_u16(data, pos + 14, (names.len() - named_count) as u16);
+    let mut entry_positions = Vec::with_capacity(names.len());
+    for (i, name) in names.into_iter().enumerate() {
+      let entry_pos = pos + 16 + i * 8;
+      match name {
+        ResourceName::Name(name) => {
+          named_entries.push((entry_pos, name.as_slice()))
+        }
+        ResourceName::Id(id) => write_u32(data, entry_pos, *id as u32),
+      }
+      entry_positions.push(entry_pos);
+    }
+    entry_positions
+  }
+
+  let mut data = Vec::new();
+  let mut named_entries = Vec::new();
+  let mut leaves = Vec::new();
+  let type_entries =
+    write_directory(&mut data, tree.keys(), &mut named_entries);
+  for (names, type_entry_pos) in tree.values().zip(type_entries) {
+    let offset = data.len() as u32 | SUBDIRECTORY_FLAG;
+    write_u32(&mut data, type_entry_pos + 4, offset);
+    let name_entries =
+      write_directory(&mut data, names.keys(), &mut named_entries);
+    for (languages, name_entry_pos) in names.values().zip(name_entries) {
+      let offset = data.len() as u32 | SUBDIRECTORY_FLAG;
+      write_u32(&mut data, name_entry_pos + 4, offset);
+      let language_entries =
+        write_directory(&mut data, languages.keys(), &mut named_entries);
+      leaves.extend(language_entries.into_iter().zip(languages.values()));
+    }
+  }
+
+  let mut data_entries = Vec::with_capacity(leaves.len());
+  for (language_entry_pos, resource) in leaves {
+    let offset = data.len() as u32;
+    write_u32(&mut data, language_entry_pos + 4, offset);
+    data_entries.push((data.len(), resource));
+    data.resize(data.len() + 16, 0);
+  }
+  for (entry_pos, name) in named_entries {
+    let offset = data.len() as u32 | SUBDIRECTORY_FLAG;
+    write_u32(&mut data, entry_pos, offset);
+    data.extend((name.len() as u16).to_le_bytes());
+    data.extend(name.iter().flat_map(|c| c.to_le_bytes()));
+  }
+  for (data_entry_pos, resource) in data_entries {
+    // align the resources to 64 bits
+    data.resize(align(data.len() as u32, 8) as usize, 0);
+    let rva = virtual_address + data.len() as u32;
+    write_u32(&mut data, data_entry_pos, rva);
+    write_u32(&mut data, data_entry_pos + 4, resource.len() as u32);
+    data.extend(resource);
+  }
+  data
+}
+
+fn to_utf16_bytes(text: &str) -> Vec<u8> {
+  text
+    .encode_utf16()
+    .chain([0])
+    .flat_map(|c| c.to_le_bytes())
+    .collect()
+}
+
+fn pad_to_u32(data: &mut Vec<u8>) {
+  data.resize(align(data.len() as u32, 4) as usize, 0);
+}
+
+fn align(value: u32, alignment: u32) -> u32 {
+  (value + alignment - 1) / alignment * alignment
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Result<u16, AnyError> {
+  match data.get(pos..pos + 2) {
+    Some(bytes) => Ok(u16::from_le_bytes([bytes[0], bytes[1]])),
+    None => bail!("Unexpected end of data at {:#x}.", pos),
+  }
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Result<u32, AnyError> {
+  match data.get(pos..pos + 4) {
+    Some(bytes) => {
+      Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    None => bail!("Unexpected end of data at {:#x}.", pos),
+  }
+}
+
+fn write_u16(data: &mut [u8], pos: usize, value: u16) {
+  data[pos..pos + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn write_u32(data: &mut [u8], pos: usize, value: u32) {
+  data[pos..pos + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  const RT_MANIFEST: u16 = 24;
+
+  /// Creates a PE32+ image with a resource section containing a manifest
+  /// and a signature after the sections.
+  fn create_binary() -> Vec<u8> {
+    let mut tree = ResourceTree::new();
+    tree.insert(
+      ResourceName::Id(RT_MANIFEST),
+      single_resource(b"<assembly />".to_vec()),
+    );
+    let resources = write_resource_tree(&tree, 0x1000);
+
+    let mut binary = vec![0; 0x200];
+    binary[0..2].copy_from_slice(b"MZ");
+    write_u32(&mut binary, 0x3c, 0x40);
+    binary[0x40..0x44].copy_from_slice(b"PE\0\0");
+    // machine, number of sections and size of the optional header
+    write_u16(&mut binary, 0x44, 0x8664);
+    write_u16(&mut binary, 0x46, 1);
+    write_u16(&mut binary, 0x54, 240);
+    // magic, alignments, size of image, size of headers and data directories
+    write_u16(&mut binary, 0x58, 0x20b);
+    write_u32(&mut binary, 0x58 + 32, 0x1000);
+    write_u32(&mut binary, 0x58 + 36, 0x200);
+    write_u32(&mut binary, 0x58 + 56, 0x2000);
+    write_u32(&mut binary, 0x58 + 60, 0x200);
+    write_u32(&mut binary, 0x58 + 108, 16);
+    write_u32(&mut binary, 0x58 + 112 + 16, 0x1000);
+    write_u32(&mut binary, 0x58 + 112 + 20, resources.len() as u32);
+    write_u32(&mut binary, 0x58 + 112 + 32, 0x400);
+    write_u32(&mut binary, 0x58 + 112 + 36, 8);
+    // the section
+    let section_pos = 0x58 + 240;
+    binary[section_pos..section_pos + 8].copy_from_slice(b".rsrc\0\0\0");
+    write_u32(&mut binary, section_pos + 8, resources.len() as u32);
+    write_u32(&mut binary, section_pos + 12, 0x1000);
+    write_u32(&mut binary, section_pos + 16, 0x200);
+    write_u32(&mut binary, section_pos + 20, 0x200);
+    binary.extend(&resources);
+    binary.resize(0x400, 0);
+    binary.extend(b"signatur");
+    binary
+  }
+
+  fn create_icon(images: &[&[u8]]) -> Vec<u8> {
+    let mut icon = vec![0, 0, 1, 0];
+    icon.extend((images.len() as u16).to_le_bytes());
+    let mut image_pos = 6 + images.len() * 16;
+    for image in images {
+      // width, height, color count, reserved, planes and bits per pixel
+      icon.extend([16, 16, 0, 0, 1, 0, 32, 0]);
+      icon.extend((image.len() as u32).to_le_bytes());
+      icon.extend((image_pos as u32).to_le_bytes());
+      image_pos += image.len();
+    }
+    for image in images {
+      icon.extend(*image);
+    }
+    icon
+  }
+
+  #[test]
+  fn patches_resources() {
+    let binary = create_binary();
+    let icon = create_icon(&[b"image1", b"image2"]);
+    let binary = patch_windows_resources(
+      binary,
+      &WindowsResources {
+        icon: Some(icon),
+        product_name: Some("My App".to_string()),
+        file_version: Some([1, 2, 3, 0]),
+      },
+    )
+    .unwrap();
+
+    let headers = PeHeaders::parse(&binary).unwrap();
+    assert_eq!(headers.sections.len(), 2);
+    assert_eq!(headers.sections[1].virtual_address, 0x2000);
+    assert_eq!(headers.sections[1].raw_pos, 0x400);
+    // the signature is replaced by the section, padded to the file alignment
+    assert_eq!(headers.sections[1].raw_size % 0x200, 0);
+    assert_eq!(binary.len(), 0x400 + headers.sections[1].raw_size as usize);
+    assert_eq!(
+      headers
+        .data_directory(&binary, SECURITY_DIRECTORY_INDEX)
+        .unwrap(),
+      (0, 0)
+    );
+
+    let tree = read_resource_tree(&binary, &headers).unwrap();
+    let resource = |resource_type: u16, name: u16| {
+      tree[&ResourceName::Id(resource_type)][&ResourceName::Id(name)]
+        [&ResourceName::Id(LANG_EN_US)]
+        .clone()
+    };
+    assert_eq!(resource(RT_MANIFEST, 1), b"<assembly />");
+    assert!(tree.contains_key(&ResourceName::Id(RT_ICON)));
+    assert!(tree.contains_key(&ResourceName::Id(RT_GROUP_ICON)));
+    assert!(tree.contains_key(&ResourceName::Id(RT_VERSION)));
+  }
+
+  /// Reads the key, value and children of a block of a version resource.
+  fn read_version_block(block: &[u8]) -> (String, &[u8], Vec<&[u8]>) {
+    let block_len = read_u16(block, 0).unwrap() as usize;
+    let value_len = read_u16(block, 2).unwrap() as usize;
+    // the length of text values is in words
+    let value_len = match read_u16(block, 4).unwrap() {
+      1 => value_len * 2,
+      _ => value_len,
+    };
+    let key = read_utf16_text(&block[6..block_len]);
+    let value_pos = align(6 + (key.len() as u32 + 1) * 2, 4) as usize;
+    let value = &block[value_pos..value_pos + value_len];
+    let mut children = Vec::new();
+    let mut pos = align((value_pos + value_len) as u32, 4) as usize;
+    while pos < block_len {
+      let child_len = read_u16(block, pos).unwrap() as usize;
+      children.push(&block[pos..pos + child_len]);
+      pos = align((pos + child_len) as u32, 4) as usize;
+    }
+    (key, value, children)
+  }
+
+  /// Reads a null terminated UTF-16 text.
+  fn read_utf16_text(data: &[u8]) -> String {
+    let text = data
+      .chunks_exact(2)
+      .map(|c| u16::from_le_bytes([c[0], c[1]]))
+      .take_while(|c| *c != 0)
+      .collect::<Vec<_>>();
+    String::from_utf16(&text).unwrap()
+  }
+
+  #[test]
+  fn reads_back_icon_group_and_version_info() {
+    let images: [&[u8]; 2] = [b"image1", b"image22"];
+    let binary = patch_windows_resources(
+      create_binary(),
+      &WindowsResources {
+        icon: Some(create_icon(&images)),
+        product_name: Some("My App".to_string()),
+        file_version: Some([1, 2, 3, 4]),
+      },
+    )
+    .unwrap();
+    let headers = PeHeaders::parse(&binary).unwrap();
+    let tree = read_resource_tree(&binary, &headers).unwrap();
+    let resource = |resource_type: u16, name: u16| {
+      tree[&ResourceName::Id(resource_type)][&ResourceName::Id(name)]
+        [&ResourceName::Id(LANG_EN_US)]
+        .clone()
+    };
+
+    // the header of the .ico file followed by its entries, which refer to
+    // the icon resources by id
+    let group = resource(RT_GROUP_ICON, 1);
+    assert_eq!(group.len(), 6 + images.len() * 14);
+    assert_eq!(group[0..6], [0, 0, 1, 0, 2, 0]);
+    for (i, image) in images.iter().enumerate() {
+      let id = i as u16 + 1;
+      let entry = &group[6 + i * 14..6 + (i + 1) * 14];
+      assert_eq!(entry[0..8], [16, 16, 0, 0, 1, 0, 32, 0]);
+      assert_eq!(read_u32(entry, 8).unwrap() as usize, image.len());
+      assert_eq!(read_u16(entry, 12).unwrap(), id);
+      assert_eq!(resource(RT_ICON, id), *image);
+    }
+
+    let version_info = resource(RT_VERSION, 1);
+    let (key, fixed_file_info, children) = read_version_block(&version_info);
+    assert_eq!(key, "VS_VERSION_INFO");
+    assert_eq!(
+      read_u16(&version_info, 0).unwrap() as usize,
+      version_info.len()
+    );
+    assert_eq!(fixed_file_info.len(), 52);
+    assert_eq!(read_u32(fixed_file_info, 0).unwrap(), 0xfeef_04bd);
+    // the file and the product version
+    assert_eq!(read_u32(fixed_file_info, 8).unwrap(), 0x0001_0002);
+    assert_eq!(read_u32(fixed_file_info, 12).unwrap(), 0x0003_0004);
+    assert_eq!(read_u32(fixed_file_info, 16).unwrap(), 0x0001_0002);
+    assert_eq!(read_u32(fixed_file_info, 20).unwrap(), 0x0003_0004);
+    assert_eq!(children.len(), 2);
+
+    let (key, _, string_tables) = read_version_block(children[0]);
+    assert_eq!(key, "StringFileInfo");
+    assert_eq!(string_tables.len(), 1);
+    let (key, _, strings) = read_version_block(string_tables[0]);
+    assert_eq!(key, "040904b0");
+    let strings = strings
+      .into_iter()
+      .map(|string| {
+        let (key, value, _) = read_version_block(string);
+        (key, read_utf16_text(value))
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      strings,
+      [
+        ("FileVersion", "1.2.3.4"),
+        ("ProductVersion", "1.2.3.4"),
+        ("FileDescription", "My App"),
+        ("ProductName", "My App"),
+      ]
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+    );
+
+    let (key, _, vars) = read_version_block(children[1]);
+    assert_eq!(key, "VarFileInfo");
+    let (key, translation, _) = read_version_block(vars[0]);
+    assert_eq!(key, "Translation");
+    assert_eq!(translation, [0x09, 0x04, 0xb0, 0x04]);
+  }
+
+  #[test]
+  fn strips_signature() {
+    let binary = create_binary();
+    assert_eq!(windows_signature_pos(&binary), Some(0x400));
+    let binary = strip_windows_signature(binary).unwrap();
+    assert_eq!(binary.len(), 0x400);
+    assert_eq!(windows_signature_pos(&binary), None);
+    assert_eq!(windows_signature_pos(b"\x7fELF"), None);
+  }
+
+  #[test]
+  fn sets_gui_subsystem() {
+    let binary = set_windows_gui_subsystem(create_binary()).unwrap();
+    assert_eq!(
+      read_u16(&binary, 0x58 + 68).unwrap(),
+      IMAGE_SUBSYSTEM_WINDOWS_GUI
+    );
+    assert_eq!(binary.len(), create_binary().len());
+    assert!(set_windows_gui_subsystem(b"\x7fELF".to_vec()).is_err());
+  }
+
+  #[test]
+  fn rejects_invalid_icons() {
+    let binary = create_binary();
+    let mut icon = create_icon(&[b"image"]);
+    // point the image out of bounds
+    write_u32(&mut icon, 6 + 12, 1000);
+    let err = patch_windows_resources(
+      binary,
+      &WindowsResources {
+        icon: Some(icon),
+        ..Default::default()
+      },
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "An image of the icon is out of bounds.");
+  }
+
+  #[test]
+  fn parses_file_versions() {
+    assert_eq!(parse_file_version("1").unwrap(), [1, 0, 0, 0]);
+    assert_eq!(parse_file_version("1.2.3").unwrap(), [1, 2, 3, 0]);
+    assert_eq!(parse_file_version("1.2.3.65535").unwrap(), [1, 2, 3, 65535]);
+    assert!(parse_file_version("1.2.3.4.5").is_err());
+    assert!(parse_file_version("1.2.x").is_err());
+    assert!(parse_file_version("65536").is_err());
+  }
+}
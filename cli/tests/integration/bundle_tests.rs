@@ -157,6 +157,43 @@ fn bundle_single_module() {
   assert_eq!(output.stderr, b"");
 }
 
+#[test]
+fn bundle_code_splitting() {
+  let main = util::testdata_path().join("bundle/code_splitting/main.ts");
+  assert!(main.is_file());
+  let t = TempDir::new();
+  let out_dir = t.path().join("dist");
+  let mut deno = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg("--outdir")
+    .arg(&out_dir)
+    .arg(main)
+    .spawn()
+    .unwrap();
+  let status = deno.wait().unwrap();
+  assert!(status.success());
+  let bundle = out_dir.join("main.js");
+  assert!(bundle.is_file());
+  // the main bundle and the chunk of the dynamically imported module
+  assert_eq!(std::fs::read_dir(&out_dir).unwrap().count(), 2);
+  let bundle_text = std::fs::read_to_string(&bundle).unwrap();
+  assert!(!bundle_text.contains("function lazy"));
+
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg("--allow-read")
+    .arg(&bundle)
+    .output()
+    .unwrap();
+  assert_eq!(
+    std::str::from_utf8(&output.stdout).unwrap().trim(),
+    "Hello from main\nHello from lazy",
+  );
+  assert_eq!(output.stderr, b"");
+}
+
 #[test]
 fn bundle_tla() {
   // First we have to generate a bundle of some module that has exports.
@@ -1,5 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
 use test_util::env_vars_for_npm_tests;
 use test_util::TestContextBuilder;
 
@@ -181,3 +183,87 @@ fn cache_put_overwrite() {
   output.assert_matches_text("res1\n");
   output.assert_exit_code(0);
 }
+
+#[test]
+fn cache_log_format_json() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("main.ts", "import './missing.ts';\n");
+  let output = context
+    .new_command()
+    .args("cache --log-format=json main.ts")
+    .split_output()
+    .run();
+  output.assert_exit_code(1);
+  let line: Value = serde_json::from_str(output.stderr().trim()).unwrap();
+  assert_eq!(line["level"], "error");
+  assert_eq!(line["event"], "error");
+  let graph_error = &line["graphErrors"][0];
+  assert_eq!(graph_error["class"], "NotFound");
+  assert_eq!(graph_error["location"]["line"], 1);
+}
+
+#[test]
+fn cache_trace_out() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("main.ts", "const a: string = 'a';\nconsole.log(a);\n");
+  let output = context
+    .new_command()
+    .args("cache --check --trace-out=trace.json main.ts")
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+  // the closing bracket is optional in the trace event format
+  let text = temp_dir.read_to_string("trace.json");
+  let events: Vec<Value> = serde_json::from_str(&format!(
+    "{}]",
+    text.trim_end().trim_end_matches(',')
+  ))
+  .unwrap();
+  let names = events
+    .iter()
+    .map(|event| event["name"].as_str().unwrap())
+    .collect::<Vec<_>>();
+  assert!(names.contains(&"build graph"), "{names:?}");
+  assert!(names.contains(&"type check"), "{names:?}");
+  assert!(names.contains(&"run subcommand"), "{names:?}");
+  assert!(events.iter().all(|event| event["ph"] == "X"));
+}
+
+#[test]
+fn cache_export_import() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "main.ts",
+    "import { printHello } from 'http://localhost:4545/subdir/print_hello.ts';\nprintHello();\n",
+  );
+  let output = context
+    .new_command()
+    .args("cache --export=cache.tar.gz main.ts")
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  // a fresh DENO_DIR without network access to the modules
+  let other_deno_dir = temp_dir.path().join("other_deno_dir");
+  let other_deno_dir = other_deno_dir.to_string_lossy();
+  let output = context
+    .new_command()
+    .args("cache --import=cache.tar.gz")
+    .env("DENO_DIR", other_deno_dir.as_ref())
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+  let output = context
+    .new_command()
+    .args("run --cached-only main.ts")
+    .env("DENO_DIR", other_deno_dir.as_ref())
+    .run();
+  output.assert_matches_text("Hello\n");
+  output.assert_exit_code(0);
+}
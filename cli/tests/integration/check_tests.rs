@@ -314,3 +314,42 @@ fn check_error_in_dep_then_fix() {
   output.assert_matches_text("Check [WILDCARD]main.ts\nerror: TS234[WILDCARD]");
   output.assert_exit_code(1);
 }
+
+#[test]
+fn check_only_affected_files_again() {
+  let test_context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write(
+    "main.ts",
+    "import { a } from './a.ts';\nimport { b } from './b.ts';\n\nconsole.log(a, b());\n",
+  );
+  temp_dir.write("a.ts", "export const a = 1;\n");
+  temp_dir.write("b.ts", "export function b() {\n  return 1;\n}\n");
+
+  let check_command = test_context.new_command().args_vec([
+    "check",
+    "--log-level=debug",
+    "main.ts",
+  ]);
+  let affected_files = || {
+    let output = check_command.run();
+    output.assert_exit_code(0);
+    let affected_files = output
+      .combined_output()
+      .lines()
+      .find_map(|line| line.trim().strip_prefix("Affected files: "))
+      .unwrap();
+    affected_files.parse::<usize>().unwrap()
+  };
+
+  // everything is checked without build info
+  assert!(affected_files() > 3);
+
+  // the signature of b.ts didn't change, so its importers aren't affected
+  temp_dir.write("b.ts", "export function b() {\n  return 2;\n}\n");
+  assert_eq!(affected_files(), 1);
+
+  // main.ts is affected by the changed signature, but a.ts isn't
+  temp_dir.write("b.ts", "export function b() {\n  return '2';\n}\n");
+  assert_eq!(affected_files(), 2);
+}
@@ -5,7 +5,6 @@ use std::process::Command;
 use test_util as util;
 use test_util::TempDir;
 use util::assert_contains;
-use util::env_vars_for_npm_tests;
 use util::TestContextBuilder;
 
 #[test]
@@ -42,6 +41,130 @@ fn compile() {
   }
 }
 
+#[test]
+fn compile_no_compress() {
+  let dir = TempDir::new();
+  let exe_path = |name: &str| {
+    if cfg!(windows) {
+      dir.path().join(format!("{name}.exe"))
+    } else {
+      dir.path().join(name)
+    }
+  };
+  let compressed_exe = exe_path("compressed");
+  let uncompressed_exe = exe_path("uncompressed");
+  for (exe, args) in [
+    (&compressed_exe, vec![]),
+    (&uncompressed_exe, vec!["--no-compress"]),
+  ] {
+    let output = util::deno_cmd_with_deno_dir(&dir)
+      .current_dir(util::root_path())
+      .arg("compile")
+      .args(args)
+      .arg("--output")
+      .arg(exe)
+      .arg("./cli/tests/testdata/compile/dynamic_imports/main.ts")
+      .output()
+      .unwrap();
+    assert!(output.status.success());
+
+    let output = Command::new(exe).env("NO_COLOR", "").output().unwrap();
+    assert!(output.status.success());
+    let expected = std::fs::read_to_string(
+      util::testdata_path().join("./compile/dynamic_imports/main.out"),
+    )
+    .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+  }
+
+  let len = |path: &std::path::Path| std::fs::metadata(path).unwrap().len();
+  assert!(len(&compressed_exe) < len(&uncompressed_exe));
+}
+
+#[cfg(unix)]
+#[test]
+fn compile_sign_cmd() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let exe = temp_dir.path().join("welcome");
+  let main_path = util::root_path().join("test_util/std/examples/welcome.ts");
+  let output = context
+    .new_command()
+    .args_vec([
+      "compile",
+      "--sign-cmd",
+      "chmod 755",
+      "--output",
+      &exe.to_string_lossy(),
+      &main_path.to_string_lossy(),
+    ])
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+  let output = context
+    .new_command()
+    .command_name(exe.to_string_lossy())
+    .run();
+  output.assert_matches_text("Welcome to Deno!\n");
+
+  // a sign command that replaces the executable is caught
+  let output = context
+    .new_command()
+    .args_vec([
+      "compile",
+      "--sign-cmd",
+      "echo signed >",
+      "--output",
+      &exe.to_string_lossy(),
+      &main_path.to_string_lossy(),
+    ])
+    .run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(concat!(
+    "[WILDCARD]error: The sign command changed [WILDCARD]welcome in a way ",
+    "that removed its embedded modules.\n",
+  ));
+}
+
+#[test]
+fn compile_log_file() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let exe = if cfg!(windows) {
+    temp_dir.path().join("welcome.exe")
+  } else {
+    temp_dir.path().join("welcome")
+  };
+  let main_path = util::root_path().join("test_util/std/examples/welcome.ts");
+  let output = context
+    .new_command()
+    .args_vec([
+      "compile",
+      "--log-file",
+      "welcome.log",
+      "--output",
+      &exe.to_string_lossy(),
+      &main_path.to_string_lossy(),
+    ])
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  // run it twice to check that the output is appended
+  for _ in 0..2 {
+    let output = context
+      .new_command()
+      .command_name(exe.to_string_lossy())
+      .run();
+    output.assert_exit_code(0);
+    output.assert_matches_text("");
+  }
+  assert_eq!(
+    temp_dir.read_to_string("welcome.log"),
+    "Welcome to Deno!\nWelcome to Deno!\n"
+  );
+}
+
 #[test]
 fn standalone_args() {
   let dir = TempDir::new();
@@ -374,6 +497,39 @@ fn compile_and_overwrite_file() {
   assert!(recompile_output.status.success());
 }
 
+#[test]
+fn standalone_baked_permissions() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let exe = if cfg!(windows) {
+    temp_dir.path().join("baked_permissions.exe")
+  } else {
+    temp_dir.path().join("baked_permissions")
+  };
+  let main_path = util::testdata_path().join("./compile/baked_permissions.ts");
+  let output = context
+    .new_command()
+    .args_vec([
+      "compile",
+      "--allow-read=.",
+      "--output",
+      &exe.to_string_lossy(),
+      &main_path.to_string_lossy(),
+    ])
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  // it doesn't prompt for the permissions that weren't granted
+  context
+    .new_command()
+    .command_name(exe.to_string_lossy())
+    .with_pty(|mut console| {
+      console.expect("granted");
+      console.expect("denied");
+    });
+}
+
 #[test]
 fn standalone_runtime_flags() {
   let dir = TempDir::new();
@@ -769,6 +925,38 @@ fn dynamic_import() {
   assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
 }
 
+#[test]
+fn include_asset() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("include_asset.exe")
+  } else {
+    dir.path().join("include_asset")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path().join("./compile/include_asset"))
+    .arg("compile")
+    .arg("--include-asset")
+    .arg("templates")
+    .arg("--output")
+    .arg(&exe)
+    .arg("main.ts")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+
+  let output = Command::new(&exe)
+    .current_dir(dir.path())
+    .env("NO_COLOR", "")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "<h1>Hello</h1>\n\nUint8Array(3) [ 0, 255, 16 ]\n"
+  );
+}
+
 #[test]
 fn dynamic_import_unanalyzable() {
   let _guard = util::http_server();
@@ -803,20 +991,38 @@ fn dynamic_import_unanalyzable() {
   assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
 }
 
-itest!(npm_specifiers_errors_no_unstable {
-  args: "compile -A --quiet npm/cached_only/main.ts",
-  output_str: Some(
-    concat!(
-      "error: Using npm specifiers with deno compile requires the --unstable flag.",
-      "\n\n",
-      "Caused by:\n",
-      "    npm specifiers have not yet been implemented for this subcommand (https://github.com/denoland/deno/issues/15960). Found: npm:chalk@5.0.1\n"
-    )
-  ),
-  exit_code: 1,
-  envs: env_vars_for_npm_tests(),
-  http_server: true,
-});
+#[test]
+fn dynamic_import_unanalyzable_glob_include() {
+  let _guard = util::http_server();
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir
+      .path()
+      .join("dynamic_import_unanalyzable_glob_include.exe")
+  } else {
+    dir.path().join("dynamic_import_unanalyzable_glob_include")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--allow-read")
+    .arg("--include")
+    .arg("./compile/dynamic_imports/import*.ts")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./compile/dynamic_imports/main_unanalyzable.ts")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+
+  let output = Command::new(&exe).env("NO_COLOR", "").output().unwrap();
+  assert!(output.status.success());
+  let expected = std::fs::read_to_string(
+    util::testdata_path().join("./compile/dynamic_imports/main.out"),
+  )
+  .unwrap();
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
 
 #[test]
 fn compile_npm_specifiers() {
@@ -847,8 +1053,8 @@ fn compile_npm_specifiers() {
 
   // try with and without --node-modules-dir
   let compile_commands = &[
-    "compile --unstable --output binary main.ts",
-    "compile --unstable --node-modules-dir --output binary main.ts",
+    "compile --output binary main.ts",
+    "compile --node-modules-dir --output binary main.ts",
   ];
 
   for compile_command in compile_commands {
@@ -910,7 +1116,7 @@ testing[WILDCARD]this
 
   let output = context
     .new_command()
-    .args("compile --unstable --output binary main.ts")
+    .args("compile --output binary main.ts")
     .run();
   output.assert_exit_code(0);
   output.skip_output_check();
@@ -922,6 +1128,54 @@ testing[WILDCARD]this
   output.assert_matches_text("2\n");
 }
 
+#[test]
+fn compile_reproducible() {
+  let context = TestContextBuilder::for_npm()
+    .use_sync_npm_download()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "main.ts",
+    concat!(
+      "import { getValue, setValue } from 'npm:@denotest/esm-basic';\n",
+      "import getValueDefault from 'npm:@denotest/esm-import-cjs-default';\n",
+      "setValue(2);\n",
+      "console.log(getValue());",
+      "console.log(getValueDefault());",
+    ),
+  );
+
+  // a lockfile is required
+  let output = context
+    .new_command()
+    .args("compile --reproducible --output first main.ts")
+    .run();
+  output.assert_exit_code(1);
+  output
+    .assert_matches_text("error: --reproducible requires a lockfile[WILDCARD]");
+
+  temp_dir.write("deno.json", "{}");
+  for name in ["first", "second"] {
+    let output = context
+      .new_command()
+      .args_vec(["compile", "--reproducible", "--output", name, "main.ts"])
+      .run();
+    output.assert_exit_code(0);
+    output.skip_output_check();
+  }
+
+  let read = |name: &str| {
+    let name = if cfg!(windows) {
+      format!("{name}.exe")
+    } else {
+      name.to_string()
+    };
+    std::fs::read(temp_dir.path().join(name)).unwrap()
+  };
+  assert!(read("first") == read("second"));
+}
+
 #[test]
 fn compile_npm_file_system() {
   run_npm_bin_compile_test(RunNpmBinCompileOptions {
@@ -1020,11 +1274,7 @@ fn run_npm_bin_compile_test(opts: RunNpmBinCompileOptions) {
       .to_string()
   };
 
-  let mut args = vec![
-    "compile".to_string(),
-    "-A".to_string(),
-    "--unstable".to_string(),
-  ];
+  let mut args = vec!["compile".to_string(), "-A".to_string()];
 
   if opts.node_modules_dir {
     args.push("--node-modules-dir".to_string());
@@ -1055,3 +1305,21 @@ fn run_npm_bin_compile_test(opts: RunNpmBinCompileOptions) {
     .run();
   output.assert_matches_file(opts.output_file);
 }
+
+itest!(compile_target_list {
+  args: "compile --target list",
+  output_str: Some(concat!(
+    "x86_64-unknown-linux-gnu\n",
+    "aarch64-unknown-linux-gnu\n",
+    "x86_64-pc-windows-msvc\n",
+    "aarch64-pc-windows-msvc\n",
+    "x86_64-apple-darwin\n",
+    "aarch64-apple-darwin\n",
+  )),
+});
+
+itest!(compile_windows_resources_other_target {
+  args: "compile --target x86_64-unknown-linux-gnu --product-name App compile/args.ts",
+  output_str: Some("error: --icon, --product-name and --file-version are only supported when compiling for Windows.\n"),
+  exit_code: 1,
+});
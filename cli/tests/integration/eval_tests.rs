@@ -54,6 +54,18 @@ itest!(eval_ts {
   output_str: Some("123\n"),
 });
 
+itest!(eval_stdin {
+  args: "eval -",
+  input: Some("console.log('hello from stdin')"),
+  output_str: Some("hello from stdin\n"),
+});
+
+itest!(eval_stdin_print_ts {
+  args: "eval --quiet --ext=ts -p -",
+  input: Some("(1 as number) + 2;\n"),
+  output_str: Some("3\n"),
+});
+
 itest!(dyn_import_eval {
   args: "eval import('./subdir/mod4.js').then(console.log)",
   output: "eval/dyn_import_eval.out",
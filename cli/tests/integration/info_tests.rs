@@ -126,6 +126,11 @@ itest!(_054_info_local_imports {
   exit_code: 0,
 });
 
+itest!(info_crons {
+  args: "info --quiet info/crons.ts",
+  output: "info/crons.out",
+});
+
 // Tests for AssertionError where "data" is unexpectedly null when
 // a file contains only triple slash references (#11196)
 itest!(data_null_error {
@@ -0,0 +1,233 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use bytes::Bytes;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use ring::hmac;
+use test_util as util;
+use test_util::TempDir;
+use zeromq::DealerSocket;
+use zeromq::Socket;
+use zeromq::SocketRecv;
+use zeromq::SocketSend;
+use zeromq::SubSocket;
+use zeromq::ZmqMessage;
+
+const DELIMITER: &[u8] = b"<IDS|MSG>";
+const KEY: &str = "jupyter-test-key";
+
+fn free_port() -> u16 {
+  let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+  listener.local_addr().unwrap().port()
+}
+
+fn sign(parts: &[&[u8]]) -> String {
+  let key = hmac::Key::new(hmac::HMAC_SHA256, KEY.as_bytes());
+  let mut context = hmac::Context::with_key(&key);
+  for part in parts {
+    context.update(part);
+  }
+  context
+    .sign()
+    .as_ref()
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect()
+}
+
+fn new_message(msg_id: &str, msg_type: &str, content: Value) -> ZmqMessage {
+  let header = serde_json::to_vec(&json!({
+    "msg_id": msg_id,
+    "session": "session",
+    "username": "test",
+    "msg_type": msg_type,
+    "version": "5.3",
+  }))
+  .unwrap();
+  let parent_header = b"{}".to_vec();
+  let metadata = b"{}".to_vec();
+  let content = serde_json::to_vec(&content).unwrap();
+  let signature = sign(&[&header, &parent_header, &metadata, &content]);
+  ZmqMessage::try_from(vec![
+    Bytes::from_static(DELIMITER),
+    Bytes::from(signature),
+    Bytes::from(header),
+    Bytes::from(parent_header),
+    Bytes::from(metadata),
+    Bytes::from(content),
+  ])
+  .unwrap()
+}
+
+/// A message received from the kernel, whose signature was verified.
+struct ReceivedMessage {
+  msg_type: String,
+  parent_msg_id: String,
+  content: Value,
+}
+
+fn parse_message(message: ZmqMessage) -> ReceivedMessage {
+  let frames = message.into_vec();
+  let delimiter = frames
+    .iter()
+    .position(|frame| &frame[..] == DELIMITER)
+    .unwrap();
+  let parts = &frames[delimiter + 1..];
+  assert_eq!(
+    std::str::from_utf8(&parts[0]).unwrap(),
+    sign(&[&parts[1], &parts[2], &parts[3], &parts[4]])
+  );
+  let header: Value = serde_json::from_slice(&parts[1]).unwrap();
+  let parent_header: Value = serde_json::from_slice(&parts[2]).unwrap();
+  ReceivedMessage {
+    msg_type: header["msg_type"].as_str().unwrap().to_string(),
+    parent_msg_id: parent_header["msg_id"]
+      .as_str()
+      .unwrap_or_default()
+      .to_string(),
+    content: serde_json::from_slice(&parts[4]).unwrap(),
+  }
+}
+
+async fn connect<S: Socket>(port: u16) -> S {
+  let endpoint = format!("tcp://127.0.0.1:{port}");
+  // the kernel binds its sockets once it started
+  for _ in 0..300 {
+    let mut socket = S::new();
+    if socket.connect(&endpoint).await.is_ok() {
+      return socket;
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+  }
+  panic!("Failed to connect to {endpoint}");
+}
+
+/// Receives the messages published in response to a request, up to the one
+/// telling that the kernel is idle again.
+async fn recv_published(
+  iopub: &mut SubSocket,
+  msg_id: &str,
+) -> Vec<ReceivedMessage> {
+  let mut messages = Vec::new();
+  loop {
+    let message = parse_message(iopub.recv().await.unwrap());
+    if message.parent_msg_id != msg_id {
+      continue;
+    }
+    let is_idle = message.msg_type == "status"
+      && message.content["execution_state"] == "idle";
+    messages.push(message);
+    if is_idle {
+      return messages;
+    }
+  }
+}
+
+#[tokio::test]
+async fn jupyter_execute_request() {
+  let temp_dir = TempDir::new();
+  let shell_port = free_port();
+  let iopub_port = free_port();
+  let conn_file = temp_dir.path().join("connection.json");
+  std::fs::write(
+    &conn_file,
+    json!({
+      "ip": "127.0.0.1",
+      "transport": "tcp",
+      "control_port": free_port(),
+      "shell_port": shell_port,
+      "hb_port": free_port(),
+      "iopub_port": iopub_port,
+      "signature_scheme": "hmac-sha256",
+      "key": KEY,
+    })
+    .to_string(),
+  )
+  .unwrap();
+  let mut child = util::deno_cmd()
+    .arg("jupyter")
+    .arg("--kernel")
+    .arg("--conn")
+    .arg(&conn_file)
+    .stdout(Stdio::null())
+    .spawn()
+    .unwrap();
+
+  let mut shell = connect::<DealerSocket>(shell_port).await;
+  let mut iopub = connect::<SubSocket>(iopub_port).await;
+  iopub.subscribe("").await.unwrap();
+
+  // messages published before the subscription is set up are lost, so wait
+  // until the status updates of a request arrive
+  for i in 0.. {
+    let msg_id = format!("kernel_info_{i}");
+    shell
+      .send(new_message(&msg_id, "kernel_info_request", json!({})))
+      .await
+      .unwrap();
+    let reply = parse_message(shell.recv().await.unwrap());
+    assert_eq!(reply.msg_type, "kernel_info_reply");
+    assert_eq!(reply.parent_msg_id, msg_id);
+    let published = tokio::time::timeout(
+      Duration::from_secs(1),
+      recv_published(&mut iopub, &msg_id),
+    )
+    .await;
+    if published.is_ok() {
+      break;
+    }
+    assert!(i < 30, "No status updates were published");
+  }
+
+  shell
+    .send(new_message(
+      "execute",
+      "execute_request",
+      json!({
+        "code": "console.log('hello from the kernel'); 1 + 2",
+        "silent": false,
+      }),
+    ))
+    .await
+    .unwrap();
+  let reply = parse_message(shell.recv().await.unwrap());
+  assert_eq!(reply.msg_type, "execute_reply");
+  assert_eq!(reply.parent_msg_id, "execute");
+  assert_eq!(reply.content["status"], "ok");
+  assert_eq!(reply.content["execution_count"], 1);
+
+  let published = recv_published(&mut iopub, "execute").await;
+  let find = |msg_type: &str| {
+    published
+      .iter()
+      .find(|message| message.msg_type == msg_type)
+      .unwrap_or_else(|| panic!("No {msg_type} message was published"))
+  };
+  assert_eq!(published[0].msg_type, "status");
+  assert_eq!(published[0].content["execution_state"], "busy");
+  let input = find("execute_input");
+  assert_eq!(
+    input.content["code"],
+    "console.log('hello from the kernel'); 1 + 2"
+  );
+  assert_eq!(input.content["execution_count"], 1);
+  let stream = find("stream");
+  assert_eq!(stream.content["name"], "stdout");
+  assert!(stream.content["text"]
+    .as_str()
+    .unwrap()
+    .contains("hello from the kernel"));
+  let result = find("execute_result");
+  assert_eq!(result.content["execution_count"], 1);
+  assert!(result.content["data"]["text/plain"]
+    .as_str()
+    .unwrap()
+    .contains('3'));
+
+  child.kill().unwrap();
+  child.wait().unwrap();
+}
@@ -121,6 +121,8 @@ mod inspector;
 mod install;
 #[path = "js_unit_tests.rs"]
 mod js_unit_tests;
+#[path = "jupyter_tests.rs"]
+mod jupyter;
 #[path = "lint_tests.rs"]
 mod lint;
 #[path = "lsp_tests.rs"]
@@ -131,6 +133,8 @@ mod node_compat_tests;
 mod node_unit_tests;
 #[path = "npm_tests.rs"]
 mod npm;
+#[path = "publish_tests.rs"]
+mod publish;
 #[path = "repl_tests.rs"]
 mod repl;
 #[path = "run_tests.rs"]
@@ -855,13 +855,213 @@ fn ensure_registry_files_local() {
   }
 }
 
-itest!(bundle_errors {
-    args: "bundle --quiet npm/esm/main.js",
-    output_str: Some("error: npm specifiers have not yet been implemented for this subcommand (https://github.com/denoland/deno/issues/15960). Found: npm:chalk@5.0.1\n"),
-    exit_code: 1,
-    envs: env_vars_for_npm_tests(),
-    http_server: true,
-  });
+#[test]
+fn bundle_inlines_esm_packages() {
+  let context = TestContextBuilder::for_npm()
+    .use_sync_npm_download()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "main.js",
+    "import chalk from 'npm:chalk@5';\nconsole.log(chalk.green('chalk esm loads'));\n",
+  );
+
+  let output = context
+    .new_command()
+    .args("bundle --quiet main.js bundle.js")
+    .run();
+  output.assert_exit_code(0);
+  let bundle_text = temp_dir.read_to_string("bundle.js");
+  assert!(!bundle_text.contains("npm:chalk"));
+
+  let output = context
+    .new_command()
+    .args("run --allow-read --allow-env bundle.js")
+    .run();
+  output.assert_matches_text("chalk esm loads\n");
+}
+
+#[test]
+fn npm_cache_dir() {
+  let context = TestContextBuilder::for_npm()
+    .use_sync_npm_download()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  let deno_dir = context.deno_dir();
+  let package_folder = "localhost_4545/npm/registry/@denotest/esm-basic/1.0.0";
+  temp_dir.write("deno.json", r#"{ "npmCacheDir": "npm_cache" }"#);
+  temp_dir.write(
+    "main.js",
+    concat!(
+      "import { getValue, setValue } from 'npm:@denotest/esm-basic@1.0.0';\n",
+      "setValue(5);\n",
+      "console.log(getValue());\n",
+    ),
+  );
+  // the config file takes precedence over the environment variable
+  let output = context
+    .new_command()
+    .env("DENO_NPM_DIR", "env_npm_cache")
+    .args("run main.js")
+    .run();
+  output.assert_matches_text("[WILDCARD]5\n");
+  output.assert_exit_code(0);
+  assert!(!temp_dir.path().join("env_npm_cache").exists());
+  assert!(temp_dir
+    .path()
+    .join(format!("npm_cache/{package_folder}/main.mjs"))
+    .exists());
+  assert!(!deno_dir.path().join("npm/localhost_4545").exists());
+
+  // without a config file, the environment variable is used
+  let output = context
+    .new_command()
+    .env("DENO_NPM_DIR", "env_npm_cache")
+    .args("run --no-config main.js")
+    .run();
+  output.assert_matches_text("[WILDCARD]5\n");
+  output.assert_exit_code(0);
+  assert!(temp_dir
+    .path()
+    .join(format!("env_npm_cache/{package_folder}/main.mjs"))
+    .exists());
+  assert!(!deno_dir.path().join("npm/localhost_4545").exists());
+}
+
+#[test]
+fn npm_cache_max_size() {
+  let context = TestContextBuilder::for_npm()
+    .use_sync_npm_download()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  let deno_dir = context.deno_dir();
+  let registry_folder = "npm/localhost_4545/npm/registry/@denotest";
+  temp_dir.write("a.js", "import 'npm:@denotest/esm-basic@1.0.0';\n");
+  temp_dir.write("b.js", "import 'npm:@denotest/cjs-default-export@1.0.0';\n");
+
+  let output = context
+    .new_command()
+    .env("DENO_NPM_CACHE_MAX_SIZE", "1")
+    .args("cache a.js")
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+  // the packages of the run are never evicted
+  assert!(deno_dir
+    .path()
+    .join(format!("{registry_folder}/esm-basic/1.0.0"))
+    .exists());
+
+  let output = context
+    .new_command()
+    .env("DENO_NPM_CACHE_MAX_SIZE", "1")
+    .args("cache b.js")
+    .run();
+  output.assert_matches_text(
+    "[WILDCARD]Evicted 1 npm package ([WILDCARD]) to keep the npm cache below 1B\n",
+  );
+  output.assert_exit_code(0);
+  assert!(!deno_dir
+    .path()
+    .join(format!("{registry_folder}/esm-basic/1.0.0"))
+    .exists());
+  assert!(deno_dir
+    .path()
+    .join(format!("{registry_folder}/cjs-default-export/1.0.0"))
+    .exists());
+}
+
+#[test]
+fn cache_verify_npm() {
+  let context = TestContextBuilder::for_npm()
+    .use_sync_npm_download()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  let deno_dir = context.deno_dir();
+  temp_dir.write("main.js", "import 'npm:@denotest/esm-basic@1.0.0';\n");
+  let output = context.new_command().args("cache main.js").run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let output = context.new_command().args("cache --verify-npm").run();
+  output.assert_matches_text(
+    "Verified [WILDCARD] npm package folder[WILDCARD], 0 modified\n",
+  );
+  output.assert_exit_code(0);
+
+  // modifying the hard link also modifies the stored file
+  let package_folder =
+    "npm/localhost_4545/npm/registry/@denotest/esm-basic/1.0.0";
+  deno_dir.write(format!("{package_folder}/main.mjs"), "tampered");
+  let output = context.new_command().args("cache --verify-npm").run();
+  output.assert_matches_text(concat!(
+    "Removed [WILDCARD]@denotest/esm-basic/1.0.0 (main.mjs), it's downloaded again when it's used next\n",
+    "Verified [WILDCARD], 1 modified\n",
+  ));
+  output.assert_exit_code(1);
+  assert!(!deno_dir.path().join(package_folder).exists());
+
+  // only the removed package is downloaded again
+  let output = context.new_command().args("cache main.js").run();
+  output.assert_matches_text(
+    "Download http://localhost:4545/npm/registry/@denotest/esm-basic/1.0.0.tgz\n",
+  );
+  let output = context.new_command().args("cache --verify-npm").run();
+  output.assert_matches_text("Verified [WILDCARD], 0 modified\n");
+  output.assert_exit_code(0);
+}
+
+#[test]
+fn cache_check_npm_integrity() {
+  let context = TestContextBuilder::for_npm()
+    .use_sync_npm_download()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  let deno_dir = context.deno_dir();
+  temp_dir.write("main.js", "import 'npm:@denotest/esm-basic@1.0.0';\n");
+  let output = context.new_command().args("cache main.js").run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let output = context
+    .new_command()
+    .args("cache --check-npm-integrity")
+    .run();
+  output.assert_matches_text("Verified [WILDCARD], 0 modified\n");
+  output.assert_exit_code(0);
+
+  // the audit only reports the modified package
+  let package_folder =
+    "npm/localhost_4545/npm/registry/@denotest/esm-basic/1.0.0";
+  deno_dir.write(format!("{package_folder}/main.mjs"), "tampered");
+  let output = context
+    .new_command()
+    .args("cache --check-npm-integrity")
+    .run();
+  output.assert_matches_text(concat!(
+    "Modified [WILDCARD]@denotest/esm-basic/1.0.0 (main.mjs)\n",
+    "Verified [WILDCARD], 1 modified\n",
+    "Run with --repair to fix the modified folders\n",
+  ));
+  output.assert_exit_code(1);
+  assert!(deno_dir.path().join(package_folder).exists());
+
+  let output = context
+    .new_command()
+    .args("cache --check-npm-integrity --repair")
+    .run();
+  output.assert_matches_text(concat!(
+    "Removed [WILDCARD]@denotest/esm-basic/1.0.0 (main.mjs), it's downloaded again when it's used next\n",
+    "Verified [WILDCARD], 1 modified\n",
+  ));
+  output.assert_exit_code(1);
+  assert!(!deno_dir.path().join(package_folder).exists());
+}
 
 itest!(info_chalk_display {
   args: "info --quiet npm/cjs_with_deps/main.js",
@@ -0,0 +1,112 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use test_util::TestContext;
+use test_util::TestContextBuilder;
+
+fn publish_context(publish_config: &str) -> TestContext {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    format!(
+      r#"{{
+  "name": "@scope/pkg",
+  "version": "1.0.0",
+  "exports": {{ ".": "./mod.ts" }},
+  "publish": {publish_config}
+}}"#
+    ),
+  );
+  temp_dir.write("mod.ts", "export const value = 1;\n");
+  context
+}
+
+#[test]
+fn publish_requires_registry() {
+  let context = publish_context("{}");
+  let output = context
+    .new_command()
+    .args("publish --token publish-token")
+    .run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "[WILDCARD]error: No registry to publish to, pass --registry or configure the \"registry\" of the \"publish\" configuration.\n",
+  );
+}
+
+#[test]
+fn publish_with_token_flag() {
+  let context = publish_context("{}");
+  let output = context
+    .new_command()
+    .args("publish --registry http://localhost:4545 --token publish-token")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "[WILDCARD]Published @scope/pkg@1.0.0 to http://localhost:4545/\n",
+  );
+
+  let output = context
+    .new_command()
+    .args("publish --registry http://localhost:4545 --token invalid-token")
+    .run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "[WILDCARD]error: Failed to publish @scope/pkg@1.0.0 to http://localhost:4545/: 401 Unauthorized\nInvalid token\n",
+  );
+}
+
+#[test]
+fn publish_with_auth_tokens_env() {
+  let context = publish_context(r#"{ "registry": "http://localhost:4545/" }"#);
+  let output = context
+    .new_command()
+    .env("DENO_AUTH_TOKENS", "publish-token@localhost:4545")
+    .args("publish")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "[WILDCARD]Published @scope/pkg@1.0.0 to http://localhost:4545/\n",
+  );
+
+  let output = context.new_command().args("publish").run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "[WILDCARD]error: No token to publish to http://localhost:4545/, pass --token or configure one in the \"auth\" configuration or DENO_AUTH_TOKENS.\n",
+  );
+}
+
+#[test]
+fn publish_allow_dirty() {
+  let context = publish_context("{}");
+  let temp_dir = context.temp_dir();
+  // the files of the package are untracked changes of the new repository
+  let status = std::process::Command::new("git")
+    .arg("init")
+    .current_dir(temp_dir.path())
+    .stdout(std::process::Stdio::null())
+    .status()
+    .unwrap();
+  assert!(status.success());
+
+  let output = context
+    .new_command()
+    .args("publish --registry http://localhost:4545 --token publish-token")
+    .run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "[WILDCARD]error: Aborting because of uncommitted changes, commit them or pass --allow-dirty:\n  ?? deno.json\n  ?? mod.ts\n",
+  );
+
+  let output = context
+    .new_command()
+    .args("publish --registry http://localhost:4545 --token publish-token --allow-dirty")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "[WILDCARD]Published @scope/pkg@1.0.0 to http://localhost:4545/\n",
+  );
+}
@@ -1255,6 +1255,24 @@ itest!(seed_random {
   output: "run/seed_random.js.out",
 });
 
+itest!(env_file {
+  args: "run --allow-env --env=run/env_file/app.env run/env_file/main.js",
+  output: "run/env_file/main.out",
+  envs: vec![("EXISTING".to_string(), "from_process".to_string())],
+});
+
+itest!(env_file_no_override {
+  args: "run --allow-env --env=run/env_file/app.env --env=run/env_file/local.env --no-env-override run/env_file/main.js",
+  output: "run/env_file/no_override.out",
+  envs: vec![("EXISTING".to_string(), "from_process".to_string())],
+});
+
+itest!(env_file_missing {
+  args: "run --allow-env --env=run/env_file/missing.env run/env_file/main.js",
+  output: "run/env_file/missing.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
 itest!(type_definitions {
   args: "run --reload run/type_definitions.ts",
   output: "run/type_definitions.ts.out",
@@ -4305,6 +4323,20 @@ itest!(permission_args {
   envs: vec![("NO_COLOR".to_string(), "1".to_string())],
 });
 
+itest!(permissions_policy_deny_env_to_object {
+  args: "run --permissions=run/permissions_policy_deny/policy.json run/permissions_policy_deny/env_to_object.js",
+  output: "run/permissions_policy_deny/env_to_object.out",
+  envs: vec![
+    ("PUBLIC_VALUE".to_string(), "public".to_string()),
+    ("SECRET_TOKEN".to_string(), "secret".to_string()),
+  ],
+});
+
+itest!(permissions_policy_deny_symlink {
+  args: "run --permissions=run/permissions_policy_deny/policy.json run/permissions_policy_deny/symlink.js",
+  output: "run/permissions_policy_deny/symlink.out",
+});
+
 itest!(permission_args_quiet {
   args: "run --quiet run/001_hello.js --allow-net",
   output: "run/001_hello.js.out",
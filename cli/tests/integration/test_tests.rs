@@ -295,6 +295,36 @@ itest!(filter {
   output: "test/filter.out",
 });
 
+itest!(timeout {
+  args: "test test/timeout.ts",
+  exit_code: 1,
+  output: "test/timeout.out",
+});
+
+itest!(timeout_flag {
+  args: "test --timeout=100 test/timeout_blocking.ts",
+  exit_code: 1,
+  output: "test/timeout_blocking.out",
+});
+
+itest!(reporter_json {
+  args: "test --reporter=json test/reporter_json.ts",
+  exit_code: 1,
+  output: "test/reporter_json.out",
+});
+
+itest!(shard {
+  args: "test --shard=2/2 test/filter",
+  exit_code: 0,
+  output: "test/shard.out",
+});
+
+itest!(isolate {
+  args: "test --isolate --allow-env test/isolate",
+  exit_code: 0,
+  output: "test/isolate.out",
+});
+
 itest!(shuffle {
   args: "test --shuffle test/shuffle",
   exit_code: 0,
@@ -781,6 +781,109 @@ async fn run_watch_external_watch_files() {
   check_alive_then_kill(child);
 }
 
+#[tokio::test]
+async fn run_watch_exclude() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.js");
+  let generated_dir = t.path().join("generated");
+  std::fs::create_dir(&generated_dir).unwrap();
+  let generated_file = generated_dir.join("runs.txt");
+  // Each run writes to a generated file in the watched directory, which
+  // would restart the process in a loop unless it's excluded.
+  write(
+    &file_to_watch,
+    format!(
+      r#"
+      const file = {:?};
+      let runs = 1;
+      try {{
+        runs += Number(Deno.readTextFileSync(file));
+      }} catch {{}}
+      Deno.writeTextFileSync(file, String(runs));
+      console.log(`runs: ${{runs}}`);
+      "#,
+      generated_file.to_string_lossy()
+    ),
+  )
+  .unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("run")
+    .arg(format!("--watch={}", t.path().to_string_lossy()))
+    .arg("--watch-exclude=generated")
+    .arg("-L")
+    .arg("debug")
+    .arg("-A")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Process started", &mut stderr_lines).await;
+  assert_eq!(next_line(&mut stdout_lines).await.unwrap(), "runs: 1");
+  wait_contains("Process finished", &mut stderr_lines).await;
+
+  write(&generated_file, "10").unwrap();
+  write(t.path().join("other.txt"), "Hello world").unwrap();
+  wait_contains("Restarting", &mut stderr_lines).await;
+  assert_eq!(next_line(&mut stdout_lines).await.unwrap(), "runs: 11");
+  wait_contains("Process finished", &mut stderr_lines).await;
+
+  check_alive_then_kill(child);
+}
+
+#[tokio::test]
+async fn run_watch_config_hooks() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.js");
+  write(&file_to_watch, "console.log('run');").unwrap();
+  write(
+    t.path().join("deno.json"),
+    r#"{
+      "watch": {
+        "debounce": 50,
+        "beforeRestart": "echo before",
+        "afterRestart": "echo after"
+      }
+    }"#,
+  )
+  .unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("run")
+    .arg("--watch")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Process started", &mut stderr_lines).await;
+  // The hooks don't run for the first run.
+  assert_eq!(next_line(&mut stdout_lines).await.unwrap(), "run");
+  wait_contains("Process finished", &mut stderr_lines).await;
+
+  write(&file_to_watch, "console.log('rerun');").unwrap();
+  wait_contains("Restarting", &mut stderr_lines).await;
+  wait_contains("Running beforeRestart hook", &mut stderr_lines).await;
+  assert_eq!(next_line(&mut stdout_lines).await.unwrap(), "before");
+  wait_contains("Running afterRestart hook", &mut stderr_lines).await;
+  let mut lines = vec![
+    next_line(&mut stdout_lines).await.unwrap(),
+    next_line(&mut stdout_lines).await.unwrap(),
+  ];
+  lines.sort();
+  assert_eq!(lines, vec!["after", "rerun"]);
+  wait_contains("Process finished", &mut stderr_lines).await;
+
+  check_alive_then_kill(child);
+}
+
 #[tokio::test]
 async fn run_watch_load_unload_events() {
   let t = TempDir::new();
@@ -0,0 +1,5 @@
+import { greet } from "./greet.ts";
+
+export function lazy() {
+  greet("lazy");
+}
@@ -0,0 +1,5 @@
+import { greet } from "./greet.ts";
+
+greet("main");
+const { lazy } = await import("./lazy.ts");
+lazy();
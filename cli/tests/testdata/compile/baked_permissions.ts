@@ -0,0 +1,2 @@
+console.log((await Deno.permissions.query({ name: "read", path: "." })).state);
+console.log((await Deno.permissions.request({ name: "env" })).state);
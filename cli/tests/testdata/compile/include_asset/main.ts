@@ -0,0 +1,7 @@
+for (const name of ["page.html", "logo.bin"]) {
+  const url = new URL(`./templates/${name}`, import.meta.url);
+  const { default: page } = await import(url.href, {
+    assert: { type: name.endsWith(".bin") ? "bytes" : "text" },
+  });
+  console.log(page);
+}
@@ -0,0 +1,2 @@
+Deno.cron("cleanup", "0 * * * *", () => {});
+Deno.cron(`report`, "*/5 * * * 1-5", { signal: AbortSignal.timeout(1) }, () => {});
@@ -112,10 +112,25 @@ async function testFetchProgrammaticProxy() {
   assertEquals(code, 0);
 }
 
+async function testFetchProgrammaticNoProxy() {
+  const { code } = await new Deno.Command(Deno.execPath(), {
+    args: [
+      "run",
+      "--quiet",
+      "--reload",
+      "--allow-net=localhost:4545,not.exising.proxy.server",
+      "--unstable",
+      "run/045_programmatic_no_proxy_client.ts",
+    ],
+  }).output();
+  assertEquals(code, 0);
+}
+
 proxyServer();
 await testFetch();
 await testModuleDownload();
 await testFetchNoProxy();
 await testModuleDownloadNoProxy();
 await testFetchProgrammaticProxy();
+await testFetchProgrammaticNoProxy();
 Deno.exit(0);
@@ -0,0 +1,7 @@
+console.log(Deno.env.get("PUBLIC_VALUE"));
+console.log(Deno.permissions.querySync({ name: "env" }).state);
+try {
+  Deno.env.toObject();
+} catch (err) {
+  console.log(err.name);
+}
@@ -0,0 +1,12 @@
+const dir = Deno.makeTempDirSync();
+try {
+  Deno.symlinkSync(new URL("./secrets/key", import.meta.url), `${dir}/a`);
+} catch (err) {
+  console.log(err.name);
+}
+try {
+  await Deno.symlink(new URL("./secrets/key", import.meta.url), `${dir}/b`);
+} catch (err) {
+  console.log(err.name);
+}
+Deno.removeSync(dir, { recursive: true });
@@ -0,0 +1,5 @@
+Deno.test("mutates global state", () => {
+  // deno-lint-ignore no-explicit-any
+  (globalThis as any).leaked = true;
+  Deno.env.set("DENO_TEST_ISOLATE_LEAKED", "1");
+});
@@ -0,0 +1,8 @@
+Deno.test("doesn't see global state of other files", () => {
+  if ("leaked" in globalThis) {
+    throw new Error("globalThis.leaked was set by another test file");
+  }
+  if (Deno.env.get("DENO_TEST_ISOLATE_LEAKED") !== undefined) {
+    throw new Error("DENO_TEST_ISOLATE_LEAKED was set by another test file");
+  }
+});
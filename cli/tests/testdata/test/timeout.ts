@@ -0,0 +1,5 @@
+Deno.test("hangs", { timeout: 100 }, async () => {
+  await new Promise((resolve) => setTimeout(resolve, 60_000));
+});
+
+Deno.test("not run", () => {});
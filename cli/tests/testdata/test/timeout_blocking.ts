@@ -0,0 +1,5 @@
+Deno.test("blocks", () => {
+  while (true) {
+    // never yields to the event loop
+  }
+});
@@ -0,0 +1,29 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// Transferring an `ArrayBuffer` moves its backing store to the receiving
+// worker without copying, so this should take about the same time no matter
+// how large the buffers are.
+const SIZE = 64 * 1024 * 1024;
+const ROUNDS = 100;
+
+function bench() {
+  return new Promise<void>((resolve) => {
+    const worker = new Worker(
+      import.meta.resolve("./worker_transfer.js"),
+      { type: "module" },
+    );
+    let rounds = ROUNDS;
+    worker.onmessage = (e) => {
+      if (--rounds === 0) {
+        worker.terminate();
+        resolve();
+        return;
+      }
+      worker.postMessage(e.data, [e.data]);
+    };
+    const buffer = new ArrayBuffer(SIZE);
+    worker.postMessage(buffer, [buffer]);
+  });
+}
+
+bench();
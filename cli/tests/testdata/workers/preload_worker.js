@@ -0,0 +1,2 @@
+self.postMessage(globalThis.preloaded);
+self.close();
@@ -747,6 +747,28 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "worker transfer ArrayBuffer",
+  fn: async function () {
+    assert(crossOriginIsolated);
+    const w = new Worker(
+      import.meta.resolve("./worker_transfer.js"),
+      { type: "module" },
+    );
+    const buffer = new ArrayBuffer(1024 * 1024);
+    new Uint8Array(buffer)[0] = 42;
+    const promise = deferred<ArrayBuffer>();
+    w.onmessage = (e) => promise.resolve(e.data);
+    w.postMessage(buffer, [buffer]);
+    // The buffer is detached once it's transferred.
+    assertEquals(buffer.byteLength, 0);
+    const received = await promise;
+    assertEquals(received.byteLength, 1024 * 1024);
+    assertEquals(new Uint8Array(received)[0], 42);
+    w.terminate();
+  },
+});
+
 Deno.test({
   name: "Send MessagePorts from / to workers",
   fn: async function () {
@@ -812,3 +834,40 @@ Deno.test({
     w.terminate();
   },
 });
+
+Deno.test({
+  name: "worker with preload modules",
+  fn: async function () {
+    const worker = new Worker(
+      new URL("preload_worker.js", import.meta.url).href,
+      {
+        type: "module",
+        deno: {
+          preload: [
+            new URL("preload_a.js", import.meta.url).href,
+            new URL("preload_b.js", import.meta.url).href,
+          ],
+        },
+      },
+    );
+    const promise = deferred();
+    worker.onmessage = (e) => promise.resolve(e.data);
+    assertEquals(await promise, ["a", "b"]);
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "worker with invalid preload modules",
+  fn: function () {
+    assertThrows(
+      () =>
+        new Worker(new URL("preload_worker.js", import.meta.url).href, {
+          type: "module",
+          // @ts-expect-error preload must be a sequence
+          deno: { preload: "preload_a.js" },
+        }),
+      TypeError,
+    );
+  },
+});
@@ -0,0 +1,5 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+onmessage = function (e) {
+  postMessage(e.data, [e.data]);
+};
@@ -885,3 +885,135 @@ Deno.test(
     );
   },
 );
+
+Deno.test(
+  { ignore: Deno.build.os === "windows", permissions: { run: true } },
+  async function commandKillProcessGroup() {
+    const child = new Deno.Command("sh", {
+      args: ["-c", "sleep 100 & echo $!; wait"],
+      stdout: "piped",
+      processGroup: 0,
+    }).spawn();
+    const reader = child.stdout.getReader();
+    const { value } = await reader.read();
+    const grandchildPid = parseInt(new TextDecoder().decode(value));
+    reader.releaseLock();
+
+    child.kill("SIGKILL", { processGroup: true });
+    const status = await child.status;
+    assertEquals(status.signal, "SIGKILL");
+    await child.stdout.cancel();
+
+    // The orphaned grandchild still has to be reaped by init.
+    for (let i = 0; i < 50; i++) {
+      try {
+        Deno.kill(grandchildPid, "SIGCONT");
+      } catch (error) {
+        assert(error instanceof Deno.errors.NotFound);
+        return;
+      }
+      await new Promise((resolve) => setTimeout(resolve, 20));
+    }
+    throw new Error("Process group was not killed");
+  },
+);
+
+Deno.test(
+  { permissions: { run: true, read: true } },
+  async function commandKillProcessGroupRequiresGroup() {
+    const child = new Deno.Command(Deno.execPath(), {
+      args: ["eval", "setTimeout(() => {}, 10000)"],
+      stdout: "null",
+      stderr: "null",
+    }).spawn();
+    assertThrows(
+      () => child.kill("SIGTERM", { processGroup: true }),
+      TypeError,
+      "Child process was not spawned with 'detached' or 'processGroup'",
+    );
+    child.kill("SIGKILL");
+    await child.status;
+  },
+);
+
+Deno.test(
+  { permissions: { run: true, read: true } },
+  async function commandDetached() {
+    const { success, stdout } = await new Deno.Command(Deno.execPath(), {
+      args: ["eval", "console.log('hello')"],
+      detached: true,
+    }).output();
+    assert(success);
+    assertEquals(new TextDecoder().decode(stdout), "hello\n");
+
+    assertThrows(
+      () =>
+        new Deno.Command(Deno.execPath(), {
+          detached: true,
+          processGroup: 0,
+        }).spawn(),
+      TypeError,
+      "The 'detached' and 'processGroup' options can't be used together",
+    );
+  },
+);
+
+Deno.test(
+  { ignore: Deno.build.os === "windows", permissions: { run: true } },
+  async function commandPty() {
+    const child = new Deno.Command("sh", {
+      args: [
+        "-c",
+        "test -t 0 && test -t 1 && stty size && read line && stty size && echo got $line",
+      ],
+      pty: { columns: 100, rows: 30 },
+    }).spawn();
+    child.resizePty({ columns: 120, rows: 40 });
+    const writer = child.stdin.getWriter();
+    await writer.write(new TextEncoder().encode("hello\n"));
+    writer.releaseLock();
+
+    const { success, stdout } = await child.output();
+    assert(success);
+    const output = new TextDecoder().decode(stdout);
+    assertStringIncludes(output, "got hello\r\n");
+    assertStringIncludes(output, "40 120\r\n");
+    assertThrows(() => child.stderr, TypeError, "stderr is not piped");
+    assertThrows(
+      () => child.resizePty({ columns: 80, rows: 24 }),
+      TypeError,
+      "Child process has already terminated.",
+    );
+  },
+);
+
+Deno.test(
+  {
+    ignore: Deno.build.os !== "windows",
+    permissions: { run: true, read: true },
+  },
+  async function commandSendCtrlBreakWindows() {
+    const child = new Deno.Command(Deno.execPath(), {
+      args: [
+        "eval",
+        `Deno.addSignalListener("SIGBREAK", () => {
+          console.log("break");
+          Deno.exit(0);
+        });
+        console.log("ready");
+        setTimeout(() => {}, 10000);`,
+      ],
+      stdout: "piped",
+      processGroup: 0,
+    }).spawn();
+    const reader = child.stdout.getReader();
+    const { value } = await reader.read();
+    assertStringIncludes(new TextDecoder().decode(value), "ready");
+    reader.releaseLock();
+
+    child.kill("SIGBREAK");
+    const { success, stdout } = await child.output();
+    assert(success);
+    assertStringIncludes(new TextDecoder().decode(stdout), "break");
+  },
+);
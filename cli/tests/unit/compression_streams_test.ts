@@ -0,0 +1,38 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+import { assertEquals, assertRejects } from "./test_util.ts";
+
+const data = new TextEncoder().encode("hello world\n".repeat(1000));
+
+async function roundTrip(format: string, input: Uint8Array) {
+  const compressed = await new Response(
+    new Blob([input]).stream().pipeThrough(new CompressionStream(format)),
+  ).arrayBuffer();
+  const decompressed = await new Response(
+    new Blob([compressed]).stream().pipeThrough(
+      new DecompressionStream(format),
+    ),
+  ).arrayBuffer();
+  return [compressed.byteLength, new Uint8Array(decompressed)] as const;
+}
+
+for (const format of ["brotli", "zstd"]) {
+  Deno.test(`compressionStream${format}RoundTrip`, async () => {
+    const [compressedLength, decompressed] = await roundTrip(format, data);
+    assertEquals(decompressed, data);
+    assertEquals(compressedLength < data.byteLength, true);
+  });
+
+  Deno.test(`compressionStream${format}Empty`, async () => {
+    const [, decompressed] = await roundTrip(format, new Uint8Array());
+    assertEquals(decompressed, new Uint8Array());
+  });
+
+  Deno.test(`decompressionStream${format}InvalidData`, async () => {
+    await assertRejects(() =>
+      new Response(
+        new Blob([data]).stream().pipeThrough(new DecompressionStream(format)),
+      ).arrayBuffer()
+    );
+  });
+}
@@ -0,0 +1,79 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+import { assertEquals, assertThrows } from "./test_util.ts";
+
+Deno.test(function cronInvalidName() {
+  assertThrows(
+    () => Deno.cron("abc!", "* * * * *", () => {}),
+    TypeError,
+    "Invalid cron name",
+  );
+  assertThrows(
+    () => Deno.cron("a".repeat(65), "* * * * *", () => {}),
+    TypeError,
+    "Invalid cron name",
+  );
+});
+
+Deno.test(function cronInvalidSchedule() {
+  for (
+    const schedule of [
+      "* * * *",
+      "60 * * * *",
+      "* 24 * * *",
+      "* * 0 * *",
+      "* * * 13 *",
+      "* * * * 8",
+      "5-1 * * * *",
+      "*/0 * * * *",
+      "a * * * *",
+    ]
+  ) {
+    assertThrows(
+      () => Deno.cron("invalid", schedule, () => {}),
+      TypeError,
+      "Invalid cron schedule",
+    );
+  }
+  assertThrows(
+    () => Deno.cron("never", "0 0 31 2 *", () => {}),
+    TypeError,
+    "Invalid cron schedule: it never runs",
+  );
+});
+
+Deno.test(function cronRequiresHandler() {
+  assertThrows(
+    // @ts-ignore testing invalid arguments
+    () => Deno.cron("handler", "* * * * *", {}),
+    TypeError,
+    "Deno.cron requires a handler",
+  );
+});
+
+Deno.test(async function cronUniqueNameAndAbort() {
+  const controller = new AbortController();
+  const promise = Deno.cron(
+    "unique",
+    "*/5 1-3,20 1,15 * 0",
+    { signal: controller.signal },
+    () => {},
+  );
+  assertThrows(
+    () => Deno.cron("unique", "* * * * *", () => {}),
+    TypeError,
+    'Cron with the name "unique" already exists',
+  );
+  controller.abort();
+  assertEquals(await promise, undefined);
+
+  // The name can be reused once the cron was aborted.
+  const controller2 = new AbortController();
+  const promise2 = Deno.cron(
+    "unique",
+    "* * * * *",
+    { signal: controller2.signal },
+    () => {},
+  );
+  controller2.abort();
+  await promise2;
+});
@@ -107,3 +107,57 @@ Deno.test(
     assertEquals(events, []);
   },
 );
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function watchFsExclude() {
+    const testDir = await makeTempDir();
+    Deno.mkdirSync(testDir + "/ignored");
+    const iter = Deno.watchFs(testDir, {
+      exclude: [testDir + "/ignored", testDir + "/*.tmp"],
+    });
+
+    const eventsPromise = getTwoEvents(iter);
+    Deno.writeFileSync(testDir + "/ignored/file.txt", new Uint8Array([0]));
+    Deno.writeFileSync(testDir + "/file.tmp", new Uint8Array([0]));
+    Deno.writeFileSync(testDir + "/file1.txt", new Uint8Array([0, 1, 2]));
+    Deno.writeFileSync(testDir + "/file2.txt", new Uint8Array([0, 1, 2]));
+
+    const events = await eventsPromise;
+    iter.close();
+    for (const event of events) {
+      for (const path of event.paths) {
+        assert(!path.includes("ignored"));
+        assert(!path.endsWith(".tmp"));
+      }
+    }
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function watchFsDebounce() {
+    const testDir = await makeTempDir();
+    const iter = Deno.watchFs(testDir, { debounce: 200 });
+
+    const file = testDir + "/file.txt";
+    Deno.writeFileSync(file, new Uint8Array([0]));
+    for (let i = 0; i < 5; i++) {
+      Deno.writeFileSync(file, new Uint8Array([i]), { append: true });
+    }
+
+    const timer = setTimeout(() => iter.close(), 1000);
+    const events = [];
+    for await (const event of iter) {
+      events.push(event);
+    }
+    clearTimeout(timer);
+    // Duplicate modify events were merged into one.
+    assertEquals(
+      events.filter((event) =>
+        event.kind === "modify" && event.paths[0] === file
+      ).length,
+      1,
+    );
+  },
+);
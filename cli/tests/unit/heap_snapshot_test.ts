@@ -0,0 +1,21 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+import { assert, assertEquals, assertThrows } from "./test_util.ts";
+
+Deno.test(
+  { permissions: { write: true, read: true } },
+  function writeHeapSnapshot() {
+    const dir = Deno.makeTempDirSync();
+    const path = `${dir}/test.heapsnapshot`;
+    assertEquals(Deno.writeHeapSnapshot(path), path);
+    const snapshot = JSON.parse(Deno.readTextFileSync(path));
+    assert(snapshot.snapshot.node_count > 0);
+    assert(Array.isArray(snapshot.nodes));
+    Deno.removeSync(dir, { recursive: true });
+  },
+);
+
+Deno.test({ permissions: { write: false } }, function writeHeapSnapshotPerm() {
+  assertThrows(() => {
+    Deno.writeHeapSnapshot("test.heapsnapshot");
+  }, Deno.errors.PermissionDenied);
+});
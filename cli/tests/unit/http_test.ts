@@ -1301,7 +1301,7 @@ function tmpUnixSocketPath(): string {
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   async function httpServerOnUnixSocket() {
     const filePath = tmpUnixSocketPath();
@@ -2263,7 +2263,7 @@ Deno.test(
 );
 
 Deno.test("upgradeHttp unix", {
-  permissions: { read: true, write: true },
+  permissions: { read: true, write: true, unix: true },
   ignore: Deno.build.os === "windows",
 }, async () => {
   const filePath = tmpUnixSocketPath();
@@ -2459,7 +2459,7 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   async function httpServerWithoutExclusiveAccessToUnixSocket() {
     const filePath = tmpUnixSocketPath();
@@ -1256,6 +1256,47 @@ dbTest("keys must be arrays", async (db) => {
   );
 });
 
+dbTest("watch", async (db) => {
+  await db.set(["a"], 1);
+  const reader = db.watch([["a"], ["b"]]).getReader();
+
+  const initial = await reader.read();
+  assert(!initial.done);
+  assertEquals(initial.value.map((entry) => entry.value), [1, null]);
+  assertEquals(initial.value[1].key, ["b"]);
+  assertEquals(initial.value[1].versionstamp, null);
+
+  const { versionstamp } = await db.set(["b"], 2);
+  const changed = await reader.read();
+  assert(!changed.done);
+  assertEquals(changed.value.map((entry) => entry.value), [1, 2]);
+  assertEquals(changed.value[1].versionstamp, versionstamp);
+
+  // Writes to other keys are not emitted.
+  await db.set(["c"], 3);
+  await db.delete(["a"]);
+  const deleted = await reader.read();
+  assert(!deleted.done);
+  assertEquals(deleted.value.map((entry) => entry.value), [null, 2]);
+
+  await reader.cancel();
+});
+
+dbTest("watch too many keys", async (db) => {
+  const keys = Array.from({ length: 11 }, (_, i) => [i]);
+  assertThrows(() => db.watch(keys), TypeError, "too many keys (max 10)");
+});
+
+Deno.test("watch ends when the database is closed", async () => {
+  const db = await Deno.openKv(":memory:");
+  const reader = db.watch([["a"]]).getReader();
+  const initial = await reader.read();
+  assert(!initial.done);
+  await db.close();
+  const next = await reader.read();
+  assert(next.done);
+});
+
 Deno.test("Deno.Kv constructor throws", () => {
   assertThrows(() => {
     new Deno.Kv();
@@ -1303,4 +1344,12 @@ async function _typeCheckingTests() {
   const j = await i.next();
   assert(!j.done);
   assertType<IsExact<typeof j.value, Deno.KvEntry<string>>>(true);
+
+  const k = kv.watch([["a"], ["b"]]);
+  assertType<
+    IsExact<
+      typeof k,
+      ReadableStream<[Deno.KvEntryMaybe<unknown>, Deno.KvEntryMaybe<unknown>]>
+    >
+  >(true);
 }
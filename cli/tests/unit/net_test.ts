@@ -30,7 +30,7 @@ Deno.test({ permissions: { net: true } }, function netTcpListenClose() {
 
 Deno.test(
   {
-    permissions: { net: true },
+    permissions: { udp: true },
   },
   function netUdpListenClose() {
     const socket = Deno.listenDatagram({
@@ -45,6 +45,39 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { permissions: { net: true, udp: false } },
+  function netUdpListenUdpPermission() {
+    assertThrows(() => {
+      Deno.listenDatagram({
+        hostname: "127.0.0.1",
+        port: 3500,
+        transport: "udp",
+      });
+    }, Deno.errors.PermissionDenied);
+  },
+);
+
+Deno.test(
+  { permissions: { udp: true } },
+  async function netUdpSetBroadcast() {
+    const socket = Deno.listenDatagram({
+      hostname: "0.0.0.0",
+      port: 3500,
+      transport: "udp",
+      broadcast: false,
+    });
+    const broadcastAddr = { ...socket.addr, hostname: "255.255.255.255" };
+    await assertRejects(
+      () => socket.send(new Uint8Array([1]), broadcastAddr),
+      Deno.errors.PermissionDenied,
+    );
+    await socket.setBroadcast(true);
+    await socket.setBroadcast(false);
+    socket.close();
+  },
+);
+
 function tmpUnixSocketPath(): string {
   const folder = Deno.makeTempDirSync();
   return join(folder, "socket");
@@ -53,7 +86,7 @@ function tmpUnixSocketPath(): string {
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   function netUnixListenClose() {
     const filePath = tmpUnixSocketPath();
@@ -70,7 +103,7 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   function netUnixPacketListenClose() {
     const filePath = tmpUnixSocketPath();
@@ -87,9 +120,9 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: false },
+    permissions: { read: true, write: true, unix: false },
   },
-  function netUnixListenWritePermission() {
+  function netUnixListenUnixPermission() {
     assertThrows(() => {
       const filePath = tmpUnixSocketPath();
       const socket = Deno.listen({
@@ -106,9 +139,9 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: false },
+    permissions: { read: true, write: true, unix: false },
   },
-  function netUnixPacketListenWritePermission() {
+  function netUnixPacketListenUnixPermission() {
     assertThrows(() => {
       const filePath = tmpUnixSocketPath();
       const socket = Deno.listenDatagram({
@@ -143,7 +176,7 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   async function netUnixCloseWhileAccept() {
     const filePath = tmpUnixSocketPath();
@@ -187,7 +220,7 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   async function netUnixConcurrentAccept() {
     const filePath = tmpUnixSocketPath();
@@ -321,7 +354,7 @@ Deno.test({ permissions: { net: true } }, async function netTcpSetKeepAlive() {
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   async function netUnixDialListen() {
     const filePath = tmpUnixSocketPath();
@@ -358,7 +391,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true } },
+  { permissions: { udp: true } },
   async function netUdpSendReceive() {
     const alice = Deno.listenDatagram({ port: 3500, transport: "udp" });
     assert(alice.addr.transport === "udp");
@@ -388,7 +421,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true }, ignore: true },
+  { permissions: { udp: true }, ignore: true },
   async function netUdpSendReceiveBroadcast() {
     // Must bind sender to an address that can send to the broadcast address on MacOS.
     // Macos will give us error 49 when sending the broadcast packet if we omit hostname here.
@@ -426,7 +459,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true }, ignore: true },
+  { permissions: { udp: true }, ignore: true },
   async function netUdpMulticastV4() {
     const listener = Deno.listenDatagram({
       hostname: "0.0.0.0",
@@ -449,7 +482,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true }, ignore: true },
+  { permissions: { udp: true }, ignore: true },
   async function netUdpMulticastV6() {
     const listener = Deno.listenDatagram({
       hostname: "::",
@@ -471,7 +504,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true }, ignore: true },
+  { permissions: { udp: true }, ignore: true },
   async function netUdpSendReceiveMulticastv4() {
     const alice = Deno.listenDatagram({
       hostname: "0.0.0.0",
@@ -521,7 +554,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true }, ignore: true },
+  { permissions: { udp: true }, ignore: true },
   async function netUdpMulticastLoopbackOption() {
     // Must bind sender to an address that can send to the broadcast address on MacOS.
     // Macos will give us error 49 when sending the broadcast packet if we omit hostname here.
@@ -561,7 +594,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true } },
+  { permissions: { udp: true } },
   async function netUdpConcurrentSendReceive() {
     const socket = Deno.listenDatagram({ port: 3500, transport: "udp" });
     assert(socket.addr.transport === "udp");
@@ -585,7 +618,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true } },
+  { permissions: { udp: true } },
   async function netUdpBorrowMutError() {
     const socket = Deno.listenDatagram({
       port: 4501,
@@ -602,7 +635,7 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   async function netUnixPacketSendReceive() {
     const aliceFilePath = tmpUnixSocketPath();
@@ -639,7 +672,7 @@ Deno.test(
 
 // TODO(lucacasonato): support concurrent reads and writes on unixpacket sockets
 Deno.test(
-  { ignore: true, permissions: { read: true, write: true } },
+  { ignore: true, permissions: { read: true, write: true, unix: true } },
   async function netUnixPacketConcurrentSendReceive() {
     const filePath = tmpUnixSocketPath();
     const socket = Deno.listenDatagram({
@@ -709,7 +742,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true } },
+  { permissions: { udp: true } },
   async function netUdpListenCloseWhileIterating() {
     const socket = Deno.listenDatagram({ port: 8000, transport: "udp" });
     const nextWhileClosing = socket[Symbol.asyncIterator]().next();
@@ -724,7 +757,7 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   async function netUnixListenCloseWhileIterating() {
     const filePath = tmpUnixSocketPath();
@@ -741,7 +774,7 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   async function netUnixPacketListenCloseWhileIterating() {
     const filePath = tmpUnixSocketPath();
@@ -884,7 +917,7 @@ Deno.test(
 Deno.test(
   {
     ignore: Deno.build.os !== "linux",
-    permissions: { read: true, write: true },
+    permissions: { read: true, write: true, unix: true },
   },
   function netUnixAbstractPathShouldNotPanic() {
     const listener = Deno.listen({
@@ -1028,7 +1061,7 @@ Deno.test(
 
 Deno.test({
   ignore: Deno.build.os === "windows",
-  permissions: { read: true, write: true },
+  permissions: { read: true, write: true, unix: true },
 }, function netUnixListenAddrAlreadyInUse() {
   const filePath = tmpUnixSocketPath();
   const listener = Deno.listen({ path: filePath, transport: "unix" });
@@ -1127,7 +1160,7 @@ Deno.test({ permissions: { net: true } }, async function netTcpReuseAddr() {
 });
 
 Deno.test(
-  { permissions: { net: true } },
+  { permissions: { udp: true } },
   async function netUdpReuseAddr() {
     const sender = Deno.listenDatagram({
       port: 4002,
@@ -1162,7 +1195,7 @@ Deno.test(
 );
 
 Deno.test(
-  { permissions: { net: true } },
+  { permissions: { udp: true } },
   function netUdpNoReuseAddr() {
     let listener1;
     try {
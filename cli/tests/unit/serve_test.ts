@@ -13,6 +13,7 @@ import {
   assertThrows,
   Deferred,
   deferred,
+  execCode,
   fail,
 } from "./test_util.ts";
 import { consoleSize } from "../../../runtime/js/40_tty.js";
@@ -59,6 +60,79 @@ Deno.test(async function httpServerShutsDownPortBeforeResolving() {
   listener!.close();
 });
 
+Deno.test(async function httpServerShutdownWaitsForInFlightRequests() {
+  const listeningPromise = deferred();
+  const requestReceived = deferred();
+  const release = deferred();
+
+  const server = Deno.serve({
+    handler: async (_req) => {
+      requestReceived.resolve();
+      await release;
+      return new Response("ok");
+    },
+    port: 4501,
+    onListen: onListen(listeningPromise),
+  });
+
+  await listeningPromise;
+  const response = fetch("http://localhost:4501/");
+  await requestReceived;
+
+  let shutdownDone = false;
+  const shutdown = server.shutdown().then(() => {
+    shutdownDone = true;
+  });
+  await new Promise((resolve) => setTimeout(resolve, 100));
+  assert(!shutdownDone);
+
+  release.resolve();
+  assertEquals(await (await response).text(), "ok");
+  await shutdown;
+  await server.finished;
+
+  const listener = Deno.listen({ port: 4501 });
+  listener.close();
+});
+
+Deno.test(async function httpServerShutdownTimeout() {
+  const listeningPromise = deferred();
+  const requestReceived = deferred();
+
+  const server = Deno.serve({
+    handler: async (_req) => {
+      requestReceived.resolve();
+      // Never responds.
+      await deferred();
+      return new Response("unreachable");
+    },
+    port: 4501,
+    onListen: onListen(listeningPromise),
+  });
+
+  await listeningPromise;
+  const response = fetch("http://localhost:4501/");
+  await requestReceived;
+
+  await server.shutdown({ timeout: 100 });
+  await assertRejects(() => response, TypeError);
+});
+
+Deno.test(
+  { permissions: { read: true, run: true, net: true } },
+  async function httpServerUnref() {
+    const [statusCode, _output] = await execCode(`
+      const server = Deno.serve({
+        port: 4501,
+        onListen() {},
+        handler: () => new Response("ok"),
+      });
+      server.unref();
+    `);
+    assertEquals(statusCode, 0);
+  },
+);
+
 Deno.test(async function httpServerCanResolveHostnames() {
   const ac = new AbortController();
   const listeningPromise = deferred();
@@ -2036,7 +2110,21 @@ const compressionTestCases = [
     length: 1024,
     in: { "Accept-Encoding": "gzip, deflate, br" },
     out: { "Content-Type": "text/plain" },
-    expect: "gzip",
+    expect: "br",
+  },
+  {
+    name: "CompressibleTypeZstd",
+    length: 1024,
+    in: { "Accept-Encoding": "gzip, deflate, br, zstd" },
+    out: { "Content-Type": "text/plain" },
+    expect: "zstd",
+  },
+  {
+    name: "CompressibleTypeZstdPreferred",
+    length: 1024,
+    in: { "Accept-Encoding": "gzip;q=0.5, zstd" },
+    out: { "Content-Type": "text/plain" },
+    expect: "zstd",
   },
   {
     name: "UncompressibleRange",
@@ -2102,12 +2190,25 @@ for (const testCase of compressionTestCases) {
               resp.headers.get("content-encoding"),
               testCase.out["Content-Encoding"] || null,
             );
-          } else if (testCase.expect == "gzip") {
+          } else if (testCase.expect == "gzip" || testCase.expect == "br") {
             // Note the fetch will transparently decompress this response, BUT we can detect that a response
             // was compressed by the lack of a content length.
             assertEquals(body.byteLength, testCase.length);
             assertEquals(resp.headers.get("content-encoding"), null);
             assertEquals(resp.headers.get("content-length"), null);
+          } else {
+            // fetch doesn't decompress zstd responses.
+            assertEquals(
+              resp.headers.get("content-encoding"),
+              testCase.expect,
+            );
+            assertEquals(resp.headers.get("content-length"), null);
+            const decompressed = await new Response(
+              new Blob([body]).stream().pipeThrough(
+                new DecompressionStream(testCase.expect),
+              ),
+            ).arrayBuffer();
+            assertEquals(decompressed.byteLength, testCase.length);
           }
         } finally {
           ac.abort();
@@ -2846,7 +2947,7 @@ Deno.test(
   async function httpServeCurlH2C() {
     const ac = new AbortController();
     const server = Deno.serve(
-      { signal: ac.signal },
+      { signal: ac.signal, h2c: true },
       () => new Response("hello world!"),
     );
 
@@ -2908,6 +3009,71 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { permissions: { net: true, run: true } },
+  async function httpServeCurlH2CNotEnabled() {
+    const ac = new AbortController();
+    const server = Deno.serve(
+      { signal: ac.signal },
+      () => new Response("hello world!"),
+    );
+
+    assertEquals(
+      "hello world!",
+      await curlRequest(["http://localhost:8000/path", "--http2"]),
+    );
+    assertEquals(
+      false,
+      await curlRequestSucceeds([
+        "http://localhost:8000/path",
+        "--http2",
+        "--http2-prior-knowledge",
+      ]),
+    );
+
+    ac.abort();
+    await server;
+  },
+);
+
+Deno.test(
+  { permissions: { net: true, run: true, read: true } },
+  async function httpsServeCurlHttp2Disabled() {
+    const ac = new AbortController();
+    const server = Deno.serve(
+      {
+        signal: ac.signal,
+        http2: false,
+        cert: Deno.readTextFileSync("cli/tests/testdata/tls/localhost.crt"),
+        key: Deno.readTextFileSync("cli/tests/testdata/tls/localhost.key"),
+      },
+      () => new Response("hello world!"),
+    );
+
+    assertEquals(
+      "HTTP/1.1 200 OK",
+      (await curlRequest([
+        "https://localhost:9000/path",
+        "-k",
+        "--http2",
+        "--head",
+      ])).split("\r\n")[0],
+    );
+
+    ac.abort();
+    await server;
+  },
+);
+
+async function curlRequestSucceeds(args: string[]) {
+  const { success } = await new Deno.Command("curl", {
+    args,
+    stdout: "null",
+    stderr: "null",
+  }).output();
+  return success;
+}
+
 async function curlRequest(args: string[]) {
   const { success, stdout } = await new Deno.Command("curl", {
     args,
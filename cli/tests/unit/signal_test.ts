@@ -5,7 +5,7 @@ Deno.test(
   { ignore: Deno.build.os !== "windows" },
   function signalsNotImplemented() {
     const msg =
-      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK).";
+      "Windows only supports ctrl-c (SIGINT), ctrl-break (SIGBREAK) and closing the console (SIGHUP).";
     assertThrows(
       () => {
         Deno.addSignalListener("SIGALRM", () => {});
@@ -20,13 +20,6 @@ Deno.test(
       Error,
       msg,
     );
-    assertThrows(
-      () => {
-        Deno.addSignalListener("SIGHUP", () => {});
-      },
-      Error,
-      msg,
-    );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGIO", () => {});
@@ -104,6 +97,17 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { ignore: Deno.build.os !== "windows" },
+  function signalListenerWindowsConsoleEvents() {
+    const listener = () => {};
+    for (const signal of ["SIGINT", "SIGBREAK", "SIGHUP"] as const) {
+      Deno.addSignalListener(signal, listener);
+      Deno.removeSignalListener(signal, listener);
+    }
+  },
+);
+
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
@@ -1249,6 +1249,74 @@ Deno.test(
   },
 );
 
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function startTLSWithClientAuth() {
+    const plainConn = await Deno.connect({
+      hostname: "localhost",
+      port: 4552,
+    });
+    const conn = await Deno.startTls(plainConn, {
+      hostname: "localhost",
+      certChain: await Deno.readTextFile(
+        "cli/tests/testdata/tls/localhost.crt",
+      ),
+      privateKey: await Deno.readTextFile(
+        "cli/tests/testdata/tls/localhost.key",
+      ),
+      caCerts: [Deno.readTextFileSync("cli/tests/testdata/tls/RootCA.pem")],
+    });
+    const result = decoder.decode(await readAll(conn));
+    assertEquals(result, "PASS");
+    conn.close();
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function connectTLSVerifyPeerCertificate() {
+    let peerCertificates: Uint8Array[] = [];
+    let peerHostname = "";
+    const conn = await Deno.connectTls({
+      hostname: "localhost",
+      port: 4557,
+      caCerts: [Deno.readTextFileSync("cli/tests/testdata/tls/RootCA.pem")],
+      verifyPeerCertificate(certificates, hostname) {
+        peerCertificates = certificates;
+        peerHostname = hostname;
+        return true;
+      },
+    });
+    assertEquals(peerHostname, "localhost");
+    assert(peerCertificates.length > 0);
+    assert(peerCertificates[0] instanceof Uint8Array);
+    // DER encoded certificates start with a SEQUENCE tag.
+    assertEquals(peerCertificates[0][0], 0x30);
+    const result = decoder.decode(await readAll(conn));
+    assertEquals(result, "PASS");
+    conn.close();
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function connectTLSVerifyPeerCertificateRejected() {
+    await assertRejects(
+      () =>
+        Deno.connectTls({
+          hostname: "localhost",
+          port: 4557,
+          caCerts: [
+            Deno.readTextFileSync("cli/tests/testdata/tls/RootCA.pem"),
+          ],
+          verifyPeerCertificate: () => Promise.resolve(false),
+        }),
+      Deno.errors.InvalidData,
+      "rejected by verifyPeerCertificate",
+    );
+  },
+);
+
 Deno.test(
   { permissions: { read: true, net: true } },
   async function tlsHandshakeSuccess() {
@@ -169,3 +169,57 @@ Deno.test(
     }
   },
 );
+
+Deno.test(
+  { sanitizeOps: false, sanitizeResources: false },
+  async function websocketSendOrderAndBufferedAmount() {
+    const count = 1000;
+    const ac = new AbortController();
+    const listeningPromise = deferred();
+
+    const server = Deno.serve({
+      handler: (req) => {
+        const { socket, response } = Deno.upgradeWebSocket(req);
+        socket.onopen = () => {
+          const payload = new Uint8Array(1024);
+          for (let i = 0; i < count; i++) {
+            socket.send(`${i}`);
+            socket.send(payload);
+          }
+          // The messages are written in the background.
+          assert(socket.bufferedAmount >= count * 1024);
+        };
+        socket.onclose = () => ac.abort();
+        socket.onerror = () => fail();
+        return response;
+      },
+      signal: ac.signal,
+      onListen: () => listeningPromise.resolve(),
+      hostname: "localhost",
+      port: 4248,
+    });
+
+    await listeningPromise;
+    const promise = deferred();
+    const ws = new WebSocket("ws://localhost:4248/");
+    ws.binaryType = "arraybuffer";
+    ws.onerror = () => fail();
+    let received = 0;
+    ws.onmessage = (e) => {
+      if (received % 2 === 0) {
+        assertEquals(e.data, `${received / 2}`);
+      } else {
+        assertEquals((e.data as ArrayBuffer).byteLength, 1024);
+      }
+      received++;
+      if (received === count * 2) {
+        ws.close();
+      }
+    };
+    ws.onclose = () => promise.resolve();
+
+    await Promise.all([promise, server]);
+    assertEquals(received, count * 2);
+    assertEquals(ws.bufferedAmount, 0);
+  },
+);
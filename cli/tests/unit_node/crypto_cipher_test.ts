@@ -93,6 +93,26 @@ Deno.test({
         "66e94bd4ef8a2c3b884cfa59ca342b2e66e94bd4ef8a2c3b884cfa59ca342b2e66e94bd4ef8a2c3b884cfa59ca342b2e",
         "baf823258ca2e6994f638daa3515e986",
       ],
+      [
+        ["aes-192-cbc", 24, 16],
+        "aae06992acbf52a3e8f4a96ec9300bd752f674b7b9030fdab13d18dc214eb331184e27fc9889994fb21fbee831428955",
+        "3031c33656cc9efbdf339dfd55b08fe7",
+      ],
+      [
+        ["aes-192-ecb", 24, 0],
+        "aae06992acbf52a3e8f4a96ec9300bd7aae06992acbf52a3e8f4a96ec9300bd7aae06992acbf52a3e8f4a96ec9300bd7",
+        "2e0f33b51bb184654311ead507ea55fc",
+      ],
+      [
+        ["aes-256-cbc", 32, 16],
+        "dc95c078a2408989ad48a2149284208708c374848c228233c2b34f332bd2e9d38b70c515a6663d38cdb8e6532b266491",
+        "2e62607a5e8b715e4cb229a12169f2b2",
+      ],
+      [
+        ["aes-256-ecb", 32, 0],
+        "dc95c078a2408989ad48a21492842087dc95c078a2408989ad48a21492842087dc95c078a2408989ad48a21492842087",
+        "0ac1d7e8655254c6814b46753932df88",
+      ],
     ] as const;
     for (
       const [[alg, keyLen, ivLen], expectedUpdate, expectedFinal] of table
@@ -104,6 +124,42 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "createCipheriv - setAutoPadding",
+  fn() {
+    const cipher = crypto.createCipheriv("aes-128-cbc", zeros(16), zeros(16))
+      .setAutoPadding(false);
+    assertEquals(
+      cipher.update(zeros(32), undefined, "hex"),
+      "66e94bd4ef8a2c3b884cfa59ca342b2ef795bd4a52e29ed713d313fa20e98dbc",
+    );
+    assertEquals(cipher.final("hex"), "");
+
+    const unaligned = crypto.createCipheriv(
+      "aes-128-cbc",
+      zeros(16),
+      zeros(16),
+    ).setAutoPadding(false);
+    unaligned.update(zeros(10));
+    assertThrows(
+      () => unaligned.final(),
+      TypeError,
+      "wrong final block length",
+    );
+  },
+});
+
+Deno.test({
+  name: "createCipheriv - invalid key length",
+  fn() {
+    assertThrows(
+      () => crypto.createCipheriv("aes-256-cbc", zeros(16), zeros(16)),
+      RangeError,
+      "Invalid key length",
+    );
+  },
+});
+
 Deno.test({
   name: "createCipheriv - input encoding",
   fn() {
@@ -153,6 +209,18 @@ Deno.test({
         ["aes-128-ecb", 16, 0],
         "66e94bd4ef8a2c3b884cfa59ca342b2e66e94bd4ef8a2c3b884cfa59ca342b2e66e94bd4ef8a2c3b884cfa59ca342b2e66e94bd4ef8a2c3b884cfa59ca342b2e66e94bd4ef8a2c3b884cfa59ca342b2ec29a917cbaf72fa9bc32129bb0d17663",
       ],
+      [
+        ["aes-192-cbc", 24, 16],
+        "aae06992acbf52a3e8f4a96ec9300bd752f674b7b9030fdab13d18dc214eb331184e27fc9889994fb21fbee83142895570df37eed13512d85cba8f621ce4e2eedc3f59beb1a980653c97b0e26b2c086bcd3a3476cbf5af2b7f1f1235652b2d03",
+      ],
+      [
+        ["aes-256-cbc", 32, 16],
+        "dc95c078a2408989ad48a2149284208708c374848c228233c2b34f332bd2e9d38b70c515a6663d38cdb8e6532b2664915d0dcc192580aee9ef8a8568193f1b44bfca557c6bab7dc79da07ffd42191b2659e6bee99cb2a6a7299f0e9a21686fc7",
+      ],
+      [
+        ["aes-256-ecb", 32, 0],
+        "dc95c078a2408989ad48a21492842087dc95c078a2408989ad48a21492842087dc95c078a2408989ad48a21492842087dc95c078a2408989ad48a21492842087dc95c078a2408989ad48a214928420877c45b49560579dd1ffc7ec626de2a968",
+      ],
     ] as const;
     for (
       const [[alg, keyLen, ivLen], input] of table
@@ -164,6 +232,27 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "createDecipheriv - setAutoPadding",
+  fn() {
+    const decipher = crypto.createDecipheriv(
+      "aes-128-cbc",
+      zeros(16),
+      zeros(16),
+    ).setAutoPadding(false);
+    assertEquals(
+      Buffer.concat([
+        decipher.update(
+          "66e94bd4ef8a2c3b884cfa59ca342b2ef795bd4a52e29ed713d313fa20e98dbc",
+          "hex",
+        ),
+        decipher.final(),
+      ]),
+      Buffer.alloc(32),
+    );
+  },
+});
+
 Deno.test({
   name: "createDecipheriv - transform stream",
   async fn() {
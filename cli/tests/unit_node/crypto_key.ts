@@ -2,6 +2,8 @@
 
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 import {
+  createPrivateKey,
+  createPublicKey,
   createSecretKey,
   generateKeyPair,
   generateKeyPairSync,
@@ -11,11 +13,25 @@ import {
 import { promisify } from "node:util";
 import { Buffer } from "node:buffer";
 import {
+  assert,
   assertEquals,
   assertThrows,
 } from "../../../test_util/std/testing/asserts.ts";
 import { createHmac } from "node:crypto";
 
+const rsaPrivatePem = Deno.readTextFileSync(
+  new URL("./testdata/rsa_private.pem", import.meta.url),
+);
+const rsaPublicPem = Deno.readTextFileSync(
+  new URL("./testdata/rsa_public.pem", import.meta.url),
+);
+const ecPrivatePem = Deno.readTextFileSync(
+  new URL("./testdata/ec_private.pem", import.meta.url),
+);
+const ecPublicPem = Deno.readTextFileSync(
+  new URL("./testdata/ec_public.pem", import.meta.url),
+);
+
 const generateKeyPairAsync = promisify(
   (
     type: any,
@@ -76,6 +92,62 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "create rsa private and public keys",
+  fn() {
+    const privateKey = createPrivateKey(rsaPrivatePem);
+    assertEquals(privateKey.type, "private");
+    assertEquals(privateKey.asymmetricKeyType, "rsa");
+    assertEquals(privateKey.asymmetricKeyDetails, {
+      modulusLength: 2048,
+      publicExponent: 65537n,
+    });
+    assertEquals(
+      privateKey.export({ format: "pem", type: "pkcs8" }),
+      rsaPrivatePem,
+    );
+
+    const publicKey = createPublicKey(rsaPublicPem);
+    assertEquals(publicKey.type, "public");
+    assertEquals(publicKey.asymmetricKeyType, "rsa");
+    assertEquals(
+      publicKey.export({ format: "pem", type: "spki" }),
+      rsaPublicPem,
+    );
+    assert(publicKey.equals(createPublicKey(privateKey)));
+    assert(publicKey.equals(createPublicKey(rsaPrivatePem)));
+    assert(!publicKey.equals(privateKey));
+  },
+});
+
+Deno.test({
+  name: "create ec private and public keys",
+  fn() {
+    const privateKey = createPrivateKey({ key: ecPrivatePem, format: "pem" });
+    assertEquals(privateKey.asymmetricKeyType, "ec");
+    assertEquals(privateKey.asymmetricKeyDetails, {
+      namedCurve: "prime256v1",
+    });
+
+    const der = privateKey.export({ format: "der", type: "pkcs8" });
+    assert(
+      createPrivateKey({ key: der, format: "der", type: "pkcs8" })
+        .equals(privateKey),
+    );
+    assertEquals(
+      createPublicKey(privateKey).export({ format: "pem", type: "spki" }),
+      ecPublicPem,
+    );
+  },
+});
+
+Deno.test({
+  name: "create public key from public key object fails",
+  fn() {
+    assertThrows(() => createPublicKey(createPublicKey(rsaPublicPem)));
+  },
+});
+
 for (const type of ["rsa", "rsa-pss", "dsa"]) {
   for (const modulusLength of [2048, 3072]) {
     Deno.test({
@@ -4,7 +4,14 @@ import {
   assert,
   assertEquals,
 } from "../../../test_util/std/testing/asserts.ts";
-import { createSign, createVerify, sign, verify } from "node:crypto";
+import {
+  createPrivateKey,
+  createPublicKey,
+  createSign,
+  createVerify,
+  sign,
+  verify,
+} from "node:crypto";
 import { Buffer } from "node:buffer";
 
 const rsaPrivatePem = Buffer.from(
@@ -18,6 +25,22 @@ const rsaPublicPem = Buffer.from(
   ),
 );
 
+const ecPrivatePem = Buffer.from(
+  await Deno.readFile(
+    new URL("./testdata/ec_private.pem", import.meta.url),
+  ),
+);
+const ed25519PrivatePem = Buffer.from(
+  await Deno.readFile(
+    new URL("./testdata/ed25519_private.pem", import.meta.url),
+  ),
+);
+const ed25519PublicPem = Buffer.from(
+  await Deno.readFile(
+    new URL("./testdata/ed25519_public.pem", import.meta.url),
+  ),
+);
+
 const table = [
   {
     algorithms: ["sha224", "RSA-SHA224"],
@@ -89,3 +112,63 @@ Deno.test({
     }
   },
 });
+
+Deno.test({
+  name: "crypto.Sign|Verify - RSA KeyObject",
+  fn() {
+    const privateKey = createPrivateKey(rsaPrivatePem);
+    const publicKey = createPublicKey(rsaPublicPem);
+    for (const testCase of table) {
+      const [algorithm] = testCase.algorithms;
+      assertEquals(
+        createSign(algorithm).update(data).sign(privateKey, "hex"),
+        testCase.signature,
+      );
+      assert(
+        createVerify(algorithm).update(data).verify(
+          { key: publicKey },
+          testCase.signature,
+          "hex",
+        ),
+      );
+    }
+  },
+});
+
+Deno.test({
+  name: "crypto.sign|verify - ECDSA with der and ieee-p1363 encodings",
+  fn() {
+    const privateKey = createPrivateKey(ecPrivatePem);
+    const publicKey = createPublicKey(privateKey);
+
+    const signature = sign("sha256", data, privateKey);
+    assert(verify("sha256", data, publicKey, signature));
+    assert(!verify("sha256", Buffer.from("other data"), publicKey, signature));
+
+    const p1363 = sign("sha256", data, {
+      key: ecPrivatePem,
+      dsaEncoding: "ieee-p1363",
+    });
+    assertEquals(p1363.length, 64);
+    assert(
+      verify(
+        "sha256",
+        data,
+        { key: publicKey, dsaEncoding: "ieee-p1363" },
+        p1363,
+      ),
+    );
+  },
+});
+
+Deno.test({
+  name: "crypto.sign|verify - Ed25519",
+  fn() {
+    const signature = sign(null, data, ed25519PrivatePem);
+    assertEquals(
+      signature.toString("hex"),
+      "149a6dc0b66916a7b9b5f6aff3a2079d167c249230fd5622bbf5cd3d4f836f554f0c0ee327d5b3ea67b93d88b7b1e42a33bc17e957db6c39d693ab48ff5bab03",
+    );
+    assert(verify(null, data, ed25519PublicPem, signature));
+  },
+});
@@ -0,0 +1,53 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+import {
+  assert,
+  assertEquals,
+} from "../../../test_util/std/testing/asserts.ts";
+import { X509Certificate } from "node:crypto";
+
+const localhostCrt = Deno.readTextFileSync(
+  new URL("../testdata/tls/localhost.crt", import.meta.url),
+);
+const rootCaPem = Deno.readTextFileSync(
+  new URL("../testdata/tls/RootCA.pem", import.meta.url),
+);
+
+Deno.test({
+  name: "X509Certificate - raw and toString",
+  fn() {
+    const cert = new X509Certificate(localhostCrt);
+    assertEquals(cert.toString(), localhostCrt);
+    assertEquals(new X509Certificate(cert.raw).toString(), localhostCrt);
+    assertEquals(
+      new X509Certificate(cert.raw).fingerprint256,
+      cert.fingerprint256,
+    );
+  },
+});
+
+Deno.test({
+  name: "X509Certificate - checkHost, checkIP and checkIssued",
+  fn() {
+    const cert = new X509Certificate(localhostCrt);
+    const ca = new X509Certificate(rootCaPem);
+    assertEquals(cert.checkHost("localhost"), "localhost");
+    assertEquals(cert.checkHost("example.com"), undefined);
+    assertEquals(cert.checkIP("127.0.0.1"), undefined);
+    assert(cert.checkIssued(ca));
+    assert(!ca.checkIssued(cert));
+  },
+});
+
+Deno.test({
+  name: "X509Certificate - publicKey",
+  fn() {
+    const { publicKey } = new X509Certificate(localhostCrt);
+    assertEquals(publicKey.type, "public");
+    assertEquals(publicKey.asymmetricKeyType, "rsa");
+    assertEquals(publicKey.asymmetricKeyDetails, {
+      modulusLength: 2048,
+      publicExponent: 65537n,
+    });
+  },
+});
@@ -0,0 +1,3 @@
+const { isMainThread, parentPort, workerData } = require("worker_threads");
+
+parentPort.postMessage({ isMainThread, workerData, env: process.env.FOO });
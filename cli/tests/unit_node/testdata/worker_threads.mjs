@@ -0,0 +1,10 @@
+import {
+  isMainThread,
+  parentPort,
+  threadId,
+  workerData,
+} from "node:worker_threads";
+
+parentPort.once("message", (message) => {
+  parentPort.postMessage({ isMainThread, message, threadId, workerData });
+});
@@ -0,0 +1,106 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+import {
+  assert,
+  assertEquals,
+  assertThrows,
+} from "../../../test_util/std/testing/asserts.ts";
+import vm from "node:vm";
+
+Deno.test("[node/vm] runInContext shares the contextified object", () => {
+  const context = vm.createContext({ x: 2 });
+  assert(vm.isContext(context));
+  assert(!vm.isContext({}));
+
+  assertEquals(vm.runInContext("x * 21", context), 42);
+  vm.runInContext(
+    "var y = x + 1; z = 'z'; function f() { return y; }",
+    context,
+  );
+  assertEquals(context.y, 3);
+  assertEquals(context.z, "z");
+  assertEquals(context.f(), 3);
+
+  // Changes to the contextified object are visible to the next run.
+  context.x = 10;
+  assertEquals(vm.runInContext("x + y", context), 13);
+});
+
+Deno.test("[node/vm] contexts have their own builtins", () => {
+  const context = vm.createContext();
+  const array = vm.runInContext("[]", context);
+  assert(Array.isArray(array));
+  assert(!(array instanceof Array));
+  assertEquals(vm.runInContext("typeof Deno", context), "undefined");
+  assertEquals(vm.runInContext("this", context), context);
+});
+
+Deno.test("[node/vm] runInNewContext", () => {
+  assertEquals(vm.runInNewContext("a + b", { a: 1, b: 2 }), 3);
+  const script = new vm.Script("count += 1");
+  const sandbox = { count: 0 };
+  script.runInNewContext(sandbox);
+  assertEquals(sandbox.count, 1);
+});
+
+Deno.test("[node/vm] errors are thrown to the caller", () => {
+  const context = vm.createContext();
+  const error = assertThrows(() =>
+    vm.runInContext("throw new TypeError('boom')", context)
+  ) as Error;
+  assertEquals(error.name, "TypeError");
+  assertEquals(error.message, "boom");
+  const syntaxError = assertThrows(() =>
+    vm.runInContext("(", context)
+  ) as Error;
+  assertEquals(syntaxError.name, "SyntaxError");
+  assertThrows(() => vm.runInContext("1", {}), TypeError);
+});
+
+Deno.test("[node/vm] compileFunction", () => {
+  const add = vm.compileFunction("return a + b", ["a", "b"]);
+  assertEquals(add(1, 2), 3);
+
+  const context = vm.createContext({ value: 5 });
+  const get = vm.compileFunction("return value", [], {
+    parsingContext: context,
+  });
+  assertEquals(get(), 5);
+});
+
+Deno.test("[node/vm] SourceTextModule", async () => {
+  const context = vm.createContext({ multiplier: 3 });
+  const dep = new vm.SourceTextModule("export const value = 14;", {
+    context,
+  });
+  const root = new vm.SourceTextModule(
+    `import { value } from "dep";
+    export const result = value * multiplier;`,
+    { context, identifier: "root.js" },
+  );
+  assertEquals(root.identifier, "root.js");
+  assertEquals(root.dependencySpecifiers, ["dep"]);
+  assertEquals(root.status, "unlinked");
+
+  await root.link((specifier: string) => {
+    assertEquals(specifier, "dep");
+    return dep;
+  });
+  assertEquals(root.status, "linked");
+  assertEquals(dep.status, "linked");
+
+  await root.evaluate();
+  assertEquals(root.status, "evaluated");
+  assertEquals(root.namespace.result, 42);
+});
+
+Deno.test("[node/vm] SourceTextModule evaluation errors", async () => {
+  const module = new vm.SourceTextModule("throw new Error('boom');");
+  await module.link(() => {
+    throw new Error("unreachable");
+  });
+  const error = await module.evaluate().catch((e: Error) => e);
+  assertEquals((error as Error).message, "boom");
+  assertEquals(module.status, "errored");
+  assertEquals(module.error.message, "boom");
+});
@@ -1,6 +1,12 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
-import { assertEquals } from "../../../test_util/std/testing/asserts.ts";
+import {
+  assert,
+  assertEquals,
+  assertObjectMatch,
+} from "../../../test_util/std/testing/asserts.ts";
+import { deferred } from "../../../test_util/std/async/deferred.ts";
+import { fromFileUrl, relative } from "../../../test_util/std/path/mod.ts";
 import workerThreads from "node:worker_threads";
 
 Deno.test("[node/worker_threads] BroadcastChannel is exported", () => {
@@ -11,3 +17,74 @@ Deno.test("[node/worker_threads] MessageChannel are MessagePort are exported", (
   assertEquals<unknown>(workerThreads.MessageChannel, MessageChannel);
   assertEquals<unknown>(workerThreads.MessagePort, MessagePort);
 });
+
+Deno.test("[node/worker_threads] isMainThread in the main thread", () => {
+  assert(workerThreads.isMainThread);
+  assertEquals(workerThreads.parentPort, null);
+  assertEquals(workerThreads.threadId, 0);
+});
+
+Deno.test("[node/worker_threads] getEnvironmentData / setEnvironmentData", () => {
+  workerThreads.setEnvironmentData("test", "value");
+  assertEquals(workerThreads.getEnvironmentData("test"), "value");
+  workerThreads.setEnvironmentData("test", undefined);
+  assertEquals(workerThreads.getEnvironmentData("test"), undefined);
+});
+
+Deno.test("[node/worker_threads] ESM worker receives workerData", async () => {
+  const worker = new workerThreads.Worker(
+    new URL("./testdata/worker_threads.mjs", import.meta.url),
+    { workerData: { hello: "world" } },
+  );
+  const online = deferred();
+  const result = deferred();
+  worker.on("online", () => online.resolve());
+  worker.on("message", (data) => result.resolve(data));
+  worker.postMessage("ping");
+  await online;
+  assertEquals(await result, {
+    isMainThread: false,
+    message: "ping",
+    threadId: worker.threadId,
+    workerData: { hello: "world" },
+  });
+  const exit = deferred<number>();
+  worker.on("exit", (code) => exit.resolve(code));
+  assertEquals(await worker.terminate(), 1);
+  assertEquals(await exit, 1);
+});
+
+Deno.test("[node/worker_threads] CommonJS worker with a relative path and env", async () => {
+  const worker = new workerThreads.Worker(
+    relative(
+      Deno.cwd(),
+      fromFileUrl(new URL("./testdata/worker_threads.cjs", import.meta.url)),
+    ),
+    { workerData: [1, 2, 3], env: { FOO: "bar" } },
+  );
+  const result = deferred();
+  worker.on("message", (data) => result.resolve(data));
+  assertObjectMatch(await result, {
+    isMainThread: false,
+    workerData: [1, 2, 3],
+    env: "bar",
+  });
+  worker.terminate();
+});
+
+Deno.test("[node/worker_threads] eval worker exits with process.exit() code", async () => {
+  const worker = new workerThreads.Worker(
+    `
+    const { parentPort, workerData } = require("worker_threads");
+    parentPort.postMessage(workerData * 2);
+    process.exit(3);
+    `,
+    { eval: true, workerData: 21 },
+  );
+  const message = deferred();
+  const exit = deferred<number>();
+  worker.on("message", (data) => message.resolve(data));
+  worker.on("exit", (code) => exit.resolve(code));
+  assertEquals(await message, 42);
+  assertEquals(await exit, 3);
+});
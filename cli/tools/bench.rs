@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::workspace_member_matches_specifier;
 use crate::args::BenchOptions;
 use crate::args::CliOptions;
 use crate::args::TypeCheckMode;
@@ -630,6 +631,22 @@ fn is_supported_bench_path(path: &Path) -> bool {
   }
 }
 
+/// Collects the bench modules, leaving out the ones excluded by the
+/// configuration of the workspace member they belong to.
+fn collect_bench_specifiers(
+  bench_options: &BenchOptions,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  let mut specifiers =
+    collect_specifiers(&bench_options.files, is_supported_bench_path)?;
+  specifiers.retain(|specifier| {
+    workspace_member_matches_specifier(
+      &bench_options.workspace_members,
+      specifier,
+    )
+  });
+  Ok(specifiers)
+}
+
 pub async fn run_benchmarks(
   cli_options: CliOptions,
   bench_options: BenchOptions,
@@ -640,10 +657,9 @@ pub async fn run_benchmarks(
   // `PermissionsContainer` - otherwise granting/revoking permissions in one
   // file would have impact on other files, which is undesirable.
   let permissions =
-    Permissions::from_options(&cli_options.permissions_options())?;
+    Permissions::from_options(&cli_options.permissions_options()?)?;
 
-  let specifiers =
-    collect_specifiers(&bench_options.files, is_supported_bench_path)?;
+  let specifiers = collect_bench_specifiers(&bench_options)?;
 
   if specifiers.is_empty() {
     return Err(generic_error("No bench modules found"));
@@ -692,7 +708,7 @@ pub async fn run_benchmarks_with_watch(
   // `PermissionsContainer` - otherwise granting/revoking permissions in one
   // file would have impact on other files, which is undesirable.
   let permissions =
-    Permissions::from_options(&cli_options.permissions_options())?;
+    Permissions::from_options(&cli_options.permissions_options()?)?;
   let no_check = cli_options.type_check_mode() == TypeCheckMode::None;
 
   let resolver = |changed: Option<Vec<PathBuf>>| {
@@ -704,8 +720,7 @@ pub async fn run_benchmarks_with_watch(
     let cli_options = cli_options.clone();
 
     async move {
-      let bench_modules =
-        collect_specifiers(&bench_options.files, is_supported_bench_path)?;
+      let bench_modules = collect_bench_specifiers(bench_options)?;
 
       let mut paths_to_watch = paths_to_watch_clone;
       let mut modules_to_reload = if files_changed {
@@ -782,19 +797,19 @@ pub async fn run_benchmarks_with_watch(
         }
       }
 
-      Ok((paths_to_watch, modules_to_reload))
+      Ok((paths_to_watch, modules_to_reload, changed))
     }
     .map(move |result| {
       if files_changed
-        && matches!(result, Ok((_, ref modules)) if modules.is_empty())
+        && matches!(result, Ok((_, ref modules, _)) if modules.is_empty())
       {
         ResolutionResult::Ignore
       } else {
         match result {
-          Ok((paths_to_watch, modules_to_reload)) => {
+          Ok((paths_to_watch, modules_to_reload, changed)) => {
             ResolutionResult::Restart {
               paths_to_watch,
-              result: Ok(modules_to_reload),
+              result: Ok((modules_to_reload, changed)),
             }
           }
           Err(e) => ResolutionResult::Restart {
@@ -808,21 +823,23 @@ pub async fn run_benchmarks_with_watch(
 
   let create_cli_main_worker_factory =
     factory.create_cli_main_worker_factory_func().await?;
-  let operation = |modules_to_reload: Vec<ModuleSpecifier>| {
+  let operation = |(modules_to_reload, changed): (
+    Vec<ModuleSpecifier>,
+    Option<Vec<PathBuf>>,
+  )| {
     let permissions = &permissions;
     let bench_options = &bench_options;
-    file_watcher.reset();
+    file_watcher.invalidate(changed.as_deref());
     let module_load_preparer = module_load_preparer.clone();
     let cli_options = cli_options.clone();
     let create_cli_main_worker_factory = create_cli_main_worker_factory.clone();
 
     async move {
       let worker_factory = Arc::new(create_cli_main_worker_factory());
-      let specifiers =
-        collect_specifiers(&bench_options.files, is_supported_bench_path)?
-          .into_iter()
-          .filter(|specifier| modules_to_reload.contains(specifier))
-          .collect::<Vec<ModuleSpecifier>>();
+      let specifiers = collect_bench_specifiers(bench_options)?
+        .into_iter()
+        .filter(|specifier| modules_to_reload.contains(specifier))
+        .collect::<Vec<ModuleSpecifier>>();
 
       check_specifiers(&cli_options, &module_load_preparer, specifiers.clone())
         .await?;
@@ -848,14 +865,10 @@ pub async fn run_benchmarks_with_watch(
     }
   };
 
-  let clear_screen = !cli_options.no_clear_screen();
   file_watcher::watch_func(
     resolver,
     operation,
-    file_watcher::PrintConfig {
-      job_name: "Bench".to_string(),
-      clear_screen,
-    },
+    file_watcher::PrintConfig::new("Bench", cli_options)?,
   )
   .await?;
 
@@ -0,0 +1,303 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use deno_core::futures::FutureExt;
+use deno_core::ModuleSpecifier;
+use deno_graph::source::CacheInfo;
+use deno_graph::source::LoadFuture;
+use deno_graph::source::LoadResponse;
+use deno_graph::source::Loader;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_graph::Position;
+use deno_graph::Resolution;
+
+use crate::util::checksum;
+
+/// A module that is bundled into its own file, which is either the main
+/// module or a module that is imported dynamically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+  pub specifier: ModuleSpecifier,
+  pub file_name: String,
+}
+
+/// Splits the graph into the chunk of its root and a chunk for every module
+/// that is imported dynamically. Modules that are statically imported by
+/// several chunks are bundled into each of them.
+pub fn find_chunks(graph: &ModuleGraph, main_file_name: String) -> Vec<Chunk> {
+  let mut chunks = vec![Chunk {
+    specifier: graph.roots[0].clone(),
+    file_name: main_file_name,
+  }];
+  for module in graph.modules() {
+    if let Module::Esm(module) = module {
+      for dependency in module.dependencies.values() {
+        if !dependency.is_dynamic {
+          continue;
+        }
+        if let Resolution::Ok(resolved) = &dependency.maybe_code {
+          let specifier = graph.resolve(&resolved.specifier);
+          if !chunks.iter().any(|chunk| chunk.specifier == specifier) {
+            chunks.push(Chunk {
+              file_name: chunk_file_name(&specifier),
+              specifier,
+            });
+          }
+        }
+      }
+    }
+  }
+  chunks
+}
+
+/// The file name of the chunk of a module, which is stable across builds so
+/// that the chunks can refer to each other before they are bundled.
+fn chunk_file_name(specifier: &ModuleSpecifier) -> String {
+  let hash = checksum::gen(&[specifier.as_str().as_bytes()]);
+  format!("chunk-{}.js", &hash[..8])
+}
+
+/// Returns the sources of the modules that import chunks dynamically, where
+/// the specifiers of these imports are replaced with the file names of the
+/// chunks.
+pub fn rewrite_dynamic_imports(
+  graph: &ModuleGraph,
+  chunks: &[Chunk],
+) -> HashMap<ModuleSpecifier, Arc<str>> {
+  let mut sources = HashMap::new();
+  for module in graph.modules() {
+    let module = match module {
+      Module::Esm(module) => module,
+      _ => continue,
+    };
+    let mut replacements = Vec::new();
+    for dependency in module.dependencies.values() {
+      if !dependency.is_dynamic {
+        continue;
+      }
+      let resolved = match &dependency.maybe_code {
+        Resolution::Ok(resolved) => resolved,
+        _ => continue,
+      };
+      let specifier = graph.resolve(&resolved.specifier);
+      let chunk = match chunks.iter().find(|chunk| chunk.specifier == specifier)
+      {
+        Some(chunk) => chunk,
+        None => continue,
+      };
+      for import in &dependency.imports {
+        let start = byte_index(&module.source, &import.range.start);
+        let end = byte_index(&module.source, &import.range.end);
+        if let (Some(start), Some(end)) = (start, end) {
+          replacements.push((start, end, &chunk.file_name));
+        }
+      }
+    }
+    if replacements.is_empty() {
+      continue;
+    }
+    replacements.sort_by_key(|(start, _, _)| *start);
+    let source = &module.source;
+    let mut text = String::with_capacity(source.len());
+    let mut last_end = 0;
+    for (start, end, file_name) in replacements {
+      let (start, end) = quoted_range(source, start, end);
+      if start < last_end {
+        continue;
+      }
+      text.push_str(&source[last_end..start]);
+      // A template literal isn't resolved by the bundler, so the import is
+      // kept as is and loads the chunk at runtime.
+      text.push_str(&format!("`./{file_name}`"));
+      last_end = end;
+    }
+    text.push_str(&source[last_end..]);
+    sources.insert(module.specifier.clone(), text.into());
+  }
+  sources
+}
+
+fn byte_index(text: &str, position: &Position) -> Option<usize> {
+  let mut line_start = 0;
+  for _ in 0..position.line {
+    line_start += text[line_start..].find('\n')? + 1;
+  }
+  let line = &text[line_start..];
+  let index = line
+    .char_indices()
+    .map(|(index, _)| index)
+    .chain(std::iter::once(line.len()))
+    .nth(position.character)?;
+  Some(line_start + index)
+}
+
+/// Extends the range of a specifier to the quotes of its string literal.
+fn quoted_range(text: &str, start: usize, end: usize) -> (usize, usize) {
+  let is_quote = |c: Option<char>| matches!(c, Some('"' | '\'' | '`'));
+  if is_quote(text[start..].chars().next()) {
+    (start, end)
+  } else if is_quote(text[..start].chars().next_back())
+    && is_quote(text[end..].chars().next())
+  {
+    (start - 1, end + 1)
+  } else {
+    (start, end)
+  }
+}
+
+/// A loader that provides the rewritten sources of modules instead of the
+/// ones loaded by the wrapped loader.
+pub struct ChunkLoader<TLoader: Loader> {
+  inner: TLoader,
+  sources: Arc<HashMap<ModuleSpecifier, Arc<str>>>,
+}
+
+impl<TLoader: Loader> ChunkLoader<TLoader> {
+  pub fn new(
+    inner: TLoader,
+    sources: HashMap<ModuleSpecifier, Arc<str>>,
+  ) -> Self {
+    Self {
+      inner,
+      sources: Arc::new(sources),
+    }
+  }
+}
+
+impl<TLoader: Loader> Loader for ChunkLoader<TLoader> {
+  fn get_cache_info(&self, specifier: &ModuleSpecifier) -> Option<CacheInfo> {
+    self.inner.get_cache_info(specifier)
+  }
+
+  fn load(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    is_dynamic: bool,
+  ) -> LoadFuture {
+    let sources = self.sources.clone();
+    self
+      .inner
+      .load(specifier, is_dynamic)
+      .map(move |result| match result {
+        Ok(Some(LoadResponse::Module {
+          specifier,
+          maybe_headers,
+          content,
+        })) => {
+          let content = sources.get(&specifier).cloned().unwrap_or(content);
+          Ok(Some(LoadResponse::Module {
+            specifier,
+            maybe_headers,
+            content,
+          }))
+        }
+        result => result,
+      })
+      .boxed_local()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_graph::source::MemoryLoader;
+  use deno_graph::source::Source;
+
+  #[tokio::test]
+  async fn chunks_of_dynamic_imports() {
+    let module = |specifier: &str, content: &str| {
+      (
+        specifier.to_string(),
+        Source::Module {
+          specifier: specifier.to_string(),
+          content: content.to_string(),
+          maybe_headers: None,
+        },
+      )
+    };
+    let mut loader = MemoryLoader::new(
+      vec![
+        module(
+          "file:///main.ts",
+          "import { a } from './a.ts';\nconst { b } = await import(\"./b.ts\");\nconsole.log(a, b);",
+        ),
+        module("file:///a.ts", "export const a = 'ä';"),
+        module(
+          "file:///b.ts",
+          "export const b = 'b';\nexport const load = () => import('./a.ts');",
+        ),
+      ],
+      Vec::new(),
+    );
+    let mut graph = ModuleGraph::default();
+    graph
+      .build(
+        vec![ModuleSpecifier::parse("file:///main.ts").unwrap()],
+        &mut loader,
+        Default::default(),
+      )
+      .await;
+
+    let chunks = find_chunks(&graph, "main.js".to_string());
+    let b_specifier = ModuleSpecifier::parse("file:///b.ts").unwrap();
+    let a_specifier = ModuleSpecifier::parse("file:///a.ts").unwrap();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].file_name, "main.js");
+    let b_file_name = chunk_file_name(&b_specifier);
+    let a_file_name = chunk_file_name(&a_specifier);
+    assert!(chunks.iter().any(|chunk| chunk.specifier == b_specifier));
+
+    let sources = rewrite_dynamic_imports(&graph, &chunks);
+    assert_eq!(sources.len(), 2);
+    assert_eq!(
+      sources[&ModuleSpecifier::parse("file:///main.ts").unwrap()].as_ref(),
+      format!(
+        "import {{ a }} from './a.ts';\nconst {{ b }} = await import(`./{b_file_name}`);\nconsole.log(a, b);"
+      )
+    );
+    assert_eq!(
+      sources[&b_specifier].as_ref(),
+      format!(
+        "export const b = 'b';\nexport const load = () => import(`./{a_file_name}`);"
+      )
+    );
+  }
+
+  #[test]
+  fn byte_index_of_position() {
+    let text = "ä\nlet a = 'b';";
+    assert_eq!(
+      byte_index(
+        text,
+        &Position {
+          line: 1,
+          character: 8
+        }
+      ),
+      Some(11)
+    );
+    assert_eq!(
+      byte_index(
+        text,
+        &Position {
+          line: 0,
+          character: 1
+        }
+      ),
+      Some(2)
+    );
+    assert_eq!(
+      byte_index(
+        text,
+        &Position {
+          line: 2,
+          character: 0
+        }
+      ),
+      None
+    );
+  }
+}
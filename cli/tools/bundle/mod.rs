@@ -0,0 +1,265 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use deno_core::error::AnyError;
+use deno_core::futures::FutureExt;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_runtime::colors;
+
+use crate::args::BundleFlags;
+use crate::args::CliOptions;
+use crate::args::Flags;
+use crate::args::TsConfigType;
+use crate::args::TypeCheckMode;
+use crate::factory::CliFactory;
+use crate::graph_util::graph_valid_with_cli_options;
+use crate::util;
+use crate::util::display;
+use crate::util::file_watcher::ResolutionResult;
+
+mod chunks;
+mod resolver;
+
+use chunks::Chunk;
+use chunks::ChunkLoader;
+use resolver::BundleResolver;
+
+pub async fn bundle(
+  flags: Flags,
+  bundle_flags: BundleFlags,
+) -> Result<(), AnyError> {
+  let cli_options = Arc::new(CliOptions::from_flags(flags)?);
+
+  let module_specifier = cli_options.resolve_main_module()?;
+
+  let resolver = |_| {
+    let cli_options = cli_options.clone();
+    let module_specifier = &module_specifier;
+    async move {
+      log::debug!(">>>>> bundle START");
+      let factory = CliFactory::from_cli_options(cli_options);
+      let module_graph_builder = factory.module_graph_builder().await?;
+      let cli_options = factory.cli_options();
+
+      let graph = module_graph_builder
+        .create_graph_and_maybe_check(vec![module_specifier.clone()])
+        .await?;
+
+      let mut paths_to_watch: Vec<PathBuf> = graph
+        .specifiers()
+        .filter_map(|(_, r)| {
+          r.ok().and_then(|module| match module {
+            Module::Esm(m) => m.specifier.to_file_path().ok(),
+            Module::Json(m) => m.specifier.to_file_path().ok(),
+            // nothing to watch
+            Module::Node(_) | Module::Npm(_) | Module::External(_) => None,
+          })
+        })
+        .collect();
+
+      if let Ok(Some(import_map_path)) = cli_options
+        .resolve_import_map_specifier()
+        .map(|ms| ms.and_then(|ref s| s.to_file_path().ok()))
+      {
+        paths_to_watch.push(import_map_path);
+      }
+
+      Ok((paths_to_watch, graph, factory))
+    }
+    .map(move |result| match result {
+      Ok((paths_to_watch, graph, factory)) => ResolutionResult::Restart {
+        paths_to_watch,
+        result: Ok((factory, graph)),
+      },
+      Err(e) => ResolutionResult::Restart {
+        paths_to_watch: vec![module_specifier.to_file_path().unwrap()],
+        result: Err(e),
+      },
+    })
+  };
+
+  let operation =
+    |(factory, graph): (CliFactory, Arc<deno_graph::ModuleGraph>)| {
+      let bundle_flags = &bundle_flags;
+      async move {
+        // the npm packages of the graph were resolved by this factory
+        match &bundle_flags.out_dir {
+          Some(out_dir) => {
+            let main_file_name = graph.roots[0]
+              .path_segments()
+              .and_then(|mut segments| segments.next_back())
+              .and_then(|name| Path::new(name).file_stem())
+              .map(|stem| format!("{}.js", stem.to_string_lossy()))
+              .unwrap_or_else(|| "main.js".to_string());
+            let chunks = chunks::find_chunks(&graph, main_file_name);
+            let graph = create_bundle_graph(&factory, &graph, &chunks).await?;
+            std::fs::create_dir_all(out_dir)?;
+            for chunk in chunks {
+              let mut chunk_graph = graph.clone();
+              chunk_graph.roots = vec![chunk.specifier];
+              let bundle_output =
+                bundle_module_graph(&chunk_graph, factory.cli_options())?;
+              write_bundle(&out_dir.join(chunk.file_name), bundle_output)?;
+            }
+          }
+          None => {
+            let bundle_output = if graph
+              .modules()
+              .any(|module| matches!(module, Module::Npm(_)))
+            {
+              let graph = create_bundle_graph(&factory, &graph, &[]).await?;
+              bundle_module_graph(&graph, factory.cli_options())?
+            } else {
+              bundle_module_graph(graph.as_ref(), factory.cli_options())?
+            };
+            match &bundle_flags.out_file {
+              Some(out_file) => write_bundle(out_file, bundle_output)?,
+              None => println!("{}", bundle_output.code),
+            }
+          }
+        }
+        log::debug!(">>>>> bundle END");
+
+        Ok(())
+      }
+    };
+
+  if cli_options.watch_paths().is_some() {
+    util::file_watcher::watch_func(
+      resolver,
+      operation,
+      util::file_watcher::PrintConfig::new("Bundle", &cli_options)?,
+    )
+    .await?;
+  } else {
+    let module_graph =
+      if let ResolutionResult::Restart { result, .. } = resolver(None).await {
+        result?
+      } else {
+        unreachable!();
+      };
+    operation(module_graph).await?;
+  }
+
+  Ok(())
+}
+
+fn write_bundle(
+  out_file: &Path,
+  bundle_output: deno_emit::BundleEmit,
+) -> Result<(), AnyError> {
+  let output_bytes = bundle_output.code.as_bytes();
+  let output_len = output_bytes.len();
+  util::fs::write_file(out_file, output_bytes, 0o644)?;
+  log::info!(
+    "{} {:?} ({})",
+    colors::green("Emit"),
+    out_file,
+    colors::gray(display::human_size(output_len as f64))
+  );
+  if let Some(bundle_map) = bundle_output.maybe_map {
+    let map_bytes = bundle_map.as_bytes();
+    let map_len = map_bytes.len();
+    let ext = if let Some(curr_ext) = out_file.extension() {
+      format!("{}.map", curr_ext.to_string_lossy())
+    } else {
+      "map".to_string()
+    };
+    let map_out_file = out_file.with_extension(ext);
+    util::fs::write_file(&map_out_file, map_bytes, 0o644)?;
+    log::info!(
+      "{} {:?} ({})",
+      colors::green("Emit"),
+      map_out_file,
+      colors::gray(display::human_size(map_len as f64))
+    );
+  }
+  Ok(())
+}
+
+/// Creates the graph of the bundled modules, where npm packages resolve to
+/// their modules in the npm cache and the dynamic imports of the chunks are
+/// replaced with imports of the chunk files.
+async fn create_bundle_graph(
+  factory: &CliFactory,
+  graph: &ModuleGraph,
+  chunks: &[Chunk],
+) -> Result<ModuleGraph, AnyError> {
+  let cli_options = factory.cli_options();
+  let npm_resolver = factory.npm_resolver().await?;
+  // ensure the npm packages are in the npm cache
+  npm_resolver.resolve_pending().await?;
+  let resolver = factory.resolver().await?;
+  let bundle_resolver = BundleResolver::new(
+    resolver.as_graph_resolver(),
+    npm_resolver.clone(),
+    factory.node_resolver().await?.clone(),
+  );
+
+  let sources = chunks::rewrite_dynamic_imports(graph, chunks);
+  let parsed_source_cache = factory.parsed_source_cache()?;
+  for specifier in sources.keys() {
+    // the original source must not be reused
+    parsed_source_cache.free(specifier);
+  }
+  let mut loader = ChunkLoader::new(
+    factory.module_graph_builder().await?.create_graph_loader(),
+    sources,
+  );
+  let analyzer = parsed_source_cache.as_analyzer();
+  let roots = if chunks.is_empty() {
+    graph.roots.clone()
+  } else {
+    chunks.iter().map(|chunk| chunk.specifier.clone()).collect()
+  };
+
+  let mut bundle_graph = ModuleGraph::default();
+  bundle_graph
+    .build(
+      roots,
+      &mut loader,
+      deno_graph::BuildOptions {
+        is_dynamic: false,
+        imports: cli_options.to_maybe_imports()?,
+        resolver: Some(&bundle_resolver),
+        npm_resolver: None,
+        module_analyzer: Some(&*analyzer),
+        reporter: None,
+      },
+    )
+    .await;
+  graph_valid_with_cli_options(
+    &bundle_graph,
+    &bundle_graph.roots,
+    cli_options,
+  )?;
+  Ok(bundle_graph)
+}
+
+fn bundle_module_graph(
+  graph: &deno_graph::ModuleGraph,
+  cli_options: &CliOptions,
+) -> Result<deno_emit::BundleEmit, AnyError> {
+  log::info!("{} {}", colors::green("Bundle"), graph.roots[0]);
+
+  let ts_config_result =
+    cli_options.resolve_ts_config_for_emit(TsConfigType::Bundle)?;
+  if cli_options.type_check_mode() == TypeCheckMode::None {
+    if let Some(ignored_options) = ts_config_result.maybe_ignored_options {
+      log::warn!("{}", ignored_options);
+    }
+  }
+
+  deno_emit::bundle_graph(
+    graph,
+    deno_emit::BundleOptions {
+      bundle_type: deno_emit::BundleType::Module,
+      emit_options: ts_config_result.ts_config.into(),
+      emit_ignore_directives: true,
+    },
+  )
+}
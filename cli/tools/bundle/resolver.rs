@@ -0,0 +1,97 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::sync::Arc;
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use deno_graph::source::Resolver;
+use deno_runtime::deno_node::NodeResolution;
+use deno_runtime::deno_node::NodeResolutionMode;
+use deno_runtime::deno_node::NodeResolver;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_semver::npm::NpmPackageReqReference;
+
+use crate::npm::CliNpmResolver;
+
+/// Resolves specifiers like the wrapped resolver, except that npm specifiers
+/// and the imports of npm packages are resolved to the modules in the npm
+/// cache like node does, so that the packages are inlined into the bundle.
+#[derive(Debug)]
+pub struct BundleResolver<'a> {
+  resolver: &'a dyn Resolver,
+  npm_resolver: Arc<CliNpmResolver>,
+  node_resolver: Arc<NodeResolver>,
+}
+
+impl<'a> BundleResolver<'a> {
+  pub fn new(
+    resolver: &'a dyn Resolver,
+    npm_resolver: Arc<CliNpmResolver>,
+    node_resolver: Arc<NodeResolver>,
+  ) -> Self {
+    Self {
+      resolver,
+      npm_resolver,
+      node_resolver,
+    }
+  }
+}
+
+impl Resolver for BundleResolver<'_> {
+  fn default_jsx_import_source(&self) -> Option<String> {
+    self.resolver.default_jsx_import_source()
+  }
+
+  fn jsx_import_source_module(&self) -> &str {
+    self.resolver.jsx_import_source_module()
+  }
+
+  fn resolve(
+    &self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let permissions = PermissionsContainer::allow_all();
+    if self.npm_resolver.in_npm_package(referrer) {
+      let resolution = self.node_resolver.resolve(
+        specifier,
+        referrer,
+        NodeResolutionMode::Execution,
+        &permissions,
+      )?;
+      return resolution_to_specifier(resolution, specifier, referrer);
+    }
+
+    let resolved = self.resolver.resolve(specifier, referrer)?;
+    match NpmPackageReqReference::from_specifier(&resolved) {
+      Ok(req_ref) => {
+        let resolution = self.node_resolver.resolve_npm_req_reference(
+          &req_ref,
+          NodeResolutionMode::Execution,
+          &permissions,
+        )?;
+        resolution_to_specifier(resolution, specifier, referrer)
+      }
+      Err(_) => Ok(resolved),
+    }
+  }
+}
+
+fn resolution_to_specifier(
+  resolution: Option<NodeResolution>,
+  specifier: &str,
+  referrer: &ModuleSpecifier,
+) -> Result<ModuleSpecifier, AnyError> {
+  match resolution {
+    Some(NodeResolution::CommonJs(url)) => bail!(
+      "Can't bundle '{}' imported from '{}', because it resolves to the CommonJS module '{}'. Only ES modules of npm packages can be inlined.",
+      specifier,
+      referrer,
+      url
+    ),
+    // built-in node modules are kept as imports
+    Some(resolution) => Ok(resolution.into_url()),
+    None => bail!("Could not resolve '{}' from '{}'.", specifier, referrer),
+  }
+}
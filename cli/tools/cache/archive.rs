@@ -0,0 +1,330 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Archives of the cached remote modules and npm packages of a module graph
+//! (`deno cache --export` and `deno cache --import`), which can populate the
+//! DENO_DIR of a machine without network access.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_graph::ModuleGraph;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tar::Header;
+
+use crate::cache::CachedUrlMetadata;
+use crate::cache::DenoDir;
+use crate::cache::HttpCache;
+use crate::graph_util::graph_assets;
+use crate::npm::CliNpmRegistryApi;
+use crate::npm::CliNpmResolver;
+use crate::npm::NpmCache;
+
+const DEPS_FOLDER: &str = "deps";
+const NPM_FOLDER: &str = "npm";
+
+/// Files of the npm cache that only describe its use on this machine.
+const SKIPPED_FILE_NAMES: [&str; 2] = [".deno_last_used", ".deno_sync_lock"];
+
+pub struct ExportSummary {
+  pub module_count: usize,
+  pub package_count: usize,
+}
+
+/// Writes the cached remote modules and npm packages of the graph to a
+/// gzipped tarball, where they have their paths relative to DENO_DIR.
+pub fn export_cache(
+  deno_dir: &DenoDir,
+  graph: &ModuleGraph,
+  npm_resolver: &CliNpmResolver,
+  npm_cache: &NpmCache,
+  output_path: &Path,
+) -> Result<ExportSummary, AnyError> {
+  // the paths in the archive along with the paths of the files
+  let mut files = BTreeMap::new();
+
+  let deps_folder = deno_dir.deps_folder_path();
+  let http_cache = HttpCache::new(&deps_folder);
+  let specifiers = graph
+    .modules()
+    .map(|module| module.specifier().clone())
+    .chain(graph.redirects.keys().cloned())
+    .chain(graph_assets(graph))
+    .filter(|specifier| matches!(specifier.scheme(), "http" | "https"))
+    .collect::<BTreeSet<_>>();
+  let mut module_count = 0;
+  for specifier in specifiers {
+    let cache_filename = match http_cache.get_cache_filename(&specifier) {
+      Some(cache_filename) => cache_filename,
+      None => continue,
+    };
+    let metadata_filename = CachedUrlMetadata::filename(&cache_filename);
+    if !metadata_filename.is_file() {
+      bail!("The remote module \"{}\" is not cached.", specifier);
+    }
+    module_count += 1;
+    for path in [cache_filename, metadata_filename] {
+      if path.is_file() {
+        files.insert(archive_path(DEPS_FOLDER, &deps_folder, &path)?, path);
+      }
+    }
+  }
+
+  let npm_folder = deno_dir.npm_folder_path();
+  let registry_url = CliNpmRegistryApi::default_url();
+  let packages = npm_resolver
+    .snapshot()
+    .all_packages_partitioned()
+    .into_all();
+  for package in &packages {
+    let folder_id = package.get_package_cache_folder_id();
+    let package_folder =
+      npm_cache.package_folder_for_id(&folder_id, registry_url);
+    if !package_folder.is_dir() {
+      bail!("The npm package \"{}\" is not cached.", folder_id.nv);
+    }
+    let registry_file = npm_cache
+      .package_name_folder(&folder_id.nv.name, registry_url)
+      .join("registry.json");
+    let mut paths = read_files_recursive(&package_folder)?;
+    if registry_file.is_file() {
+      paths.push(registry_file);
+    }
+    for path in paths {
+      files.insert(archive_path(NPM_FOLDER, &npm_folder, &path)?, path);
+    }
+  }
+
+  write_archive(&files, output_path)?;
+  Ok(ExportSummary {
+    module_count,
+    package_count: packages.len(),
+  })
+}
+
+/// Gets the path of the file in the archive, which always uses forward
+/// slashes.
+fn archive_path(
+  folder_name: &str,
+  folder: &Path,
+  path: &Path,
+) -> Result<String, AnyError> {
+  let relative_path = path.strip_prefix(folder).with_context(|| {
+    format!("'{}' is not in '{}'", path.display(), folder.display())
+  })?;
+  let mut archive_path = folder_name.to_string();
+  for component in relative_path.components() {
+    archive_path.push('/');
+    archive_path.push_str(&component.as_os_str().to_string_lossy());
+  }
+  Ok(archive_path)
+}
+
+fn read_files_recursive(folder: &Path) -> Result<Vec<PathBuf>, AnyError> {
+  let mut files = Vec::new();
+  let mut pending = vec![folder.to_path_buf()];
+  while let Some(folder) = pending.pop() {
+    for entry in fs::read_dir(&folder)? {
+      let entry = entry?;
+      if entry.file_type()?.is_dir() {
+        pending.push(entry.path());
+      } else if !SKIPPED_FILE_NAMES
+        .iter()
+        .any(|name| entry.file_name() == *name)
+      {
+        files.push(entry.path());
+      }
+    }
+  }
+  Ok(files)
+}
+
+/// Writes the files sorted by their path and without timestamps, so that
+/// exporting the same files results in the same archive.
+fn write_archive(
+  files: &BTreeMap<String, PathBuf>,
+  output_path: &Path,
+) -> Result<(), AnyError> {
+  let file = fs::File::create(output_path)
+    .with_context(|| format!("Failed creating '{}'", output_path.display()))?;
+  let mut builder =
+    tar::Builder::new(GzEncoder::new(file, Compression::default()));
+  for (archive_path, path) in files {
+    let data = fs::read(path)
+      .with_context(|| format!("Failed to read '{}'", path.display()))?;
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(file_mode(path)?);
+    header.set_mtime(0);
+    header.set_cksum();
+    builder
+      .append_data(&mut header, archive_path, data.as_slice())
+      .with_context(|| {
+        format!("Failed to add '{archive_path}' to the archive")
+      })?;
+  }
+  let mut encoder = builder.into_inner()?;
+  encoder.flush()?;
+  encoder.finish()?;
+  Ok(())
+}
+
+/// Keeps the executable bit, which the binaries of npm packages need.
+fn file_mode(path: &Path) -> Result<u32, AnyError> {
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path)?.permissions().mode();
+    Ok(if mode & 0o111 != 0 { 0o755 } else { 0o644 })
+  }
+  #[cfg(not(unix))]
+  {
+    let _ = path;
+    Ok(0o644)
+  }
+}
+
+/// Extracts an archive of `export_cache` into DENO_DIR, returning the number
+/// of extracted files.
+pub fn import_cache(
+  deno_dir: &DenoDir,
+  archive_path: &Path,
+) -> Result<usize, AnyError> {
+  let file = fs::File::open(archive_path)
+    .with_context(|| format!("Failed opening '{}'", archive_path.display()))?;
+  let mut archive = tar::Archive::new(GzDecoder::new(file));
+  let mut file_count = 0;
+  for entry in archive.entries()? {
+    let mut entry = entry?;
+    if !entry.header().entry_type().is_file() {
+      continue;
+    }
+    let path = entry.path()?.into_owned();
+    let output_path = match resolve_import_path(deno_dir, &path) {
+      Some(output_path) => output_path,
+      None => bail!(
+        "Invalid path '{}' in the cache archive '{}'.",
+        path.display(),
+        archive_path.display()
+      ),
+    };
+    fs::create_dir_all(output_path.parent().unwrap())?;
+    entry
+      .unpack(&output_path)
+      .with_context(|| format!("Failed writing '{}'", output_path.display()))?;
+    file_count += 1;
+  }
+  Ok(file_count)
+}
+
+/// Resolves the path of a file in the archive to the path in DENO_DIR, which
+/// must be in one of the caches that are exported.
+fn resolve_import_path(deno_dir: &DenoDir, path: &Path) -> Option<PathBuf> {
+  let mut components = path.components();
+  let mut output_path = match components.next()? {
+    Component::Normal(name) if name == DEPS_FOLDER => {
+      deno_dir.deps_folder_path()
+    }
+    Component::Normal(name) if name == NPM_FOLDER => deno_dir.npm_folder_path(),
+    _ => return None,
+  };
+  let mut is_empty = true;
+  for component in components {
+    match component {
+      Component::Normal(name) => output_path.push(name),
+      _ => return None,
+    }
+    is_empty = false;
+  }
+  (!is_empty).then_some(output_path)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn export_and_import_archive() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("deno_dir/deps/https/deno.land");
+    temp_dir.write("deno_dir/deps/https/deno.land/abc", "export {};");
+    temp_dir.write("deno_dir/deps/https/deno.land/abc.metadata.json", "{}");
+    temp_dir.create_dir_all("deno_dir/npm/registry.npmjs.org/chalk/5.0.1");
+    temp_dir.write(
+      "deno_dir/npm/registry.npmjs.org/chalk/5.0.1/package.json",
+      "{}",
+    );
+    let deno_dir =
+      DenoDir::new(Some(temp_dir.path().join("deno_dir"))).unwrap();
+    let mut files = BTreeMap::new();
+    for path in read_files_recursive(&deno_dir.deps_folder_path()).unwrap() {
+      let archive_path =
+        archive_path(DEPS_FOLDER, &deno_dir.deps_folder_path(), &path).unwrap();
+      files.insert(archive_path, path);
+    }
+    for path in read_files_recursive(&deno_dir.npm_folder_path()).unwrap() {
+      let archive_path =
+        archive_path(NPM_FOLDER, &deno_dir.npm_folder_path(), &path).unwrap();
+      files.insert(archive_path, path);
+    }
+    assert_eq!(
+      files.keys().collect::<Vec<_>>(),
+      vec![
+        "deps/https/deno.land/abc",
+        "deps/https/deno.land/abc.metadata.json",
+        "npm/registry.npmjs.org/chalk/5.0.1/package.json",
+      ]
+    );
+
+    let archive_path = temp_dir.path().join("cache.tar.gz");
+    write_archive(&files, &archive_path).unwrap();
+    let first_bytes = fs::read(&archive_path).unwrap();
+    write_archive(&files, &archive_path).unwrap();
+    assert_eq!(fs::read(&archive_path).unwrap(), first_bytes);
+
+    let other_deno_dir =
+      DenoDir::new(Some(temp_dir.path().join("other_deno_dir"))).unwrap();
+    assert_eq!(import_cache(&other_deno_dir, &archive_path).unwrap(), 3);
+    assert_eq!(
+      temp_dir.read_to_string("other_deno_dir/deps/https/deno.land/abc"),
+      "export {};"
+    );
+    assert_eq!(
+      temp_dir.read_to_string(
+        "other_deno_dir/npm/registry.npmjs.org/chalk/5.0.1/package.json"
+      ),
+      "{}"
+    );
+  }
+
+  #[test]
+  fn import_paths() {
+    let deno_dir = DenoDir::new(Some(PathBuf::from("/deno_dir"))).unwrap();
+    assert_eq!(
+      resolve_import_path(&deno_dir, Path::new("npm/registry.npmjs.org/a")),
+      Some(
+        deno_dir
+          .npm_folder_path()
+          .join("registry.npmjs.org")
+          .join("a")
+      )
+    );
+    assert_eq!(resolve_import_path(&deno_dir, Path::new("npm")), None);
+    assert_eq!(resolve_import_path(&deno_dir, Path::new("gen/file")), None);
+    assert_eq!(
+      resolve_import_path(&deno_dir, Path::new("deps/../../etc/passwd")),
+      None
+    );
+    assert_eq!(resolve_import_path(&deno_dir, Path::new("/deps/a")), None);
+  }
+}
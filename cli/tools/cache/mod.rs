@@ -0,0 +1,539 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+mod archive;
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_ast::MediaType;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::ModuleSpecifier;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_npm::registry::NpmPackageInfo;
+use deno_npm::NpmPackageId;
+use deno_runtime::permissions::is_glob_pattern;
+use deno_runtime::permissions::matches_pattern;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_semver::npm::NpmPackageNv;
+
+use crate::args::integrity_for_bytes;
+use crate::args::CacheFlags;
+use crate::args::CacheListFlags;
+use crate::args::CheckNpmIntegrityFlags;
+use crate::args::Flags;
+use crate::args::Lockfile;
+use crate::cache::CachedUrlMetadata;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::file_fetcher::FileFetcher;
+use crate::graph_util::graph_assets;
+use crate::graph_util::GraphErrors;
+use crate::npm::read_npm_package_folders;
+use crate::npm::synthesize_npm_types;
+use crate::npm::CliNpmRegistryApi;
+use crate::npm::NpmCache;
+use crate::npm::PackageIntegrity;
+use crate::npm::PackageVerification;
+use crate::util::display;
+
+const METADATA_SUFFIX: &str = ".metadata.json";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedModule {
+  url: ModuleSpecifier,
+  path: PathBuf,
+  size: u64,
+  media_type: String,
+  /// RFC 3339 timestamp of when the module was fetched.
+  fetched_at: String,
+}
+
+/// Caches the files and their dependencies, returning the exit code.
+pub async fn cache_files(
+  flags: Flags,
+  cache_flags: CacheFlags,
+) -> Result<i32, AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let module_load_preparer = factory.module_load_preparer().await?;
+  let emitter = factory.emitter()?;
+  let graph_container = factory.graph_container();
+  let result = module_load_preparer
+    .load_and_type_check_files(&cache_flags.files)
+    .await;
+  if cache_flags.json {
+    let errors = match result {
+      Ok(()) => Vec::new(),
+      Err(err) => match err.downcast::<GraphErrors>() {
+        Ok(graph_errors) => graph_errors.errors().to_vec(),
+        Err(err) => return Err(err),
+      },
+    };
+    display::write_json_to_stdout(&json!({ "errors": errors }))?;
+    if !errors.is_empty() {
+      return Ok(1);
+    }
+  } else {
+    result?;
+  }
+  let graph = graph_container.graph();
+  emitter.cache_module_emits(&graph)?;
+  if cache_flags.synth_npm_types {
+    let npm_resolver = factory.npm_resolver().await?;
+    npm_resolver.resolve_pending().await?;
+    for package in synthesize_npm_types(npm_resolver)? {
+      log::info!("{} types for npm:{}", colors::green("Synthesized"), package);
+    }
+  }
+  if cache_flags.print_integrity {
+    let file_fetcher = factory.file_fetcher()?;
+    let integrity = graph_integrity(&graph, file_fetcher).await?;
+    display::write_json_to_stdout(&json!({ "integrity": integrity }))?;
+  }
+  if let Some(export_path) = &cache_flags.export {
+    let npm_resolver = factory.npm_resolver().await?;
+    npm_resolver.resolve_pending().await?;
+    let summary = archive::export_cache(
+      factory.deno_dir()?,
+      &graph,
+      npm_resolver,
+      factory.npm_cache()?,
+      export_path,
+    )?;
+    log::info!(
+      "{} {} remote module{} and {} npm package{} to {}",
+      colors::green("Exported"),
+      summary.module_count,
+      if summary.module_count == 1 { "" } else { "s" },
+      summary.package_count,
+      if summary.package_count == 1 { "" } else { "s" },
+      export_path.display(),
+    );
+  }
+  Ok(0)
+}
+
+/// Extracts an archive of `deno cache --export` into the cache.
+pub async fn import_cache(
+  flags: Flags,
+  archive_path: PathBuf,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let deno_dir = factory.deno_dir()?;
+  let file_count = archive::import_cache(deno_dir, &archive_path)?;
+  log::info!(
+    "{} {} file{} to {}",
+    colors::green("Imported"),
+    file_count,
+    if file_count == 1 { "" } else { "s" },
+    deno_dir.root_path_for_display(),
+  );
+  Ok(())
+}
+
+/// Verifies the files of the cached npm packages. With integrity flags the
+/// tarballs the packages were extracted from are checked against the lockfile
+/// or registry metadata as well and, unless repairing, the issues are only
+/// reported.
+pub async fn verify_npm_cache(
+  flags: Flags,
+  maybe_integrity_flags: Option<CheckNpmIntegrityFlags>,
+) -> Result<i32, AnyError> {
+  let repair = maybe_integrity_flags
+    .as_ref()
+    .map(|flags| flags.repair)
+    .unwrap_or(true);
+  let factory = CliFactory::from_flags(flags).await?;
+  let npm_folder = factory.deno_dir()?.npm_folder_path();
+  let npm_cache = factory.npm_cache()?;
+  let store = npm_cache.content_store();
+  let lockfile_integrities =
+    match (&maybe_integrity_flags, factory.maybe_lockfile()) {
+      (Some(_), Some(lockfile)) => lockfile_npm_integrities(&lockfile.lock())?,
+      _ => HashMap::new(),
+    };
+
+  let mut verified_count = 0;
+  let mut unverifiable_count = 0;
+  let mut modified_count = 0;
+  for package_folder in read_npm_package_folders(&npm_folder)? {
+    for entry in fs::read_dir(&package_folder)? {
+      let entry = entry?;
+      if !entry.file_type()?.is_dir() {
+        continue;
+      }
+      let version_folder = entry.path();
+      let display_path = version_folder
+        .strip_prefix(&npm_folder)
+        .unwrap_or(&version_folder)
+        .display()
+        .to_string();
+      if maybe_integrity_flags.is_some() {
+        if let Some(mismatch) = check_tarball_integrity(
+          npm_cache,
+          &lockfile_integrities,
+          &version_folder,
+        )? {
+          modified_count += 1;
+          if repair {
+            remove_package_folder(&version_folder)?;
+            log::warn!(
+              "{} {} ({}), it's downloaded again when it's used next",
+              colors::red("Removed"),
+              display_path,
+              mismatch,
+            );
+            verified_count += 1;
+            continue;
+          }
+          log::warn!(
+            "{} {} ({})",
+            colors::red("Mismatch"),
+            display_path,
+            mismatch
+          );
+        }
+      }
+      match store.verify_package_folder(&version_folder, repair)? {
+        PackageVerification::Valid => {}
+        PackageVerification::Unverifiable => {
+          log::debug!("Skipping {}, which has no digests", display_path);
+          unverifiable_count += 1;
+          continue;
+        }
+        PackageVerification::Modified(files) => {
+          log::warn!(
+            "{} {} ({})",
+            colors::yellow("Modified"),
+            display_path,
+            files.join(", ")
+          );
+          modified_count += 1;
+        }
+        PackageVerification::Healed(files) => {
+          log::warn!(
+            "{} {} ({})",
+            colors::yellow("Restored"),
+            display_path,
+            files.join(", ")
+          );
+          modified_count += 1;
+        }
+        PackageVerification::Corrupted(files) => {
+          remove_package_folder(&version_folder)?;
+          log::warn!(
+            "{} {} ({}), it's downloaded again when it's used next",
+            colors::red("Removed"),
+            display_path,
+            files.join(", ")
+          );
+          modified_count += 1;
+        }
+      }
+      verified_count += 1;
+    }
+  }
+
+  log::info!(
+    "{} {} npm package folder{}, {} modified",
+    colors::green("Verified"),
+    verified_count,
+    if verified_count == 1 { "" } else { "s" },
+    modified_count,
+  );
+  if unverifiable_count > 0 {
+    log::info!(
+      "Skipped {} folder{} extracted before digests were recorded",
+      unverifiable_count,
+      if unverifiable_count == 1 { "" } else { "s" },
+    );
+  }
+  if modified_count > 0 && !repair {
+    log::info!("Run with --repair to fix the modified folders");
+  }
+  Ok(if modified_count > 0 { 1 } else { 0 })
+}
+
+fn remove_package_folder(folder: &Path) -> Result<(), AnyError> {
+  fs::remove_dir_all(folder)
+    .with_context(|| format!("Failed removing '{}'.", folder.display()))
+}
+
+fn lockfile_npm_integrities(
+  lockfile: &Lockfile,
+) -> Result<HashMap<NpmPackageNv, String>, AnyError> {
+  let mut integrities = HashMap::new();
+  for (key, package) in &lockfile.content.npm.packages {
+    let id = NpmPackageId::from_serialized(key)?;
+    integrities.insert(id.nv, package.integrity.clone());
+  }
+  Ok(integrities)
+}
+
+/// Compares the integrity of the tarball a package version folder was
+/// extracted from to the one in the lockfile, or otherwise to the one in the
+/// cached registry metadata, returning a description of the mismatch.
+fn check_tarball_integrity(
+  npm_cache: &NpmCache,
+  lockfile_integrities: &HashMap<NpmPackageNv, String>,
+  version_folder: &Path,
+) -> Result<Option<String>, AnyError> {
+  let actual = match PackageIntegrity::read(version_folder)?
+    .and_then(|integrity| integrity.tarball_integrity)
+  {
+    Some(actual) => actual,
+    None => return Ok(None),
+  };
+  let specifier = match ModuleSpecifier::from_directory_path(version_folder) {
+    Ok(specifier) => specifier,
+    Err(()) => return Ok(None),
+  };
+  let folder_id = match npm_cache.resolve_package_folder_id_from_specifier(
+    &specifier,
+    CliNpmRegistryApi::default_url(),
+  ) {
+    Ok(folder_id) => folder_id,
+    Err(_) => return Ok(None),
+  };
+  let (expected, source) = match lockfile_integrities.get(&folder_id.nv) {
+    Some(expected) => (expected.clone(), "lockfile"),
+    None => {
+      let registry_file =
+        version_folder.parent().unwrap().join("registry.json");
+      match registry_integrity(&registry_file, &folder_id.nv) {
+        Some(expected) => (expected, "registry"),
+        None => return Ok(None),
+      }
+    }
+  };
+  Ok((actual != expected).then(|| {
+    format!("extracted from {actual}, but the {source} has {expected}")
+  }))
+}
+
+fn registry_integrity(
+  registry_file: &Path,
+  nv: &NpmPackageNv,
+) -> Option<String> {
+  let text = fs::read_to_string(registry_file).ok()?;
+  let info = serde_json::from_str::<NpmPackageInfo>(&text).ok()?;
+  let version_info = info.versions.get(&nv.version)?;
+  Some(version_info.dist.integrity().to_string())
+}
+
+/// Computes the integrity metadata of the remote modules and assets of the
+/// graph, which can be added to the `integrity` section of a config file.
+async fn graph_integrity(
+  graph: &ModuleGraph,
+  file_fetcher: &FileFetcher,
+) -> Result<BTreeMap<ModuleSpecifier, String>, AnyError> {
+  let specifiers = graph
+    .modules()
+    .filter(|module| matches!(module, Module::Esm(_) | Module::Json(_)))
+    .map(|module| module.specifier().clone())
+    .chain(graph_assets(graph))
+    .filter(|specifier| matches!(specifier.scheme(), "http" | "https"))
+    .collect::<BTreeSet<_>>();
+  let mut integrity = BTreeMap::new();
+  for specifier in specifiers {
+    let file = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await?;
+    let bytes = fs::read(&file.local).with_context(|| {
+      format!("Reading the cached contents of \"{specifier}\"")
+    })?;
+    integrity.insert(specifier, integrity_for_bytes(&bytes));
+  }
+  Ok(integrity)
+}
+
+/// Lists the remote modules in the http cache.
+pub async fn list_cached_modules(
+  flags: Flags,
+  list_flags: CacheListFlags,
+  json: bool,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let deps_folder = factory.deno_dir()?.deps_folder_path();
+  let mut modules = read_cached_modules(&deps_folder)?;
+  if let Some(filter) = &list_flags.filter {
+    modules.retain(|module| matches_filter(&module.url, filter));
+  }
+  modules.sort_by(|a, b| a.url.cmp(&b.url));
+
+  if json {
+    return display::write_json_to_stdout(&modules);
+  }
+  for module in &modules {
+    println!("{}", colors::bold(&module.url));
+    println!(
+      "  {} {} {}",
+      module.media_type,
+      colors::gray(display::human_size(module.size as f64)),
+      colors::gray(&module.fetched_at),
+    );
+    println!("  {}", colors::gray(module.path.display().to_string()));
+  }
+  let total_size = modules.iter().map(|m| m.size).sum::<u64>();
+  println!(
+    "{} cached remote module{} ({})",
+    modules.len(),
+    if modules.len() == 1 { "" } else { "s" },
+    display::human_size(total_size as f64),
+  );
+  Ok(())
+}
+
+/// Matches the module against a url pattern like
+/// `https://deno.land/std@*/http/*`, a url prefix or a host with an optional
+/// port, which also matches its subdomains.
+fn matches_filter(url: &ModuleSpecifier, filter: &str) -> bool {
+  if is_glob_pattern(filter) {
+    return matches_pattern(filter, url.as_str());
+  }
+  if filter.contains("://") {
+    return url.as_str().starts_with(filter);
+  }
+  let host = match url.host_str() {
+    Some(host) => host.to_lowercase(),
+    None => return false,
+  };
+  let host = match url.port() {
+    Some(port) => format!("{host}:{port}"),
+    None => host,
+  };
+  let filter = filter.to_lowercase();
+  host == filter || host.ends_with(&format!(".{filter}"))
+}
+
+fn read_cached_modules(
+  deps_folder: &Path,
+) -> Result<Vec<CachedModule>, AnyError> {
+  let mut modules = Vec::new();
+  if !deps_folder.is_dir() {
+    return Ok(modules);
+  }
+  let mut pending = vec![deps_folder.to_path_buf()];
+  while let Some(folder) = pending.pop() {
+    for entry in fs::read_dir(&folder)? {
+      let entry = entry?;
+      let path = entry.path();
+      if entry.file_type()?.is_dir() {
+        pending.push(path);
+        continue;
+      }
+      let path_str = path.to_string_lossy();
+      let cache_filename = match path_str.strip_suffix(METADATA_SUFFIX) {
+        Some(cache_filename) => PathBuf::from(cache_filename),
+        None => continue,
+      };
+      match read_cached_module(&cache_filename) {
+        Ok(module) => modules.push(module),
+        Err(err) => log::debug!(
+          "Skipping cached module {}: {:#}",
+          cache_filename.display(),
+          err
+        ),
+      }
+    }
+  }
+  Ok(modules)
+}
+
+fn read_cached_module(cache_filename: &Path) -> Result<CachedModule, AnyError> {
+  let metadata = CachedUrlMetadata::read(cache_filename)?;
+  let url = ModuleSpecifier::parse(&metadata.url)?;
+  let size = fs::metadata(cache_filename)?.len();
+  let media_type =
+    MediaType::from_specifier_and_headers(&url, Some(&metadata.headers));
+  let fetched_at = chrono::DateTime::<chrono::Utc>::from(metadata.now)
+    .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+  Ok(CachedModule {
+    url,
+    path: cache_filename.to_path_buf(),
+    size,
+    media_type: media_type.to_string(),
+    fetched_at,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::cache::HttpCache;
+  use pretty_assertions::assert_eq;
+  use std::collections::HashMap;
+
+  #[test]
+  fn filter_modules() {
+    let url =
+      ModuleSpecifier::parse("https://deno.land/std@0.190.0/http/server.ts")
+        .unwrap();
+    assert!(matches_filter(&url, "deno.land"));
+    assert!(matches_filter(&url, "Deno.Land"));
+    assert!(matches_filter(&url, "land"));
+    assert!(!matches_filter(&url, "and"));
+    assert!(!matches_filter(&url, "deno.land:8080"));
+    assert!(matches_filter(&url, "https://deno.land/std@"));
+    assert!(matches_filter(&url, "https://deno.land/std@*/http/*"));
+    assert!(!matches_filter(&url, "https://deno.land/x/*"));
+
+    let url = ModuleSpecifier::parse("http://localhost:4545/mod.ts").unwrap();
+    assert!(matches_filter(&url, "localhost:4545"));
+    assert!(!matches_filter(&url, "localhost"));
+  }
+
+  #[test]
+  fn read_modules() {
+    let temp_dir = test_util::TempDir::new();
+    let deps_folder = temp_dir.path().join("deps");
+    let cache = HttpCache::new(&deps_folder);
+    let url =
+      ModuleSpecifier::parse("https://deno.land/std@0.190.0/http/server.ts")
+        .unwrap();
+    cache
+      .set(&url, HashMap::new(), b"export const a = 1;")
+      .unwrap();
+    let url = ModuleSpecifier::parse("https://example.com/data").unwrap();
+    cache
+      .set(
+        &url,
+        HashMap::from([(
+          "content-type".to_string(),
+          "application/json".to_string(),
+        )]),
+        b"{}",
+      )
+      .unwrap();
+
+    let mut modules = read_cached_modules(&deps_folder).unwrap();
+    modules.sort_by(|a, b| a.url.cmp(&b.url));
+    assert_eq!(
+      modules
+        .iter()
+        .map(|m| (m.url.as_str(), m.size, m.media_type.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "https://deno.land/std@0.190.0/http/server.ts",
+          19,
+          "TypeScript"
+        ),
+        ("https://example.com/data", 2, "Json"),
+      ]
+    );
+    assert_eq!(
+      modules[0].path,
+      cache.get_cache_filename(&modules[0].url).unwrap()
+    );
+  }
+}
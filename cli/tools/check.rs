@@ -1,11 +1,16 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 use deno_ast::MediaType;
 use deno_ast::ModuleSpecifier;
 use deno_core::error::AnyError;
+use deno_core::futures::FutureExt;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json::json;
 use deno_graph::Module;
 use deno_graph::ModuleGraph;
 use deno_runtime::colors;
@@ -13,7 +18,9 @@ use deno_runtime::deno_node::NodeResolver;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::args::CheckFlags;
 use crate::args::CliOptions;
+use crate::args::Flags;
 use crate::args::TsConfig;
 use crate::args::TsConfigType;
 use crate::args::TsTypeLib;
@@ -21,8 +28,17 @@ use crate::args::TypeCheckMode;
 use crate::cache::Caches;
 use crate::cache::FastInsecureHasher;
 use crate::cache::TypeCheckCache;
+use crate::factory::CliFactory;
+use crate::graph_util::graph_lock_or_exit;
+use crate::graph_util::graph_valid_with_cli_options;
 use crate::npm::CliNpmResolver;
 use crate::tsc;
+use crate::util::display;
+use crate::util::file_watcher;
+use crate::util::file_watcher::ResolutionResult;
+use crate::util::progress_bar::ProgressBar;
+use crate::util::progress_bar::ProgressMessagePrompt;
+use crate::util::trace;
 use crate::version;
 
 /// Options for performing a check of a module graph. Note that the decision to
@@ -42,6 +58,7 @@ pub struct TypeChecker {
   cli_options: Arc<CliOptions>,
   node_resolver: Arc<NodeResolver>,
   npm_resolver: Arc<CliNpmResolver>,
+  progress_bar: ProgressBar,
 }
 
 impl TypeChecker {
@@ -50,12 +67,14 @@ impl TypeChecker {
     cli_options: Arc<CliOptions>,
     node_resolver: Arc<NodeResolver>,
     npm_resolver: Arc<CliNpmResolver>,
+    progress_bar: ProgressBar,
   ) -> Self {
     Self {
       caches,
       cli_options,
       node_resolver,
       npm_resolver,
+      progress_bar,
     }
   }
 
@@ -110,12 +129,17 @@ impl TypeChecker {
 
     let root_names = get_tsc_roots(&graph, check_js);
     // while there might be multiple roots, we can't "merge" the build info, so we
-    // try to retrieve the build info for first root, which is the most common use
-    // case.
+    // store the build info for the first root, which is the most common use
+    // case. The build info of any root is a valid starting point though, as tsc
+    // only reuses the state of files whose version didn't change, so fall back
+    // to the other roots (ex. when the roots were reordered or one was added).
     let maybe_tsbuildinfo = if options.reload {
       None
     } else {
-      cache.get_tsbuildinfo(&graph.roots[0])
+      graph
+        .roots
+        .iter()
+        .find_map(|root| cache.get_tsbuildinfo(root))
     };
     // to make tsc build info work, we need to consistently hash modules, so that
     // tsc can better determine if an emit is still valid or not, so we provide
@@ -127,6 +151,15 @@ impl TypeChecker {
       hasher.finish()
     };
 
+    let _status = self.progress_bar.status(
+      ProgressMessagePrompt::Check,
+      &format!("{} modules", root_names.len()),
+    );
+    let _span = trace::span_with_args(
+      "check",
+      "type check",
+      || json!({ "roots": root_names.len() }),
+    );
     let response = tsc::exec(tsc::Request {
       config: ts_config,
       debug,
@@ -179,6 +212,112 @@ impl TypeChecker {
   }
 }
 
+/// Type checks the files again whenever one of their local modules changes,
+/// printing a summary of each check. Only the modules affected by the changes
+/// are checked again, because the build info of the previous check is reused.
+pub async fn check_watch(
+  flags: Flags,
+  check_flags: CheckFlags,
+) -> Result<(), AnyError> {
+  let cli_options = Arc::new(CliOptions::from_flags(flags)?);
+  let roots = check_flags
+    .files
+    .iter()
+    .map(|file| resolve_url_or_path(file, cli_options.initial_cwd()))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let resolver = |_| {
+    let cli_options = cli_options.clone();
+    let roots = &roots;
+    async move {
+      let factory = CliFactory::from_cli_options(cli_options);
+      let module_graph_builder = factory.module_graph_builder().await?;
+      let graph = module_graph_builder.create_graph(roots.clone()).await?;
+      if let Some(lockfile) = factory.maybe_lockfile() {
+        graph_lock_or_exit(&graph, &mut lockfile.lock());
+      }
+      let paths_to_watch: Vec<PathBuf> = graph
+        .specifiers()
+        .filter_map(|(_, r)| match r.ok()? {
+          Module::Esm(m) => m.specifier.to_file_path().ok(),
+          Module::Json(m) => m.specifier.to_file_path().ok(),
+          // nothing to watch
+          Module::Node(_) | Module::Npm(_) | Module::External(_) => None,
+        })
+        .collect();
+      let type_checker = factory.type_checker().await?.clone();
+      Ok((
+        paths_to_watch,
+        (Arc::new(graph), type_checker, factory.cli_options().clone()),
+      ))
+    }
+    .map(move |result| match result {
+      Ok((paths_to_watch, operation_arg)) => ResolutionResult::Restart {
+        paths_to_watch,
+        result: Ok(operation_arg),
+      },
+      Err(err) => ResolutionResult::Restart {
+        paths_to_watch: roots
+          .iter()
+          .filter_map(|root| root.to_file_path().ok())
+          .collect(),
+        result: Err(err),
+      },
+    })
+  };
+
+  let operation = |(graph, type_checker, cli_options): (
+    Arc<ModuleGraph>,
+    Arc<TypeChecker>,
+    Arc<CliOptions>,
+  )| async move {
+    let started = Instant::now();
+    graph_valid_with_cli_options(&graph, &graph.roots, &cli_options)?;
+    let result = type_checker
+      .check(
+        graph,
+        CheckOptions {
+          lib: cli_options.ts_type_lib_window(),
+          log_ignored_options: true,
+          reload: false,
+        },
+      )
+      .await;
+    let elapsed = display::human_elapsed(started.elapsed().as_millis());
+    match result {
+      Ok(()) => {
+        log::info!("{} passed ({})", colors::green("Check"), elapsed);
+        Ok(())
+      }
+      Err(err) => match err.downcast_ref::<tsc::Diagnostics>() {
+        Some(diagnostics) => {
+          eprintln!("{}: {}", colors::red_bold("error"), diagnostics);
+          log::info!(
+            "{} failed with {} {} ({})",
+            colors::red("Check"),
+            diagnostics.len(),
+            if diagnostics.len() == 1 {
+              "error"
+            } else {
+              "errors"
+            },
+            elapsed,
+          );
+          Ok(())
+        }
+        None => Err(err),
+      },
+    }
+  };
+
+  file_watcher::watch_func(
+    resolver,
+    operation,
+    file_watcher::PrintConfig::new("Check", &cli_options)?,
+  )
+  .await
+}
+
 enum CheckHashResult {
   Hash(u64),
   NoFiles,
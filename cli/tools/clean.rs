@@ -0,0 +1,587 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use deno_core::error::AnyError;
+use deno_npm::NpmPackageId;
+
+use crate::args::CleanFlags;
+use crate::args::Flags;
+use crate::cache::DenoDir;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::npm::read_npm_package_folders;
+use crate::npm::CliNpmRegistryApi;
+use crate::npm::ContentStore;
+use crate::util::display::human_size;
+
+/// A part of a cache that is removed as a whole, eg. a remote module along
+/// with its metadata or a version of an npm package.
+#[derive(Debug)]
+struct CacheEntry {
+  /// Index of the cache the entry belongs to.
+  cache_index: usize,
+  paths: Vec<PathBuf>,
+  size: u64,
+  last_used: SystemTime,
+}
+
+struct Cache {
+  name: &'static str,
+  /// The folder of the cache, whose empty sub folders are removed after
+  /// removing entries.
+  maybe_folder: Option<PathBuf>,
+}
+
+pub async fn clean(
+  flags: Flags,
+  clean_flags: CleanFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let deno_dir = factory.deno_dir()?;
+
+  let (caches, entries) = collect_cache_entries(deno_dir)?;
+  remove_entries(
+    &caches,
+    &entries,
+    &clean_flags,
+    deno_dir.root_path_for_display(),
+  )?;
+  if !clean_flags.dry_run {
+    remove_unused_npm_files(&deno_dir.npm_folder_path())?;
+  }
+  Ok(())
+}
+
+/// Removes the npm packages that aren't used by the lockfile of the current
+/// project (`deno cache --prune-npm`), limited to the packages that weren't
+/// used recently or exceed the size budget like `deno clean`.
+pub async fn prune_npm_cache(
+  flags: Flags,
+  prune_flags: CleanFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let npm_folder = factory.deno_dir()?.npm_folder_path();
+  let npm_cache = factory.npm_cache()?;
+
+  let caches = vec![Cache {
+    name: "npm packages",
+    maybe_folder: Some(npm_folder.clone()),
+  }];
+  let mut entries = Vec::new();
+  collect_npm_entries(0, &npm_folder, &mut entries)?;
+
+  if let Some(lockfile) = factory.maybe_lockfile() {
+    let lockfile = lockfile.lock();
+    let registry_url = CliNpmRegistryApi::default_url();
+    let mut locked_folders = HashSet::new();
+    for key in lockfile.content.npm.packages.keys() {
+      if let Ok(id) = NpmPackageId::from_serialized(key) {
+        locked_folders.insert(
+          npm_cache.package_folder_for_name_and_version(&id.nv, registry_url),
+        );
+        locked_folders.insert(
+          npm_cache
+            .package_name_folder(&id.nv.name, registry_url)
+            .join("registry.json"),
+        );
+      }
+    }
+    let entry_count = entries.len();
+    entries.retain(|entry| {
+      !entry
+        .paths
+        .iter()
+        .any(|path| locked_folders.contains(&without_copy_index(path)))
+    });
+    log::info!(
+      "Keeping {} entries used by the lockfile {}",
+      entry_count - entries.len(),
+      lockfile.filename.display(),
+    );
+  }
+
+  remove_entries(&caches, &entries, &prune_flags, npm_folder.display())?;
+  if !prune_flags.dry_run {
+    remove_unused_npm_files(&npm_folder)?;
+  }
+  Ok(())
+}
+
+/// Removes the files of the npm content store that were only linked by the
+/// removed packages.
+fn remove_unused_npm_files(npm_folder: &Path) -> Result<(), AnyError> {
+  let removed_size = ContentStore::new(npm_folder).remove_unused()?;
+  log::debug!(
+    "Removed {} of unused npm package files",
+    human_size(removed_size as f64)
+  );
+  Ok(())
+}
+
+/// Returns the folder of the package version whose copy the path is, eg.
+/// `<name>/1.0.0` for `<name>/1.0.0_1`.
+fn without_copy_index(path: &Path) -> PathBuf {
+  match path.file_name().and_then(|name| name.to_str()) {
+    // versions can't contain an underscore
+    Some(name) if name.contains('_') => {
+      path.with_file_name(name.split('_').next().unwrap())
+    }
+    _ => path.to_path_buf(),
+  }
+}
+
+fn remove_entries(
+  caches: &[Cache],
+  entries: &[CacheEntry],
+  clean_flags: &CleanFlags,
+  root_display: impl std::fmt::Display,
+) -> Result<(), AnyError> {
+  let total_size = entries.iter().map(|e| e.size).sum::<u64>();
+  let removals =
+    select_entries_to_remove(entries, SystemTime::now(), clean_flags);
+
+  let mut removed_counts = vec![0usize; caches.len()];
+  let mut removed_sizes = vec![0u64; caches.len()];
+  for index in removals {
+    let entry = &entries[index];
+    if !clean_flags.dry_run {
+      for path in &entry.paths {
+        remove_path(path)?;
+      }
+    }
+    removed_counts[entry.cache_index] += 1;
+    removed_sizes[entry.cache_index] += entry.size;
+  }
+  if !clean_flags.dry_run {
+    for folder in caches.iter().filter_map(|c| c.maybe_folder.as_ref()) {
+      remove_empty_folders(folder)?;
+    }
+  }
+
+  let action = if clean_flags.dry_run {
+    "Would remove"
+  } else {
+    "Removed"
+  };
+  for (cache_index, cache) in caches.iter().enumerate() {
+    let cache_count = entries
+      .iter()
+      .filter(|e| e.cache_index == cache_index)
+      .count();
+    log::info!(
+      "{} {} of {} {} ({})",
+      colors::green(action),
+      removed_counts[cache_index],
+      cache_count,
+      cache.name,
+      human_size(removed_sizes[cache_index] as f64),
+    );
+  }
+  log::info!(
+    "{} {} of {} in {}",
+    if clean_flags.dry_run {
+      "Would free"
+    } else {
+      "Freed"
+    },
+    human_size(removed_sizes.iter().sum::<u64>() as f64),
+    human_size(total_size as f64),
+    root_display,
+  );
+  Ok(())
+}
+
+/// Returns the indexes of the entries to remove. Without a maximum age or
+/// size every entry is removed.
+fn select_entries_to_remove(
+  entries: &[CacheEntry],
+  now: SystemTime,
+  clean_flags: &CleanFlags,
+) -> Vec<usize> {
+  if clean_flags.max_age.is_none() && clean_flags.max_size.is_none() {
+    return (0..entries.len()).collect();
+  }
+
+  // least recently used first
+  let mut indexes = (0..entries.len()).collect::<Vec<_>>();
+  indexes.sort_by_key(|index| entries[*index].last_used);
+
+  let mut removals = Vec::new();
+  let mut remaining_size = entries.iter().map(|e| e.size).sum::<u64>();
+  for index in indexes {
+    let entry = &entries[index];
+    let is_expired = match clean_flags.max_age {
+      Some(max_age) => now
+        .duration_since(entry.last_used)
+        .map(|age| age > max_age)
+        .unwrap_or(false),
+      None => false,
+    };
+    let is_over_size = match clean_flags.max_size {
+      Some(max_size) => remaining_size > max_size,
+      None => false,
+    };
+    if is_expired || is_over_size {
+      removals.push(index);
+      remaining_size -= entry.size;
+    }
+  }
+  removals
+}
+
+fn collect_cache_entries(
+  deno_dir: &DenoDir,
+) -> Result<(Vec<Cache>, Vec<CacheEntry>), AnyError> {
+  let mut caches = Vec::new();
+  let mut entries = Vec::new();
+
+  // a remote module is stored along with a `.metadata.json` file
+  let deps_folder = deno_dir.deps_folder_path();
+  caches.push(Cache {
+    name: "remote modules",
+    maybe_folder: Some(deps_folder.clone()),
+  });
+  collect_grouped_files(
+    caches.len() - 1,
+    &deps_folder,
+    |path| {
+      let path_str = path.to_string_lossy();
+      match path_str.strip_suffix(".metadata.json") {
+        Some(module_path) => PathBuf::from(module_path),
+        None => path.to_path_buf(),
+      }
+    },
+    &mut entries,
+  )?;
+
+  // emits are stored by content hash and modules have a `.meta` file
+  let gen_folder = deno_dir.gen_cache.location.clone();
+  caches.push(Cache {
+    name: "emitted files",
+    maybe_folder: Some(gen_folder.clone()),
+  });
+  collect_grouped_files(
+    caches.len() - 1,
+    &gen_folder,
+    |path| path.with_extension(""),
+    &mut entries,
+  )?;
+
+  caches.push(Cache {
+    name: "type checking, analysis and code caches",
+    maybe_folder: None,
+  });
+  for path in [
+    deno_dir.type_checking_cache_db_file_path(),
+    deno_dir.dep_analysis_db_file_path(),
+    deno_dir.node_analysis_db_file_path(),
+    deno_dir.fmt_incremental_cache_db_file_path(),
+    deno_dir.lint_incremental_cache_db_file_path(),
+    deno_dir.code_cache_db_file_path(),
+    deno_dir.npm_snapshot_db_file_path(),
+  ] {
+    if let Some(entry) = read_entry(caches.len() - 1, vec![path])? {
+      entries.push(entry);
+    }
+  }
+
+  let npm_folder = deno_dir.npm_folder_path();
+  caches.push(Cache {
+    name: "npm packages",
+    maybe_folder: Some(npm_folder.clone()),
+  });
+  collect_npm_entries(caches.len() - 1, &npm_folder, &mut entries)?;
+
+  Ok((caches, entries))
+}
+
+/// Collects the files below the folder as entries, grouping the files that
+/// `get_group` returns the same path for.
+fn collect_grouped_files(
+  cache_index: usize,
+  folder: &Path,
+  get_group: impl Fn(&Path) -> PathBuf,
+  entries: &mut Vec<CacheEntry>,
+) -> Result<(), AnyError> {
+  let mut groups: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+  for path in read_files_recursive(folder)? {
+    groups.entry(get_group(&path)).or_default().push(path);
+  }
+  for mut paths in groups.into_values() {
+    paths.sort();
+    if let Some(entry) = read_entry(cache_index, paths)? {
+      entries.push(entry);
+    }
+  }
+  Ok(())
+}
+
+/// Collects every version of a package as well as its `registry.json` as
+/// entries.
+fn collect_npm_entries(
+  cache_index: usize,
+  npm_folder: &Path,
+  entries: &mut Vec<CacheEntry>,
+) -> Result<(), AnyError> {
+  for package_folder in read_npm_package_folders(npm_folder)? {
+    for entry in fs::read_dir(&package_folder)? {
+      let path = entry?.path();
+      // the lock files of the version folders may be held by other processes
+      if path.extension().map(|ext| ext == "lock").unwrap_or(false) {
+        continue;
+      }
+      if let Some(entry) = read_entry(cache_index, vec![path])? {
+        entries.push(entry);
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Reads the size and the last use of the paths, which are the most recent
+/// access or modification of the files below them.
+fn read_entry(
+  cache_index: usize,
+  paths: Vec<PathBuf>,
+) -> Result<Option<CacheEntry>, AnyError> {
+  let mut size = 0;
+  let mut last_used = SystemTime::UNIX_EPOCH;
+  let mut found = false;
+  for path in &paths {
+    let files = if path.is_dir() {
+      read_files_recursive(path)?
+    } else if path.exists() {
+      vec![path.clone()]
+    } else {
+      continue;
+    };
+    found = true;
+    for file in files {
+      let metadata = fs::metadata(&file)?;
+      size += metadata.len();
+      for time in [metadata.modified(), metadata.accessed()]
+        .into_iter()
+        .flatten()
+      {
+        last_used = last_used.max(time);
+      }
+    }
+  }
+  Ok(found.then_some(CacheEntry {
+    cache_index,
+    paths,
+    size,
+    last_used,
+  }))
+}
+
+fn read_files_recursive(folder: &Path) -> Result<Vec<PathBuf>, AnyError> {
+  let mut files = Vec::new();
+  if !folder.is_dir() {
+    return Ok(files);
+  }
+  let mut pending = vec![folder.to_path_buf()];
+  while let Some(folder) = pending.pop() {
+    for entry in fs::read_dir(&folder)? {
+      let entry = entry?;
+      if entry.file_type()?.is_dir() {
+        pending.push(entry.path());
+      } else {
+        files.push(entry.path());
+      }
+    }
+  }
+  Ok(files)
+}
+
+fn read_sub_folders(folder: &Path) -> Result<Vec<PathBuf>, AnyError> {
+  let mut folders = Vec::new();
+  if !folder.is_dir() {
+    return Ok(folders);
+  }
+  for entry in fs::read_dir(folder)? {
+    let entry = entry?;
+    if entry.file_type()?.is_dir() {
+      folders.push(entry.path());
+    }
+  }
+  Ok(folders)
+}
+
+fn remove_path(path: &Path) -> Result<(), AnyError> {
+  let result = if path.is_dir() {
+    fs::remove_dir_all(path)
+  } else {
+    fs::remove_file(path)
+  };
+  match result {
+    Ok(()) => Ok(()),
+    // removed by another process in the meantime
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    Err(err) => Err(err.into()),
+  }
+}
+
+/// Removes the empty folders below the folder, keeping the folder itself.
+fn remove_empty_folders(folder: &Path) -> Result<(), AnyError> {
+  for sub_folder in read_sub_folders(folder)? {
+    remove_empty_folders(&sub_folder)?;
+    if fs::read_dir(&sub_folder)?.next().is_none() {
+      fs::remove_dir(&sub_folder)?;
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use std::time::Duration;
+
+  const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+  #[test]
+  fn select_entries() {
+    let now = SystemTime::UNIX_EPOCH + DAY * 100;
+    let entry = |size: u64, age_days: u32| CacheEntry {
+      cache_index: 0,
+      paths: vec![],
+      size,
+      last_used: now - DAY * age_days,
+    };
+    let entries = vec![entry(100, 40), entry(200, 1), entry(300, 10)];
+    let select = |max_age: Option<Duration>, max_size: Option<u64>| {
+      let mut removals = select_entries_to_remove(
+        &entries,
+        now,
+        &CleanFlags {
+          dry_run: false,
+          max_age,
+          max_size,
+        },
+      );
+      removals.sort();
+      removals
+    };
+
+    assert_eq!(select(None, None), vec![0, 1, 2]);
+    assert_eq!(select(Some(DAY * 30), None), vec![0]);
+    assert_eq!(select(Some(DAY * 5), None), vec![0, 2]);
+    // least recently used first
+    assert_eq!(select(None, Some(500)), vec![0]);
+    assert_eq!(select(None, Some(250)), vec![0, 2]);
+    assert_eq!(select(None, Some(0)), vec![0, 1, 2]);
+    assert_eq!(select(Some(DAY * 30), Some(10_000)), vec![0]);
+  }
+
+  #[test]
+  fn package_folder_without_copy_index() {
+    let name_folder = PathBuf::from("npm/registry.npmjs.org/chalk");
+    assert_eq!(
+      without_copy_index(&name_folder.join("5.0.1_2")),
+      name_folder.join("5.0.1")
+    );
+    assert_eq!(
+      without_copy_index(&name_folder.join("5.0.1-beta.1")),
+      name_folder.join("5.0.1-beta.1")
+    );
+    assert_eq!(
+      without_copy_index(&name_folder.join("registry.json")),
+      name_folder.join("registry.json")
+    );
+  }
+
+  #[test]
+  fn collect_entries() {
+    let temp_dir = test_util::TempDir::new();
+    let deno_dir = DenoDir::new(Some(temp_dir.path().to_path_buf())).unwrap();
+    temp_dir.create_dir_all("deps/https/deno.land");
+    temp_dir.write("deps/https/deno.land/abc", "export {};");
+    temp_dir.write("deps/https/deno.land/abc.metadata.json", "{}");
+    temp_dir.write("deps/https/deno.land/def", "export {};");
+    temp_dir.write("check_cache_v1", "");
+    temp_dir.create_dir_all("npm/registry.npmjs.org/chalk/5.0.1");
+    temp_dir.write("npm/registry.npmjs.org/chalk/registry.json", "{}");
+    temp_dir.write("npm/registry.npmjs.org/chalk/5.0.1.lock", "");
+    temp_dir.write("npm/registry.npmjs.org/chalk/5.0.1/package.json", "{}");
+    temp_dir.create_dir_all("npm/registry.npmjs.org/@types/node/18.0.0");
+    temp_dir.write("npm/registry.npmjs.org/@types/node/registry.json", "{}");
+    temp_dir.create_dir_all("npm/_content/sha512/ab");
+    temp_dir.write("npm/_content/sha512/ab/abc", "{}");
+
+    let (caches, entries) = collect_cache_entries(&deno_dir).unwrap();
+    let mut entry_paths = entries
+      .iter()
+      .map(|entry| {
+        let paths = entry
+          .paths
+          .iter()
+          .map(|p| {
+            p.strip_prefix(temp_dir.path())
+              .unwrap()
+              .to_string_lossy()
+              .replace('\\', "/")
+          })
+          .collect::<Vec<_>>();
+        (caches[entry.cache_index].name, paths.join(","))
+      })
+      .collect::<Vec<_>>();
+    entry_paths.sort();
+    assert_eq!(
+      entry_paths,
+      vec![
+        (
+          "npm packages",
+          "npm/registry.npmjs.org/@types/node/18.0.0".to_string()
+        ),
+        (
+          "npm packages",
+          "npm/registry.npmjs.org/@types/node/registry.json".to_string()
+        ),
+        (
+          "npm packages",
+          "npm/registry.npmjs.org/chalk/5.0.1".to_string()
+        ),
+        (
+          "npm packages",
+          "npm/registry.npmjs.org/chalk/registry.json".to_string()
+        ),
+        (
+          "remote modules",
+          "deps/https/deno.land/abc,deps/https/deno.land/abc.metadata.json"
+            .to_string()
+        ),
+        ("remote modules", "deps/https/deno.land/def".to_string()),
+        (
+          "type checking, analysis and code caches",
+          "check_cache_v1".to_string()
+        ),
+      ]
+    );
+
+    let entry = entries
+      .iter()
+      .find(|e| e.paths[0].ends_with("abc"))
+      .unwrap();
+    assert_eq!(entry.size, 12);
+
+    remove_path(&temp_dir.path().join("deps/https/deno.land/def")).unwrap();
+    remove_empty_folders(&temp_dir.path().join("npm")).unwrap();
+    // the folder of the empty version is removed
+    assert!(!temp_dir
+      .path()
+      .join("npm/registry.npmjs.org/@types/node/18.0.0")
+      .exists());
+    assert!(temp_dir
+      .path()
+      .join("npm/registry.npmjs.org/chalk")
+      .exists());
+  }
+}
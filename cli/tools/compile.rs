@@ -2,16 +2,23 @@
 
 use crate::args::CompileFlags;
 use crate::args::Flags;
+use crate::args::COMPILE_TARGETS;
 use crate::factory::CliFactory;
-use crate::graph_util::error_for_any_npm_specifier;
+use crate::graph_util::graph_assets;
 use crate::standalone::is_standalone_binary;
+use crate::standalone::WindowsResources;
+use crate::util::fs::normalize_path;
 use crate::util::path::path_has_trailing_slash;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
+use deno_core::ModuleSpecifier;
 use deno_runtime::colors;
+use deno_runtime::permissions::is_glob_pattern;
+use deno_runtime::permissions::path_is_glob_match;
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -22,18 +29,35 @@ pub async fn compile(
   flags: Flags,
   compile_flags: CompileFlags,
 ) -> Result<(), AnyError> {
+  if compile_flags.target.as_deref() == Some("list") {
+    for target in COMPILE_TARGETS {
+      println!("{target}");
+    }
+    return Ok(());
+  }
+
+  let windows_resources = WindowsResources::from_flags(&compile_flags)?;
+  if compile_flags.no_terminal && !compile_flags.is_windows_target() {
+    bail!("--no-terminal is only supported when compiling for Windows.");
+  }
   let factory = CliFactory::from_flags(flags).await?;
   let cli_options = factory.cli_options();
   let module_graph_builder = factory.module_graph_builder().await?;
   let parsed_source_cache = factory.parsed_source_cache()?;
   let binary_writer = factory.create_compile_binary_writer().await?;
   let module_specifier = cli_options.resolve_main_module()?;
+  if compile_flags.reproducible && cli_options.maybe_lockfile().is_none() {
+    bail!(
+      "--reproducible requires a lockfile, which pins the remote modules and the npm packages. Use a deno.json or --lock."
+    );
+  }
   let module_roots = {
     let mut vec = Vec::with_capacity(compile_flags.include.len() + 1);
     vec.push(module_specifier.clone());
-    for side_module in &compile_flags.include {
-      vec.push(resolve_url_or_path(side_module, cli_options.initial_cwd())?);
-    }
+    vec.extend(resolve_include_roots(
+      &compile_flags.include,
+      cli_options.initial_cwd(),
+    )?);
     vec
   };
 
@@ -50,12 +74,11 @@ pub async fn compile(
   )
   .unwrap();
 
-  if !cli_options.unstable() {
-    error_for_any_npm_specifier(&graph).context(
-      "Using npm specifiers with deno compile requires the --unstable flag.",
-    )?;
-  }
-
+  let mut assets = graph_assets(&graph);
+  assets.extend(collect_include_assets(
+    &compile_flags.include_assets,
+    cli_options.initial_cwd(),
+  )?);
   let parser = parsed_source_cache.as_capturing_parser();
   let eszip = eszip::EszipV2::from_graph(graph, &parser, Default::default())?;
 
@@ -72,8 +95,10 @@ pub async fn compile(
     .write_bin(
       &mut file,
       eszip,
+      &assets,
       &module_specifier,
       &compile_flags,
+      windows_resources.as_ref(),
       cli_options,
     )
     .await
@@ -85,12 +110,132 @@ pub async fn compile(
   {
     use std::os::unix::fs::PermissionsExt;
     let perms = std::fs::Permissions::from_mode(0o777);
-    std::fs::set_permissions(output_path, perms)?;
+    std::fs::set_permissions(&output_path, perms)?;
   }
 
+  if let Some(sign_cmd) = &compile_flags.sign_cmd {
+    sign_executable(sign_cmd, &output_path)?;
+  }
+
+  Ok(())
+}
+
+/// Runs the `--sign-cmd` command in the shell with the path of the
+/// executable, then checks that the executable still works.
+fn sign_executable(sign_cmd: &str, output_path: &Path) -> Result<(), AnyError> {
+  log::info!("{} {}", colors::green("Sign"), output_path.display());
+  let mut command = if cfg!(windows) {
+    let mut command = std::process::Command::new("cmd");
+    command.arg("/C").arg(format!(
+      "{} \"{}\"",
+      sign_cmd,
+      output_path.display()
+    ));
+    command
+  } else {
+    // pass the path as a positional parameter so it doesn't need quoting
+    let mut command = std::process::Command::new("sh");
+    command
+      .arg("-c")
+      .arg(format!("{sign_cmd} \"$@\""))
+      .arg("sh")
+      .arg(output_path);
+    command
+  };
+  let status = command
+    .status()
+    .with_context(|| format!("Failed to run the sign command: {sign_cmd}"))?;
+  if !status.success() {
+    bail!("The sign command failed with {}: {}", status, sign_cmd);
+  }
+  if !is_standalone_binary(output_path) {
+    bail!(
+      "The sign command changed {} in a way that removed its embedded modules.",
+      output_path.display()
+    );
+  }
   Ok(())
 }
 
+/// Resolves the modules of `--include`, expanding the glob patterns of local
+/// paths (eg. `./plugins/**/*.ts`) to the files they match.
+fn resolve_include_roots(
+  includes: &[String],
+  cwd: &Path,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  let mut roots = Vec::with_capacity(includes.len());
+  for include in includes {
+    if !is_glob_pattern(include) || include.contains("://") {
+      roots.push(resolve_url_or_path(include, cwd)?);
+      continue;
+    }
+    let pattern = normalize_path(cwd.join(include));
+    // walk the directory before the first component with a glob
+    let base_dir = pattern
+      .components()
+      .take_while(|component| {
+        !is_glob_pattern(&component.as_os_str().to_string_lossy())
+      })
+      .collect::<PathBuf>();
+    let mut matched = Vec::new();
+    for entry in walkdir::WalkDir::new(&base_dir).sort_by_file_name() {
+      let entry = match entry {
+        Ok(entry) => entry,
+        // the base directory may not exist
+        Err(_) => continue,
+      };
+      if entry.file_type().is_file()
+        && path_is_glob_match(&pattern, entry.path())
+      {
+        matched.push(ModuleSpecifier::from_file_path(entry.path()).map_err(
+          |_| {
+            generic_error(format!(
+              "Invalid module path: {}",
+              entry.path().display()
+            ))
+          },
+        )?);
+      }
+    }
+    if matched.is_empty() {
+      bail!("No modules matched the included pattern: {}", include);
+    }
+    roots.extend(matched);
+  }
+  Ok(roots)
+}
+
+/// Resolves the files to embed for `--include-asset`, walking the directories
+/// recursively. The files keep the file URL of their path, which is how they
+/// are imported by the compiled modules.
+fn collect_include_assets(
+  paths: &[PathBuf],
+  cwd: &Path,
+) -> Result<BTreeSet<ModuleSpecifier>, AnyError> {
+  let mut assets = BTreeSet::new();
+  for path in paths {
+    let path = normalize_path(cwd.join(path));
+    if !path.exists() {
+      bail!("Asset to include was not found: {}", path.display());
+    }
+    for entry in walkdir::WalkDir::new(&path).follow_links(true) {
+      let entry =
+        entry.with_context(|| format!("Reading asset: {}", path.display()))?;
+      if entry.file_type().is_file() {
+        assets.insert(ModuleSpecifier::from_file_path(entry.path()).map_err(
+          |_| {
+            generic_error(format!(
+              "Invalid asset path: {}",
+              entry.path().display()
+            ))
+          },
+        )?);
+      }
+    }
+  }
+  Ok(assets)
+}
+
 /// This function writes out a final binary to specified path. If output path
 /// is not already standalone binary it will return error instead.
 fn validate_output_path(output_path: &Path) -> Result<(), AnyError> {
@@ -209,6 +354,15 @@ mod test {
         args: Vec::new(),
         target: Some("x86_64-unknown-linux-gnu".to_string()),
         include: vec![],
+        include_assets: vec![],
+        no_compress: false,
+        icon: None,
+        product_name: None,
+        file_version: None,
+        sign_cmd: None,
+        reproducible: false,
+        no_terminal: false,
+        log_file: None,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -230,6 +384,15 @@ mod test {
         args: Vec::new(),
         target: Some("x86_64-pc-windows-msvc".to_string()),
         include: vec![],
+        include_assets: vec![],
+        no_compress: false,
+        icon: None,
+        product_name: None,
+        file_version: None,
+        sign_cmd: None,
+        reproducible: false,
+        no_terminal: false,
+        log_file: None,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -238,6 +401,85 @@ mod test {
     assert_eq!(path.file_name().unwrap(), "file.exe");
   }
 
+  #[test]
+  fn resolve_include_roots_expands_globs() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("plugins/nested");
+    temp_dir.write("plugins/a.ts", "");
+    temp_dir.write("plugins/b.js", "");
+    temp_dir.write("plugins/nested/c.ts", "");
+    temp_dir.write("worker.ts", "");
+
+    let url = |path: &str| {
+      ModuleSpecifier::from_file_path(temp_dir.path().join(path)).unwrap()
+    };
+    let roots = resolve_include_roots(
+      &[
+        "./plugins/**/*.ts".to_string(),
+        "worker.ts".to_string(),
+        "https://deno.land/x/mod.ts".to_string(),
+      ],
+      temp_dir.path(),
+    )
+    .unwrap();
+    assert_eq!(
+      roots,
+      vec![
+        url("plugins/a.ts"),
+        url("plugins/nested/c.ts"),
+        url("worker.ts"),
+        ModuleSpecifier::parse("https://deno.land/x/mod.ts").unwrap(),
+      ]
+    );
+
+    let roots =
+      resolve_include_roots(&["plugins/*".to_string()], temp_dir.path())
+        .unwrap();
+    assert_eq!(roots, vec![url("plugins/a.ts"), url("plugins/b.js")]);
+
+    let err =
+      resolve_include_roots(&["missing/*.ts".to_string()], temp_dir.path())
+        .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "No modules matched the included pattern: missing/*.ts"
+    );
+  }
+
+  #[test]
+  fn collect_include_assets_walks_dirs() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("templates/partials");
+    temp_dir.write("templates/page.html", "<main></main>");
+    temp_dir.write("templates/partials/nav.html", "<nav></nav>");
+    temp_dir.write("schema.sql", "CREATE TABLE a (b TEXT);");
+    temp_dir.write("other.txt", "");
+
+    let assets = collect_include_assets(
+      &[PathBuf::from("templates"), PathBuf::from("schema.sql")],
+      temp_dir.path(),
+    )
+    .unwrap();
+    let url = |path: &str| {
+      ModuleSpecifier::from_file_path(temp_dir.path().join(path)).unwrap()
+    };
+    assert_eq!(
+      assets,
+      BTreeSet::from([
+        url("templates/page.html"),
+        url("templates/partials/nav.html"),
+        url("schema.sql"),
+      ])
+    );
+
+    let err =
+      collect_include_assets(&[PathBuf::from("missing")], temp_dir.path())
+        .unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("Asset to include was not found"));
+  }
+
   #[test]
   fn test_os_specific_file_path() {
     fn run_test(path: &str, target: Option<&str>, expected: &str) {
@@ -7,11 +7,14 @@
 //! the future it can be easily extended to provide
 //! the same functions as ops available in JS runtime.
 
+use crate::args::workspace_member_matches_path;
+use crate::args::workspace_options_for_path;
 use crate::args::CliOptions;
 use crate::args::FilesConfig;
 use crate::args::FmtOptions;
 use crate::args::FmtOptionsConfig;
 use crate::args::ProseWrap;
+use crate::args::WorkspaceMemberOptions;
 use crate::colors;
 use crate::factory::CliFactory;
 use crate::util::diff::diff;
@@ -64,11 +67,12 @@ pub async fn format(
   let files = fmt_options.files;
   let check = fmt_options.check;
   let fmt_config_options = fmt_options.options;
+  let workspace_members = Arc::new(fmt_options.workspace_members);
 
   let resolver = |changed: Option<Vec<PathBuf>>| {
     let files_changed = changed.is_some();
 
-    let result = collect_fmt_files(&files).map(|files| {
+    let result = collect_fmt_files(&files, &workspace_members).map(|files| {
       let refmt_files = if let Some(paths) = changed {
         if check {
           files
@@ -108,14 +112,31 @@ pub async fn format(
   let operation = |(paths, fmt_options): (Vec<PathBuf>, FmtOptionsConfig)| async {
     let incremental_cache = Arc::new(IncrementalCache::new(
       caches.fmt_incremental_cache_db(),
-      &fmt_options,
+      &(
+        &fmt_options,
+        workspace_members
+          .iter()
+          .map(|member| &member.options)
+          .collect::<Vec<_>>(),
+      ),
       &paths,
     ));
     if check {
-      check_source_files(paths, fmt_options, incremental_cache.clone()).await?;
+      check_source_files(
+        paths,
+        fmt_options,
+        workspace_members.clone(),
+        incremental_cache.clone(),
+      )
+      .await?;
     } else {
-      format_source_files(paths, fmt_options, incremental_cache.clone())
-        .await?;
+      format_source_files(
+        paths,
+        fmt_options,
+        workspace_members.clone(),
+        incremental_cache.clone(),
+      )
+      .await?;
     }
     incremental_cache.wait_completion().await;
     Ok(())
@@ -125,32 +146,35 @@ pub async fn format(
     file_watcher::watch_func(
       resolver,
       operation,
-      file_watcher::PrintConfig {
-        job_name: "Fmt".to_string(),
-        clear_screen: !cli_options.no_clear_screen(),
-      },
+      file_watcher::PrintConfig::new("Fmt", cli_options)?,
     )
     .await?;
   } else {
-    let files = collect_fmt_files(&files).and_then(|files| {
-      if files.is_empty() {
-        Err(generic_error("No target files found."))
-      } else {
-        Ok(files)
-      }
-    })?;
+    let files =
+      collect_fmt_files(&files, &workspace_members).and_then(|files| {
+        if files.is_empty() {
+          Err(generic_error("No target files found."))
+        } else {
+          Ok(files)
+        }
+      })?;
     operation((files, fmt_config_options)).await?;
   }
 
   Ok(())
 }
 
-fn collect_fmt_files(files: &FilesConfig) -> Result<Vec<PathBuf>, AnyError> {
-  FileCollector::new(is_supported_ext_fmt)
+fn collect_fmt_files(
+  files: &FilesConfig,
+  workspace_members: &[WorkspaceMemberOptions<FmtOptionsConfig>],
+) -> Result<Vec<PathBuf>, AnyError> {
+  let mut paths = FileCollector::new(is_supported_ext_fmt)
     .ignore_git_folder()
     .ignore_node_modules()
     .add_ignore_paths(&files.exclude)
-    .collect_files(&files.include)
+    .collect_files(&files.include)?;
+  paths.retain(|path| workspace_member_matches_path(workspace_members, path));
+  Ok(paths)
 }
 
 /// Formats markdown (using <https://github.com/dprint/dprint-plugin-markdown>) and its code blocks
@@ -255,6 +279,7 @@ pub fn format_parsed_source(
 async fn check_source_files(
   paths: Vec<PathBuf>,
   fmt_options: FmtOptionsConfig,
+  workspace_members: Arc<Vec<WorkspaceMemberOptions<FmtOptionsConfig>>>,
   incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let not_formatted_files_count = Arc::new(AtomicUsize::new(0));
@@ -275,7 +300,12 @@ async fn check_source_files(
         return Ok(());
       }
 
-      match format_file(&file_path, &file_text, &fmt_options) {
+      let fmt_options = workspace_options_for_path(
+        &workspace_members,
+        &fmt_options,
+        &file_path,
+      );
+      match format_file(&file_path, &file_text, fmt_options) {
         Ok(Some(formatted_text)) => {
           not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
           let _g = output_lock.lock();
@@ -336,6 +366,7 @@ async fn check_source_files(
 async fn format_source_files(
   paths: Vec<PathBuf>,
   fmt_options: FmtOptionsConfig,
+  workspace_members: Arc<Vec<WorkspaceMemberOptions<FmtOptionsConfig>>>,
   incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let formatted_files_count = Arc::new(AtomicUsize::new(0));
@@ -354,10 +385,15 @@ async fn format_source_files(
         return Ok(());
       }
 
+      let fmt_options = workspace_options_for_path(
+        &workspace_members,
+        &fmt_options,
+        &file_path,
+      );
       match format_ensure_stable(
         &file_path,
         &file_contents.text,
-        &fmt_options,
+        fmt_options,
         format_file,
       ) {
         Ok(Some(formatted_text)) => {
@@ -5,6 +5,10 @@ use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Write;
 
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::noop_visit_type;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
 use deno_ast::ModuleSpecifier;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
@@ -23,9 +27,11 @@ use deno_runtime::colors;
 use deno_semver::npm::NpmPackageNv;
 use deno_semver::npm::NpmPackageNvReference;
 use deno_semver::npm::NpmPackageReqReference;
+use serde::Serialize;
 
 use crate::args::Flags;
 use crate::args::InfoFlags;
+use crate::cache::ParsedSourceCache;
 use crate::display;
 use crate::factory::CliFactory;
 use crate::graph_util::graph_lock_or_exit;
@@ -50,13 +56,18 @@ pub async fn info(flags: Flags, info_flags: InfoFlags) -> Result<(), AnyError> {
       graph_lock_or_exit(&graph, &mut lockfile.lock());
     }
 
+    let crons = collect_crons(&graph, factory.parsed_source_cache()?);
     if info_flags.json {
       let mut json_graph = json!(graph);
       add_npm_packages_to_json(&mut json_graph, npm_resolver);
+      if !crons.is_empty() {
+        json_graph["crons"] = serde_json::to_value(&crons)?;
+      }
       display::write_json_to_stdout(&json_graph)?;
     } else {
       let mut output = String::new();
       GraphDisplayContext::write(&graph, npm_resolver, &mut output)?;
+      write_crons(&crons, &mut output)?;
       display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
     }
   } else {
@@ -241,6 +252,107 @@ fn add_npm_packages_to_json(
   json.insert("npmPackages".to_string(), json_packages.into());
 }
 
+/// A `Deno.cron()` call with a literal name and schedule.
+#[derive(Serialize)]
+struct CronInfo {
+  name: String,
+  schedule: String,
+  specifier: ModuleSpecifier,
+}
+
+/// Finds the cron jobs registered by the modules of the graph.
+fn collect_crons(
+  graph: &ModuleGraph,
+  parsed_source_cache: &ParsedSourceCache,
+) -> Vec<CronInfo> {
+  let mut crons = Vec::new();
+  for module in graph.modules().filter_map(|module| module.esm()) {
+    // Errors are surfaced by the module graph.
+    let parsed_source =
+      match parsed_source_cache.get_parsed_source_from_esm_module(module) {
+        Ok(parsed_source) => parsed_source,
+        Err(_) => continue,
+      };
+    let mut collector = CronCollector::default();
+    let program = parsed_source.program();
+    let program: &ast::Program = &program;
+    program.visit_with(&mut collector);
+    crons.extend(collector.crons.into_iter().map(|(name, schedule)| {
+      CronInfo {
+        name,
+        schedule,
+        specifier: module.specifier.clone(),
+      }
+    }));
+  }
+  crons
+}
+
+#[derive(Default)]
+struct CronCollector {
+  crons: Vec<(String, String)>,
+}
+
+impl Visit for CronCollector {
+  noop_visit_type!();
+
+  fn visit_call_expr(&mut self, node: &ast::CallExpr) {
+    if is_deno_cron(&node.callee) && node.args.len() >= 2 {
+      if let (Some(name), Some(schedule)) = (
+        string_literal(&node.args[0].expr),
+        string_literal(&node.args[1].expr),
+      ) {
+        self.crons.push((name, schedule));
+      }
+    }
+    node.visit_children_with(self);
+  }
+}
+
+fn is_deno_cron(callee: &ast::Callee) -> bool {
+  if let ast::Callee::Expr(expr) = callee {
+    if let ast::Expr::Member(member_expr) = expr.as_ref() {
+      if let (ast::Expr::Ident(obj), ast::MemberProp::Ident(prop)) =
+        (member_expr.obj.as_ref(), &member_expr.prop)
+      {
+        return &*obj.sym == "Deno" && &*prop.sym == "cron";
+      }
+    }
+  }
+  false
+}
+
+fn string_literal(expr: &ast::Expr) -> Option<String> {
+  match expr {
+    ast::Expr::Lit(ast::Lit::Str(lit)) => Some(lit.value.to_string()),
+    ast::Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+      tpl.quasis.first().map(|quasi| quasi.raw.to_string())
+    }
+    _ => None,
+  }
+}
+
+fn write_crons<TWrite: Write>(
+  crons: &[CronInfo],
+  writer: &mut TWrite,
+) -> fmt::Result {
+  if crons.is_empty() {
+    return Ok(());
+  }
+  writeln!(writer)?;
+  writeln!(writer, "{}", colors::bold("crons:"))?;
+  for cron in crons {
+    writeln!(
+      writer,
+      "  {} {} {}",
+      cron.name,
+      colors::gray(format!("({})", cron.schedule)),
+      colors::gray(&cron.specifier),
+    )?;
+  }
+  Ok(())
+}
+
 struct TreeNode {
   text: String,
   children: Vec<TreeNode>,
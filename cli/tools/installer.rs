@@ -345,6 +345,23 @@ async fn resolve_shim_data(
     executable_args.push("--cert".to_string());
     executable_args.push(ca_file.to_owned())
   }
+  for client_cert in &flags.client_certs {
+    executable_args.push("--client-cert".to_string());
+    executable_args.push(format!(
+      "{}={},{}",
+      client_cert.host,
+      client_cert.cert_file.display(),
+      client_cert.key_file.display()
+    ));
+  }
+  for host_ca_cert in &flags.host_ca_certs {
+    executable_args.push("--host-ca".to_string());
+    executable_args.push(format!(
+      "{}={}",
+      host_ca_cert.host,
+      host_ca_cert.ca_file.display()
+    ));
+  }
   if let Some(log_level) = flags.log_level {
     if log_level == Level::Error {
       executable_args.push("--quiet".to_string());
@@ -402,6 +419,10 @@ async fn resolve_shim_data(
     executable_args.push(seed.to_string());
   }
 
+  if flags.heap_snapshot_on_oom {
+    executable_args.push("--heap-snapshot-on-oom".to_string());
+  }
+
   if let Some(inspect) = flags.inspect {
     executable_args.push(format!("--inspect={inspect}"));
   }
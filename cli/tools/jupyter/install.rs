@@ -0,0 +1,48 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use std::process::Command;
+
+/// Installs the kernelspec of the current executable with
+/// `jupyter kernelspec install`, which picks the location Jupyter looks for
+/// kernels in.
+pub fn install() -> Result<(), AnyError> {
+  let temp_dir = tempfile::TempDir::new()?;
+  let kernel_json_path = temp_dir.path().join("kernel.json");
+
+  // https://jupyter-client.readthedocs.io/en/stable/kernels.html#kernel-specs
+  let exe_path = std::env::current_exe()?;
+  let json_data = json!({
+    "argv": [
+      exe_path.to_string_lossy(),
+      "jupyter",
+      "--kernel",
+      "--conn",
+      "{connection_file}",
+    ],
+    "display_name": "Deno",
+    "language": "typescript",
+  });
+  std::fs::write(kernel_json_path, serde_json::to_string_pretty(&json_data)?)?;
+
+  let output = Command::new("jupyter")
+    .args(["kernelspec", "install", "--user", "--name", "deno"])
+    .arg(temp_dir.path())
+    .output()
+    .context(
+      "Failed to run `jupyter kernelspec install`, is Jupyter installed?",
+    )?;
+  if !output.status.success() {
+    bail!(
+      "Failed to install kernelspec: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+
+  log::info!("Deno kernelspec installed successfully.");
+  Ok(())
+}
@@ -0,0 +1,224 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// The wire format of the Jupyter messaging protocol, see
+// https://jupyter-client.readthedocs.io/en/latest/messaging.html#the-wire-protocol
+
+use bytes::Bytes;
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use ring::hmac;
+use zeromq::SocketRecv;
+use zeromq::SocketSend;
+use zeromq::ZmqMessage;
+
+use crate::util::time::utc_now;
+
+const DELIMITER: &[u8] = b"<IDS|MSG>";
+const PROTOCOL_VERSION: &str = "5.3";
+
+/// A socket whose messages are signed with the key of the connection file.
+pub struct Connection<S> {
+  socket: S,
+  key: Option<hmac::Key>,
+}
+
+impl<S> Connection<S> {
+  pub fn new(socket: S, key: &str) -> Self {
+    // An empty key disables signing.
+    let key = if key.is_empty() {
+      None
+    } else {
+      Some(hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes()))
+    };
+    Self { socket, key }
+  }
+}
+
+impl<S: SocketRecv> Connection<S> {
+  pub async fn recv(&mut self) -> Result<JupyterMessage, AnyError> {
+    let message = self.socket.recv().await?;
+    JupyterMessage::from_raw(message.into_vec(), self.key.as_ref())
+  }
+}
+
+impl<S: SocketSend> Connection<S> {
+  pub async fn send(
+    &mut self,
+    message: &JupyterMessage,
+  ) -> Result<(), AnyError> {
+    let frames = message.to_raw(self.key.as_ref())?;
+    let message = ZmqMessage::try_from(frames).map_err(|err| anyhow!(err))?;
+    self.socket.send(message).await?;
+    Ok(())
+  }
+}
+
+fn signature(key: Option<&hmac::Key>, parts: &[&[u8]]) -> String {
+  match key {
+    Some(key) => {
+      let mut context = hmac::Context::with_key(key);
+      for part in parts {
+        context.update(part);
+      }
+      hex_encode(context.sign().as_ref())
+    }
+    None => String::new(),
+  }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hex_decode(text: &[u8]) -> Option<Vec<u8>> {
+  if text.len() % 2 != 0 {
+    return None;
+  }
+  text
+    .chunks(2)
+    .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+    .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct JupyterMessage {
+  /// The routing prefix of the sender, which a reply is sent back to.
+  identities: Vec<Bytes>,
+  header: Value,
+  parent_header: Value,
+  metadata: Value,
+  content: Value,
+}
+
+impl JupyterMessage {
+  fn from_raw(
+    mut frames: Vec<Bytes>,
+    key: Option<&hmac::Key>,
+  ) -> Result<Self, AnyError> {
+    let delimiter = frames
+      .iter()
+      .position(|frame| &frame[..] == DELIMITER)
+      .ok_or_else(|| anyhow!("Missing delimiter in Jupyter message"))?;
+    let parts = frames.split_off(delimiter + 1);
+    frames.pop();
+    if parts.len() < 5 {
+      bail!("Incomplete Jupyter message");
+    }
+    if let Some(key) = key {
+      let signature = hex_decode(&parts[0]).unwrap_or_default();
+      let data =
+        [&parts[1][..], &parts[2][..], &parts[3][..], &parts[4][..]].concat();
+      // the comparison takes constant time, so that a valid signature can't be
+      // guessed from the time it takes to reject a message
+      hmac::verify(key, &data, &signature)
+        .map_err(|_| anyhow!("Invalid signature of Jupyter message"))?;
+    }
+    Ok(Self {
+      identities: frames,
+      header: serde_json::from_slice(&parts[1])?,
+      parent_header: serde_json::from_slice(&parts[2])?,
+      metadata: serde_json::from_slice(&parts[3])?,
+      content: serde_json::from_slice(&parts[4])?,
+    })
+  }
+
+  fn to_raw(&self, key: Option<&hmac::Key>) -> Result<Vec<Bytes>, AnyError> {
+    let header = serde_json::to_vec(&self.header)?;
+    let parent_header = serde_json::to_vec(&self.parent_header)?;
+    let metadata = serde_json::to_vec(&self.metadata)?;
+    let content = serde_json::to_vec(&self.content)?;
+    let signature =
+      signature(key, &[&header, &parent_header, &metadata, &content]);
+    let mut frames = self.identities.clone();
+    frames.push(Bytes::from_static(DELIMITER));
+    frames.push(Bytes::from(signature));
+    frames.push(Bytes::from(header));
+    frames.push(Bytes::from(parent_header));
+    frames.push(Bytes::from(metadata));
+    frames.push(Bytes::from(content));
+    Ok(frames)
+  }
+
+  pub fn message_type(&self) -> &str {
+    self.header["msg_type"].as_str().unwrap_or_default()
+  }
+
+  pub fn content(&self) -> &Value {
+    &self.content
+  }
+
+  /// Creates a message in reply to this one, which is routed back to its
+  /// sender.
+  pub fn new_reply(&self, message_type: &str, content: Value) -> Self {
+    let mut message = self.new_message(message_type, content);
+    message.identities = self.identities.clone();
+    message
+  }
+
+  /// Creates a message caused by this one, like the ones published on the
+  /// IOPub socket while a request is handled.
+  pub fn new_message(&self, message_type: &str, content: Value) -> Self {
+    Self {
+      identities: vec![],
+      header: json!({
+        "msg_id": uuid::Uuid::new_v4().to_string(),
+        "session": self.header["session"].clone(),
+        "username": self.header["username"].clone(),
+        "date": utc_now().to_rfc3339(),
+        "msg_type": message_type,
+        "version": PROTOCOL_VERSION,
+      }),
+      parent_header: self.header.clone(),
+      metadata: json!({}),
+      content,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn message_roundtrip() {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+    let request = JupyterMessage {
+      identities: vec![Bytes::from_static(b"client")],
+      header: json!({
+        "msg_id": "1",
+        "session": "session",
+        "username": "user",
+        "msg_type": "execute_request",
+        "version": PROTOCOL_VERSION,
+      }),
+      parent_header: json!({}),
+      metadata: json!({}),
+      content: json!({ "code": "1 + 1" }),
+    };
+    let frames = request.to_raw(Some(&key)).unwrap();
+    assert_eq!(&frames[0][..], b"client");
+    assert_eq!(&frames[1][..], DELIMITER);
+
+    let received =
+      JupyterMessage::from_raw(frames.clone(), Some(&key)).unwrap();
+    assert_eq!(received.message_type(), "execute_request");
+    assert_eq!(received.content()["code"], "1 + 1");
+
+    let reply = received.new_reply("execute_reply", json!({ "status": "ok" }));
+    assert_eq!(reply.identities, request.identities);
+    assert_eq!(reply.parent_header["msg_id"], "1");
+    assert_eq!(reply.header["session"], "session");
+
+    // messages with a wrong or malformed signature are rejected
+    let other_key = hmac::Key::new(hmac::HMAC_SHA256, b"other");
+    let received = JupyterMessage::from_raw(frames.clone(), Some(&other_key));
+    assert!(received.is_err());
+    let mut tampered = frames;
+    tampered[2] = Bytes::from_static(b"not hex");
+    assert!(JupyterMessage::from_raw(tampered, Some(&key)).is_err());
+  }
+}
@@ -0,0 +1,75 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::args::Flags;
+use crate::args::JupyterFlags;
+use crate::factory::CliFactory;
+use crate::tools::repl::ReplSession;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_runtime::permissions::PermissionsContainer;
+
+mod install;
+mod jupyter_msg;
+mod server;
+
+/// The connection file Jupyter passes to the kernel, see
+/// https://jupyter-client.readthedocs.io/en/latest/kernels.html#connection-files
+#[derive(Debug, Deserialize)]
+pub struct ConnectionSpec {
+  ip: String,
+  transport: String,
+  control_port: u32,
+  shell_port: u32,
+  hb_port: u32,
+  iopub_port: u32,
+  signature_scheme: String,
+  key: String,
+}
+
+pub async fn kernel(
+  flags: Flags,
+  jupyter_flags: JupyterFlags,
+) -> Result<(), AnyError> {
+  if jupyter_flags.install {
+    return install::install();
+  }
+  let conn_file = match jupyter_flags.conn_file {
+    Some(conn_file) if jupyter_flags.kernel => conn_file,
+    _ => bail!(
+      "Run `deno jupyter --install` to install the kernel, Jupyter then starts it with `--kernel --conn <file>`."
+    ),
+  };
+  let conn_file_text =
+    std::fs::read_to_string(&conn_file).with_context(|| {
+      format!("Failed to read connection file '{}'", conn_file.display())
+    })?;
+  let spec: ConnectionSpec = serde_json::from_str(&conn_file_text)
+    .context("Failed to parse connection file")?;
+  if spec.signature_scheme != "hmac-sha256" {
+    bail!(
+      "Unsupported signature scheme \"{}\", only \"hmac-sha256\" is supported",
+      spec.signature_scheme
+    );
+  }
+
+  let factory = CliFactory::from_flags(flags).await?;
+  let cli_options = factory.cli_options();
+  let main_module = cli_options.resolve_main_module()?;
+  let npm_resolver = factory.npm_resolver().await?.clone();
+  let resolver = factory.resolver().await?.clone();
+  let worker_factory = factory.create_cli_main_worker_factory().await?;
+  // Like the REPL started by `deno`, notebooks run with all permissions.
+  let mut worker = worker_factory
+    .create_main_worker(main_module, PermissionsContainer::allow_all())
+    .await?;
+  worker.setup_repl().await?;
+  let worker = worker.into_main_worker();
+  let repl_session =
+    ReplSession::initialize(cli_options, npm_resolver, resolver, worker)
+      .await?;
+
+  server::JupyterServer::start(spec, repl_session).await
+}
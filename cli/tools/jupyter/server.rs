@@ -0,0 +1,459 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// The kernel side of the Jupyter messaging protocol, see
+// https://jupyter-client.readthedocs.io/en/latest/messaging.html
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::task::spawn;
+use zeromq::PubSocket;
+use zeromq::RepSocket;
+use zeromq::RouterSocket;
+use zeromq::Socket;
+use zeromq::SocketRecv;
+use zeromq::SocketSend;
+
+use super::jupyter_msg::Connection;
+use super::jupyter_msg::JupyterMessage;
+use super::ConnectionSpec;
+use crate::tools::repl::EvaluationOutput;
+use crate::tools::repl::ReplSession;
+use crate::version;
+
+/// Redirects the console to a buffer that's flushed to the notebook after
+/// every cell.
+const SETUP_OUTPUT: &str = r#"function (internals) {
+  internals.jupyterOutput = [];
+  globalThis.console = new Deno[Deno.internal].Console((message, level) => {
+    internals.jupyterOutput.push([level > 1 ? "stderr" : "stdout", message]);
+  });
+}"#;
+
+const TAKE_OUTPUT: &str = r#"function (internals) {
+  return internals.jupyterOutput.splice(0);
+}"#;
+
+/// Returns the MIME bundle to display the result of a cell with, or `null`
+/// when there's nothing to display.
+const DISPLAY_RESULT: &str = r#"async function (internals) {
+  const value = internals.lastEvalResult;
+  if (value === undefined) {
+    return null;
+  }
+  const display = value?.[Symbol.for("Jupyter.display")];
+  if (typeof display === "function") {
+    return await display.call(value);
+  }
+
+  const bundle = {
+    "text/plain": internals.inspectArgs(["%o", value], {
+      colors: !internals.noColor,
+    }),
+  };
+  if (value instanceof Uint8Array) {
+    const mediaType = imageMediaType(value);
+    if (mediaType !== null) {
+      bundle[mediaType] = toBase64(value);
+    }
+  } else if (
+    Array.isArray(value) && value.length > 0 &&
+    value.every((row) => typeof row === "object" && row !== null)
+  ) {
+    bundle["text/html"] = toTable(value);
+  }
+  return bundle;
+
+  function imageMediaType(bytes) {
+    const startsWith = (signature) =>
+      signature.every((byte, i) => bytes[i] === byte);
+    if (startsWith([0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a])) {
+      return "image/png";
+    } else if (startsWith([0xff, 0xd8, 0xff])) {
+      return "image/jpeg";
+    } else if (startsWith([0x47, 0x49, 0x46, 0x38])) {
+      return "image/gif";
+    }
+    return null;
+  }
+
+  function toBase64(bytes) {
+    let binary = "";
+    for (let i = 0; i < bytes.length; i += 0x8000) {
+      binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+    }
+    return btoa(binary);
+  }
+
+  function toTable(rows) {
+    const escape = (text) =>
+      String(text).replace(/[&<>"]/g, (c) => `&#${c.charCodeAt(0)};`);
+    const format = (cell) =>
+      typeof cell === "object" && cell !== null
+        ? internals.inspectArgs(["%o", cell], { colors: false })
+        : String(cell);
+    const columns = [...new Set(rows.flatMap((row) => Object.keys(row)))];
+    const header = columns.map((column) => `<th>${escape(column)}</th>`);
+    const body = rows.map((row) =>
+      `<tr>${
+        columns.map((column) =>
+          `<td>${column in row ? escape(format(row[column])) : ""}</td>`
+        ).join("")
+      }</tr>`
+    );
+    return `<table><thead><tr>${header.join("")}</tr></thead>` +
+      `<tbody>${body.join("")}</tbody></table>`;
+  }
+}"#;
+
+pub struct JupyterServer {
+  execution_count: usize,
+  iopub: Connection<PubSocket>,
+  repl_session: ReplSession,
+}
+
+impl JupyterServer {
+  pub async fn start(
+    spec: ConnectionSpec,
+    mut repl_session: ReplSession,
+  ) -> Result<(), AnyError> {
+    let mut heartbeat = bind_socket::<RepSocket>(&spec, spec.hb_port).await?;
+    let mut control = Connection::new(
+      bind_socket::<RouterSocket>(&spec, spec.control_port).await?,
+      &spec.key,
+    );
+    let mut shell = Connection::new(
+      bind_socket::<RouterSocket>(&spec, spec.shell_port).await?,
+      &spec.key,
+    );
+    let iopub = Connection::new(
+      bind_socket::<PubSocket>(&spec, spec.iopub_port).await?,
+      &spec.key,
+    );
+
+    repl_session
+      .call_function_with_internals(SETUP_OUTPUT)
+      .await?;
+
+    spawn(async move {
+      if let Err(err) = handle_heartbeat(&mut heartbeat).await {
+        log::error!("Heartbeat error: {}", err);
+      }
+    });
+    spawn(async move {
+      if let Err(err) = handle_control(&mut control).await {
+        log::error!("Control error: {}", err);
+      }
+    });
+
+    let mut server = Self {
+      execution_count: 0,
+      iopub,
+      repl_session,
+    };
+    server.handle_shell(&mut shell).await
+  }
+
+  async fn handle_shell(
+    &mut self,
+    connection: &mut Connection<RouterSocket>,
+  ) -> Result<(), AnyError> {
+    let mut poll_worker = true;
+    loop {
+      // Keep running timers and other pending work of the previous cells
+      // while waiting for the next request.
+      let message = tokio::select! {
+        message = connection.recv() => message?,
+        _ = self.repl_session.run_event_loop(), if poll_worker => {
+          poll_worker = false;
+          continue;
+        }
+      };
+      poll_worker = true;
+      self
+        .publish(&message, "status", json!({ "execution_state": "busy" }))
+        .await?;
+      self.handle_shell_message(&message, connection).await?;
+      self.flush_output(&message).await?;
+      self
+        .publish(&message, "status", json!({ "execution_state": "idle" }))
+        .await?;
+    }
+  }
+
+  async fn handle_shell_message(
+    &mut self,
+    message: &JupyterMessage,
+    connection: &mut Connection<RouterSocket>,
+  ) -> Result<(), AnyError> {
+    let reply = match message.message_type() {
+      "kernel_info_request" => message.new_reply(
+        "kernel_info_reply",
+        json!({
+          "status": "ok",
+          "protocol_version": "5.3",
+          "implementation": "Deno kernel",
+          "implementation_version": version::deno(),
+          "language_info": {
+            "name": "typescript",
+            "version": version::TYPESCRIPT,
+            "mimetype": "text/x.typescript",
+            "file_extension": ".ts",
+            "pygments_lexer": "typescript",
+            "codemirror_mode": "typescript",
+          },
+          "banner": format!("Deno {}", version::deno()),
+          "help_links": [{
+            "text": "Visit Deno manual",
+            "url": "https://deno.land/manual",
+          }],
+        }),
+      ),
+      "execute_request" => self.handle_execute(message).await?,
+      "complete_request" => self.handle_complete(message).await,
+      "is_complete_request" => {
+        message.new_reply("is_complete_reply", json!({ "status": "complete" }))
+      }
+      "comm_info_request" => message
+        .new_reply("comm_info_reply", json!({ "status": "ok", "comms": {} })),
+      "history_request" => message
+        .new_reply("history_reply", json!({ "status": "ok", "history": [] })),
+      message_type => {
+        log::debug!("Unhandled Jupyter shell message: {}", message_type);
+        return Ok(());
+      }
+    };
+    connection.send(&reply).await
+  }
+
+  async fn handle_execute(
+    &mut self,
+    message: &JupyterMessage,
+  ) -> Result<JupyterMessage, AnyError> {
+    let code = message.content()["code"].as_str().unwrap_or_default();
+    let silent = message.content()["silent"].as_bool().unwrap_or(false);
+    if !silent {
+      self.execution_count += 1;
+    }
+    self
+      .publish(
+        message,
+        "execute_input",
+        json!({ "code": code, "execution_count": self.execution_count }),
+      )
+      .await?;
+
+    let output = self.repl_session.evaluate_line_and_get_output(code).await;
+    // Print what was logged before the result.
+    self.flush_output(message).await?;
+    match output {
+      EvaluationOutput::Value(_) => {
+        let data = self
+          .repl_session
+          .call_function_with_internals(DISPLAY_RESULT)
+          .await;
+        match data {
+          Ok(Value::Null) => {}
+          Ok(data) if !silent => {
+            self
+              .publish(
+                message,
+                "execute_result",
+                json!({
+                  "execution_count": self.execution_count,
+                  "data": data,
+                  "metadata": {},
+                }),
+              )
+              .await?;
+          }
+          Ok(_) => {}
+          Err(err) => {
+            return self.execute_error(message, format!("{err:#}")).await;
+          }
+        }
+        Ok(message.new_reply(
+          "execute_reply",
+          json!({
+            "status": "ok",
+            "execution_count": self.execution_count,
+            "payload": [],
+            "user_expressions": {},
+          }),
+        ))
+      }
+      EvaluationOutput::Error(text) => self.execute_error(message, text).await,
+    }
+  }
+
+  async fn execute_error(
+    &mut self,
+    message: &JupyterMessage,
+    text: String,
+  ) -> Result<JupyterMessage, AnyError> {
+    self
+      .publish(
+        message,
+        "error",
+        json!({ "ename": "Error", "evalue": &text, "traceback": [&text] }),
+      )
+      .await?;
+    let content = json!({
+      "status": "error",
+      "execution_count": self.execution_count,
+      "ename": "Error",
+      "evalue": &text,
+      "traceback": [&text],
+    });
+    Ok(message.new_reply("execute_reply", content))
+  }
+
+  async fn handle_complete(
+    &mut self,
+    message: &JupyterMessage,
+  ) -> JupyterMessage {
+    let code = message.content()["code"].as_str().unwrap_or_default();
+    // Jupyter counts positions in unicode code points.
+    let cursor_pos =
+      message.content()["cursor_pos"].as_u64().unwrap_or(0) as usize;
+    let byte_pos = code
+      .char_indices()
+      .nth(cursor_pos)
+      .map(|(i, _)| i)
+      .unwrap_or(code.len());
+    let completions = self
+      .repl_session
+      .language_server
+      .completions(code, byte_pos)
+      .await;
+    let to_char_pos = |byte_pos: usize| code[..byte_pos].chars().count();
+    let (cursor_start, cursor_end) = match completions.first() {
+      Some(item) => {
+        (to_char_pos(item.range.start), to_char_pos(item.range.end))
+      }
+      None => (cursor_pos, cursor_pos),
+    };
+    let matches = completions
+      .into_iter()
+      .map(|item| item.new_text)
+      .collect::<Vec<_>>();
+    message.new_reply(
+      "complete_reply",
+      json!({
+        "status": "ok",
+        "matches": matches,
+        "cursor_start": cursor_start,
+        "cursor_end": cursor_end,
+        "metadata": {},
+      }),
+    )
+  }
+
+  /// Sends what was written to the console to the notebook.
+  async fn flush_output(
+    &mut self,
+    message: &JupyterMessage,
+  ) -> Result<(), AnyError> {
+    let output = self
+      .repl_session
+      .call_function_with_internals(TAKE_OUTPUT)
+      .await?;
+    let mut streams: Vec<(String, String)> = Vec::new();
+    for entry in output.as_array().into_iter().flatten() {
+      let name = entry[0].as_str().unwrap_or("stdout");
+      let text = entry[1].as_str().unwrap_or_default();
+      match streams.last_mut() {
+        Some((last_name, last_text)) if last_name == name => {
+          last_text.push_str(text);
+        }
+        _ => streams.push((name.to_string(), text.to_string())),
+      }
+    }
+    for (name, text) in streams {
+      self
+        .publish(message, "stream", json!({ "name": name, "text": text }))
+        .await?;
+    }
+    Ok(())
+  }
+
+  async fn publish(
+    &mut self,
+    parent: &JupyterMessage,
+    message_type: &str,
+    content: Value,
+  ) -> Result<(), AnyError> {
+    self
+      .iopub
+      .send(&parent.new_message(message_type, content))
+      .await
+  }
+}
+
+async fn bind_socket<S: Socket>(
+  spec: &ConnectionSpec,
+  port: u32,
+) -> Result<S, AnyError> {
+  let endpoint = format!("{}://{}:{}", spec.transport, spec.ip, port);
+  let mut socket = S::new();
+  socket
+    .bind(&endpoint)
+    .await
+    .with_context(|| format!("Failed to bind {endpoint}"))?;
+  Ok(socket)
+}
+
+/// Echoes the heartbeat messages, which tells Jupyter the kernel is alive.
+async fn handle_heartbeat(socket: &mut RepSocket) -> Result<(), AnyError> {
+  loop {
+    let message = socket.recv().await?;
+    socket.send(message).await?;
+  }
+}
+
+async fn handle_control(
+  connection: &mut Connection<RouterSocket>,
+) -> Result<(), AnyError> {
+  loop {
+    let message = connection.recv().await?;
+    match message.message_type() {
+      "kernel_info_request" => {
+        connection
+          .send(&message.new_reply(
+            "kernel_info_reply",
+            json!({ "status": "ok", "protocol_version": "5.3" }),
+          ))
+          .await?;
+      }
+      "shutdown_request" => {
+        let restart = message.content()["restart"].as_bool().unwrap_or(false);
+        connection
+          .send(&message.new_reply(
+            "shutdown_reply",
+            json!({ "status": "ok", "restart": restart }),
+          ))
+          .await?;
+        // Jupyter starts a new kernel to restart it.
+        std::process::exit(0);
+      }
+      "interrupt_request" => {
+        // Interrupting a running cell isn't supported yet.
+        connection
+          .send(&message.new_reply(
+            "interrupt_reply",
+            json!({
+              "status": "error",
+              "ename": "Error",
+              "evalue": "Interrupting isn't supported",
+              "traceback": [],
+            }),
+          ))
+          .await?;
+      }
+      message_type => {
+        log::debug!("Unhandled Jupyter control message: {}", message_type);
+      }
+    }
+  }
+}
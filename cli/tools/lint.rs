@@ -6,11 +6,14 @@
 //! At the moment it is only consumed using CLI but in
 //! the future it can be easily extended to provide
 //! the same functions as ops available in JS runtime.
+use crate::args::workspace_member_matches_path;
+use crate::args::workspace_options_for_path;
 use crate::args::CliOptions;
 use crate::args::FilesConfig;
 use crate::args::LintOptions;
 use crate::args::LintReporterKind;
 use crate::args::LintRulesConfig;
+use crate::args::WorkspaceMemberOptions;
 use crate::colors;
 use crate::factory::CliFactory;
 use crate::tools::fmt::run_parallelized;
@@ -66,12 +69,29 @@ pub async fn lint(
     bail!("No rules have been configured")
   }
 
+  // the rules of workspace members apply to the files in their directories
+  let workspace_members = Arc::new(
+    lint_options
+      .workspace_members
+      .iter()
+      .map(|member| member.map_options(|r| get_configured_rules(r.clone())))
+      .collect::<Vec<_>>(),
+  );
+  for member in workspace_members.iter() {
+    if member.options.is_empty() {
+      bail!(
+        "No rules have been configured for workspace member \"{}\"",
+        member.dir.display()
+      )
+    }
+  }
+
   let files = lint_options.files;
   let reporter_kind = lint_options.reporter_kind;
 
   let resolver = |changed: Option<Vec<PathBuf>>| {
     let files_changed = changed.is_some();
-    let result = collect_lint_files(&files).map(|files| {
+    let result = collect_lint_files(&files, &workspace_members).map(|files| {
       if let Some(paths) = changed {
         files
           .iter()
@@ -105,12 +125,13 @@ pub async fn lint(
     let incremental_cache = Arc::new(IncrementalCache::new(
       caches.lint_incremental_cache_db(),
       // use a hash of the rule names in order to bust the cache
-      &{
-        // ensure this is stable by sorting it
-        let mut names = lint_rules.iter().map(|r| r.code()).collect::<Vec<_>>();
-        names.sort_unstable();
-        names
-      },
+      &(
+        sorted_rule_codes(&lint_rules),
+        workspace_members
+          .iter()
+          .map(|member| sorted_rule_codes(&member.options))
+          .collect::<Vec<_>>(),
+      ),
       &paths,
     ));
     let target_files_len = paths.len();
@@ -120,6 +141,7 @@ pub async fn lint(
     run_parallelized(paths, {
       let has_error = has_error.clone();
       let lint_rules = lint_rules.clone();
+      let workspace_members = workspace_members.clone();
       let reporter_lock = reporter_lock.clone();
       let incremental_cache = incremental_cache.clone();
       move |file_path| {
@@ -130,6 +152,12 @@ pub async fn lint(
           return Ok(());
         }
 
+        let lint_rules = workspace_options_for_path(
+          &workspace_members,
+          &lint_rules,
+          &file_path,
+        )
+        .clone();
         let r = lint_file(&file_path, file_text, lint_rules);
         if let Ok((file_diagnostics, file_text)) = &r {
           if file_diagnostics.is_empty() {
@@ -163,10 +191,7 @@ pub async fn lint(
     file_watcher::watch_func(
       resolver,
       operation,
-      file_watcher::PrintConfig {
-        job_name: "Lint".to_string(),
-        clear_screen: !cli_options.no_clear_screen(),
-      },
+      file_watcher::PrintConfig::new("Lint", cli_options)?,
     )
     .await?;
   } else {
@@ -181,13 +206,14 @@ pub async fn lint(
       );
       reporter_lock.lock().unwrap().close(1);
     } else {
-      let target_files = collect_lint_files(&files).and_then(|files| {
-        if files.is_empty() {
-          Err(generic_error("No target files found."))
-        } else {
-          Ok(files)
-        }
-      })?;
+      let target_files = collect_lint_files(&files, &workspace_members)
+        .and_then(|files| {
+          if files.is_empty() {
+            Err(generic_error("No target files found."))
+          } else {
+            Ok(files)
+          }
+        })?;
       debug!("Found {} files", target_files.len());
       operation(target_files).await?;
     };
@@ -200,12 +226,24 @@ pub async fn lint(
   Ok(())
 }
 
-fn collect_lint_files(files: &FilesConfig) -> Result<Vec<PathBuf>, AnyError> {
-  FileCollector::new(is_supported_ext)
+fn collect_lint_files(
+  files: &FilesConfig,
+  workspace_members: &[WorkspaceMemberOptions<Vec<&'static dyn LintRule>>],
+) -> Result<Vec<PathBuf>, AnyError> {
+  let mut paths = FileCollector::new(is_supported_ext)
     .ignore_git_folder()
     .ignore_node_modules()
     .add_ignore_paths(&files.exclude)
-    .collect_files(&files.include)
+    .collect_files(&files.include)?;
+  paths.retain(|path| workspace_member_matches_path(workspace_members, path));
+  Ok(paths)
+}
+
+/// Returns the codes of the rules, sorted to be stable.
+fn sorted_rule_codes(rules: &[&'static dyn LintRule]) -> Vec<&'static str> {
+  let mut codes = rules.iter().map(|r| r.code()).collect::<Vec<_>>();
+  codes.sort_unstable();
+  codes
 }
 
 pub fn print_rules_list(json: bool) {
@@ -0,0 +1,619 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fmt;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::resolve_url_or_path;
+use deno_core::ModuleSpecifier;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_npm::registry::NpmRegistryApi;
+use deno_npm::NpmPackageId;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_semver::npm::NpmPackageReq;
+
+use crate::args::lockfile_remote_entries;
+use crate::args::set_lockfile_remote_entries;
+use crate::args::Flags;
+use crate::args::LockFlags;
+use crate::args::Lockfile;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::graph_util::graph_lock_or_exit;
+use crate::graph_util::graph_valid_with_cli_options;
+use crate::util::checksum;
+
+/// A dependency to resolve again with `deno lock --update`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LockedDependency {
+  /// The name of an npm package.
+  Npm(String),
+  /// The prefix of remote modules or `jsr:` specifiers.
+  Remote(String),
+}
+
+impl LockedDependency {
+  fn parse(text: &str) -> Result<Self, AnyError> {
+    if text.starts_with("http://")
+      || text.starts_with("https://")
+      || text.starts_with("jsr:")
+    {
+      return Ok(Self::Remote(text.to_string()));
+    }
+    let req_text = text.strip_prefix("npm:").unwrap_or(text);
+    let req = NpmPackageReq::from_str(req_text)
+      .with_context(|| format!("Invalid dependency \"{text}\""))?;
+    Ok(Self::Npm(req.name))
+  }
+
+  /// The entry of the `--reload` blocklist that fetches the dependency again.
+  fn reload_specifier(&self) -> String {
+    match self {
+      Self::Npm(name) => format!("npm:{name}"),
+      Self::Remote(prefix) => prefix.clone(),
+    }
+  }
+}
+
+impl fmt::Display for LockedDependency {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Npm(name) => write!(f, "npm:{name}"),
+      Self::Remote(prefix) => write!(f, "{prefix}"),
+    }
+  }
+}
+
+pub async fn lock(
+  mut flags: Flags,
+  lock_flags: LockFlags,
+) -> Result<(), AnyError> {
+  let dependencies = lock_flags
+    .update
+    .iter()
+    .map(|text| LockedDependency::parse(text))
+    .collect::<Result<Vec<_>, _>>()?;
+  if !flags.reload {
+    // fetch the latest versions of the updated dependencies
+    flags
+      .cache_blocklist
+      .extend(dependencies.iter().map(|d| d.reload_specifier()));
+  }
+  let factory = CliFactory::from_flags(flags).await?;
+  let lockfile = match factory.cli_options().maybe_lockfile() {
+    Some(lockfile) => lockfile,
+    None => bail!(
+      "No lock file found. Specify one with --lock or use a configuration file."
+    ),
+  };
+
+  if lock_flags.verify {
+    verify(&factory, &lockfile).await
+  } else if lock_flags.prune {
+    prune(&factory, &lockfile, &lock_flags.files).await
+  } else {
+    update(&factory, &lockfile, &dependencies, &lock_flags.files).await
+  }
+}
+
+/// Checks that the remote modules and npm packages of the lock file can
+/// still be loaded and match their hashes.
+async fn verify(
+  factory: &CliFactory,
+  lockfile: &Mutex<Lockfile>,
+) -> Result<(), AnyError> {
+  let (filename, remote, npm_specifiers, npm_packages) = {
+    let lockfile = lockfile.lock();
+    (
+      lockfile.filename.clone(),
+      lockfile_remote_entries(&lockfile)
+        .into_iter()
+        .collect::<Vec<_>>(),
+      lockfile
+        .content
+        .npm
+        .specifiers
+        .iter()
+        .map(|(req, id)| (req.clone(), id.clone()))
+        .collect::<Vec<_>>(),
+      lockfile
+        .content
+        .npm
+        .packages
+        .iter()
+        .map(|(id, package)| (id.clone(), package.integrity.clone()))
+        .collect::<BTreeMap<_, _>>(),
+    )
+  };
+  let mut errors = Vec::new();
+
+  let file_fetcher = factory.file_fetcher()?;
+  let mut module_count = 0;
+  for (key, value) in &remote {
+    if !is_remote_key(key) {
+      // a resolution (ex. of a jsr: specifier) whose module is locked too
+      if ModuleSpecifier::parse(value).is_err() {
+        errors
+          .push(format!("\"{key}\" resolves to an invalid url \"{value}\""));
+      }
+      continue;
+    }
+    module_count += 1;
+    let specifier = match ModuleSpecifier::parse(key) {
+      Ok(specifier) => specifier,
+      Err(err) => {
+        errors.push(format!("\"{key}\" is not a valid url: {err}"));
+        continue;
+      }
+    };
+    match file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await
+    {
+      Ok(file) if file.specifier != specifier => errors.push(format!(
+        "\"{}\" now redirects to \"{}\"",
+        specifier, file.specifier
+      )),
+      Ok(file) => {
+        if checksum::gen(&[file.source.as_bytes()]) != *value {
+          errors
+            .push(format!("\"{specifier}\" does not match its hash anymore"));
+        }
+      }
+      Err(err) => {
+        errors.push(format!("\"{specifier}\" could not be loaded: {err:#}"))
+      }
+    }
+  }
+
+  let api = factory.npm_api()?;
+  for (id_text, integrity) in &npm_packages {
+    let id = match NpmPackageId::from_serialized(id_text) {
+      Ok(id) => id,
+      Err(err) => {
+        errors.push(format!("npm package \"{id_text}\" is invalid: {err:#}"));
+        continue;
+      }
+    };
+    let package_info = match api.package_info(&id.nv.name).await {
+      Ok(package_info) => package_info,
+      Err(err) => {
+        errors.push(format!(
+          "npm package \"{}\" could not be loaded: {:#}",
+          id.nv,
+          AnyError::from(err)
+        ));
+        continue;
+      }
+    };
+    match package_info.version_info(&id.nv) {
+      Ok(version_info) => {
+        if version_info.dist.integrity().to_string() != *integrity {
+          errors.push(format!(
+            "npm package \"{}\" does not match its integrity anymore",
+            id.nv
+          ));
+        }
+      }
+      Err(_) => errors.push(format!(
+        "npm package \"{}\" is not in the registry anymore",
+        id.nv
+      )),
+    }
+  }
+  for (req_text, id_text) in &npm_specifiers {
+    if !npm_packages.contains_key(id_text) {
+      errors.push(format!(
+        "npm specifier \"{req_text}\" resolves to \"{id_text}\", which is missing"
+      ));
+      continue;
+    }
+    let (req, id) = match (
+      NpmPackageReq::from_str(req_text),
+      NpmPackageId::from_serialized(id_text),
+    ) {
+      (Ok(req), Ok(id)) => (req, id),
+      // the invalid package ids were reported above
+      (Err(err), _) => {
+        errors
+          .push(format!("npm specifier \"{req_text}\" is invalid: {err:#}"));
+        continue;
+      }
+      (_, Err(_)) => continue,
+    };
+    let matches = req.name == id.nv.name
+      && req
+        .version_req
+        .as_ref()
+        .map(|version_req| version_req.matches(&id.nv.version))
+        .unwrap_or(true);
+    if !matches {
+      errors.push(format!(
+        "npm specifier \"{}\" resolves to \"{}\", which doesn't satisfy it",
+        req_text, id.nv
+      ));
+    }
+  }
+
+  if !errors.is_empty() {
+    bail!(
+      "Found {} problem{} in the lock file.\n  Lock file: {}\n\n  {}",
+      errors.len(),
+      if errors.len() == 1 { "" } else { "s" },
+      filename.display(),
+      errors.join("\n  "),
+    );
+  }
+  log::info!(
+    "{} {} remote module{} and {} npm package{} in {}",
+    colors::green("Verified"),
+    module_count,
+    if module_count == 1 { "" } else { "s" },
+    npm_packages.len(),
+    if npm_packages.len() == 1 { "" } else { "s" },
+    filename.display(),
+  );
+  Ok(())
+}
+
+/// Removes the entries of the lock file that the entrypoints don't depend on.
+async fn prune(
+  factory: &CliFactory,
+  lockfile: &Mutex<Lockfile>,
+  files: &[String],
+) -> Result<(), AnyError> {
+  let graph = create_locked_graph(factory, files).await?;
+  let package_json_reqs = factory
+    .cli_options()
+    .maybe_package_json_deps()
+    .map(|deps| {
+      deps
+        .into_values()
+        .filter_map(|req| req.ok())
+        .map(|req| req.to_string())
+        .collect::<HashSet<_>>()
+    })
+    .unwrap_or_default();
+
+  let mut lockfile = lockfile.lock();
+  let removed_count = prune_lockfile(&mut lockfile, &graph, &package_json_reqs);
+  if removed_count == 0 {
+    log::info!("The lock file has no unused entries.");
+    return Ok(());
+  }
+  lockfile.has_content_changed = true;
+  lockfile.write()?;
+  log::info!(
+    "{} {} unused entr{} from {}",
+    colors::green("Removed"),
+    removed_count,
+    if removed_count == 1 { "y" } else { "ies" },
+    lockfile.filename.display(),
+  );
+  Ok(())
+}
+
+/// Removes the entries that aren't reachable from the modules of the graph or
+/// the package.json dependencies, returning how many were removed.
+fn prune_lockfile(
+  lockfile: &mut Lockfile,
+  graph: &ModuleGraph,
+  package_json_reqs: &HashSet<String>,
+) -> usize {
+  let mut remote = lockfile_remote_entries(lockfile);
+  let content = &mut lockfile.content;
+  let entry_count =
+    remote.len() + content.npm.specifiers.len() + content.npm.packages.len();
+
+  let specifiers = graph
+    .modules()
+    .map(|module| module.specifier().as_str())
+    .chain(
+      graph
+        .redirects
+        .iter()
+        .flat_map(|(from, to)| [from.as_str(), to.as_str()]),
+    )
+    .collect::<HashSet<_>>();
+  remote.retain(|key, value| {
+    specifiers.contains(key.as_str())
+      || (!is_remote_key(key) && specifiers.contains(value.as_str()))
+  });
+
+  let npm_nvs = graph
+    .modules()
+    .filter_map(|module| match module {
+      Module::Npm(module) => Some(&module.nv_reference.nv),
+      _ => None,
+    })
+    .collect::<HashSet<_>>();
+  content.npm.specifiers.retain(|req, id| {
+    if package_json_reqs.contains(req) {
+      return true;
+    }
+    if graph.has_node_specifier && req.starts_with("@types/node@") {
+      // injected for type checking node: specifiers
+      return true;
+    }
+    match NpmPackageId::from_serialized(id) {
+      Ok(id) => npm_nvs.contains(&id.nv),
+      Err(_) => false,
+    }
+  });
+  let mut pending = content.npm.specifiers.values().collect::<Vec<_>>();
+  let mut reachable = HashSet::new();
+  while let Some(id) = pending.pop() {
+    if !reachable.insert(id.clone()) {
+      continue;
+    }
+    if let Some(package) = content.npm.packages.get(id) {
+      pending.extend(package.dependencies.values());
+    }
+  }
+  content.npm.packages.retain(|id, _| reachable.contains(id));
+
+  let remaining_count =
+    remote.len() + content.npm.specifiers.len() + content.npm.packages.len();
+  set_lockfile_remote_entries(lockfile, remote);
+  entry_count - remaining_count
+}
+
+/// Resolves the dependencies again, ignoring their locked resolutions.
+async fn update(
+  factory: &CliFactory,
+  lockfile: &Mutex<Lockfile>,
+  dependencies: &[LockedDependency],
+  files: &[String],
+) -> Result<(), AnyError> {
+  let previous_npm_specifiers = {
+    let mut lockfile = lockfile.lock();
+    let previous_npm_specifiers = lockfile.content.npm.specifiers.clone();
+    for dependency in dependencies {
+      if !unlock_dependency(&mut lockfile, dependency) {
+        bail!(
+          "\"{}\" is not in the lock file.\n  Lock file: {}",
+          dependency,
+          lockfile.filename.display()
+        );
+      }
+    }
+    previous_npm_specifiers
+  };
+
+  // the dependencies are resolved while building the graph
+  let graph = create_locked_graph(factory, files).await?;
+  let mut lockfile = lockfile.lock();
+  lockfile.has_content_changed = true;
+  lockfile.write()?;
+
+  for dependency in dependencies {
+    match dependency {
+      LockedDependency::Npm(name) => {
+        for (req, id) in &previous_npm_specifiers {
+          if !is_npm_req_of(req, name) {
+            continue;
+          }
+          let version = |id: &str| {
+            NpmPackageId::from_serialized(id)
+              .map(|id| id.nv.version.to_string())
+              .unwrap_or_else(|_| id.to_string())
+          };
+          match lockfile.content.npm.specifiers.get(req) {
+            Some(new_id) if new_id != id => log::info!(
+              "{} {} from {} to {}",
+              colors::green("Updated"),
+              req,
+              version(id),
+              version(new_id),
+            ),
+            Some(_) => log::info!("{} is up to date ({})", req, version(id)),
+            None => log::info!(
+              "{} {} as the entrypoints don't use it",
+              colors::green("Removed"),
+              req
+            ),
+          }
+        }
+      }
+      LockedDependency::Remote(prefix) => {
+        let count = graph
+          .modules()
+          .filter(|module| module.specifier().as_str().starts_with(prefix))
+          .count();
+        log::info!(
+          "{} {} module{} of {}",
+          colors::green("Updated"),
+          count,
+          if count == 1 { "" } else { "s" },
+          prefix,
+        );
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Removes the locked resolution of the dependency, returning if it was
+/// in the lock file.
+fn unlock_dependency(
+  lockfile: &mut Lockfile,
+  dependency: &LockedDependency,
+) -> bool {
+  match dependency {
+    LockedDependency::Remote(prefix) => {
+      let mut remote = lockfile_remote_entries(lockfile);
+      let len = remote.len();
+      remote.retain(|key, _| !key.starts_with(prefix.as_str()));
+      let changed = len != remote.len();
+      set_lockfile_remote_entries(lockfile, remote);
+      changed
+    }
+    LockedDependency::Npm(name) => {
+      let content = &mut lockfile.content;
+      let len = content.npm.specifiers.len();
+      content
+        .npm
+        .specifiers
+        .retain(|req, _| !is_npm_req_of(req, name));
+      // remove the versions other packages don't depend on, so that they
+      // aren't reused when resolving the package again
+      let dependency_ids = content
+        .npm
+        .packages
+        .values()
+        .flat_map(|package| package.dependencies.values().cloned())
+        .collect::<HashSet<_>>();
+      content.npm.packages.retain(|id, _| {
+        dependency_ids.contains(id)
+          || NpmPackageId::from_serialized(id)
+            .map(|id| id.nv.name != *name)
+            .unwrap_or(true)
+      });
+      len != content.npm.specifiers.len()
+    }
+  }
+}
+
+/// Builds the graph of the entrypoints, which adds the resolutions that
+/// aren't locked yet to the lock file.
+async fn create_locked_graph(
+  factory: &CliFactory,
+  files: &[String],
+) -> Result<ModuleGraph, AnyError> {
+  let cli_options = factory.cli_options();
+  let roots = files
+    .iter()
+    .map(|file| resolve_url_or_path(file, cli_options.initial_cwd()))
+    .collect::<Result<Vec<_>, _>>()?;
+  let graph = factory
+    .module_graph_builder()
+    .await?
+    .create_graph(roots)
+    .await?;
+  graph_valid_with_cli_options(&graph, &graph.roots, cli_options)?;
+  if let Some(lockfile) = cli_options.maybe_lockfile() {
+    graph_lock_or_exit(&graph, &mut lockfile.lock());
+  }
+  Ok(graph)
+}
+
+/// Whether the key of a remote entry is the url of a module, rather than a
+/// resolution like the one of a `jsr:` specifier or an override.
+fn is_remote_key(key: &str) -> bool {
+  key.starts_with("http://") || key.starts_with("https://")
+}
+
+fn is_npm_req_of(req: &str, name: &str) -> bool {
+  NpmPackageReq::from_str(req)
+    .map(|req| req.name == name)
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn lockfile(json: &str) -> Lockfile {
+    let temp_dir = test_util::TempDir::new();
+    let path = temp_dir.path().join("deno.lock");
+    std::fs::write(&path, json).unwrap();
+    Lockfile::new(path, false).unwrap()
+  }
+
+  #[test]
+  fn parse_dependency() {
+    assert_eq!(
+      LockedDependency::parse("chalk").unwrap(),
+      LockedDependency::Npm("chalk".to_string())
+    );
+    assert_eq!(
+      LockedDependency::parse("npm:@scope/pkg@^1").unwrap(),
+      LockedDependency::Npm("@scope/pkg".to_string())
+    );
+    assert_eq!(
+      LockedDependency::parse("jsr:@std/fs").unwrap(),
+      LockedDependency::Remote("jsr:@std/fs".to_string())
+    );
+    assert_eq!(
+      LockedDependency::parse("https://deno.land/std@0.190.0/").unwrap(),
+      LockedDependency::Remote("https://deno.land/std@0.190.0/".to_string())
+    );
+  }
+
+  #[test]
+  fn unlock_npm_dependency() {
+    let mut lockfile = lockfile(
+      r#"{
+  "version": "2",
+  "remote": {},
+  "npm": {
+    "specifiers": {
+      "chalk@5": "chalk@5.0.0",
+      "debug@4": "debug@4.3.4"
+    },
+    "packages": {
+      "chalk@5.0.0": { "integrity": "sha512-a", "dependencies": {} },
+      "debug@4.3.4": {
+        "integrity": "sha512-b",
+        "dependencies": { "ms": "ms@2.1.2" }
+      },
+      "ms@2.1.2": { "integrity": "sha512-c", "dependencies": {} }
+    }
+  }
+}"#,
+    );
+    assert!(unlock_dependency(
+      &mut lockfile,
+      &LockedDependency::Npm("chalk".to_string())
+    ));
+    assert_eq!(
+      lockfile.content.npm.specifiers.keys().collect::<Vec<_>>(),
+      vec!["debug@4"]
+    );
+    assert_eq!(
+      lockfile.content.npm.packages.keys().collect::<Vec<_>>(),
+      vec!["debug@4.3.4", "ms@2.1.2"]
+    );
+
+    // still a dependency of debug
+    assert!(!unlock_dependency(
+      &mut lockfile,
+      &LockedDependency::Npm("ms".to_string())
+    ));
+    assert!(lockfile.content.npm.packages.contains_key("ms@2.1.2"));
+  }
+
+  #[test]
+  fn unlock_remote_dependency() {
+    let mut lockfile = lockfile(
+      r#"{
+  "version": "2",
+  "remote": {
+    "https://deno.land/std@0.190.0/fs/mod.ts": "a",
+    "https://deno.land/std@0.190.0/path/mod.ts": "b",
+    "https://deno.land/x/oak@v12.5.0/mod.ts": "c"
+  },
+  "npm": { "specifiers": {}, "packages": {} }
+}"#,
+    );
+    assert!(unlock_dependency(
+      &mut lockfile,
+      &LockedDependency::Remote("https://deno.land/std@0.190.0/".to_string())
+    ));
+    assert_eq!(
+      lockfile_remote_entries(&lockfile)
+        .keys()
+        .collect::<Vec<_>>(),
+      vec!["https://deno.land/x/oak@v12.5.0/mod.ts"]
+    );
+    assert!(!unlock_dependency(
+      &mut lockfile,
+      &LockedDependency::Remote("https://esm.sh/".to_string())
+    ));
+  }
+}
@@ -2,7 +2,9 @@
 
 pub mod bench;
 pub mod bundle;
+pub mod cache;
 pub mod check;
+pub mod clean;
 pub mod compile;
 pub mod coverage;
 pub mod doc;
@@ -10,9 +12,13 @@ pub mod fmt;
 pub mod info;
 pub mod init;
 pub mod installer;
+pub mod jupyter;
 pub mod lint;
+pub mod lock;
+pub mod publish;
 pub mod repl;
 pub mod run;
+pub mod serve;
 pub mod task;
 pub mod test;
 pub mod upgrade;
@@ -0,0 +1,319 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::process::Command;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::url::Url;
+use deno_runtime::deno_fetch::reqwest::header::HeaderValue;
+use deno_runtime::deno_fetch::reqwest::header::AUTHORIZATION;
+use deno_runtime::deno_fetch::reqwest::header::CONTENT_TYPE;
+use deno_semver::Version;
+
+use crate::args::Flags;
+use crate::args::PublishFlags;
+use crate::auth_tokens::AuthTokens;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::util::checksum;
+use crate::util::display;
+use crate::util::fs::canonicalize_path;
+use crate::util::fs::FileCollector;
+use crate::util::path::specifier_to_file_path;
+
+mod provenance;
+mod tarball;
+
+pub async fn publish(
+  flags: Flags,
+  publish_flags: PublishFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let cli_options = factory.cli_options();
+  let config_file = match cli_options.maybe_config_file() {
+    Some(config_file) => config_file,
+    None => bail!(
+      "Couldn't find a configuration file, which is required to publish a package."
+    ),
+  };
+  let config_path = specifier_to_file_path(&config_file.specifier)?;
+  let package_dir = canonicalize_path(config_path.parent().unwrap())?;
+
+  let name = match &config_file.json.name {
+    Some(name) => name.clone(),
+    None => bail!("Missing \"name\" field in the configuration file."),
+  };
+  let (scope, package) = parse_package_name(&name)?;
+  let version = match &config_file.json.version {
+    Some(version) => version.clone(),
+    None => bail!("Missing \"version\" field in the configuration file."),
+  };
+  Version::parse_standard(&version)
+    .with_context(|| format!("Invalid version \"{version}\""))?;
+  let exports = match config_file.to_exports_config()? {
+    Some(exports) => exports,
+    None => bail!("Missing \"exports\" field in the configuration file."),
+  };
+  let mut export_paths = Vec::with_capacity(exports.len());
+  for (key, path) in &exports {
+    let export_path = package_dir.join(path.trim_start_matches("./"));
+    if !export_path.is_file() {
+      bail!(
+        "The export \"{key}\" points to '{path}', which doesn't exist in the package directory."
+      );
+    }
+    export_paths.push(export_path);
+  }
+
+  if !publish_flags.allow_dirty {
+    ensure_clean_git_tree(&package_dir)?;
+  }
+
+  // type check the exports, unless `--no-check` is passed
+  factory
+    .module_load_preparer()
+    .await?
+    .load_and_type_check_files(
+      &export_paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<Vec<_>>(),
+    )
+    .await?;
+
+  let publish_config = config_file.to_publish_config()?.unwrap_or_default();
+  let files = FileCollector::new(|_| true)
+    .ignore_git_folder()
+    .ignore_node_modules()
+    .add_ignore_paths(&publish_config.exclude)
+    .collect_files(&[package_dir.clone()])?;
+  let tarball = tarball::create_tarball(
+    &package_dir,
+    &files,
+    name.clone(),
+    version.clone(),
+    exports,
+  )?;
+
+  if publish_flags.dry_run {
+    for (path, entry) in &tarball.manifest.files {
+      log::info!(
+        "{} {}",
+        path,
+        colors::gray(display::human_size(entry.size as f64))
+      );
+    }
+    log::info!(
+      "{} {}@{} ({} files, {}), not uploading because of --dry-run",
+      colors::green("Checked"),
+      name,
+      version,
+      tarball.manifest.files.len(),
+      display::human_size(tarball.bytes.len() as f64)
+    );
+    return Ok(());
+  }
+
+  let registry_url = match publish_flags.registry {
+    Some(registry) => parse_registry_url(&registry)?,
+    None => match publish_config.registry {
+      Some(registry) => registry,
+      None => bail!(
+        "No registry to publish to, pass --registry or configure the \"registry\" of the \"publish\" configuration."
+      ),
+    },
+  };
+  let (auth_header_name, auth_header_value) = match publish_flags.token {
+    Some(token) => (AUTHORIZATION.to_string(), format!("Bearer {token}")),
+    None => {
+      let mut auth_tokens =
+        AuthTokens::new(std::env::var("DENO_AUTH_TOKENS").ok());
+      if let Some(auth_config) = cli_options.resolve_auth_config()? {
+        auth_tokens.extend(AuthTokens::from_config(&auth_config, |name| {
+          std::env::var(name).ok()
+        }));
+      }
+      match auth_tokens.get(&registry_url) {
+        Some(token) => (
+          token
+            .header_name()
+            .unwrap_or(AUTHORIZATION.as_str())
+            .to_string(),
+          token.to_string(),
+        ),
+        None => bail!(
+          "No token to publish to {registry_url}, pass --token or configure one in the \"auth\" configuration or DENO_AUTH_TOKENS."
+        ),
+      }
+    }
+  };
+
+  let manifest_json = serde_json::to_vec(&tarball.manifest)?;
+  let http_client = factory.http_client()?;
+  let provenance = if publish_flags.provenance {
+    let manifest_digest = checksum::gen(&[&manifest_json]);
+    let audience = registry_url.host_str().unwrap_or_default().to_string();
+    Some(
+      provenance::generate_provenance(
+        http_client,
+        &format!("pkg:jsr/{name}@{version}"),
+        &manifest_digest,
+        &audience,
+      )
+      .await?,
+    )
+  } else {
+    None
+  };
+
+  let publish_url = registry_url.join(&format!(
+    "api/scopes/{scope}/packages/{package}/versions/{version}"
+  ))?;
+  let body = json!({
+    "manifest": tarball.manifest,
+    "tarball": base64::encode(&tarball.bytes),
+    "provenance": provenance,
+  });
+  let request = http_client
+    .post(publish_url)?
+    .header(auth_header_name, HeaderValue::from_str(&auth_header_value)?)
+    .header(CONTENT_TYPE, "application/json")
+    .body(serde_json::to_vec(&body)?);
+  let response = request.send().await?;
+  let status = response.status();
+  if !status.is_success() {
+    let text = response.text().await.unwrap_or_default();
+    bail!(
+      "Failed to publish {}@{} to {}: {}{}",
+      name,
+      version,
+      registry_url,
+      status,
+      if text.is_empty() {
+        String::new()
+      } else {
+        format!("\n{}", text.trim())
+      }
+    );
+  }
+
+  log::info!(
+    "{} {}@{} to {}{}",
+    colors::green("Published"),
+    name,
+    version,
+    registry_url,
+    if publish_flags.provenance {
+      " with provenance"
+    } else {
+      ""
+    }
+  );
+  Ok(())
+}
+
+/// Splits a package name like `@scope/name` into its scope and name.
+fn parse_package_name(name: &str) -> Result<(&str, &str), AnyError> {
+  fn is_valid_part(part: &str) -> bool {
+    !part.is_empty()
+      && !part.starts_with('-')
+      && part
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+  }
+
+  match name
+    .strip_prefix('@')
+    .and_then(|name| name.split_once('/'))
+  {
+    Some((scope, package)) if is_valid_part(scope) && is_valid_part(package) => {
+      Ok((scope, package))
+    }
+    _ => bail!(
+      "Invalid package name \"{name}\", expected a scoped name of lowercase letters, digits and dashes like \"@scope/name\""
+    ),
+  }
+}
+
+fn parse_registry_url(registry: &str) -> Result<Url, AnyError> {
+  // ensure there is a trailing slash for the directory
+  Url::parse(&format!("{}/", registry.trim_end_matches('/')))
+    .with_context(|| format!("Invalid registry url \"{registry}\""))
+}
+
+/// Errors if the git repository of the package directory has uncommitted
+/// changes. Directories that aren't in a git repository, or systems without
+/// git, are not checked.
+fn ensure_clean_git_tree(package_dir: &Path) -> Result<(), AnyError> {
+  let output = match Command::new("git")
+    .args(["status", "--porcelain", "--", "."])
+    .current_dir(package_dir)
+    .output()
+  {
+    Ok(output) if output.status.success() => output,
+    Ok(output) => {
+      log::debug!(
+        "Skipping the uncommitted changes check: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+      );
+      return Ok(());
+    }
+    Err(err) => {
+      log::debug!("Skipping the uncommitted changes check: {:#}", err);
+      return Ok(());
+    }
+  };
+  let changes = String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| format!("  {}", line.trim()))
+    .collect::<Vec<_>>();
+  if !changes.is_empty() {
+    bail!(
+      "Aborting because of uncommitted changes, commit them or pass --allow-dirty:\n{}",
+      changes.join("\n")
+    );
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn package_name() {
+    assert_eq!(parse_package_name("@std/path").unwrap(), ("std", "path"));
+    assert_eq!(
+      parse_package_name("@my-scope/pkg-2").unwrap(),
+      ("my-scope", "pkg-2")
+    );
+    for name in [
+      "std/path",
+      "@std",
+      "@/path",
+      "@std/",
+      "@Std/path",
+      "@std/-a",
+    ] {
+      assert!(parse_package_name(name).is_err(), "{name}");
+    }
+  }
+
+  #[test]
+  fn registry_url() {
+    assert_eq!(
+      parse_registry_url("https://registry.example.com/base")
+        .unwrap()
+        .join("api/scopes")
+        .unwrap()
+        .as_str(),
+      "https://registry.example.com/base/api/scopes"
+    );
+    assert!(parse_registry_url("not a url").is_err());
+  }
+}
@@ -0,0 +1,207 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// Provenance attestations in the format of SLSA build provenance, see
+// https://slsa.dev/spec/v1.0/provenance
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::url::Url;
+
+use crate::http_util::HttpClient;
+
+const GITHUB_BUILD_TYPE: &str =
+  "https://slsa-framework.github.io/github-actions-buildtypes/workflow/v1";
+const GITHUB_HOSTED_BUILDER: &str =
+  "https://github.com/actions/runner/github-hosted";
+
+/// The provenance of a package version: an in-toto statement describing the
+/// workflow run that published it and the OIDC token of that run, which the
+/// registry verifies the statement against.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provenance {
+  pub statement: Value,
+  pub oidc_token: String,
+}
+
+/// Creates the provenance of the package version whose manifest has the
+/// given sha256 digest. This is only supported on GitHub Actions, where the
+/// workflow needs the `id-token: write` permission.
+pub async fn generate_provenance(
+  http_client: &HttpClient,
+  subject_name: &str,
+  manifest_digest: &str,
+  audience: &str,
+) -> Result<Provenance, AnyError> {
+  let get_env = |name: &str| std::env::var(name).ok();
+  if get_env("GITHUB_ACTIONS").as_deref() != Some("true") {
+    bail!("Provenance can only be generated on GitHub Actions");
+  }
+  let statement = create_statement(subject_name, manifest_digest, get_env)?;
+  let oidc_token = fetch_oidc_token(http_client, audience).await?;
+  Ok(Provenance {
+    statement,
+    oidc_token,
+  })
+}
+
+fn create_statement(
+  subject_name: &str,
+  manifest_digest: &str,
+  get_env: impl Fn(&str) -> Option<String>,
+) -> Result<Value, AnyError> {
+  let env = |name: &str| {
+    get_env(name).with_context(|| {
+      format!("Missing the {name} environment variable of GitHub Actions")
+    })
+  };
+  let server_url = env("GITHUB_SERVER_URL")?;
+  let repository = env("GITHUB_REPOSITORY")?;
+  let git_ref = env("GITHUB_REF")?;
+  // eg. `denoland/deno/.github/workflows/publish.yml@refs/heads/main`
+  let workflow_ref = env("GITHUB_WORKFLOW_REF")?;
+  let workflow_path = workflow_ref
+    .strip_prefix(&format!("{repository}/"))
+    .and_then(|path| path.split('@').next())
+    .unwrap_or(&workflow_ref)
+    .to_string();
+  let repository_url = format!("{server_url}/{repository}");
+
+  Ok(json!({
+    "_type": "https://in-toto.io/Statement/v1",
+    "subject": [{
+      "name": subject_name,
+      "digest": { "sha256": manifest_digest },
+    }],
+    "predicateType": "https://slsa.dev/provenance/v1",
+    "predicate": {
+      "buildDefinition": {
+        "buildType": GITHUB_BUILD_TYPE,
+        "externalParameters": {
+          "workflow": {
+            "ref": git_ref,
+            "repository": repository_url,
+            "path": workflow_path,
+          },
+        },
+        "internalParameters": {
+          "github": {
+            "event_name": env("GITHUB_EVENT_NAME")?,
+            "repository_id": env("GITHUB_REPOSITORY_ID")?,
+            "repository_owner_id": env("GITHUB_REPOSITORY_OWNER_ID")?,
+          },
+        },
+        "resolvedDependencies": [{
+          "uri": format!("git+{repository_url}@{git_ref}"),
+          "digest": { "gitCommit": env("GITHUB_SHA")? },
+        }],
+      },
+      "runDetails": {
+        "builder": { "id": GITHUB_HOSTED_BUILDER },
+        "metadata": {
+          "invocationId": format!(
+            "{repository_url}/actions/runs/{}/attempts/{}",
+            env("GITHUB_RUN_ID")?,
+            env("GITHUB_RUN_ATTEMPT")?,
+          ),
+        },
+      },
+    },
+  }))
+}
+
+/// Requests an OIDC token for the registry from GitHub Actions, see
+/// https://docs.github.com/en/actions/deployment/security-hardening-your-deployments/about-security-hardening-with-openid-connect
+async fn fetch_oidc_token(
+  http_client: &HttpClient,
+  audience: &str,
+) -> Result<String, AnyError> {
+  #[derive(Deserialize)]
+  struct OidcTokenResponse {
+    value: String,
+  }
+
+  let (request_url, request_token) = match (
+    std::env::var("ACTIONS_ID_TOKEN_REQUEST_URL"),
+    std::env::var("ACTIONS_ID_TOKEN_REQUEST_TOKEN"),
+  ) {
+    (Ok(request_url), Ok(request_token)) => (request_url, request_token),
+    _ => bail!(
+      "Missing the OIDC token request of GitHub Actions, does the workflow have the `id-token: write` permission?"
+    ),
+  };
+  let mut url = Url::parse(&request_url)
+    .context("Invalid ACTIONS_ID_TOKEN_REQUEST_URL environment variable")?;
+  url.query_pairs_mut().append_pair("audience", audience);
+  let response = http_client
+    .get_no_redirect(url)?
+    .bearer_auth(request_token)
+    .send()
+    .await?;
+  if !response.status().is_success() {
+    bail!(
+      "Failed to request the OIDC token of GitHub Actions: {}",
+      response.status()
+    );
+  }
+  let bytes = response.bytes().await?;
+  let response: OidcTokenResponse = serde_json::from_slice(&bytes)
+    .context("Invalid OIDC token response of GitHub Actions")?;
+  Ok(response.value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashMap;
+
+  #[test]
+  fn statement_from_github_env() {
+    let env = HashMap::from([
+      ("GITHUB_SERVER_URL", "https://github.com"),
+      ("GITHUB_REPOSITORY", "owner/repo"),
+      ("GITHUB_REF", "refs/tags/v1.0.0"),
+      (
+        "GITHUB_WORKFLOW_REF",
+        "owner/repo/.github/workflows/publish.yml@refs/tags/v1.0.0",
+      ),
+      ("GITHUB_EVENT_NAME", "push"),
+      ("GITHUB_REPOSITORY_ID", "1"),
+      ("GITHUB_REPOSITORY_OWNER_ID", "2"),
+      ("GITHUB_SHA", "abc"),
+      ("GITHUB_RUN_ID", "3"),
+      ("GITHUB_RUN_ATTEMPT", "1"),
+    ]);
+    let statement =
+      create_statement("pkg:jsr/@scope/pkg@1.0.0", "digest", |name| {
+        env.get(name).map(|value| value.to_string())
+      })
+      .unwrap();
+    assert_eq!(statement["subject"][0]["digest"]["sha256"], "digest");
+    let definition = &statement["predicate"]["buildDefinition"];
+    assert_eq!(
+      definition["externalParameters"]["workflow"]["path"],
+      ".github/workflows/publish.yml"
+    );
+    assert_eq!(
+      definition["resolvedDependencies"][0]["uri"],
+      "git+https://github.com/owner/repo@refs/tags/v1.0.0"
+    );
+    assert_eq!(
+      statement["predicate"]["runDetails"]["metadata"]["invocationId"],
+      "https://github.com/owner/repo/actions/runs/3/attempts/1"
+    );
+
+    let err = create_statement("pkg", "digest", |_| None).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Missing the GITHUB_SERVER_URL environment variable of GitHub Actions"
+    );
+  }
+}
@@ -0,0 +1,174 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Serialize;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use indexmap::IndexMap;
+use tar::Header;
+
+use crate::util::checksum;
+
+/// The files of a package, keyed by their path relative to the package
+/// directory, eg. `/mod.ts`.
+#[derive(Debug, Serialize)]
+pub struct PublishManifest {
+  pub name: String,
+  pub version: String,
+  pub exports: IndexMap<String, String>,
+  pub files: BTreeMap<String, PublishManifestEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublishManifestEntry {
+  pub size: usize,
+  pub checksum: String,
+}
+
+pub struct PublishTarball {
+  pub bytes: Vec<u8>,
+  pub manifest: PublishManifest,
+}
+
+/// Creates the gzipped tarball of the files. The entries are added in a
+/// stable order and without timestamps, so that the same files always result
+/// in the same tarball.
+pub fn create_tarball(
+  package_dir: &Path,
+  files: &[PathBuf],
+  name: String,
+  version: String,
+  exports: IndexMap<String, String>,
+) -> Result<PublishTarball, AnyError> {
+  let mut sorted_files = files
+    .iter()
+    .map(|file| {
+      let relative_path = file.strip_prefix(package_dir).map_err(|_| {
+        anyhow!(
+          "File '{}' is not in the package directory '{}'",
+          file.display(),
+          package_dir.display()
+        )
+      })?;
+      let path = relative_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+      Ok((format!("/{path}"), file))
+    })
+    .collect::<Result<Vec<_>, AnyError>>()?;
+  sorted_files.sort();
+
+  let mut files = BTreeMap::new();
+  let mut builder =
+    tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+  for (path, file) in sorted_files {
+    let data = std::fs::read(file)
+      .with_context(|| format!("Failed to read '{}'", file.display()))?;
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    header.set_cksum();
+    builder
+      .append_data(&mut header, path.trim_start_matches('/'), data.as_slice())
+      .with_context(|| format!("Failed to add '{path}' to the tarball"))?;
+    files.insert(
+      path,
+      PublishManifestEntry {
+        size: data.len(),
+        checksum: format!("sha256-{}", checksum::gen(&[&data])),
+      },
+    );
+  }
+  let mut encoder = builder.into_inner()?;
+  encoder.flush()?;
+  let bytes = encoder.finish()?;
+
+  Ok(PublishTarball {
+    bytes,
+    manifest: PublishManifest {
+      name,
+      version,
+      exports,
+      files,
+    },
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use flate2::read::GzDecoder;
+  use std::io::Read;
+  use test_util::TempDir;
+
+  #[test]
+  fn tarball_and_manifest() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("lib");
+    temp_dir.write("mod.ts", "export * from './lib/a.ts';");
+    temp_dir.write("lib/a.ts", "export const a = 1;");
+    let package_dir = temp_dir.path().to_path_buf();
+    let files = vec![package_dir.join("mod.ts"), package_dir.join("lib/a.ts")];
+    let exports = IndexMap::from([(".".to_string(), "./mod.ts".to_string())]);
+
+    let tarball = create_tarball(
+      &package_dir,
+      &files,
+      "@scope/pkg".to_string(),
+      "1.0.0".to_string(),
+      exports.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+      tarball.manifest.files.keys().collect::<Vec<_>>(),
+      vec!["/lib/a.ts", "/mod.ts"]
+    );
+    let entry = &tarball.manifest.files["/lib/a.ts"];
+    assert_eq!(entry.size, 19);
+    assert_eq!(
+      entry.checksum,
+      format!("sha256-{}", checksum::gen(&[b"export const a = 1;"]))
+    );
+
+    let mut archive = tar::Archive::new(GzDecoder::new(&tarball.bytes[..]));
+    let mut entries = Vec::new();
+    for entry in archive.entries().unwrap() {
+      let mut entry = entry.unwrap();
+      let path = entry.path().unwrap().to_string_lossy().to_string();
+      let mut text = String::new();
+      entry.read_to_string(&mut text).unwrap();
+      entries.push((path, text));
+    }
+    assert_eq!(
+      entries,
+      vec![
+        ("lib/a.ts".to_string(), "export const a = 1;".to_string()),
+        (
+          "mod.ts".to_string(),
+          "export * from './lib/a.ts';".to_string()
+        ),
+      ]
+    );
+
+    // the tarball is reproducible
+    let other = create_tarball(
+      &package_dir,
+      &files,
+      "@scope/pkg".to_string(),
+      "1.0.0".to_string(),
+      exports,
+    )
+    .unwrap();
+    assert_eq!(tarball.bytes, other.bytes);
+  }
+}
@@ -17,6 +17,7 @@ use deno_ast::swc::visit::VisitWith;
 use deno_ast::DiagnosticsError;
 use deno_ast::ImportsNotUsedAsValues;
 use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::anyhow;
 use deno_core::error::AnyError;
 use deno_core::futures::channel::mpsc::UnboundedReceiver;
 use deno_core::futures::FutureExt;
@@ -225,6 +226,45 @@ impl ReplSession {
       .await
   }
 
+  /// Calls the given function with the object holding the REPL internals and
+  /// returns its result by value, awaiting it if it's a promise.
+  pub async fn call_function_with_internals(
+    &mut self,
+    function_declaration: &str,
+  ) -> Result<Value, AnyError> {
+    let response = self
+      .post_message_with_event_loop(
+        "Runtime.callFunctionOn",
+        Some(cdp::CallFunctionOnArgs {
+          function_declaration: format!(
+            r#"function () {{ return ({function_declaration})({}); }}"#,
+            *REPL_INTERNALS_NAME
+          ),
+          object_id: None,
+          arguments: None,
+          silent: None,
+          return_by_value: Some(true),
+          generate_preview: None,
+          user_gesture: None,
+          await_promise: Some(true),
+          execution_context_id: Some(self.context_id),
+          object_group: None,
+          throw_on_side_effect: None,
+        }),
+      )
+      .await?;
+    let response: cdp::CallFunctionOnResponse =
+      serde_json::from_value(response)?;
+    if let Some(exception_details) = response.exception_details {
+      let description = exception_details
+        .exception
+        .and_then(|exception| exception.description)
+        .unwrap_or_else(|| "undefined".to_string());
+      return Err(anyhow!("{} {}", exception_details.text, description));
+    }
+    Ok(response.result.value.unwrap_or(Value::Null))
+  }
+
   pub async fn run_event_loop(&mut self) -> Result<(), AnyError> {
     self.worker.run_event_loop(true).await
   }
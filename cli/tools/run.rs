@@ -1,6 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use std::io::Read;
+use std::path::PathBuf;
 
 use deno_ast::MediaType;
 use deno_ast::ModuleSpecifier;
@@ -35,20 +36,22 @@ To grant permissions, set them before the script argument. For example:
   // map specified and bare specifier is used on the command line
   let factory = CliFactory::from_flags(flags).await?;
   let deno_dir = factory.deno_dir()?;
-  let http_client = factory.http_client();
+  let http_client = factory.http_client()?;
   let cli_options = factory.cli_options();
 
   // Run a background task that checks for available upgrades. If an earlier
   // run of this background task found a new version of Deno.
-  super::upgrade::check_for_upgrades(
-    http_client.clone(),
-    deno_dir.upgrade_check_file_path(),
-  );
+  if !cli_options.is_offline() {
+    super::upgrade::check_for_upgrades(
+      http_client.clone(),
+      deno_dir.upgrade_check_file_path(),
+    );
+  }
 
   let main_module = cli_options.resolve_main_module()?;
 
   let permissions = PermissionsContainer::new(Permissions::from_options(
-    &cli_options.permissions_options(),
+    &cli_options.permissions_options()?,
   )?);
   let worker_factory = factory.create_cli_main_worker_factory().await?;
   let mut worker = worker_factory
@@ -67,7 +70,7 @@ pub async fn run_from_stdin(flags: Flags) -> Result<i32, AnyError> {
   let worker_factory = factory.create_cli_main_worker_factory().await?;
 
   let permissions = PermissionsContainer::new(Permissions::from_options(
-    &cli_options.permissions_options(),
+    &cli_options.permissions_options()?,
   )?);
   let mut source = Vec::new();
   std::io::stdin().read_to_end(&mut source)?;
@@ -93,7 +96,7 @@ pub async fn run_from_stdin(flags: Flags) -> Result<i32, AnyError> {
 
 // TODO(bartlomieju): this function is not handling `exit_code` set by the runtime
 // code properly.
-async fn run_with_watch(flags: Flags) -> Result<i32, AnyError> {
+pub async fn run_with_watch(flags: Flags) -> Result<i32, AnyError> {
   let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
   let factory = CliFactoryBuilder::new()
     .with_watcher(sender.clone())
@@ -101,15 +104,15 @@ async fn run_with_watch(flags: Flags) -> Result<i32, AnyError> {
     .await?;
   let file_watcher = factory.file_watcher()?;
   let cli_options = factory.cli_options();
-  let clear_screen = !cli_options.no_clear_screen();
   let main_module = cli_options.resolve_main_module()?;
   let create_cli_main_worker_factory =
     factory.create_cli_main_worker_factory_func().await?;
 
-  let operation = |main_module: ModuleSpecifier| {
-    file_watcher.reset();
+  let operation = |main_module: ModuleSpecifier,
+                   changed: Option<Vec<PathBuf>>| {
+    file_watcher.invalidate(changed.as_deref());
     let permissions = PermissionsContainer::new(Permissions::from_options(
-      &cli_options.permissions_options(),
+      &cli_options.permissions_options()?,
     )?);
     let create_cli_main_worker_factory = create_cli_main_worker_factory.clone();
 
@@ -127,10 +130,7 @@ async fn run_with_watch(flags: Flags) -> Result<i32, AnyError> {
     receiver,
     operation,
     main_module,
-    util::file_watcher::PrintConfig {
-      job_name: "Process".to_string(),
-      clear_screen,
-    },
+    util::file_watcher::PrintConfig::new("Process", cli_options)?,
   )
   .await?;
 
@@ -148,21 +148,34 @@ pub async fn eval_command(
 
   let main_module = cli_options.resolve_main_module()?;
   let permissions = PermissionsContainer::new(Permissions::from_options(
-    &cli_options.permissions_options(),
+    &cli_options.permissions_options()?,
   )?);
+  let code = if eval_flags.is_stdin() {
+    let mut source = Vec::new();
+    std::io::stdin().read_to_end(&mut source)?;
+    String::from_utf8(source)?
+  } else {
+    eval_flags.code
+  };
   // Create a dummy source file.
   let source_code = if eval_flags.print {
-    format!("console.log({})", eval_flags.code)
+    format!("console.log({})", code.trim_end().trim_end_matches(';'))
   } else {
-    eval_flags.code
-  }
-  .into_bytes();
+    code
+  };
+  let media_type = match cli_options.ext_flag().as_deref() {
+    Some("ts") => MediaType::TypeScript,
+    Some("tsx") => MediaType::Tsx,
+    Some("js") => MediaType::JavaScript,
+    Some("jsx") => MediaType::Jsx,
+    _ => MediaType::Unknown,
+  };
 
   let file = File {
     local: main_module.clone().to_file_path().unwrap(),
     maybe_types: None,
-    media_type: MediaType::Unknown,
-    source: String::from_utf8(source_code)?.into(),
+    media_type,
+    source: source_code.into(),
     specifier: main_module.clone(),
     maybe_headers: None,
   };
@@ -0,0 +1,102 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::sync::Arc;
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::futures::future::join_all;
+use deno_core::task::spawn_blocking;
+use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_runtime::tokio_util::create_and_run_current_thread;
+
+use crate::args::Flags;
+use crate::args::ServeFlags;
+use crate::factory::CliFactory;
+use crate::worker::CliMainWorkerFactory;
+
+pub async fn serve(
+  flags: Flags,
+  serve_flags: ServeFlags,
+) -> Result<i32, AnyError> {
+  if flags.watch.is_some() {
+    // the server is closed on unload, so it's started again on restarts
+    return super::run::run_with_watch(flags).await;
+  }
+
+  let worker_count = serve_flags.worker_count.map(|c| c.get()).unwrap_or(1);
+  if worker_count > 1 {
+    if !cfg!(target_os = "linux") {
+      bail!("--parallel is only supported on Linux.");
+    }
+    if !flags.unstable {
+      bail!(
+        "--parallel requires the --unstable flag, since the workers share the port with Deno.listen({{ reusePort: true }})."
+      );
+    }
+  }
+
+  let factory = CliFactory::from_flags(flags).await?;
+  let cli_options = factory.cli_options();
+  let main_module = cli_options.resolve_main_module()?;
+  let permissions =
+    Permissions::from_options(&cli_options.permissions_options()?)?;
+  let worker_factory = factory.create_cli_main_worker_factory().await?;
+
+  if worker_count == 1 {
+    let mut worker = worker_factory
+      .create_main_worker(main_module, PermissionsContainer::new(permissions))
+      .await?;
+    return worker.run().await;
+  }
+
+  log::info!(
+    "{} {} server workers",
+    crate::colors::green("Starting"),
+    worker_count
+  );
+  serve_with_workers(
+    Arc::new(worker_factory),
+    main_module,
+    permissions,
+    worker_count,
+  )
+  .await
+}
+
+/// Runs the server in several workers, each on its own thread, which share
+/// the port. Each worker gets its own permissions so that they don't affect
+/// each other.
+async fn serve_with_workers(
+  worker_factory: Arc<CliMainWorkerFactory>,
+  main_module: ModuleSpecifier,
+  permissions: Permissions,
+  worker_count: usize,
+) -> Result<i32, AnyError> {
+  let join_handles = (0..worker_count).map(|_| {
+    let worker_factory = worker_factory.clone();
+    let main_module = main_module.clone();
+    let permissions = permissions.clone();
+    spawn_blocking(move || {
+      create_and_run_current_thread(async move {
+        let mut worker = worker_factory
+          .create_main_worker(
+            main_module,
+            PermissionsContainer::new(permissions),
+          )
+          .await?;
+        worker.run().await
+      })
+    })
+  });
+
+  let mut exit_code = 0;
+  for result in join_all(join_handles).await {
+    let worker_exit_code = result??;
+    if worker_exit_code != 0 {
+      exit_code = worker_exit_code;
+    }
+  }
+  Ok(exit_code)
+}
@@ -1,9 +1,13 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::workspace_member_matches_specifier;
 use crate::args::CliOptions;
 use crate::args::FilesConfig;
 use crate::args::TestOptions;
+use crate::args::TestReporterConfig;
+use crate::args::TestShard;
 use crate::args::TypeCheckMode;
+use crate::args::WorkspaceMemberOptions;
 use crate::colors;
 use crate::display;
 use crate::factory::CliFactory;
@@ -12,18 +16,22 @@ use crate::file_fetcher::FileFetcher;
 use crate::graph_util::graph_valid_with_cli_options;
 use crate::module_loader::ModuleLoadPreparer;
 use crate::ops;
+use crate::ops::testing::next_test_id;
 use crate::util::checksum;
 use crate::util::file_watcher;
 use crate::util::file_watcher::ResolutionResult;
 use crate::util::fs::collect_specifiers;
+use crate::util::logger;
 use crate::util::path::get_extension;
 use crate::util::path::is_supported_ext;
 use crate::util::path::mapped_specifier_for_tsc;
 use crate::worker::CliMainWorkerFactory;
 
+use console_static_text::ansi::strip_ansi_codes;
 use deno_ast::swc::common::comments::CommentKind;
 use deno_ast::MediaType;
 use deno_ast::SourceRangedForSpanned;
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
@@ -33,6 +41,9 @@ use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
 use deno_core::located_script_name;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_core::serde_v8;
 use deno_core::task::spawn;
 use deno_core::task::spawn_blocking;
@@ -45,6 +56,7 @@ use deno_runtime::fmt_errors::format_js_error;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::tokio_util::create_and_run_current_thread;
+use deno_runtime::worker::MainWorker;
 use indexmap::IndexMap;
 use indexmap::IndexSet;
 use log::Level;
@@ -53,6 +65,7 @@ use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use regex::Regex;
 use serde::Deserialize;
+use serde::Serialize;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
@@ -70,6 +83,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 use std::time::SystemTime;
+use tokio::io::AsyncBufReadExt;
 use tokio::signal;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedSender;
@@ -138,7 +152,7 @@ impl TestFilter {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct TestLocation {
   pub file_name: String,
@@ -146,7 +160,7 @@ pub struct TestLocation {
   pub column_number: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct TestDescription {
   pub id: usize,
@@ -155,6 +169,8 @@ pub struct TestDescription {
   pub only: bool,
   pub origin: String,
   pub location: TestLocation,
+  /// The timeout in milliseconds of the `timeout` option of `Deno.test`.
+  pub timeout: Option<u64>,
 }
 
 impl TestDescription {
@@ -171,7 +187,7 @@ pub enum TestOutput {
 }
 
 #[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestFailure {
   JsError(Box<JsError>),
@@ -183,6 +199,7 @@ pub enum TestFailure {
   Incomplete,
   OverlapsWithSanitizers(IndexSet<String>), // Long names of overlapped tests
   HasSanitizersAndOverlaps(IndexSet<String>), // Long names of overlapped tests
+  TimedOut(u64, Vec<String>), // Timeout in milliseconds, pending async ops
 }
 
 impl ToString for TestFailure {
@@ -224,6 +241,16 @@ impl ToString for TestFailure {
         }
         string
       }
+      TestFailure::TimedOut(timeout, pending_ops) => {
+        let mut string = format!("Test timed out after {timeout}ms.");
+        if !pending_ops.is_empty() {
+          string.push_str("\nPending async ops:");
+          for pending_op in pending_ops {
+            string.push_str(&format!("\n  - {}", pending_op));
+          }
+        }
+        string
+      }
     }
   }
 }
@@ -232,6 +259,7 @@ impl TestFailure {
   fn format_label(&self) -> String {
     match self {
       TestFailure::Incomplete => colors::gray("INCOMPLETE").to_string(),
+      TestFailure::TimedOut(..) => colors::red("TIMED OUT").to_string(),
       _ => colors::red("FAILED").to_string(),
     }
   }
@@ -258,7 +286,7 @@ impl TestFailure {
 }
 
 #[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestResult {
   Ok,
@@ -267,7 +295,7 @@ pub enum TestResult {
   Cancelled,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestStepDescription {
   pub id: usize,
@@ -291,7 +319,7 @@ impl TestStepDescription {
 }
 
 #[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestStepResult {
   Ok,
@@ -299,7 +327,7 @@ pub enum TestStepResult {
   Failed(TestFailure),
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestPlan {
   pub origin: String,
@@ -308,7 +336,7 @@ pub struct TestPlan {
   pub used_only: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestEvent {
   Register(TestDescription),
@@ -343,6 +371,8 @@ struct TestSpecifiersOptions {
   concurrent_jobs: NonZeroUsize,
   fail_fast: Option<NonZeroUsize>,
   log_level: Option<log::Level>,
+  reporter: TestReporterConfig,
+  isolate: bool,
   specifier: TestSpecifierOptions,
 }
 
@@ -351,6 +381,8 @@ pub struct TestSpecifierOptions {
   pub shuffle: Option<u64>,
   pub filter: TestFilter,
   pub trace_ops: bool,
+  /// The timeout in milliseconds of `--timeout`.
+  pub timeout: Option<u64>,
 }
 
 impl TestSummary {
@@ -375,6 +407,50 @@ impl TestSummary {
   }
 }
 
+trait TestReporter {
+  fn report_register(&mut self, description: &TestDescription);
+  fn report_plan(&mut self, plan: &TestPlan);
+  fn report_wait(&mut self, description: &TestDescription);
+  fn report_output(&mut self, output: &[u8]);
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  );
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError);
+  fn report_step_register(&mut self, description: &TestStepDescription);
+  fn report_step_wait(&mut self, description: &TestStepDescription);
+  fn report_step_result(
+    &mut self,
+    desc: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  );
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration);
+  fn report_sigint(
+    &mut self,
+    tests_pending: &HashSet<usize>,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  );
+}
+
+fn create_reporter(
+  config: TestReporterConfig,
+  parallel: bool,
+  echo_output: bool,
+) -> Box<dyn TestReporter + Send> {
+  match config {
+    TestReporterConfig::Pretty => {
+      Box::new(PrettyTestReporter::new(parallel, echo_output))
+    }
+    TestReporterConfig::Json => Box::new(JsonTestReporter),
+  }
+}
+
 struct PrettyTestReporter {
   parallel: bool,
   echo_output: bool,
@@ -508,7 +584,9 @@ impl PrettyTestReporter {
       self.did_have_user_output = false;
     }
   }
+}
 
+impl TestReporter for PrettyTestReporter {
   fn report_register(&mut self, _description: &TestDescription) {}
 
   fn report_plan(&mut self, plan: &TestPlan) {
@@ -626,7 +704,7 @@ impl PrettyTestReporter {
           "{} =>",
           self.to_relative_path_or_remote_url(&desc.origin)
         )),
-        self.format_test_step_ancestry(desc, tests, test_steps)
+        format_test_step_ancestry(desc, tests, test_steps)
       );
       self.in_new_line = false;
       self.scope_test_id = Some(desc.id);
@@ -793,37 +871,9 @@ impl PrettyTestReporter {
     println!();
     self.in_new_line = true;
   }
+}
 
-  fn format_test_step_ancestry(
-    &self,
-    desc: &TestStepDescription,
-    tests: &IndexMap<usize, TestDescription>,
-    test_steps: &IndexMap<usize, TestStepDescription>,
-  ) -> String {
-    let root;
-    let mut ancestor_names = vec![];
-    let mut current_desc = desc;
-    loop {
-      if let Some(step_desc) = test_steps.get(&current_desc.parent_id) {
-        ancestor_names.push(&step_desc.name);
-        current_desc = step_desc;
-      } else {
-        root = tests.get(&current_desc.parent_id).unwrap();
-        break;
-      }
-    }
-    ancestor_names.reverse();
-    let mut result = String::new();
-    result.push_str(&root.name);
-    result.push_str(" ... ");
-    for name in ancestor_names {
-      result.push_str(name);
-      result.push_str(" ... ");
-    }
-    result.push_str(&desc.name);
-    result
-  }
-
+impl PrettyTestReporter {
   fn format_test_for_summary(&self, desc: &TestDescription) -> String {
     format!(
       "{} {}",
@@ -843,7 +893,7 @@ impl PrettyTestReporter {
     tests: &IndexMap<usize, TestDescription>,
     test_steps: &IndexMap<usize, TestStepDescription>,
   ) -> String {
-    let long_name = self.format_test_step_ancestry(desc, tests, test_steps);
+    let long_name = format_test_step_ancestry(desc, tests, test_steps);
     format!(
       "{} {}",
       long_name,
@@ -857,6 +907,235 @@ impl PrettyTestReporter {
   }
 }
 
+/// Writes a JSON object per line to stdout for each test event, for
+/// `--reporter=json`.
+struct JsonTestReporter;
+
+impl JsonTestReporter {
+  fn write_event(&self, event: Value) {
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{event}").unwrap();
+    // flush so that the events are received as they happen
+    stdout.flush().unwrap();
+  }
+}
+
+impl TestReporter for JsonTestReporter {
+  fn report_register(&mut self, description: &TestDescription) {
+    self.write_event(json!({
+      "type": "register",
+      "id": description.id,
+      "name": description.name,
+      "origin": description.origin,
+      "location": description.location,
+      "ignore": description.ignore,
+      "only": description.only,
+    }));
+  }
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    self.write_event(json!({
+      "type": "plan",
+      "origin": plan.origin,
+      "total": plan.total,
+      "filteredOut": plan.filtered_out,
+      "usedOnly": plan.used_only,
+    }));
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    self.write_event(json!({
+      "type": "testStart",
+      "id": description.id,
+      "name": description.name,
+      "origin": description.origin,
+      "location": description.location,
+    }));
+  }
+
+  fn report_output(&mut self, output: &[u8]) {
+    self.write_event(json!({
+      "type": "output",
+      "output": String::from_utf8_lossy(output),
+    }));
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let (result, error) = match result {
+      TestResult::Ok => ("ok", None),
+      TestResult::Ignored => ("ignored", None),
+      TestResult::Failed(failure) => ("failed", Some(failure_to_json(failure))),
+      TestResult::Cancelled => ("cancelled", None),
+    };
+    self.write_event(json!({
+      "type": "testResult",
+      "id": description.id,
+      "name": description.name,
+      "origin": description.origin,
+      "location": description.location,
+      "result": result,
+      "error": error,
+      "duration": elapsed,
+    }));
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError) {
+    self.write_event(json!({
+      "type": "uncaughtError",
+      "origin": origin,
+      "error": js_error_to_json(error),
+    }));
+  }
+
+  fn report_step_register(&mut self, description: &TestStepDescription) {
+    self.write_event(json!({
+      "type": "stepRegister",
+      "id": description.id,
+      "name": description.name,
+      "origin": description.origin,
+      "location": description.location,
+      "level": description.level,
+      "parentId": description.parent_id,
+      "rootId": description.root_id,
+    }));
+  }
+
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    self.write_event(json!({
+      "type": "stepStart",
+      "id": description.id,
+      "name": description.name,
+      "parentId": description.parent_id,
+      "rootId": description.root_id,
+    }));
+  }
+
+  fn report_step_result(
+    &mut self,
+    desc: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let (result, error) = match result {
+      TestStepResult::Ok => ("ok", None),
+      TestStepResult::Ignored => ("ignored", None),
+      TestStepResult::Failed(failure) => {
+        ("failed", Some(failure_to_json(failure)))
+      }
+    };
+    self.write_event(json!({
+      "type": "stepResult",
+      "id": desc.id,
+      "name": desc.name,
+      "parentId": desc.parent_id,
+      "rootId": desc.root_id,
+      "result": result,
+      "error": error,
+      "duration": elapsed,
+    }));
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    self.write_event(json!({
+      "type": "summary",
+      "ok": !summary.has_failed(),
+      "total": summary.total,
+      "passed": summary.passed,
+      "failed": summary.failed,
+      "ignored": summary.ignored,
+      "passedSteps": summary.passed_steps,
+      "failedSteps": summary.failed_steps,
+      "ignoredSteps": summary.ignored_steps,
+      "filteredOut": summary.filtered_out,
+      "measured": summary.measured,
+      "duration": elapsed.as_millis() as u64,
+    }));
+  }
+
+  fn report_sigint(
+    &mut self,
+    tests_pending: &HashSet<usize>,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let mut pending = tests_pending.iter().collect::<Vec<_>>();
+    pending.sort();
+    self.write_event(json!({ "type": "sigint", "pending": pending }));
+  }
+}
+
+/// Converts a test failure to a JSON error object. The stack frames of
+/// JavaScript errors are source mapped.
+fn failure_to_json(failure: &TestFailure) -> Value {
+  match failure {
+    TestFailure::JsError(js_error) => js_error_to_json(js_error),
+    failure => json!({
+      "message": strip_ansi_codes(&failure.to_string()),
+    }),
+  }
+}
+
+fn js_error_to_json(js_error: &JsError) -> Value {
+  let js_error = abbreviate_test_error(js_error);
+  let stack = js_error
+    .frames
+    .iter()
+    .map(|frame| {
+      json!({
+        "functionName": frame.function_name,
+        "fileName": frame.file_name,
+        "lineNumber": frame.line_number,
+        "columnNumber": frame.column_number,
+      })
+    })
+    .collect::<Vec<_>>();
+  json!({
+    "name": js_error.name,
+    "message": js_error.message,
+    "formatted": strip_ansi_codes(&format_test_error(&js_error)),
+    "stack": stack,
+    "cause": js_error.cause.as_deref().map(js_error_to_json),
+  })
+}
+
+/// Formats the name of a test step with the names of its ancestors, eg.
+/// `test ... step ... nested step`.
+fn format_test_step_ancestry(
+  desc: &TestStepDescription,
+  tests: &IndexMap<usize, TestDescription>,
+  test_steps: &IndexMap<usize, TestStepDescription>,
+) -> String {
+  let root;
+  let mut ancestor_names = vec![];
+  let mut current_desc = desc;
+  loop {
+    if let Some(step_desc) = test_steps.get(&current_desc.parent_id) {
+      ancestor_names.push(&step_desc.name);
+      current_desc = step_desc;
+    } else {
+      root = tests.get(&current_desc.parent_id).unwrap();
+      break;
+    }
+  }
+  ancestor_names.reverse();
+  let mut result = String::new();
+  result.push_str(&root.name);
+  result.push_str(" ... ");
+  for name in ancestor_names {
+    result.push_str(name);
+    result.push_str(" ... ");
+  }
+  result.push_str(&desc.name);
+  result
+}
+
 fn abbreviate_test_error(js_error: &JsError) -> JsError {
   let mut js_error = js_error.clone();
   let frames = std::mem::take(&mut js_error.frames);
@@ -1008,7 +1287,31 @@ pub async fn test_specifier(
     }
     sender.send(TestEvent::Wait(desc.id))?;
     let earlier = SystemTime::now();
-    let result = match worker.js_runtime.call_and_await(&function).await {
+    let result = match desc.timeout.or(options.timeout) {
+      Some(timeout) => {
+        match call_with_timeout(&mut worker, &function, timeout).await {
+          Some(result) => result,
+          None => {
+            let pending_ops = get_pending_op_details(&mut worker)?;
+            let failure = TestFailure::TimedOut(timeout, pending_ops);
+            let elapsed =
+              SystemTime::now().duration_since(earlier)?.as_millis();
+            fail_fast_tracker.add_failure();
+            sender.send(TestEvent::Result(
+              desc.id,
+              TestResult::Failed(failure),
+              elapsed as u64,
+            ))?;
+            // the ops of the test are still pending, so the rest of the tests
+            // of the module can't be run reliably
+            had_uncaught_error = true;
+            continue;
+          }
+        }
+      }
+      None => worker.js_runtime.call_and_await(&function).await,
+    };
+    let result = match result {
       Ok(r) => r,
       Err(error) => {
         if error.is::<JsError>() {
@@ -1048,6 +1351,56 @@ pub async fn test_specifier(
   Ok(())
 }
 
+/// Calls a test function, giving up after the timeout in milliseconds. Tests
+/// that block the event loop are interrupted by terminating the execution of
+/// the isolate from another thread. Returns `None` when the test timed out.
+async fn call_with_timeout(
+  worker: &mut MainWorker,
+  function: &v8::Global<v8::Function>,
+  timeout: u64,
+) -> Option<Result<v8::Global<v8::Value>, AnyError>> {
+  let isolate_handle = worker.js_runtime.v8_isolate().thread_safe_handle();
+  let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+  let watchdog = std::thread::spawn(move || {
+    let timed_out = done_rx
+      .recv_timeout(Duration::from_millis(timeout))
+      .is_err();
+    if timed_out {
+      isolate_handle.terminate_execution();
+    }
+    timed_out
+  });
+  let result = tokio::time::timeout(
+    Duration::from_millis(timeout),
+    worker.js_runtime.call_and_await(function),
+  )
+  .await;
+  // the watchdog can't terminate the execution after it's joined
+  done_tx.send(()).ok();
+  let terminated = watchdog.join().unwrap();
+  if terminated {
+    worker.js_runtime.v8_isolate().cancel_terminate_execution();
+  }
+  match result {
+    Ok(result) if !terminated => Some(result),
+    _ => None,
+  }
+}
+
+/// Describes the async ops that haven't completed, for the failure of a test
+/// that timed out.
+fn get_pending_op_details(
+  worker: &mut MainWorker,
+) -> Result<Vec<String>, AnyError> {
+  let details = worker.js_runtime.execute_script_static(
+    located_script_name!(),
+    "Deno[Deno.internal].getPendingOpDetails()",
+  )?;
+  let scope = &mut worker.js_runtime.handle_scope();
+  let details = v8::Local::new(scope, details);
+  Ok(serde_v8::from_v8::<Vec<String>>(scope, details)?)
+}
+
 fn extract_files_from_regex_blocks(
   specifier: &ModuleSpecifier,
   source: &str,
@@ -1297,6 +1650,229 @@ pub async fn check_specifiers(
 static HAS_TEST_RUN_SIGINT_HANDLER: AtomicBool = AtomicBool::new(false);
 
 /// Test a collection of specifiers with test modes concurrently.
+/// Logs a test event as a JSON line for `--log-format=json`.
+fn log_json_test_event(
+  event: &TestEvent,
+  tests: &IndexMap<usize, TestDescription>,
+  test_steps: &IndexMap<usize, TestStepDescription>,
+) {
+  let name = |id: &usize| {
+    tests
+      .get(id)
+      .map(|description| description.name.clone())
+      .or_else(|| test_steps.get(id).map(|step| step.name.clone()))
+  };
+  let failure_message =
+    |failure: &TestFailure| strip_ansi_codes(&failure.to_string()).to_string();
+  let (event, data) = match event {
+    TestEvent::Register(description) => (
+      "testRegister",
+      json!({
+        "id": description.id,
+        "name": description.name,
+        "origin": description.origin,
+        "ignore": description.ignore,
+        "only": description.only,
+      }),
+    ),
+    TestEvent::Plan(plan) => (
+      "testPlan",
+      json!({
+        "origin": plan.origin,
+        "total": plan.total,
+        "filteredOut": plan.filtered_out,
+        "usedOnly": plan.used_only,
+      }),
+    ),
+    TestEvent::Wait(id) | TestEvent::StepWait(id) => {
+      ("testWait", json!({ "id": id, "name": name(id) }))
+    }
+    TestEvent::Result(id, result, elapsed) => {
+      let (result, failure) = match result {
+        TestResult::Ok => ("ok", None),
+        TestResult::Ignored => ("ignored", None),
+        TestResult::Failed(failure) => {
+          ("failed", Some(failure_message(failure)))
+        }
+        TestResult::Cancelled => ("cancelled", None),
+      };
+      (
+        "testResult",
+        json!({
+          "id": id,
+          "name": name(id),
+          "result": result,
+          "failure": failure,
+          "duration": elapsed,
+        }),
+      )
+    }
+    TestEvent::StepRegister(description) => (
+      "testStepRegister",
+      json!({
+        "id": description.id,
+        "name": description.name,
+        "origin": description.origin,
+        "parentId": description.parent_id,
+        "rootId": description.root_id,
+      }),
+    ),
+    TestEvent::StepResult(id, result, duration) => {
+      let (result, failure) = match result {
+        TestStepResult::Ok => ("ok", None),
+        TestStepResult::Ignored => ("ignored", None),
+        TestStepResult::Failed(failure) => {
+          ("failed", Some(failure_message(failure)))
+        }
+      };
+      (
+        "testStepResult",
+        json!({
+          "id": id,
+          "name": name(id),
+          "result": result,
+          "failure": failure,
+          "duration": duration,
+        }),
+      )
+    }
+    TestEvent::UncaughtError(origin, error) => (
+      "testUncaughtError",
+      json!({
+        "origin": origin,
+        "error": strip_ansi_codes(&format_test_error(error)).to_string(),
+      }),
+    ),
+    // the output of the tests is written to stdout as is
+    TestEvent::Output(_) | TestEvent::Sigint => return,
+  };
+  logger::log_json_event(event, data);
+}
+
+/// The environment variable with the test module that a subprocess of
+/// `--isolate` runs.
+const ISOLATED_SPECIFIER_ENV_VAR: &str = "DENO_TEST_ISOLATED_SPECIFIER";
+
+/// Checks if this is a subprocess of `--isolate`, which runs a single test
+/// module and writes its test events to stdout.
+pub fn is_isolated_test_process() -> bool {
+  std::env::var_os(ISOLATED_SPECIFIER_ENV_VAR).is_some()
+}
+
+/// Runs a test module in a subprocess for `--isolate`, forwarding the test
+/// events that it writes to stdout as JSON lines.
+async fn test_specifier_in_subprocess(
+  specifier: ModuleSpecifier,
+  mut sender: TestEventSender,
+  fail_fast_tracker: FailFastTracker,
+) -> Result<(), AnyError> {
+  if fail_fast_tracker.should_stop() {
+    return Ok(());
+  }
+  // the subprocess gets the same arguments, so it uses the same permissions,
+  // config file and test options
+  let mut child = tokio::process::Command::new(std::env::current_exe()?)
+    .args(std::env::args_os().skip(1))
+    .env(ISOLATED_SPECIFIER_ENV_VAR, specifier.as_str())
+    .stdin(std::process::Stdio::null())
+    .stdout(std::process::Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()?;
+  let stdout = child.stdout.take().unwrap();
+  let mut lines = tokio::io::BufReader::new(stdout).lines();
+  // test ids are only unique within a process
+  let mut ids = HashMap::new();
+  while let Some(line) = lines.next_line().await? {
+    let mut event: TestEvent = serde_json::from_str(&line)
+      .with_context(|| format!("Invalid test event: {line}"))?;
+    remap_test_event_ids(&mut event, &mut ids);
+    sender.send(event)?;
+  }
+
+  let status = child.wait().await?;
+  if !status.success() {
+    // eg. a crash of native code, which can't be reported by the subprocess
+    let message = format!("The test process exited with {status}.");
+    fail_fast_tracker.add_failure();
+    sender.send(TestEvent::UncaughtError(
+      specifier.to_string(),
+      Box::new(JsError {
+        name: Some("Error".to_string()),
+        exception_message: format!("Error: {message}"),
+        message: Some(message),
+        stack: None,
+        cause: None,
+        frames: vec![],
+        source_line: None,
+        source_line_frame_index: None,
+        aggregated: None,
+      }),
+    ))?;
+  }
+  Ok(())
+}
+
+/// Replaces the ids of the test events of a subprocess with ids that are
+/// unique in this process.
+fn remap_test_event_ids(
+  event: &mut TestEvent,
+  ids: &mut HashMap<usize, usize>,
+) {
+  let mut remap =
+    |id: &mut usize| *id = *ids.entry(*id).or_insert_with(next_test_id);
+  match event {
+    TestEvent::Register(description) => remap(&mut description.id),
+    TestEvent::StepRegister(description) => {
+      remap(&mut description.id);
+      remap(&mut description.parent_id);
+      remap(&mut description.root_id);
+    }
+    TestEvent::Wait(id)
+    | TestEvent::StepWait(id)
+    | TestEvent::Result(id, ..)
+    | TestEvent::StepResult(id, ..) => remap(id),
+    TestEvent::Plan(_)
+    | TestEvent::Output(_)
+    | TestEvent::UncaughtError(..)
+    | TestEvent::Sigint => {}
+  }
+}
+
+/// Runs the test module of a subprocess of `--isolate`, writing its test
+/// events to stdout as JSON lines.
+async fn run_isolated_test_specifier(
+  factory: &CliFactory,
+  permissions: Permissions,
+  test_options: TestOptions,
+  specifier: ModuleSpecifier,
+) -> Result<(), AnyError> {
+  let worker_factory =
+    Arc::new(factory.create_cli_main_worker_factory().await?);
+  let (sender, mut receiver) = unbounded_channel::<TestEvent>();
+  let join_handle = spawn_blocking(move || {
+    create_and_run_current_thread(test_specifier(
+      worker_factory,
+      permissions,
+      specifier,
+      TestEventSender::new(sender),
+      FailFastTracker::new(test_options.fail_fast),
+      TestSpecifierOptions {
+        filter: TestFilter::from_flag(&test_options.filter),
+        shuffle: test_options.shuffle,
+        trace_ops: test_options.trace_ops,
+        timeout: test_options.timeout,
+      },
+    ))
+  });
+
+  while let Some(event) = receiver.recv().await {
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", serde_json::to_string(&event)?)?;
+    stdout.flush()?;
+  }
+  join_handle.await?
+}
+
 async fn test_specifiers(
   worker_factory: Arc<CliMainWorkerFactory>,
   permissions: &Permissions,
@@ -1330,6 +1906,13 @@ async fn test_specifiers(
     let sender = sender.clone();
     let fail_fast_tracker = FailFastTracker::new(options.fail_fast);
     let specifier_options = options.specifier.clone();
+    if options.isolate {
+      return spawn(test_specifier_in_subprocess(
+        specifier,
+        sender,
+        fail_fast_tracker,
+      ));
+    }
     spawn_blocking(move || {
       create_and_run_current_thread(test_specifier(
         worker_factory,
@@ -1346,10 +1929,11 @@ async fn test_specifiers(
     .buffer_unordered(concurrent_jobs.get())
     .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
 
-  let mut reporter = Box::new(PrettyTestReporter::new(
+  let mut reporter = create_reporter(
+    options.reporter,
     concurrent_jobs.get() > 1,
     options.log_level != Some(Level::Error),
-  ));
+  );
 
   let handler = {
     spawn(async move {
@@ -1362,6 +1946,9 @@ async fn test_specifiers(
       let mut used_only = false;
 
       while let Some(event) = receiver.recv().await {
+        if logger::is_json_format() {
+          log_json_test_event(&event, &tests, &test_steps);
+        }
         match event {
           TestEvent::Register(description) => {
             reporter.report_register(&description);
@@ -1445,7 +2032,7 @@ async fn test_specifiers(
                   summary.failures.push((
                     TestDescription {
                       id: description.id,
-                      name: reporter.format_test_step_ancestry(
+                      name: format_test_step_ancestry(
                         description,
                         &tests,
                         &test_steps,
@@ -1454,6 +2041,7 @@ async fn test_specifiers(
                       only: false,
                       origin: description.origin.clone(),
                       location: description.location.clone(),
+                      timeout: None,
                     },
                     failure.clone(),
                   ))
@@ -1563,27 +2151,32 @@ fn is_supported_test_ext(path: &Path) -> bool {
 /// - Specifiers matching both predicates are marked as `TestMode::Both`
 fn collect_specifiers_with_test_mode(
   files: &FilesConfig,
+  workspace_members: &[WorkspaceMemberOptions<()>],
   include_inline: &bool,
 ) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
-  let module_specifiers = collect_specifiers(files, is_supported_test_path)?;
+  let module_specifiers =
+    collect_test_specifiers(files, workspace_members, is_supported_test_path)?;
 
   if *include_inline {
-    return collect_specifiers(files, is_supported_test_ext).map(
-      |specifiers| {
-        specifiers
-          .into_iter()
-          .map(|specifier| {
-            let mode = if module_specifiers.contains(&specifier) {
-              TestMode::Both
-            } else {
-              TestMode::Documentation
-            };
-
-            (specifier, mode)
-          })
-          .collect()
-      },
-    );
+    return collect_test_specifiers(
+      files,
+      workspace_members,
+      is_supported_test_ext,
+    )
+    .map(|specifiers| {
+      specifiers
+        .into_iter()
+        .map(|specifier| {
+          let mode = if module_specifiers.contains(&specifier) {
+            TestMode::Both
+          } else {
+            TestMode::Documentation
+          };
+
+          (specifier, mode)
+        })
+        .collect()
+    });
   }
 
   let specifiers_with_mode = module_specifiers
@@ -1594,6 +2187,20 @@ fn collect_specifiers_with_test_mode(
   Ok(specifiers_with_mode)
 }
 
+/// Collects specifiers with `collect_specifiers`, leaving out the ones excluded
+/// by the configuration of the workspace member they belong to.
+fn collect_test_specifiers(
+  files: &FilesConfig,
+  workspace_members: &[WorkspaceMemberOptions<()>],
+  predicate: impl Fn(&Path) -> bool,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  let mut specifiers = collect_specifiers(files, predicate)?;
+  specifiers.retain(|specifier| {
+    workspace_member_matches_specifier(workspace_members, specifier)
+  });
+  Ok(specifiers)
+}
+
 /// Collects module and document specifiers with test modes via
 /// `collect_specifiers_with_test_mode` which are then pre-fetched and adjusted
 /// based on the media type.
@@ -1605,9 +2212,11 @@ fn collect_specifiers_with_test_mode(
 async fn fetch_specifiers_with_test_mode(
   file_fetcher: &FileFetcher,
   files: &FilesConfig,
+  workspace_members: &[WorkspaceMemberOptions<()>],
   doc: &bool,
 ) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
-  let mut specifiers_with_mode = collect_specifiers_with_test_mode(files, doc)?;
+  let mut specifiers_with_mode =
+    collect_specifiers_with_test_mode(files, workspace_members, doc)?;
 
   for (specifier, mode) in &mut specifiers_with_mode {
     let file = file_fetcher
@@ -1624,6 +2233,34 @@ async fn fetch_specifiers_with_test_mode(
   Ok(specifiers_with_mode)
 }
 
+/// Keeps the specifiers of the given shard. The specifiers are assigned to the
+/// shards in sorted order, so the assignment doesn't depend on the order in
+/// which they were collected.
+fn shard_specifiers<T>(
+  specifiers: Vec<(ModuleSpecifier, T)>,
+  shard: Option<TestShard>,
+) -> Vec<(ModuleSpecifier, T)> {
+  let shard = match shard {
+    Some(shard) => shard,
+    None => return specifiers,
+  };
+  let mut sorted = specifiers
+    .iter()
+    .map(|(specifier, _)| specifier)
+    .collect::<Vec<_>>();
+  sorted.sort();
+  let in_shard = sorted
+    .into_iter()
+    .enumerate()
+    .filter(|(index, _)| index % shard.count == shard.index - 1)
+    .map(|(_, specifier)| specifier.clone())
+    .collect::<HashSet<_>>();
+  specifiers
+    .into_iter()
+    .filter(|(specifier, _)| in_shard.contains(specifier))
+    .collect()
+}
+
 pub async fn run_tests(
   cli_options: CliOptions,
   test_options: TestOptions,
@@ -1636,12 +2273,23 @@ pub async fn run_tests(
   // `PermissionsContainer` - otherwise granting/revoking permissions in one
   // file would have impact on other files, which is undesirable.
   let permissions =
-    Permissions::from_options(&cli_options.permissions_options())?;
+    Permissions::from_options(&cli_options.permissions_options()?)?;
   let log_level = cli_options.log_level();
 
+  if let Ok(specifier) = std::env::var(ISOLATED_SPECIFIER_ENV_VAR) {
+    return run_isolated_test_specifier(
+      &factory,
+      permissions,
+      test_options,
+      ModuleSpecifier::parse(&specifier)?,
+    )
+    .await;
+  }
+
   let specifiers_with_mode = fetch_specifiers_with_test_mode(
     file_fetcher,
     &test_options.files,
+    &test_options.workspace_members,
     &test_options.doc,
   )
   .await?;
@@ -1649,6 +2297,9 @@ pub async fn run_tests(
   if !test_options.allow_none && specifiers_with_mode.is_empty() {
     return Err(generic_error("No test modules found"));
   }
+  // a shard can be empty when there are more shards than test modules
+  let specifiers_with_mode =
+    shard_specifiers(specifiers_with_mode, test_options.shard);
 
   check_specifiers(
     cli_options,
@@ -1679,10 +2330,13 @@ pub async fn run_tests(
       concurrent_jobs: test_options.concurrent_jobs,
       fail_fast: test_options.fail_fast,
       log_level,
+      reporter: test_options.reporter,
+      isolate: test_options.isolate,
       specifier: TestSpecifierOptions {
         filter: TestFilter::from_flag(&test_options.filter),
         shuffle: test_options.shuffle,
         trace_ops: test_options.trace_ops,
+        timeout: test_options.timeout,
       },
     },
   )
@@ -1705,7 +2359,7 @@ pub async fn run_tests_with_watch(
   // `PermissionsContainer` - otherwise granting/revoking permissions in one
   // file would have impact on other files, which is undesirable.
   let permissions =
-    Permissions::from_options(&cli_options.permissions_options())?;
+    Permissions::from_options(&cli_options.permissions_options()?)?;
   let no_check = cli_options.type_check_mode() == TypeCheckMode::None;
   let log_level = cli_options.log_level();
 
@@ -1718,11 +2372,15 @@ pub async fn run_tests_with_watch(
     let module_graph_builder = module_graph_builder.clone();
 
     async move {
-      let test_modules = if test_options.doc {
-        collect_specifiers(&test_options.files, is_supported_test_ext)
-      } else {
-        collect_specifiers(&test_options.files, is_supported_test_path)
-      }?;
+      let test_modules = collect_test_specifiers(
+        &test_options.files,
+        &test_options.workspace_members,
+        if test_options.doc {
+          is_supported_test_ext
+        } else {
+          is_supported_test_path
+        },
+      )?;
 
       let mut paths_to_watch = paths_to_watch_clone;
       let mut modules_to_reload = if files_changed {
@@ -1799,19 +2457,19 @@ pub async fn run_tests_with_watch(
         }
       }
 
-      Ok((paths_to_watch, modules_to_reload))
+      Ok((paths_to_watch, modules_to_reload, changed))
     }
     .map(move |result| {
       if files_changed
-        && matches!(result, Ok((_, ref modules)) if modules.is_empty())
+        && matches!(result, Ok((_, ref modules, _)) if modules.is_empty())
       {
         ResolutionResult::Ignore
       } else {
         match result {
-          Ok((paths_to_watch, modules_to_reload)) => {
+          Ok((paths_to_watch, modules_to_reload, changed)) => {
             ResolutionResult::Restart {
               paths_to_watch,
-              result: Ok(modules_to_reload),
+              result: Ok((modules_to_reload, changed)),
             }
           }
           Err(e) => ResolutionResult::Restart {
@@ -1825,10 +2483,13 @@ pub async fn run_tests_with_watch(
 
   let create_cli_main_worker_factory =
     factory.create_cli_main_worker_factory_func().await?;
-  let operation = |modules_to_reload: Vec<ModuleSpecifier>| {
+  let operation = |(modules_to_reload, changed): (
+    Vec<ModuleSpecifier>,
+    Option<Vec<PathBuf>>,
+  )| {
     let permissions = &permissions;
     let test_options = &test_options;
-    file_watcher.reset();
+    file_watcher.invalidate(changed.as_deref());
     let cli_options = cli_options.clone();
     let file_fetcher = file_fetcher.clone();
     let module_load_preparer = module_load_preparer.clone();
@@ -1839,12 +2500,17 @@ pub async fn run_tests_with_watch(
       let specifiers_with_mode = fetch_specifiers_with_test_mode(
         &file_fetcher,
         &test_options.files,
+        &test_options.workspace_members,
         &test_options.doc,
       )
-      .await?
-      .into_iter()
-      .filter(|(specifier, _)| modules_to_reload.contains(specifier))
-      .collect::<Vec<(ModuleSpecifier, TestMode)>>();
+      .await?;
+      // shard all the specifiers so a module stays in the same shard across
+      // reloads
+      let specifiers_with_mode =
+        shard_specifiers(specifiers_with_mode, test_options.shard)
+          .into_iter()
+          .filter(|(specifier, _)| modules_to_reload.contains(specifier))
+          .collect::<Vec<(ModuleSpecifier, TestMode)>>();
 
       check_specifiers(
         &cli_options,
@@ -1872,10 +2538,13 @@ pub async fn run_tests_with_watch(
           concurrent_jobs: test_options.concurrent_jobs,
           fail_fast: test_options.fail_fast,
           log_level,
+          reporter: test_options.reporter,
+          isolate: test_options.isolate,
           specifier: TestSpecifierOptions {
             filter: TestFilter::from_flag(&test_options.filter),
             shuffle: test_options.shuffle,
             trace_ops: test_options.trace_ops,
+            timeout: test_options.timeout,
           },
         },
       )
@@ -1898,14 +2567,10 @@ pub async fn run_tests_with_watch(
     }
   });
 
-  let clear_screen = !cli_options.no_clear_screen();
   file_watcher::watch_func(
     resolver,
     operation,
-    file_watcher::PrintConfig {
-      job_name: "Test".to_string(),
-      clear_screen,
-    },
+    file_watcher::PrintConfig::new("Test", cli_options)?,
   )
   .await?;
 
@@ -2162,4 +2827,26 @@ mod inner_test {
     assert!(!is_supported_test_path(Path::new("notatest.js")));
     assert!(!is_supported_test_path(Path::new("NotAtest.ts")));
   }
+
+  #[test]
+  fn test_shard_specifiers() {
+    let specifiers = ["c", "a", "d", "b", "e"]
+      .iter()
+      .map(|name| {
+        let specifier =
+          ModuleSpecifier::parse(&format!("file:///{name}_test.ts")).unwrap();
+        (specifier, TestMode::Executable)
+      })
+      .collect::<Vec<_>>();
+    let shard = |index: usize| {
+      shard_specifiers(specifiers.clone(), Some(TestShard { index, count: 2 }))
+        .into_iter()
+        .map(|(specifier, _)| specifier.path().to_string())
+        .collect::<Vec<_>>()
+    };
+    // the collected order is kept
+    assert_eq!(shard(1), ["/c_test.ts", "/a_test.ts", "/e_test.ts"]);
+    assert_eq!(shard(2), ["/d_test.ts", "/b_test.ts"]);
+    assert_eq!(shard_specifiers(specifiers.clone(), None), specifiers);
+  }
 }
@@ -825,20 +825,31 @@ delete Object.prototype.__proto__;
         })
       : undefined;
 
-    if (checkFiles != null) {
-      // When calling program.getSemanticDiagnostics(...) with a source file, we
-      // need to call this code first in order to get it to invalidate cached
-      // diagnostics correctly. This is what program.getSemanticDiagnostics()
-      // does internally when calling without any arguments.
-      const checkFileNames = new Set(checkFiles.map((f) => f.fileName));
-      while (
-        program.getSemanticDiagnosticsOfNextAffectedFile(
-          undefined,
-          /* ignoreSourceFile */ (s) => !checkFileNames.has(s.fileName),
-        )
-      ) {
-        // keep going until there are no more affected files
+    // Only the files affected by changes since the previous build info are
+    // checked, the diagnostics of the other files are reused from it. When
+    // calling program.getSemanticDiagnostics(...) with a source file, we need
+    // to call this code first in order to get it to invalidate cached
+    // diagnostics correctly. This is what program.getSemanticDiagnostics()
+    // does internally when calling without any arguments.
+    const checkFileNames = checkFiles == null
+      ? undefined
+      : new Set(checkFiles.map((f) => f.fileName));
+    let affectedFileCount = 0;
+    while (true) {
+      const affectedResult = program.getSemanticDiagnosticsOfNextAffectedFile(
+        undefined,
+        /* ignoreSourceFile */ checkFileNames == null
+          ? undefined
+          : (s) => !checkFileNames.has(s.fileName),
+      );
+      if (affectedResult == null) {
+        // no more affected files
+        break;
       }
+      // the whole program is affected when there is no usable build info
+      affectedFileCount += "getSourceFiles" in affectedResult.affected
+        ? affectedResult.affected.getSourceFiles().length
+        : 1;
     }
 
     const diagnostics = [
@@ -862,6 +873,7 @@ delete Object.prototype.__proto__;
     program.emitBuildInfo(host.writeFile);
 
     performanceProgram({ program });
+    stats.push(["Affected files", affectedFileCount]);
 
     ops.op_respond({
       diagnostics: fromTypeScriptDiagnostic(diagnostics),
@@ -16,7 +16,6 @@ const MAX_SOURCE_LINE_LENGTH: usize = 150;
 
 const UNSTABLE_DENO_PROPS: &[&str] = &[
   "CreateHttpClientOptions",
-  "DatagramConn",
   "HttpClient",
   "UnixConnectOptions",
   "UnixListenOptions",
@@ -30,10 +29,14 @@ const UNSTABLE_DENO_PROPS: &[&str] = &[
   "shutdown",
   "umask",
   "serve",
+  "HttpServer",
+  "HttpServerShutdownOptions",
   "ServeInit",
   "ServeTlsInit",
   "Handler",
   "osUptime",
+  "cron",
+  "writeHeapSnapshot",
 ];
 
 static MSG_MISSING_PROPERTY_DENO: Lazy<Regex> =
@@ -336,6 +339,10 @@ impl Diagnostics {
   pub fn is_empty(&self) -> bool {
     self.0.is_empty()
   }
+
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
 }
 
 impl<'de> Deserialize<'de> for Diagnostics {
@@ -582,6 +582,17 @@ declare namespace Deno {
      */
     net?: "inherit" | boolean | string[];
 
+    /** Specifies if the `udp` permission should be requested or revoked.
+     * If set to `"inherit"`, the current `udp` permission will be inherited.
+     * If set to `true`, the global `udp` permission will be requested.
+     * If set to `false`, the global `udp` permission will be revoked.
+     * If set to `string[]`, the `udp` permission will be requested with the
+     * specified host strings with the format `"<host>[:<port>]`.
+     *
+     * @default {false}
+     */
+    udp?: "inherit" | boolean | string[];
+
     /** Specifies if the `ffi` permission should be requested or revoked.
      * If set to `"inherit"`, the current `ffi` permission will be inherited.
      * If set to `true`, the global `ffi` permission will be requested.
@@ -591,6 +602,17 @@ declare namespace Deno {
      */
     ffi?: "inherit" | boolean | Array<string | URL>;
 
+    /** Specifies if the `unix` permission should be requested or revoked.
+     * If set to `"inherit"`, the current `unix` permission will be inherited.
+     * If set to `true`, the global `unix` permission will be requested.
+     * If set to `false`, the global `unix` permission will be revoked.
+     * If set to `Array<string | URL>`, the `unix` permission will be requested
+     * with the specified socket paths.
+     *
+     * @default {false}
+     */
+    unix?: "inherit" | boolean | Array<string | URL>;
+
     /** Specifies if the `read` permission should be requested or revoked.
      * If set to `"inherit"`, the current `read` permission will be inherited.
      * If set to `true`, the global `read` permission will be requested.
@@ -779,6 +801,9 @@ declare namespace Deno {
      *
      * @default {true} */
     sanitizeExit?: boolean;
+    /** Fail the test when it takes longer than this number of milliseconds,
+     * overriding the `--timeout` flag of `deno test`. */
+    timeout?: number;
     /** Specifies the permissions that should be used to run the test.
      *
      * Set this to "inherit" to keep the calling runtime permissions, set this
@@ -3624,8 +3649,19 @@ declare namespace Deno {
    *
    * @category File System */
   export interface FsEvent {
-    /** The kind/type of the file system event. */
-    kind: "any" | "access" | "create" | "modify" | "remove" | "other";
+    /** The kind/type of the file system event.
+     *
+     * A `"rename"` event is only reported on platforms where both paths of the
+     * rename are known, which are then given as `[from, to]`. Otherwise, or in
+     * addition to it, the paths are reported through `"modify"` events. */
+    kind:
+      | "any"
+      | "access"
+      | "create"
+      | "modify"
+      | "rename"
+      | "remove"
+      | "other";
     /** An array of paths that are associated with the file system event. */
     paths: string[];
     /** Any additional flags associated with the event. */
@@ -3665,6 +3701,22 @@ declare namespace Deno {
    * Note that the exact ordering of the events can vary between operating
    * systems.
    *
+   * Paths matched by the `exclude` option don't produce events. It takes
+   * paths and glob patterns relative to the current working directory, and
+   * excluding a directory also excludes everything in it.
+   *
+   * The `debounce` option, in milliseconds, collects events until none
+   * arrived for that long and then yields them at once, with duplicate events
+   * merged. This is useful to react once to the burst of events caused by a
+   * single save in an editor.
+   *
+   * ```ts
+   * const watcher = Deno.watchFs(".", {
+   *   exclude: ["./node_modules", "./dist"],
+   *   debounce: 100,
+   * });
+   * ```
+   *
    * ```ts
    * const watcher = Deno.watchFs("/");
    * for await (const event of watcher) {
@@ -3694,9 +3746,26 @@ declare namespace Deno {
    */
   export function watchFs(
     paths: string | string[],
-    options?: { recursive: boolean },
+    options?: WatchFsOptions,
   ): FsWatcher;
 
+  /** Options which can be set when calling {@linkcode Deno.watchFs}.
+   *
+   * @category File System
+   */
+  export interface WatchFsOptions {
+    /** Whether directories are watched including all their sub directories.
+     *
+     * @default {true} */
+    recursive?: boolean;
+    /** Paths or glob patterns, relative to the current working directory, that
+     * don't produce events. */
+    exclude?: string[];
+    /** Collect events until none arrived for this many milliseconds, merging
+     * duplicate events, before yielding them. */
+    debounce?: number;
+  }
+
   /**
    * @deprecated Use {@linkcode Deno.Command} instead.
    *
@@ -3911,8 +3980,8 @@ declare namespace Deno {
    * );
    * ```
    *
-   * _Note_: On Windows only `"SIGINT"` (CTRL+C) and `"SIGBREAK"` (CTRL+Break)
-   * are supported.
+   * _Note_: On Windows only `"SIGINT"` (CTRL+C), `"SIGBREAK"` (CTRL+Break)
+   * and `"SIGHUP"` (the console window is being closed) are supported.
    *
    * @category Runtime Environment
    */
@@ -3929,8 +3998,8 @@ declare namespace Deno {
    * Deno.removeSignalListener("SIGTERM", listener);
    * ```
    *
-   * _Note_: On Windows only `"SIGINT"` (CTRL+C) and `"SIGBREAK"` (CTRL+Break)
-   * are supported.
+   * _Note_: On Windows only `"SIGINT"` (CTRL+C), `"SIGBREAK"` (CTRL+Break)
+   * and `"SIGHUP"` (the console window is being closed) are supported.
    *
    * @category Runtime Environment
    */
@@ -4104,10 +4173,25 @@ declare namespace Deno {
      * status. */
     output(): Promise<CommandOutput>;
     /** Kills the process with given {@linkcode Deno.Signal}.
+     *
+     * When `processGroup` is `true`, the signal is sent to every process in
+     * the process group of the child instead. This requires the child to be
+     * spawned with the `detached` or `processGroup` option. On Windows, the
+     * processes of the group are terminated for `"SIGKILL"` and `"SIGTERM"`,
+     * and sent a CTRL+Break event for `"SIGBREAK"`, other signals are not
+     * supported.
+     *
+     * ```ts
+     * const child = new Deno.Command("sh", {
+     *   args: ["-c", "sleep 100 & sleep 100"],
+     *   processGroup: 0,
+     * }).spawn();
+     * child.kill("SIGTERM", { processGroup: true });
+     * ```
      *
      * @param [signo="SIGTERM"]
      */
-    kill(signo?: Signal): void;
+    kill(signo?: Signal, options?: { processGroup?: boolean }): void;
 
     /** Ensure that the status of the child process prevents the Deno process
      * from exiting. */
@@ -4179,6 +4263,29 @@ declare namespace Deno {
      *
      * @default {false} */
     windowsRawArguments?: boolean;
+
+    /** Run the child process independently of the Deno process. It is not
+     * killed when the {@linkcode Deno.ChildProcess} is garbage collected or
+     * when Deno exits, and it becomes the leader of a new process group.
+     *
+     * On Unix, the child is started in a new session, so it also doesn't share
+     * the controlling terminal. On Windows, it doesn't get a console.
+     *
+     * To allow Deno to exit before the child, call
+     * {@linkcode Deno.ChildProcess.unref}.
+     *
+     * Can't be combined with `processGroup`.
+     *
+     * @default {false} */
+    detached?: boolean;
+    /** The id of the process group the child process should join. `0` creates
+     * a new process group with the pid of the child as its id.
+     *
+     * On Windows, only `0` is supported. The child can then be sent
+     * `"SIGBREAK"` with {@linkcode Deno.ChildProcess.kill}.
+     *
+     * Can't be combined with `detached`. */
+    processGroup?: number;
   }
 
   /**
@@ -4301,9 +4408,11 @@ declare namespace Deno {
     | "read"
     | "write"
     | "net"
+    | "udp"
     | "env"
     | "sys"
     | "ffi"
+    | "unix"
     | "hrtime";
 
   /** The current status of the permission:
@@ -4380,6 +4489,21 @@ declare namespace Deno {
     host?: string;
   }
 
+  /** The permission descriptor for the `allow-udp` permissions, which controls
+   * access to binding UDP sockets and sending datagrams with them. The option
+   * `host` allows scoping the permission to a specific host and port.
+   *
+   * @category Permissions */
+  export interface UdpPermissionDescriptor {
+    name: "udp";
+    /** Optional host string of the form `"<hostname>[:<port>]"`. Examples:
+     *
+     *      "1.1.1.1:53"
+     *      "0.0.0.0:5353"
+     */
+    host?: string;
+  }
+
   /** The permission descriptor for the `allow-env` permissions, which controls
    * access to being able to read and write to the process environment variables
    * as well as access other information about the environment. The option
@@ -4426,6 +4550,18 @@ declare namespace Deno {
     path?: string | URL;
   }
 
+  /** The permission descriptor for the `allow-unix` permissions, which
+   * controls access to listening on and connecting to Unix domain sockets.
+   * The option `path` allows scoping the permission to a specific socket path
+   * (and if the path is a directory, any sockets below it).
+   *
+   * @category Permissions */
+  export interface UnixPermissionDescriptor {
+    name: "unix";
+    /** Optional socket path on the local host to scope the permission to. */
+    path?: string | URL;
+  }
+
   /** The permission descriptor for the `allow-hrtime` permission, which
    * controls if the runtime code has access to high resolution time. High
    * resolution time is consider sensitive information, because it can be used
@@ -4450,9 +4586,11 @@ declare namespace Deno {
     | ReadPermissionDescriptor
     | WritePermissionDescriptor
     | NetPermissionDescriptor
+    | UdpPermissionDescriptor
     | EnvPermissionDescriptor
     | SysPermissionDescriptor
     | FfiPermissionDescriptor
+    | UnixPermissionDescriptor
     | HrtimePermissionDescriptor;
 
   /** The interface which defines what event types are supported by
@@ -5199,6 +5337,11 @@ declare namespace Deno {
    * identified by `pid`. An error will be thrown if a negative `pid` is used on
    * Windows.
    *
+   * On Windows, only `"SIGKILL"` and `"SIGTERM"`, which terminate the process,
+   * and `"SIGBREAK"` are supported. `"SIGBREAK"` sends a CTRL+Break event to
+   * the console process group identified by `pid`, such as a child process
+   * spawned with the `processGroup: 0` option of {@linkcode Deno.Command}.
+   *
    * ```ts
    * const p = Deno.run({
    *   cmd: ["sleep", "10000"]
@@ -597,6 +597,13 @@ declare class Performance extends EventTarget {
 /** @category Performance */
 declare var performance: Performance;
 
+/** Whether `SharedArrayBuffer`s can be created and shared with workers using
+ * `postMessage()`. This is always `true` in Deno.
+ *
+ * @category Web Workers
+ */
+declare var crossOriginIsolated: boolean;
+
 /** @category Performance */
 declare interface PerformanceMarkOptions {
   /** Metadata to be included in the mark. */
@@ -512,6 +512,16 @@ declare namespace Deno {
     parameters: Parameters;
     /** The current result of the callback. */
     result: Result;
+    /** When `true`, calls to the callback coming in from foreign threads are
+     * queued to run on the isolate thread and return immediately instead of
+     * blocking the calling thread until the callback has run. Arguments are
+     * copied when the call is queued, so pointers passed to the callback must
+     * stay valid until it runs.
+     *
+     * Only callbacks with a `"void"` result can be nonblocking.
+     *
+     * @default {false} */
+    nonblocking?: boolean;
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -783,6 +793,70 @@ declare namespace Deno {
     opt: T,
   ): Process<T>;
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Unstable options which can be set when calling {@linkcode Deno.Command}.
+   *
+   * @category Sub Process
+   */
+  export interface CommandOptions {
+    /** The permissions passed down to a spawned `deno` process, which can't
+     * be granted more than them. Set this to `"inherit"` to pass down all
+     * permissions of the current process, `"none"` to revoke all
+     * permissions, or narrow them down using a
+     * {@linkcode PermissionOptionsObject}.
+     *
+     * If the spawned process is run with permission flags, they must be
+     * within the passed down permissions. Otherwise all passed down
+     * permissions are granted.
+     *
+     * ```ts
+     * const command = new Deno.Command(Deno.execPath(), {
+     *   args: ["run", "worker.ts"],
+     *   permissions: { read: ["./data"], net: false },
+     * });
+     * ```
+     */
+    permissions?: PermissionOptions;
+    /** Attach the child process to a new pseudo-terminal of the given size,
+     * or of 80 columns and 24 rows if set to `true`. The terminal becomes the
+     * controlling terminal of the child, so it behaves like it was started
+     * interactively, eg. it prints colors and prompts.
+     *
+     * The `stdin`, `stdout` and `stderr` options are ignored. Input written to
+     * {@linkcode Deno.ChildProcess.stdin} is sent to the terminal, and
+     * everything the child prints to the terminal, from both stdout and
+     * stderr, can be read from {@linkcode Deno.ChildProcess.stdout}. The
+     * terminal processes input and output like a real one, eg. it echoes the
+     * input and translates `"\n"` to `"\r\n"` in the output.
+     *
+     * Only supported by {@linkcode Deno.Command.spawn} and
+     * {@linkcode Deno.Command.output}, and not on Windows.
+     *
+     * ```ts
+     * const child = new Deno.Command("bash", {
+     *   pty: { columns: 120, rows: 40 },
+     * }).spawn();
+     * child.stdout.pipeTo(Deno.stdout.writable);
+     * child.resizePty({ columns: 100, rows: 30 });
+     * ```
+     */
+    pty?: boolean | { columns: number; rows: number };
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Unstable methods of a child process spawned with
+   * {@linkcode Deno.Command.spawn}.
+   *
+   * @category Sub Process
+   */
+  export interface ChildProcess {
+    /** Resizes the pseudo-terminal of a child process spawned with the `pty`
+     * option. The child is sent a `SIGWINCH` signal. */
+    resizePty(size: { columns: number; rows: number }): void;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * A custom `HttpClient` for use with {@linkcode fetch} function. This is
@@ -835,6 +909,12 @@ declare namespace Deno {
     url: string;
     /** The basic auth credentials to be used against the proxy server. */
     basicAuth?: BasicAuth;
+    /** Hosts that are connected to directly instead of through the proxy.
+     *
+     * Like with the `NO_PROXY` environment variable, `"*"` matches every host
+     * and `"example.com"` matches the domain and all its subdomains. An entry
+     * with a port, like `"localhost:8080"`, only matches that port. */
+    noProxy?: string[];
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -870,76 +950,20 @@ declare namespace Deno {
    * const response = await fetch("https://myserver.com", { client });
    * ```
    *
+   * @example ```ts
+   * const client = Deno.createHttpClient({
+   *   proxy: { url: "http://myproxy.com:8080", noProxy: ["localhost"] }
+   * });
+   * // Doesn't go through the proxy.
+   * const response = await fetch("http://localhost:8000", { client });
+   * ```
+   *
    * @category Fetch API
    */
   export function createHttpClient(
     options: CreateHttpClientOptions,
   ): HttpClient;
 
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * Represents membership of a IPv4 multicast group.
-   *
-   * @category Network
-   */
-  interface MulticastV4Membership {
-    /** Leaves the multicast group. */
-    leave: () => Promise<void>;
-    /** Sets the multicast loopback option. If enabled, multicast packets will be looped back to the local socket. */
-    setLoopback: (loopback: boolean) => Promise<void>;
-    /** Sets the time-to-live of outgoing multicast packets for this socket. */
-    setTTL: (ttl: number) => Promise<void>;
-  }
-
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * Represents membership of a IPv6 multicast group.
-   *
-   * @category Network
-   */
-  interface MulticastV6Membership {
-    /** Leaves the multicast group. */
-    leave: () => Promise<void>;
-    /** Sets the multicast loopback option. If enabled, multicast packets will be looped back to the local socket. */
-    setLoopback: (loopback: boolean) => Promise<void>;
-  }
-
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * A generic transport listener for message-oriented protocols.
-   *
-   * @category Network
-   */
-  export interface DatagramConn extends AsyncIterable<[Uint8Array, Addr]> {
-    /** Joins an IPv4 multicast group. */
-    joinMulticastV4(
-      address: string,
-      networkInterface: string,
-    ): Promise<MulticastV4Membership>;
-
-    /** Joins an IPv6 multicast group. */
-    joinMulticastV6(
-      address: string,
-      networkInterface: number,
-    ): Promise<MulticastV6Membership>;
-
-    /** Waits for and resolves to the next message to the instance.
-     *
-     * Messages are received in the format of a tuple containing the data array
-     * and the address information.
-     */
-    receive(p?: Uint8Array): Promise<[Uint8Array, Addr]>;
-    /** Sends a message to the target via the connection. The method resolves
-     * with the number of bytes sent. */
-    send(p: Uint8Array, addr: Addr): Promise<number>;
-    /** Close closes the socket. Any pending message promises will be rejected
-     * with errors. */
-    close(): void;
-    /** Return the address of the instance. */
-    readonly addr: Addr;
-    [Symbol.asyncIterator](): AsyncIterableIterator<[Uint8Array, Addr]>;
-  }
-
   /**
    * @category Network
    */
@@ -958,81 +982,6 @@ declare namespace Deno {
     reusePort?: boolean;
   }
 
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * Unstable options which can be set when opening a Unix listener via
-   * {@linkcode Deno.listen} or {@linkcode Deno.listenDatagram}.
-   *
-   * @category Network
-   */
-  export interface UnixListenOptions {
-    /** A path to the Unix Socket. */
-    path: string;
-  }
-
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * Unstable options which can be set when opening a datagram listener via
-   * {@linkcode Deno.listenDatagram}.
-   *
-   * @category Network
-   */
-  export interface UdpListenOptions extends ListenOptions {
-    /** When `true` the specified address will be reused, even if another
-     * process has already bound a socket on it. This effectively steals the
-     * socket from the listener.
-     *
-     * @default {false} */
-    reuseAddress?: boolean;
-
-    /** When `true`, sent multicast packets will be looped back to the local socket.
-     *
-     * @default {false} */
-    loopback?: boolean;
-  }
-
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * Listen announces on the local transport address.
-   *
-   * ```ts
-   * const listener = Deno.listen({ path: "/foo/bar.sock", transport: "unix" })
-   * ```
-   *
-   * Requires `allow-read` and `allow-write` permission.
-   *
-   * @tags allow-read, allow-write
-   * @category Network
-   */
-  export function listen(
-    options: UnixListenOptions & { transport: "unix" },
-  ): Listener;
-
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * Listen announces on the local transport address.
-   *
-   * ```ts
-   * const listener1 = Deno.listenDatagram({
-   *   port: 80,
-   *   transport: "udp"
-   * });
-   * const listener2 = Deno.listenDatagram({
-   *   hostname: "golang.org",
-   *   port: 80,
-   *   transport: "udp"
-   * });
-   * ```
-   *
-   * Requires `allow-net` permission.
-   *
-   * @tags allow-net
-   * @category Network
-   */
-  export function listenDatagram(
-    options: UdpListenOptions & { transport: "udp" },
-  ): DatagramConn;
-
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Listen announces on the local transport address.
@@ -1044,63 +993,15 @@ declare namespace Deno {
    * });
    * ```
    *
-   * Requires `allow-read` and `allow-write` permission.
+   * Requires `allow-unix` permission.
    *
-   * @tags allow-read, allow-write
+   * @tags allow-unix
    * @category Network
    */
   export function listenDatagram(
     options: UnixListenOptions & { transport: "unixpacket" },
   ): DatagramConn;
 
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * @category Network
-   */
-  export interface UnixConnectOptions {
-    transport: "unix";
-    path: string;
-  }
-
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * Connects to the hostname (default is "127.0.0.1") and port on the named
-   * transport (default is "tcp"), and resolves to the connection (`Conn`).
-   *
-   * ```ts
-   * const conn1 = await Deno.connect({ port: 80 });
-   * const conn2 = await Deno.connect({ hostname: "192.0.2.1", port: 80 });
-   * const conn3 = await Deno.connect({ hostname: "[2001:db8::1]", port: 80 });
-   * const conn4 = await Deno.connect({ hostname: "golang.org", port: 80, transport: "tcp" });
-   * const conn5 = await Deno.connect({ path: "/foo/bar.sock", transport: "unix" });
-   * ```
-   *
-   * Requires `allow-net` permission for "tcp" and `allow-read` for "unix".
-   *
-   * @tags allow-net, allow-read
-   * @category Network
-   */
-  export function connect(options: ConnectOptions): Promise<TcpConn>;
-  /** **UNSTABLE**: New API, yet to be vetted.
-   *
-   * Connects to the hostname (default is "127.0.0.1") and port on the named
-   * transport (default is "tcp"), and resolves to the connection (`Conn`).
-   *
-   * ```ts
-   * const conn1 = await Deno.connect({ port: 80 });
-   * const conn2 = await Deno.connect({ hostname: "192.0.2.1", port: 80 });
-   * const conn3 = await Deno.connect({ hostname: "[2001:db8::1]", port: 80 });
-   * const conn4 = await Deno.connect({ hostname: "golang.org", port: 80, transport: "tcp" });
-   * const conn5 = await Deno.connect({ path: "/foo/bar.sock", transport: "unix" });
-   * ```
-   *
-   * Requires `allow-net` permission for "tcp" and `allow-read` for "unix".
-   *
-   * @tags allow-net, allow-read
-   * @category Network
-   */
-  export function connect(options: UnixConnectOptions): Promise<UnixConn>;
-
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * @category Network
@@ -1123,8 +1024,33 @@ declare namespace Deno {
      * TLS handshake.
      */
     alpnProtocols?: string[];
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Called once the TLS handshake completed, with the DER encoded
+     * certificate chain presented by the server (starting with its own
+     * certificate) and the hostname that was connected to. The connection is
+     * closed and rejected with {@linkcode Deno.errors.InvalidData} unless it
+     * returns or resolves to `true`.
+     *
+     * This runs in addition to the default certificate verification, so it
+     * can be used to pin certificates but not to accept certificates that
+     * would otherwise be rejected. Use `caCerts` to trust additional roots.
+     */
+    verifyPeerCertificate?: VerifyPeerCertificate;
   }
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A custom check of the certificate chain presented by the peer of a TLS
+   * connection. See {@linkcode Deno.ConnectTlsOptions.verifyPeerCertificate}.
+   *
+   * @category Network
+   */
+  export type VerifyPeerCertificate = (
+    certificates: Uint8Array[],
+    hostname: string,
+  ) => boolean | Promise<boolean>;
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * @category Network
@@ -1191,6 +1117,16 @@ declare namespace Deno {
    * @category Network
    */
   export interface StartTlsOptions {
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * PEM formatted client certificate chain.
+     */
+    certChain?: string;
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * PEM formatted (RSA or PKCS8) private key of client certificate.
+     */
+    privateKey?: string;
     /** **UNSTABLE**: New API, yet to be vetted.
      *
      * Application-Layer Protocol Negotiation (ALPN) protocols to announce to
@@ -1198,6 +1134,13 @@ declare namespace Deno {
      * TLS handshake.
      */
     alpnProtocols?: string[];
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Called once the TLS handshake completed to check the certificate chain
+     * presented by the server. See
+     * {@linkcode Deno.ConnectTlsOptions.verifyPeerCertificate}.
+     */
+    verifyPeerCertificate?: VerifyPeerCertificate;
   }
 
   /** **UNSTABLE**: New API, yet to be vetted.
@@ -1273,6 +1216,20 @@ declare namespace Deno {
     /** Sets `SO_REUSEPORT` on POSIX systems. */
     reusePort?: boolean;
 
+    /** Negotiate HTTP/2 with clients over TLS using ALPN. HTTP/1.1 is always
+     * available as well. Stream priorities sent by clients are not used to
+     * schedule responses.
+     *
+     * @default {true} */
+    http2?: boolean;
+
+    /** Accept cleartext HTTP/2 connections ("h2c") from clients with prior
+     * knowledge, e.g. gRPC clients behind a TLS terminating load balancer.
+     * Upgrading an HTTP/1.1 connection to h2c is not supported.
+     *
+     * @default {false} */
+    h2c?: boolean;
+
     /** The handler to invoke when route handlers throw an error. */
     onError?: (error: unknown) => Response | Promise<Response>;
 
@@ -1294,6 +1251,31 @@ declare namespace Deno {
     key: string;
   }
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options which are used when serving over a Unix socket instead of a TCP
+   * port. Requires `allow-unix` permission.
+   *
+   * ```ts
+   * Deno.serve({ path: "/run/app.sock" }, (_req) => new Response("Hello, world"));
+   * ```
+   *
+   * @category HTTP Server
+   */
+  export interface ServeUnixOptions {
+    /** The path to the Unix socket to listen on. */
+    path: string;
+
+    /** An {@linkcode AbortSignal} to close the server and all connections. */
+    signal?: AbortSignal;
+
+    /** The handler to invoke when route handlers throw an error. */
+    onError?: (error: unknown) => Response | Promise<Response>;
+
+    /** The callback which is called when the server starts listening. */
+    onListen?: (params: { path: string }) => void;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * @category HTTP Server
@@ -1303,6 +1285,44 @@ declare namespace Deno {
     handler: ServeHandler;
   }
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options for {@linkcode Deno.HttpServer.shutdown}.
+   *
+   * @category HTTP Server
+   */
+  export interface HttpServerShutdownOptions {
+    /** The number of milliseconds to wait for requests in flight to be
+     * answered. Connections that are still open afterwards are closed. By
+     * default there is no time limit. */
+    timeout?: number;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A server returned by {@linkcode Deno.serve}. Awaiting it waits until the
+   * server is finished, like awaiting {@linkcode HttpServer.finished}.
+   *
+   * @category HTTP Server
+   */
+  export interface HttpServer extends PromiseLike<void> {
+    /** A promise that settles once the server stopped serving requests, and
+     * rejects if it failed to start or serve them. */
+    readonly finished: Promise<void>;
+
+    /** Gracefully shuts down the server. It stops listening for connections
+     * and accepting new requests right away, and resolves once the requests
+     * in flight are answered and all connections are closed. */
+    shutdown(options?: HttpServerShutdownOptions): Promise<void>;
+
+    /** Make the server block the event loop from finishing, which is the
+     * default. */
+    ref(): void;
+
+    /** Make the server not block the event loop from finishing. */
+    unref(): void;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Serves HTTP requests with the given handler.
@@ -1326,7 +1346,7 @@ declare namespace Deno {
    * You can stop the server with an {@linkcode AbortSignal}. The abort signal
    * needs to be passed as the `signal` option in the options bag. The server
    * aborts when the abort signal is aborted. To wait for the server to close,
-   * await the promise returned from the `Deno.serve` API.
+   * await the server returned from the `Deno.serve` API.
    *
    * ```ts
    * const ac = new AbortController();
@@ -1338,6 +1358,19 @@ declare namespace Deno {
    * ac.abort();
    * ```
    *
+   * Aborting the signal closes the open connections right away. To let the
+   * requests in flight finish first, for example during a rolling restart,
+   * use `shutdown()` instead. An optional timeout limits how long it waits.
+   *
+   * ```ts
+   * const server = Deno.serve((_req) => new Response("Hello, world"));
+   *
+   * Deno.addSignalListener("SIGTERM", async () => {
+   *   await server.shutdown({ timeout: 10_000 });
+   *   Deno.exit();
+   * });
+   * ```
+   *
    * By default `Deno.serve` prints the message
    * `Listening on http://<hostname>:<port>/` on listening. If you like to
    * change this behavior, you can specify a custom `onListen` callback.
@@ -1362,7 +1395,7 @@ declare namespace Deno {
    *
    * @category HTTP Server
    */
-  export function serve(handler: ServeHandler): Promise<void>;
+  export function serve(handler: ServeHandler): HttpServer;
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Serves HTTP requests with the given handler.
@@ -1386,7 +1419,7 @@ declare namespace Deno {
    * You can stop the server with an {@linkcode AbortSignal}. The abort signal
    * needs to be passed as the `signal` option in the options bag. The server
    * aborts when the abort signal is aborted. To wait for the server to close,
-   * await the promise returned from the `Deno.serve` API.
+   * await the server returned from the `Deno.serve` API.
    *
    * ```ts
    * const ac = new AbortController();
@@ -1398,6 +1431,19 @@ declare namespace Deno {
    * ac.abort();
    * ```
    *
+   * Aborting the signal closes the open connections right away. To let the
+   * requests in flight finish first, for example during a rolling restart,
+   * use `shutdown()` instead. An optional timeout limits how long it waits.
+   *
+   * ```ts
+   * const server = Deno.serve((_req) => new Response("Hello, world"));
+   *
+   * Deno.addSignalListener("SIGTERM", async () => {
+   *   await server.shutdown({ timeout: 10_000 });
+   *   Deno.exit();
+   * });
+   * ```
+   *
    * By default `Deno.serve` prints the message
    * `Listening on http://<hostname>:<port>/` on listening. If you like to
    * change this behavior, you can specify a custom `onListen` callback.
@@ -1423,9 +1469,9 @@ declare namespace Deno {
    * @category HTTP Server
    */
   export function serve(
-    options: ServeOptions | ServeTlsOptions,
+    options: ServeOptions | ServeTlsOptions | ServeUnixOptions,
     handler: ServeHandler,
-  ): Promise<void>;
+  ): HttpServer;
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Serves HTTP requests with the given handler.
@@ -1449,7 +1495,7 @@ declare namespace Deno {
    * You can stop the server with an {@linkcode AbortSignal}. The abort signal
    * needs to be passed as the `signal` option in the options bag. The server
    * aborts when the abort signal is aborted. To wait for the server to close,
-   * await the promise returned from the `Deno.serve` API.
+   * await the server returned from the `Deno.serve` API.
    *
    * ```ts
    * const ac = new AbortController();
@@ -1461,6 +1507,19 @@ declare namespace Deno {
    * ac.abort();
    * ```
    *
+   * Aborting the signal closes the open connections right away. To let the
+   * requests in flight finish first, for example during a rolling restart,
+   * use `shutdown()` instead. An optional timeout limits how long it waits.
+   *
+   * ```ts
+   * const server = Deno.serve((_req) => new Response("Hello, world"));
+   *
+   * Deno.addSignalListener("SIGTERM", async () => {
+   *   await server.shutdown({ timeout: 10_000 });
+   *   Deno.exit();
+   * });
+   * ```
+   *
    * By default `Deno.serve` prints the message
    * `Listening on http://<hostname>:<port>/` on listening. If you like to
    * change this behavior, you can specify a custom `onListen` callback.
@@ -1486,8 +1545,8 @@ declare namespace Deno {
    * @category HTTP Server
    */
   export function serve(
-    options: ServeInit & (ServeOptions | ServeTlsOptions),
-  ): Promise<void>;
+    options: ServeInit & (ServeOptions | ServeTlsOptions | ServeUnixOptions),
+  ): HttpServer;
 
   /** **UNSTABLE**: New API, yet to be vetted.
    *
@@ -1517,6 +1576,82 @@ declare namespace Deno {
     request: Request,
   ): Promise<[Deno.Conn, Uint8Array]>;
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Synchronously writes a V8 heap snapshot of the current isolate to the
+   * given file and returns its path. It can be loaded in the "Memory" tab of
+   * the Chrome DevTools to find what is holding on to memory. Without a path,
+   * the snapshot is written to a file named like
+   * `Heap.<timestamp>.<pid>.heapsnapshot` in the current directory.
+   *
+   * Taking a snapshot blocks the process and temporarily needs about as much
+   * memory as the heap itself. For example, to write a snapshot of a running
+   * server on demand:
+   *
+   * ```ts
+   * Deno.addSignalListener("SIGUSR2", () => {
+   *   console.log(`Wrote ${Deno.writeHeapSnapshot()}`);
+   * });
+   * ```
+   *
+   * Requires `allow-write` permission.
+   *
+   * @tags allow-write
+   * @category Runtime Environment
+   */
+  export function writeHeapSnapshot(path?: string): string;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Registers a handler that is run in the current process on the given cron
+   * schedule, until the optional abort signal is aborted. The returned promise
+   * resolves once it is.
+   *
+   * The schedule uses the five standard cron fields (minute, hour, day of
+   * month, month and day of week) and is evaluated in UTC. Fields support
+   * `*`, single values, ranges like `1-5`, steps like `*\/15` and lists
+   * like `1,15`. When both the day of month and the day of week are
+   * restricted, a day matching either of them runs the handler.
+   *
+   * The name identifies the cron job and must be unique in the process. If
+   * the previous run of the handler didn't finish yet when the schedule is
+   * due again, that run is skipped.
+   *
+   * ```ts
+   * Deno.cron("cleanup", "0 * * * *", async () => {
+   *   await Deno.remove("./tmp", { recursive: true });
+   * });
+   * ```
+   *
+   * @category Cron
+   */
+  export function cron(
+    name: string,
+    schedule: string,
+    handler: () => Promise<void> | void,
+  ): Promise<void>;
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Registers a handler that is run in the current process on the given cron
+   * schedule, until the abort signal of the options is aborted. The returned
+   * promise resolves once it is.
+   *
+   * ```ts
+   * const controller = new AbortController();
+   * Deno.cron("report", "*\/5 * * * *", { signal: controller.signal }, () => {
+   *   console.log("Runs every five minutes");
+   * });
+   * ```
+   *
+   * @category Cron
+   */
+  export function cron(
+    name: string,
+    schedule: string,
+    options: { signal?: AbortSignal },
+    handler: () => Promise<void> | void,
+  ): Promise<void>;
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Open a new {@linkcode Deno.Kv} connection to persist data.
@@ -2049,6 +2184,32 @@ declare namespace Deno {
      */
     atomic(): AtomicOperation;
 
+    /**
+     * Watch for changes to the given keys. The returned stream first emits the
+     * current entries of all keys, and then emits them again every time the
+     * value of at least one of the keys changed. The entries are emitted in
+     * the same order as the keys, in the form of a
+     * {@linkcode Deno.KvEntryMaybe}.
+     *
+     * Changes that happen in quick succession may be coalesced, so not every
+     * intermediate value is guaranteed to be observed. Changes made by other
+     * processes using the same database file are noticed with a small delay.
+     *
+     * A maximum of 10 keys can be watched at once. The stream ends once the
+     * database is closed.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * const stream = db.watch([["counter"]]);
+     * for await (const [entry] of stream) {
+     *   console.log(entry.value);
+     * }
+     * ```
+     */
+    watch<T extends readonly unknown[]>(
+      keys: readonly [...{ [K in keyof T]: KvKey }],
+    ): ReadableStream<{ [K in keyof T]: KvEntryMaybe<T[K]> }>;
+
     /**
      * Close the database connection. This will prevent any further operations
      * from being performed on the database, but will wait for any in-flight
@@ -2104,6 +2265,11 @@ declare interface WorkerOptions {
    * - A list of routes can be provided that are relative to the file the worker
    *   is created in to limit the access of the worker (read/write permissions
    *   only)
+   * - Any other string references a named preset from the `"workers"` section
+   *   of the configuration file
+   *
+   * When not specified, the default permissions from the `"workers"` section
+   * of the configuration file are used if present.
    *
    * Example:
    *
@@ -2123,7 +2289,12 @@ declare interface WorkerOptions {
    */
   deno?: {
     /** Set to `"none"` to disable all the permissions in the worker. */
-    permissions?: Deno.PermissionOptions;
+    permissions?: Deno.PermissionOptions | string;
+    /** Modules that are evaluated in order in the worker before its main
+     * module, after any modules from the `"workers.preload"` setting of the
+     * configuration file. Relative specifiers are resolved like the specifier
+     * of the worker. */
+    preload?: string[];
   };
 }
 
@@ -3,6 +3,7 @@
 use crate::args::TsConfig;
 use crate::args::TypeCheckMode;
 use crate::cache::FastInsecureHasher;
+use crate::graph_util::is_asset_assert_type;
 use crate::node;
 use crate::util::checksum;
 use crate::util::path::mapped_specifier_for_tsc;
@@ -442,6 +443,18 @@ fn op_load(state: &mut OpState, args: Value) -> Result<Value, AnyError> {
     hash = Some("1".to_string());
     media_type = MediaType::Dts;
     Some(Cow::Borrowed("declare const __: any;\nexport = __;\n"))
+  } else if &v.specifier == "internal:///text_module.d.ts" {
+    hash = Some("1".to_string());
+    media_type = MediaType::Dts;
+    Some(Cow::Borrowed(
+      "declare const text: string;\nexport default text;\n",
+    ))
+  } else if &v.specifier == "internal:///bytes_module.d.ts" {
+    hash = Some("1".to_string());
+    media_type = MediaType::Dts;
+    Some(Cow::Borrowed(
+      "declare const bytes: Uint8Array;\nexport default bytes;\n",
+    ))
   } else if let Some(name) = v.specifier.strip_prefix("asset:///") {
     let maybe_source = get_lazily_loaded_asset(name);
     hash = get_maybe_hash(maybe_source, state.hash_data);
@@ -556,11 +569,26 @@ fn op_resolve(
     }
 
     let graph = &state.graph;
-    let resolved_dep = graph
+    let maybe_dep = graph
       .get(&referrer)
       .and_then(|m| m.esm())
-      .and_then(|m| m.dependencies.get(&specifier))
-      .and_then(|d| d.maybe_type.ok().or_else(|| d.maybe_code.ok()));
+      .and_then(|m| m.dependencies.get(&specifier));
+
+    // assets imported with a `text` or `bytes` import assertion are typed by
+    // their assertion instead of their contents
+    if let Some(assert_type) = maybe_dep
+      .and_then(|d| d.maybe_assert_type.as_deref())
+      .filter(|assert_type| is_asset_assert_type(assert_type))
+    {
+      resolved.push((
+        format!("internal:///{assert_type}_module.d.ts"),
+        ".d.ts".to_string(),
+      ));
+      continue;
+    }
+
+    let resolved_dep =
+      maybe_dep.and_then(|d| d.maybe_type.ok().or_else(|| d.maybe_code.ok()));
 
     let maybe_result = match resolved_dep {
       Some(ResolutionResolved { specifier, .. }) => {
@@ -1087,6 +1115,24 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn test_load_asset_module_declarations() {
+    let mut state = setup(None, None, None).await;
+    let actual = op_load::call(
+      &mut state,
+      json!({ "specifier": "internal:///bytes_module.d.ts"}),
+    )
+    .expect("should have invoked op");
+    assert_eq!(
+      actual,
+      json!({
+        "data": "declare const bytes: Uint8Array;\nexport default bytes;\n",
+        "version": "1",
+        "scriptKind": 3,
+      })
+    );
+  }
+
   #[tokio::test]
   async fn test_load_missing_specifier() {
     let mut state = setup(None, None, None).await;
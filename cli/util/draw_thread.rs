@@ -39,7 +39,9 @@ struct InternalEntry {
 struct InternalState {
   // this ensures only one actual draw thread is running
   drawer_id: usize,
-  hide: bool,
+  /// The number of callers hiding the draw thread, which is drawn again
+  /// once all of them show it.
+  hide_count: usize,
   has_draw_thread: bool,
   next_entry_id: u16,
   entries: Vec<InternalEntry>,
@@ -55,7 +57,7 @@ impl InternalState {
 static INTERNAL_STATE: Lazy<Arc<Mutex<InternalState>>> = Lazy::new(|| {
   Arc::new(Mutex::new(InternalState {
     drawer_id: 0,
-    hide: false,
+    hide_count: 0,
     has_draw_thread: false,
     entries: Vec::new(),
     next_entry_id: 0,
@@ -108,20 +110,21 @@ impl DrawThread {
     DrawThreadGuard(id)
   }
 
-  /// Hides the draw thread.
+  /// Hides the draw thread until [`DrawThread::show`] is called.
   pub fn hide() {
     let internal_state = &*INTERNAL_STATE;
     let mut internal_state = internal_state.lock();
-    internal_state.hide = true;
+    internal_state.hide_count += 1;
 
     Self::clear_and_stop_draw_thread(&mut internal_state);
   }
 
-  /// Shows the draw thread if it was previously hidden.
+  /// Shows the draw thread if it was previously hidden and nothing else is
+  /// hiding it.
   pub fn show() {
     let internal_state = &*INTERNAL_STATE;
     let mut internal_state = internal_state.lock();
-    internal_state.hide = false;
+    internal_state.hide_count = internal_state.hide_count.saturating_sub(1);
 
     Self::maybe_start_draw_thread(&mut internal_state);
   }
@@ -152,7 +155,7 @@ impl DrawThread {
 
   fn maybe_start_draw_thread(internal_state: &mut InternalState) {
     if internal_state.has_draw_thread
-      || internal_state.hide
+      || internal_state.hide_count > 0
       || internal_state.entries.is_empty()
       || !DrawThread::is_supported()
     {
@@ -1,12 +1,16 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::CliOptions;
 use crate::colors;
 use crate::util::fs::canonicalize_path;
+use crate::util::path::specifier_to_file_path;
 
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
+use deno_core::futures::future::join;
 use deno_core::futures::Future;
 use deno_runtime::fmt_errors::format_js_error;
+use deno_runtime::permissions::path_matches_glob;
 use log::info;
 use notify::event::Event as NotifyEvent;
 use notify::event::EventKind;
@@ -15,16 +19,18 @@ use notify::RecommendedWatcher;
 use notify::RecursiveMode;
 use notify::Watcher;
 use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::select;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::task::LocalSet;
 use tokio::time::sleep;
 
 const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
-const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(200);
+const DEFAULT_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(200);
 
 struct DebouncedReceiver {
   // The `recv()` call could be used in a tokio `select!` macro,
@@ -32,16 +38,20 @@ struct DebouncedReceiver {
   // lose items if a `recv()` never completes
   received_items: HashSet<PathBuf>,
   receiver: UnboundedReceiver<Vec<PathBuf>>,
+  debounce: Duration,
 }
 
 impl DebouncedReceiver {
-  fn new_with_sender() -> (Arc<mpsc::UnboundedSender<Vec<PathBuf>>>, Self) {
+  fn new_with_sender(
+    debounce: Duration,
+  ) -> (Arc<mpsc::UnboundedSender<Vec<PathBuf>>>, Self) {
     let (sender, receiver) = mpsc::unbounded_channel();
     (
       Arc::new(sender),
       Self {
         receiver,
         received_items: HashSet::new(),
+        debounce,
       },
     )
   }
@@ -58,7 +68,7 @@ impl DebouncedReceiver {
         items = self.receiver.recv() => {
           self.received_items.extend(items?);
         }
-        _ = sleep(DEBOUNCE_INTERVAL) => {
+        _ = sleep(self.debounce) => {
           return Some(self.received_items.drain().collect());
         }
       }
@@ -95,13 +105,22 @@ pub enum ResolutionResult<T> {
 async fn next_restart<R, T, F>(
   resolver: &mut R,
   debounced_receiver: &mut DebouncedReceiver,
+  watch_paths: &[PathBuf],
 ) -> (Vec<PathBuf>, Result<T, AnyError>)
 where
   R: FnMut(Option<Vec<PathBuf>>) -> F,
   F: Future<Output = ResolutionResult<T>>,
 {
   loop {
-    let changed = debounced_receiver.recv().await;
+    // The resolver doesn't know about the paths passed to `--watch`, so a
+    // change to one of them restarts from scratch like the first run.
+    let changed = debounced_receiver.recv().await.filter(|changed| {
+      !changed.iter().any(|path| {
+        watch_paths
+          .iter()
+          .any(|watch_path| path.starts_with(watch_path))
+      })
+    });
     match resolver(changed).await {
       ResolutionResult::Ignore => {
         log::debug!("File change ignored")
@@ -125,6 +144,111 @@ pub struct PrintConfig {
   pub job_name: String,
   /// determine whether to clear the terminal screen; applicable to TTY environments only.
   pub clear_screen: bool,
+  /// paths to watch in addition to the ones returned by the resolver.
+  pub watch_paths: Vec<PathBuf>,
+  /// globs of paths whose changes are ignored.
+  pub exclude: Vec<PathBuf>,
+  /// how long to wait for further changes before restarting.
+  pub debounce: Duration,
+  /// commands to run when restarting.
+  pub hooks: RestartHooks,
+}
+
+impl PrintConfig {
+  pub fn new(
+    job_name: &str,
+    cli_options: &CliOptions,
+  ) -> Result<Self, AnyError> {
+    let watch_config = cli_options.resolve_watch_config()?;
+    // Like tasks, hooks run in the directory of the config file.
+    let hooks_cwd = cli_options
+      .maybe_config_file_specifier()
+      .and_then(|specifier| specifier_to_file_path(&specifier).ok())
+      .and_then(|path| path.parent().map(ToOwned::to_owned))
+      .unwrap_or_else(|| cli_options.initial_cwd().to_path_buf());
+    Ok(Self {
+      job_name: job_name.to_string(),
+      clear_screen: !cli_options.no_clear_screen()
+        && watch_config.clear_screen.unwrap_or(true),
+      watch_paths: cli_options.extra_watch_paths(),
+      exclude: cli_options.watch_exclude(),
+      debounce: watch_config
+        .debounce
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_DEBOUNCE_INTERVAL),
+      hooks: RestartHooks {
+        before_restart: watch_config.before_restart,
+        after_restart: watch_config.after_restart,
+        cwd: hooks_cwd,
+      },
+    })
+  }
+}
+
+/// Commands of the `watch` configuration that run when the watcher restarts
+/// the operation. They don't run for the first run.
+#[derive(Clone, Debug, Default)]
+pub struct RestartHooks {
+  /// runs to completion before the operation restarts.
+  pub before_restart: Option<String>,
+  /// runs alongside the restarted operation.
+  pub after_restart: Option<String>,
+  pub cwd: PathBuf,
+}
+
+impl RestartHooks {
+  async fn run_around<F>(&self, restarted: bool, operation: F)
+  where
+    F: Future<Output = ()>,
+  {
+    if !restarted {
+      return operation.await;
+    }
+    run_hook("beforeRestart", self.before_restart.as_deref(), &self.cwd).await;
+    join(
+      operation,
+      run_hook("afterRestart", self.after_restart.as_deref(), &self.cwd),
+    )
+    .await;
+  }
+}
+
+async fn run_hook(name: &str, command: Option<&str>, cwd: &Path) {
+  let command = match command {
+    Some(command) => command,
+    None => return,
+  };
+  info!(
+    "{} Running {} hook: {}",
+    colors::intense_blue("Watcher"),
+    name,
+    command
+  );
+  let seq_list = match deno_task_shell::parser::parse(command) {
+    Ok(seq_list) => seq_list,
+    Err(err) => {
+      eprintln!(
+        "{}: Failed to parse the {} hook: {:#}",
+        colors::red_bold("error"),
+        name,
+        err
+      );
+      return;
+    }
+  };
+  let env_vars = std::env::vars().collect();
+  let local = LocalSet::new();
+  let future =
+    deno_task_shell::execute(seq_list, env_vars, cwd, Default::default());
+  let exit_code = local.run_until(future).await;
+  if exit_code != 0 {
+    eprintln!(
+      "{}: The {} hook exited with code {}",
+      colors::red_bold("error"),
+      name,
+      exit_code
+    );
+  }
 }
 
 fn create_print_after_restart_fn(clear_screen: bool) -> impl Fn() {
@@ -160,17 +284,22 @@ where
   F1: Future<Output = ResolutionResult<T>>,
   F2: Future<Output = Result<(), AnyError>>,
 {
-  let (sender, mut receiver) = DebouncedReceiver::new_with_sender();
-
   let PrintConfig {
     job_name,
     clear_screen,
+    watch_paths,
+    exclude,
+    debounce,
+    hooks,
   } = print_config;
 
+  let (sender, mut receiver) = DebouncedReceiver::new_with_sender(debounce);
+
   // Store previous data. If module resolution fails at some point, the watcher will try to
   // continue watching files using these data.
   let mut paths_to_watch;
   let mut resolution_result;
+  let mut restarted = false;
 
   let print_after_restart = create_print_after_restart_fn(clear_screen);
 
@@ -187,9 +316,11 @@ where
         colors::intense_blue("Watcher"),
       );
 
-      let (paths, result) = next_restart(&mut resolver, &mut receiver).await;
+      let (paths, result) =
+        next_restart(&mut resolver, &mut receiver, &watch_paths).await;
       paths_to_watch = paths;
       resolution_result = result;
+      restarted = true;
 
       print_after_restart();
     }
@@ -209,18 +340,21 @@ where
   info!("{} {} started.", colors::intense_blue("Watcher"), job_name,);
 
   loop {
-    let mut watcher = new_watcher(sender.clone())?;
+    let mut watcher = new_watcher(sender.clone(), exclude.clone())?;
     add_paths_to_watcher(&mut watcher, &paths_to_watch);
+    add_paths_to_watcher(&mut watcher, &watch_paths);
 
     match resolution_result {
       Ok(operation_arg) => {
-        let fut = error_handler(operation(operation_arg));
+        let fut =
+          hooks.run_around(restarted, error_handler(operation(operation_arg)));
         select! {
-          (paths, result) = next_restart(&mut resolver, &mut receiver) => {
+          (paths, result) = next_restart(&mut resolver, &mut receiver, &watch_paths) => {
             if result.is_ok() {
               paths_to_watch = paths;
             }
             resolution_result = result;
+            restarted = true;
 
             print_after_restart();
             continue;
@@ -244,11 +378,13 @@ where
       }
     }
 
-    let (paths, result) = next_restart(&mut resolver, &mut receiver).await;
+    let (paths, result) =
+      next_restart(&mut resolver, &mut receiver, &watch_paths).await;
     if result.is_ok() {
       paths_to_watch = paths;
     }
     resolution_result = result;
+    restarted = true;
 
     print_after_restart();
 
@@ -260,7 +396,8 @@ where
 ///
 /// - `operation` is the actual operation we want to run every time the watcher detects file
 /// changes. For example, in the case where we would like to bundle, then `operation` would
-/// have the logic for it like bundling the code.
+/// have the logic for it like bundling the code. It receives the files that changed since
+/// the last run, which is `None` for the first run.
 pub async fn watch_func2<T: Clone, O, F>(
   mut paths_to_watch_receiver: UnboundedReceiver<Vec<PathBuf>>,
   mut operation: O,
@@ -268,17 +405,22 @@ pub async fn watch_func2<T: Clone, O, F>(
   print_config: PrintConfig,
 ) -> Result<(), AnyError>
 where
-  O: FnMut(T) -> Result<F, AnyError>,
+  O: FnMut(T, Option<Vec<PathBuf>>) -> Result<F, AnyError>,
   F: Future<Output = Result<(), AnyError>>,
 {
-  let (watcher_sender, mut watcher_receiver) =
-    DebouncedReceiver::new_with_sender();
-
+  // The paths passed to `--watch` are sent through `paths_to_watch_receiver`.
   let PrintConfig {
     job_name,
     clear_screen,
+    watch_paths: _,
+    exclude,
+    debounce,
+    hooks,
   } = print_config;
 
+  let (watcher_sender, mut watcher_receiver) =
+    DebouncedReceiver::new_with_sender(debounce);
+
   let print_after_restart = create_print_after_restart_fn(clear_screen);
 
   info!("{} {} started.", colors::intense_blue("Watcher"), job_name,);
@@ -303,6 +445,7 @@ where
     }
   }
 
+  let mut changed_paths = None;
   loop {
     // We may need to give the runtime a tick to settle, as cancellations may need to propagate
     // to tasks. We choose yielding 10 times to the runtime as a decent heuristic. If watch tests
@@ -311,7 +454,7 @@ where
       tokio::task::yield_now().await;
     }
 
-    let mut watcher = new_watcher(watcher_sender.clone())?;
+    let mut watcher = new_watcher(watcher_sender.clone(), exclude.clone())?;
     consume_paths_to_watch(&mut watcher, &mut paths_to_watch_receiver);
 
     let receiver_future = async {
@@ -320,11 +463,16 @@ where
         add_paths_to_watcher(&mut watcher, &maybe_paths.unwrap());
       }
     };
-    let operation_future = error_handler(operation(operation_args.clone())?);
+    let restarted = changed_paths.is_some();
+    let operation_future = hooks.run_around(
+      restarted,
+      error_handler(operation(operation_args.clone(), changed_paths.take())?),
+    );
 
     select! {
       _ = receiver_future => {},
-      _ = watcher_receiver.recv() => {
+      paths = watcher_receiver.recv() => {
+        changed_paths = paths;
         print_after_restart();
         continue;
       },
@@ -347,7 +495,8 @@ where
     };
     select! {
       _ = receiver_future => {},
-      _ = watcher_receiver.recv() => {
+      paths = watcher_receiver.recv() => {
+        changed_paths = paths;
         print_after_restart();
         continue;
       },
@@ -357,6 +506,7 @@ where
 
 fn new_watcher(
   sender: Arc<mpsc::UnboundedSender<Vec<PathBuf>>>,
+  exclude: Vec<PathBuf>,
 ) -> Result<RecommendedWatcher, AnyError> {
   let watcher = Watcher::new(
     move |res: Result<NotifyEvent, NotifyError>| {
@@ -369,8 +519,13 @@ fn new_watcher(
             .paths
             .iter()
             .filter_map(|path| canonicalize_path(path).ok())
-            .collect();
-          sender.send(paths).unwrap();
+            .filter(|path| {
+              !exclude.iter().any(|glob| path_matches_glob(glob, path))
+            })
+            .collect::<Vec<_>>();
+          if !paths.is_empty() {
+            sender.send(paths).unwrap();
+          }
         }
       }
     },
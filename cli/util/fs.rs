@@ -399,6 +399,117 @@ pub fn hard_link_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
   Ok(())
 }
 
+/// Clones the files in one directory to another directory with copy-on-write,
+/// which needs a file system that supports it (ex. btrfs, XFS or APFS).
+///
+/// Note: Does not handle symlinks.
+pub fn reflink_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
+  link_dir_recursive(from, to, "Reflinking", &reflink_file)
+}
+
+/// Symlinks the files in one directory to another directory. The folders are
+/// created instead of symlinked.
+///
+/// Note: Does not handle symlinks.
+pub fn symlink_files_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
+  link_dir_recursive(from, to, "Symlinking", &symlink_file)
+}
+
+fn link_dir_recursive(
+  from: &Path,
+  to: &Path,
+  action: &str,
+  link_file: &dyn Fn(&Path, &Path) -> std::io::Result<()>,
+) -> Result<(), AnyError> {
+  std::fs::create_dir_all(to)
+    .with_context(|| format!("Creating {}", to.display()))?;
+  let read_dir = std::fs::read_dir(from)
+    .with_context(|| format!("Reading {}", from.display()))?;
+
+  for entry in read_dir {
+    let entry = entry?;
+    let file_type = entry.file_type()?;
+    let new_from = from.join(entry.file_name());
+    let new_to = to.join(entry.file_name());
+
+    if file_type.is_dir() {
+      link_dir_recursive(&new_from, &new_to, action, link_file).with_context(
+        || format!("Dir {} to {}", new_from.display(), new_to.display()),
+      )?;
+    } else if file_type.is_file() {
+      link_file(&new_from, &new_to).with_context(|| {
+        format!("{} {} to {}", action, new_from.display(), new_to.display())
+      })?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Clones a file with copy-on-write, which fails when the platform or the
+/// file system doesn't support it.
+pub fn reflink_file(from: &Path, to: &Path) -> std::io::Result<()> {
+  #[cfg(target_os = "linux")]
+  {
+    use std::os::unix::io::AsRawFd;
+
+    // _IOW(0x94, 9, int) from linux/fs.h
+    const FICLONE: libc::c_ulong = 0x40049409;
+    let source = std::fs::File::open(from)?;
+    let dest = OpenOptions::new().write(true).create_new(true).open(to)?;
+    // SAFETY: both file descriptors are open for the duration of the call
+    let result = unsafe {
+      libc::ioctl(dest.as_raw_fd(), FICLONE as _, source.as_raw_fd())
+    };
+    if result == -1 {
+      let err = Error::last_os_error();
+      drop(dest);
+      let _ = std::fs::remove_file(to);
+      return Err(err);
+    }
+    dest.set_permissions(source.metadata()?.permissions())
+  }
+  #[cfg(target_os = "macos")]
+  {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    extern "C" {
+      fn clonefile(
+        src: *const libc::c_char,
+        dst: *const libc::c_char,
+        flags: u32,
+      ) -> libc::c_int;
+    }
+    let from = CString::new(from.as_os_str().as_bytes())?;
+    let to = CString::new(to.as_os_str().as_bytes())?;
+    // SAFETY: both paths are nul terminated strings
+    if unsafe { clonefile(from.as_ptr(), to.as_ptr(), 0) } == -1 {
+      return Err(Error::last_os_error());
+    }
+    Ok(())
+  }
+  #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+  {
+    let _ = (from, to);
+    Err(Error::new(
+      ErrorKind::Unsupported,
+      "copy-on-write clones are not supported on this platform",
+    ))
+  }
+}
+
+fn symlink_file(oldpath: &Path, newpath: &Path) -> std::io::Result<()> {
+  #[cfg(unix)]
+  {
+    std::os::unix::fs::symlink(oldpath, newpath)
+  }
+  #[cfg(not(unix))]
+  {
+    std::os::windows::fs::symlink_file(oldpath, newpath)
+  }
+}
+
 pub fn symlink_dir(oldpath: &Path, newpath: &Path) -> Result<(), AnyError> {
   let err_mapper = |err: Error| {
     Error::new(
@@ -438,6 +549,36 @@ pub fn dir_size(path: &Path) -> std::io::Result<u64> {
   Ok(total)
 }
 
+/// Gets the total size (in bytes) of the files of a directory that aren't
+/// hard linked from anywhere else, which is what removing the directory
+/// frees. Link counts are only available on unix, so elsewhere every file
+/// is counted.
+pub fn unlinked_files_size(path: &Path) -> std::io::Result<u64> {
+  let entries = std::fs::read_dir(path)?;
+  let mut total = 0;
+  for entry in entries {
+    let entry = entry?;
+    let metadata = entry.metadata()?;
+    if metadata.is_dir() {
+      total += unlinked_files_size(&entry.path())?;
+    } else if is_only_link(&metadata) {
+      total += metadata.len();
+    }
+  }
+  Ok(total)
+}
+
+#[cfg(unix)]
+fn is_only_link(metadata: &std::fs::Metadata) -> bool {
+  use std::os::unix::fs::MetadataExt;
+  metadata.nlink() == 1
+}
+
+#[cfg(not(unix))]
+fn is_only_link(_metadata: &std::fs::Metadata) -> bool {
+  true
+}
+
 struct LaxSingleProcessFsFlagInner {
   file_path: PathBuf,
   fs_file: std::fs::File,
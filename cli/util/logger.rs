@@ -1,6 +1,21 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use console_static_text::ansi::strip_ansi_codes;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+
+use crate::args::LogFormat;
+use crate::util::draw_thread::DrawThread;
+
+/// The target of the records of [`log_json_event`], which are already
+/// formatted as JSON.
+const JSON_EVENT_TARGET: &str = "deno::event";
+
+static JSON_FORMAT: AtomicBool = AtomicBool::new(false);
 
 struct CliLogger(env_logger::Logger);
 
@@ -21,7 +36,11 @@ impl log::Log for CliLogger {
 
   fn log(&self, record: &log::Record) {
     if self.enabled(record.metadata()) {
+      // clear the progress bars while writing, which are drawn again below
+      // the record, so that they don't garble it
+      DrawThread::hide();
       self.0.log(record);
+      DrawThread::show();
     }
   }
 
@@ -30,7 +49,40 @@ impl log::Log for CliLogger {
   }
 }
 
-pub fn init(maybe_level: Option<log::Level>) {
+/// Whether the diagnostics are output as JSON lines (`--log-format=json`).
+pub fn is_json_format() -> bool {
+  JSON_FORMAT.load(Ordering::Relaxed)
+}
+
+/// Logs a machine-readable event with the fields of `data`, which is only
+/// output when the diagnostics are output as JSON lines.
+pub fn log_json_event(event: &str, data: Value) {
+  if !is_json_format() {
+    return;
+  }
+  let mut line = json!({ "level": "info", "event": event });
+  if let (Some(line), Value::Object(data)) = (line.as_object_mut(), data) {
+    line.extend(data);
+  }
+  log::info!(target: JSON_EVENT_TARGET, "{}", line);
+}
+
+/// The JSON line of an error that ends the process.
+pub fn json_error_line(message: &str, data: Value) -> String {
+  let mut line = json!({
+    "level": "error",
+    "event": "error",
+    "message": strip_ansi_codes(message),
+  });
+  if let (Some(line), Value::Object(data)) = (line.as_object_mut(), data) {
+    line.extend(data);
+  }
+  line.to_string()
+}
+
+pub fn init(maybe_level: Option<log::Level>, log_format: LogFormat) {
+  let is_json = log_format == LogFormat::Json;
+  JSON_FORMAT.store(is_json, Ordering::Relaxed);
   let log_level = maybe_level.unwrap_or(log::Level::Info);
   let logger = env_logger::Builder::from_env(
     env_logger::Env::default()
@@ -44,7 +96,20 @@ pub fn init(maybe_level: Option<log::Level>) {
   // used to make available the lsp_debug which is then filtered out at runtime
   // in the cli logger
   .filter_module("deno::lsp::performance", log::LevelFilter::Debug)
-  .format(|buf, record| {
+  .format(move |buf, record| {
+    if is_json {
+      if record.target() == JSON_EVENT_TARGET {
+        return writeln!(buf, "{}", record.args());
+      }
+      let mut line = json!({
+        "level": record.level().as_str().to_lowercase(),
+        "message": strip_ansi_codes(&record.args().to_string()),
+      });
+      if record.level() > log::Level::Info {
+        line["target"] = record.target().into();
+      }
+      return writeln!(buf, "{}", line);
+    }
     let mut target = record.target().to_string();
     if let Some(line_no) = record.line() {
       target.push(':');
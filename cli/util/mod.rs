@@ -14,6 +14,7 @@ pub mod progress_bar;
 pub mod sync;
 pub mod text_encoding;
 pub mod time;
+pub mod trace;
 pub mod unix;
 pub mod v8;
 pub mod windows;
@@ -1,11 +1,13 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::SystemTime;
 
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json::json;
 use deno_runtime::ops::tty::ConsoleSize;
 
 use crate::colors;
@@ -17,17 +19,31 @@ use self::renderer::ProgressDataDisplayEntry;
 use super::draw_thread::DrawThread;
 use super::draw_thread::DrawThreadGuard;
 use super::draw_thread::DrawThreadRenderer;
+use super::logger;
 
 mod renderer;
 
 // Inspired by Indicatif, but this custom implementation allows
 // for more control over what's going on under the hood.
 
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Hides all progress output, while other diagnostics are still output
+/// (`--quiet-progress`).
+pub fn set_quiet(quiet: bool) {
+  QUIET.store(quiet, Ordering::Relaxed);
+}
+
+fn is_quiet() -> bool {
+  QUIET.load(Ordering::Relaxed)
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum ProgressMessagePrompt {
   Download,
   Blocking,
   Initialize,
+  Check,
 }
 
 impl ProgressMessagePrompt {
@@ -38,6 +54,16 @@ impl ProgressMessagePrompt {
       ProgressMessagePrompt::Initialize => {
         colors::green("Initialize").to_string()
       }
+      ProgressMessagePrompt::Check => colors::green("Check").to_string(),
+    }
+  }
+
+  fn as_json_kind(&self) -> &'static str {
+    match self {
+      ProgressMessagePrompt::Download => "download",
+      ProgressMessagePrompt::Blocking => "blocking",
+      ProgressMessagePrompt::Initialize => "initialize",
+      ProgressMessagePrompt::Check => "check",
     }
   }
 }
@@ -125,6 +151,9 @@ struct InternalState {
   start_time: SystemTime,
   keep_alive_count: usize,
   total_entries: usize,
+  /// The number of operations waiting to start, which are counted as pending
+  /// entries.
+  queued_count: usize,
   entries: Vec<ProgressBarEntry>,
 }
 
@@ -142,6 +171,7 @@ impl ProgressBarInner {
         start_time: SystemTime::now(),
         keep_alive_count: 0,
         total_entries: 0,
+        queued_count: 0,
         entries: Vec::new(),
       })),
       renderer,
@@ -184,6 +214,14 @@ impl ProgressBarInner {
     }
   }
 
+  fn increment_queued(&self) {
+    self.state.lock().queued_count += 1;
+  }
+
+  fn decrement_queued(&self) {
+    self.state.lock().queued_count -= 1;
+  }
+
   pub fn increment_clear(&self) {
     let mut internal_state = self.state.lock();
     internal_state.keep_alive_count += 1;
@@ -221,23 +259,22 @@ impl DrawThreadRenderer for ProgressBarInner {
       if state.entries.is_empty() {
         return String::new();
       }
-      let preferred_entry = state
-        .entries
-        .iter()
-        .find(|e| e.percent() > 0f64)
-        .or_else(|| state.entries.iter().last())
-        .unwrap();
+      let total_entries = state.total_entries + state.queued_count;
       ProgressData {
         duration: state.start_time.elapsed().unwrap(),
         terminal_width: size.cols,
-        pending_entries: state.entries.len(),
-        total_entries: state.total_entries,
-        display_entry: ProgressDataDisplayEntry {
-          prompt: preferred_entry.prompt,
-          message: preferred_entry.message.clone(),
-          position: preferred_entry.position(),
-          total_size: preferred_entry.total_size(),
-        },
+        pending_entries: state.entries.len() + state.queued_count,
+        total_entries,
+        display_entries: state
+          .entries
+          .iter()
+          .map(|entry| ProgressDataDisplayEntry {
+            prompt: entry.prompt,
+            message: entry.message.clone(),
+            position: entry.position(),
+            total_size: entry.total_size(),
+          })
+          .collect(),
         percent_done: {
           let mut total_percent_sum = 0f64;
           for entry in &state.entries {
@@ -245,7 +282,7 @@ impl DrawThreadRenderer for ProgressBarInner {
           }
           total_percent_sum +=
             (state.total_entries - state.entries.len()) as f64;
-          total_percent_sum / (state.total_entries as f64)
+          total_percent_sum / (total_entries as f64)
         },
       }
     };
@@ -286,6 +323,18 @@ impl ProgressBar {
     kind: ProgressMessagePrompt,
     msg: &str,
   ) -> UpdateGuard {
+    if is_quiet() {
+      return UpdateGuard { maybe_entry: None };
+    }
+    if logger::is_json_format() {
+      if !msg.is_empty() {
+        logger::log_json_event(
+          "progress",
+          json!({ "kind": kind.as_json_kind(), "message": msg }),
+        );
+      }
+      return UpdateGuard { maybe_entry: None };
+    }
     // only check if progress bars are supported once we go
     // to update so that we lazily initialize the progress bar
     if ProgressBar::are_supported() {
@@ -302,6 +351,26 @@ impl ProgressBar {
     }
   }
 
+  /// Shows a status line until the returned guard is dropped. Unlike
+  /// [`ProgressBar::update_with_prompt`], nothing is logged when progress
+  /// bars aren't supported, so this is for phases that are logged already.
+  pub fn status(&self, kind: ProgressMessagePrompt, msg: &str) -> UpdateGuard {
+    if is_quiet() || logger::is_json_format() || !ProgressBar::are_supported() {
+      return UpdateGuard { maybe_entry: None };
+    }
+    UpdateGuard {
+      maybe_entry: Some(self.inner.add_entry(kind, msg.to_string())),
+    }
+  }
+
+  /// Counts an operation that waits to start, eg. for a download slot, as
+  /// pending until the returned guard is dropped, so that the total progress
+  /// includes it before it shows up as an entry.
+  pub fn queue(&self) -> QueuedGuard {
+    self.inner.increment_queued();
+    QueuedGuard { pb: self.clone() }
+  }
+
   pub fn clear_guard(&self) -> ClearGuard {
     self.inner.increment_clear();
     ClearGuard { pb: self.clone() }
@@ -312,6 +381,16 @@ impl ProgressBar {
   }
 }
 
+pub struct QueuedGuard {
+  pb: ProgressBar,
+}
+
+impl Drop for QueuedGuard {
+  fn drop(&mut self) {
+    self.pb.inner.decrement_queued();
+  }
+}
+
 pub struct ClearGuard {
   pb: ProgressBar,
 }
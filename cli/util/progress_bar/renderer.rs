@@ -19,64 +19,69 @@ pub struct ProgressDataDisplayEntry {
 #[derive(Clone)]
 pub struct ProgressData {
   pub terminal_width: u32,
-  pub display_entry: ProgressDataDisplayEntry,
+  /// The entries in progress, each of which is rendered on its own line.
+  pub display_entries: Vec<ProgressDataDisplayEntry>,
   pub pending_entries: usize,
   pub percent_done: f64,
   pub total_entries: usize,
   pub duration: Duration,
 }
 
+/// The maximum number of entries that are rendered at once. The others are
+/// summarized in a single line.
+const MAX_DISPLAY_ENTRIES: usize = 5;
+
 pub trait ProgressBarRenderer: Send + Sync + std::fmt::Debug {
   fn render(&self, data: ProgressData) -> String;
 }
 
-/// Indicatif style progress bar.
+/// Indicatif style progress bar, below a line for each entry in progress.
 #[derive(Debug)]
 pub struct BarProgressBarRenderer;
 
 impl ProgressBarRenderer for BarProgressBarRenderer {
   fn render(&self, data: ProgressData) -> String {
-    let (bytes_text, bytes_text_max_width) = {
-      let total_size = data.display_entry.total_size;
-      let pos = data.display_entry.position;
-      if total_size == 0 {
-        (String::new(), 0)
-      } else {
-        let total_size_str = human_download_size(total_size, total_size);
-        (
-          format!(
-            " {}/{}",
-            human_download_size(pos, total_size),
-            total_size_str,
-          ),
-          2 + total_size_str.len() * 2,
-        )
+    let (message_entries, bar_entries): (Vec<_>, Vec<_>) = data
+      .display_entries
+      .iter()
+      .partition(|entry| !entry.message.is_empty());
+    // the size of an entry without a message is shown next to the bar
+    let bar_bytes_text = bar_entries
+      .first()
+      .map(|entry| bytes_text(entry.position, entry.total_size))
+      .unwrap_or_default();
+    let bytes_text_max_width = match data.display_entries.first() {
+      Some(entry) if entry.total_size > 0 => {
+        let total_size_str =
+          human_download_size(entry.total_size, entry.total_size);
+        2 + total_size_str.len() * 2
       }
+      _ => 0,
     };
     let (total_text, total_text_max_width) = if data.total_entries <= 1 {
       (String::new(), 0)
     } else {
       let total_entries_str = data.total_entries.to_string();
       (
-        format!(
-          " ({}/{})",
-          data.total_entries - data.pending_entries,
-          data.total_entries
-        ),
+        total_text(data.pending_entries, data.total_entries),
         4 + total_entries_str.len() * 2,
       )
     };
 
     let elapsed_text = get_elapsed_text(data.duration);
     let mut text = String::new();
-    if !data.display_entry.message.is_empty() {
+    for entry in message_entries.iter().take(MAX_DISPLAY_ENTRIES) {
       text.push_str(&format!(
         "{} {}{}\n",
-        colors::green("Download"),
-        data.display_entry.message,
-        bytes_text,
+        entry.prompt.as_text(),
+        entry.message,
+        bytes_text(entry.position, entry.total_size),
       ));
     }
+    if let Some(more_text) = more_entries_text(message_entries.len()) {
+      text.push_str(&more_text);
+      text.push('\n');
+    }
     text.push_str(&elapsed_text);
     let max_width = (data.terminal_width as i32 - 5).clamp(10, 75) as usize;
     let same_line_text_width =
@@ -106,50 +111,71 @@ impl ProgressBarRenderer for BarProgressBarRenderer {
     text.push(']');
 
     // suffix
-    if data.display_entry.message.is_empty() {
-      text.push_str(&colors::gray(bytes_text).to_string());
-    }
+    text.push_str(&colors::gray(bar_bytes_text).to_string());
     text.push_str(&colors::gray(total_text).to_string());
 
     text
   }
 }
 
+/// A line for each entry in progress, where the first line also has the
+/// count of finished entries.
 #[derive(Debug)]
 pub struct TextOnlyProgressBarRenderer;
 
 impl ProgressBarRenderer for TextOnlyProgressBarRenderer {
   fn render(&self, data: ProgressData) -> String {
-    let bytes_text = {
-      let total_size = data.display_entry.total_size;
-      let pos = data.display_entry.position;
-      if total_size == 0 {
-        String::new()
-      } else {
-        format!(
-          " {}/{}",
-          human_download_size(pos, total_size),
-          human_download_size(total_size, total_size)
-        )
-      }
-    };
     let total_text = if data.total_entries <= 1 {
       String::new()
     } else {
-      format!(
-        " ({}/{})",
-        data.total_entries - data.pending_entries,
-        data.total_entries
-      )
+      total_text(data.pending_entries, data.total_entries)
     };
 
+    let mut lines = data
+      .display_entries
+      .iter()
+      .take(MAX_DISPLAY_ENTRIES)
+      .map(|entry| {
+        format!(
+          "{} {}{}",
+          entry.prompt.as_text(),
+          entry.message,
+          colors::gray(bytes_text(entry.position, entry.total_size)),
+        )
+      })
+      .collect::<Vec<_>>();
+    if let Some(first_line) = lines.first_mut() {
+      first_line.push_str(&colors::gray(total_text).to_string());
+    }
+    lines.extend(more_entries_text(data.display_entries.len()));
+    lines.join("\n")
+  }
+}
+
+fn bytes_text(position: u64, total_size: u64) -> String {
+  if total_size == 0 {
+    String::new()
+  } else {
     format!(
-      "{} {}{}{}",
-      data.display_entry.prompt.as_text(),
-      data.display_entry.message,
-      colors::gray(bytes_text),
-      colors::gray(total_text),
+      " {}/{}",
+      human_download_size(position, total_size),
+      human_download_size(total_size, total_size)
+    )
+  }
+}
+
+fn total_text(pending_entries: usize, total_entries: usize) -> String {
+  format!(" ({}/{})", total_entries - pending_entries, total_entries)
+}
+
+fn more_entries_text(entries: usize) -> Option<String> {
+  if entries > MAX_DISPLAY_ENTRIES {
+    Some(
+      colors::gray(format!("  ...and {} more", entries - MAX_DISPLAY_ENTRIES))
+        .to_string(),
     )
+  } else {
+    None
   }
 }
 
@@ -197,12 +223,12 @@ mod test {
   fn should_render_bar_progress() {
     let renderer = BarProgressBarRenderer;
     let mut data = ProgressData {
-      display_entry: ProgressDataDisplayEntry {
+      display_entries: vec![ProgressDataDisplayEntry {
         prompt: ProgressMessagePrompt::Download,
         message: "data".to_string(),
         position: 0,
         total_size: 10 * BYTES_TO_KIB,
-      },
+      }],
       duration: Duration::from_secs(1),
       pending_entries: 1,
       total_entries: 1,
@@ -220,8 +246,8 @@ mod test {
     );
 
     data.percent_done = 0.5f64;
-    data.display_entry.position = 5 * BYTES_TO_KIB;
-    data.display_entry.message = String::new();
+    data.display_entries[0].position = 5 * BYTES_TO_KIB;
+    data.display_entries[0].message = String::new();
     data.total_entries = 3;
     let text = renderer.render(data.clone());
     let text = test_util::strip_ansi_codes(&text);
@@ -235,31 +261,57 @@ mod test {
 
     data.terminal_width = 50;
     data.pending_entries = 0;
-    data.display_entry.position = 10 * BYTES_TO_KIB;
+    data.display_entries[0].position = 10 * BYTES_TO_KIB;
     data.percent_done = 1.0f64;
     let text = renderer.render(data.clone());
     let text = test_util::strip_ansi_codes(&text);
     assert_eq!(text, "[00:01] [###########] 10.00KiB/10.00KiB (3/3)",);
 
-    data.display_entry.position = 0;
-    data.display_entry.total_size = 0;
+    data.display_entries[0].position = 0;
+    data.display_entries[0].total_size = 0;
     data.pending_entries = 0;
     data.total_entries = 1;
-    let text = renderer.render(data);
+    let text = renderer.render(data.clone());
     let text = test_util::strip_ansi_codes(&text);
     assert_eq!(text, "[00:01] [###################################]",);
+
+    data.display_entries = (0..7)
+      .map(|i| ProgressDataDisplayEntry {
+        prompt: ProgressMessagePrompt::Download,
+        message: format!("data{i}"),
+        position: 0,
+        total_size: 0,
+      })
+      .collect();
+    data.pending_entries = 7;
+    data.total_entries = 8;
+    data.percent_done = 0.125f64;
+    let text = renderer.render(data);
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(
+      text,
+      concat!(
+        "Download data0\n",
+        "Download data1\n",
+        "Download data2\n",
+        "Download data3\n",
+        "Download data4\n",
+        "  ...and 2 more\n",
+        "[00:01] [##>--------------------------] (1/8)",
+      ),
+    );
   }
 
   #[test]
   fn should_render_text_only_progress() {
     let renderer = TextOnlyProgressBarRenderer;
     let mut data = ProgressData {
-      display_entry: ProgressDataDisplayEntry {
+      display_entries: vec![ProgressDataDisplayEntry {
         prompt: ProgressMessagePrompt::Blocking,
         message: "data".to_string(),
         position: 0,
         total_size: 10 * BYTES_TO_KIB,
-      },
+      }],
       duration: Duration::from_secs(1),
       pending_entries: 1,
       total_entries: 3,
@@ -272,10 +324,22 @@ mod test {
 
     data.pending_entries = 0;
     data.total_entries = 1;
-    data.display_entry.position = 0;
-    data.display_entry.total_size = 0;
-    let text = renderer.render(data);
+    data.display_entries[0].position = 0;
+    data.display_entries[0].total_size = 0;
+    let text = renderer.render(data.clone());
     let text = test_util::strip_ansi_codes(&text);
     assert_eq!(text, "Blocking data");
+
+    data.display_entries.push(ProgressDataDisplayEntry {
+      prompt: ProgressMessagePrompt::Download,
+      message: "other".to_string(),
+      position: 5 * BYTES_TO_KIB,
+      total_size: 10 * BYTES_TO_KIB,
+    });
+    data.pending_entries = 2;
+    data.total_entries = 3;
+    let text = renderer.render(data);
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(text, "Blocking data (1/3)\nDownload other 5.00KiB/10.00KiB");
   }
 }
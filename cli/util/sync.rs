@@ -1,8 +1,13 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 
+use deno_core::parking_lot::Mutex;
+use tokio::sync::oneshot;
+
 /// Simplifies the use of an atomic boolean as a flag.
 #[derive(Debug, Default)]
 pub struct AtomicFlag(AtomicBool);
@@ -19,9 +24,138 @@ impl AtomicFlag {
   }
 }
 
+/// A queue that limits the number of tasks running at the same time. Waiting
+/// tasks are started in order of their priority, where a lower value is a
+/// higher priority, and then in the order they were queued.
+#[derive(Debug)]
+pub struct PriorityTaskQueue {
+  limit: usize,
+  state: Mutex<PriorityTaskQueueState>,
+}
+
+#[derive(Debug, Default)]
+struct PriorityTaskQueueState {
+  running: usize,
+  next_id: u64,
+  waiters: BinaryHeap<PriorityTaskQueueWaiter>,
+}
+
+#[derive(Debug)]
+struct PriorityTaskQueueWaiter {
+  priority: u8,
+  id: u64,
+  sender: oneshot::Sender<()>,
+}
+
+impl PriorityTaskQueueWaiter {
+  fn key(&self) -> (u8, u64) {
+    (self.priority, self.id)
+  }
+}
+
+impl PartialEq for PriorityTaskQueueWaiter {
+  fn eq(&self, other: &Self) -> bool {
+    self.key() == other.key()
+  }
+}
+
+impl Eq for PriorityTaskQueueWaiter {}
+
+impl PartialOrd for PriorityTaskQueueWaiter {
+  fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for PriorityTaskQueueWaiter {
+  fn cmp(&self, other: &Self) -> CmpOrdering {
+    // reversed, because the binary heap pops the greatest item first
+    other.key().cmp(&self.key())
+  }
+}
+
+impl PriorityTaskQueue {
+  pub fn new(limit: usize) -> Self {
+    Self {
+      limit,
+      state: Default::default(),
+    }
+  }
+
+  /// Waits until the task can run. The task runs until the returned permit
+  /// is dropped.
+  pub async fn acquire(&self, priority: u8) -> PriorityTaskQueuePermit<'_> {
+    let receiver = {
+      let mut state = self.state.lock();
+      if state.running < self.limit && state.waiters.is_empty() {
+        state.running += 1;
+        return PriorityTaskQueuePermit(self);
+      }
+      let (sender, receiver) = oneshot::channel();
+      let id = state.next_id;
+      state.next_id += 1;
+      state.waiters.push(PriorityTaskQueueWaiter {
+        priority,
+        id,
+        sender,
+      });
+      receiver
+    };
+    let mut pending = PendingPermit {
+      queue: self,
+      receiver: Some(receiver),
+    };
+    // the sender is only dropped without sending when the receiver was closed
+    let _ = pending.receiver.as_mut().unwrap().await;
+    pending.receiver = None;
+    PriorityTaskQueuePermit(self)
+  }
+
+  fn release(&self) {
+    let mut state = self.state.lock();
+    while let Some(waiter) = state.waiters.pop() {
+      // hand the slot over to the waiter, unless it stopped waiting
+      if waiter.sender.send(()).is_ok() {
+        return;
+      }
+    }
+    state.running -= 1;
+  }
+}
+
+/// Releases the slot that was handed over to a waiter when it stops waiting
+/// before receiving it (ex. when its future is dropped).
+struct PendingPermit<'a> {
+  queue: &'a PriorityTaskQueue,
+  receiver: Option<oneshot::Receiver<()>>,
+}
+
+impl<'a> Drop for PendingPermit<'a> {
+  fn drop(&mut self) {
+    if let Some(mut receiver) = self.receiver.take() {
+      receiver.close();
+      if receiver.try_recv().is_ok() {
+        self.queue.release();
+      }
+    }
+  }
+}
+
+pub struct PriorityTaskQueuePermit<'a>(&'a PriorityTaskQueue);
+
+impl<'a> Drop for PriorityTaskQueuePermit<'a> {
+  fn drop(&mut self) {
+    self.0.release();
+  }
+}
+
 #[cfg(test)]
 mod test {
+  use deno_core::futures;
+  use deno_core::parking_lot::Mutex;
+
   use super::AtomicFlag;
+  use super::PriorityTaskQueue;
 
   #[test]
   fn atomic_flag_raises() {
@@ -32,4 +166,51 @@ mod test {
     assert!(!flag.raise());
     assert!(flag.is_raised());
   }
+
+  #[tokio::test]
+  async fn priority_task_queue_order() {
+    let queue = PriorityTaskQueue::new(1);
+    let order = Mutex::new(Vec::new());
+    let first = queue.acquire(0).await;
+    let low = async {
+      let _permit = queue.acquire(1).await;
+      order.lock().push("low");
+    };
+    let high = async {
+      let _permit = queue.acquire(0).await;
+      order.lock().push("high");
+    };
+    let next_high = async {
+      let _permit = queue.acquire(0).await;
+      order.lock().push("next_high");
+    };
+    let release = async move {
+      tokio::task::yield_now().await;
+      drop(first);
+    };
+    futures::join!(low, high, next_high, release);
+    assert_eq!(*order.lock(), vec!["high", "next_high", "low"]);
+  }
+
+  #[tokio::test]
+  async fn priority_task_queue_dropped_waiter() {
+    let queue = PriorityTaskQueue::new(1);
+
+    // waiter dropped while waiting
+    let first = queue.acquire(0).await;
+    {
+      let mut waiter = Box::pin(queue.acquire(0));
+      assert!(futures::poll!(&mut waiter).is_pending());
+    }
+    drop(first);
+    let second = queue.acquire(0).await;
+
+    // waiter dropped after the slot was handed over to it
+    {
+      let mut waiter = Box::pin(queue.acquire(0));
+      assert!(futures::poll!(&mut waiter).is_pending());
+      drop(second);
+    }
+    let _third = queue.acquire(0).await;
+  }
 }
@@ -0,0 +1,111 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Spans of the phases of the CLI in the Chrome trace event format, which
+//! can be opened in `chrome://tracing` or https://ui.perfetto.dev
+//! (`--trace-out`).
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use once_cell::sync::OnceCell;
+
+struct Tracer {
+  file: Mutex<File>,
+  start: Instant,
+}
+
+static TRACER: OnceCell<Tracer> = OnceCell::new();
+
+thread_local! {
+  static THREAD_ID: usize = {
+    static NEXT_THREAD_ID: AtomicUsize = AtomicUsize::new(1);
+    NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed)
+  };
+}
+
+/// Starts writing the spans to the file at the path.
+pub fn init(path: &Path) -> Result<(), AnyError> {
+  let mut file = File::create(path)
+    .with_context(|| format!("Failed creating '{}'.", path.display()))?;
+  // The events are written as they end and the closing bracket of the array
+  // is optional in the format, so the trace is complete even when the
+  // process exits abruptly.
+  file.write_all(b"[\n")?;
+  let _ = TRACER.set(Tracer {
+    file: Mutex::new(file),
+    start: Instant::now(),
+  });
+  Ok(())
+}
+
+/// Starts a span that ends when the returned guard is dropped.
+pub fn span(category: &'static str, name: &'static str) -> TraceSpan {
+  span_with_args(category, name, || Value::Null)
+}
+
+/// Starts a span with arguments that are shown with it, which are only
+/// created when tracing is enabled.
+pub fn span_with_args(
+  category: &'static str,
+  name: &'static str,
+  args: impl FnOnce() -> Value,
+) -> TraceSpan {
+  let maybe_start = TRACER.get().map(|tracer| SpanStart {
+    start_us: tracer.start.elapsed().as_micros(),
+    thread_id: THREAD_ID.with(|id| *id),
+    args: args(),
+  });
+  TraceSpan {
+    category,
+    name,
+    maybe_start,
+  }
+}
+
+struct SpanStart {
+  start_us: u128,
+  thread_id: usize,
+  args: Value,
+}
+
+#[must_use = "the span ends when it's dropped"]
+pub struct TraceSpan {
+  category: &'static str,
+  name: &'static str,
+  maybe_start: Option<SpanStart>,
+}
+
+impl Drop for TraceSpan {
+  fn drop(&mut self) {
+    let (tracer, start) = match (TRACER.get(), self.maybe_start.take()) {
+      (Some(tracer), Some(start)) => (tracer, start),
+      _ => return,
+    };
+    let end_us = tracer.start.elapsed().as_micros();
+    let mut event = json!({
+      "name": self.name,
+      "cat": self.category,
+      "ph": "X",
+      "ts": start.start_us as u64,
+      "dur": (end_us - start.start_us) as u64,
+      "pid": std::process::id(),
+      "tid": start.thread_id,
+    });
+    if !start.args.is_null() {
+      event["args"] = start.args;
+    }
+    let mut file = tracer.file.lock();
+    if let Err(err) = writeln!(file, "{event},") {
+      log::debug!("Failed writing trace event: {:#}", err);
+    }
+  }
+}
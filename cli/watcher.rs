@@ -2,6 +2,7 @@
 
 use crate::args::CliOptions;
 use crate::cache::ParsedSourceCache;
+use crate::colors;
 use crate::graph_util::ModuleGraphContainer;
 use crate::module_loader::CjsResolutionStore;
 
@@ -10,6 +11,7 @@ use deno_core::ModuleSpecifier;
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub struct FileWatcher {
   cli_options: Arc<CliOptions>,
@@ -45,6 +47,63 @@ impl FileWatcher {
     self.init_watcher();
   }
 
+  /// Reset the runtime state of the modules affected by the changed files,
+  /// which are the changed modules and the modules depending on them. The
+  /// state of the other modules is kept so they don't need to be fetched,
+  /// analyzed and type checked again. All the state is reset on the first
+  /// run and when a changed file isn't a module of the graph (ex. a config
+  /// file), since it could affect any module.
+  pub fn invalidate(&self, changed_paths: Option<&[PathBuf]>) {
+    let changed_paths = match changed_paths {
+      Some(paths) if !paths.is_empty() => paths,
+      _ => return self.reset(),
+    };
+    let started = Instant::now();
+    let changed = changed_paths
+      .iter()
+      .filter_map(|path| ModuleSpecifier::from_file_path(path).ok())
+      .collect::<Vec<_>>();
+    let modules_len = self.graph_container.graph().modules().count();
+    let maybe_invalidated = if changed.len() == changed_paths.len() {
+      self.graph_container.invalidate(&changed)
+    } else {
+      None
+    };
+    let invalidated = match maybe_invalidated {
+      Some(invalidated) => invalidated,
+      None => {
+        log::debug!("Changed files are not in the module graph.");
+        return self.reset();
+      }
+    };
+    for specifier in &changed {
+      self.parsed_source_cache.free(specifier);
+      self.cjs_resolutions.remove(specifier);
+    }
+    self.init_watcher();
+    // the kept modules won't be loaded again, so watch them from here
+    if let Some(reporter) = &self.maybe_reporter {
+      let file_paths = self
+        .graph_container
+        .graph()
+        .modules()
+        .filter_map(|module| module.specifier().to_file_path().ok())
+        .collect::<Vec<_>>();
+      reporter.sender.send(file_paths).unwrap();
+    }
+
+    for specifier in &invalidated {
+      log::debug!("Invalidated {}", specifier);
+    }
+    log::info!(
+      "{} Invalidated {} of {} modules ({}ms)",
+      colors::intense_blue("Watcher"),
+      invalidated.len(),
+      modules_len,
+      started.elapsed().as_millis(),
+    );
+  }
+
   // Add invariant files like the import map and explicit watch flag list to
   // the watcher. Dedup for build_for_file_watcher and reset_for_file_watcher.
   pub fn init_watcher(&self) {
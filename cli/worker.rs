@@ -5,11 +5,16 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
+use deno_core::error::JsError;
 use deno_core::futures::task::LocalFutureObj;
 use deno_core::futures::FutureExt;
 use deno_core::located_script_name;
+use deno_core::serde_v8;
 use deno_core::url::Url;
+use deno_core::v8;
 use deno_core::CompiledWasmModuleStore;
 use deno_core::Extension;
 use deno_core::ModuleId;
@@ -29,12 +34,16 @@ use deno_runtime::inspector_server::InspectorServer;
 use deno_runtime::ops::worker_host::CreateWebWorkerCb;
 use deno_runtime::ops::worker_host::WorkerEventCb;
 use deno_runtime::permissions::PermissionsContainer;
+use deno_runtime::permissions::WorkerPermissionsConfig;
+use deno_runtime::sandbox::apply_os_sandbox;
+use deno_runtime::sandbox::SandboxOptions;
 use deno_runtime::web_worker::WebWorker;
 use deno_runtime::web_worker::WebWorkerOptions;
 use deno_runtime::worker::MainWorker;
 use deno_runtime::worker::WorkerOptions;
 use deno_runtime::BootstrapOptions;
 use deno_semver::npm::NpmPackageReqReference;
+use serde::Serialize;
 
 use crate::args::StorageKeyResolver;
 use crate::errors;
@@ -67,6 +76,17 @@ pub trait HasNodeSpecifierChecker: Send + Sync {
   fn has_node_specifier(&self) -> bool;
 }
 
+/// Options of the server started for the default export of the main module
+/// with `deno serve`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServeOptions {
+  pub port: u16,
+  pub hostname: String,
+  /// Whether several workers share the port with `SO_REUSEPORT`.
+  pub reuse_port: bool,
+}
+
 #[derive(Clone)]
 pub struct CliMainWorkerOptions {
   pub argv: Vec<String>,
@@ -79,11 +99,18 @@ pub struct CliMainWorkerOptions {
   pub is_inspecting: bool,
   pub is_npm_main: bool,
   pub location: Option<Url>,
+  pub node_conditions: Vec<String>,
   pub maybe_binary_npm_command_name: Option<String>,
   pub origin_data_folder_path: Option<PathBuf>,
+  /// OS sandbox applied once the main module was loaded, before it runs.
+  pub sandbox_options: Option<SandboxOptions>,
   pub seed: Option<u64>,
+  pub heap_snapshot_on_oom: bool,
+  /// Serve the default export of the main module (`deno serve`).
+  pub serve_options: Option<ServeOptions>,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub unstable: bool,
+  pub worker_permissions_config: Arc<WorkerPermissionsConfig>,
 }
 
 struct SharedWorkerState {
@@ -133,6 +160,11 @@ impl CliMainWorker {
     log::debug!("main_module {}", self.main_module);
 
     if self.is_main_cjs {
+      if self.shared.options.serve_options.is_some() {
+        bail!(
+          "Serving CommonJS modules is not supported, the main module must be an ES module with a default export."
+        );
+      }
       self.initialize_main_module_for_node()?;
       deno_node::load_cjs_module(
         &mut self.worker.js_runtime,
@@ -245,16 +277,63 @@ impl CliMainWorker {
     &mut self,
   ) -> Result<(), AnyError> {
     let id = self.worker.preload_main_module(&self.main_module).await?;
-    self.evaluate_module_possibly_with_npm(id).await
+    self.maybe_apply_os_sandbox()?;
+    self.evaluate_module_possibly_with_npm(id).await?;
+    if let Some(serve_options) = self.shared.options.serve_options.clone() {
+      self.serve_main_module(id, &serve_options)?;
+    }
+    Ok(())
+  }
+
+  /// Starts the server for the fetch handler of the default export of the
+  /// main module. The server then runs on the event loop.
+  fn serve_main_module(
+    &mut self,
+    id: ModuleId,
+    serve_options: &ServeOptions,
+  ) -> Result<(), AnyError> {
+    let namespace = self.worker.js_runtime.get_module_namespace(id)?;
+    let serve_main_module = self.worker.js_runtime.execute_script_static(
+      located_script_name!(),
+      "Deno[Deno.internal].serveMainModule",
+    )?;
+    let scope = &mut self.worker.js_runtime.handle_scope();
+    let serve_main_module = v8::Local::new(scope, serve_main_module);
+    let serve_main_module =
+      v8::Local::<v8::Function>::try_from(serve_main_module)
+        .map_err(|_| generic_error("Could not find the serve function."))?;
+    let namespace = v8::Local::new(scope, namespace);
+    let serve_options = serde_v8::to_v8(scope, serve_options)?;
+    let undefined = v8::undefined(scope);
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    serve_main_module.call(
+      tc_scope,
+      undefined.into(),
+      &[namespace.into(), serve_options],
+    );
+    if let Some(exception) = tc_scope.exception() {
+      return Err(JsError::from_v8_exception(tc_scope, exception).into());
+    }
+    Ok(())
   }
 
   pub async fn execute_side_module_possibly_with_npm(
     &mut self,
   ) -> Result<(), AnyError> {
     let id = self.worker.preload_side_module(&self.main_module).await?;
+    self.maybe_apply_os_sandbox()?;
     self.evaluate_module_possibly_with_npm(id).await
   }
 
+  /// Applies the OS sandbox, if requested, once the modules of the program
+  /// were loaded and before any of its code runs.
+  fn maybe_apply_os_sandbox(&self) -> Result<(), AnyError> {
+    match &self.shared.options.sandbox_options {
+      Some(sandbox_options) => apply_os_sandbox(sandbox_options),
+      None => Ok(()),
+    }
+  }
+
   async fn evaluate_module_possibly_with_npm(
     &mut self,
     id: ModuleId,
@@ -437,8 +516,13 @@ impl CliMainWorkerFactory {
         .clone(),
       root_cert_store_provider: Some(shared.root_cert_store_provider.clone()),
       seed: shared.options.seed,
+      heap_snapshot_on_oom: shared.options.heap_snapshot_on_oom,
       source_map_getter: maybe_source_map_getter,
       format_js_error_fn: Some(Arc::new(format_js_error)),
+      worker_permissions_config: shared
+        .options
+        .worker_permissions_config
+        .clone(),
       create_web_worker_cb,
       web_worker_preload_module_cb,
       web_worker_pre_execute_module_cb,
@@ -448,6 +532,7 @@ impl CliMainWorkerFactory {
       module_loader,
       fs: shared.fs.clone(),
       npm_resolver: Some(shared.npm_resolver.clone()),
+      node_conditions: shared.options.node_conditions.clone(),
       get_error_class_fn: Some(&errors::get_error_class_name),
       cache_storage_dir,
       origin_storage_dir,
@@ -569,10 +654,15 @@ fn create_web_worker_callback(
       preload_module_cb,
       pre_execute_module_cb,
       format_js_error_fn: Some(Arc::new(format_js_error)),
+      worker_permissions_config: shared
+        .options
+        .worker_permissions_config
+        .clone(),
       source_map_getter: maybe_source_map_getter,
       module_loader,
       fs: shared.fs.clone(),
       npm_resolver: Some(shared.npm_resolver.clone()),
+      node_conditions: shared.options.node_conditions.clone(),
       worker_type: args.worker_type,
       maybe_inspector_server,
       get_error_class_fn: Some(&errors::get_error_class_name),
@@ -321,9 +321,12 @@ pub extern "C" fn host_initialize_import_meta_object_callback(
   let module_map = module_map_rc.borrow();
 
   let module_global = v8::Global::new(scope, module);
-  let info = module_map
-    .get_info(&module_global)
-    .expect("Module not found");
+  let info = match module_map.get_info(&module_global) {
+    Some(info) => info,
+    // Modules that weren't loaded through the module map (eg. ones created by
+    // an embedder in a separate context) get an empty `import.meta`.
+    None => return,
+  };
 
   let url_key = v8::String::new_external_onebyte_static(scope, b"url").unwrap();
   let url_val = info.name.v8(scope);
@@ -447,7 +450,12 @@ pub extern "C" fn promise_reject_callback(message: v8::PromiseRejectMessage) {
   // SAFETY: `CallbackScope` can be safely constructed from `&PromiseRejectMessage`
   let scope = &mut unsafe { v8::CallbackScope::new(&message) };
 
-  let context_state_rc = JsRealm::state_from_scope(scope);
+  // Contexts that aren't backed by a `JsRealm` (eg. ones created by an
+  // embedder) don't track promise rejections.
+  let context_state_rc = match JsRealm::try_state_from_scope(scope) {
+    Some(context_state_rc) => context_state_rc,
+    None => return,
+  };
   let mut context_state = context_state_rc.borrow_mut();
 
   if let Some(js_promise_reject_cb) = context_state.js_promise_reject_cb.clone()
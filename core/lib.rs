@@ -83,6 +83,7 @@ pub use crate::modules::ExtModuleLoader;
 pub use crate::modules::ExtModuleLoaderCb;
 pub use crate::modules::FsModuleLoader;
 pub use crate::modules::ModuleCode;
+pub use crate::modules::ModuleCodeCache;
 pub use crate::modules::ModuleId;
 pub use crate::modules::ModuleLoader;
 pub use crate::modules::ModuleSource;
@@ -91,6 +92,7 @@ pub use crate::modules::ModuleType;
 pub use crate::modules::NoopModuleLoader;
 pub use crate::modules::ResolutionKind;
 pub use crate::normalize_path::normalize_path;
+pub use crate::ops::with_current_op_scope;
 pub use crate::ops::OpCall;
 pub use crate::ops::OpError;
 pub use crate::ops::OpId;
@@ -133,6 +135,7 @@ pub mod _ops {
   pub use super::bindings::throw_type_error;
   pub use super::error_codes::get_error_code;
   pub use super::ops::to_op_result;
+  pub use super::ops::OpCallGuard;
   pub use super::ops::OpCtx;
   pub use super::ops::OpResult;
   pub use super::runtime::map_async_op1;
@@ -48,7 +48,7 @@ fn strip_bom(source_code: &[u8]) -> &[u8] {
   }
 }
 
-const SUPPORTED_TYPE_ASSERTIONS: &[&str] = &["json"];
+const SUPPORTED_TYPE_ASSERTIONS: &[&str] = &["json", "text", "bytes"];
 
 /// Throws V8 exception if assertions are invalid
 pub(crate) fn validate_import_assertions(
@@ -116,12 +116,11 @@ pub(crate) fn get_asserted_module_type_from_assertions(
 ) -> AssertedModuleType {
   assertions
     .get("type")
-    .map(|ty| {
-      if ty == "json" {
-        AssertedModuleType::Json
-      } else {
-        AssertedModuleType::JavaScriptOrWasm
-      }
+    .map(|ty| match ty.as_str() {
+      "json" => AssertedModuleType::Json,
+      "text" => AssertedModuleType::Text,
+      "bytes" => AssertedModuleType::Bytes,
+      _ => AssertedModuleType::JavaScriptOrWasm,
     })
     .unwrap_or(AssertedModuleType::JavaScriptOrWasm)
 }
@@ -129,7 +128,7 @@ pub(crate) fn get_asserted_module_type_from_assertions(
 // Clippy thinks the return value doesn't need to be an Option, it's unaware
 // of the mapping that MapFnFrom<F> does for ResolveModuleCallback.
 #[allow(clippy::unnecessary_wraps)]
-fn json_module_evaluation_steps<'a>(
+fn synthetic_module_evaluation_steps<'a>(
   context: v8::Local<'a, v8::Context>,
   module: v8::Local<v8::Module>,
 ) -> Option<v8::Local<'a, v8::Value>> {
@@ -141,7 +140,7 @@ fn json_module_evaluation_steps<'a>(
   let handle = v8::Global::<v8::Module>::new(tc_scope, module);
   let value_handle = module_map
     .borrow_mut()
-    .json_value_store
+    .synthetic_module_value_store
     .remove(&handle)
     .unwrap();
   let value_local = v8::Local::new(tc_scope, value_handle);
@@ -172,6 +171,12 @@ fn json_module_evaluation_steps<'a>(
 pub enum ModuleType {
   JavaScript,
   Json,
+  /// A module imported with a `text` import assertion, whose default export
+  /// is its source as a string.
+  Text,
+  /// A module imported with a `bytes` import assertion, whose default export
+  /// is its contents as a `Uint8Array`.
+  Bytes,
 }
 
 impl std::fmt::Display for ModuleType {
@@ -179,6 +184,8 @@ impl std::fmt::Display for ModuleType {
     match self {
       Self::JavaScript => write!(f, "JavaScript"),
       Self::Json => write!(f, "JSON"),
+      Self::Text => write!(f, "Text"),
+      Self::Bytes => write!(f, "Bytes"),
     }
   }
 }
@@ -206,6 +213,20 @@ pub struct ModuleSource {
   module_url_specified: ModuleName,
   /// If the module was found somewhere other than the specified address, this will be [`Some`].
   module_url_found: Option<ModuleName>,
+  code_cache: Option<ModuleCodeCache>,
+  bytes: Option<Vec<u8>>,
+}
+
+/// The V8 code cache of a module, which allows V8 to skip parsing and
+/// compiling the module. See [`ModuleSource::with_code_cache`].
+#[derive(Debug)]
+pub struct ModuleCodeCache {
+  /// A hash of the module code the cache belongs to.
+  pub hash: u64,
+  /// The cache created by V8 on a previous run, or [`None`] to have V8 create
+  /// a cache after compiling the module. It is passed to
+  /// [`ModuleLoader::code_cache_ready`] so it can be stored.
+  pub data: Option<Vec<u8>>,
 }
 
 impl ModuleSource {
@@ -221,6 +242,8 @@ impl ModuleSource {
       module_type: module_type.into(),
       module_url_specified,
       module_url_found: None,
+      code_cache: None,
+      bytes: None,
     }
   }
 
@@ -243,9 +266,36 @@ impl ModuleSource {
       module_type: module_type.into(),
       module_url_specified,
       module_url_found,
+      code_cache: None,
+      bytes: None,
     }
   }
 
+  /// Create a [`ModuleSource`] of a `bytes` module (see
+  /// [`ModuleLoader::load_asset`]), whose contents don't need to be valid
+  /// text.
+  pub fn new_bytes(
+    bytes: Vec<u8>,
+    specifier: &ModuleSpecifier,
+    specifier_found: &ModuleSpecifier,
+  ) -> Self {
+    let mut module_source = Self::new_with_redirect(
+      ModuleType::Bytes,
+      Default::default(),
+      specifier,
+      specifier_found,
+    );
+    module_source.bytes = Some(bytes);
+    module_source
+  }
+
+  /// Sets the V8 code cache of the module. Only JavaScript modules use a code
+  /// cache.
+  pub fn with_code_cache(mut self, code_cache: ModuleCodeCache) -> Self {
+    self.code_cache = Some(code_cache);
+    self
+  }
+
   #[cfg(test)]
   pub fn for_test(code: &'static str, file: impl AsRef<str>) -> Self {
     Self {
@@ -253,6 +303,8 @@ impl ModuleSource {
       module_type: ModuleType::JavaScript,
       module_url_specified: file.as_ref().to_owned().into(),
       module_url_found: None,
+      code_cache: None,
+      bytes: None,
     }
   }
 
@@ -275,6 +327,8 @@ impl ModuleSource {
       module_type: ModuleType::JavaScript,
       module_url_specified: specified.into(),
       module_url_found: found,
+      code_cache: None,
+      bytes: None,
     }
   }
 }
@@ -347,6 +401,41 @@ pub trait ModuleLoader {
   ) -> Pin<Box<dyn Future<Output = Result<(), Error>>>> {
     async { Ok(()) }.boxed_local()
   }
+
+  /// Called with the code cache V8 created for a module that was loaded
+  /// with a [`ModuleCodeCache`] without data, so that it can be stored and
+  /// provided when loading the module again.
+  ///
+  /// It's not required to implement this method.
+  fn code_cache_ready(
+    &self,
+    _module_specifier: &ModuleSpecifier,
+    _hash: u64,
+    _code_cache: &[u8],
+  ) {
+  }
+
+  /// Given ModuleSpecifier, load the contents of a module imported with a
+  /// `text` or `bytes` import assertion (eg.
+  /// `import data from "./data.bin" assert { type: "bytes" }`), which become
+  /// the default export of the module. The returned source must be of the
+  /// given `module_type`, see [`ModuleSource::new_bytes`] for `bytes`
+  /// modules.
+  ///
+  /// It's not required to implement this method, but these imports fail
+  /// otherwise.
+  fn load_asset(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    _maybe_referrer: Option<&ModuleSpecifier>,
+    _is_dyn_import: bool,
+    module_type: ModuleType,
+  ) -> Pin<Box<ModuleSourceFuture>> {
+    let err = generic_error(format!(
+      "Loading {module_type} modules is not supported; attempted to load: \"{module_specifier}\""
+    ));
+    async move { Err(err) }.boxed_local()
+  }
 }
 
 /// Placeholder structure used when creating
@@ -498,6 +587,29 @@ impl ExtModuleLoader {
     .boxed_local()
   }
 
+  /// Loads a module for an import with the given asserted module type.
+  pub(crate) fn load_asserted(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    maybe_referrer: Option<&ModuleSpecifier>,
+    is_dyn_import: bool,
+    asserted_module_type: AssertedModuleType,
+  ) -> Pin<Box<ModuleSourceFuture>> {
+    let module_type = match asserted_module_type {
+      AssertedModuleType::JavaScriptOrWasm | AssertedModuleType::Json => {
+        return self.load(module_specifier, maybe_referrer, is_dyn_import);
+      }
+      AssertedModuleType::Text => ModuleType::Text,
+      AssertedModuleType::Bytes => ModuleType::Bytes,
+    };
+    self.module_loader.load_asset(
+      module_specifier,
+      maybe_referrer,
+      is_dyn_import,
+      module_type,
+    )
+  }
+
   pub fn prepare_load(
     &self,
     op_state: Rc<RefCell<OpState>>,
@@ -517,6 +629,17 @@ impl ExtModuleLoader {
     )
   }
 
+  pub fn code_cache_ready(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    hash: u64,
+    code_cache: &[u8],
+  ) {
+    self
+      .module_loader
+      .code_cache_ready(module_specifier, hash, code_cache)
+  }
+
   pub fn allow_ext_resolution(&self) {
     *self.ext_resolution_allowed.borrow_mut() = true;
   }
@@ -826,20 +949,38 @@ impl RecursiveModuleLoad {
         id
       }
       None => match module_source.module_type {
-        ModuleType::JavaScript => {
-          self.module_map_rc.borrow_mut().new_es_module(
+        ModuleType::JavaScript => self
+          .module_map_rc
+          .borrow_mut()
+          .new_es_module_with_code_cache(
             scope,
             self.is_currently_loading_main_module(),
             module_url_found,
             module_source.code,
             self.is_dynamic_import(),
-          )?
-        }
+            module_source.code_cache,
+          )?,
         ModuleType::Json => self.module_map_rc.borrow_mut().new_json_module(
           scope,
           module_url_found,
           module_source.code,
         )?,
+        ModuleType::Text => self.module_map_rc.borrow_mut().new_text_module(
+          scope,
+          module_url_found,
+          module_source.code,
+        )?,
+        ModuleType::Bytes => {
+          let bytes = match module_source.bytes {
+            Some(bytes) => bytes,
+            None => module_source.code.as_bytes().to_vec(),
+          };
+          self.module_map_rc.borrow_mut().new_bytes_module(
+            scope,
+            module_url_found,
+            bytes,
+          )?
+        }
       },
     };
 
@@ -879,7 +1020,12 @@ impl RecursiveModuleLoad {
             let is_dynamic_import = self.is_dynamic_import();
             let fut = async move {
               let load_result = loader
-                .load(&specifier, Some(&referrer), is_dynamic_import)
+                .load_asserted(
+                  &specifier,
+                  Some(&referrer),
+                  is_dynamic_import,
+                  request.asserted_module_type,
+                )
                 .await;
               load_result.map(|s| (request, s))
             };
@@ -961,10 +1107,11 @@ impl Stream for RecursiveModuleLoad {
           let is_dynamic_import = inner.is_dynamic_import();
           async move {
             let result = loader
-              .load(
+              .load_asserted(
                 &module_specifier,
                 maybe_referrer.as_ref(),
                 is_dynamic_import,
+                module_request.asserted_module_type,
               )
               .await;
             result.map(|s| (module_request, s))
@@ -992,6 +1139,8 @@ impl Stream for RecursiveModuleLoad {
 pub(crate) enum AssertedModuleType {
   JavaScriptOrWasm,
   Json,
+  Text,
+  Bytes,
 }
 
 impl From<ModuleType> for AssertedModuleType {
@@ -999,6 +1148,8 @@ impl From<ModuleType> for AssertedModuleType {
     match module_type {
       ModuleType::JavaScript => AssertedModuleType::JavaScriptOrWasm,
       ModuleType::Json => AssertedModuleType::Json,
+      ModuleType::Text => AssertedModuleType::Text,
+      ModuleType::Bytes => AssertedModuleType::Bytes,
     }
   }
 }
@@ -1008,14 +1159,17 @@ impl std::fmt::Display for AssertedModuleType {
     match self {
       Self::JavaScriptOrWasm => write!(f, "JavaScriptOrWasm"),
       Self::Json => write!(f, "JSON"),
+      Self::Text => write!(f, "Text"),
+      Self::Bytes => write!(f, "Bytes"),
     }
   }
 }
 
 /// Describes a request for a module as parsed from the source code.
 /// Usually executable (`JavaScriptOrWasm`) is used, except when an
-/// import assertions explicitly constrains an import to JSON, in
-/// which case this will have a `AssertedModuleType::Json`.
+/// import assertions explicitly constrains an import to JSON, text or bytes,
+/// in which case this will have a `AssertedModuleType::Json`,
+/// `AssertedModuleType::Text` or `AssertedModuleType::Bytes`.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub(crate) struct ModuleRequest {
   pub specifier: String,
@@ -1057,6 +1211,8 @@ pub(crate) struct ModuleMap {
   pub info: Vec<ModuleInfo>,
   pub(crate) by_name_js: HashMap<ModuleName, SymbolicModule>,
   pub(crate) by_name_json: HashMap<ModuleName, SymbolicModule>,
+  pub(crate) by_name_text: HashMap<ModuleName, SymbolicModule>,
+  pub(crate) by_name_bytes: HashMap<ModuleName, SymbolicModule>,
   pub(crate) next_load_id: ModuleLoadId,
 
   // Handling of futures for loading module sources
@@ -1069,9 +1225,11 @@ pub(crate) struct ModuleMap {
   pub(crate) pending_dynamic_imports:
     FuturesUnordered<StreamFuture<RecursiveModuleLoad>>,
 
-  // This store is used temporarly, to forward parsed JSON
-  // value from `new_json_module` to `json_module_evaluation_steps`
-  json_value_store: HashMap<v8::Global<v8::Module>, v8::Global<v8::Value>>,
+  // This store is used temporarly, to forward the default export of synthetic
+  // modules (eg. the parsed JSON value from `new_json_module`) to
+  // `synthetic_module_evaluation_steps`
+  synthetic_module_value_store:
+    HashMap<v8::Global<v8::Module>, v8::Global<v8::Value>>,
 }
 
 impl ModuleMap {
@@ -1082,6 +1240,8 @@ impl ModuleMap {
     for module_type in [
       AssertedModuleType::JavaScriptOrWasm,
       AssertedModuleType::Json,
+      AssertedModuleType::Text,
+      AssertedModuleType::Bytes,
     ] {
       output.extend(
         self
@@ -1279,6 +1439,8 @@ impl ModuleMap {
           let asserted_module_type = match asserted_module_type_no {
             0 => AssertedModuleType::JavaScriptOrWasm,
             1 => AssertedModuleType::Json,
+            2 => AssertedModuleType::Text,
+            3 => AssertedModuleType::Bytes,
             _ => unreachable!(),
           };
           requests.push(ModuleRequest {
@@ -1296,6 +1458,8 @@ impl ModuleMap {
         let module_type = match module_type_no {
           0 => ModuleType::JavaScript,
           1 => ModuleType::Json,
+          2 => ModuleType::Text,
+          3 => ModuleType::Bytes,
           _ => unreachable!(),
         };
 
@@ -1316,6 +1480,8 @@ impl ModuleMap {
       .by_name_mut(AssertedModuleType::JavaScriptOrWasm)
       .clear();
     self.by_name_mut(AssertedModuleType::Json).clear();
+    self.by_name_mut(AssertedModuleType::Text).clear();
+    self.by_name_mut(AssertedModuleType::Bytes).clear();
 
     {
       let by_name_arr: v8::Local<v8::Array> =
@@ -1340,6 +1506,8 @@ impl ModuleMap {
         {
           0 => AssertedModuleType::JavaScriptOrWasm,
           1 => AssertedModuleType::Json,
+          2 => AssertedModuleType::Text,
+          3 => AssertedModuleType::Bytes,
           _ => unreachable!(),
         };
 
@@ -1377,13 +1545,15 @@ impl ModuleMap {
       info: vec![],
       by_name_js: HashMap::new(),
       by_name_json: HashMap::new(),
+      by_name_text: HashMap::new(),
+      by_name_bytes: HashMap::new(),
       next_load_id: 1,
       loader,
       op_state,
       dynamic_import_map: HashMap::new(),
       preparing_dynamic_imports: FuturesUnordered::new(),
       pending_dynamic_imports: FuturesUnordered::new(),
-      json_value_store: HashMap::new(),
+      synthetic_module_value_store: HashMap::new(),
     }
   }
 
@@ -1418,7 +1588,6 @@ impl ModuleMap {
     name: ModuleName,
     source: ModuleCode,
   ) -> Result<ModuleId, ModuleError> {
-    let name_str = name.v8(scope);
     let source_str = v8::String::new_from_utf8(
       scope,
       strip_bom(source.as_bytes()),
@@ -1438,22 +1607,74 @@ impl ModuleMap {
       }
     };
 
-    let export_names = [v8::String::new(tc_scope, "default").unwrap()];
+    Ok(self.new_synthetic_module(tc_scope, name, ModuleType::Json, parsed_json))
+  }
+
+  fn new_text_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: ModuleName,
+    source: ModuleCode,
+  ) -> Result<ModuleId, ModuleError> {
+    let text = match v8::String::new_from_utf8(
+      scope,
+      strip_bom(source.as_bytes()),
+      v8::NewStringType::Normal,
+    ) {
+      Some(text) => text,
+      None => {
+        return Err(ModuleError::Other(generic_error(format!(
+          "Module is too large to be imported as text: {}",
+          name.as_str()
+        ))))
+      }
+    };
+    Ok(self.new_synthetic_module(scope, name, ModuleType::Text, text.into()))
+  }
+
+  fn new_bytes_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: ModuleName,
+    bytes: Vec<u8>,
+  ) -> Result<ModuleId, ModuleError> {
+    let len = bytes.len();
+    let backing_store =
+      v8::ArrayBuffer::new_backing_store_from_vec(bytes).make_shared();
+    let buffer = v8::ArrayBuffer::with_backing_store(scope, &backing_store);
+    let u8array = v8::Uint8Array::new(scope, buffer, 0, len).unwrap();
+    Ok(self.new_synthetic_module(
+      scope,
+      name,
+      ModuleType::Bytes,
+      u8array.into(),
+    ))
+  }
+
+  /// Creates a module whose only export is `value` as the default export.
+  fn new_synthetic_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: ModuleName,
+    module_type: ModuleType,
+    value: v8::Local<v8::Value>,
+  ) -> ModuleId {
+    let name_str = name.v8(scope);
+    let export_names = [v8::String::new(scope, "default").unwrap()];
     let module = v8::Module::create_synthetic_module(
-      tc_scope,
+      scope,
       name_str,
       &export_names,
-      json_module_evaluation_steps,
+      synthetic_module_evaluation_steps,
     );
 
-    let handle = v8::Global::<v8::Module>::new(tc_scope, module);
-    let value_handle = v8::Global::<v8::Value>::new(tc_scope, parsed_json);
-    self.json_value_store.insert(handle.clone(), value_handle);
-
-    let id =
-      self.create_module_info(name, ModuleType::Json, handle, false, vec![]);
+    let handle = v8::Global::<v8::Module>::new(scope, module);
+    let value_handle = v8::Global::<v8::Value>::new(scope, value);
+    self
+      .synthetic_module_value_store
+      .insert(handle.clone(), value_handle);
 
-    Ok(id)
+    self.create_module_info(name, module_type, handle, false, vec![])
   }
 
   /// Create and compile an ES module.
@@ -1464,16 +1685,58 @@ impl ModuleMap {
     name: ModuleName,
     source: ModuleCode,
     is_dynamic_import: bool,
+  ) -> Result<ModuleId, ModuleError> {
+    self.new_es_module_with_code_cache(
+      scope,
+      main,
+      name,
+      source,
+      is_dynamic_import,
+      None,
+    )
+  }
+
+  /// Create and compile an ES module, consuming or creating its V8 code
+  /// cache.
+  pub(crate) fn new_es_module_with_code_cache(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    main: bool,
+    name: ModuleName,
+    source: ModuleCode,
+    is_dynamic_import: bool,
+    code_cache: Option<ModuleCodeCache>,
   ) -> Result<ModuleId, ModuleError> {
     let name_str = name.v8(scope);
     let source_str = source.v8(scope);
 
     let origin = bindings::module_origin(scope, name_str);
-    let source = v8::script_compiler::Source::new(source_str, Some(&origin));
 
     let tc_scope = &mut v8::TryCatch::new(scope);
 
-    let maybe_module = v8::script_compiler::compile_module(tc_scope, source);
+    let maybe_module = match code_cache.as_ref().and_then(|c| c.data.as_ref()) {
+      Some(data) => {
+        // V8 verifies the cache was created for this source by the same V8
+        // version and flags, and compiles the module from scratch otherwise
+        let cached_data = v8::script_compiler::CachedData::new(data);
+        let source = v8::script_compiler::Source::new_with_cached_data(
+          source_str,
+          Some(&origin),
+          cached_data,
+        );
+        v8::script_compiler::compile_module2(
+          tc_scope,
+          source,
+          v8::script_compiler::CompileOptions::ConsumeCodeCache,
+          v8::script_compiler::NoCacheReason::NoReason,
+        )
+      }
+      None => {
+        let source =
+          v8::script_compiler::Source::new(source_str, Some(&origin));
+        v8::script_compiler::compile_module(tc_scope, source)
+      }
+    };
 
     if tc_scope.has_caught() {
       assert!(maybe_module.is_none());
@@ -1484,6 +1747,19 @@ impl ModuleMap {
 
     let module = maybe_module.unwrap();
 
+    if let Some(code_cache) = &code_cache {
+      if code_cache.data.is_none() {
+        let unbound_module_script = module.get_unbound_module_script(tc_scope);
+        if let Some(data) = unbound_module_script.create_code_cache() {
+          if let Ok(specifier) = ModuleSpecifier::parse(name.as_ref()) {
+            self
+              .loader
+              .code_cache_ready(&specifier, code_cache.hash, &data);
+          }
+        }
+      }
+    }
+
     let mut requests: Vec<ModuleRequest> = vec![];
     let module_requests = module.get_module_requests();
     for i in 0..module_requests.length() {
@@ -1605,6 +1881,8 @@ impl ModuleMap {
     match asserted_module_type {
       AssertedModuleType::Json => &self.by_name_json,
       AssertedModuleType::JavaScriptOrWasm => &self.by_name_js,
+      AssertedModuleType::Text => &self.by_name_text,
+      AssertedModuleType::Bytes => &self.by_name_bytes,
     }
   }
 
@@ -1615,6 +1893,8 @@ impl ModuleMap {
     match asserted_module_type {
       AssertedModuleType::Json => &mut self.by_name_json,
       AssertedModuleType::JavaScriptOrWasm => &mut self.by_name_js,
+      AssertedModuleType::Text => &mut self.by_name_text,
+      AssertedModuleType::Bytes => &mut self.by_name_bytes,
     }
   }
 
@@ -2175,6 +2455,82 @@ import "/a.js";
     assert_eq!(DISPATCH_COUNT.load(Ordering::Relaxed), 1);
   }
 
+  #[test]
+  fn test_code_cache() {
+    #[derive(Default)]
+    struct CodeCacheLoader {
+      code_caches: RefCell<Vec<(ModuleSpecifier, u64, Vec<u8>)>>,
+    }
+
+    impl ModuleLoader for CodeCacheLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _kind: ResolutionKind,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer)?)
+      }
+
+      fn load(
+        &self,
+        _module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<&ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        unreachable!()
+      }
+
+      fn code_cache_ready(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        hash: u64,
+        code_cache: &[u8],
+      ) {
+        self.code_caches.borrow_mut().push((
+          module_specifier.clone(),
+          hash,
+          code_cache.to_vec(),
+        ));
+      }
+    }
+
+    let compile = |loader: Rc<CodeCacheLoader>, data: Option<Vec<u8>>| {
+      let mut runtime = JsRuntime::new(RuntimeOptions {
+        module_loader: Some(loader),
+        ..Default::default()
+      });
+      let module_map_rc = JsRuntime::module_map(runtime.v8_isolate());
+      let scope = &mut runtime.handle_scope();
+      module_map_rc
+        .borrow_mut()
+        .new_es_module_with_code_cache(
+          scope,
+          true,
+          ascii_str!("file:///a.js"),
+          ascii_str!("export function a() { return 'a'; }"),
+          false,
+          Some(ModuleCodeCache { hash: 1, data }),
+        )
+        .unwrap();
+    };
+
+    // the code cache is created when there is none yet
+    let loader = Rc::new(CodeCacheLoader::default());
+    compile(loader.clone(), None);
+    let code_caches = loader.code_caches.take();
+    assert_eq!(code_caches.len(), 1);
+    let (specifier, hash, data) = code_caches.into_iter().next().unwrap();
+    assert_eq!(specifier.as_str(), "file:///a.js");
+    assert_eq!(hash, 1);
+    assert!(!data.is_empty());
+
+    // and consumed when loading the module again
+    let loader = Rc::new(CodeCacheLoader::default());
+    compile(loader.clone(), Some(data));
+    assert!(loader.code_caches.borrow().is_empty());
+  }
+
   #[test]
   fn test_json_module() {
     #[derive(Default)]
@@ -2281,6 +2637,92 @@ import "/a.js";
     futures::executor::block_on(receiver).unwrap().unwrap();
   }
 
+  #[tokio::test]
+  async fn test_text_and_bytes_modules() {
+    struct AssetLoader;
+
+    impl ModuleLoader for AssetLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _kind: ResolutionKind,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer)?)
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<&ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        assert_eq!(module_specifier.as_str(), "file:///main.js");
+        let code = ascii_str!(
+          r#"
+          import text from "./data.txt" assert { type: "text" };
+          import bytes from "./data.txt" assert { type: "bytes" };
+          if (text !== "h\u00e9llo") {
+            throw new Error(`Unexpected text: ${text}`);
+          }
+          if (!(bytes instanceof Uint8Array) || bytes.length !== 6) {
+            throw new Error(`Unexpected bytes: ${bytes}`);
+          }
+          "#
+        );
+        let source =
+          ModuleSource::new(ModuleType::JavaScript, code, module_specifier);
+        futures::future::ok(source).boxed_local()
+      }
+
+      fn load_asset(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<&ModuleSpecifier>,
+        _is_dyn_import: bool,
+        module_type: ModuleType,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        assert_eq!(module_specifier.as_str(), "file:///data.txt");
+        let source = match module_type {
+          ModuleType::Text => ModuleSource::new(
+            ModuleType::Text,
+            "h\u{e9}llo".to_string().into(),
+            module_specifier,
+          ),
+          ModuleType::Bytes => ModuleSource::new_bytes(
+            "h\u{e9}llo".as_bytes().to_vec(),
+            module_specifier,
+            module_specifier,
+          ),
+          _ => unreachable!(),
+        };
+        futures::future::ok(source).boxed_local()
+      }
+    }
+
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(Rc::new(AssetLoader)),
+      ..Default::default()
+    });
+    let spec = resolve_url("file:///main.js").unwrap();
+    let main_id = runtime.load_main_module(&spec, None).await.unwrap();
+    let receiver = runtime.mod_evaluate(main_id);
+    runtime.run_event_loop(false).await.unwrap();
+    receiver.await.unwrap().unwrap();
+
+    let module_map_rc = JsRuntime::module_map(runtime.v8_isolate());
+    let module_map = module_map_rc.borrow();
+    assert!(module_map
+      .get_id("file:///data.txt", AssertedModuleType::Text)
+      .is_some());
+    assert!(module_map
+      .get_id("file:///data.txt", AssertedModuleType::Bytes)
+      .is_some());
+    assert!(module_map
+      .get_id("file:///data.txt", AssertedModuleType::JavaScriptOrWasm)
+      .is_none());
+  }
+
   #[tokio::test]
   async fn dyn_import_err() {
     #[derive(Clone, Default)]
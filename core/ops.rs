@@ -13,6 +13,7 @@ use futures::Future;
 use futures::FutureExt;
 use pin_project::pin_project;
 use serde::Serialize;
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::ops::Deref;
 use std::ops::DerefMut;
@@ -138,6 +139,54 @@ pub fn to_op_result<R: Serialize + 'static>(
   }
 }
 
+thread_local! {
+  // The callback info of the op currently being called on this thread, so
+  // code running inside of the op can get a scope without being passed one.
+  static CURRENT_OP_CALLBACK_INFO: Cell<*const v8::FunctionCallbackInfo> =
+    Cell::new(std::ptr::null());
+}
+
+/// Marks an op as being called on the current thread until it's dropped.
+///
+/// Created by the `#[op]` macro around every call of an op that isn't a fast
+/// call.
+pub struct OpCallGuard {
+  previous: *const v8::FunctionCallbackInfo,
+}
+
+impl OpCallGuard {
+  pub fn new(info: &v8::FunctionCallbackInfo) -> Self {
+    let previous =
+      CURRENT_OP_CALLBACK_INFO.with(|current| current.replace(info));
+    Self { previous }
+  }
+}
+
+impl Drop for OpCallGuard {
+  fn drop(&mut self) {
+    CURRENT_OP_CALLBACK_INFO.with(|current| current.set(self.previous));
+  }
+}
+
+/// Calls `f` with a scope nested in the scope of the op currently being
+/// called on this thread. Returns `None` if no op is being called, or if it
+/// was called through a fast call.
+pub fn with_current_op_scope<R>(
+  f: impl FnOnce(&mut v8::HandleScope) -> R,
+) -> Option<R> {
+  let info = CURRENT_OP_CALLBACK_INFO.with(|current| current.get());
+  if info.is_null() {
+    return None;
+  }
+  // SAFETY: the pointer is only set while the op it belongs to is being
+  // called, so the callback info is valid. A callback scope is entered on top
+  // of whatever scope is currently active, which makes this valid from
+  // anywhere inside of the op.
+  let scope = &mut unsafe { v8::CallbackScope::new(&*info) };
+  let scope = &mut v8::HandleScope::new(scope);
+  Some(f(scope))
+}
+
 // TODO(@AaronO): optimize OpCtx(s) mem usage ?
 pub struct OpCtx {
   pub id: OpId,
@@ -130,6 +130,18 @@ impl JsRealm {
       .clone()
   }
 
+  /// Like [`JsRealm::state_from_scope`], but returns `None` if the current
+  /// context wasn't created by a [`JsRuntime`].
+  #[inline(always)]
+  pub(crate) fn try_state_from_scope(
+    scope: &mut v8::HandleScope,
+  ) -> Option<Rc<RefCell<ContextState>>> {
+    let context = scope.get_current_context();
+    context
+      .get_slot::<Rc<RefCell<ContextState>>>(scope)
+      .cloned()
+  }
+
   /// For info on the [`v8::Isolate`] parameter, check [`JsRealm#panics`].
   #[inline(always)]
   pub fn handle_scope<'s>(
@@ -7,6 +7,7 @@ use std::borrow::Cow;
 use std::cell::RefCell;
 use std::cmp::min;
 use std::convert::From;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -23,6 +24,7 @@ use deno_core::op;
 use deno_core::BufView;
 use deno_core::WriteOutcome;
 
+use deno_core::url::Host;
 use deno_core::url::Url;
 use deno_core::AsyncRefCell;
 use deno_core::AsyncResult;
@@ -43,6 +45,7 @@ use deno_tls::RootCertStoreProvider;
 use data_url::DataUrl;
 use http::header::CONTENT_LENGTH;
 use http::Uri;
+use hyper::client::connect::dns::Name;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderName;
 use reqwest::header::HeaderValue;
@@ -171,12 +174,25 @@ impl FetchHandler for DefaultFileFetchHandler {
   }
 }
 
+/// Checks an address that a host name resolved to, right before connecting
+/// to it.
+pub type ResolvedAddrCheck =
+  Arc<dyn Fn(&str, &SocketAddr) -> Result<(), AnyError> + Send + Sync>;
+
 pub trait FetchPermissions {
   fn check_net_url(
     &mut self,
     _url: &Url,
     api_name: &str,
   ) -> Result<(), AnyError>;
+  /// Checks the host of a URL before resolving it. The addresses it resolves
+  /// to are checked with `resolved_addr_check`.
+  fn check_net_url_unresolved(
+    &mut self,
+    _url: &Url,
+    api_name: &str,
+  ) -> Result<(), AnyError>;
+  fn resolved_addr_check(&self) -> ResolvedAddrCheck;
   fn check_read(&mut self, _p: &Path, api_name: &str) -> Result<(), AnyError>;
 }
 
@@ -191,24 +207,42 @@ pub struct FetchReturn {
   cancel_handle_rid: Option<ResourceId>,
 }
 
-pub fn get_or_create_client_from_state(
+pub fn get_or_create_client_from_state<FP>(
   state: &mut OpState,
-) -> Result<reqwest::Client, AnyError> {
-  if let Some(client) = state.try_borrow::<reqwest::Client>() {
-    Ok(client.clone())
-  } else {
-    let options = state.borrow::<Options>();
-    let client = create_http_client(
+) -> Result<reqwest::Client, AnyError>
+where
+  FP: FetchPermissions + 'static,
+{
+  Ok(get_or_create_default_client::<FP>(state)?.client)
+}
+
+fn get_or_create_default_client<FP>(
+  state: &mut OpState,
+) -> Result<HttpClientResource, AnyError>
+where
+  FP: FetchPermissions + 'static,
+{
+  if let Some(client) = state.try_borrow::<HttpClientResource>() {
+    return Ok(client.clone());
+  }
+  let options = state.borrow::<Options>();
+  let checks_resolved_addrs = options.proxy.is_none() && !has_system_proxy();
+  let resolved_addr_check =
+    checks_resolved_addrs.then(|| state.borrow::<FP>().resolved_addr_check());
+  let client = HttpClientResource {
+    client: create_http_client(
       &options.user_agent,
       options.root_cert_store()?,
       vec![],
       options.proxy.clone(),
       options.unsafely_ignore_certificate_errors.clone(),
       options.client_cert_chain_and_key.clone(),
-    )?;
-    state.put::<reqwest::Client>(client.clone());
-    Ok(client)
-  }
+      resolved_addr_check,
+    )?,
+    checks_resolved_addrs,
+  };
+  state.put::<HttpClientResource>(client.clone());
+  Ok(client)
 }
 
 #[op]
@@ -225,11 +259,14 @@ pub fn op_fetch<FP>(
 where
   FP: FetchPermissions + 'static,
 {
-  let client = if let Some(rid) = client_rid {
+  let HttpClientResource {
+    client,
+    checks_resolved_addrs,
+  } = if let Some(rid) = client_rid {
     let r = state.resource_table.get::<HttpClientResource>(rid)?;
-    r.client.clone()
+    (*r).clone()
   } else {
-    get_or_create_client_from_state(state)?
+    get_or_create_default_client::<FP>(state)?
   };
 
   let method = Method::from_bytes(&method)?;
@@ -267,7 +304,12 @@ where
     }
     "http" | "https" => {
       let permissions = state.borrow_mut::<FP>();
-      permissions.check_net_url(&url, "fetch()")?;
+      if checks_resolved_addrs {
+        permissions.check_net_url_unresolved(&url, "fetch()")?;
+      } else {
+        // a proxy resolves the host, so only its name can be checked
+        permissions.check_net_url(&url, "fetch()")?;
+      }
 
       // Make sure that we have a valid URI early, as reqwest's `RequestBuilder::send`
       // internally uses `expect_uri`, which panics instead of returning a usable `Result`.
@@ -590,8 +632,12 @@ impl Resource for FetchResponseBodyResource {
   }
 }
 
+#[derive(Clone)]
 struct HttpClientResource {
   client: Client,
+  /// Whether the client checks the addresses it resolves hosts to. It doesn't
+  /// when it connects through a proxy.
+  checks_resolved_addrs: bool,
 }
 
 impl Resource for HttpClientResource {
@@ -600,12 +646,6 @@ impl Resource for HttpClientResource {
   }
 }
 
-impl HttpClientResource {
-  fn new(client: Client) -> Self {
-    Self { client }
-  }
-}
-
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateHttpClientOptions {
@@ -651,6 +691,9 @@ where
     .map(|cert| cert.into_bytes())
     .collect::<Vec<_>>();
 
+  let checks_resolved_addrs = args.proxy.is_none() && !has_system_proxy();
+  let resolved_addr_check =
+    checks_resolved_addrs.then(|| state.borrow::<FP>().resolved_addr_check());
   let client = create_http_client(
     &options.user_agent,
     options.root_cert_store()?,
@@ -658,14 +701,56 @@ where
     args.proxy,
     options.unsafely_ignore_certificate_errors.clone(),
     client_cert_chain_and_key,
+    resolved_addr_check,
   )?;
 
-  let rid = state.resource_table.add(HttpClientResource::new(client));
+  let rid = state.resource_table.add(HttpClientResource {
+    client,
+    checks_resolved_addrs,
+  });
   Ok(rid)
 }
 
+/// Returns true if the environment configures proxies, which reqwest clients
+/// use unless they're given a proxy.
+fn has_system_proxy() -> bool {
+  ["HTTP_PROXY", "http_proxy", "HTTPS_PROXY", "https_proxy"]
+    .iter()
+    .any(|name| {
+      std::env::var(name)
+        .map(|value| !value.trim().is_empty())
+        .unwrap_or(false)
+    })
+}
+
+/// A resolver that checks the addresses host names resolve to before an HTTP
+/// client connects to them.
+struct CheckedResolver {
+  check: ResolvedAddrCheck,
+}
+
+impl reqwest::dns::Resolve for CheckedResolver {
+  fn resolve(&self, name: Name) -> reqwest::dns::Resolving {
+    let check = self.check.clone();
+    Box::pin(async move {
+      let host = name.as_str();
+      let addrs = tokio::net::lookup_host((host, 0))
+        .await?
+        .collect::<Vec<_>>();
+      for addr in &addrs {
+        check(host, addr)?;
+      }
+      let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+      Ok(addrs)
+    })
+  }
+}
+
 /// Create new instance of async reqwest::Client. This client supports
-/// proxies and doesn't follow redirects.
+/// proxies, negotiates HTTP/2 with servers that support it and doesn't follow
+/// redirects. Connections are pooled per origin, so a single client should be
+/// reused for many requests to the same host. With `resolved_addr_check`, the
+/// addresses hosts resolve to are checked before connecting to them.
 pub fn create_http_client(
   user_agent: &str,
   root_cert_store: Option<RootCertStore>,
@@ -673,6 +758,7 @@ pub fn create_http_client(
   proxy: Option<Proxy>,
   unsafely_ignore_certificate_errors: Option<Vec<String>>,
   client_cert_chain_and_key: Option<(String, String)>,
+  resolved_addr_check: Option<ResolvedAddrCheck>,
 ) -> Result<Client, AnyError> {
   let mut tls_config = deno_tls::create_client_config(
     root_cert_store,
@@ -688,10 +774,30 @@ pub fn create_http_client(
   let mut builder = Client::builder()
     .redirect(Policy::none())
     .default_headers(headers)
-    .use_preconfigured_tls(tls_config);
+    .use_preconfigured_tls(tls_config)
+    // grow the flow control window of HTTP/2 streams with the bandwidth, so
+    // that large bodies (eg. npm tarballs) aren't throttled by the default
+    // window while many small requests are multiplexed on one connection
+    .http2_adaptive_window(true);
+
+  if let Some(check) = resolved_addr_check {
+    builder = builder.dns_resolver(Arc::new(CheckedResolver { check }));
+  }
 
   if let Some(proxy) = proxy {
-    let mut reqwest_proxy = reqwest::Proxy::all(&proxy.url)?;
+    let mut reqwest_proxy = if proxy.no_proxy.is_empty() {
+      reqwest::Proxy::all(&proxy.url)?
+    } else {
+      let proxy_url = Url::parse(&proxy.url)?;
+      let no_proxy = proxy.no_proxy.clone();
+      reqwest::Proxy::custom(move |url| {
+        if is_no_proxy(&no_proxy, url) {
+          None
+        } else {
+          Some(proxy_url.clone())
+        }
+      })
+    };
     if let Some(basic_auth) = &proxy.basic_auth {
       reqwest_proxy =
         reqwest_proxy.basic_auth(&basic_auth.username, &basic_auth.password);
@@ -702,3 +808,92 @@ pub fn create_http_client(
   // unwrap here because it can only fail when native TLS is used.
   Ok(builder.build().unwrap())
 }
+
+/// Returns whether `url` should bypass the proxy. Like with `NO_PROXY`, `*`
+/// matches every host, while `example.com` and `.example.com` match the
+/// domain and all its subdomains. An entry with a port only matches that
+/// port.
+fn is_no_proxy(no_proxy: &[String], url: &Url) -> bool {
+  let host = match url.host() {
+    Some(Host::Domain(domain)) => domain.to_ascii_lowercase(),
+    Some(Host::Ipv4(ip)) => ip.to_string(),
+    Some(Host::Ipv6(ip)) => ip.to_string(),
+    None => return false,
+  };
+  let port = url.port_or_known_default();
+  no_proxy.iter().any(|entry| {
+    let entry = entry.trim();
+    if entry == "*" {
+      return true;
+    }
+    let (entry_host, entry_port) = split_no_proxy_entry(entry);
+    if entry_port.is_some() && entry_port != port {
+      return false;
+    }
+    let entry_host = entry_host.trim_start_matches('.').to_ascii_lowercase();
+    !entry_host.is_empty()
+      && (host == entry_host
+        || host
+          .strip_suffix(&entry_host)
+          .map(|prefix| prefix.ends_with('.'))
+          .unwrap_or(false))
+  })
+}
+
+/// Splits a `NO_PROXY` entry into its host and optional port. IPv6 addresses
+/// must be wrapped in brackets to be followed by a port.
+fn split_no_proxy_entry(entry: &str) -> (&str, Option<u16>) {
+  if let Some(rest) = entry.strip_prefix('[') {
+    if let Some((host, rest)) = rest.split_once(']') {
+      let port = rest.strip_prefix(':').and_then(|port| port.parse().ok());
+      return (host, port);
+    }
+  }
+  match entry.split_once(':') {
+    Some((host, port)) if !port.contains(':') => (host, port.parse().ok()),
+    _ => (entry, None),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_is_no_proxy() {
+    let no_proxy = vec![
+      "localhost".to_string(),
+      ".example.com".to_string(),
+      "deno.land:8080".to_string(),
+      "127.0.0.1".to_string(),
+      "[::1]".to_string(),
+    ];
+    let cases = [
+      ("http://localhost:4545/", true),
+      ("http://LOCALHOST/", true),
+      ("https://example.com/", true),
+      ("https://api.example.com/", true),
+      ("https://notexample.com/", false),
+      ("http://deno.land:8080/", true),
+      ("https://deno.land/", false),
+      ("http://127.0.0.1:4545/", true),
+      ("http://[::1]:4545/", true),
+      ("http://192.168.0.1/", false),
+    ];
+    for (url, expected) in cases {
+      assert_eq!(
+        is_no_proxy(&no_proxy, &Url::parse(url).unwrap()),
+        expected,
+        "{url}"
+      );
+    }
+    assert!(is_no_proxy(
+      &["*".to_string()],
+      &Url::parse("https://deno.land/").unwrap()
+    ));
+    assert!(!is_no_proxy(
+      &[],
+      &Url::parse("https://deno.land/").unwrap()
+    ));
+  }
+}
@@ -378,11 +378,6 @@ class UnsafeCallback {
   pointer;
 
   constructor(definition, callback) {
-    if (definition.nonblocking) {
-      throw new TypeError(
-        "Invalid UnsafeCallback, cannot be nonblocking",
-      );
-    }
     const { 0: rid, 1: pointer } = ops.op_ffi_unsafe_callback_create(
       definition,
       callback,
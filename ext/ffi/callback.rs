@@ -8,6 +8,7 @@ use crate::ForeignFunction;
 use crate::PendingFfiAsyncWork;
 use crate::MAX_SAFE_INTEGER;
 use crate::MIN_SAFE_INTEGER;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::futures::channel::mpsc;
 use deno_core::op;
@@ -30,8 +31,10 @@ use std::ptr;
 use std::ptr::NonNull;
 use std::rc::Rc;
 use std::sync::atomic;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU32;
 use std::sync::mpsc::sync_channel;
+use std::sync::Arc;
 use std::task::Poll;
 use std::task::Waker;
 
@@ -95,7 +98,9 @@ impl Resource for UnsafeCallbackResource {
 struct CallbackInfo {
   pub async_work_sender: mpsc::UnboundedSender<PendingFfiAsyncWork>,
   pub callback: NonNull<v8::Function>,
+  pub closed: Arc<AtomicBool>,
   pub context: NonNull<v8::Context>,
+  pub nonblocking: bool,
   pub parameters: Box<[NativeType]>,
   pub result: NativeType,
   pub thread_id: u32,
@@ -124,6 +129,52 @@ unsafe extern "C" fn deno_ffi_callback(
     if *s.borrow() == info.thread_id {
       // Own isolate thread, okay to call directly
       do_ffi_callback(cif, info, result, args);
+    } else if info.nonblocking {
+      // Nonblocking callbacks return void, so there is no need to wait for
+      // the isolate thread: copy the arguments and queue the call.
+      let arg_types =
+        std::slice::from_raw_parts(cif.arg_types, cif.nargs as usize);
+      let vals = std::slice::from_raw_parts(args, cif.nargs as usize);
+      let arg_values: Vec<Box<[u64]>> = arg_types
+        .iter()
+        .zip(vals)
+        .map(|(arg_type, val)| {
+          let size = (**arg_type).size;
+          // Use u64 words to keep the copied values aligned.
+          let mut value = vec![0u64; (size + 7) / 8].into_boxed_slice();
+          ptr::copy_nonoverlapping(
+            *val as *const u8,
+            value.as_mut_ptr() as *mut u8,
+            size,
+          );
+          value
+        })
+        .collect();
+      // SAFETY: The CIF and callback info are only accessed after checking
+      // that the callback has not been closed in the meantime.
+      let cif: &'static libffi::low::ffi_cif = std::mem::transmute(cif);
+      let info: &'static CallbackInfo = std::mem::transmute(info);
+      let closed = info.closed.clone();
+      let fut = Box::new(move || {
+        if closed.load(atomic::Ordering::Acquire) {
+          return;
+        }
+        let args: Vec<*const c_void> = arg_values
+          .iter()
+          .map(|value| value.as_ptr() as *const c_void)
+          .collect();
+        let mut result = 0u64;
+        do_ffi_callback(
+          cif,
+          info,
+          &mut *(&mut result as *mut u64 as *mut c_void),
+          args.as_ptr(),
+        );
+      });
+      info.async_work_sender.unbounded_send(fut).unwrap();
+      if let Some(waker) = info.waker.as_ref() {
+        waker.wake_by_ref();
+      }
     } else {
       let async_work_sender = &info.async_work_sender;
       // SAFETY: Safe as this function blocks until `do_ffi_callback` completes and a response message is received.
@@ -534,6 +585,8 @@ pub fn op_ffi_unsafe_callback_ref(
 pub struct RegisterCallbackArgs {
   parameters: Vec<NativeType>,
   result: NativeType,
+  #[serde(default)]
+  nonblocking: bool,
 }
 
 #[op(v8)]
@@ -553,6 +606,12 @@ where
   let v8_value = cb.v8_value;
   let cb = v8::Local::<v8::Function>::try_from(v8_value)?;
 
+  if args.nonblocking && args.result != NativeType::Void {
+    return Err(type_error(
+      "Invalid UnsafeCallback, nonblocking callbacks must return void",
+    ));
+  }
+
   let thread_id: u32 = LOCAL_THREAD_ID.with(|s| {
     let value = *s.borrow();
     if value == 0 {
@@ -577,7 +636,9 @@ where
   let info: *mut CallbackInfo = Box::leak(Box::new(CallbackInfo {
     async_work_sender,
     callback,
+    closed: Arc::new(AtomicBool::new(false)),
     context,
+    nonblocking: args.nonblocking,
     parameters: args.parameters.clone().into(),
     result: args.result.clone(),
     thread_id,
@@ -628,6 +689,8 @@ pub fn op_ffi_unsafe_callback_close(
     let callback_resource =
       state.resource_table.take::<UnsafeCallbackResource>(rid)?;
     let info = Box::from_raw(callback_resource.info);
+    // Calls from foreign threads that are still queued must not run anymore.
+    info.closed.store(true, atomic::Ordering::Release);
     let _ = v8::Global::from_raw(scope, info.callback);
     let _ = v8::Global::from_raw(scope, info.context);
     callback_resource.close();
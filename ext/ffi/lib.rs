@@ -149,7 +149,8 @@ fn event_loop_middleware(
 
     drop(op_state);
   }
-  while let Some(async_work_fut) = work_items.pop() {
+  // Run the callbacks in the order they were called.
+  for async_work_fut in work_items {
     async_work_fut();
   }
 
@@ -38,11 +38,14 @@ import { TcpConn } from "ext:deno_net/01_net.js";
 const {
   ObjectPrototypeIsPrototypeOf,
   PromisePrototypeCatch,
+  PromisePrototypeThen,
+  PromiseReject,
   SafeSet,
   SafeSetIterator,
   SetPrototypeAdd,
   SetPrototypeDelete,
   Symbol,
+  SymbolFor,
   TypeError,
   Uint8Array,
   Uint8ArrayPrototype,
@@ -60,6 +63,7 @@ const {
   op_http_set_response_body_text,
   op_http_set_response_header,
   op_http_set_response_headers,
+  op_http_shutdown,
   op_http_upgrade_raw,
   op_http_upgrade_websocket_next,
   op_http_wait,
@@ -75,11 +79,13 @@ const {
   "op_http_set_response_body_text",
   "op_http_set_response_header",
   "op_http_set_response_headers",
+  "op_http_shutdown",
   "op_http_upgrade_raw",
   "op_http_upgrade_websocket_next",
   "op_http_wait",
 );
 const _upgraded = Symbol("_upgraded");
+const promiseIdSymbol = SymbolFor("Deno.core.internalPromiseId");
 
 function internalServerError() {
   // "Internal Server Error"
@@ -334,6 +340,8 @@ class CallbackContext {
   fallbackHost;
   serverRid;
   closed;
+  refed = true;
+  waitPromise = null;
 
   initialize(args) {
     this.serverRid = args[0];
@@ -350,6 +358,72 @@ class CallbackContext {
       // Pass
     }
   }
+
+  shutdown() {
+    try {
+      op_http_shutdown(this.serverRid);
+    } catch {
+      // The server already stopped.
+    }
+  }
+
+  ref() {
+    this.refed = true;
+    if (this.waitPromise !== null) {
+      core.refOp(this.waitPromise[promiseIdSymbol]);
+    }
+  }
+
+  unref() {
+    this.refed = false;
+    if (this.waitPromise !== null) {
+      core.unrefOp(this.waitPromise[promiseIdSymbol]);
+    }
+  }
+}
+
+class HttpServer {
+  #context;
+  #finished;
+
+  constructor(context, finished) {
+    this.#context = context;
+    this.#finished = finished;
+  }
+
+  /** Settles once the server stopped serving requests. */
+  get finished() {
+    return this.#finished;
+  }
+
+  // Awaiting the server waits until it's finished.
+  then(onFulfilled, onRejected) {
+    return PromisePrototypeThen(this.#finished, onFulfilled, onRejected);
+  }
+
+  ref() {
+    this.#context?.ref();
+  }
+
+  unref() {
+    this.#context?.unref();
+  }
+
+  async shutdown(options = {}) {
+    const context = this.#context;
+    let timeout;
+    if (context !== null) {
+      context.shutdown();
+      if (options.timeout !== undefined) {
+        timeout = setTimeout(() => context.close(), options.timeout);
+      }
+    }
+    try {
+      await this.#finished;
+    } finally {
+      clearTimeout(timeout);
+    }
+  }
 }
 
 function fastSyncResponseOrStream(req, respBody) {
@@ -561,7 +635,16 @@ function mapToCallback(responseBodies, context, signal, callback, onError) {
   };
 }
 
-async function serve(arg1, arg2) {
+function serve(arg1, arg2) {
+  try {
+    return createServer(arg1, arg2);
+  } catch (error) {
+    // Report invalid options and listen errors through `finished`.
+    return new HttpServer(null, PromiseReject(error));
+  }
+}
+
+function createServer(arg1, arg2) {
   let options = undefined;
   let handler = undefined;
   if (typeof arg1 === "function") {
@@ -588,6 +671,7 @@ async function serve(arg1, arg2) {
   }
 
   const wantsHttps = options.cert || options.key;
+  const wantsUnix = options.path !== undefined;
   const signal = options.signal;
   const onError = options.onError ?? function (error) {
     console.error(error);
@@ -598,6 +682,12 @@ async function serve(arg1, arg2) {
     port: options.port ?? (wantsHttps ? 9000 : 8000),
     reusePort: options.reusePort ?? false,
   };
+  // HTTP/2 is negotiated over TLS by default, while cleartext HTTP/2 (h2c)
+  // must be opted into.
+  const protocols = {
+    http2: options.http2 ?? true,
+    h2c: options.h2c ?? false,
+  };
 
   const abortController = new AbortController();
 
@@ -611,7 +701,20 @@ async function serve(arg1, arg2) {
     onError,
   );
 
-  if (wantsHttps) {
+  if (wantsUnix) {
+    if (wantsHttps) {
+      throw new TypeError("Serving HTTPS over a Unix socket is not supported.");
+    }
+    const listener = Deno.listen({
+      transport: "unix",
+      path: options.path,
+    });
+    listenOpts.path = listener.addr.path;
+    context.initialize(op_http_serve(
+      listener.rid,
+      protocols,
+    ));
+  } else if (wantsHttps) {
     if (!options.cert || !options.key) {
       throw new TypeError(
         "Both cert and key must be provided to enable HTTPS.",
@@ -619,17 +722,21 @@ async function serve(arg1, arg2) {
     }
     listenOpts.cert = options.cert;
     listenOpts.key = options.key;
-    listenOpts.alpnProtocols = ["h2", "http/1.1"];
+    listenOpts.alpnProtocols = protocols.http2
+      ? ["h2", "http/1.1"]
+      : ["http/1.1"];
     const listener = Deno.listenTls(listenOpts);
     listenOpts.port = listener.addr.port;
     context.initialize(op_http_serve(
       listener.rid,
+      protocols,
     ));
   } else {
     const listener = Deno.listen(listenOpts);
     listenOpts.port = listener.addr.port;
     context.initialize(op_http_serve(
       listener.rid,
+      protocols,
     ));
   }
 
@@ -639,7 +746,11 @@ async function serve(arg1, arg2) {
     { once: true },
   );
 
-  const onListen = options.onListen ?? function ({ port }) {
+  const onListen = options.onListen ?? function ({ port, path }) {
+    if (wantsUnix) {
+      console.log(`Listening on ${context.scheme}${path}`);
+      return;
+    }
     // If the hostname is "0.0.0.0", we display "localhost" in console
     // because browsers in Windows don't resolve "0.0.0.0".
     // See the discussion in https://github.com/denoland/deno_std/issues/1165
@@ -649,18 +760,31 @@ async function serve(arg1, arg2) {
     console.log(`Listening on ${context.scheme}${hostname}:${port}/`);
   };
 
-  onListen({ port: listenOpts.port });
+  onListen(wantsUnix ? { path: listenOpts.path } : { port: listenOpts.port });
 
+  return new HttpServer(
+    context,
+    serveRequests(context, callback, responseBodies),
+  );
+}
+
+async function serveRequests(context, callback, responseBodies) {
   while (true) {
     const rid = context.serverRid;
     let req;
     try {
-      req = await op_http_wait(rid);
+      context.waitPromise = op_http_wait(rid);
+      if (!context.refed) {
+        core.unrefOp(context.waitPromise[promiseIdSymbol]);
+      }
+      req = await context.waitPromise;
     } catch (error) {
       if (ObjectPrototypeIsPrototypeOf(BadResourcePrototype, error)) {
         break;
       }
       throw new Deno.errors.Http(error);
+    } finally {
+      context.waitPromise = null;
     }
     if (req === 0xffffffff) {
       break;
@@ -680,6 +804,32 @@ async function serve(arg1, arg2) {
   }
 }
 
+/**
+ * Serves the fetch handler of the default export of the main module, which
+ * is how `deno serve` runs a module. The server is closed on unload, so the
+ * port is released when the file watcher restarts the module.
+ */
+function serveMainModule(main, options) {
+  const handler = main.default;
+  if (typeof handler?.fetch !== "function") {
+    throw new TypeError(
+      "The default export of the main module must be an object with a fetch method, for example `export default { fetch(req) { return new Response(); } }`.",
+    );
+  }
+  const abortController = new AbortController();
+  globalThis.addEventListener("unload", () => abortController.abort(), {
+    once: true,
+  });
+  return serve({
+    port: options.port,
+    hostname: options.hostname,
+    reusePort: options.reusePort,
+    signal: abortController.signal,
+    handler: (req) => handler.fetch(req),
+  });
+}
+
 internals.upgradeHttpRaw = upgradeHttpRaw;
+internals.serveMainModule = serveMainModule;
 
 export { serve, upgradeHttpRaw };
@@ -15,6 +15,9 @@ use crate::websocket_upgrade::WebSocketUpgrade;
 use crate::LocalExecutor;
 use cache_control::CacheControl;
 use deno_core::error::AnyError;
+use deno_core::futures::future::pending;
+use deno_core::futures::future::select;
+use deno_core::futures::future::Either;
 use deno_core::futures::TryFutureExt;
 use deno_core::op;
 use deno_core::task::spawn;
@@ -54,11 +57,13 @@ use hyper1::upgrade::OnUpgrade;
 use hyper1::StatusCode;
 use pin_project::pin_project;
 use pin_project::pinned_drop;
+use serde::Deserialize;
 use slab::Slab;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::future::Future;
 use std::io;
+use std::pin::pin;
 use std::pin::Pin;
 use std::rc::Rc;
 
@@ -88,6 +93,28 @@ const TLS_ALPN_HTTP_2: &[u8] = b"h2";
 /// ALPN negotation for "http/1.1"
 const TLS_ALPN_HTTP_11: &[u8] = b"http/1.1";
 
+/// The maximum number of concurrent streams a client may open on a single
+/// HTTP/2 connection. Streams beyond this are refused until others complete,
+/// which bounds the number of requests a single connection can queue up.
+const HTTP2_MAX_CONCURRENT_STREAMS: u32 = 256;
+
+/// The maximum number of bytes buffered per HTTP/2 stream for sending. A
+/// response body isn't read any further until the client has consumed the
+/// buffered data, so slow clients apply backpressure to the handler.
+const HTTP2_MAX_SEND_BUF_SIZE: usize = 1024 * 1024;
+
+/// The protocols a server started with `op_http_serve` speaks.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpServeProtocols {
+  /// Negotiate HTTP/2 with the "h2" ALPN protocol over TLS.
+  http2: bool,
+  /// Accept HTTP/2 over cleartext connections from clients with prior
+  /// knowledge ("h2c"). This is opt-in, as a plain text connection starting
+  /// with the HTTP/2 connection preface is otherwise served as HTTP/1.1.
+  h2c: bool,
+}
+
 /// Name a trait for streams we can serve HTTP over.
 trait HttpServeStream:
   tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static
@@ -485,23 +512,37 @@ fn is_request_compressible(headers: &HeaderMap) -> Compression {
   let Some(accept_encoding) = headers.get(ACCEPT_ENCODING) else {
     return Compression::None;
   };
-  // Firefox and Chrome send this -- no need to parse
+  // Firefox and Chrome send these -- no need to parse
+  if accept_encoding == "gzip, deflate, br, zstd" {
+    return Compression::Zstd;
+  }
   if accept_encoding == "gzip, deflate, br" {
-    return Compression::GZip;
+    return Compression::Brotli;
   }
   if accept_encoding == "gzip" {
     return Compression::GZip;
   }
   // Fall back to the expensive parser
   let accepted = fly_accept_encoding::encodings_iter(headers).filter(|r| {
-    matches!(r, Ok((Some(Encoding::Identity | Encoding::Gzip), _)))
+    matches!(
+      r,
+      Ok((
+        Some(
+          Encoding::Identity
+            | Encoding::Gzip
+            | Encoding::Brotli
+            | Encoding::Zstd
+        ),
+        _
+      ))
+    )
   });
-  #[allow(clippy::single_match)]
   match fly_accept_encoding::preferred(accepted) {
-    Ok(Some(fly_accept_encoding::Encoding::Gzip)) => return Compression::GZip,
-    _ => {}
+    Ok(Some(Encoding::Gzip)) => Compression::GZip,
+    Ok(Some(Encoding::Brotli)) => Compression::Brotli,
+    Ok(Some(Encoding::Zstd)) => Compression::Zstd,
+    _ => Compression::None,
   }
-  Compression::None
 }
 
 fn is_response_compressible(headers: &HeaderMap) -> bool {
@@ -551,7 +592,10 @@ fn modify_compressibility_from_response(
   }
   weaken_etag(headers);
   headers.remove(CONTENT_LENGTH);
-  headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+  headers.insert(
+    CONTENT_ENCODING,
+    HeaderValue::from_static(compression.content_encoding().unwrap()),
+  );
   compression
 }
 
@@ -740,59 +784,96 @@ impl<F: Future<Output = ()>> Future for SlabFuture<F> {
   }
 }
 
-fn serve_http11_unconditional(
+/// Polls a connection to completion. Once the `graceful` cancel handle is
+/// used, the connection shuts down gracefully: no new requests are accepted,
+/// but the ones in flight are answered.
+async fn serve_until_graceful_shutdown<C>(
+  conn: C,
+  graceful: Rc<CancelHandle>,
+  graceful_shutdown: impl FnOnce(Pin<&mut C>),
+) -> Result<(), AnyError>
+where
+  C: Future<Output = Result<(), hyper1::Error>>,
+{
+  let conn = pin!(conn);
+  let shutdown = pin!(pending::<()>().or_cancel(graceful));
+  match select(conn, shutdown).await {
+    Either::Left((result, _)) => result.map_err(AnyError::from),
+    Either::Right((_, mut conn)) => {
+      graceful_shutdown(conn.as_mut());
+      conn.await.map_err(AnyError::from)
+    }
+  }
+}
+
+async fn serve_http11_unconditional(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = ResponseBytes> + 'static,
-) -> impl Future<Output = Result<(), AnyError>> + 'static {
+  graceful: Rc<CancelHandle>,
+) -> Result<(), AnyError> {
   let conn = http1::Builder::new()
     .keep_alive(true)
-    .serve_connection(io, svc);
+    .serve_connection(io, svc)
+    .with_upgrades();
 
-  conn.with_upgrades().map_err(AnyError::from)
+  serve_until_graceful_shutdown(conn, graceful, |conn| conn.graceful_shutdown())
+    .await
 }
 
-fn serve_http2_unconditional(
+async fn serve_http2_unconditional(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = ResponseBytes> + 'static,
-) -> impl Future<Output = Result<(), AnyError>> + 'static {
-  let conn = http2::Builder::new(LocalExecutor).serve_connection(io, svc);
-  conn.map_err(AnyError::from)
+  graceful: Rc<CancelHandle>,
+) -> Result<(), AnyError> {
+  // Flow control windows are only grown as request bodies are read, so a
+  // handler that doesn't consume a body applies backpressure to the client.
+  let conn = http2::Builder::new(LocalExecutor)
+    .adaptive_window(true)
+    .max_concurrent_streams(HTTP2_MAX_CONCURRENT_STREAMS)
+    .max_send_buf_size(HTTP2_MAX_SEND_BUF_SIZE)
+    .serve_connection(io, svc);
+
+  serve_until_graceful_shutdown(conn, graceful, |conn| conn.graceful_shutdown())
+    .await
 }
 
 async fn serve_http2_autodetect(
   io: impl HttpServeStream,
   svc: impl HttpService<Incoming, ResBody = ResponseBytes> + 'static,
+  graceful: Rc<CancelHandle>,
 ) -> Result<(), AnyError> {
   let prefix = NetworkStreamPrefixCheck::new(io, HTTP2_PREFIX);
   let (matches, io) = prefix.match_prefix().await?;
   if matches {
-    serve_http2_unconditional(io, svc).await
+    serve_http2_unconditional(io, svc, graceful).await
   } else {
-    serve_http11_unconditional(io, svc).await
+    serve_http11_unconditional(io, svc, graceful).await
   }
 }
 
 fn serve_https(
   mut io: TlsStream,
   request_info: HttpConnectionProperties,
+  protocols: HttpServeProtocols,
   cancel: Rc<CancelHandle>,
+  graceful: Rc<CancelHandle>,
   tx: tokio::sync::mpsc::Sender<u32>,
 ) -> JoinHandle<Result<(), AnyError>> {
   let svc = service_fn(move |req: Request| {
     new_slab_future(req, request_info.clone(), tx.clone())
   });
   spawn(
-    async {
+    async move {
       io.handshake().await?;
       // If the client specifically negotiates a protocol, we will use it. If not, we'll auto-detect
       // based on the prefix bytes
       let handshake = io.get_ref().1.alpn_protocol();
-      if handshake == Some(TLS_ALPN_HTTP_2) {
-        serve_http2_unconditional(io, svc).await
-      } else if handshake == Some(TLS_ALPN_HTTP_11) {
-        serve_http11_unconditional(io, svc).await
+      if !protocols.http2 || handshake == Some(TLS_ALPN_HTTP_11) {
+        serve_http11_unconditional(io, svc, graceful).await
+      } else if handshake == Some(TLS_ALPN_HTTP_2) {
+        serve_http2_unconditional(io, svc, graceful).await
       } else {
-        serve_http2_autodetect(io, svc).await
+        serve_http2_autodetect(io, svc, graceful).await
       }
     }
     .try_or_cancel(cancel),
@@ -802,19 +883,27 @@ fn serve_https(
 fn serve_http(
   io: impl HttpServeStream,
   request_info: HttpConnectionProperties,
+  protocols: HttpServeProtocols,
   cancel: Rc<CancelHandle>,
+  graceful: Rc<CancelHandle>,
   tx: tokio::sync::mpsc::Sender<u32>,
 ) -> JoinHandle<Result<(), AnyError>> {
   let svc = service_fn(move |req: Request| {
     new_slab_future(req, request_info.clone(), tx.clone())
   });
-  spawn(serve_http2_autodetect(io, svc).try_or_cancel(cancel))
+  if protocols.h2c {
+    spawn(serve_http2_autodetect(io, svc, graceful).try_or_cancel(cancel))
+  } else {
+    spawn(serve_http11_unconditional(io, svc, graceful).try_or_cancel(cancel))
+  }
 }
 
 fn serve_http_on<HTTP>(
   connection: HTTP::Connection,
   listen_properties: &HttpListenProperties,
+  protocols: HttpServeProtocols,
   cancel: Rc<CancelHandle>,
+  graceful: Rc<CancelHandle>,
   tx: tokio::sync::mpsc::Sender<u32>,
 ) -> JoinHandle<Result<(), AnyError>>
 where
@@ -827,14 +916,14 @@ where
 
   match network_stream {
     NetworkStream::Tcp(conn) => {
-      serve_http(conn, connection_properties, cancel, tx)
+      serve_http(conn, connection_properties, protocols, cancel, graceful, tx)
     }
     NetworkStream::Tls(conn) => {
-      serve_https(conn, connection_properties, cancel, tx)
+      serve_https(conn, connection_properties, protocols, cancel, graceful, tx)
     }
     #[cfg(unix)]
     NetworkStream::Unix(conn) => {
-      serve_http(conn, connection_properties, cancel, tx)
+      serve_http(conn, connection_properties, protocols, cancel, graceful, tx)
     }
   }
 }
@@ -844,12 +933,27 @@ struct HttpJoinHandle(
   // Cancel handle must live in a separate Rc to avoid keeping the outer join handle ref'd
   Rc<CancelHandle>,
   AsyncRefCell<tokio::sync::mpsc::Receiver<u32>>,
+  // Stops accepting connections and shuts down the open ones gracefully
+  Rc<CancelHandle>,
 );
 
 impl HttpJoinHandle {
+  fn new(rx: tokio::sync::mpsc::Receiver<u32>) -> Self {
+    Self(
+      AsyncRefCell::new(None),
+      CancelHandle::new_rc(),
+      AsyncRefCell::new(rx),
+      CancelHandle::new_rc(),
+    )
+  }
+
   fn cancel_handle(self: &Rc<Self>) -> Rc<CancelHandle> {
     self.1.clone()
   }
+
+  fn graceful_cancel_handle(self: &Rc<Self>) -> Rc<CancelHandle> {
+    self.3.clone()
+  }
 }
 
 impl Resource for HttpJoinHandle {
@@ -873,6 +977,7 @@ impl Drop for HttpJoinHandle {
 pub fn op_http_serve<HTTP>(
   state: Rc<RefCell<OpState>>,
   listener_rid: ResourceId,
+  protocols: HttpServeProtocols,
 ) -> Result<(ResourceId, &'static str, String), AnyError>
 where
   HTTP: HttpPropertyExtractor,
@@ -883,27 +988,32 @@ where
   let listen_properties = HTTP::listen_properties_from_listener(&listener)?;
 
   let (tx, rx) = tokio::sync::mpsc::channel(10);
-  let resource: Rc<HttpJoinHandle> = Rc::new(HttpJoinHandle(
-    AsyncRefCell::new(None),
-    CancelHandle::new_rc(),
-    AsyncRefCell::new(rx),
-  ));
+  let resource: Rc<HttpJoinHandle> = Rc::new(HttpJoinHandle::new(rx));
   let cancel_clone = resource.cancel_handle();
+  let graceful_clone = resource.graceful_cancel_handle();
 
   let listen_properties_clone: HttpListenProperties = listen_properties.clone();
   let handle = spawn(async move {
     loop {
-      let conn = HTTP::accept_connection_from_listener(&listener)
+      // The listener is dropped once a graceful shutdown starts, while the
+      // connections that are already open keep being served.
+      let conn = match HTTP::accept_connection_from_listener(&listener)
         .try_or_cancel(cancel_clone.clone())
-        .await?;
+        .or_cancel(graceful_clone.clone())
+        .await
+      {
+        Ok(conn) => conn?,
+        Err(_) => break,
+      };
       serve_http_on::<HTTP>(
         conn,
         &listen_properties_clone,
+        protocols,
         cancel_clone.clone(),
+        graceful_clone.clone(),
         tx.clone(),
       );
     }
-    #[allow(unreachable_code)]
     Ok::<_, AnyError>(())
   });
 
@@ -923,6 +1033,7 @@ where
 pub fn op_http_serve_on<HTTP>(
   state: Rc<RefCell<OpState>>,
   connection_rid: ResourceId,
+  protocols: HttpServeProtocols,
 ) -> Result<(ResourceId, &'static str, String), AnyError>
 where
   HTTP: HttpPropertyExtractor,
@@ -933,17 +1044,15 @@ where
   let listen_properties = HTTP::listen_properties_from_connection(&connection)?;
 
   let (tx, rx) = tokio::sync::mpsc::channel(10);
-  let resource: Rc<HttpJoinHandle> = Rc::new(HttpJoinHandle(
-    AsyncRefCell::new(None),
-    CancelHandle::new_rc(),
-    AsyncRefCell::new(rx),
-  ));
+  let resource: Rc<HttpJoinHandle> = Rc::new(HttpJoinHandle::new(rx));
 
   let handle: JoinHandle<Result<(), deno_core::anyhow::Error>> =
     serve_http_on::<HTTP>(
       connection,
       &listen_properties,
+      protocols,
       resource.cancel_handle(),
+      resource.graceful_cancel_handle(),
       tx,
     );
 
@@ -959,6 +1068,18 @@ where
   ))
 }
 
+/// Stops accepting new connections and requests, so the `op_http_wait` loop
+/// ends once the requests in flight are answered.
+#[op]
+pub fn op_http_shutdown(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<(), AnyError> {
+  let join_handle = state.resource_table.get::<HttpJoinHandle>(rid)?;
+  join_handle.graceful_cancel_handle().cancel();
+  Ok(())
+}
+
 #[op]
 pub async fn op_http_wait(
   state: Rc<RefCell<OpState>>,
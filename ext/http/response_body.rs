@@ -110,10 +110,42 @@ trait PollFrame: Unpin {
   fn size_hint(&self) -> SizeHint;
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Compression {
   None,
   GZip,
+  Brotli,
+  Zstd,
+}
+
+impl Compression {
+  /// The value of the `Content-Encoding` header for this compression.
+  pub fn content_encoding(&self) -> Option<&'static str> {
+    match self {
+      Compression::None => None,
+      Compression::GZip => Some("gzip"),
+      Compression::Brotli => Some("br"),
+      Compression::Zstd => Some("zstd"),
+    }
+  }
+
+  fn compress(&self, bytes: &[u8]) -> Vec<u8> {
+    match self {
+      Compression::None => bytes.to_vec(),
+      Compression::GZip => {
+        let mut writer =
+          GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        writer.write_all(bytes).unwrap();
+        writer.finish().unwrap()
+      }
+      Compression::Brotli | Compression::Zstd => {
+        let mut encoder = ResponseEncoder::new(*self);
+        let mut out = encoder.write(bytes).unwrap();
+        out.extend(encoder.finish().unwrap());
+        out
+      }
+    }
+  }
 }
 
 pub enum ResponseStream {
@@ -136,6 +168,8 @@ pub enum ResponseBytesInner {
   UncompressedStream(ResponseStream),
   /// A GZip stream.
   GZipStream(GZipResponseStream),
+  /// A Brotli or Zstd stream.
+  EncoderStream(EncoderResponseStream),
 }
 
 impl std::fmt::Debug for ResponseBytesInner {
@@ -146,6 +180,7 @@ impl std::fmt::Debug for ResponseBytesInner {
       Self::Bytes(..) => f.write_str("Bytes"),
       Self::UncompressedStream(..) => f.write_str("Uncompressed"),
       Self::GZipStream(..) => f.write_str("GZip"),
+      Self::EncoderStream(..) => f.write_str("Encoder"),
     }
   }
 }
@@ -185,14 +220,17 @@ impl ResponseBytesInner {
       Self::Bytes(bytes) => SizeHint::with_exact(bytes.len() as u64),
       Self::UncompressedStream(res) => res.size_hint(),
       Self::GZipStream(..) => SizeHint::default(),
+      Self::EncoderStream(..) => SizeHint::default(),
     }
   }
 
   fn from_stream(compression: Compression, stream: ResponseStream) -> Self {
-    if compression == Compression::GZip {
-      Self::GZipStream(GZipResponseStream::new(stream))
-    } else {
-      Self::UncompressedStream(stream)
+    match compression {
+      Compression::None => Self::UncompressedStream(stream),
+      Compression::GZip => Self::GZipStream(GZipResponseStream::new(stream)),
+      Compression::Brotli | Compression::Zstd => {
+        Self::EncoderStream(EncoderResponseStream::new(compression, stream))
+      }
     }
   }
 
@@ -215,22 +253,14 @@ impl ResponseBytesInner {
   }
 
   pub fn from_slice(compression: Compression, bytes: &[u8]) -> Self {
-    if compression == Compression::GZip {
-      let mut writer = GzEncoder::new(Vec::new(), flate2::Compression::fast());
-      writer.write_all(bytes).unwrap();
-      Self::Bytes(BufView::from(writer.finish().unwrap()))
-    } else {
-      Self::Bytes(BufView::from(bytes.to_vec()))
-    }
+    Self::Bytes(BufView::from(compression.compress(bytes)))
   }
 
   pub fn from_vec(compression: Compression, vec: Vec<u8>) -> Self {
-    if compression == Compression::GZip {
-      let mut writer = GzEncoder::new(Vec::new(), flate2::Compression::fast());
-      writer.write_all(&vec).unwrap();
-      Self::Bytes(BufView::from(writer.finish().unwrap()))
-    } else {
+    if compression == Compression::None {
       Self::Bytes(BufView::from(vec))
+    } else {
+      Self::Bytes(BufView::from(compression.compress(&vec)))
     }
   }
 }
@@ -258,6 +288,9 @@ impl Body for ResponseBytes {
         ResponseBytesInner::GZipStream(stm) => {
           ready!(Pin::new(stm).poll_frame(cx))
         }
+        ResponseBytesInner::EncoderStream(stm) => {
+          ready!(Pin::new(stm).poll_frame(cx))
+        }
       };
       // This is where we retry the NoData response
       if matches!(res, ResponseStreamResult::NoData) {
@@ -527,6 +560,114 @@ impl PollFrame for GZipResponseStream {
   }
 }
 
+/// An encoder for the compressions that are only available as
+/// `std::io::Write` implementations.
+enum ResponseEncoder {
+  Brotli(Box<brotli::CompressorWriter<Vec<u8>>>),
+  Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+}
+
+impl ResponseEncoder {
+  fn new(compression: Compression) -> Self {
+    match compression {
+      // Quality 6 is the default of google's nginx module for on-the-fly
+      // compression, with a window of (2**22)-16 bytes (~4MB).
+      //
+      // https://github.com/google/ngx_brotli#brotli_comp_level
+      Compression::Brotli => Self::Brotli(Box::new(
+        brotli::CompressorWriter::new(Vec::new(), 4096, 6, 22),
+      )),
+      // Creating an encoder only fails if the level is out of range.
+      Compression::Zstd => Self::Zstd(
+        zstd::stream::write::Encoder::new(
+          Vec::new(),
+          zstd::DEFAULT_COMPRESSION_LEVEL,
+        )
+        .unwrap(),
+      ),
+      Compression::None | Compression::GZip => unreachable!(),
+    }
+  }
+
+  /// Compresses and flushes `buf`, returning all the output that's
+  /// available so far.
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<Vec<u8>> {
+    let out = match self {
+      Self::Brotli(encoder) => {
+        encoder.write_all(buf)?;
+        encoder.flush()?;
+        encoder.get_mut()
+      }
+      Self::Zstd(encoder) => {
+        encoder.write_all(buf)?;
+        encoder.flush()?;
+        encoder.get_mut()
+      }
+    };
+    Ok(std::mem::take(out))
+  }
+
+  fn finish(self) -> std::io::Result<Vec<u8>> {
+    match self {
+      Self::Brotli(encoder) => Ok(encoder.into_inner()),
+      Self::Zstd(encoder) => encoder.finish(),
+    }
+  }
+}
+
+/// A Brotli or Zstd compressed stream. Unlike [`GZipResponseStream`], each
+/// chunk of the underlying stream is flushed through the encoder, so that it
+/// is sent as soon as possible.
+#[pin_project]
+pub struct EncoderResponseStream {
+  encoder: Option<ResponseEncoder>,
+  #[pin]
+  underlying: ResponseStream,
+}
+
+impl EncoderResponseStream {
+  pub fn new(compression: Compression, underlying: ResponseStream) -> Self {
+    Self {
+      encoder: Some(ResponseEncoder::new(compression)),
+      underlying,
+    }
+  }
+}
+
+impl PollFrame for EncoderResponseStream {
+  fn poll_frame(
+    self: Pin<&mut Self>,
+    cx: &mut std::task::Context<'_>,
+  ) -> std::task::Poll<ResponseStreamResult> {
+    let this = self.get_mut();
+    if this.encoder.is_none() {
+      return std::task::Poll::Ready(ResponseStreamResult::EndOfStream);
+    }
+    let res = match ready!(Pin::new(&mut this.underlying).poll_frame(cx)) {
+      ResponseStreamResult::NonEmptyBuf(buf) => {
+        match this.encoder.as_mut().unwrap().write(&buf) {
+          Ok(out) if out.is_empty() => ResponseStreamResult::NoData,
+          Ok(out) => ResponseStreamResult::NonEmptyBuf(BufView::from(out)),
+          Err(err) => ResponseStreamResult::Error(err.into()),
+        }
+      }
+      ResponseStreamResult::EndOfStream => {
+        match this.encoder.take().unwrap().finish() {
+          Ok(out) if out.is_empty() => ResponseStreamResult::EndOfStream,
+          Ok(out) => ResponseStreamResult::NonEmptyBuf(BufView::from(out)),
+          Err(err) => ResponseStreamResult::Error(err.into()),
+        }
+      }
+      x => x,
+    };
+    std::task::Poll::Ready(res)
+  }
+
+  fn size_hint(&self) -> SizeHint {
+    SizeHint::default()
+  }
+}
+
 /// A response body object that can be passed to V8. This body will feed byte buffers to a channel which
 /// feed's hyper's HTTP response.
 pub struct V8StreamHttpResponseBody(
@@ -747,4 +888,61 @@ mod tests {
   test!(zeros);
   test!(hard_to_gzip_data);
   test!(already_gzipped_data);
+
+  async fn test_encoder(
+    compression: Compression,
+    i: impl Iterator<Item = Vec<u8>> + Send + 'static,
+  ) -> (Vec<u8>, Vec<u8>) {
+    let v = i.collect::<Vec<_>>();
+    let expected = v.concat();
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    let underlying = ResponseStream::V8Stream(rx);
+    let mut resp = EncoderResponseStream::new(compression, underlying);
+    let handle = tokio::task::spawn(async move {
+      for chunk in v {
+        tx.send(chunk.into()).await.ok().unwrap();
+      }
+    });
+    let mut v: Vec<u8> = vec![];
+    loop {
+      let frame = poll_fn(|cx| Pin::new(&mut resp).poll_frame(cx)).await;
+      match frame {
+        ResponseStreamResult::EndOfStream => break,
+        ResponseStreamResult::NoData => continue,
+        ResponseStreamResult::NonEmptyBuf(buf) => v.extend(&*buf),
+        _ => panic!("Unexpected stream type"),
+      }
+    }
+    handle.await.unwrap();
+    (v, expected)
+  }
+
+  #[tokio::test]
+  async fn test_brotli() {
+    let (compressed, expected) =
+      test_encoder(Compression::Brotli, random(hard_to_gzip_data())).await;
+    let mut v = vec![];
+    brotli::Decompressor::new(&*compressed, 4096)
+      .read_to_end(&mut v)
+      .unwrap();
+    assert_eq!(v, expected);
+  }
+
+  #[tokio::test]
+  async fn test_zstd() {
+    let (compressed, expected) =
+      test_encoder(Compression::Zstd, chunk(zeros())).await;
+    assert_eq!(zstd::decode_all(&*compressed).unwrap(), expected);
+  }
+
+  #[test]
+  fn test_compress_slice() {
+    let data = b"hello world".repeat(100);
+    match ResponseBytesInner::from_slice(Compression::Zstd, &data) {
+      ResponseBytesInner::Bytes(buf) => {
+        assert_eq!(zstd::decode_all(&*buf).unwrap(), data);
+      }
+      _ => panic!("Unexpected response type"),
+    }
+  }
 }
@@ -1,5 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+import { ReadableStream } from "ext:deno_web/06_streams.js";
+
 // @ts-ignore internal api
 const {
   AsyncGeneratorPrototype,
@@ -203,6 +205,36 @@ class Kv {
     };
   }
 
+  watch(keys: Deno.KvKey[]): ReadableStream<Deno.KvEntryMaybe<unknown>[]> {
+    const rid = ops.op_kv_watch(this.#rid, keys);
+    return new ReadableStream({
+      async pull(controller) {
+        const entries: (RawKvEntry | null)[] | null = await core.opAsync(
+          "op_kv_watch_next",
+          rid,
+        );
+        if (entries === null) {
+          core.tryClose(rid);
+          controller.close();
+          return;
+        }
+        controller.enqueue(entries.map((entry, i) => {
+          if (entry === null) {
+            return {
+              key: keys[i],
+              value: null,
+              versionstamp: null,
+            };
+          }
+          return deserializeValue(entry);
+        }));
+      },
+      cancel() {
+        core.tryClose(rid);
+      },
+    });
+  }
+
   close() {
     core.close(this.#rid);
   }
@@ -3,10 +3,12 @@
 use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::num::NonZeroU32;
+use std::pin::Pin;
 use std::rc::Rc;
 
 use async_trait::async_trait;
 use deno_core::error::AnyError;
+use deno_core::futures::Stream;
 use deno_core::OpState;
 use num_bigint::BigInt;
 
@@ -35,8 +37,18 @@ pub trait Database {
     &self,
     write: AtomicWrite,
   ) -> Result<Option<CommitResult>, AnyError>;
+
+  /// Watches the given keys for changes. The returned stream first yields the
+  /// current entries of all keys, and then yields them again every time at
+  /// least one of the entries changed. An entry is `None` if the key doesn't
+  /// exist. The stream ends once the database is closed.
+  fn watch(&self, keys: Vec<Vec<u8>>) -> WatchStream;
 }
 
+/// A stream of the entries of watched keys, see [`Database::watch`].
+pub type WatchStream =
+  Pin<Box<dyn Stream<Item = Result<Vec<Option<KvEntry>>, AnyError>>>>;
+
 /// Options for a snapshot read.
 pub struct SnapshotReadOptions {
   pub consistency: Consistency,
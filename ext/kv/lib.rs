@@ -14,11 +14,16 @@ use codec::encode_key;
 use deno_core::anyhow::Context;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
+use deno_core::futures::StreamExt;
 use deno_core::op;
 use deno_core::serde_v8::AnyValue;
 use deno_core::serde_v8::BigInt;
+use deno_core::AsyncRefCell;
 use deno_core::ByteString;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
 use deno_core::OpState;
+use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ResourceId;
 use deno_core::ZeroCopyBuf;
@@ -35,6 +40,7 @@ const MAX_READ_RANGES: usize = 10;
 const MAX_READ_ENTRIES: usize = 1000;
 const MAX_CHECKS: usize = 10;
 const MAX_MUTATIONS: usize = 10;
+const MAX_WATCHED_KEYS: usize = 10;
 
 struct UnstableChecker {
   pub unstable: bool,
@@ -53,13 +59,15 @@ impl UnstableChecker {
 }
 
 deno_core::extension!(deno_kv,
-  deps = [ deno_console ],
+  deps = [ deno_console, deno_web ],
   parameters = [ DBH: DatabaseHandler ],
   ops = [
     op_kv_database_open<DBH>,
     op_kv_snapshot_read<DBH>,
     op_kv_atomic_write<DBH>,
     op_kv_encode_cursor,
+    op_kv_watch<DBH>,
+    op_kv_watch_next,
   ],
   esm = [ "01_db.ts" ],
   options = {
@@ -587,6 +595,78 @@ where
   Ok(result.map(|res| hex::encode(res.versionstamp)))
 }
 
+struct DatabaseWatcherResource {
+  stream: AsyncRefCell<WatchStream>,
+  cancel: CancelHandle,
+}
+
+impl Resource for DatabaseWatcherResource {
+  fn name(&self) -> Cow<str> {
+    "databaseWatcher".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel()
+  }
+}
+
+#[op]
+fn op_kv_watch<DBH>(
+  state: &mut OpState,
+  rid: ResourceId,
+  keys: Vec<KvKey>,
+) -> Result<ResourceId, AnyError>
+where
+  DBH: DatabaseHandler + 'static,
+{
+  let db = state.resource_table.get::<DatabaseResource<DBH::DB>>(rid)?;
+
+  if keys.len() > MAX_WATCHED_KEYS {
+    return Err(type_error(format!(
+      "too many keys (max {})",
+      MAX_WATCHED_KEYS
+    )));
+  }
+
+  let keys = keys
+    .into_iter()
+    .map(encode_v8_key)
+    .collect::<std::io::Result<Vec<_>>>()?;
+
+  for key in &keys {
+    check_read_key_size(key)?;
+  }
+
+  let stream = db.db.watch(keys);
+  let rid = state.resource_table.add(DatabaseWatcherResource {
+    stream: AsyncRefCell::new(stream),
+    cancel: CancelHandle::new(),
+  });
+  Ok(rid)
+}
+
+#[op]
+async fn op_kv_watch_next(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Option<Vec<Option<V8KvEntry>>>, AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<DatabaseWatcherResource>(rid)?;
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+  let mut stream = RcRef::map(&resource, |r| &r.stream).borrow_mut().await;
+  let entries = match stream.next().or_cancel(cancel).await? {
+    Some(entries) => entries?,
+    None => return Ok(None),
+  };
+  let entries = entries
+    .into_iter()
+    .map(|entry| entry.map(TryInto::try_into).transpose())
+    .collect::<Result<Vec<_>, AnyError>>()?;
+  Ok(Some(entries))
+}
+
 // (prefix, start, end)
 type EncodeCursorRangeSelector = (Option<KvKey>, Option<KvKey>, Option<KvKey>);
 
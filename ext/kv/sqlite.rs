@@ -6,10 +6,12 @@ use std::marker::PhantomData;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
+use deno_core::futures::stream;
 use deno_core::OpState;
 use rusqlite::params;
 use rusqlite::OpenFlags;
@@ -26,6 +28,7 @@ use crate::ReadRange;
 use crate::ReadRangeOutput;
 use crate::SnapshotReadOptions;
 use crate::Value;
+use crate::WatchStream;
 
 const STATEMENT_INC_AND_GET_DATA_VERSION: &str =
   "update data_version set version = version + 1 where k = 0 returning version";
@@ -33,6 +36,8 @@ const STATEMENT_KV_RANGE_SCAN: &str =
   "select k, v, v_encoding, version from kv where k >= ? and k < ? order by k asc limit ?";
 const STATEMENT_KV_RANGE_SCAN_REVERSE: &str =
   "select k, v, v_encoding, version from kv where k >= ? and k < ? order by k desc limit ?";
+const STATEMENT_KV_POINT_GET: &str =
+  "select v, v_encoding, version from kv where k = ?";
 const STATEMENT_KV_POINT_GET_VALUE_ONLY: &str =
   "select v, v_encoding from kv where k = ?";
 const STATEMENT_KV_POINT_GET_VERSION_ONLY: &str =
@@ -84,6 +89,10 @@ create table queue_running(
 ",
 ];
 
+/// How often watched keys are checked for changes made by other processes.
+/// Changes made through the same database handle are noticed immediately.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct SqliteDbHandler<P: SqliteDbHandlerPermissions + 'static> {
   pub default_storage_dir: Option<PathBuf>,
   _permissions: PhantomData<P>,
@@ -165,11 +174,19 @@ impl<P: SqliteDbHandlerPermissions> DatabaseHandler for SqliteDbHandler<P> {
       }
     }
 
-    Ok(SqliteDb(RefCell::new(conn)))
+    let (write_notifier, _) = tokio::sync::watch::channel(());
+    Ok(SqliteDb {
+      conn: Rc::new(RefCell::new(conn)),
+      write_notifier,
+    })
   }
 }
 
-pub struct SqliteDb(RefCell<rusqlite::Connection>);
+pub struct SqliteDb {
+  conn: Rc<RefCell<rusqlite::Connection>>,
+  /// Notifies watchers about committed writes.
+  write_notifier: tokio::sync::watch::Sender<()>,
+}
 
 #[async_trait(?Send)]
 impl Database for SqliteDb {
@@ -179,7 +196,7 @@ impl Database for SqliteDb {
     _options: SnapshotReadOptions,
   ) -> Result<Vec<ReadRangeOutput>, AnyError> {
     let mut responses = Vec::with_capacity(requests.len());
-    let mut db = self.0.borrow_mut();
+    let mut db = self.conn.borrow_mut();
     let tx = db.transaction()?;
 
     for request in requests {
@@ -221,7 +238,7 @@ impl Database for SqliteDb {
     &self,
     write: AtomicWrite,
   ) -> Result<Option<CommitResult>, AnyError> {
-    let mut db = self.0.borrow_mut();
+    let mut db = self.conn.borrow_mut();
 
     let tx = db.transaction()?;
 
@@ -276,6 +293,7 @@ impl Database for SqliteDb {
     // TODO(@losfair): enqueues
 
     tx.commit()?;
+    self.write_notifier.send_replace(());
 
     let new_vesionstamp = version_to_versionstamp(version);
 
@@ -283,6 +301,67 @@ impl Database for SqliteDb {
       versionstamp: new_vesionstamp,
     }))
   }
+
+  fn watch(&self, keys: Vec<Vec<u8>>) -> WatchStream {
+    let conn = self.conn.clone();
+    let last_versionstamps: Option<Vec<Option<[u8; 10]>>> = None;
+    let state = (keys, last_versionstamps, self.write_notifier.subscribe());
+    Box::pin(stream::try_unfold(
+      state,
+      move |(keys, last_versionstamps, mut write_receiver)| {
+        let conn = conn.clone();
+        async move {
+          loop {
+            let entries = read_keys(&mut conn.borrow_mut(), &keys)?;
+            let versionstamps = entries
+              .iter()
+              .map(|entry| entry.as_ref().map(|entry| entry.versionstamp))
+              .collect::<Vec<_>>();
+            if last_versionstamps.as_ref() != Some(&versionstamps) {
+              let state = (keys, Some(versionstamps), write_receiver);
+              return Ok::<_, AnyError>(Some((entries, state)));
+            }
+
+            tokio::select! {
+              changed = write_receiver.changed() => {
+                if changed.is_err() {
+                  // The database was closed.
+                  return Ok(None);
+                }
+              }
+              _ = tokio::time::sleep(WATCH_POLL_INTERVAL) => {}
+            }
+          }
+        }
+      },
+    ))
+  }
+}
+
+/// Reads the entries of the given keys in a single transaction.
+fn read_keys(
+  db: &mut rusqlite::Connection,
+  keys: &[Vec<u8>],
+) -> Result<Vec<Option<KvEntry>>, AnyError> {
+  let tx = db.transaction()?;
+  let mut entries = Vec::with_capacity(keys.len());
+  for key in keys {
+    let entry = tx
+      .prepare_cached(STATEMENT_KV_POINT_GET)?
+      .query_row([key.as_slice()], |row| {
+        let value: Vec<u8> = row.get(0)?;
+        let encoding: i64 = row.get(1)?;
+        let version: i64 = row.get(2)?;
+        Ok(KvEntry {
+          key: key.clone(),
+          value: decode_value(value, encoding),
+          versionstamp: version_to_versionstamp(version),
+        })
+      })
+      .optional()?;
+    entries.push(entry);
+  }
+  Ok(entries)
 }
 
 /// Mutates a LE64 value in the database, defaulting to setting it to the
@@ -342,6 +342,13 @@ class Datagram {
     };
   }
 
+  async setBroadcast(broadcast) {
+    if (this.addr.transport !== "udp") {
+      throw new Error(`Unsupported transport: ${this.addr.transport}`);
+    }
+    await core.opAsync("op_net_set_broadcast_udp", this.rid, broadcast);
+  }
+
   async receive(p) {
     const buf = p || new Uint8Array(this.bufSize);
     let nread;
@@ -449,6 +456,7 @@ function createListenDatagram(udpOpFn, unixOpFn) {
           },
           args.reuseAddress ?? false,
           args.loopback ?? false,
+          args.broadcast ?? true,
         );
         addr.transport = "udp";
         return new Datagram(rid, addr);
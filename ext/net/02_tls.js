@@ -20,6 +20,30 @@ class TlsConn extends Conn {
   }
 }
 
+async function verifyPeer(conn, hostname, verifyPeerCertificate) {
+  if (verifyPeerCertificate === undefined) {
+    return conn;
+  }
+  let verified;
+  try {
+    const certificates = await core.opAsync(
+      "op_tls_peer_certificates",
+      conn.rid,
+    );
+    verified = await verifyPeerCertificate(certificates, hostname);
+  } catch (error) {
+    conn.close();
+    throw error;
+  }
+  if (verified !== true) {
+    conn.close();
+    throw new Deno.errors.InvalidData(
+      `The certificate of "${hostname}" was rejected by verifyPeerCertificate`,
+    );
+  }
+  return conn;
+}
+
 async function connectTls({
   port,
   hostname = "127.0.0.1",
@@ -29,6 +53,7 @@ async function connectTls({
   certChain = undefined,
   privateKey = undefined,
   alpnProtocols = undefined,
+  verifyPeerCertificate = undefined,
 }) {
   if (transport !== "tcp") {
     throw new TypeError(`Unsupported transport: '${transport}'`);
@@ -40,7 +65,11 @@ async function connectTls({
   );
   localAddr.transport = "tcp";
   remoteAddr.transport = "tcp";
-  return new TlsConn(rid, remoteAddr, localAddr);
+  return verifyPeer(
+    new TlsConn(rid, remoteAddr, localAddr),
+    hostname,
+    verifyPeerCertificate,
+  );
 }
 
 class TlsListener extends Listener {
@@ -82,7 +111,10 @@ async function startTls(
     hostname = "127.0.0.1",
     certFile = undefined,
     caCerts = [],
+    certChain = undefined,
+    privateKey = undefined,
     alpnProtocols = undefined,
+    verifyPeerCertificate = undefined,
   } = {},
 ) {
   const { 0: rid, 1: localAddr, 2: remoteAddr } = await opStartTls({
@@ -90,9 +122,15 @@ async function startTls(
     hostname,
     certFile,
     caCerts,
+    certChain,
+    privateKey,
     alpnProtocols,
   });
-  return new TlsConn(rid, remoteAddr, localAddr);
+  return verifyPeer(
+    new TlsConn(rid, remoteAddr, localAddr),
+    hostname,
+    verifyPeerCertificate,
+  );
 }
 
 export { connectTls, listenTls, startTls, TlsConn, TlsListener };
@@ -13,6 +13,7 @@ use deno_core::OpState;
 use deno_tls::rustls::RootCertStore;
 use deno_tls::RootCertStoreProvider;
 use std::cell::RefCell;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -24,9 +25,43 @@ pub trait NetPermissions {
     _host: &(T, Option<u16>),
     _api_name: &str,
   ) -> Result<(), AnyError>;
+  fn check_udp<T: AsRef<str>>(
+    &mut self,
+    _host: &(T, Option<u16>),
+    _api_name: &str,
+  ) -> Result<(), AnyError>;
+  /// Checks a host before resolving it. The address it resolves to must be
+  /// checked with `check_net_resolved` before connecting.
+  fn check_net_unresolved<T: AsRef<str>>(
+    &mut self,
+    _host: &(T, Option<u16>),
+    _api_name: &str,
+  ) -> Result<(), AnyError>;
+  /// Checks a host before resolving it. The address it resolves to must be
+  /// checked with `check_udp_resolved` before sending.
+  fn check_udp_unresolved<T: AsRef<str>>(
+    &mut self,
+    _host: &(T, Option<u16>),
+    _api_name: &str,
+  ) -> Result<(), AnyError>;
+  /// Checks a host right before connecting to an address it resolved to.
+  fn check_net_resolved<T: AsRef<str>>(
+    &mut self,
+    _host: &(T, Option<u16>),
+    _addr: &SocketAddr,
+    _api_name: &str,
+  ) -> Result<(), AnyError>;
+  /// Checks a host right before sending to an address it resolved to.
+  fn check_udp_resolved<T: AsRef<str>>(
+    &mut self,
+    _host: &(T, Option<u16>),
+    _addr: &SocketAddr,
+    _api_name: &str,
+  ) -> Result<(), AnyError>;
   fn check_read(&mut self, _p: &Path, _api_name: &str) -> Result<(), AnyError>;
   fn check_write(&mut self, _p: &Path, _api_name: &str)
     -> Result<(), AnyError>;
+  fn check_unix(&mut self, _p: &Path, _api_name: &str) -> Result<(), AnyError>;
 }
 
 /// `UnstableChecker` is a struct so it can be placed inside `GothamState`;
@@ -104,6 +139,7 @@ deno_core::extension!(deno_net,
     ops::op_net_leave_multi_v6_udp<P>,
     ops::op_net_set_multi_loopback_udp<P>,
     ops::op_net_set_multi_ttl_udp<P>,
+    ops::op_net_set_broadcast_udp,
     ops::op_dns_resolve<P>,
     ops::op_set_nodelay,
     ops::op_set_keepalive,
@@ -113,6 +149,7 @@ deno_core::extension!(deno_net,
     ops_tls::op_net_listen_tls<P>,
     ops_tls::op_net_accept_tls,
     ops_tls::op_tls_handshake,
+    ops_tls::op_tls_peer_certificates,
 
     #[cfg(unix)] ops_unix::op_net_accept_unix,
     #[cfg(unix)] ops_unix::op_net_connect_unix<P>,
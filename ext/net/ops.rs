@@ -50,6 +50,10 @@ use trust_dns_resolver::AsyncResolver;
 #[serde(rename_all = "camelCase")]
 pub struct TlsHandshakeInfo {
   pub alpn_protocol: Option<ByteString>,
+  /// The DER encoded certificate chain presented by the peer, starting with
+  /// the end-entity certificate.
+  #[serde(skip)]
+  pub peer_certificates: Vec<Vec<u8>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -137,15 +141,23 @@ where
 {
   {
     let mut s = state.borrow_mut();
-    s.borrow_mut::<NP>().check_net(
+    s.borrow_mut::<NP>().check_udp_unresolved(
       &(&addr.hostname, Some(addr.port)),
       "Deno.DatagramConn.send()",
     )?;
   }
-  let addr = resolve_addr(&addr.hostname, addr.port)
+  let send_addr = resolve_addr(&addr.hostname, addr.port)
     .await?
     .next()
     .ok_or_else(|| generic_error("No resolved address found"))?;
+  {
+    let mut s = state.borrow_mut();
+    s.borrow_mut::<NP>().check_udp_resolved(
+      &(&addr.hostname, Some(addr.port)),
+      &send_addr,
+      "Deno.DatagramConn.send()",
+    )?;
+  }
 
   let resource = state
     .borrow_mut()
@@ -153,7 +165,7 @@ where
     .get::<UdpSocketResource>(rid)
     .map_err(|_| bad_resource("Socket has been closed"))?;
   let socket = RcRef::map(&resource, |r| &r.socket).borrow().await;
-  let nwritten = socket.send_to(&zero_copy, &addr).await?;
+  let nwritten = socket.send_to(&zero_copy, &send_addr).await?;
 
   Ok(nwritten)
 }
@@ -303,6 +315,24 @@ where
   Ok(())
 }
 
+#[op]
+async fn op_net_set_broadcast_udp(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  broadcast: bool,
+) -> Result<(), AnyError> {
+  let resource = state
+    .borrow_mut()
+    .resource_table
+    .get::<UdpSocketResource>(rid)
+    .map_err(|_| bad_resource("Socket has been closed"))?;
+  let socket = RcRef::map(&resource, |r| &r.socket).borrow().await;
+
+  socket.set_broadcast(broadcast)?;
+
+  Ok(())
+}
+
 #[op]
 pub async fn op_net_connect_tcp<NP>(
   state: Rc<RefCell<OpState>>,
@@ -313,16 +343,24 @@ where
 {
   {
     let mut state_ = state.borrow_mut();
-    state_
-      .borrow_mut::<NP>()
-      .check_net(&(&addr.hostname, Some(addr.port)), "Deno.connect()")?;
+    state_.borrow_mut::<NP>().check_net_unresolved(
+      &(&addr.hostname, Some(addr.port)),
+      "Deno.connect()",
+    )?;
   }
-
-  let addr = resolve_addr(&addr.hostname, addr.port)
+  let connect_addr = resolve_addr(&addr.hostname, addr.port)
     .await?
     .next()
     .ok_or_else(|| generic_error("No resolved address found"))?;
-  let tcp_stream = TcpStream::connect(&addr).await?;
+  {
+    let mut state_ = state.borrow_mut();
+    state_.borrow_mut::<NP>().check_net_resolved(
+      &(&addr.hostname, Some(addr.port)),
+      &connect_addr,
+      "Deno.connect()",
+    )?;
+  }
+  let tcp_stream = TcpStream::connect(&connect_addr).await?;
   let local_addr = tcp_stream.local_addr()?;
   let remote_addr = tcp_stream.peer_addr()?;
 
@@ -415,13 +453,14 @@ fn net_listen_udp<NP>(
   addr: IpAddr,
   reuse_address: bool,
   loopback: bool,
+  broadcast: bool,
 ) -> Result<(ResourceId, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
 {
   state
     .borrow_mut::<NP>()
-    .check_net(&(&addr.hostname, Some(addr.port)), "Deno.listenDatagram()")?;
+    .check_udp(&(&addr.hostname, Some(addr.port)), "Deno.listenDatagram()")?;
   let addr = resolve_addr_sync(&addr.hostname, addr.port)?
     .next()
     .ok_or_else(|| generic_error("No resolved address found"))?;
@@ -451,8 +490,8 @@ where
   socket_tmp.bind(&socket_addr)?;
   socket_tmp.set_nonblocking(true)?;
 
-  // Enable messages to be sent to the broadcast address (255.255.255.255) by default
-  socket_tmp.set_broadcast(true)?;
+  // Allow messages to be sent to the broadcast address (255.255.255.255)
+  socket_tmp.set_broadcast(broadcast)?;
 
   if domain == Domain::IPV4 {
     socket_tmp.set_multicast_loop_v4(loopback)?;
@@ -479,12 +518,12 @@ fn op_net_listen_udp<NP>(
   addr: IpAddr,
   reuse_address: bool,
   loopback: bool,
+  broadcast: bool,
 ) -> Result<(ResourceId, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
 {
-  super::check_unstable(state, "Deno.listenDatagram");
-  net_listen_udp::<NP>(state, addr, reuse_address, loopback)
+  net_listen_udp::<NP>(state, addr, reuse_address, loopback, broadcast)
 }
 
 #[op]
@@ -493,11 +532,12 @@ fn op_node_unstable_net_listen_udp<NP>(
   addr: IpAddr,
   reuse_address: bool,
   loopback: bool,
+  broadcast: bool,
 ) -> Result<(ResourceId, IpAddr), AnyError>
 where
   NP: NetPermissions + 'static,
 {
-  net_listen_udp::<NP>(state, addr, reuse_address, loopback)
+  net_listen_udp::<NP>(state, addr, reuse_address, loopback, broadcast)
 }
 
 #[derive(Serialize, Eq, PartialEq, Debug)]
@@ -986,6 +1026,48 @@ mod tests {
       Ok(())
     }
 
+    fn check_udp<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _api_name: &str,
+    ) -> Result<(), AnyError> {
+      Ok(())
+    }
+
+    fn check_net_unresolved<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _api_name: &str,
+    ) -> Result<(), AnyError> {
+      Ok(())
+    }
+
+    fn check_udp_unresolved<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _api_name: &str,
+    ) -> Result<(), AnyError> {
+      Ok(())
+    }
+
+    fn check_net_resolved<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _addr: &SocketAddr,
+      _api_name: &str,
+    ) -> Result<(), AnyError> {
+      Ok(())
+    }
+
+    fn check_udp_resolved<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _addr: &SocketAddr,
+      _api_name: &str,
+    ) -> Result<(), AnyError> {
+      Ok(())
+    }
+
     fn check_read(
       &mut self,
       _p: &Path,
@@ -1001,6 +1083,14 @@ mod tests {
     ) -> Result<(), AnyError> {
       Ok(())
     }
+
+    fn check_unix(
+      &mut self,
+      _p: &Path,
+      _api_name: &str,
+    ) -> Result<(), AnyError> {
+      Ok(())
+    }
   }
 
   #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -36,6 +36,7 @@ use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ResourceId;
+use deno_core::ZeroCopyBuf;
 use deno_tls::create_client_config;
 use deno_tls::load_certs;
 use deno_tls::load_private_keys;
@@ -179,6 +180,15 @@ impl TlsStream {
   fn get_alpn_protocol(&mut self) -> Option<ByteString> {
     self.inner_mut().tls.alpn_protocol().map(|s| s.into())
   }
+
+  fn get_peer_certificates(&mut self) -> Vec<Vec<u8>> {
+    self
+      .inner_mut()
+      .tls
+      .peer_certificates()
+      .map(|certs| certs.iter().map(|cert| cert.0.clone()).collect())
+      .unwrap_or_default()
+  }
 }
 
 impl AsyncRead for TlsStream {
@@ -528,6 +538,10 @@ impl WriteHalf {
   fn get_alpn_protocol(&mut self) -> Option<ByteString> {
     self.shared.get_alpn_protocol()
   }
+
+  fn get_peer_certificates(&mut self) -> Vec<Vec<u8>> {
+    self.shared.get_peer_certificates()
+  }
 }
 
 impl AsyncWrite for WriteHalf {
@@ -651,6 +665,11 @@ impl Shared {
     let mut tls_stream = self.tls_stream.lock();
     tls_stream.get_alpn_protocol()
   }
+
+  fn get_peer_certificates(self: &Arc<Self>) -> Vec<Vec<u8>> {
+    let mut tls_stream = self.tls_stream.lock();
+    tls_stream.get_peer_certificates()
+  }
 }
 
 struct ImplementReadTrait<'a, T>(&'a mut T);
@@ -737,7 +756,11 @@ impl TlsStreamResource {
     wr.handshake().try_or_cancel(cancel_handle).await?;
 
     let alpn_protocol = wr.get_alpn_protocol();
-    let tls_info = TlsHandshakeInfo { alpn_protocol };
+    let peer_certificates = wr.get_peer_certificates();
+    let tls_info = TlsHandshakeInfo {
+      alpn_protocol,
+      peer_certificates,
+    };
     self.handshake_info.replace(Some(tls_info.clone()));
     Ok(tls_info)
   }
@@ -776,9 +799,25 @@ pub struct StartTlsArgs {
   rid: ResourceId,
   ca_certs: Vec<String>,
   hostname: String,
+  cert_chain: Option<String>,
+  private_key: Option<String>,
   alpn_protocols: Option<Vec<String>>,
 }
 
+fn cert_chain_and_key(
+  cert_chain: Option<String>,
+  private_key: Option<String>,
+) -> Result<Option<(String, String)>, AnyError> {
+  if cert_chain.is_none() && private_key.is_none() {
+    return Ok(None);
+  }
+  let cert_chain =
+    cert_chain.ok_or_else(|| type_error("No certificate chain provided"))?;
+  let private_key =
+    private_key.ok_or_else(|| type_error("No private key provided"))?;
+  Ok(Some((cert_chain, private_key)))
+}
+
 #[op]
 pub async fn op_tls_start<NP>(
   state: Rc<RefCell<OpState>>,
@@ -793,6 +832,13 @@ where
     n => n,
   };
 
+  if args.cert_chain.is_some() {
+    super::check_unstable2(&state, "StartTlsOptions.certChain");
+  }
+  if args.private_key.is_some() {
+    super::check_unstable2(&state, "StartTlsOptions.privateKey");
+  }
+
   {
     let mut s = state.borrow_mut();
     let permissions = s.borrow_mut::<NP>();
@@ -837,7 +883,7 @@ where
     root_cert_store,
     ca_certs,
     unsafely_ignore_certificate_errors,
-    None,
+    cert_chain_and_key(args.cert_chain, args.private_key)?,
   )?;
 
   if let Some(alpn_protocols) = args.alpn_protocols {
@@ -886,8 +932,10 @@ where
   {
     let mut s = state.borrow_mut();
     let permissions = s.borrow_mut::<NP>();
-    permissions
-      .check_net(&(&addr.hostname, Some(addr.port)), "Deno.connectTls()")?;
+    permissions.check_net_unresolved(
+      &(&addr.hostname, Some(addr.port)),
+      "Deno.connectTls()",
+    )?;
     if let Some(path) = cert_file {
       permissions.check_read(Path::new(path), "Deno.connectTls()")?;
     }
@@ -915,28 +963,23 @@ where
     .await?
     .next()
     .ok_or_else(|| generic_error("No resolved address found"))?;
+  {
+    let mut s = state.borrow_mut();
+    s.borrow_mut::<NP>().check_net_resolved(
+      &(&addr.hostname, Some(addr.port)),
+      &connect_addr,
+      "Deno.connectTls()",
+    )?;
+  }
   let tcp_stream = TcpStream::connect(connect_addr).await?;
   let local_addr = tcp_stream.local_addr()?;
   let remote_addr = tcp_stream.peer_addr()?;
 
-  let cert_chain_and_key =
-    if args.cert_chain.is_some() || args.private_key.is_some() {
-      let cert_chain = args
-        .cert_chain
-        .ok_or_else(|| type_error("No certificate chain provided"))?;
-      let private_key = args
-        .private_key
-        .ok_or_else(|| type_error("No private key provided"))?;
-      Some((cert_chain, private_key))
-    } else {
-      None
-    };
-
   let mut tls_config = create_client_config(
     root_cert_store,
     ca_certs,
     unsafely_ignore_certificate_errors,
-    cert_chain_and_key,
+    cert_chain_and_key(args.cert_chain, args.private_key)?,
   )?;
 
   if let Some(alpn_protocols) = args.alpn_protocols {
@@ -1150,3 +1193,23 @@ pub async fn op_tls_handshake(
     .get::<TlsStreamResource>(rid)?;
   resource.handshake().await
 }
+
+#[op]
+pub async fn op_tls_peer_certificates(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Vec<ZeroCopyBuf>, AnyError> {
+  super::check_unstable2(&state, "ConnectTlsOptions.verifyPeerCertificate");
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<TlsStreamResource>(rid)?;
+  let tls_info = resource.handshake().await?;
+  Ok(
+    tls_info
+      .peer_certificates
+      .into_iter()
+      .map(ZeroCopyBuf::from)
+      .collect(),
+  )
+}
@@ -180,9 +180,16 @@ deno_core::extension!(deno_node,
     ops::crypto::op_node_ecdh_generate_keys,
     ops::crypto::op_node_ecdh_compute_secret,
     ops::crypto::op_node_ecdh_compute_public_key,
+    ops::crypto::keys::op_node_create_private_key,
+    ops::crypto::keys::op_node_create_public_key,
+    ops::crypto::keys::op_node_sign_ed25519,
+    ops::crypto::keys::op_node_verify_ed25519,
     ops::crypto::x509::op_node_x509_parse,
     ops::crypto::x509::op_node_x509_ca,
     ops::crypto::x509::op_node_x509_check_email,
+    ops::crypto::x509::op_node_x509_check_host,
+    ops::crypto::x509::op_node_x509_check_ip,
+    ops::crypto::x509::op_node_x509_check_issued,
     ops::crypto::x509::op_node_x509_fingerprint,
     ops::crypto::x509::op_node_x509_fingerprint256,
     ops::crypto::x509::op_node_x509_fingerprint512,
@@ -192,9 +199,21 @@ deno_core::extension!(deno_node,
     ops::crypto::x509::op_node_x509_get_valid_to,
     ops::crypto::x509::op_node_x509_get_serial_number,
     ops::crypto::x509::op_node_x509_key_usage,
+    ops::crypto::x509::op_node_x509_raw,
+    ops::crypto::x509::op_node_x509_public_key,
     ops::winerror::op_node_sys_to_uv_error,
     ops::v8::op_v8_cached_data_version_tag,
     ops::v8::op_v8_get_heap_statistics,
+    ops::vm::op_vm_create_context,
+    ops::vm::op_vm_run_in_context,
+    ops::vm::op_vm_module_compile,
+    ops::vm::op_vm_module_link,
+    ops::vm::op_vm_module_instantiate,
+    ops::vm::op_vm_module_evaluate,
+    ops::vm::op_vm_module_status,
+    ops::vm::op_vm_module_namespace,
+    ops::vm::op_vm_module_error,
+    ops::vm::op_vm_module_drop,
     ops::idna::op_node_idna_domain_to_ascii,
     ops::idna::op_node_idna_domain_to_unicode,
     ops::idna::op_node_idna_punycode_decode,
@@ -458,6 +477,7 @@ deno_core::extension!(deno_node,
   options = {
     maybe_npm_resolver: Option<NpmResolverRc>,
     fs: deno_fs::FileSystemRc,
+    conditions: Vec<String>,
   },
   state = |state, options| {
     let fs = options.fs;
@@ -467,6 +487,7 @@ deno_core::extension!(deno_node,
       state.put(Rc::new(NodeResolver::new(
         fs,
         npm_resolver,
+        options.conditions,
       )))
     }
   },
@@ -4,6 +4,7 @@ use aes::cipher::block_padding::Pkcs7;
 use aes::cipher::BlockDecryptMut;
 use aes::cipher::BlockEncryptMut;
 use aes::cipher::KeyIvInit;
+use deno_core::error::range_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::Resource;
@@ -15,14 +16,33 @@ use std::rc::Rc;
 
 enum Cipher {
   Aes128Cbc(Box<cbc::Encryptor<aes::Aes128>>),
+  Aes192Cbc(Box<cbc::Encryptor<aes::Aes192>>),
+  Aes256Cbc(Box<cbc::Encryptor<aes::Aes256>>),
   Aes128Ecb(Box<ecb::Encryptor<aes::Aes128>>),
-  // TODO(kt3k): add more algorithms Aes192Cbc, Aes256Cbc, Aes128GCM, etc.
+  Aes192Ecb(Box<ecb::Encryptor<aes::Aes192>>),
+  Aes256Ecb(Box<ecb::Encryptor<aes::Aes256>>),
+  // TODO(kt3k): add more algorithms Aes128GCM, etc.
 }
 
 enum Decipher {
   Aes128Cbc(Box<cbc::Decryptor<aes::Aes128>>),
+  Aes192Cbc(Box<cbc::Decryptor<aes::Aes192>>),
+  Aes256Cbc(Box<cbc::Decryptor<aes::Aes256>>),
   Aes128Ecb(Box<ecb::Decryptor<aes::Aes128>>),
-  // TODO(kt3k): add more algorithms Aes192Cbc, Aes256Cbc, Aes128GCM, etc.
+  Aes192Ecb(Box<ecb::Decryptor<aes::Aes192>>),
+  Aes256Ecb(Box<ecb::Decryptor<aes::Aes256>>),
+  // TODO(kt3k): add more algorithms Aes128GCM, etc.
+}
+
+/// Encrypts or decrypts the blocks of the input with any of the block mode
+/// ciphers, which all share the same block size of 16 bytes.
+macro_rules! blocks_b2b {
+  ($method:ident, $cipher:expr, $input:expr, $output:expr) => {{
+    assert!($input.len() % 16 == 0);
+    for (input, output) in $input.chunks(16).zip($output.chunks_mut(16)) {
+      $cipher.$method(input.into(), output.into());
+    }
+  }};
 }
 
 pub struct CipherContext {
@@ -46,13 +66,14 @@ impl CipherContext {
 
   pub fn r#final(
     self,
+    auto_pad: bool,
     input: &[u8],
     output: &mut [u8],
   ) -> Result<(), AnyError> {
     Rc::try_unwrap(self.cipher)
       .map_err(|_| type_error("Cipher context is already in use"))?
       .into_inner()
-      .r#final(input, output)
+      .r#final(auto_pad, input, output)
   }
 }
 
@@ -69,13 +90,14 @@ impl DecipherContext {
 
   pub fn r#final(
     self,
+    auto_pad: bool,
     input: &[u8],
     output: &mut [u8],
   ) -> Result<(), AnyError> {
     Rc::try_unwrap(self.decipher)
       .map_err(|_| type_error("Decipher context is already in use"))?
       .into_inner()
-      .r#final(input, output)
+      .r#final(auto_pad, input, output)
   }
 }
 
@@ -98,11 +120,20 @@ impl Cipher {
     iv: &[u8],
   ) -> Result<Self, AnyError> {
     use Cipher::*;
+    check_key_and_iv(algorithm_name, key, iv)?;
     Ok(match algorithm_name {
       "aes-128-cbc" => {
         Aes128Cbc(Box::new(cbc::Encryptor::new(key.into(), iv.into())))
       }
+      "aes-192-cbc" => {
+        Aes192Cbc(Box::new(cbc::Encryptor::new(key.into(), iv.into())))
+      }
+      "aes-256-cbc" => {
+        Aes256Cbc(Box::new(cbc::Encryptor::new(key.into(), iv.into())))
+      }
       "aes-128-ecb" => Aes128Ecb(Box::new(ecb::Encryptor::new(key.into()))),
+      "aes-192-ecb" => Aes192Ecb(Box::new(ecb::Encryptor::new(key.into()))),
+      "aes-256-ecb" => Aes256Ecb(Box::new(ecb::Encryptor::new(key.into()))),
       _ => return Err(type_error(format!("Unknown cipher {algorithm_name}"))),
     })
   }
@@ -112,37 +143,58 @@ impl Cipher {
     use Cipher::*;
     match self {
       Aes128Cbc(encryptor) => {
-        assert!(input.len() % 16 == 0);
-        for (input, output) in input.chunks(16).zip(output.chunks_mut(16)) {
-          encryptor.encrypt_block_b2b_mut(input.into(), output.into());
-        }
+        blocks_b2b!(encrypt_block_b2b_mut, encryptor, input, output)
+      }
+      Aes192Cbc(encryptor) => {
+        blocks_b2b!(encrypt_block_b2b_mut, encryptor, input, output)
+      }
+      Aes256Cbc(encryptor) => {
+        blocks_b2b!(encrypt_block_b2b_mut, encryptor, input, output)
       }
       Aes128Ecb(encryptor) => {
-        assert!(input.len() % 16 == 0);
-        for (input, output) in input.chunks(16).zip(output.chunks_mut(16)) {
-          encryptor.encrypt_block_b2b_mut(input.into(), output.into());
-        }
+        blocks_b2b!(encrypt_block_b2b_mut, encryptor, input, output)
+      }
+      Aes192Ecb(encryptor) => {
+        blocks_b2b!(encrypt_block_b2b_mut, encryptor, input, output)
+      }
+      Aes256Ecb(encryptor) => {
+        blocks_b2b!(encrypt_block_b2b_mut, encryptor, input, output)
       }
     }
   }
 
-  /// r#final encrypts the last block of the input data.
-  fn r#final(self, input: &[u8], output: &mut [u8]) -> Result<(), AnyError> {
+  /// r#final encrypts the last block of the input data. Without padding the
+  /// input data must be a multiple of the block size, so there is no last
+  /// block left to encrypt.
+  fn r#final(
+    self,
+    auto_pad: bool,
+    input: &[u8],
+    output: &mut [u8],
+  ) -> Result<(), AnyError> {
     assert!(input.len() < 16);
-    use Cipher::*;
-    match self {
-      Aes128Cbc(encryptor) => {
-        let _ = (*encryptor)
-          .encrypt_padded_b2b_mut::<Pkcs7>(input, output)
-          .map_err(|_| type_error("Cannot pad the input data"))?;
-        Ok(())
+    if !auto_pad {
+      if !input.is_empty() {
+        return Err(type_error("wrong final block length"));
       }
-      Aes128Ecb(encryptor) => {
-        let _ = (*encryptor)
+      return Ok(());
+    }
+    macro_rules! encrypt_padded {
+      ($encryptor:expr) => {{
+        let _ = (*$encryptor)
           .encrypt_padded_b2b_mut::<Pkcs7>(input, output)
           .map_err(|_| type_error("Cannot pad the input data"))?;
         Ok(())
-      }
+      }};
+    }
+    use Cipher::*;
+    match self {
+      Aes128Cbc(encryptor) => encrypt_padded!(encryptor),
+      Aes192Cbc(encryptor) => encrypt_padded!(encryptor),
+      Aes256Cbc(encryptor) => encrypt_padded!(encryptor),
+      Aes128Ecb(encryptor) => encrypt_padded!(encryptor),
+      Aes192Ecb(encryptor) => encrypt_padded!(encryptor),
+      Aes256Ecb(encryptor) => encrypt_padded!(encryptor),
     }
   }
 }
@@ -154,11 +206,20 @@ impl Decipher {
     iv: &[u8],
   ) -> Result<Self, AnyError> {
     use Decipher::*;
+    check_key_and_iv(algorithm_name, key, iv)?;
     Ok(match algorithm_name {
       "aes-128-cbc" => {
         Aes128Cbc(Box::new(cbc::Decryptor::new(key.into(), iv.into())))
       }
+      "aes-192-cbc" => {
+        Aes192Cbc(Box::new(cbc::Decryptor::new(key.into(), iv.into())))
+      }
+      "aes-256-cbc" => {
+        Aes256Cbc(Box::new(cbc::Decryptor::new(key.into(), iv.into())))
+      }
       "aes-128-ecb" => Aes128Ecb(Box::new(ecb::Decryptor::new(key.into()))),
+      "aes-192-ecb" => Aes192Ecb(Box::new(ecb::Decryptor::new(key.into()))),
+      "aes-256-ecb" => Aes256Ecb(Box::new(ecb::Decryptor::new(key.into()))),
       _ => return Err(type_error(format!("Unknown cipher {algorithm_name}"))),
     })
   }
@@ -168,37 +229,89 @@ impl Decipher {
     use Decipher::*;
     match self {
       Aes128Cbc(decryptor) => {
-        assert!(input.len() % 16 == 0);
-        for (input, output) in input.chunks(16).zip(output.chunks_mut(16)) {
-          decryptor.decrypt_block_b2b_mut(input.into(), output.into());
-        }
+        blocks_b2b!(decrypt_block_b2b_mut, decryptor, input, output)
+      }
+      Aes192Cbc(decryptor) => {
+        blocks_b2b!(decrypt_block_b2b_mut, decryptor, input, output)
+      }
+      Aes256Cbc(decryptor) => {
+        blocks_b2b!(decrypt_block_b2b_mut, decryptor, input, output)
       }
       Aes128Ecb(decryptor) => {
-        assert!(input.len() % 16 == 0);
-        for (input, output) in input.chunks(16).zip(output.chunks_mut(16)) {
-          decryptor.decrypt_block_b2b_mut(input.into(), output.into());
-        }
+        blocks_b2b!(decrypt_block_b2b_mut, decryptor, input, output)
+      }
+      Aes192Ecb(decryptor) => {
+        blocks_b2b!(decrypt_block_b2b_mut, decryptor, input, output)
+      }
+      Aes256Ecb(decryptor) => {
+        blocks_b2b!(decrypt_block_b2b_mut, decryptor, input, output)
       }
     }
   }
 
-  /// r#final decrypts the last block of the input data.
-  fn r#final(self, input: &[u8], output: &mut [u8]) -> Result<(), AnyError> {
-    assert!(input.len() == 16);
-    use Decipher::*;
-    match self {
-      Aes128Cbc(decryptor) => {
-        let _ = (*decryptor)
-          .decrypt_padded_b2b_mut::<Pkcs7>(input, output)
-          .map_err(|_| type_error("Cannot unpad the input data"))?;
-        Ok(())
-      }
-      Aes128Ecb(decryptor) => {
-        let _ = (*decryptor)
+  /// r#final decrypts the last block of the input data. Without padding the
+  /// last block is decrypted as is, and may also be absent.
+  fn r#final(
+    mut self,
+    auto_pad: bool,
+    input: &[u8],
+    output: &mut [u8],
+  ) -> Result<(), AnyError> {
+    if !auto_pad {
+      return match input.len() {
+        0 => Ok(()),
+        16 => {
+          self.decrypt(input, output);
+          Ok(())
+        }
+        _ => Err(type_error("wrong final block length")),
+      };
+    }
+    if input.len() != 16 {
+      return Err(type_error("wrong final block length"));
+    }
+    macro_rules! decrypt_padded {
+      ($decryptor:expr) => {{
+        let _ = (*$decryptor)
           .decrypt_padded_b2b_mut::<Pkcs7>(input, output)
           .map_err(|_| type_error("Cannot unpad the input data"))?;
         Ok(())
-      }
+      }};
     }
+    use Decipher::*;
+    match self {
+      Aes128Cbc(decryptor) => decrypt_padded!(decryptor),
+      Aes192Cbc(decryptor) => decrypt_padded!(decryptor),
+      Aes256Cbc(decryptor) => decrypt_padded!(decryptor),
+      Aes128Ecb(decryptor) => decrypt_padded!(decryptor),
+      Aes192Ecb(decryptor) => decrypt_padded!(decryptor),
+      Aes256Ecb(decryptor) => decrypt_padded!(decryptor),
+    }
+  }
+}
+
+/// Checks the lengths of the key and iv, which would otherwise panic when
+/// converting them to the arrays of the ciphers.
+fn check_key_and_iv(
+  algorithm_name: &str,
+  key: &[u8],
+  iv: &[u8],
+) -> Result<(), AnyError> {
+  let (key_len, iv_len) = match algorithm_name {
+    "aes-128-cbc" => (16, 16),
+    "aes-192-cbc" => (24, 16),
+    "aes-256-cbc" => (32, 16),
+    "aes-128-ecb" => (16, 0),
+    "aes-192-ecb" => (24, 0),
+    "aes-256-ecb" => (32, 0),
+    _ => return Err(type_error(format!("Unknown cipher {algorithm_name}"))),
+  };
+  if key.len() != key_len {
+    return Err(range_error("Invalid key length"));
+  }
+  // ECB mode ignores the iv
+  if iv_len != 0 && iv.len() != iv_len {
+    return Err(type_error("Invalid initialization vector"));
   }
+  Ok(())
 }
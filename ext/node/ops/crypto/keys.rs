@@ -0,0 +1,409 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::ZeroCopyBuf;
+use num_traits::ToPrimitive;
+use p256::pkcs8::DecodePrivateKey as _;
+use p256::pkcs8::DecodePublicKey as _;
+use p256::pkcs8::EncodePrivateKey as _;
+use p256::pkcs8::EncodePublicKey as _;
+use ring::signature::Ed25519KeyPair;
+use ring::signature::KeyPair;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::der::Decode;
+use rsa::pkcs8::spki::SubjectPublicKeyInfo;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::pkcs8::EncodePrivateKey;
+use rsa::pkcs8::EncodePublicKey;
+use rsa::pkcs8::ObjectIdentifier;
+use rsa::pkcs8::PrivateKeyInfo;
+use rsa::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use rsa::RsaPublicKey;
+use serde::Serialize;
+use x509_parser::pem;
+use x509_parser::prelude::FromDer;
+use x509_parser::prelude::X509Certificate;
+
+// rsaEncryption OBJECT IDENTIFIER ::= { 1 2 840 113549 1 1 1 }
+const RSA_ENCRYPTION_OID: ObjectIdentifier =
+  ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+// id-ecPublicKey OBJECT IDENTIFIER ::= { 1 2 840 10045 2 1 }
+const EC_PUBLIC_KEY_OID: ObjectIdentifier =
+  ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+// secp256r1 OBJECT IDENTIFIER ::= { 1 2 840 10045 3 1 7 }
+const SECP256R1_OID: ObjectIdentifier =
+  ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+// secp384r1 OBJECT IDENTIFIER ::= { 1 3 132 0 34 }
+const SECP384R1_OID: ObjectIdentifier =
+  ObjectIdentifier::new_unwrap("1.3.132.0.34");
+// id-Ed25519 OBJECT IDENTIFIER ::= { 1 3 101 112 }
+const ED25519_OID: ObjectIdentifier =
+  ObjectIdentifier::new_unwrap("1.3.101.112");
+
+// SubjectPublicKeyInfo header of an Ed25519 key, followed by the 32 bytes of
+// the public key.
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+  0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// An asymmetric key normalized to PKCS#8 (private keys) or SPKI (public
+/// keys) DER, which is what the `KeyObject`s of the polyfill hold.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AsymmetricKey {
+  asymmetric_key_type: &'static str,
+  der: ZeroCopyBuf,
+  details: AsymmetricKeyDetails,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AsymmetricKeyDetails {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  modulus_length: Option<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  public_exponent: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  named_curve: Option<&'static str>,
+}
+
+#[op]
+pub fn op_node_create_private_key(
+  key: &[u8],
+  format: &str,
+  r#type: &str,
+) -> Result<AsymmetricKey, AnyError> {
+  let der = private_key_to_pkcs8(key, format, r#type)?;
+  let (asymmetric_key_type, details) = {
+    let info = PrivateKeyInfo::from_der(&der)
+      .map_err(|_| type_error("Invalid private key"))?;
+    if info.algorithm.oid == RSA_ENCRYPTION_OID {
+      let key = RsaPrivateKey::from_pkcs8_der(&der)
+        .map_err(|_| type_error("Invalid RSA private key"))?;
+      ("rsa", rsa_details(&key))
+    } else if info.algorithm.oid == EC_PUBLIC_KEY_OID {
+      ("ec", ec_details(info.algorithm.parameters_oid().ok())?)
+    } else if info.algorithm.oid == ED25519_OID {
+      ("ed25519", AsymmetricKeyDetails::default())
+    } else {
+      return Err(type_error(format!(
+        "Unsupported key algorithm: {}",
+        info.algorithm.oid
+      )));
+    }
+  };
+  Ok(AsymmetricKey {
+    asymmetric_key_type,
+    der: der.into(),
+    details,
+  })
+}
+
+#[op]
+pub fn op_node_create_public_key(
+  key: &[u8],
+  format: &str,
+  r#type: &str,
+) -> Result<AsymmetricKey, AnyError> {
+  let der = public_key_to_spki(key, format, r#type)?;
+  let (asymmetric_key_type, details) = {
+    let info = SubjectPublicKeyInfo::from_der(&der)
+      .map_err(|_| type_error("Invalid public key"))?;
+    if info.algorithm.oid == RSA_ENCRYPTION_OID {
+      let key = RsaPublicKey::from_public_key_der(&der)
+        .map_err(|_| type_error("Invalid RSA public key"))?;
+      ("rsa", rsa_details(&key))
+    } else if info.algorithm.oid == EC_PUBLIC_KEY_OID {
+      ("ec", ec_details(info.algorithm.parameters_oid().ok())?)
+    } else if info.algorithm.oid == ED25519_OID {
+      ("ed25519", AsymmetricKeyDetails::default())
+    } else {
+      return Err(type_error(format!(
+        "Unsupported key algorithm: {}",
+        info.algorithm.oid
+      )));
+    }
+  };
+  Ok(AsymmetricKey {
+    asymmetric_key_type,
+    der: der.into(),
+    details,
+  })
+}
+
+#[op]
+pub fn op_node_sign_ed25519(
+  key: &[u8],
+  data: &[u8],
+) -> Result<ZeroCopyBuf, AnyError> {
+  let pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(key)
+    .map_err(|_| type_error("Invalid Ed25519 private key"))?;
+  Ok(pair.sign(data).as_ref().to_vec().into())
+}
+
+#[op]
+pub fn op_node_verify_ed25519(
+  key: &[u8],
+  data: &[u8],
+  signature: &[u8],
+) -> Result<bool, AnyError> {
+  let info = SubjectPublicKeyInfo::from_der(key)
+    .map_err(|_| type_error("Invalid Ed25519 public key"))?;
+  let public_key = ring::signature::UnparsedPublicKey::new(
+    &ring::signature::ED25519,
+    info.subject_public_key,
+  );
+  Ok(public_key.verify(data, signature).is_ok())
+}
+
+/// Signs the digest with the PKCS#8 encoded EC private key, encoding the
+/// signature as DER or, with the "ieee-p1363" encoding, as `r || s`.
+pub fn ecdsa_sign(
+  key: &[u8],
+  digest: &[u8],
+  dsa_encoding: &str,
+) -> Result<Vec<u8>, AnyError> {
+  use p256::ecdsa::signature::hazmat::PrehashSigner;
+
+  macro_rules! sign {
+    ($curve:ident) => {{
+      let signing_key = $curve::ecdsa::SigningKey::from_pkcs8_der(key)
+        .map_err(|_| type_error("Invalid EC private key"))?;
+      let signature: $curve::ecdsa::Signature = signing_key
+        .sign_prehash(digest)
+        .map_err(|_| type_error("Failed to sign the digest"))?;
+      match dsa_encoding {
+        "ieee-p1363" => signature.to_bytes().to_vec(),
+        _ => signature.to_der().as_bytes().to_vec(),
+      }
+    }};
+  }
+
+  let curve = PrivateKeyInfo::from_der(key)
+    .map_err(|_| type_error("Invalid EC private key"))?
+    .algorithm
+    .parameters_oid()
+    .ok();
+  Ok(match curve {
+    Some(oid) if oid == SECP256R1_OID => sign!(p256),
+    Some(oid) if oid == SECP384R1_OID => sign!(p384),
+    _ => return Err(type_error("Unsupported EC curve")),
+  })
+}
+
+/// Verifies the signature of the digest with the SPKI encoded EC public key.
+pub fn ecdsa_verify(
+  key: &[u8],
+  digest: &[u8],
+  signature: &[u8],
+  dsa_encoding: &str,
+) -> Result<bool, AnyError> {
+  use p256::ecdsa::signature::hazmat::PrehashVerifier;
+
+  macro_rules! verify {
+    ($curve:ident) => {{
+      let verifying_key = $curve::ecdsa::VerifyingKey::from_public_key_der(key)
+        .map_err(|_| type_error("Invalid EC public key"))?;
+      let signature = match dsa_encoding {
+        "ieee-p1363" => $curve::ecdsa::Signature::from_slice(signature),
+        _ => $curve::ecdsa::Signature::from_der(signature),
+      };
+      match signature {
+        Ok(signature) => {
+          verifying_key.verify_prehash(digest, &signature).is_ok()
+        }
+        Err(_) => false,
+      }
+    }};
+  }
+
+  let curve = SubjectPublicKeyInfo::from_der(key)
+    .map_err(|_| type_error("Invalid EC public key"))?
+    .algorithm
+    .parameters_oid()
+    .ok();
+  Ok(match curve {
+    Some(oid) if oid == SECP256R1_OID => verify!(p256),
+    Some(oid) if oid == SECP384R1_OID => verify!(p384),
+    _ => return Err(type_error("Unsupported EC curve")),
+  })
+}
+
+fn rsa_details(key: &impl PublicKeyParts) -> AsymmetricKeyDetails {
+  AsymmetricKeyDetails {
+    modulus_length: Some(key.n().bits()),
+    public_exponent: key.e().to_u64(),
+    ..Default::default()
+  }
+}
+
+fn ec_details(
+  curve: Option<ObjectIdentifier>,
+) -> Result<AsymmetricKeyDetails, AnyError> {
+  // Node.js reports the OpenSSL names of the curves.
+  let named_curve = match curve {
+    Some(oid) if oid == SECP256R1_OID => "prime256v1",
+    Some(oid) if oid == SECP384R1_OID => "secp384r1",
+    _ => return Err(type_error("Unsupported EC curve")),
+  };
+  Ok(AsymmetricKeyDetails {
+    named_curve: Some(named_curve),
+    ..Default::default()
+  })
+}
+
+fn decode_pem(key: &[u8]) -> Result<(String, Vec<u8>), AnyError> {
+  let (_, pem) = pem::parse_x509_pem(key)
+    .map_err(|_| type_error("Invalid PEM formatted key"))?;
+  Ok((pem.label, pem.contents))
+}
+
+fn private_key_to_pkcs8(
+  key: &[u8],
+  format: &str,
+  r#type: &str,
+) -> Result<Vec<u8>, AnyError> {
+  let (r#type, der) = match format {
+    "pem" => {
+      let (label, der) = decode_pem(key)?;
+      let r#type = match label.as_str() {
+        "PRIVATE KEY" => "pkcs8",
+        "RSA PRIVATE KEY" => "pkcs1",
+        "EC PRIVATE KEY" => "sec1",
+        "ENCRYPTED PRIVATE KEY" => {
+          return Err(type_error("Encrypted private keys are not supported"))
+        }
+        _ => return Err(type_error(format!("Unsupported PEM label: {label}"))),
+      };
+      (r#type, der)
+    }
+    "der" => (r#type, key.to_vec()),
+    _ => return Err(type_error(format!("Unsupported key format: {format}"))),
+  };
+  match r#type {
+    "pkcs8" => Ok(der),
+    "pkcs1" => {
+      let key = RsaPrivateKey::from_pkcs1_der(&der)
+        .map_err(|_| type_error("Invalid RSA private key"))?;
+      Ok(
+        key
+          .to_pkcs8_der()
+          .map_err(|_| type_error("Invalid RSA private key"))?
+          .as_bytes()
+          .to_vec(),
+      )
+    }
+    "sec1" => {
+      let pkcs8 = if let Ok(key) = p256::SecretKey::from_sec1_der(&der) {
+        key.to_pkcs8_der()
+      } else if let Ok(key) = p384::SecretKey::from_sec1_der(&der) {
+        key.to_pkcs8_der()
+      } else {
+        return Err(type_error("Invalid EC private key"));
+      };
+      Ok(
+        pkcs8
+          .map_err(|_| type_error("Invalid EC private key"))?
+          .as_bytes()
+          .to_vec(),
+      )
+    }
+    _ => Err(type_error(format!("Unsupported key type: {}", r#type))),
+  }
+}
+
+fn public_key_to_spki(
+  key: &[u8],
+  format: &str,
+  r#type: &str,
+) -> Result<Vec<u8>, AnyError> {
+  let (r#type, der) = match format {
+    "pem" => {
+      let (label, der) = decode_pem(key)?;
+      let r#type = match label.as_str() {
+        "PUBLIC KEY" => "spki",
+        "RSA PUBLIC KEY" => "pkcs1",
+        "CERTIFICATE" => "certificate",
+        // the public key of a private key
+        _ => return spki_from_pkcs8(&private_key_to_pkcs8(key, format, "")?),
+      };
+      (r#type, der)
+    }
+    "der" => (r#type, key.to_vec()),
+    _ => return Err(type_error(format!("Unsupported key format: {format}"))),
+  };
+  match r#type {
+    "spki" => Ok(der),
+    "pkcs1" => {
+      let key = RsaPublicKey::from_pkcs1_der(&der)
+        .map_err(|_| type_error("Invalid RSA public key"))?;
+      Ok(
+        key
+          .to_public_key_der()
+          .map_err(|_| type_error("Invalid RSA public key"))?
+          .as_bytes()
+          .to_vec(),
+      )
+    }
+    "certificate" => {
+      let (_, cert) = X509Certificate::from_der(&der)
+        .map_err(|_| type_error("Invalid certificate"))?;
+      Ok(cert.public_key().raw.to_vec())
+    }
+    "pkcs8" | "sec1" => {
+      spki_from_pkcs8(&private_key_to_pkcs8(key, format, r#type)?)
+    }
+    _ => Err(type_error(format!("Unsupported key type: {}", r#type))),
+  }
+}
+
+fn spki_from_pkcs8(der: &[u8]) -> Result<Vec<u8>, AnyError> {
+  let info = PrivateKeyInfo::from_der(der)
+    .map_err(|_| type_error("Invalid private key"))?;
+  if info.algorithm.oid == RSA_ENCRYPTION_OID {
+    let key = RsaPrivateKey::from_pkcs8_der(der)
+      .map_err(|_| type_error("Invalid RSA private key"))?;
+    Ok(
+      key
+        .to_public_key()
+        .to_public_key_der()
+        .map_err(|_| type_error("Invalid RSA private key"))?
+        .as_bytes()
+        .to_vec(),
+    )
+  } else if info.algorithm.oid == EC_PUBLIC_KEY_OID {
+    let spki = match info.algorithm.parameters_oid().ok() {
+      Some(oid) if oid == SECP256R1_OID => p256::SecretKey::from_pkcs8_der(der)
+        .map_err(|_| type_error("Invalid EC private key"))?
+        .public_key()
+        .to_public_key_der(),
+      Some(oid) if oid == SECP384R1_OID => p384::SecretKey::from_pkcs8_der(der)
+        .map_err(|_| type_error("Invalid EC private key"))?
+        .public_key()
+        .to_public_key_der(),
+      _ => return Err(type_error("Unsupported EC curve")),
+    };
+    Ok(
+      spki
+        .map_err(|_| type_error("Invalid EC private key"))?
+        .as_bytes()
+        .to_vec(),
+    )
+  } else if info.algorithm.oid == ED25519_OID {
+    let pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der)
+      .map_err(|_| type_error("Invalid Ed25519 private key"))?;
+    let mut spki = ED25519_SPKI_PREFIX.to_vec();
+    spki.extend_from_slice(pair.public_key().as_ref());
+    Ok(spki)
+  } else {
+    Err(type_error(format!(
+      "Unsupported key algorithm: {}",
+      info.algorithm.oid
+    )))
+  }
+}
@@ -36,6 +36,7 @@ use secp256k1::SecretKey;
 mod cipher;
 mod dh;
 mod digest;
+pub mod keys;
 mod primes;
 pub mod x509;
 
@@ -214,19 +215,15 @@ pub fn op_node_public_encrypt(
   }
 }
 
-#[op(fast)]
+#[op]
 pub fn op_node_create_cipheriv(
   state: &mut OpState,
   algorithm: &str,
   key: &[u8],
   iv: &[u8],
-) -> u32 {
-  state.resource_table.add(
-    match cipher::CipherContext::new(algorithm, key, iv) {
-      Ok(context) => context,
-      Err(_) => return 0,
-    },
-  )
+) -> Result<u32, AnyError> {
+  let context = cipher::CipherContext::new(algorithm, key, iv)?;
+  Ok(state.resource_table.add(context))
 }
 
 #[op(fast)]
@@ -248,28 +245,25 @@ pub fn op_node_cipheriv_encrypt(
 pub fn op_node_cipheriv_final(
   state: &mut OpState,
   rid: u32,
+  auto_pad: bool,
   input: &[u8],
   output: &mut [u8],
 ) -> Result<(), AnyError> {
   let context = state.resource_table.take::<cipher::CipherContext>(rid)?;
   let context = Rc::try_unwrap(context)
     .map_err(|_| type_error("Cipher context is already in use"))?;
-  context.r#final(input, output)
+  context.r#final(auto_pad, input, output)
 }
 
-#[op(fast)]
+#[op]
 pub fn op_node_create_decipheriv(
   state: &mut OpState,
   algorithm: &str,
   key: &[u8],
   iv: &[u8],
-) -> u32 {
-  state.resource_table.add(
-    match cipher::DecipherContext::new(algorithm, key, iv) {
-      Ok(context) => context,
-      Err(_) => return 0,
-    },
-  )
+) -> Result<u32, AnyError> {
+  let context = cipher::DecipherContext::new(algorithm, key, iv)?;
+  Ok(state.resource_table.add(context))
 }
 
 #[op(fast)]
@@ -291,13 +285,14 @@ pub fn op_node_decipheriv_decrypt(
 pub fn op_node_decipheriv_final(
   state: &mut OpState,
   rid: u32,
+  auto_pad: bool,
   input: &[u8],
   output: &mut [u8],
 ) -> Result<(), AnyError> {
   let context = state.resource_table.take::<cipher::DecipherContext>(rid)?;
   let context = Rc::try_unwrap(context)
     .map_err(|_| type_error("Cipher context is already in use"))?;
-  context.r#final(input, output)
+  context.r#final(auto_pad, input, output)
 }
 
 #[op]
@@ -307,6 +302,7 @@ pub fn op_node_sign(
   key: StringOrBuffer,
   key_type: &str,
   key_format: &str,
+  dsa_encoding: &str,
 ) -> Result<ZeroCopyBuf, AnyError> {
   match key_type {
     "rsa" => {
@@ -315,7 +311,9 @@ pub fn op_node_sign(
       let key = match key_format {
         "pem" => RsaPrivateKey::from_pkcs8_pem((&key).try_into()?)
           .map_err(|_| type_error("Invalid RSA private key"))?,
-        // TODO(kt3k): Support der and jwk formats
+        "der" => RsaPrivateKey::from_pkcs8_der(&key)
+          .map_err(|_| type_error("Invalid RSA private key"))?,
+        // TODO(kt3k): Support jwk format
         _ => {
           return Err(type_error(format!(
             "Unsupported key format: {}",
@@ -351,6 +349,9 @@ pub fn op_node_sign(
         .into(),
       )
     }
+    "ec" if key_format == "der" => {
+      Ok(keys::ecdsa_sign(&key, digest, dsa_encoding)?.into())
+    }
     _ => Err(type_error(format!(
       "Signing with {} keys is not supported yet",
       key_type
@@ -366,6 +367,7 @@ fn op_node_verify(
   key_type: &str,
   key_format: &str,
   signature: &[u8],
+  dsa_encoding: &str,
 ) -> Result<bool, AnyError> {
   match key_type {
     "rsa" => {
@@ -374,7 +376,9 @@ fn op_node_verify(
       let key = match key_format {
         "pem" => RsaPublicKey::from_public_key_pem((&key).try_into()?)
           .map_err(|_| type_error("Invalid RSA public key"))?,
-        // TODO(kt3k): Support der and jwk formats
+        "der" => RsaPublicKey::from_public_key_der(&key)
+          .map_err(|_| type_error("Invalid RSA public key"))?,
+        // TODO(kt3k): Support jwk format
         _ => {
           return Err(type_error(format!(
             "Unsupported key format: {}",
@@ -403,6 +407,9 @@ fn op_node_verify(
         }
       })
     }
+    "ec" if key_format == "der" => {
+      keys::ecdsa_verify(&key, digest, signature, dsa_encoding)
+    }
     _ => Err(type_error(format!(
       "Verifying with {} keys is not supported yet",
       key_type
@@ -837,21 +844,21 @@ pub fn op_node_random_int(min: i32, max: i32) -> Result<i32, AnyError> {
 fn scrypt(
   password: StringOrBuffer,
   salt: StringOrBuffer,
-  keylen: u32,
   cost: u32,
   block_size: u32,
   parallelization: u32,
   _maxmem: u32,
   output_buffer: &mut [u8],
 ) -> Result<(), AnyError> {
-  // Construct Params
+  // Construct Params. The length of the params is only used for the PHC
+  // string format, the length of the derived key is the output buffer's.
   let params = scrypt::Params::new(
     cost as u8,
     block_size,
     parallelization,
-    keylen as usize,
+    scrypt::Params::RECOMMENDED_LEN,
   )
-  .unwrap();
+  .map_err(|_| type_error("Invalid scrypt params"))?;
 
   // Call into scrypt
   let res = scrypt::scrypt(&password, &salt, &params, output_buffer);
@@ -867,7 +874,7 @@ fn scrypt(
 pub fn op_node_scrypt_sync(
   password: StringOrBuffer,
   salt: StringOrBuffer,
-  keylen: u32,
+  _keylen: u32,
   cost: u32,
   block_size: u32,
   parallelization: u32,
@@ -877,7 +884,6 @@ pub fn op_node_scrypt_sync(
   scrypt(
     password,
     salt,
-    keylen,
     cost,
     block_size,
     parallelization,
@@ -901,7 +907,6 @@ pub async fn op_node_scrypt_async(
     let res = scrypt(
       password,
       salt,
-      keylen,
       cost,
       block_size,
       parallelization,
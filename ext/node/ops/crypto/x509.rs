@@ -5,8 +5,10 @@ use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::OpState;
 use deno_core::Resource;
+use deno_core::ZeroCopyBuf;
 
 use std::borrow::Cow;
+use std::net::IpAddr;
 
 use x509_parser::der_parser::asn1_rs::Any;
 use x509_parser::der_parser::asn1_rs::Tag;
@@ -18,25 +20,37 @@ use x509_parser::prelude::*;
 use digest::Digest;
 
 struct Certificate {
-  _buf: Vec<u8>,
-  pem: Option<pem::Pem>,
+  der: Vec<u8>,
   cert: X509Certificate<'static>,
 }
 
 impl Certificate {
   fn fingerprint<D: Digest>(&self) -> Option<String> {
-    self.pem.as_ref().map(|pem| {
-      let mut hasher = D::new();
-      hasher.update(&pem.contents);
-      let bytes = hasher.finalize();
-      // OpenSSL returns colon separated upper case hex values.
-      let mut hex = String::with_capacity(bytes.len() * 2);
-      for byte in bytes {
-        hex.push_str(&format!("{:02X}:", byte));
-      }
-      hex.pop();
-      hex
-    })
+    let mut hasher = D::new();
+    hasher.update(&self.der);
+    let bytes = hasher.finalize();
+    // OpenSSL returns colon separated upper case hex values.
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+      hex.push_str(&format!("{:02X}:", byte));
+    }
+    hex.pop();
+    Some(hex)
+  }
+
+  fn subject_alt_names(&self) -> Vec<&extensions::GeneralName> {
+    self
+      .extensions()
+      .iter()
+      .find(|e| {
+        e.oid == x509_parser::oid_registry::OID_X509_EXT_SUBJECT_ALT_NAME
+      })
+      .and_then(|e| match e.parsed_extension() {
+        extensions::ParsedExtension::SubjectAlternativeName(s) => Some(s),
+        _ => None,
+      })
+      .map(|s| s.general_names.iter().collect())
+      .unwrap_or_default()
   }
 }
 
@@ -59,22 +73,18 @@ pub fn op_node_x509_parse(
   state: &mut OpState,
   buf: &[u8],
 ) -> Result<u32, AnyError> {
-  let pem = match pem::parse_x509_pem(buf) {
-    Ok((_, pem)) => Some(pem),
-    Err(_) => None,
+  let der = match pem::parse_x509_pem(buf) {
+    Ok((_, pem)) => pem.contents,
+    Err(_) => buf.to_vec(),
   };
 
-  let cert = pem
-    .as_ref()
-    .map(|pem| pem.parse_x509())
-    .unwrap_or_else(|| X509Certificate::from_der(buf).map(|(_, cert)| cert))?;
+  let (_, cert) = X509Certificate::from_der(&der)?;
 
   let cert = Certificate {
-    _buf: buf.to_vec(),
     // SAFETY: Extending the lifetime of the certificate. Backing buffer is
     // owned by the resource.
     cert: unsafe { std::mem::transmute(cert) },
-    pem,
+    der,
   };
   let rid = state.resource_table.add(cert);
   Ok(rid)
@@ -111,22 +121,10 @@ pub fn op_node_x509_check_email(
     return Ok(true);
   }
 
-  let subject_alt = cert
-    .extensions()
-    .iter()
-    .find(|e| e.oid == x509_parser::oid_registry::OID_X509_EXT_SUBJECT_ALT_NAME)
-    .and_then(|e| match e.parsed_extension() {
-      extensions::ParsedExtension::SubjectAlternativeName(s) => Some(s),
-      _ => None,
-    });
-
-  if let Some(subject_alt) = subject_alt {
-    for name in &subject_alt.general_names {
-      dbg!(name);
-      if let extensions::GeneralName::RFC822Name(n) = name {
-        if *n == email {
-          return Ok(true);
-        }
+  for name in cert.subject_alt_names() {
+    if let extensions::GeneralName::RFC822Name(n) = name {
+      if *n == email {
+        return Ok(true);
       }
     }
   }
@@ -134,6 +132,119 @@ pub fn op_node_x509_check_email(
   Ok(false)
 }
 
+#[op]
+pub fn op_node_x509_check_host(
+  state: &mut OpState,
+  rid: u32,
+  host: &str,
+) -> Result<bool, AnyError> {
+  let cert = state
+    .resource_table
+    .get::<Certificate>(rid)
+    .map_err(|_| bad_resource_id())?;
+
+  let dns_names = cert
+    .subject_alt_names()
+    .into_iter()
+    .filter_map(|name| match name {
+      extensions::GeneralName::DNSName(n) => Some(*n),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+
+  // The common name of the subject is only checked when there are no DNS
+  // names in the subject alternative names.
+  if dns_names.is_empty() {
+    return Ok(
+      cert
+        .subject()
+        .iter_common_name()
+        .filter_map(|cn| cn.as_str().ok())
+        .any(|cn| host_matches(cn, host)),
+    );
+  }
+
+  Ok(dns_names.into_iter().any(|name| host_matches(name, host)))
+}
+
+#[op]
+pub fn op_node_x509_check_ip(
+  state: &mut OpState,
+  rid: u32,
+  ip: &str,
+) -> Result<bool, AnyError> {
+  let cert = state
+    .resource_table
+    .get::<Certificate>(rid)
+    .map_err(|_| bad_resource_id())?;
+
+  let ip = match ip.parse::<IpAddr>() {
+    Ok(IpAddr::V4(ip)) => ip.octets().to_vec(),
+    Ok(IpAddr::V6(ip)) => ip.octets().to_vec(),
+    Err(_) => return Ok(false),
+  };
+
+  Ok(cert.subject_alt_names().into_iter().any(|name| match name {
+    extensions::GeneralName::IPAddress(n) => *n == ip.as_slice(),
+    _ => false,
+  }))
+}
+
+#[op]
+pub fn op_node_x509_check_issued(
+  state: &mut OpState,
+  rid: u32,
+  issuer_rid: u32,
+) -> Result<bool, AnyError> {
+  let cert = state
+    .resource_table
+    .get::<Certificate>(rid)
+    .map_err(|_| bad_resource_id())?;
+  let issuer = state
+    .resource_table
+    .get::<Certificate>(issuer_rid)
+    .map_err(|_| bad_resource_id())?;
+  Ok(cert.issuer().as_raw() == issuer.subject().as_raw())
+}
+
+/// Matches the host against a DNS name of a certificate, which may start
+/// with a wildcard for a single label (ex. `*.example.com`).
+fn host_matches(pattern: &str, host: &str) -> bool {
+  let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+  let host = host.trim_end_matches('.').to_ascii_lowercase();
+  match pattern.strip_prefix("*.") {
+    Some(suffix) => match host.split_once('.') {
+      Some((label, rest)) => !label.is_empty() && rest == suffix,
+      None => false,
+    },
+    None => pattern == host,
+  }
+}
+
+#[op]
+pub fn op_node_x509_raw(
+  state: &mut OpState,
+  rid: u32,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let cert = state
+    .resource_table
+    .get::<Certificate>(rid)
+    .map_err(|_| bad_resource_id())?;
+  Ok(cert.der.clone().into())
+}
+
+#[op]
+pub fn op_node_x509_public_key(
+  state: &mut OpState,
+  rid: u32,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let cert = state
+    .resource_table
+    .get::<Certificate>(rid)
+    .map_err(|_| bad_resource_id())?;
+  Ok(cert.public_key().raw.to_vec().into())
+}
+
 #[op]
 pub fn op_node_x509_fingerprint(
   state: &mut OpState,
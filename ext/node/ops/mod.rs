@@ -4,5 +4,6 @@ pub mod crypto;
 pub mod idna;
 pub mod require;
 pub mod v8;
+pub mod vm;
 pub mod winerror;
 pub mod zlib;
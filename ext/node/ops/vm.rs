@@ -0,0 +1,459 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::serde_v8;
+use deno_core::v8;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Serialize)]
+pub struct VmError<'s> {
+  thrown: serde_v8::Value<'s>,
+}
+
+/// Same shape as the result of `Deno.core.evalContext()`: either a value or
+/// the exception thrown while producing it.
+#[derive(Serialize)]
+pub struct VmResult<'s>(Option<serde_v8::Value<'s>>, Option<VmError<'s>>);
+
+impl<'s> VmResult<'s> {
+  fn new(
+    scope: &mut v8::HandleScope<'s>,
+    result: Result<
+      Option<v8::Global<v8::Value>>,
+      Option<v8::Global<v8::Value>>,
+    >,
+  ) -> Self {
+    match result {
+      Ok(value) => {
+        VmResult(value.map(|value| v8::Local::new(scope, value).into()), None)
+      }
+      Err(Some(exception)) => VmResult(
+        None,
+        Some(VmError {
+          thrown: v8::Local::new(scope, exception).into(),
+        }),
+      ),
+      // Execution was terminated, there's no exception to rethrow.
+      Err(None) => VmResult(None, None),
+    }
+  }
+}
+
+fn script_origin<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  resource_name: v8::Local<'a, v8::String>,
+  line_offset: i32,
+  column_offset: i32,
+  is_module: bool,
+) -> v8::ScriptOrigin<'a> {
+  let source_map_url = v8::String::empty(scope);
+  v8::ScriptOrigin::new(
+    scope,
+    resource_name.into(),
+    line_offset,
+    column_offset,
+    false,
+    123,
+    source_map_url.into(),
+    true,
+    false,
+    is_module,
+  )
+}
+
+/// Returns the context of the global object returned by
+/// `op_vm_create_context`, or the current context if `null` is passed.
+fn context_from_global<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  global: v8::Local<v8::Value>,
+) -> Result<v8::Local<'s, v8::Context>, AnyError> {
+  if global.is_null_or_undefined() {
+    return Ok(scope.get_current_context());
+  }
+  v8::Local::<v8::Object>::try_from(global)
+    .ok()
+    .and_then(|global| global.get_creation_context(scope))
+    .ok_or_else(|| type_error("Invalid vm context"))
+}
+
+fn own_enumerable_keys<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  object: v8::Local<v8::Object>,
+) -> Option<v8::Local<'s, v8::Array>> {
+  object.get_own_property_names(
+    scope,
+    v8::GetPropertyNamesArgsBuilder::new()
+      .key_conversion(v8::KeyConversionMode::ConvertToString)
+      .build(),
+  )
+}
+
+/// Makes the own enumerable properties of the context's global object match
+/// the ones of the contextified object. Returns `None` if an exception was
+/// thrown.
+fn copy_properties_in(
+  scope: &mut v8::HandleScope,
+  sandbox: v8::Local<v8::Object>,
+  global: v8::Local<v8::Object>,
+) -> Option<()> {
+  let keys = own_enumerable_keys(scope, global)?;
+  for i in 0..keys.length() {
+    let key = keys.get_index(scope, i)?;
+    if !sandbox.has(scope, key)? {
+      global.delete(scope, key)?;
+    }
+  }
+  copy_properties_out(scope, sandbox, global)
+}
+
+/// Copies the own enumerable properties of `from` to `to`. Returns `None` if
+/// an exception was thrown.
+fn copy_properties_out(
+  scope: &mut v8::HandleScope,
+  from: v8::Local<v8::Object>,
+  to: v8::Local<v8::Object>,
+) -> Option<()> {
+  let keys = own_enumerable_keys(scope, from)?;
+  for i in 0..keys.length() {
+    let key = keys.get_index(scope, i)?;
+    let value = from.get(scope, key)?;
+    to.set(scope, key, value)?;
+  }
+  Some(())
+}
+
+fn run_script<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  sandbox: v8::Local<'s, v8::Object>,
+  global: v8::Local<v8::Object>,
+  source: v8::Local<v8::String>,
+  origin: &v8::ScriptOrigin,
+) -> Option<v8::Local<'s, v8::Value>> {
+  copy_properties_in(scope, sandbox, global)?;
+  let script = v8::Script::compile(scope, source, Some(origin))?;
+  let result = script.run(scope)?;
+  copy_properties_out(scope, global, sandbox)?;
+  // The global object of the context can't be used from the calling context,
+  // hand out the contextified object in its place.
+  if result.strict_equals(global.into()) {
+    return Some(sandbox.into());
+  }
+  Some(result)
+}
+
+/// Creates a new V8 context and returns its global object. The global object
+/// must be treated as an opaque handle: V8 doesn't allow accessing its
+/// properties from another context.
+#[op(v8)]
+pub fn op_vm_create_context<'a>(
+  scope: &mut v8::HandleScope<'a>,
+) -> serde_v8::Value<'a> {
+  let context = v8::Context::new(scope);
+  let global: v8::Local<v8::Value> = context.global(scope).into();
+  global.into()
+}
+
+#[op(v8)]
+pub fn op_vm_run_in_context<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  context_global: serde_v8::Value<'a>,
+  sandbox: serde_v8::Value<'a>,
+  source: serde_v8::Value<'a>,
+  filename: String,
+  line_offset: i32,
+  column_offset: i32,
+) -> Result<VmResult<'a>, AnyError> {
+  let context = context_from_global(scope, context_global.v8_value)?;
+  let sandbox = v8::Local::<v8::Object>::try_from(sandbox.v8_value)
+    .map_err(|_| type_error("Invalid contextified object"))?;
+  let source = v8::Local::<v8::String>::try_from(source.v8_value)
+    .map_err(|_| type_error("Invalid source"))?;
+  let filename = v8::String::new(scope, &filename).unwrap();
+  let origin =
+    script_origin(scope, filename, line_offset, column_offset, false);
+
+  let result = {
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    let global = context.global(tc_scope);
+    match run_script(tc_scope, sandbox, global, source, &origin) {
+      Some(result) => Ok(Some(v8::Global::new(tc_scope, result))),
+      None => Err(
+        tc_scope
+          .exception()
+          .map(|exception| v8::Global::new(tc_scope, exception)),
+      ),
+    }
+  };
+
+  Ok(VmResult::new(scope, result))
+}
+
+/// Modules created by `vm.SourceTextModule`. They live outside of the
+/// `JsRuntime`'s module map, their dependencies are provided by the linker
+/// function passed from JavaScript.
+#[derive(Default)]
+struct VmModuleMap {
+  next_id: u32,
+  modules: HashMap<u32, VmModule>,
+  ids: HashMap<v8::Global<v8::Module>, u32>,
+}
+
+struct VmModule {
+  handle: v8::Global<v8::Module>,
+  context: v8::Global<v8::Context>,
+  links: HashMap<String, u32>,
+}
+
+fn vm_module_map(isolate: &mut v8::Isolate) -> Rc<RefCell<VmModuleMap>> {
+  if let Some(map) = isolate.get_slot::<Rc<RefCell<VmModuleMap>>>() {
+    return map.clone();
+  }
+  let map = Rc::new(RefCell::new(VmModuleMap::default()));
+  isolate.set_slot(map.clone());
+  map
+}
+
+fn get_vm_module<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  id: u32,
+) -> Result<(v8::Local<'s, v8::Module>, v8::Local<'s, v8::Context>), AnyError> {
+  let map_rc = vm_module_map(scope);
+  let map = map_rc.borrow();
+  let module = map
+    .modules
+    .get(&id)
+    .ok_or_else(|| type_error("Invalid vm module"))?;
+  Ok((
+    v8::Local::new(scope, &module.handle),
+    v8::Local::new(scope, &module.context),
+  ))
+}
+
+/// Called by V8 during `op_vm_module_instantiate`.
+fn vm_module_resolve_callback<'s>(
+  context: v8::Local<'s, v8::Context>,
+  specifier: v8::Local<'s, v8::String>,
+  _import_assertions: v8::Local<'s, v8::FixedArray>,
+  referrer: v8::Local<'s, v8::Module>,
+) -> Option<v8::Local<'s, v8::Module>> {
+  // SAFETY: `CallbackScope` can be safely constructed from `Local<Context>`
+  let scope = &mut unsafe { v8::CallbackScope::new(context) };
+
+  let map_rc = vm_module_map(scope);
+  let map = map_rc.borrow();
+
+  let specifier = specifier.to_rust_string_lossy(scope);
+  let referrer = v8::Global::new(scope, referrer);
+  let maybe_module = map
+    .ids
+    .get(&referrer)
+    .and_then(|id| map.modules.get(id))
+    .and_then(|module| module.links.get(&specifier))
+    .and_then(|id| map.modules.get(id))
+    .map(|module| v8::Local::new(scope, &module.handle));
+  if maybe_module.is_none() {
+    let message = v8::String::new(
+      scope,
+      &format!(r#"Cannot resolve module "{specifier}""#),
+    )
+    .unwrap();
+    let exception = v8::Exception::type_error(scope, message);
+    scope.throw_exception(exception);
+  }
+  maybe_module
+}
+
+#[derive(Serialize)]
+pub struct VmModuleCompileResult<'s>(
+  Option<(u32, Vec<String>)>,
+  Option<VmError<'s>>,
+);
+
+/// Compiles a module in the given context. Returns its id and the specifiers
+/// it imports, which must be linked before it can be instantiated.
+#[op(v8)]
+pub fn op_vm_module_compile<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  context_global: serde_v8::Value<'a>,
+  source: serde_v8::Value<'a>,
+  identifier: String,
+) -> Result<VmModuleCompileResult<'a>, AnyError> {
+  let context = context_from_global(scope, context_global.v8_value)?;
+  let source = v8::Local::<v8::String>::try_from(source.v8_value)
+    .map_err(|_| type_error("Invalid source"))?;
+  let name = v8::String::new(scope, &identifier).unwrap();
+  let origin = script_origin(scope, name, 0, 0, true);
+
+  let result = {
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    let source = v8::script_compiler::Source::new(source, Some(&origin));
+    match v8::script_compiler::compile_module(tc_scope, source) {
+      Some(module) => Ok(v8::Global::new(tc_scope, module)),
+      None => Err(
+        tc_scope
+          .exception()
+          .map(|exception| v8::Global::new(tc_scope, exception)),
+      ),
+    }
+  };
+
+  let handle = match result {
+    Ok(handle) => handle,
+    Err(exception) => {
+      let VmResult(_, error) = VmResult::new(scope, Err(exception));
+      return Ok(VmModuleCompileResult(None, error));
+    }
+  };
+
+  let module = v8::Local::new(scope, &handle);
+  let module_requests = module.get_module_requests();
+  let mut requests = Vec::with_capacity(module_requests.length());
+  for i in 0..module_requests.length() {
+    let module_request = v8::Local::<v8::ModuleRequest>::try_from(
+      module_requests.get(scope, i).unwrap(),
+    )
+    .unwrap();
+    requests.push(module_request.get_specifier().to_rust_string_lossy(scope));
+  }
+
+  let context = v8::Global::new(scope, context);
+  let map_rc = vm_module_map(scope);
+  let mut map = map_rc.borrow_mut();
+  let id = map.next_id;
+  map.next_id += 1;
+  map.ids.insert(handle.clone(), id);
+  map.modules.insert(
+    id,
+    VmModule {
+      handle,
+      context,
+      links: HashMap::new(),
+    },
+  );
+
+  Ok(VmModuleCompileResult(Some((id, requests)), None))
+}
+
+#[op(v8)]
+pub fn op_vm_module_link(
+  scope: &mut v8::HandleScope,
+  id: u32,
+  specifier: String,
+  target: u32,
+) -> Result<(), AnyError> {
+  let map_rc = vm_module_map(scope);
+  let mut map = map_rc.borrow_mut();
+  if !map.modules.contains_key(&target) {
+    return Err(type_error("Invalid vm module"));
+  }
+  let module = map
+    .modules
+    .get_mut(&id)
+    .ok_or_else(|| type_error("Invalid vm module"))?;
+  module.links.insert(specifier, target);
+  Ok(())
+}
+
+#[op(v8)]
+pub fn op_vm_module_instantiate<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  id: u32,
+) -> Result<VmResult<'a>, AnyError> {
+  let (module, context) = get_vm_module(scope, id)?;
+
+  // IMPORTANT: No borrows to `VmModuleMap` can be held at this point because
+  // `vm_module_resolve_callback` will be calling into it.
+  let result = {
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    match module.instantiate_module(tc_scope, vm_module_resolve_callback) {
+      Some(_) => Ok(None),
+      None => Err(
+        tc_scope
+          .exception()
+          .map(|exception| v8::Global::new(tc_scope, exception)),
+      ),
+    }
+  };
+
+  Ok(VmResult::new(scope, result))
+}
+
+/// Evaluates an instantiated module, returning the promise V8 creates for
+/// its evaluation.
+#[op(v8)]
+pub fn op_vm_module_evaluate<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  id: u32,
+) -> Result<VmResult<'a>, AnyError> {
+  let (module, context) = get_vm_module(scope, id)?;
+
+  let result = {
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    match module.evaluate(tc_scope) {
+      Some(promise) => Ok(Some(v8::Global::new(tc_scope, promise))),
+      None => Err(
+        tc_scope
+          .exception()
+          .map(|exception| v8::Global::new(tc_scope, exception)),
+      ),
+    }
+  };
+
+  Ok(VmResult::new(scope, result))
+}
+
+#[op(v8)]
+pub fn op_vm_module_status(
+  scope: &mut v8::HandleScope,
+  id: u32,
+) -> Result<String, AnyError> {
+  let (module, _) = get_vm_module(scope, id)?;
+  let status = match module.get_status() {
+    v8::ModuleStatus::Uninstantiated => "unlinked",
+    v8::ModuleStatus::Instantiating => "linking",
+    v8::ModuleStatus::Instantiated => "linked",
+    v8::ModuleStatus::Evaluating => "evaluating",
+    v8::ModuleStatus::Evaluated => "evaluated",
+    v8::ModuleStatus::Errored => "errored",
+  };
+  Ok(status.to_string())
+}
+
+#[op(v8)]
+pub fn op_vm_module_namespace<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  id: u32,
+) -> Result<serde_v8::Value<'a>, AnyError> {
+  let (module, _) = get_vm_module(scope, id)?;
+  let namespace = v8::Global::new(scope, module.get_module_namespace());
+  Ok(v8::Local::new(scope, namespace).into())
+}
+
+#[op(v8)]
+pub fn op_vm_module_error<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  id: u32,
+) -> Result<serde_v8::Value<'a>, AnyError> {
+  let (module, _) = get_vm_module(scope, id)?;
+  let exception = v8::Global::new(scope, module.get_exception());
+  Ok(v8::Local::new(scope, exception).into())
+}
+
+/// Called once the `SourceTextModule` owning the module is garbage collected.
+#[op(v8)]
+pub fn op_vm_module_drop(scope: &mut v8::HandleScope, id: u32) {
+  let map_rc = vm_module_map(scope);
+  let mut map = map_rc.borrow_mut();
+  if let Some(module) = map.modules.remove(&id) {
+    map.ids.remove(&module.handle);
+  }
+}
@@ -127,6 +127,8 @@ export class Cipheriv extends Transform implements Cipher {
   /** plaintext data cache */
   #cache: BlockModeCache;
 
+  #autoPadding = true;
+
   constructor(
     cipher: string,
     key: CipherKey,
@@ -149,8 +151,14 @@ export class Cipheriv extends Transform implements Cipher {
   }
 
   final(encoding: string = getDefaultEncoding()): Buffer | string {
-    const buf = new Buffer(16);
-    ops.op_node_cipheriv_final(this.#context, this.#cache.cache, buf);
+    // Without padding, the data must have been a multiple of the block size.
+    const buf = new Buffer(this.#autoPadding ? 16 : 0);
+    ops.op_node_cipheriv_final(
+      this.#context,
+      this.#autoPadding,
+      this.#cache.cache,
+      buf,
+    );
     return encoding === "buffer" ? buf : buf.toString(encoding);
   }
 
@@ -168,8 +176,8 @@ export class Cipheriv extends Transform implements Cipher {
     return this;
   }
 
-  setAutoPadding(_autoPadding?: boolean): this {
-    notImplemented("crypto.Cipheriv.prototype.setAutoPadding");
+  setAutoPadding(autoPadding = true): this {
+    this.#autoPadding = !!autoPadding;
     return this;
   }
 
@@ -243,6 +251,8 @@ export class Decipheriv extends Transform implements Cipher {
   /** ciphertext data cache */
   #cache: BlockModeCache;
 
+  #autoPadding = true;
+
   constructor(
     cipher: string,
     key: CipherKey,
@@ -265,9 +275,17 @@ export class Decipheriv extends Transform implements Cipher {
   }
 
   final(encoding: string = getDefaultEncoding()): Buffer | string {
-    let buf = new Buffer(16);
-    ops.op_node_decipheriv_final(this.#context, this.#cache.cache, buf);
-    buf = buf.subarray(0, 16 - buf.at(-1)); // Padded in Pkcs7 mode
+    // Without padding, the last block is kept in the cache if there is one.
+    let buf = new Buffer(this.#autoPadding ? 16 : this.#cache.cache.length);
+    ops.op_node_decipheriv_final(
+      this.#context,
+      this.#autoPadding,
+      this.#cache.cache,
+      buf,
+    );
+    if (this.#autoPadding) {
+      buf = buf.subarray(0, 16 - buf.at(-1)); // Padded in Pkcs7 mode
+    }
     return encoding === "buffer" ? buf : buf.toString(encoding);
   }
 
@@ -284,8 +302,9 @@ export class Decipheriv extends Transform implements Cipher {
     notImplemented("crypto.Decipheriv.prototype.setAuthTag");
   }
 
-  setAutoPadding(_autoPadding?: boolean): this {
-    notImplemented("crypto.Decipheriv.prototype.setAutoPadding");
+  setAutoPadding(autoPadding = true): this {
+    this.#autoPadding = !!autoPadding;
+    return this;
   }
 
   update(
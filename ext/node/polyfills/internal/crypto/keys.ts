@@ -36,6 +36,8 @@ import {
   forgivingBase64UrlEncode as encodeToBase64Url,
 } from "ext:deno_web/00_infra.js";
 
+const { ops } = globalThis.__bootstrap.core;
+
 const getArrayBufferOrView = hideStackFrames(
   (
     buffer,
@@ -200,14 +202,20 @@ export class KeyObject {
       );
     }
 
-    notImplemented("crypto.KeyObject.prototype.equals");
+    if (otherKeyObject.type !== this.type) {
+      return false;
+    }
+    const key = getKeyMaterial(this);
+    const otherKey = getKeyMaterial(otherKeyObject);
+    return key.length === otherKey.length &&
+      key.every((byte, i) => byte === otherKey[i]);
   }
 
   export(options: KeyExportOptions<"pem">): string | Buffer;
   export(options?: KeyExportOptions<"der">): Buffer;
   export(options?: JwkKeyExportOptions): JsonWebKey;
   export(_options?: unknown): string | Buffer | JsonWebKey {
-    notImplemented("crypto.KeyObject.prototype.asymmetricKeyType");
+    notImplemented("crypto.KeyObject.prototype.export");
   }
 }
 
@@ -216,16 +224,143 @@ export interface JsonWebKeyInput {
   format: "jwk";
 }
 
+const kAsymmetricKeyType = Symbol("kAsymmetricKeyType");
+const kAsymmetricKeyDetails = Symbol("kAsymmetricKeyDetails");
+
+interface AsymmetricKey {
+  asymmetricKeyType: KeyType;
+  /** PKCS#8 DER of private keys, SPKI DER of public keys */
+  der: Uint8Array;
+  details: {
+    modulusLength?: number;
+    publicExponent?: number;
+    namedCurve?: string;
+  };
+}
+
+class AsymmetricKeyObject extends KeyObject {
+  [kAsymmetricKeyType]: KeyType;
+  [kAsymmetricKeyDetails]: AsymmetricKeyDetails;
+
+  constructor(type: "public" | "private", key: AsymmetricKey) {
+    super(type, setOwnedKey(key.der));
+    this[kAsymmetricKeyType] = key.asymmetricKeyType;
+    const { publicExponent, ...details } = key.details;
+    this[kAsymmetricKeyDetails] = publicExponent === undefined
+      ? details
+      : { ...details, publicExponent: BigInt(publicExponent) };
+  }
+
+  get asymmetricKeyType(): KeyType {
+    return this[kAsymmetricKeyType];
+  }
+
+  get asymmetricKeyDetails(): AsymmetricKeyDetails {
+    return { ...this[kAsymmetricKeyDetails] };
+  }
+
+  get symmetricKeySize(): undefined {
+    return undefined;
+  }
+
+  export(options?: KeyExportOptions<KeyFormat>): string | Buffer {
+    if (options === undefined) {
+      throw new ERR_INVALID_ARG_TYPE("options", "object", options);
+    }
+    validateObject(options, "options");
+    const { format = "pem", type, cipher } = options;
+    validateOneOf(format, "options.format", ["pem", "der", "jwk"]);
+    const expectedType = this.type === "private" ? "pkcs8" : "spki";
+    if (format === "jwk" || type !== expectedType || cipher !== undefined) {
+      // TODO: support jwk, pkcs1, sec1 and encrypted keys
+      notImplemented(
+        `crypto.KeyObject.prototype.export with ${format} format and ${type} type`,
+      );
+    }
+    const der = Buffer.from(getKeyMaterial(this));
+    if (format === "der") {
+      return der;
+    }
+    const label = this.type === "private" ? "PRIVATE KEY" : "PUBLIC KEY";
+    const lines = der.toString("base64").match(/.{1,64}/g)!;
+    return `-----BEGIN ${label}-----\n${
+      lines.join("\n")
+    }\n-----END ${label}-----\n`;
+  }
+}
+
+export class PrivateKeyObject extends AsymmetricKeyObject {
+  constructor(key: AsymmetricKey) {
+    super("private", key);
+  }
+}
+
+export class PublicKeyObject extends AsymmetricKeyObject {
+  constructor(key: AsymmetricKey) {
+    super("public", key);
+  }
+}
+
+function prepareAsymmetricKey(
+  key: unknown,
+): { data: Uint8Array; format: string; type: string } {
+  if (typeof key === "string" || isArrayBufferView(key)) {
+    return { data: toKeyData(key, undefined), format: "pem", type: "" };
+  }
+  validateObject(key, "key");
+  // deno-lint-ignore no-explicit-any
+  const { key: data, format = "pem", type = "", encoding } = key as any;
+  validateOneOf(format, "key.format", ["pem", "der", "jwk"]);
+  if (format === "jwk") {
+    notImplemented("crypto.createPrivateKey with jwk format");
+  }
+  if (typeof data !== "string" && !isArrayBufferView(data)) {
+    throw new ERR_INVALID_ARG_TYPE(
+      "key.key",
+      ["string", "Buffer", "TypedArray", "DataView"],
+      data,
+    );
+  }
+  return { data: toKeyData(data, encoding), format, type };
+}
+
+function toKeyData(
+  key: string | ArrayBufferView,
+  encoding: string | undefined,
+): Uint8Array {
+  if (typeof key === "string") {
+    return Buffer.from(key, encoding);
+  }
+  return new Uint8Array(key.buffer, key.byteOffset, key.byteLength);
+}
+
 export function createPrivateKey(
-  _key: PrivateKeyInput | string | Buffer | JsonWebKeyInput,
+  key: PrivateKeyInput | string | Buffer | JsonWebKeyInput,
 ): KeyObject {
-  notImplemented("crypto.createPrivateKey");
+  const { data, format, type } = prepareAsymmetricKey(key);
+  return new PrivateKeyObject(
+    ops.op_node_create_private_key(data, format, type),
+  );
 }
 
 export function createPublicKey(
-  _key: PublicKeyInput | string | Buffer | KeyObject | JsonWebKeyInput,
+  key: PublicKeyInput | string | Buffer | KeyObject | JsonWebKeyInput,
 ): KeyObject {
-  notImplemented("crypto.createPublicKey");
+  // deno-lint-ignore no-explicit-any
+  const keyObject = isKeyObject(key) ? key : (key as any)?.key;
+  if (isKeyObject(keyObject)) {
+    // the public key of a private key
+    if (keyObject.type !== "private") {
+      throw new ERR_CRYPTO_INVALID_KEY_OBJECT_TYPE(keyObject.type, "private");
+    }
+    return new PublicKeyObject(
+      ops.op_node_create_public_key(getKeyMaterial(keyObject), "der", "pkcs8"),
+    );
+  }
+  const { data, format, type } = prepareAsymmetricKey(key);
+  return new PublicKeyObject(
+    ops.op_node_create_public_key(data, format, type),
+  );
 }
 
 function getKeyTypes(allowKeyObject: boolean, bufferOnly = false) {
@@ -347,4 +482,6 @@ export default {
   prepareSecretKey,
   setOwnedKey,
   SecretKeyObject,
+  PrivateKeyObject,
+  PublicKeyObject,
 };
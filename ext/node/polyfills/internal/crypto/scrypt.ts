@@ -25,6 +25,9 @@ SOFTWARE.
 
 import { Buffer } from "ext:deno_node/buffer.ts";
 import { HASH_DATA } from "ext:deno_node/internal/crypto/types.ts";
+import {
+  ERR_CRYPTO_SCRYPT_INVALID_PARAMETER,
+} from "ext:deno_node/internal/errors.ts";
 
 const { core } = globalThis.__bootstrap;
 const { ops } = core;
@@ -54,6 +57,11 @@ const fixOpts = (opts?: Opts) => {
 
   if (opts.maxmem) out.maxmem = opts.maxmem;
 
+  // N must be a power of two greater than 1
+  if (out.N < 2 || (out.N & (out.N - 1)) !== 0) {
+    throw new ERR_CRYPTO_SCRYPT_INVALID_PARAMETER();
+  }
+
   return out;
 };
 
@@ -118,7 +126,10 @@ export function scrypt(
       maxmem,
     ).then(
       (buf: Uint8Array) => {
-        cb(null, Buffer.from(buf.buffer));
+        cb!(null, Buffer.from(buf.buffer));
+      },
+      (err: unknown) => {
+        cb!(err);
       },
     );
   } catch (err: unknown) {
@@ -16,11 +16,20 @@ import type {
   PrivateKeyInput,
   PublicKeyInput,
 } from "ext:deno_node/internal/crypto/types.ts";
-import { KeyObject } from "ext:deno_node/internal/crypto/keys.ts";
+import {
+  createPrivateKey,
+  createPublicKey,
+  getKeyMaterial,
+  isKeyObject,
+  KeyObject,
+} from "ext:deno_node/internal/crypto/keys.ts";
 import { createHash, Hash } from "ext:deno_node/internal/crypto/hash.ts";
-import { KeyFormat, KeyType } from "ext:deno_node/internal/crypto/types.ts";
 import { isArrayBufferView } from "ext:deno_node/internal/util/types.ts";
-import { ERR_CRYPTO_SIGN_KEY_REQUIRED } from "ext:deno_node/internal/errors.ts";
+import {
+  ERR_CRYPTO_INVALID_KEY_OBJECT_TYPE,
+  ERR_CRYPTO_SIGN_KEY_REQUIRED,
+} from "ext:deno_node/internal/errors.ts";
+import { crypto as constants } from "ext:deno_node/internal_binding/constants.ts";
 
 const { core } = globalThis.__bootstrap;
 const { ops } = core;
@@ -46,6 +55,43 @@ export interface VerifyKeyObjectInput extends SigningOptions {
 
 export type KeyLike = string | Buffer | KeyObject;
 
+/** Normalizes the key argument of the signing functions to a KeyObject of
+ * the given type and the signing options. */
+function prepareKey(
+  key: unknown,
+  keyType: "private" | "public",
+): { keyObject: KeyObject; dsaEncoding: DSAEncoding } {
+  let options: SigningOptions = {};
+  if (
+    typeof key === "object" && key !== null && !isArrayBufferView(key) &&
+    !isKeyObject(key)
+  ) {
+    options = key as SigningOptions;
+  }
+  if (options.padding === constants.RSA_PKCS1_PSS_PADDING) {
+    notImplemented("crypto.Sign with RSA_PKCS1_PSS_PADDING");
+  }
+  // deno-lint-ignore no-explicit-any
+  const keyObject = isKeyObject(key) ? key : (key as any).key;
+  let resolved: KeyObject;
+  if (isKeyObject(keyObject)) {
+    if (keyObject.type === keyType) {
+      resolved = keyObject;
+    } else if (keyType === "public" && keyObject.type === "private") {
+      resolved = createPublicKey(keyObject);
+    } else {
+      throw new ERR_CRYPTO_INVALID_KEY_OBJECT_TYPE(keyObject.type, keyType);
+    }
+  } else if (keyType === "private") {
+    // deno-lint-ignore no-explicit-any
+    resolved = createPrivateKey(key as any);
+  } else {
+    // deno-lint-ignore no-explicit-any
+    resolved = createPublicKey(key as any);
+  }
+  return { keyObject: resolved, dsaEncoding: options.dsaEncoding ?? "der" };
+}
+
 export class SignImpl extends Writable {
   hash: Hash;
   #digestType: string;
@@ -74,26 +120,14 @@ export class SignImpl extends Writable {
     privateKey: BinaryLike | SignKeyObjectInput | SignPrivateKeyInput,
     encoding?: BinaryToTextEncoding,
   ): Buffer | string {
-    let keyData: Uint8Array;
-    let keyType: KeyType;
-    let keyFormat: KeyFormat;
-    if (typeof privateKey === "string" || isArrayBufferView(privateKey)) {
-      // if the key is BinaryLike, interpret it as a PEM encoded RSA key
-      // deno-lint-ignore no-explicit-any
-      keyData = privateKey as any;
-      keyType = "rsa";
-      keyFormat = "pem";
-    } else {
-      // TODO(kt3k): Add support for the case when privateKey is a KeyObject,
-      // CryptoKey, etc
-      notImplemented("crypto.Sign.prototype.sign with non BinaryLike input");
-    }
+    const { keyObject, dsaEncoding } = prepareKey(privateKey, "private");
     const ret = Buffer.from(ops.op_node_sign(
       this.hash.digest(),
       this.#digestType,
-      keyData!,
-      keyType,
-      keyFormat,
+      getKeyMaterial(keyObject),
+      keyObject.asymmetricKeyType,
+      "der",
+      dsaEncoding,
     ));
     return encoding ? ret.toString(encoding) : ret;
   }
@@ -148,29 +182,15 @@ export class VerifyImpl extends Writable {
     signature: BinaryLike,
     encoding?: BinaryToTextEncoding,
   ): boolean {
-    let keyData: BinaryLike;
-    let keyType: KeyType;
-    let keyFormat: KeyFormat;
-    if (typeof publicKey === "string" || isArrayBufferView(publicKey)) {
-      // if the key is BinaryLike, interpret it as a PEM encoded RSA key
-      // deno-lint-ignore no-explicit-any
-      keyData = publicKey as any;
-      keyType = "rsa";
-      keyFormat = "pem";
-    } else {
-      // TODO(kt3k): Add support for the case when publicKey is a KeyObject,
-      // CryptoKey, etc
-      notImplemented(
-        "crypto.Verify.prototype.verify with non BinaryLike input",
-      );
-    }
+    const { keyObject, dsaEncoding } = prepareKey(publicKey, "public");
     return ops.op_node_verify(
       this.hash.digest(),
       this.#digestType,
-      keyData!,
-      keyType,
-      keyFormat,
+      getKeyMaterial(keyObject),
+      keyObject.asymmetricKeyType,
+      "der",
       Buffer.from(signature, encoding),
+      dsaEncoding,
     );
   }
 }
@@ -199,7 +219,16 @@ export function signOneShot(
     throw new ERR_CRYPTO_SIGN_KEY_REQUIRED();
   }
 
-  const result = Sign(algorithm!).update(data).sign(key);
+  let result: Buffer;
+  const { keyObject } = prepareKey(key, "private");
+  if (keyObject.asymmetricKeyType === "ed25519") {
+    // Ed25519 signs the data itself instead of a digest of it
+    result = Buffer.from(
+      ops.op_node_sign_ed25519(getKeyMaterial(keyObject), data),
+    );
+  } else {
+    result = Sign(algorithm ?? "sha256").update(data).sign(key) as Buffer;
+  }
 
   if (callback) {
     setTimeout(() => callback(null, result));
@@ -227,7 +256,21 @@ export function verifyOneShot(
     throw new ERR_CRYPTO_SIGN_KEY_REQUIRED();
   }
 
-  const result = Verify(algorithm!).update(data).verify(key, signature);
+  let result: boolean;
+  const { keyObject } = prepareKey(key, "public");
+  if (keyObject.asymmetricKeyType === "ed25519") {
+    // Ed25519 verifies the data itself instead of a digest of it
+    result = ops.op_node_verify_ed25519(
+      getKeyMaterial(keyObject),
+      typeof data === "string" ? Buffer.from(data) : data,
+      typeof signature === "string" ? Buffer.from(signature) : signature,
+    );
+  } else {
+    result = Verify(algorithm ?? "sha256").update(data).verify(
+      key,
+      signature,
+    );
+  }
 
   if (callback) {
     setTimeout(() => callback(null, result));
@@ -1,7 +1,10 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent, Inc. and Node.js contributors. All rights reserved. MIT license.
 
-import { KeyObject } from "ext:deno_node/internal/crypto/keys.ts";
+import {
+  createPublicKey,
+  KeyObject,
+} from "ext:deno_node/internal/crypto/keys.ts";
 import { Buffer } from "ext:deno_node/buffer.ts";
 import { ERR_INVALID_ARG_TYPE } from "ext:deno_node/internal/errors.ts";
 import { isArrayBufferView } from "ext:deno_node/internal/util/types.ts";
@@ -70,16 +73,30 @@ export class X509Certificate {
     }
   }
 
-  checkHost(_name: string, _options?: X509CheckOptions): string | undefined {
-    notImplemented("crypto.X509Certificate.prototype.checkHost");
+  checkHost(name: string, _options?: X509CheckOptions): string | undefined {
+    validateString(name, "name");
+    // TODO: support the options of the wildcard matching
+    if (ops.op_node_x509_check_host(this.#handle, name)) {
+      return name;
+    }
   }
 
-  checkIP(_ip: string): string | undefined {
-    notImplemented("crypto.X509Certificate.prototype.checkIP");
+  checkIP(ip: string): string | undefined {
+    validateString(ip, "ip");
+    if (ops.op_node_x509_check_ip(this.#handle, ip)) {
+      return ip;
+    }
   }
 
-  checkIssued(_otherCert: X509Certificate): boolean {
-    notImplemented("crypto.X509Certificate.prototype.checkIssued");
+  checkIssued(otherCert: X509Certificate): boolean {
+    if (!(otherCert instanceof X509Certificate)) {
+      throw new ERR_INVALID_ARG_TYPE(
+        "otherCert",
+        "X509Certificate",
+        otherCert,
+      );
+    }
+    return ops.op_node_x509_check_issued(this.#handle, otherCert.#handle);
   }
 
   checkPrivateKey(_privateKey: KeyObject): boolean {
@@ -129,15 +146,15 @@ export class X509Certificate {
   }
 
   get publicKey(): KeyObject {
-    notImplemented("crypto.X509Certificate.prototype.publicKey");
-
-    return {} as KeyObject;
+    return createPublicKey({
+      key: Buffer.from(ops.op_node_x509_public_key(this.#handle)),
+      format: "der",
+      type: "spki",
+    });
   }
 
   get raw(): Buffer {
-    notImplemented("crypto.X509Certificate.prototype.raw");
-
-    return {} as Buffer;
+    return Buffer.from(ops.op_node_x509_raw(this.#handle));
   }
 
   get serialNumber(): string {
@@ -161,7 +178,10 @@ export class X509Certificate {
   }
 
   toString(): string {
-    notImplemented("crypto.X509Certificate.prototype.toString");
+    const lines = this.raw.toString("base64").match(/.{1,64}/g)!;
+    return `-----BEGIN CERTIFICATE-----\n${
+      lines.join("\n")
+    }\n-----END CERTIFICATE-----\n`;
   }
 
   get validFrom(): string {
@@ -2,14 +2,67 @@
 
 // deno-lint-ignore-file no-explicit-any
 
+// Contexts are backed by separate V8 contexts. Unlike in Node.js, the global
+// object of a context doesn't intercept property accesses to reach the
+// contextified object. Instead, the own enumerable properties of the
+// contextified object are copied onto the global object before running code
+// and copied back once it finished. As a result:
+// - properties the code sets later on (eg. from a timer callback) only show up
+//   on the contextified object after the next run in that context,
+// - the context's global object itself is never handed out, `this` or
+//   `globalThis` are replaced with the contextified object only when they are
+//   the completion value of the script,
+// - the `timeout`, `breakOnSigint`, `microtaskMode` and code cache options are
+//   ignored,
+// - promise rejections inside of a context aren't reported as unhandled.
+// `SourceTextModule` modules get an empty `import.meta` and can't use dynamic
+// `import()`.
+
 import { notImplemented } from "ext:deno_node/_utils.ts";
+import {
+  ERR_INVALID_ARG_TYPE,
+  ERR_VM_MODULE_ALREADY_LINKED,
+  ERR_VM_MODULE_DIFFERENT_CONTEXT,
+  ERR_VM_MODULE_NOT_MODULE,
+  ERR_VM_MODULE_STATUS,
+} from "ext:deno_node/internal/errors.ts";
+import {
+  validateFunction,
+  validateObject,
+  validateString,
+} from "ext:deno_node/internal/validators.mjs";
 
 const { core } = globalThis.__bootstrap;
+const { ops } = core;
+
+// Maps contextified objects to the (opaque) global object of their context.
+const contexts = new WeakMap<object, unknown>();
+
+function getContextGlobal(contextifiedObject: any): unknown {
+  if (!isContext(contextifiedObject)) {
+    throw new ERR_INVALID_ARG_TYPE(
+      "contextifiedObject",
+      "vm.Context",
+      contextifiedObject,
+    );
+  }
+  return contexts.get(contextifiedObject);
+}
 
 export class Script {
   code: string;
-  constructor(code: string, _options = {}) {
+  #filename: string;
+  #lineOffset: number;
+  #columnOffset: number;
+
+  constructor(code: string, options: any = {}) {
     this.code = `${code}`;
+    if (typeof options === "string") {
+      options = { filename: options };
+    }
+    this.#filename = options.filename ?? "evalmachine.<anonymous>";
+    this.#lineOffset = options.lineOffset ?? 0;
+    this.#columnOffset = options.columnOffset ?? 0;
   }
 
   runInThisContext(_options: any) {
@@ -20,21 +73,36 @@ export class Script {
     return result;
   }
 
-  runInContext(_contextifiedObject: any, _options: any) {
-    notImplemented("Script.prototype.runInContext");
+  runInContext(contextifiedObject: any, _options: any) {
+    const [result, error] = ops.op_vm_run_in_context(
+      getContextGlobal(contextifiedObject),
+      contextifiedObject,
+      this.code,
+      this.#filename,
+      this.#lineOffset,
+      this.#columnOffset,
+    );
+    if (error) {
+      throw error.thrown;
+    }
+    return result;
   }
 
-  runInNewContext(_contextObject: any, _options: any) {
-    notImplemented("Script.prototype.runInNewContext");
+  runInNewContext(contextObject: any, options: any) {
+    return this.runInContext(createContext(contextObject, options), options);
   }
 
   createCachedData() {
-    notImplemented("Script.prototyp.createCachedData");
+    notImplemented("Script.prototype.createCachedData");
   }
 }
 
-export function createContext(_contextObject: any, _options: any) {
-  notImplemented("createContext");
+export function createContext(contextObject: any = {}, _options?: any) {
+  if (isContext(contextObject)) {
+    return contextObject;
+  }
+  contexts.set(contextObject, ops.op_vm_create_context());
+  return contextObject;
 }
 
 export function createScript(code: string, options: any) {
@@ -42,19 +110,19 @@ export function createScript(code: string, options: any) {
 }
 
 export function runInContext(
-  _code: string,
-  _contextifiedObject: any,
-  _options: any,
+  code: string,
+  contextifiedObject: any,
+  options: any,
 ) {
-  notImplemented("runInContext");
+  return createScript(code, options).runInContext(contextifiedObject, options);
 }
 
 export function runInNewContext(
-  _code: string,
-  _contextObject: any,
-  _options: any,
+  code: string,
+  contextObject: any,
+  options: any,
 ) {
-  notImplemented("runInNewContext");
+  return createScript(code, options).runInNewContext(contextObject, options);
 }
 
 export function runInThisContext(
@@ -64,20 +132,164 @@ export function runInThisContext(
   return createScript(code, options).runInThisContext(options);
 }
 
-export function isContext(_maybeContext: any) {
-  notImplemented("isContext");
+export function isContext(maybeContext: any) {
+  validateObject(maybeContext, "contextObject", {
+    allowArray: true,
+    allowFunction: true,
+  });
+  return contexts.has(maybeContext);
 }
 
-export function compileFunction(_code: string, _params: any, _options: any) {
-  notImplemented("compileFunction");
+export function compileFunction(
+  code: string,
+  params: string[] = [],
+  options: any = {},
+) {
+  validateString(code, "code");
+  const { parsingContext, contextExtensions } = options;
+  if (contextExtensions?.length > 0) {
+    notImplemented("compileFunction with contextExtensions");
+  }
+  // The wrapper adds a line before the function body.
+  const script = new Script(
+    `(function (${params.join(", ")}) {\n${code}\n})`,
+    {
+      filename: options.filename,
+      lineOffset: (options.lineOffset ?? 0) - 1,
+      columnOffset: options.columnOffset,
+    },
+  );
+  return parsingContext === undefined
+    ? script.runInThisContext(options)
+    : script.runInContext(parsingContext, options);
 }
 
 export function measureMemory(_options: any) {
   notImplemented("measureMemory");
 }
 
+let moduleCount = 0;
+
+const moduleRegistry = new FinalizationRegistry((id: number) => {
+  ops.op_vm_module_drop(id);
+});
+
+export class SourceTextModule {
+  readonly identifier: string;
+  readonly context: any;
+  #id: number;
+  #dependencySpecifiers: string[];
+  // Keeps the modules this module was linked against alive.
+  #dependencies: SourceTextModule[] = [];
+  #linking = false;
+
+  constructor(sourceText: string, options: any = {}) {
+    validateString(sourceText, "sourceText");
+    validateObject(options, "options");
+    const {
+      context,
+      identifier = `vm:module(${moduleCount++})`,
+    } = options;
+    validateString(identifier, "options.identifier");
+
+    const [result, error] = ops.op_vm_module_compile(
+      context === undefined ? null : getContextGlobal(context),
+      sourceText,
+      identifier,
+    );
+    if (error) {
+      throw error.thrown;
+    }
+    this.identifier = identifier;
+    this.context = context;
+    [this.#id, this.#dependencySpecifiers] = result;
+    moduleRegistry.register(this, this.#id);
+  }
+
+  get dependencySpecifiers(): string[] {
+    return Object.freeze([...this.#dependencySpecifiers]) as string[];
+  }
+
+  get status(): string {
+    const status = ops.op_vm_module_status(this.#id);
+    return status === "unlinked" && this.#linking ? "linking" : status;
+  }
+
+  get namespace(): any {
+    const status = this.status;
+    if (status === "unlinked" || status === "linking") {
+      throw new ERR_VM_MODULE_STATUS("must not be unlinked or linking");
+    }
+    return ops.op_vm_module_namespace(this.#id);
+  }
+
+  get error(): any {
+    if (this.status !== "errored") {
+      throw new ERR_VM_MODULE_STATUS("must be errored");
+    }
+    return ops.op_vm_module_error(this.#id);
+  }
+
+  async link(linker: (...args: any[]) => any) {
+    validateFunction(linker, "linker");
+    const status = this.status;
+    if (status === "linked" || status === "evaluated") {
+      throw new ERR_VM_MODULE_ALREADY_LINKED();
+    }
+    if (status !== "unlinked") {
+      throw new ERR_VM_MODULE_STATUS("must be unlinked");
+    }
+    await this.#link(linker);
+    const [, error] = ops.op_vm_module_instantiate(this.#id);
+    if (error) {
+      throw error.thrown;
+    }
+  }
+
+  async #link(linker: (...args: any[]) => any) {
+    this.#linking = true;
+    try {
+      for (const specifier of this.#dependencySpecifiers) {
+        const module = await linker(specifier, this, { assert: {} });
+        if (!(module instanceof SourceTextModule)) {
+          throw new ERR_VM_MODULE_NOT_MODULE();
+        }
+        if (module.context !== this.context) {
+          throw new ERR_VM_MODULE_DIFFERENT_CONTEXT();
+        }
+        // Dependencies that are already being linked are part of a cycle and
+        // report a "linking" status.
+        if (module.status === "unlinked") {
+          await module.#link(linker);
+        }
+        ops.op_vm_module_link(this.#id, specifier, module.#id);
+        this.#dependencies.push(module);
+      }
+    } finally {
+      this.#linking = false;
+    }
+  }
+
+  async evaluate(_options?: any) {
+    const status = this.status;
+    if (
+      status !== "linked" && status !== "evaluated" && status !== "errored"
+    ) {
+      throw new ERR_VM_MODULE_STATUS(
+        "must be one of linked, evaluated, or errored",
+      );
+    }
+    const [promise, error] = ops.op_vm_module_evaluate(this.#id);
+    if (error) {
+      throw error.thrown;
+    }
+    await promise;
+  }
+}
+
 export default {
   Script,
+  SourceTextModule,
   createContext,
   createScript,
   runInContext,
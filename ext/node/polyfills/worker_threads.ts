@@ -1,19 +1,28 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent and Node contributors. All rights reserved. MIT license.
 
-import { resolve, toFileUrl } from "ext:deno_node/path.ts";
+import { fromFileUrl, resolve, toFileUrl } from "ext:deno_node/path.ts";
 import { notImplemented } from "ext:deno_node/_utils.ts";
 import { EventEmitter } from "ext:deno_node/events.ts";
+import {
+  ERR_INVALID_ARG_TYPE,
+  ERR_INVALID_URL_SCHEME,
+} from "ext:deno_node/internal/errors.ts";
+import process from "ext:deno_node/process.ts";
 import { BroadcastChannel } from "ext:deno_broadcast_channel/01_broadcast_channel.js";
 import { MessageChannel, MessagePort } from "ext:deno_web/13_message_port.js";
+import { refWorker, unrefWorker } from "ext:runtime/11_workers.js";
 
-const environmentData = new Map();
+const { ops } = globalThis.__bootstrap.core;
+const internals = globalThis.__bootstrap.internals;
+
+let environmentData = new Map();
 let threads = 0;
 
 export interface WorkerOptions {
   // only for typings
   argv?: unknown[];
-  env?: Record<string, unknown>;
+  env?: Record<string, unknown> | typeof SHARE_ENV;
   execArgv?: string[];
   stdin?: boolean;
   stdout?: boolean;
@@ -31,8 +40,65 @@ export interface WorkerOptions {
   workerData?: unknown;
 }
 
+export const SHARE_ENV = Symbol.for("nodejs.worker_threads.SHARE_ENV");
+
 const kHandle = Symbol("kHandle");
 const PRIVATE_WORKER_THREAD_NAME = "$DENO_STD_NODE_WORKER_THREAD";
+// Messages carrying this key are sent by the worker runtime itself (as opposed
+// to user code) and are never surfaced as "message" events.
+const PRIVATE_WORKER_MESSAGE_KEY = "$DENO_STD_NODE_WORKER_THREAD_MESSAGE";
+
+// Returns `true` if the file should be loaded with `require()` rather than
+// imported as an ES module, following the same rules Node.js uses.
+function isCommonJsFile(filename: string): boolean {
+  if (filename.endsWith(".cjs")) {
+    return true;
+  }
+  if (!filename.endsWith(".js")) {
+    return false;
+  }
+  const pkg = ops.op_require_read_package_scope(filename);
+  return !(pkg && pkg.exists && pkg.typ == "module");
+}
+
+// Builds the source of the module the underlying web worker is started with.
+// It waits for the startup message to be processed, so that `workerData` and
+// friends are available synchronously, and then loads the user code.
+function createEntrypoint(
+  specifier: URL | string,
+  options?: WorkerOptions,
+): string {
+  const ready = "await Deno[Deno.internal].__nodeWorkerThreadReady;";
+  if (options?.eval === true) {
+    const filename = JSON.stringify(resolve("[worker eval]"));
+    return `${ready}
+const { default: Module } = await import("node:module");
+const module = new Module(${filename});
+module.filename = ${filename};
+module.paths = Module._nodeModulePaths(${JSON.stringify(resolve("."))});
+module._compile(${JSON.stringify(String(specifier))}, ${filename});`;
+  }
+
+  let url: string;
+  if (typeof specifier === "string") {
+    url = toFileUrl(resolve(specifier)).href;
+  } else if (specifier.protocol === "file:") {
+    url = specifier.href;
+  } else {
+    throw new ERR_INVALID_URL_SCHEME("file");
+  }
+
+  const filename = fromFileUrl(url);
+  if (isCommonJsFile(filename)) {
+    return `${ready}
+Deno[Deno.internal].node.loadCjsModule(${
+      JSON.stringify(filename)
+    }, true, false);`;
+  }
+  return `${ready}
+await import(${JSON.stringify(url)});`;
+}
+
 class _Worker extends EventEmitter {
   readonly threadId: number;
   readonly resourceLimits: Required<
@@ -44,49 +110,96 @@ class _Worker extends EventEmitter {
     stackSizeMb: 4,
   };
   private readonly [kHandle]: Worker;
+  #exitCode: number | undefined;
 
   postMessage: Worker["postMessage"];
 
   constructor(specifier: URL | string, options?: WorkerOptions) {
-    notImplemented("Worker");
     super();
-    if (options?.eval === true) {
-      specifier = `data:text/javascript,${specifier}`;
-    } else if (typeof specifier === "string") {
-      // @ts-ignore This API is temporarily disabled
-      specifier = toFileUrl(resolve(specifier));
+    // Deno's environment is shared by all threads, so without an explicit
+    // `env` object the worker behaves as if `SHARE_ENV` was passed.
+    let env: Record<string, string> | undefined;
+    if (options?.env !== undefined && options.env !== SHARE_ENV) {
+      if (options.env === null || typeof options.env !== "object") {
+        throw new ERR_INVALID_ARG_TYPE(
+          "options.env",
+          ["object", "undefined"],
+          options.env,
+        );
+      }
+      env = {};
+      for (const [key, value] of Object.entries(options.env)) {
+        env[key] = String(value);
+      }
     }
+    const entrypoint = createEntrypoint(specifier, options);
     const handle = this[kHandle] = new Worker(
-      specifier,
+      `data:text/javascript,${encodeURIComponent(entrypoint)}`,
       {
         name: PRIVATE_WORKER_THREAD_NAME,
         type: "module",
       } as globalThis.WorkerOptions, // bypass unstable type error
     );
-    handle.addEventListener(
-      "error",
-      (event) => this.emit("error", event.error || event.message),
-    );
+    handle.addEventListener("error", (event) => {
+      // Node.js doesn't propagate uncaught worker errors to the parent's
+      // global scope, it emits them on the `Worker` and stops the thread.
+      event.preventDefault();
+      this.emit("error", event.error ?? new Error(event.message));
+      this.#exit(1);
+    });
     handle.addEventListener(
       "messageerror",
       (event) => this.emit("messageerror", event.data),
     );
-    handle.addEventListener(
-      "message",
-      (event) => this.emit("message", event.data),
-    );
+    handle.addEventListener("message", (event) => {
+      const data = event.data;
+      if (
+        data !== null && typeof data === "object" &&
+        Object.hasOwn(data, PRIVATE_WORKER_MESSAGE_KEY)
+      ) {
+        const [type, code] = data[PRIVATE_WORKER_MESSAGE_KEY];
+        if (type === "online") {
+          this.emit("online");
+        } else if (type === "exit") {
+          this.#exit(code);
+        }
+        return;
+      }
+      this.emit("message", data);
+    });
+
     handle.postMessage({
+      argv: (options?.argv ?? []).map(String),
+      env,
       environmentData,
       threadId: (this.threadId = ++threads),
       workerData: options?.workerData,
     }, options?.transferList || []);
     this.postMessage = handle.postMessage.bind(handle);
-    this.emit("online");
   }
 
-  terminate() {
+  #exit(code: number) {
+    if (this.#exitCode !== undefined) {
+      return;
+    }
+    this.#exitCode = code;
     this[kHandle].terminate();
-    this.emit("exit", 0);
+    this.emit("exit", code);
+  }
+
+  terminate(): Promise<number> {
+    this.#exit(1);
+    return Promise.resolve(this.#exitCode!);
+  }
+
+  ref() {
+    // deno-lint-ignore no-explicit-any
+    (this[kHandle] as any)[refWorker]();
+  }
+
+  unref() {
+    // deno-lint-ignore no-explicit-any
+    (this[kHandle] as any)[unrefWorker]();
   }
 
   readonly getHeapSnapshot = () =>
@@ -95,20 +208,14 @@ class _Worker extends EventEmitter {
   readonly performance = globalThis.performance;
 }
 
-export const isMainThread =
-  // deno-lint-ignore no-explicit-any
-  (globalThis as any).name !== PRIVATE_WORKER_THREAD_NAME;
-
+// These are only known once the node runtime is initialized in a given
+// thread, see `internals.__initWorkerThreads` below.
+export let isMainThread = true;
 // fake resourceLimits
-export const resourceLimits = isMainThread ? {} : {
-  maxYoungGenerationSizeMb: 48,
-  maxOldGenerationSizeMb: 2048,
-  codeRangeSizeMb: 0,
-  stackSizeMb: 4,
-};
+export let resourceLimits = {};
 
-const threadId = 0;
-const workerData: unknown = null;
+let threadId = 0;
+let workerData: unknown = null;
 
 // Like https://github.com/nodejs/node/blob/48655e17e1d84ba5021d7a94b4b88823f7c9c6cf/lib/internal/event_target.js#L611
 interface NodeEventTarget extends
@@ -131,12 +238,30 @@ interface NodeEventTarget extends
 type ParentPort = typeof self & NodeEventTarget;
 
 // deno-lint-ignore no-explicit-any
-const parentPort: ParentPort = null as any;
+let parentPort: ParentPort = null as any;
+
+// Should be called only once, in `02_init.js` when the node runtime is
+// initialized.
+internals.__initWorkerThreads = function () {
+  delete internals.__initWorkerThreads;
+
+  // deno-lint-ignore no-explicit-any
+  isMainThread = (globalThis as any).name !== PRIVATE_WORKER_THREAD_NAME;
+  defaultExport.isMainThread = isMainThread;
+  if (isMainThread) {
+    return;
+  }
 
-/*
-if (!isMainThread) {
   // deno-lint-ignore no-explicit-any
   delete (globalThis as any).name;
+  resourceLimits = {
+    maxYoungGenerationSizeMb: 48,
+    maxOldGenerationSizeMb: 2048,
+    codeRangeSizeMb: 0,
+    stackSizeMb: 4,
+  };
+  defaultExport.resourceLimits = resourceLimits;
+
   // deno-lint-ignore no-explicit-any
   const listeners = new WeakMap<(...args: any[]) => void, (ev: any) => any>();
 
@@ -163,9 +288,12 @@ if (!isMainThread) {
   };
   parentPort.once = function (this: ParentPort, name, listener) {
     // deno-lint-ignore no-explicit-any
-    const _listener = (ev: any) => listener(ev.data);
+    const _listener = (ev: any) => {
+      listeners.delete(listener);
+      listener(ev.data);
+    };
     listeners.set(listener, _listener);
-    this.addEventListener(name, _listener);
+    this.addEventListener(name, _listener, { once: true });
     return this;
   };
 
@@ -178,27 +306,41 @@ if (!isMainThread) {
   parentPort.emit = () => notImplemented("parentPort.emit");
   parentPort.removeAllListeners = () =>
     notImplemented("parentPort.removeAllListeners");
+  defaultExport.parentPort = parentPort;
 
-  // Receive startup message
-  [{ threadId, workerData, environmentData }] = await once(
-    parentPort,
-    "message",
-  );
+  // The first message is always the startup message sent by the `Worker`
+  // constructor. The entrypoint module waits for it before loading user code.
+  internals.__nodeWorkerThreadReady = new Promise<void>((resolve) => {
+    // deno-lint-ignore no-explicit-any
+    const onStartup = (ev: any) => {
+      ev.stopImmediatePropagation();
+      self.removeEventListener("message", onStartup);
+      const data = ev.data;
+      threadId = data.threadId;
+      workerData = data.workerData;
+      environmentData = data.environmentData;
+      defaultExport.threadId = threadId;
+      defaultExport.workerData = workerData;
+      if (data.env !== undefined) {
+        process.env = data.env;
+      }
+      process.argv.push(...data.argv);
 
-  // alias
-  parentPort.addEventListener("offline", () => {
-    parentPort.emit("close");
+      process.on("exit", (code: number) => {
+        self.postMessage({ [PRIVATE_WORKER_MESSAGE_KEY]: ["exit", code] });
+      });
+      self.postMessage({ [PRIVATE_WORKER_MESSAGE_KEY]: ["online"] });
+      resolve();
+    };
+    self.addEventListener("message", onStartup);
   });
-}
-*/
+};
 
 export function getEnvironmentData(key: unknown) {
-  notImplemented("getEnvironmentData");
   return environmentData.get(key);
 }
 
 export function setEnvironmentData(key: unknown, value?: unknown) {
-  notImplemented("setEnvironmentData");
   if (value === undefined) {
     environmentData.delete(key);
   } else {
@@ -206,7 +348,6 @@ export function setEnvironmentData(key: unknown, value?: unknown) {
   }
 }
 
-export const SHARE_ENV = Symbol.for("nodejs.worker_threads.SHARE_ENV");
 export function markAsUntransferable() {
   notImplemented("markAsUntransferable");
 }
@@ -226,7 +367,7 @@ export {
   workerData,
 };
 
-export default {
+const defaultExport = {
   markAsUntransferable,
   moveMessagePortToContext,
   receiveMessageOnPort,
@@ -243,3 +384,5 @@ export default {
   parentPort,
   isMainThread,
 };
+
+export default defaultExport;
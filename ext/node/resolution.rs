@@ -111,11 +111,23 @@ pub type NodeResolverRc = deno_fs::sync::MaybeArc<NodeResolver>;
 pub struct NodeResolver {
   fs: FileSystemRc,
   npm_resolver: NpmResolverRc,
+  /// Custom conditions that are matched in addition to the default ones
+  /// when resolving the `exports` and `imports` of packages (ex.
+  /// `react-server`).
+  conditions: Vec<String>,
 }
 
 impl NodeResolver {
-  pub fn new(fs: FileSystemRc, npm_resolver: NpmResolverRc) -> Self {
-    Self { fs, npm_resolver }
+  pub fn new(
+    fs: FileSystemRc,
+    npm_resolver: NpmResolverRc,
+    conditions: Vec<String>,
+  ) -> Self {
+    Self {
+      fs,
+      npm_resolver,
+      conditions,
+    }
   }
 
   pub fn in_npm_package(&self, specifier: &ModuleSpecifier) -> bool {
@@ -826,6 +838,7 @@ impl NodeResolver {
 
         if key == "default"
           || conditions.contains(&key.as_str())
+          || self.conditions.contains(key)
           || mode.is_types() && key.as_str() == "types"
         {
           let condition_target = target_obj.get(key).unwrap().to_owned();
@@ -136,6 +136,9 @@ impl ServerCertVerifier for NoCertificateVerification {
 pub struct Proxy {
   pub url: String,
   pub basic_auth: Option<BasicAuth>,
+  /// Hosts that are connected to directly instead of through the proxy, in
+  /// the format of the `NO_PROXY` environment variable.
+  pub no_proxy: Vec<String>,
 }
 
 #[derive(Deserialize, Default, Debug, Clone)]
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::OpState;
@@ -18,11 +19,10 @@ use std::cell::RefCell;
 use std::io::Write;
 use std::rc::Rc;
 
-#[derive(Debug)]
 struct CompressionResource(RefCell<Inner>);
 
-/// https://wicg.github.io/compression/#supported-formats
-#[derive(Debug)]
+/// https://wicg.github.io/compression/#supported-formats, as well as the
+/// non-standard "brotli" and "zstd" formats.
 enum Inner {
   DeflateDecoder(ZlibDecoder<Vec<u8>>),
   DeflateEncoder(ZlibEncoder<Vec<u8>>),
@@ -30,6 +30,10 @@ enum Inner {
   DeflateRawEncoder(DeflateEncoder<Vec<u8>>),
   GzDecoder(GzDecoder<Vec<u8>>),
   GzEncoder(GzEncoder<Vec<u8>>),
+  BrotliDecoder(Box<brotli::DecompressorWriter<Vec<u8>>>),
+  BrotliEncoder(Box<brotli::CompressorWriter<Vec<u8>>>),
+  ZstdDecoder(zstd::stream::write::Decoder<'static, Vec<u8>>),
+  ZstdEncoder(zstd::stream::write::Encoder<'static, Vec<u8>>),
 }
 
 impl Resource for CompressionResource {
@@ -43,7 +47,7 @@ pub fn op_compression_new(
   state: &mut OpState,
   format: &str,
   is_decoder: bool,
-) -> ResourceId {
+) -> Result<ResourceId, AnyError> {
   let w = Vec::new();
   let inner = match (format, is_decoder) {
     ("deflate", true) => Inner::DeflateDecoder(ZlibDecoder::new(w)),
@@ -58,10 +62,25 @@ pub fn op_compression_new(
     ("gzip", false) => {
       Inner::GzEncoder(GzEncoder::new(w, Compression::default()))
     }
+    ("brotli", true) => {
+      Inner::BrotliDecoder(Box::new(brotli::DecompressorWriter::new(w, 4096)))
+    }
+    ("brotli", false) => {
+      // Quality 6 is the default of google's nginx module for on-the-fly
+      // compression, with a window of (2**22)-16 bytes (~4MB).
+      Inner::BrotliEncoder(Box::new(brotli::CompressorWriter::new(
+        w, 4096, 6, 22,
+      )))
+    }
+    ("zstd", true) => Inner::ZstdDecoder(zstd::stream::write::Decoder::new(w)?),
+    ("zstd", false) => Inner::ZstdEncoder(zstd::stream::write::Encoder::new(
+      w,
+      zstd::DEFAULT_COMPRESSION_LEVEL,
+    )?),
     _ => unreachable!(),
   };
   let resource = CompressionResource(RefCell::new(inner));
-  state.resource_table.add(resource)
+  Ok(state.resource_table.add(resource))
 }
 
 #[op]
@@ -103,6 +122,26 @@ pub fn op_compression_write(
       d.flush()?;
       d.get_mut().drain(..)
     }
+    Inner::BrotliDecoder(d) => {
+      d.write_all(input)?;
+      d.flush()?;
+      d.get_mut().drain(..)
+    }
+    Inner::BrotliEncoder(d) => {
+      d.write_all(input)?;
+      d.flush()?;
+      d.get_mut().drain(..)
+    }
+    Inner::ZstdDecoder(d) => {
+      d.write_all(input)?;
+      d.flush()?;
+      d.get_mut().drain(..)
+    }
+    Inner::ZstdEncoder(d) => {
+      d.write_all(input)?;
+      d.flush()?;
+      d.get_mut().drain(..)
+    }
   }
   .collect();
   Ok(out.into())
@@ -114,7 +153,7 @@ pub fn op_compression_finish(
   rid: ResourceId,
 ) -> Result<ZeroCopyBuf, AnyError> {
   let resource = state.resource_table.take::<CompressionResource>(rid)?;
-  let resource = Rc::try_unwrap(resource).unwrap();
+  let resource = Rc::try_unwrap(resource).ok().unwrap();
   let inner = resource.0.into_inner();
   let out: Vec<u8> = match inner {
     Inner::DeflateDecoder(d) => d.finish()?,
@@ -123,6 +162,15 @@ pub fn op_compression_finish(
     Inner::DeflateRawEncoder(d) => d.finish()?,
     Inner::GzDecoder(d) => d.finish()?,
     Inner::GzEncoder(d) => d.finish()?,
+    Inner::BrotliDecoder(d) => d.into_inner().map_err(|_| {
+      type_error("The compressed data ended before the end of the stream")
+    })?,
+    Inner::BrotliEncoder(d) => d.into_inner(),
+    Inner::ZstdDecoder(mut d) => {
+      d.flush()?;
+      d.into_inner()
+    }
+    Inner::ZstdEncoder(d) => d.finish()?,
   };
   Ok(out.into())
 }
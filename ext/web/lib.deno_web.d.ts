@@ -979,6 +979,9 @@ declare class CompressionStream {
    * Creates a new `CompressionStream` object which compresses a stream of
    * data.
    *
+   * The supported formats are `"deflate"`, `"deflate-raw"` and `"gzip"`, as
+   * well as the non-standard `"brotli"` and `"zstd"` formats.
+   *
    * Throws a `TypeError` if the format passed to the constructor is not
    * supported.
    */
@@ -1008,6 +1011,9 @@ declare class DecompressionStream {
    * Creates a new `DecompressionStream` object which decompresses a stream of
    * data.
    *
+   * The supported formats are `"deflate"`, `"deflate-raw"` and `"gzip"`, as
+   * well as the non-standard `"brotli"` and `"zstd"` formats.
+   *
    * Throws a `TypeError` if the format passed to the constructor is not
    * supported.
    */
@@ -23,12 +23,10 @@ const primordials = globalThis.__bootstrap.primordials;
 const {
   ArrayBufferPrototype,
   ArrayBufferIsView,
-  ArrayBufferPrototypeGetByteLength,
   ArrayPrototypeJoin,
   ArrayPrototypeMap,
   ArrayPrototypeSome,
   DataView,
-  DataViewPrototypeGetByteLength,
   ErrorPrototypeToString,
   ObjectDefineProperties,
   ObjectPrototypeIsPrototypeOf,
@@ -46,22 +44,21 @@ const {
   PromisePrototypeCatch,
   SymbolFor,
   TypedArrayPrototypeGetByteLength,
-  TypedArrayPrototypeGetSymbolToStringTag,
 } = primordials;
-const op_ws_check_permission_and_cancel_handle =
-  core.ops.op_ws_check_permission_and_cancel_handle;
 const {
-  op_ws_create,
-  op_ws_close,
+  op_ws_check_permission_and_cancel_handle,
   op_ws_send_binary,
   op_ws_send_text,
+  op_ws_get_buffered_amount,
+} = core.ops;
+const {
+  op_ws_create,
+  op_ws_close,
   op_ws_next_event,
   op_ws_send_ping,
 } = core.generateAsyncOpHandler(
   "op_ws_create",
   "op_ws_close",
-  "op_ws_send_binary",
-  "op_ws_send_text",
   "op_ws_next_event",
   "op_ws_send_ping",
 );
@@ -118,7 +115,6 @@ const _role = Symbol("[[role]]");
 const _extensions = Symbol("[[extensions]]");
 const _protocol = Symbol("[[protocol]]");
 const _binaryType = Symbol("[[binaryType]]");
-const _bufferedAmount = Symbol("[[bufferedAmount]]");
 const _eventLoop = Symbol("[[eventLoop]]");
 
 const _server = Symbol("[[server]]");
@@ -186,10 +182,13 @@ class WebSocket extends EventTarget {
     }
   }
 
-  [_bufferedAmount] = 0;
   get bufferedAmount() {
     webidl.assertBranded(this, WebSocketPrototype);
-    return this[_bufferedAmount];
+    if (this[_readyState] === OPEN) {
+      return op_ws_get_buffered_amount(this[_rid]);
+    } else {
+      return 0;
+    }
   }
 
   constructor(url, protocols = []) {
@@ -324,55 +323,18 @@ class WebSocket extends EventTarget {
       throw new DOMException("readyState not OPEN", "InvalidStateError");
     }
 
-    /**
-     * @param {ArrayBufferView} view
-     * @param {number} byteLength
-     */
-    const sendTypedArray = (view, byteLength) => {
-      this[_bufferedAmount] += byteLength;
-      PromisePrototypeThen(
-        op_ws_send_binary(
-          this[_rid],
-          view,
-        ),
-        () => {
-          this[_bufferedAmount] -= byteLength;
-        },
-      );
-    };
-
     if (ObjectPrototypeIsPrototypeOf(BlobPrototype, data)) {
       PromisePrototypeThen(
         data.slice().arrayBuffer(),
-        (ab) =>
-          sendTypedArray(
-            new DataView(ab),
-            ArrayBufferPrototypeGetByteLength(ab),
-          ),
+        (ab) => op_ws_send_binary(this[_rid], new DataView(ab)),
       );
-    } else if (ArrayBufferIsView(data)) {
-      if (TypedArrayPrototypeGetSymbolToStringTag(data) === undefined) {
-        // DataView
-        sendTypedArray(data, DataViewPrototypeGetByteLength(data));
-      } else {
-        // TypedArray
-        sendTypedArray(data, TypedArrayPrototypeGetByteLength(data));
-      }
-    } else if (ObjectPrototypeIsPrototypeOf(ArrayBufferPrototype, data)) {
-      sendTypedArray(data, ArrayBufferPrototypeGetByteLength(data));
+    } else if (
+      ArrayBufferIsView(data) ||
+      ObjectPrototypeIsPrototypeOf(ArrayBufferPrototype, data)
+    ) {
+      op_ws_send_binary(this[_rid], data);
     } else {
-      const string = String(data);
-      const d = core.encode(string);
-      this[_bufferedAmount] += TypedArrayPrototypeGetByteLength(d);
-      PromisePrototypeThen(
-        op_ws_send_text(
-          this[_rid],
-          string,
-        ),
-        () => {
-          this[_bufferedAmount] -= TypedArrayPrototypeGetByteLength(d);
-        },
-      );
+      op_ws_send_text(this[_rid], String(data));
     }
   }
 
@@ -6,6 +6,7 @@ use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::url;
+use deno_core::AsyncMutFuture;
 use deno_core::AsyncRefCell;
 use deno_core::ByteString;
 use deno_core::CancelFuture;
@@ -34,6 +35,7 @@ use std::cell::RefCell;
 use std::convert::TryFrom;
 use std::fmt;
 use std::future::Future;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -69,11 +71,21 @@ impl WsRootStoreProvider {
 pub struct WsUserAgent(pub String);
 
 pub trait WebSocketPermissions {
+  /// Checks the host of a URL before resolving it. The address it resolves to
+  /// must be checked with `check_net_resolved` before connecting.
   fn check_net_url(
     &mut self,
     _url: &url::Url,
     _api_name: &str,
   ) -> Result<(), AnyError>;
+  /// Checks the host of a URL right before connecting to an address it
+  /// resolved to.
+  fn check_net_resolved(
+    &mut self,
+    _url: &url::Url,
+    _addr: &SocketAddr,
+    _api_name: &str,
+  ) -> Result<(), AnyError>;
 }
 
 /// `UnsafelyIgnoreCertificateErrors` is a wrapper struct so it can be placed inside `GothamState`;
@@ -255,8 +267,18 @@ where
     Some("ws") => 80,
     _ => unreachable!(),
   });
-  let addr = format!("{domain}:{port}");
-  let tcp_socket = TcpStream::connect(addr).await?;
+  let addrs = tokio::net::lookup_host(format!("{domain}:{port}"))
+    .await?
+    .collect::<Vec<_>>();
+  {
+    let mut s = state.borrow_mut();
+    let permissions = s.borrow_mut::<WP>();
+    let url = url::Url::parse(&url)?;
+    for addr in &addrs {
+      permissions.check_net_resolved(&url, addr, &api_name)?;
+    }
+  }
+  let tcp_socket = TcpStream::connect(&*addrs).await?;
 
   let (stream, response) = match uri.scheme_str() {
     Some("ws") => handshake(cancel_resource, request, tcp_socket).await?,
@@ -280,10 +302,7 @@ where
     state.borrow_mut().resource_table.close(cancel_rid).ok();
   }
 
-  let resource = ServerWebSocket {
-    ws: AsyncRefCell::new(FragmentCollector::new(stream)),
-    closed: Rc::new(Cell::new(false)),
-  };
+  let resource = ServerWebSocket::new(stream);
   let mut state = state.borrow_mut();
   let rid = state.resource_table.add(resource);
 
@@ -314,25 +333,67 @@ pub enum MessageKind {
 }
 
 pub struct ServerWebSocket {
+  buffered: Cell<usize>,
+  error: Cell<Option<String>>,
   ws: AsyncRefCell<FragmentCollector<WebSocketStream>>,
+  /// Queues writes, so that frames are written in the order they were sent.
+  write_lock: AsyncRefCell<()>,
   closed: Rc<Cell<bool>>,
 }
 
 impl ServerWebSocket {
+  fn new(ws: WebSocket<WebSocketStream>) -> Self {
+    Self {
+      buffered: Cell::new(0),
+      error: Cell::new(None),
+      ws: AsyncRefCell::new(FragmentCollector::new(ws)),
+      write_lock: AsyncRefCell::new(()),
+      closed: Rc::new(Cell::new(false)),
+    }
+  }
+
+  /// Reserves a place in the write queue. The write has to be performed with
+  /// `write_frame` once the returned future resolves.
+  #[inline]
+  pub fn reserve_lock(self: &Rc<Self>) -> AsyncMutFuture<()> {
+    RcRef::map(self, |r| &r.write_lock).borrow_mut()
+  }
+
   #[inline]
   pub async fn write_frame(
-    self: Rc<Self>,
+    self: &Rc<Self>,
+    lock: AsyncMutFuture<()>,
     frame: Frame,
   ) -> Result<(), AnyError> {
+    let _lock = lock.await;
     // SAFETY: fastwebsockets only needs a mutable reference to the WebSocket
     // to populate the write buffer. We encounter an await point when writing
     // to the socket after the frame has already been written to the buffer.
+    // Other writes are queued behind `write_lock`.
     let ws = unsafe { &mut *self.ws.as_ptr() };
     ws.write_frame(frame)
       .await
       .map_err(|err| type_error(err.to_string()))?;
     Ok(())
   }
+
+  /// Writes a data frame in the background, keeping track of the amount of
+  /// buffered data until it was written. A failed write is reported as an
+  /// error event by `op_ws_next_event`.
+  fn send(self: Rc<Self>, opcode: OpCode, data: Vec<u8>) {
+    let len = data.len();
+    self.buffered.set(self.buffered.get() + len);
+    let lock = self.reserve_lock();
+    deno_core::task::spawn(async move {
+      match self
+        .write_frame(lock, Frame::new(true, opcode, None, data))
+        .await
+      {
+        Ok(()) => self.buffered.set(self.buffered.get() - len),
+        Err(err) => self.error.set(Some(err.to_string())),
+      }
+    });
+  }
 }
 
 impl Resource for ServerWebSocket {
@@ -357,17 +418,38 @@ pub fn ws_create_server_stream(
   ws.set_auto_close(true);
   ws.set_auto_pong(true);
 
-  let ws_resource = ServerWebSocket {
-    ws: AsyncRefCell::new(FragmentCollector::new(ws)),
-    closed: Rc::new(Cell::new(false)),
-  };
+  let ws_resource = ServerWebSocket::new(ws);
 
   let rid = state.resource_table.add(ws_resource);
   Ok(rid)
 }
 
 #[op]
-pub async fn op_ws_send_binary(
+pub fn op_ws_send_binary(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: ZeroCopyBuf,
+) -> Result<(), AnyError> {
+  let resource = state.resource_table.get::<ServerWebSocket>(rid)?;
+  resource.send(OpCode::Binary, data.to_vec());
+  Ok(())
+}
+
+#[op]
+pub fn op_ws_send_text(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: String,
+) -> Result<(), AnyError> {
+  let resource = state.resource_table.get::<ServerWebSocket>(rid)?;
+  resource.send(OpCode::Text, data.into_bytes());
+  Ok(())
+}
+
+/// Async version of `op_ws_send_binary`, which resolves once the frame was
+/// written. Used by `WebSocketStream`.
+#[op]
+pub async fn op_ws_send_binary_async(
   state: Rc<RefCell<OpState>>,
   rid: ResourceId,
   data: ZeroCopyBuf,
@@ -376,13 +458,16 @@ pub async fn op_ws_send_binary(
     .borrow_mut()
     .resource_table
     .get::<ServerWebSocket>(rid)?;
+  let lock = resource.reserve_lock();
   resource
-    .write_frame(Frame::new(true, OpCode::Binary, None, data.to_vec()))
+    .write_frame(lock, Frame::new(true, OpCode::Binary, None, data.to_vec()))
     .await
 }
 
+/// Async version of `op_ws_send_text`, which resolves once the frame was
+/// written. Used by `WebSocketStream`.
 #[op]
-pub async fn op_ws_send_text(
+pub async fn op_ws_send_text_async(
   state: Rc<RefCell<OpState>>,
   rid: ResourceId,
   data: String,
@@ -391,11 +476,24 @@ pub async fn op_ws_send_text(
     .borrow_mut()
     .resource_table
     .get::<ServerWebSocket>(rid)?;
+  let lock = resource.reserve_lock();
   resource
-    .write_frame(Frame::new(true, OpCode::Text, None, data.into_bytes()))
+    .write_frame(
+      lock,
+      Frame::new(true, OpCode::Text, None, data.into_bytes()),
+    )
     .await
 }
 
+#[op]
+pub fn op_ws_get_buffered_amount(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<u32, AnyError> {
+  let resource = state.resource_table.get::<ServerWebSocket>(rid)?;
+  Ok(resource.buffered.get() as u32)
+}
+
 #[op]
 pub async fn op_ws_send_pong(
   state: Rc<RefCell<OpState>>,
@@ -405,7 +503,8 @@ pub async fn op_ws_send_pong(
     .borrow_mut()
     .resource_table
     .get::<ServerWebSocket>(rid)?;
-  resource.write_frame(Frame::pong(vec![])).await
+  let lock = resource.reserve_lock();
+  resource.write_frame(lock, Frame::pong(vec![])).await
 }
 
 #[op]
@@ -417,8 +516,9 @@ pub async fn op_ws_send_ping(
     .borrow_mut()
     .resource_table
     .get::<ServerWebSocket>(rid)?;
+  let lock = resource.reserve_lock();
   resource
-    .write_frame(Frame::new(true, OpCode::Ping, None, vec![]))
+    .write_frame(lock, Frame::new(true, OpCode::Ping, None, vec![]))
     .await
 }
 
@@ -439,7 +539,8 @@ pub async fn op_ws_close(
 
   let cell = Rc::clone(&resource.closed);
   cell.set(true);
-  resource.write_frame(frame).await?;
+  let lock = resource.reserve_lock();
+  resource.write_frame(lock, frame).await?;
   Ok(())
 }
 
@@ -453,6 +554,10 @@ pub async fn op_ws_next_event(
     .resource_table
     .get::<ServerWebSocket>(rid)?;
 
+  if let Some(err) = resource.error.take() {
+    return Ok((MessageKind::Error as u16, StringOrBuffer::String(err)));
+  }
+
   let mut ws = RcRef::map(&resource, |r| &r.ws).borrow_mut().await;
   loop {
     let val = match ws.read_frame().await {
@@ -476,10 +581,13 @@ pub async fn op_ws_next_event(
     };
 
     break Ok(match val.opcode {
-      OpCode::Text => (
-        MessageKind::Text as u16,
-        StringOrBuffer::String(String::from_utf8(val.payload).unwrap()),
-      ),
+      OpCode::Text => match String::from_utf8(val.payload) {
+        Ok(text) => (MessageKind::Text as u16, StringOrBuffer::String(text)),
+        Err(_) => (
+          MessageKind::Error as u16,
+          StringOrBuffer::String("Invalid string data".to_string()),
+        ),
+      },
       OpCode::Binary => (
         MessageKind::Binary as u16,
         StringOrBuffer::Buffer(val.payload.into()),
@@ -491,7 +599,7 @@ pub async fn op_ws_next_event(
 
         let close_code =
           CloseCode::from(u16::from_be_bytes([val.payload[0], val.payload[1]]));
-        let reason = String::from_utf8(val.payload[2..].to_vec()).unwrap();
+        let reason = String::from_utf8_lossy(&val.payload[2..]).into_owned();
         (close_code.into(), StringOrBuffer::String(reason))
       }
       OpCode::Pong => (
@@ -514,7 +622,10 @@ deno_core::extension!(deno_websocket,
     op_ws_close,
     op_ws_next_event,
     op_ws_send_binary,
+    op_ws_send_binary_async,
     op_ws_send_text,
+    op_ws_send_text_async,
+    op_ws_get_buffered_amount,
     op_ws_send_ping,
     op_ws_send_pong,
   ],
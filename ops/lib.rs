@@ -191,6 +191,7 @@ impl Op {
         #[allow(clippy::not_unsafe_ptr_arg_deref)]
         pub extern "C" fn v8_fn_ptr #generics (info: *const #core::v8::FunctionCallbackInfo) #where_clause {
           let info = unsafe { &*info };
+          let _op_call = #core::_ops::OpCallGuard::new(info);
           let scope = &mut unsafe { #core::v8::CallbackScope::new(info) };
           let args = #core::v8::FunctionCallbackArguments::from_function_callback_info(info);
           let rv = #core::v8::ReturnValue::from_function_callback_info(info);
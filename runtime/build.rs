@@ -70,6 +70,18 @@ mod startup_snapshot {
       unreachable!("snapshotting!")
     }
 
+    fn check_net_url_unresolved(
+      &mut self,
+      _url: &deno_core::url::Url,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+
+    fn resolved_addr_check(&self) -> deno_fetch::ResolvedAddrCheck {
+      unreachable!("snapshotting!")
+    }
+
     fn check_read(
       &mut self,
       _p: &Path,
@@ -87,6 +99,15 @@ mod startup_snapshot {
     ) -> Result<(), deno_core::error::AnyError> {
       unreachable!("snapshotting!")
     }
+
+    fn check_net_resolved(
+      &mut self,
+      _url: &deno_core::url::Url,
+      _addr: &std::net::SocketAddr,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
   }
 
   impl deno_web::TimersPermission for Permissions {
@@ -136,6 +157,48 @@ mod startup_snapshot {
       unreachable!("snapshotting!")
     }
 
+    fn check_udp<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+
+    fn check_net_unresolved<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+
+    fn check_udp_unresolved<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+
+    fn check_net_resolved<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _addr: &std::net::SocketAddr,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+
+    fn check_udp_resolved<T: AsRef<str>>(
+      &mut self,
+      _host: &(T, Option<u16>),
+      _addr: &std::net::SocketAddr,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+
     fn check_read(
       &mut self,
       _p: &Path,
@@ -151,6 +214,14 @@ mod startup_snapshot {
     ) -> Result<(), deno_core::error::AnyError> {
       unreachable!("snapshotting!")
     }
+
+    fn check_unix(
+      &mut self,
+      _p: &Path,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
   }
 
   impl deno_fs::FsPermissions for Permissions {
@@ -246,6 +317,7 @@ mod startup_snapshot {
       "11_workers.js",
       "13_buffer.js",
       "30_os.js",
+      "40_cron.js",
       "40_fs_events.js",
       "40_http.js",
       "40_process.js",
@@ -326,7 +398,11 @@ mod startup_snapshot {
       runtime::init_ops_and_esm(),
       // FIXME(bartlomieju): these extensions are specified last, because they
       // depend on `runtime`, even though it should be other way around
-      deno_node::deno_node::init_ops_and_esm::<Permissions>(None, fs),
+      deno_node::deno_node::init_ops_and_esm::<Permissions>(
+        None,
+        fs,
+        Vec::new(),
+      ),
       runtime_main::init_ops_and_esm(),
     ];
 
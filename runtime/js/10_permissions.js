@@ -32,15 +32,17 @@ const illegalConstructorKey = Symbol("illegalConstructorKey");
  * @property {PermissionStatus} status
  */
 
-/** @type {ReadonlyArray<"read" | "write" | "net" | "env" | "sys" | "run" | "ffi" | "hrtime">} */
+/** @type {ReadonlyArray<"read" | "write" | "net" | "udp" | "env" | "sys" | "run" | "ffi" | "unix" | "hrtime">} */
 const permissionNames = [
   "read",
   "write",
   "net",
+  "udp",
   "env",
   "sys",
   "run",
   "ffi",
+  "unix",
   "hrtime",
 ];
 
@@ -123,11 +125,12 @@ const statusCache = new SafeMap();
 function cache(desc, state) {
   let { name: key } = desc;
   if (
-    (desc.name === "read" || desc.name === "write" || desc.name === "ffi") &&
+    (desc.name === "read" || desc.name === "write" || desc.name === "ffi" ||
+      desc.name === "unix") &&
     ReflectHas(desc, "path")
   ) {
     key += `-${desc.path}&`;
-  } else if (desc.name === "net" && desc.host) {
+  } else if ((desc.name === "net" || desc.name === "udp") && desc.host) {
     key += `-${desc.host}&`;
   } else if (desc.name === "run" && desc.command) {
     key += `-${desc.command}&`;
@@ -168,7 +171,8 @@ function isValidDescriptor(desc) {
  */
 function formDescriptor(desc) {
   if (
-    desc.name === "read" || desc.name === "write" || desc.name === "ffi"
+    desc.name === "read" || desc.name === "write" || desc.name === "ffi" ||
+    desc.name === "unix"
   ) {
     desc.path = pathFromURL(desc.path);
   } else if (desc.name === "run") {
@@ -254,7 +258,7 @@ function serializePermissions(permissions) {
   if (typeof permissions == "object" && permissions != null) {
     const serializedPermissions = {};
     for (
-      const key of new SafeArrayIterator(["read", "write", "run", "ffi"])
+      const key of new SafeArrayIterator(["read", "write", "run", "ffi", "unix"])
     ) {
       if (ArrayIsArray(permissions[key])) {
         serializedPermissions[key] = ArrayPrototypeMap(
@@ -266,7 +270,7 @@ function serializePermissions(permissions) {
       }
     }
     for (
-      const key of new SafeArrayIterator(["env", "hrtime", "net", "sys"])
+      const key of new SafeArrayIterator(["env", "hrtime", "net", "udp", "sys"])
     ) {
       if (ArrayIsArray(permissions[key])) {
         serializedPermissions[key] = ArrayPrototypeSlice(permissions[key]);
@@ -5,10 +5,13 @@ const ops = core.ops;
 const primordials = globalThis.__bootstrap.primordials;
 const {
   ArrayPrototypeFilter,
+  ArrayPrototypeMap,
   Error,
   ObjectPrototypeIsPrototypeOf,
   String,
   StringPrototypeStartsWith,
+  Symbol,
+  SymbolFor,
   SymbolIterator,
   SymbolToStringTag,
 } = primordials;
@@ -29,11 +32,19 @@ import {
   serializeJsMessageData,
 } from "ext:deno_web/13_message_port.js";
 
+const promiseIdSymbol = SymbolFor("Deno.core.internalPromiseId");
+
+// Used by the `node:worker_threads` polyfill to implement `Worker.ref()` and
+// `Worker.unref()`.
+const refWorker = Symbol("[[refWorker]]");
+const unrefWorker = Symbol("[[unrefWorker]]");
+
 function createWorker(
   specifier,
   hasSourceCode,
   sourceCode,
   permissions,
+  preload,
   name,
   workerType,
 ) {
@@ -41,6 +52,7 @@ function createWorker(
     hasSourceCode,
     name,
     permissions: serializePermissions(permissions),
+    preload,
     sourceCode,
     specifier,
     workerType,
@@ -63,6 +75,20 @@ function hostRecvMessage(id) {
   return core.opAsync("op_host_recv_message", id);
 }
 
+function resolveWorkerSpecifier(specifier, workerType) {
+  if (
+    StringPrototypeStartsWith(specifier, "./") ||
+    StringPrototypeStartsWith(specifier, "../") ||
+    StringPrototypeStartsWith(specifier, "/") || workerType === "classic"
+  ) {
+    const baseUrl = getLocationHref();
+    if (baseUrl != null) {
+      return new URL(specifier, baseUrl).href;
+    }
+  }
+  return specifier;
+}
+
 class Worker extends EventTarget {
   #id = 0;
   #name = "";
@@ -74,6 +100,11 @@ class Worker extends EventTarget {
   // still be messages left to receive.
   #status = "RUNNING";
 
+  // When unrefed, pending receive ops don't keep the host's event loop alive.
+  #refed = true;
+  #controlPromiseId = null;
+  #messagePromiseId = null;
+
   constructor(specifier, options = {}) {
     super();
     specifier = String(specifier);
@@ -85,15 +116,17 @@ class Worker extends EventTarget {
 
     const workerType = webidl.converters["WorkerType"](type);
 
-    if (
-      StringPrototypeStartsWith(specifier, "./") ||
-      StringPrototypeStartsWith(specifier, "../") ||
-      StringPrototypeStartsWith(specifier, "/") || workerType === "classic"
-    ) {
-      const baseUrl = getLocationHref();
-      if (baseUrl != null) {
-        specifier = new URL(specifier, baseUrl).href;
-      }
+    specifier = resolveWorkerSpecifier(specifier, workerType);
+    let preload = [];
+    if (deno?.preload !== undefined) {
+      preload = ArrayPrototypeMap(
+        webidl.converters["sequence<USVString>"](
+          deno.preload,
+          "Failed to construct 'Worker'",
+          "Argument 2.deno.preload",
+        ),
+        (specifier) => resolveWorkerSpecifier(specifier, "module"),
+      );
     }
 
     this.#name = name;
@@ -111,6 +144,7 @@ class Worker extends EventTarget {
       hasSourceCode,
       sourceCode,
       deno?.permissions,
+      preload,
       name,
       workerType,
     );
@@ -142,7 +176,11 @@ class Worker extends EventTarget {
 
   #pollControl = async () => {
     while (this.#status === "RUNNING") {
-      const { 0: type, 1: data } = await hostRecvCtrl(this.#id);
+      const promise = hostRecvCtrl(this.#id);
+      this.#controlPromiseId = promise[promiseIdSymbol];
+      if (!this.#refed) core.unrefOp(this.#controlPromiseId);
+      const { 0: type, 1: data } = await promise;
+      this.#controlPromiseId = null;
 
       // If terminate was called then we ignore all messages
       if (this.#status === "TERMINATED") {
@@ -173,7 +211,11 @@ class Worker extends EventTarget {
 
   #pollMessages = async () => {
     while (this.#status !== "TERMINATED") {
-      const data = await hostRecvMessage(this.#id);
+      const promise = hostRecvMessage(this.#id);
+      this.#messagePromiseId = promise[promiseIdSymbol];
+      if (!this.#refed) core.unrefOp(this.#messagePromiseId);
+      const data = await promise;
+      this.#messagePromiseId = null;
       if (this.#status === "TERMINATED" || data === null) {
         return;
       }
@@ -239,6 +281,26 @@ class Worker extends EventTarget {
     }
   }
 
+  [refWorker]() {
+    this.#refed = true;
+    if (typeof this.#controlPromiseId === "number") {
+      core.refOp(this.#controlPromiseId);
+    }
+    if (typeof this.#messagePromiseId === "number") {
+      core.refOp(this.#messagePromiseId);
+    }
+  }
+
+  [unrefWorker]() {
+    this.#refed = false;
+    if (typeof this.#controlPromiseId === "number") {
+      core.unrefOp(this.#controlPromiseId);
+    }
+    if (typeof this.#messagePromiseId === "number") {
+      core.unrefOp(this.#messagePromiseId);
+    }
+  }
+
   [SymbolToStringTag] = "Worker";
 }
 
@@ -251,4 +313,4 @@ webidl.converters["WorkerType"] = webidl.createEnumConverter("WorkerType", [
   "module",
 ]);
 
-export { Worker };
+export { refWorker, unrefWorker, Worker };
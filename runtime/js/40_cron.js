@@ -0,0 +1,227 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+const primordials = globalThis.__bootstrap.primordials;
+const {
+  Array,
+  ArrayPrototypeFill,
+  ArrayPrototypeMap,
+  Date,
+  DateNow,
+  DatePrototypeGetUTCDate,
+  DatePrototypeGetUTCDay,
+  DatePrototypeGetUTCFullYear,
+  DatePrototypeGetUTCHours,
+  DatePrototypeGetUTCMinutes,
+  DatePrototypeGetUTCMonth,
+  DateUTC,
+  MathMin,
+  NumberParseInt,
+  Promise,
+  RegExpPrototypeExec,
+  RegExpPrototypeTest,
+  SafeArrayIterator,
+  SafeSet,
+  SetPrototypeAdd,
+  SetPrototypeDelete,
+  SetPrototypeHas,
+  StringPrototypeSplit,
+  StringPrototypeStartsWith,
+  StringPrototypeTrim,
+  TypeError,
+} = primordials;
+import { clearTimeout, setTimeout } from "ext:deno_web/02_timers.js";
+
+const MINUTE = 60 * 1000;
+// Timers can't be longer than 2^31 - 1 milliseconds.
+const MAX_TIMEOUT = 2 ** 31 - 1;
+// A schedule that doesn't run in 5 years, which covers leap days, never
+// runs.
+const MAX_SEARCH = 5 * 366 * 24 * 60 * MINUTE;
+
+const FIELDS = [
+  { name: "minute", min: 0, max: 59 },
+  { name: "hour", min: 0, max: 23 },
+  { name: "day of month", min: 1, max: 31 },
+  { name: "month", min: 1, max: 12 },
+  { name: "day of week", min: 0, max: 7 },
+];
+
+const RANGE_REGEX = /^(\*|(\d+)(?:-(\d+))?)(?:\/(\d+))?$/;
+const NAME_REGEX = /^[a-zA-Z0-9_ -]+$/;
+
+/** Parses a field of a cron schedule into an array of the values that
+ * match. */
+function parseField(text, field) {
+  const matches = ArrayPrototypeFill(new Array(field.max + 1), false);
+  for (const part of new SafeArrayIterator(StringPrototypeSplit(text, ","))) {
+    const match = RegExpPrototypeExec(RANGE_REGEX, part);
+    if (match === null) {
+      throw new TypeError(
+        `Invalid cron schedule: invalid ${field.name} "${part}"`,
+      );
+    }
+    let start = field.min;
+    let end = field.max;
+    if (match[1] !== "*") {
+      start = NumberParseInt(match[2], 10);
+      // `n/step` means from `n` to the end.
+      end = match[3] !== undefined
+        ? NumberParseInt(match[3], 10)
+        : match[4] !== undefined
+        ? field.max
+        : start;
+    }
+    const step = match[4] !== undefined ? NumberParseInt(match[4], 10) : 1;
+    if (start < field.min || end > field.max || start > end || step === 0) {
+      throw new TypeError(
+        `Invalid cron schedule: invalid ${field.name} "${part}"`,
+      );
+    }
+    for (let value = start; value <= end; value += step) {
+      matches[value] = true;
+    }
+  }
+  return matches;
+}
+
+/** Parses a cron schedule with the five standard fields, which are evaluated
+ * in UTC. */
+function parseSchedule(schedule) {
+  const texts = StringPrototypeSplit(StringPrototypeTrim(schedule), /\s+/);
+  if (texts.length !== FIELDS.length) {
+    throw new TypeError(
+      `Invalid cron schedule: expected 5 fields, got ${texts.length}`,
+    );
+  }
+  const { 0: minutes, 1: hours, 2: daysOfMonth, 3: months, 4: daysOfWeek } =
+    ArrayPrototypeMap(texts, (text, i) => parseField(text, FIELDS[i]));
+  // Sunday can be both 0 and 7.
+  daysOfWeek[0] ||= daysOfWeek[7];
+  return {
+    minutes,
+    hours,
+    daysOfMonth,
+    months,
+    daysOfWeek,
+    // Like in other cron implementations, a day matches either field if both
+    // are restricted.
+    anyDay: StringPrototypeStartsWith(texts[2], "*") ||
+      StringPrototypeStartsWith(texts[4], "*"),
+  };
+}
+
+function matchesDay(schedule, date) {
+  const dayOfMonth = schedule.daysOfMonth[DatePrototypeGetUTCDate(date)];
+  const dayOfWeek = schedule.daysOfWeek[DatePrototypeGetUTCDay(date)];
+  return schedule.anyDay
+    ? dayOfMonth && dayOfWeek
+    : dayOfMonth || dayOfWeek;
+}
+
+/** Returns the next time after `after` the schedule runs at, or `null` if it
+ * never runs. */
+function nextRun(schedule, after) {
+  let time = after - after % MINUTE + MINUTE;
+  while (time <= after + MAX_SEARCH) {
+    const date = new Date(time);
+    const year = DatePrototypeGetUTCFullYear(date);
+    const month = DatePrototypeGetUTCMonth(date);
+    const day = DatePrototypeGetUTCDate(date);
+    const hour = DatePrototypeGetUTCHours(date);
+    if (!schedule.months[month + 1]) {
+      time = DateUTC(year, month + 1, 1);
+    } else if (!matchesDay(schedule, date)) {
+      time = DateUTC(year, month, day + 1);
+    } else if (!schedule.hours[hour]) {
+      time = DateUTC(year, month, day, hour + 1);
+    } else if (!schedule.minutes[DatePrototypeGetUTCMinutes(date)]) {
+      time += MINUTE;
+    } else {
+      return time;
+    }
+  }
+  return null;
+}
+
+const cronNames = new SafeSet();
+
+function cron(name, schedule, handlerOrOptions1, handlerOrOptions2) {
+  if (name === undefined) {
+    throw new TypeError("Deno.cron requires a unique name");
+  }
+  if (schedule === undefined) {
+    throw new TypeError("Deno.cron requires a valid schedule");
+  }
+  name = `${name}`;
+  if (name.length > 64 || !RegExpPrototypeTest(NAME_REGEX, name)) {
+    throw new TypeError(
+      "Invalid cron name: it must be at most 64 characters long and only contain alphanumeric characters, whitespace, hyphens and underscores",
+    );
+  }
+
+  let handler;
+  let options;
+  if (typeof handlerOrOptions1 === "function") {
+    handler = handlerOrOptions1;
+    options = handlerOrOptions2;
+  } else {
+    options = handlerOrOptions1;
+    handler = handlerOrOptions2;
+  }
+  if (typeof handler !== "function") {
+    throw new TypeError("Deno.cron requires a handler");
+  }
+  const signal = options?.signal;
+  signal?.throwIfAborted();
+
+  const parsedSchedule = parseSchedule(`${schedule}`);
+  if (nextRun(parsedSchedule, DateNow()) === null) {
+    throw new TypeError("Invalid cron schedule: it never runs");
+  }
+  if (SetPrototypeHas(cronNames, name)) {
+    throw new TypeError(`Cron with the name "${name}" already exists`);
+  }
+  SetPrototypeAdd(cronNames, name);
+
+  let timer;
+  let running = false;
+
+  const scheduleNext = () => {
+    const next = nextRun(parsedSchedule, DateNow());
+    timer = setTimeout(() => {
+      if (DateNow() < next) {
+        // Woke up early because of the maximum timer duration.
+        scheduleNext();
+        return;
+      }
+      scheduleNext();
+      run();
+    }, MathMin(next - DateNow(), MAX_TIMEOUT));
+  };
+
+  const run = async () => {
+    // A run is skipped if the previous one didn't finish yet.
+    if (running) {
+      return;
+    }
+    running = true;
+    try {
+      await handler();
+    } catch (error) {
+      globalThis.console.error(`Exception in cron handler "${name}"`, error);
+    } finally {
+      running = false;
+    }
+  };
+
+  return new Promise((resolve) => {
+    scheduleNext();
+    signal?.addEventListener("abort", () => {
+      clearTimeout(timer);
+      SetPrototypeDelete(cronNames, name);
+      resolve();
+    }, { once: true });
+  });
+}
+
+export { cron };
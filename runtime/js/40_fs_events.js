@@ -13,8 +13,13 @@ class FsWatcher {
   #rid = 0;
 
   constructor(paths, options) {
-    const { recursive } = options;
-    this.#rid = ops.op_fs_events_open({ recursive, paths });
+    const { recursive = true, exclude = [], debounce } = options;
+    this.#rid = ops.op_fs_events_open({
+      recursive,
+      paths,
+      exclude,
+      debounce: debounce ?? null,
+    });
   }
 
   get rid() {
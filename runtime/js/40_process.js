@@ -146,6 +146,16 @@ function run({
 const illegalConstructorKey = Symbol("illegalConstructorKey");
 const promiseIdSymbol = SymbolFor("Deno.core.internalPromiseId");
 
+function normalizePtySize(pty) {
+  if (pty === undefined || pty === false) {
+    return undefined;
+  }
+  if (pty === true) {
+    return { columns: 80, rows: 24 };
+  }
+  return { columns: pty.columns, rows: pty.rows };
+}
+
 function spawnChildInner(opFn, command, apiName, {
   args = [],
   cwd = undefined,
@@ -158,6 +168,10 @@ function spawnChildInner(opFn, command, apiName, {
   stderr = "piped",
   signal = undefined,
   windowsRawArguments = false,
+  detached = false,
+  processGroup = undefined,
+  pty = undefined,
+  permissions = undefined,
 } = {}) {
   const child = opFn({
     cmd: pathFromURL(command),
@@ -171,6 +185,10 @@ function spawnChildInner(opFn, command, apiName, {
     stdout,
     stderr,
     windowsRawArguments,
+    detached,
+    processGroup,
+    pty: normalizePtySize(pty),
+    permissions,
   }, apiName);
   return new ChildProcess(illegalConstructorKey, {
     ...child,
@@ -317,11 +335,18 @@ class ChildProcess {
     };
   }
 
-  kill(signo = "SIGTERM") {
+  kill(signo = "SIGTERM", { processGroup = false } = {}) {
+    if (this.#waitComplete) {
+      throw new TypeError("Child process has already terminated.");
+    }
+    ops.op_spawn_kill(this.#rid, signo, processGroup);
+  }
+
+  resizePty({ columns, rows }) {
     if (this.#waitComplete) {
       throw new TypeError("Child process has already terminated.");
     }
-    ops.op_spawn_kill(this.#rid, signo);
+    ops.op_spawn_resize_pty(this.#rid, columns, rows);
   }
 
   ref() {
@@ -365,6 +390,10 @@ function spawnSync(command, {
   stdout = "piped",
   stderr = "piped",
   windowsRawArguments = false,
+  detached = false,
+  processGroup = undefined,
+  pty = undefined,
+  permissions = undefined,
 } = {}) {
   if (stdin === "piped") {
     throw new TypeError(
@@ -383,6 +412,10 @@ function spawnSync(command, {
     stdout,
     stderr,
     windowsRawArguments,
+    detached,
+    processGroup,
+    pty: normalizePtySize(pty),
+    permissions,
   });
   return {
     success: result.status.success,
@@ -16,6 +16,7 @@ import * as io from "ext:deno_io/12_io.js";
 import * as buffer from "ext:runtime/13_buffer.js";
 import * as fs from "ext:deno_fs/30_fs.js";
 import * as os from "ext:runtime/30_os.js";
+import * as cron from "ext:runtime/40_cron.js";
 import * as fsEvents from "ext:runtime/40_fs_events.js";
 import * as process from "ext:runtime/40_process.js";
 import * as signals from "ext:runtime/40_signals.js";
@@ -109,6 +110,10 @@ const denoNs = {
   seekSync: fs.seekSync,
   connect: net.connect,
   listen: net.listen,
+  listenDatagram: net.createListenDatagram(
+    ops.op_net_listen_udp,
+    ops.op_net_listen_unixpacket,
+  ),
   loadavg: os.loadavg,
   connectTls: tls.connectTls,
   listenTls: tls.listenTls,
@@ -152,10 +157,6 @@ const denoNs = {
 };
 
 const denoNsUnstable = {
-  listenDatagram: net.createListenDatagram(
-    ops.op_net_listen_udp,
-    ops.op_net_listen_unixpacket,
-  ),
   umask: fs.umask,
   HttpClient: httpClient.HttpClient,
   createHttpClient: httpClient.createHttpClient,
@@ -176,6 +177,8 @@ const denoNsUnstable = {
   Kv: kv.Kv,
   KvU64: kv.KvU64,
   KvListIterator: kv.KvListIterator,
+  cron: cron.cron,
+  writeHeapSnapshot: (path) => ops.op_write_heap_snapshot(path),
 };
 
 export { denoNs, denoNsUnstable };
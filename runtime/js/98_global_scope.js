@@ -40,6 +40,11 @@ import * as globalInterfaces from "ext:deno_web/04_global_interfaces.js";
 import * as webStorage from "ext:deno_webstorage/01_webstorage.js";
 import * as prompt from "ext:runtime/41_prompt.js";
 
+// There are no cross-origin documents to isolate from, so the runtime is
+// isolated whenever `SharedArrayBuffer`s can be created and shared with
+// workers.
+const crossOriginIsolated = typeof globalThis.SharedArrayBuffer === "function";
+
 // https://developer.mozilla.org/en-US/docs/Web/API/WindowOrWorkerGlobalScope
 const windowOrWorkerGlobalScope = {
   AbortController: util.nonEnumerable(abortSignal.AbortController),
@@ -125,6 +130,7 @@ const windowOrWorkerGlobalScope = {
   console: util.nonEnumerable(
     new console.Console((msg, level) => core.print(msg, level > 1)),
   ),
+  crossOriginIsolated: util.getterOnly(() => crossOriginIsolated),
   crypto: util.readOnly(crypto.crypto),
   Crypto: util.nonEnumerable(crypto.Crypto),
   SubtleCrypto: util.nonEnumerable(crypto.SubtleCrypto),
@@ -29,6 +29,7 @@ pub mod inspector_server;
 pub mod js;
 pub mod ops;
 pub mod permissions;
+pub mod sandbox;
 pub mod tokio_util;
 pub mod web_worker;
 pub mod worker;
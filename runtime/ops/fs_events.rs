@@ -1,5 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::fs_util::resolve_from_cwd;
+use crate::permissions::path_matches_glob;
 use crate::permissions::PermissionsContainer;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
@@ -14,6 +16,8 @@ use deno_core::ResourceId;
 use deno_core::op;
 
 use notify::event::Event as NotifyEvent;
+use notify::event::ModifyKind;
+use notify::event::RenameMode;
 use notify::Error as NotifyError;
 use notify::EventKind;
 use notify::RecommendedWatcher;
@@ -23,9 +27,12 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::convert::From;
+use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 deno_core::extension!(
@@ -40,6 +47,9 @@ struct FsEventsResource {
   #[allow(unused)]
   watcher: RecommendedWatcher,
   receiver: AsyncRefCell<mpsc::Receiver<Result<FsEvent, AnyError>>>,
+  /// Events of the last debounced batch that haven't been polled yet.
+  pending: RefCell<VecDeque<FsEvent>>,
+  debounce: Option<Duration>,
   cancel: CancelHandle,
 }
 
@@ -61,7 +71,7 @@ impl Resource for FsEventsResource {
 ///
 /// Feel free to expand this struct as long as you can add tests to demonstrate
 /// the complexity.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, PartialEq)]
 struct FsEvent {
   kind: &'static str,
   paths: Vec<PathBuf>,
@@ -74,6 +84,9 @@ impl From<NotifyEvent> for FsEvent {
       EventKind::Any => "any",
       EventKind::Access(_) => "access",
       EventKind::Create(_) => "create",
+      // Only reported when the watcher could correlate both paths of the
+      // rename, which are then given as `[from, to]`.
+      EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => "rename",
       EventKind::Modify(_) => "modify",
       EventKind::Remove(_) => "remove",
       EventKind::Other => "other",
@@ -93,6 +106,24 @@ impl From<NotifyEvent> for FsEvent {
 pub struct OpenArgs {
   recursive: bool,
   paths: Vec<String>,
+  exclude: Vec<String>,
+  debounce: Option<u64>,
+}
+
+/// Removes the paths of `event` matched by `exclude`. Returns `None` if no
+/// paths are left.
+fn exclude_paths(exclude: &[PathBuf], mut event: FsEvent) -> Option<FsEvent> {
+  if exclude.is_empty() || event.paths.is_empty() {
+    return Some(event);
+  }
+  event
+    .paths
+    .retain(|path| !exclude.iter().any(|glob| path_matches_glob(glob, path)));
+  if event.paths.is_empty() {
+    None
+  } else {
+    Some(event)
+  }
 }
 
 #[op]
@@ -100,11 +131,22 @@ fn op_fs_events_open(
   state: &mut OpState,
   args: OpenArgs,
 ) -> Result<ResourceId, AnyError> {
+  let exclude = args
+    .exclude
+    .iter()
+    .map(|path| resolve_from_cwd(Path::new(path)))
+    .collect::<Result<Vec<_>, _>>()?;
   let (sender, receiver) = mpsc::channel::<Result<FsEvent, AnyError>>(16);
   let sender = Mutex::new(sender);
   let mut watcher: RecommendedWatcher = Watcher::new(
     move |res: Result<NotifyEvent, NotifyError>| {
-      let res2 = res.map(FsEvent::from).map_err(AnyError::from);
+      let res2 = match res {
+        Ok(event) => match exclude_paths(&exclude, FsEvent::from(event)) {
+          Some(event) => Ok(event),
+          None => return,
+        },
+        Err(err) => Err(AnyError::from(err)),
+      };
       let sender = sender.lock();
       // Ignore result, if send failed it means that watcher was already closed,
       // but not all messages have been flushed.
@@ -127,6 +169,8 @@ fn op_fs_events_open(
   let resource = FsEventsResource {
     watcher,
     receiver: AsyncRefCell::new(receiver),
+    pending: Default::default(),
+    debounce: args.debounce.map(Duration::from_millis),
     cancel: Default::default(),
   };
   let rid = state.resource_table.add(resource);
@@ -139,12 +183,43 @@ async fn op_fs_events_poll(
   rid: ResourceId,
 ) -> Result<Option<FsEvent>, AnyError> {
   let resource = state.borrow().resource_table.get::<FsEventsResource>(rid)?;
+  if let Some(event) = resource.pending.borrow_mut().pop_front() {
+    return Ok(Some(event));
+  }
   let mut receiver = RcRef::map(&resource, |r| &r.receiver).borrow_mut().await;
-  let cancel = RcRef::map(resource, |r| &r.cancel);
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
   let maybe_result = receiver.recv().or_cancel(cancel).await?;
-  match maybe_result {
-    Some(Ok(value)) => Ok(Some(value)),
-    Some(Err(err)) => Err(err),
-    None => Ok(None),
+  let event = match maybe_result {
+    Some(Ok(value)) => value,
+    Some(Err(err)) => return Err(err),
+    None => return Ok(None),
+  };
+  let debounce = match resource.debounce {
+    Some(debounce) => debounce,
+    None => return Ok(Some(event)),
+  };
+
+  // Collect events until none arrived for the debounce duration, dropping
+  // duplicates of events that are already part of the batch.
+  let mut batch = vec![event];
+  loop {
+    let cancel = RcRef::map(&resource, |r| &r.cancel);
+    let result = tokio::time::timeout(debounce, receiver.recv())
+      .or_cancel(cancel)
+      .await?;
+    match result {
+      Ok(Some(Ok(event))) => {
+        if !batch.contains(&event) {
+          batch.push(event);
+        }
+      }
+      Ok(Some(Err(err))) => return Err(err),
+      // The watcher was closed or the debounce duration elapsed.
+      Ok(None) | Err(_) => break,
+    }
   }
+  let mut batch = batch.into_iter();
+  let first = batch.next();
+  resource.pending.borrow_mut().extend(batch);
+  Ok(first)
 }
@@ -50,12 +50,20 @@ pub fn op_query_permission(
       }
       .as_ref(),
     ),
+    "udp" => permissions.udp.query(
+      match args.host.as_deref() {
+        None => None,
+        Some(h) => Some(parse_host(h)?),
+      }
+      .as_ref(),
+    ),
     "env" => permissions.env.query(args.variable.as_deref()),
     "sys" => permissions
       .sys
       .query(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.query(args.command.as_deref()),
     "ffi" => permissions.ffi.query(args.path.as_deref().map(Path::new)),
+    "unix" => permissions.unix.query(args.path.as_deref().map(Path::new)),
     "hrtime" => permissions.hrtime.query(),
     n => {
       return Err(custom_error(
@@ -84,12 +92,20 @@ pub fn op_revoke_permission(
       }
       .as_ref(),
     ),
+    "udp" => permissions.udp.revoke(
+      match args.host.as_deref() {
+        None => None,
+        Some(h) => Some(parse_host(h)?),
+      }
+      .as_ref(),
+    ),
     "env" => permissions.env.revoke(args.variable.as_deref()),
     "sys" => permissions
       .sys
       .revoke(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.revoke(args.command.as_deref()),
     "ffi" => permissions.ffi.revoke(args.path.as_deref().map(Path::new)),
+    "unix" => permissions.unix.revoke(args.path.as_deref().map(Path::new)),
     "hrtime" => permissions.hrtime.revoke(),
     n => {
       return Err(custom_error(
@@ -118,12 +134,22 @@ pub fn op_request_permission(
       }
       .as_ref(),
     ),
+    "udp" => permissions.udp.request(
+      match args.host.as_deref() {
+        None => None,
+        Some(h) => Some(parse_host(h)?),
+      }
+      .as_ref(),
+    ),
     "env" => permissions.env.request(args.variable.as_deref()),
     "sys" => permissions
       .sys
       .request(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.request(args.command.as_deref()),
     "ffi" => permissions.ffi.request(args.path.as_deref().map(Path::new)),
+    "unix" => permissions
+      .unix
+      .request(args.path.as_deref().map(Path::new)),
     "hrtime" => permissions.hrtime.request(),
     n => {
       return Err(custom_error(
@@ -1,13 +1,18 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use super::check_unstable;
+use crate::permissions::create_child_permissions;
+use crate::permissions::ChildPermissionsArg;
 use crate::permissions::PermissionsContainer;
+use crate::permissions::INHERITED_PERMISSIONS_ENV_VAR_NAME;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::serde_json;
+use deno_core::task::spawn_blocking;
 use deno_core::AsyncMutFuture;
 use deno_core::AsyncRefCell;
+use deno_core::AsyncResult;
 use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
@@ -28,6 +33,8 @@ use tokio::process::Command;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
 #[cfg(unix)]
 use std::os::unix::prelude::ExitStatusExt;
 #[cfg(unix)]
@@ -108,6 +115,7 @@ deno_core::extension!(
     op_spawn_wait,
     op_spawn_sync,
     op_spawn_kill,
+    op_spawn_resize_pty,
     deprecated::op_run,
     deprecated::op_run_status,
     deprecated::op_kill,
@@ -117,9 +125,114 @@ deno_core::extension!(
   },
 );
 
-/// Second member stores the pid separately from the RefCell. It's needed for
-/// `op_spawn_kill`, where the RefCell is borrowed mutably by `op_spawn_wait`.
-struct ChildResource(RefCell<tokio::process::Child>, u32);
+struct ChildResource {
+  child: RefCell<tokio::process::Child>,
+  /// The pid is stored separately from the RefCell. It's needed for
+  /// `op_spawn_kill`, where the RefCell is borrowed mutably by
+  /// `op_spawn_wait`.
+  pid: u32,
+  /// The process group the child belongs to, if it was spawned with
+  /// `detached`, `processGroup` or `pty`.
+  process_group: Option<ProcessGroup>,
+  /// The master side of the pseudo-terminal the child is attached to.
+  pty: Option<std::fs::File>,
+}
+
+/// The process group id of the child.
+#[cfg(unix)]
+struct ProcessGroup(i32);
+
+/// Windows doesn't allow signaling a process group other than with console
+/// control events, so the child is also assigned to a job object that allows
+/// terminating the whole group.
+#[cfg(windows)]
+struct ProcessGroup {
+  job: winapi::um::winnt::HANDLE,
+  /// The pid of the child, which is the id of its console process group.
+  id: u32,
+}
+
+#[cfg(windows)]
+impl Drop for ProcessGroup {
+  fn drop(&mut self) {
+    // SAFETY: winapi call, the handle is owned by this struct.
+    unsafe {
+      winapi::um::handleapi::CloseHandle(self.job);
+    }
+  }
+}
+
+impl ProcessGroup {
+  #[cfg(unix)]
+  fn new(
+    child: &tokio::process::Child,
+    args: &SpawnArgs,
+  ) -> Result<Option<Self>, AnyError> {
+    let pid = child.id().expect("Process ID should be set.") as i32;
+    Ok(match args.process_group {
+      // The child is the leader of a newly created group.
+      Some(0) => Some(ProcessGroup(pid)),
+      Some(pgid) => Some(ProcessGroup(pgid)),
+      // setsid() also creates a new process group.
+      None if args.detached || args.pty.is_some() => Some(ProcessGroup(pid)),
+      None => None,
+    })
+  }
+
+  #[cfg(windows)]
+  fn new(
+    child: &tokio::process::Child,
+    args: &SpawnArgs,
+  ) -> Result<Option<Self>, AnyError> {
+    use std::io::Error;
+    use winapi::um::jobapi2::AssignProcessToJobObject;
+    use winapi::um::jobapi2::CreateJobObjectW;
+
+    if !args.detached && args.process_group.is_none() {
+      return Ok(None);
+    }
+    let handle = child.raw_handle().expect("Process handle should be set.");
+    // SAFETY: winapi calls
+    unsafe {
+      let job = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
+      if job.is_null() {
+        return Err(Error::last_os_error().into());
+      }
+      let group = ProcessGroup {
+        job,
+        id: child.id().expect("Process ID should be set."),
+      };
+      if AssignProcessToJobObject(job, handle as _) == 0 {
+        return Err(Error::last_os_error().into());
+      }
+      Ok(Some(group))
+    }
+  }
+
+  #[cfg(unix)]
+  fn kill(&self, signal: &str) -> Result<(), AnyError> {
+    // A negative pid signals every process in the group.
+    deprecated::kill(-self.0, signal)
+  }
+
+  #[cfg(windows)]
+  fn kill(&self, signal: &str) -> Result<(), AnyError> {
+    use std::io::Error;
+    use winapi::um::jobapi2::TerminateJobObject;
+
+    if signal == "SIGBREAK" {
+      return deprecated::send_ctrl_break(self.id);
+    }
+    if !matches!(signal, "SIGKILL" | "SIGTERM") {
+      return Err(type_error(format!("Invalid signal: {signal}")));
+    }
+    // SAFETY: winapi call
+    if unsafe { TerminateJobObject(self.job, 1) } == 0 {
+      return Err(Error::last_os_error().into());
+    }
+    Ok(())
+  }
+}
 
 impl Resource for ChildResource {
   fn name(&self) -> Cow<str> {
@@ -141,11 +254,25 @@ pub struct SpawnArgs {
   uid: Option<u32>,
   #[cfg(windows)]
   windows_raw_arguments: bool,
+  #[serde(default)]
+  detached: bool,
+  #[serde(default)]
+  process_group: Option<i32>,
+  #[serde(default)]
+  pty: Option<PtySize>,
+  #[serde(default)]
+  permissions: Option<ChildPermissionsArg>,
 
   #[serde(flatten)]
   stdio: ChildStdio,
 }
 
+#[derive(Clone, Copy, Deserialize)]
+pub struct PtySize {
+  columns: u16,
+  rows: u16,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChildStdio {
@@ -207,14 +334,14 @@ pub struct SpawnOutput {
 
 fn create_command(
   state: &mut OpState,
-  args: SpawnArgs,
+  args: &SpawnArgs,
   api_name: &str,
 ) -> Result<std::process::Command, AnyError> {
   state
     .borrow_mut::<PermissionsContainer>()
-    .check_run(&args.cmd, api_name)?;
+    .check_run(&args.cmd, &args.args, api_name)?;
 
-  let mut command = std::process::Command::new(args.cmd);
+  let mut command = std::process::Command::new(&args.cmd);
 
   #[cfg(windows)]
   if args.windows_raw_arguments {
@@ -222,20 +349,28 @@ fn create_command(
       command.raw_arg(arg);
     }
   } else {
-    command.args(args.args);
+    command.args(&args.args);
   }
 
   #[cfg(not(windows))]
-  command.args(args.args);
+  command.args(&args.args);
 
-  if let Some(cwd) = args.cwd {
+  if let Some(cwd) = &args.cwd {
     command.current_dir(cwd);
   }
 
   if args.clear_env {
     command.env_clear();
   }
-  command.envs(args.env);
+  command.envs(args.env.iter().map(|(key, value)| (key, value)));
+  if args.permissions.is_some() {
+    check_unstable(state, "Deno.Command.permissions");
+  }
+  if let Some(permissions) =
+    inherited_permissions_for_child(state, args.permissions.clone())?
+  {
+    command.env(INHERITED_PERMISSIONS_ENV_VAR_NAME, permissions);
+  }
 
   #[cfg(unix)]
   if let Some(gid) = args.gid {
@@ -245,12 +380,55 @@ fn create_command(
   if let Some(uid) = args.uid {
     command.uid(uid);
   }
+
+  if args.detached && args.process_group.is_some() {
+    return Err(type_error(
+      "The 'detached' and 'processGroup' options can't be used together",
+    ));
+  }
+  if args.pty.is_some() && (args.detached || args.process_group.is_some()) {
+    return Err(type_error(
+      "The 'pty' option can't be used together with 'detached' or 'processGroup'",
+    ));
+  }
+  #[cfg(unix)]
+  if let Some(pgid) = args.process_group {
+    command.process_group(pgid);
+  }
+  #[cfg(windows)]
+  {
+    use winapi::um::winbase::CREATE_NEW_PROCESS_GROUP;
+    use winapi::um::winbase::DETACHED_PROCESS;
+
+    match args.process_group {
+      Some(0) => {
+        command.creation_flags(CREATE_NEW_PROCESS_GROUP);
+      }
+      Some(_) => {
+        return Err(type_error(
+          "Joining an existing process group is not supported on Windows",
+        ));
+      }
+      None if args.detached => {
+        command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+      }
+      None => {}
+    }
+  }
+
+  #[cfg(unix)]
+  let detached = args.detached;
   #[cfg(unix)]
   // TODO(bartlomieju):
   #[allow(clippy::undocumented_unsafe_blocks)]
   unsafe {
-    command.pre_exec(|| {
+    command.pre_exec(move || {
       libc::setgroups(0, std::ptr::null());
+      // Start a new session, so the child doesn't belong to the process
+      // group or the controlling terminal of this process.
+      if detached && libc::setsid() == -1 {
+        return Err(std::io::Error::last_os_error());
+      }
       Ok(())
     });
   }
@@ -268,6 +446,29 @@ fn create_command(
   Ok(command)
 }
 
+/// Serializes the narrowed set of permissions passed down to a `deno`
+/// subprocess. When the current process was itself started with inherited
+/// permissions, they are always passed on so that a subprocess can't shed
+/// them, eg. by clearing the environment.
+fn inherited_permissions_for_child(
+  state: &mut OpState,
+  permissions: Option<ChildPermissionsArg>,
+) -> Result<Option<String>, AnyError> {
+  let permissions = match permissions {
+    Some(permissions) => permissions,
+    None if std::env::var_os(INHERITED_PERMISSIONS_ENV_VAR_NAME).is_some() => {
+      ChildPermissionsArg::inherit()
+    }
+    None => return Ok(None),
+  };
+  let mut parent_permissions = state.borrow::<PermissionsContainer>().0.lock();
+  let child_permissions =
+    create_child_permissions(&mut parent_permissions, permissions)?;
+  Ok(Some(serde_json::to_string(
+    &child_permissions.to_options(),
+  )?))
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Child {
@@ -278,37 +479,208 @@ struct Child {
   stderr_rid: Option<ResourceId>,
 }
 
+/// Attaches the stdio of the command to a new pseudo-terminal, which becomes
+/// the controlling terminal of the child. Returns the master side.
+#[cfg(unix)]
+fn attach_pty(
+  command: &mut std::process::Command,
+  size: PtySize,
+) -> Result<std::fs::File, AnyError> {
+  use std::os::unix::io::FromRawFd;
+
+  let mut master = 0;
+  let mut slave = 0;
+  let mut winsize = libc::winsize {
+    ws_row: size.rows,
+    ws_col: size.columns,
+    ws_xpixel: 0,
+    ws_ypixel: 0,
+  };
+  // `openpty` takes a mutable pointer on some platforms
+  let winsize_ptr: *mut libc::winsize = &mut winsize;
+  // SAFETY: libc calls, the file descriptors are owned by the returned files.
+  let (master, slave) = unsafe {
+    if libc::openpty(
+      &mut master,
+      &mut slave,
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+      winsize_ptr,
+    ) != 0
+    {
+      return Err(std::io::Error::last_os_error().into());
+    }
+    let master = std::fs::File::from_raw_fd(master);
+    let slave = std::fs::File::from_raw_fd(slave);
+    for fd in [master.as_raw_fd(), slave.as_raw_fd()] {
+      if libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) == -1 {
+        return Err(std::io::Error::last_os_error().into());
+      }
+    }
+    (master, slave)
+  };
+
+  command.stdin(slave.try_clone()?);
+  command.stdout(slave.try_clone()?);
+  command.stderr(slave);
+  // SAFETY: only async-signal-safe functions are called.
+  unsafe {
+    command.pre_exec(|| {
+      // Start a new session that has the pseudo-terminal, which is stdin
+      // by now, as its controlling terminal.
+      if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+        return Err(std::io::Error::last_os_error());
+      }
+      Ok(())
+    });
+  }
+
+  Ok(master)
+}
+
+#[cfg(windows)]
+fn attach_pty(
+  _command: &mut std::process::Command,
+  _size: PtySize,
+) -> Result<std::fs::File, AnyError> {
+  Err(type_error("The 'pty' option is not supported on Windows"))
+}
+
+#[cfg(unix)]
+fn resize_pty(pty: &std::fs::File, size: PtySize) -> Result<(), AnyError> {
+  let winsize = libc::winsize {
+    ws_row: size.rows,
+    ws_col: size.columns,
+    ws_xpixel: 0,
+    ws_ypixel: 0,
+  };
+  let winsize_ptr: *const libc::winsize = &winsize;
+  // SAFETY: libc call, the child is sent a SIGWINCH signal.
+  if unsafe { libc::ioctl(pty.as_raw_fd(), libc::TIOCSWINSZ, winsize_ptr) }
+    == -1
+  {
+    return Err(std::io::Error::last_os_error().into());
+  }
+  Ok(())
+}
+
+#[cfg(windows)]
+fn resize_pty(_pty: &std::fs::File, _size: PtySize) -> Result<(), AnyError> {
+  unreachable!()
+}
+
+/// The reading end of the master side of a pseudo-terminal, it reads
+/// everything the child writes to stdout and stderr.
+struct PtyReaderResource(std::fs::File);
+
+impl PtyReaderResource {
+  async fn read(self: Rc<Self>, data: &mut [u8]) -> Result<usize, AnyError> {
+    use std::io::Read;
+
+    let mut file = self.0.try_clone()?;
+    let mut buf = vec![0; data.len()];
+    let (nread, buf) = spawn_blocking(move || match file.read(&mut buf) {
+      Ok(nread) => Ok((nread, buf)),
+      // Linux reports EIO once the child closed its side of the terminal.
+      #[cfg(unix)]
+      Err(err) if err.raw_os_error() == Some(libc::EIO) => Ok((0, buf)),
+      Err(err) => Err(err),
+    })
+    .await??;
+    data[..nread].copy_from_slice(&buf[..nread]);
+    Ok(nread)
+  }
+}
+
+impl Resource for PtyReaderResource {
+  deno_core::impl_readable_byob!();
+
+  fn name(&self) -> Cow<str> {
+    "ptyReader".into()
+  }
+}
+
+/// The writing end of the master side of a pseudo-terminal, the child reads
+/// what is written to it from stdin.
+struct PtyWriterResource(std::fs::File);
+
+impl PtyWriterResource {
+  async fn write(self: Rc<Self>, data: &[u8]) -> Result<usize, AnyError> {
+    use std::io::Write;
+
+    let mut file = self.0.try_clone()?;
+    let data = data.to_vec();
+    let nwritten = spawn_blocking(move || file.write(&data)).await??;
+    Ok(nwritten)
+  }
+}
+
+impl Resource for PtyWriterResource {
+  deno_core::impl_writable!();
+
+  fn name(&self) -> Cow<str> {
+    "ptyWriter".into()
+  }
+}
+
 fn spawn_child(
   state: &mut OpState,
   command: std::process::Command,
+  args: &SpawnArgs,
+  pty: Option<std::fs::File>,
 ) -> Result<Child, AnyError> {
   let mut command = tokio::process::Command::from(command);
-  // TODO(@crowlkats): allow detaching processes.
-  //  currently deno will orphan a process when exiting with an error or Deno.exit()
-  // We want to kill child when it's closed
-  command.kill_on_drop(true);
+  // We want to kill child when it's closed, unless it should outlive this
+  // process.
+  // TODO(@crowlkats): currently deno will orphan a process when exiting with
+  //  an error or Deno.exit()
+  command.kill_on_drop(!args.detached);
 
   let mut child = command.spawn()?;
   let pid = child.id().expect("Process ID should be set.");
+  let process_group = match ProcessGroup::new(&child, args) {
+    Ok(process_group) => process_group,
+    Err(err) => {
+      let _ = child.start_kill();
+      return Err(err);
+    }
+  };
 
-  let stdin_rid = child
-    .stdin
-    .take()
-    .map(|stdin| state.resource_table.add(ChildStdinResource::from(stdin)));
-
-  let stdout_rid = child
-    .stdout
-    .take()
-    .map(|stdout| state.resource_table.add(ChildStdoutResource::from(stdout)));
+  let (stdin_rid, stdout_rid) = match &pty {
+    Some(pty) => (
+      Some(
+        state
+          .resource_table
+          .add(PtyWriterResource(pty.try_clone()?)),
+      ),
+      Some(
+        state
+          .resource_table
+          .add(PtyReaderResource(pty.try_clone()?)),
+      ),
+    ),
+    None => (
+      child
+        .stdin
+        .take()
+        .map(|stdin| state.resource_table.add(ChildStdinResource::from(stdin))),
+      child.stdout.take().map(|stdout| {
+        state.resource_table.add(ChildStdoutResource::from(stdout))
+      }),
+    ),
+  };
 
   let stderr_rid = child
     .stderr
     .take()
     .map(|stderr| state.resource_table.add(ChildStderrResource::from(stderr)));
 
-  let child_rid = state
-    .resource_table
-    .add(ChildResource(RefCell::new(child), pid));
+  let child_rid = state.resource_table.add(ChildResource {
+    child: RefCell::new(child),
+    pid,
+    process_group,
+    pty,
+  });
 
   Ok(Child {
     rid: child_rid,
@@ -325,8 +697,15 @@ fn op_spawn_child(
   args: SpawnArgs,
   api_name: String,
 ) -> Result<Child, AnyError> {
-  let command = create_command(state, args, &api_name)?;
-  spawn_child(state, command)
+  let mut command = create_command(state, &args, &api_name)?;
+  let pty = match args.pty {
+    Some(size) => {
+      check_unstable(state, "Deno.Command.pty");
+      Some(attach_pty(&mut command, size)?)
+    }
+    None => None,
+  };
+  spawn_child(state, command, &args, pty)
 }
 
 #[op]
@@ -339,7 +718,7 @@ async fn op_spawn_wait(
     .borrow_mut()
     .resource_table
     .get::<ChildResource>(rid)?;
-  let result = resource.0.try_borrow_mut()?.wait().await?.try_into();
+  let result = resource.child.try_borrow_mut()?.wait().await?.try_into();
   state
     .borrow_mut()
     .resource_table
@@ -353,10 +732,15 @@ fn op_spawn_sync(
   state: &mut OpState,
   args: SpawnArgs,
 ) -> Result<SpawnOutput, AnyError> {
+  if args.pty.is_some() {
+    return Err(type_error(
+      "The 'pty' option is not supported in Deno.Command().outputSync()",
+    ));
+  }
   let stdout = matches!(args.stdio.stdout, Stdio::Piped);
   let stderr = matches!(args.stdio.stderr, Stdio::Piped);
   let output =
-    create_command(state, args, "Deno.Command().outputSync()")?.output()?;
+    create_command(state, &args, "Deno.Command().outputSync()")?.output()?;
 
   Ok(SpawnOutput {
     status: output.status.try_into()?,
@@ -378,14 +762,43 @@ fn op_spawn_kill(
   state: &mut OpState,
   rid: ResourceId,
   signal: String,
+  process_group: bool,
 ) -> Result<(), AnyError> {
   if let Ok(child_resource) = state.resource_table.get::<ChildResource>(rid) {
-    deprecated::kill(child_resource.1 as i32, &signal)?;
+    if process_group {
+      match &child_resource.process_group {
+        Some(group) => group.kill(&signal)?,
+        None => {
+          return Err(type_error(
+            "Child process was not spawned with 'detached' or 'processGroup'",
+          ))
+        }
+      }
+    } else {
+      deprecated::kill(child_resource.pid as i32, &signal)?;
+    }
     return Ok(());
   }
   Err(type_error("Child process has already terminated."))
 }
 
+#[op]
+fn op_spawn_resize_pty(
+  state: &mut OpState,
+  rid: ResourceId,
+  columns: u16,
+  rows: u16,
+) -> Result<(), AnyError> {
+  let child_resource = state
+    .resource_table
+    .get::<ChildResource>(rid)
+    .map_err(|_| type_error("Child process has already terminated."))?;
+  match &child_resource.pty {
+    Some(pty) => resize_pty(pty, PtySize { columns, rows }),
+    None => Err(type_error("Child process is not attached to a pty")),
+  }
+}
+
 mod deprecated {
   use super::*;
 
@@ -438,9 +851,11 @@ mod deprecated {
     run_args: RunArgs,
   ) -> Result<RunInfo, AnyError> {
     let args = run_args.cmd;
-    state
-      .borrow_mut::<PermissionsContainer>()
-      .check_run(&args[0], "Deno.run()")?;
+    state.borrow_mut::<PermissionsContainer>().check_run(
+      &args[0],
+      &args[1..],
+      "Deno.run()",
+    )?;
     let env = run_args.env;
     let cwd = run_args.cwd;
 
@@ -458,6 +873,9 @@ mod deprecated {
     for (key, value) in &env {
       c.env(key, value);
     }
+    if let Some(permissions) = inherited_permissions_for_child(state, None)? {
+      c.env(INHERITED_PERMISSIONS_ENV_VAR_NAME, permissions);
+    }
 
     #[cfg(unix)]
     if let Some(gid) = run_args.gid {
@@ -609,7 +1027,9 @@ mod deprecated {
     use winapi::um::processthreadsapi::TerminateProcess;
     use winapi::um::winnt::PROCESS_TERMINATE;
 
-    if !matches!(signal, "SIGKILL" | "SIGTERM") {
+    if signal == "SIGBREAK" && pid > 0 {
+      send_ctrl_break(pid as DWORD)
+    } else if !matches!(signal, "SIGKILL" | "SIGTERM") {
       Err(type_error(format!("Invalid signal: {signal}")))
     } else if pid <= 0 {
       Err(type_error("Invalid pid"))
@@ -640,6 +1060,23 @@ mod deprecated {
     }
   }
 
+  /// Sends a ctrl-break event to the console process group with the given id,
+  /// which is the pid of a process spawned with `CREATE_NEW_PROCESS_GROUP`.
+  /// The processes of the group must share the console with this process.
+  #[cfg(not(unix))]
+  pub fn send_ctrl_break(process_group_id: u32) -> Result<(), AnyError> {
+    use winapi::um::wincon::GenerateConsoleCtrlEvent;
+    use winapi::um::wincon::CTRL_BREAK_EVENT;
+
+    // SAFETY: winapi call
+    if unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, process_group_id) }
+      == 0
+    {
+      return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+  }
+
   #[op]
   fn op_kill(
     state: &mut OpState,
@@ -3,12 +3,18 @@
 use crate::permissions::PermissionsContainer;
 use deno_core::error::AnyError;
 use deno_core::op;
+use deno_core::v8;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 deno_core::extension!(
   deno_runtime,
-  ops = [op_main_module, op_ppid],
+  ops = [op_main_module, op_ppid, op_write_heap_snapshot],
   options = { main_module: ModuleSpecifier },
   state = |state, options| {
     state.put::<ModuleSpecifier>(options.main_module);
@@ -31,6 +37,56 @@ fn op_main_module(state: &mut OpState) -> Result<String, AnyError> {
   Ok(main_path)
 }
 
+/// Writes a V8 heap snapshot of the isolate to the given file. It can be
+/// loaded in the "Memory" tab of the Chrome DevTools.
+pub fn write_heap_snapshot(
+  isolate: &mut v8::Isolate,
+  path: &Path,
+) -> std::io::Result<()> {
+  let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+  let mut result = Ok(());
+  isolate.take_heap_snapshot(|chunk| match file.write_all(chunk) {
+    Ok(()) => true,
+    Err(err) => {
+      result = Err(err);
+      false
+    }
+  });
+  result?;
+  file.flush()
+}
+
+/// Returns a unique file name in the current directory to write a heap
+/// snapshot to, like `Heap.1685000000000.1234.heapsnapshot`.
+pub fn default_heap_snapshot_path() -> PathBuf {
+  let timestamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_millis())
+    .unwrap_or_default();
+  PathBuf::from(format!(
+    "Heap.{}.{}.heapsnapshot",
+    timestamp,
+    std::process::id()
+  ))
+}
+
+#[op(v8)]
+fn op_write_heap_snapshot(
+  scope: &mut v8::HandleScope,
+  state: &mut OpState,
+  path: Option<String>,
+) -> Result<String, AnyError> {
+  super::check_unstable(state, "Deno.writeHeapSnapshot");
+  let path = path
+    .map(PathBuf::from)
+    .unwrap_or_else(default_heap_snapshot_path);
+  state
+    .borrow_mut::<PermissionsContainer>()
+    .check_write(&path, "Deno.writeHeapSnapshot()")?;
+  write_heap_snapshot(scope, &path)?;
+  Ok(path.to_string_lossy().into_owned())
+}
+
 /// This is an op instead of being done at initialization time because
 /// it's expensive to retreive the ppid on Windows.
 #[op]
@@ -25,9 +25,13 @@ use tokio::signal::windows::ctrl_break;
 #[cfg(windows)]
 use tokio::signal::windows::ctrl_c;
 #[cfg(windows)]
+use tokio::signal::windows::ctrl_close;
+#[cfg(windows)]
 use tokio::signal::windows::CtrlBreak;
 #[cfg(windows)]
 use tokio::signal::windows::CtrlC;
+#[cfg(windows)]
+use tokio::signal::windows::CtrlClose;
 
 deno_core::extension!(
   deno_signal,
@@ -56,12 +60,13 @@ impl Resource for SignalStreamResource {
   }
 }
 
-// TODO: CtrlClose could be mapped to SIGHUP but that needs a
-// tokio::windows::signal::CtrlClose type, or something from a different crate
+// Console control events are mapped to the signals a Unix process would
+// receive in the same situation, closing the console window is SIGHUP.
 #[cfg(windows)]
 enum WindowsSignal {
   Sigint(CtrlC),
   Sigbreak(CtrlBreak),
+  Sighup(CtrlClose),
 }
 
 #[cfg(windows)]
@@ -78,12 +83,20 @@ impl From<CtrlBreak> for WindowsSignal {
   }
 }
 
+#[cfg(windows)]
+impl From<CtrlClose> for WindowsSignal {
+  fn from(ctrl_close: CtrlClose) -> Self {
+    WindowsSignal::Sighup(ctrl_close)
+  }
+}
+
 #[cfg(windows)]
 impl WindowsSignal {
   pub async fn recv(&mut self) -> Option<()> {
     match self {
       WindowsSignal::Sigint(ctrl_c) => ctrl_c.recv().await,
       WindowsSignal::Sigbreak(ctrl_break) => ctrl_break.recv().await,
+      WindowsSignal::Sighup(ctrl_close) => ctrl_close.recv().await,
     }
   }
 }
@@ -437,10 +450,11 @@ pub fn signal_int_to_str(s: libc::c_int) -> Result<&'static str, AnyError> {
 #[cfg(target_os = "windows")]
 pub fn signal_str_to_int(s: &str) -> Result<libc::c_int, AnyError> {
   match s {
+    "SIGHUP" => Ok(1),
     "SIGINT" => Ok(2),
     "SIGBREAK" => Ok(21),
     _ => Err(type_error(
-      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK).",
+      "Windows only supports ctrl-c (SIGINT), ctrl-break (SIGBREAK) and closing the console (SIGHUP).",
     )),
   }
 }
@@ -448,10 +462,11 @@ pub fn signal_str_to_int(s: &str) -> Result<libc::c_int, AnyError> {
 #[cfg(target_os = "windows")]
 pub fn signal_int_to_str(s: libc::c_int) -> Result<&'static str, AnyError> {
   match s {
+    1 => Ok("SIGHUP"),
     2 => Ok("SIGINT"),
     21 => Ok("SIGBREAK"),
     _ => Err(type_error(
-      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK).",
+      "Windows only supports ctrl-c (SIGINT), ctrl-break (SIGBREAK) and closing the console (SIGHUP).",
     )),
   }
 }
@@ -485,6 +500,10 @@ fn op_signal_bind(
   let signo = signal_str_to_int(sig)?;
   let resource = SignalStreamResource {
     signal: AsyncRefCell::new(match signo {
+      // SIGHUP
+      1 => ctrl_close()
+        .expect("There was an issue creating ctrl+close event stream.")
+        .into(),
       // SIGINT
       2 => ctrl_c()
         .expect("There was an issue creating ctrl+c event stream.")
@@ -2,8 +2,9 @@
 
 use crate::ops::TestingFeaturesEnabled;
 use crate::permissions::create_child_permissions;
-use crate::permissions::ChildPermissionsArg;
 use crate::permissions::PermissionsContainer;
+use crate::permissions::WorkerPermissionsArg;
+use crate::permissions::WorkerPermissionsConfig;
 use crate::web_worker::run_web_worker;
 use crate::web_worker::SendableWebWorkerHandle;
 use crate::web_worker::WebWorker;
@@ -60,6 +61,9 @@ struct PreloadModuleCbHolder(Arc<WorkerEventCb>);
 #[derive(Clone)]
 struct PreExecuteModuleCbHolder(Arc<WorkerEventCb>);
 
+#[derive(Clone)]
+struct WorkerPermissionsConfigHolder(Arc<WorkerPermissionsConfig>);
+
 pub struct WorkerThread {
   worker_handle: WebWorkerHandle,
   cancel_handle: Rc<CancelHandle>,
@@ -101,6 +105,7 @@ deno_core::extension!(
     preload_module_cb: Arc<WorkerEventCb>,
     pre_execute_module_cb: Arc<WorkerEventCb>,
     format_js_error_fn: Option<Arc<FormatJsErrorFn>>,
+    worker_permissions_config: Arc<WorkerPermissionsConfig>,
   },
   state = |state, options| {
     state.put::<WorkersTable>(WorkersTable::default());
@@ -118,6 +123,9 @@ deno_core::extension!(
     let format_js_error_fn_holder =
       FormatJsErrorFnHolder(options.format_js_error_fn);
     state.put::<FormatJsErrorFnHolder>(format_js_error_fn_holder);
+    state.put::<WorkerPermissionsConfigHolder>(WorkerPermissionsConfigHolder(
+      options.worker_permissions_config,
+    ));
   },
   customizer = |ext: &mut deno_core::ExtensionBuilder| {
     ext.force_op_registration();
@@ -129,7 +137,9 @@ deno_core::extension!(
 pub struct CreateWorkerArgs {
   has_source_code: bool,
   name: Option<String>,
-  permissions: Option<ChildPermissionsArg>,
+  permissions: Option<WorkerPermissionsArg>,
+  #[serde(default)]
+  preload: Vec<String>,
   source_code: String,
   specifier: String,
   worker_type: WebWorkerType,
@@ -163,8 +173,20 @@ fn op_create_worker(
   if args.permissions.is_some() {
     super::check_unstable(state, "Worker.deno.permissions");
   }
+  if !args.preload.is_empty() {
+    super::check_unstable(state, "Worker.deno.preload");
+  }
+  let worker_permissions_config =
+    state.borrow::<WorkerPermissionsConfigHolder>().0.clone();
+  let maybe_child_permissions_arg =
+    worker_permissions_config.resolve(args.permissions)?;
+  let mut preload_modules = worker_permissions_config.preload.clone();
+  for specifier in &args.preload {
+    preload_modules.push(deno_core::resolve_url(specifier)?);
+  }
   let parent_permissions = state.borrow_mut::<PermissionsContainer>();
-  let worker_permissions = if let Some(child_permissions_arg) = args.permissions
+  let worker_permissions = if let Some(child_permissions_arg) =
+    maybe_child_permissions_arg
   {
     let mut parent_permissions = parent_permissions.0.lock();
     let perms =
@@ -224,6 +246,7 @@ fn op_create_worker(
       worker,
       module_specifier,
       maybe_source_code,
+      preload_modules,
       preload_module_cb.0,
       pre_execute_module_cb.0,
       format_js_error_fn.0,
@@ -0,0 +1,158 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// An IP address range in CIDR notation, eg. `10.0.0.0/8` or `[fd00::]/8`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct IpCidr {
+  addr: IpAddr,
+  prefix_len: u8,
+}
+
+impl IpCidr {
+  pub fn contains(&self, ip: &IpAddr) -> bool {
+    match (self.addr, ip) {
+      (IpAddr::V4(range), IpAddr::V4(ip)) => {
+        prefix_matches(&range.octets(), &ip.octets(), self.prefix_len)
+      }
+      (IpAddr::V6(range), IpAddr::V6(ip)) => {
+        prefix_matches(&range.octets(), &ip.octets(), self.prefix_len)
+      }
+      // IPv4-mapped IPv6 addresses (eg. `::ffff:10.0.0.1`)
+      (IpAddr::V4(_), IpAddr::V6(ip)) => ip
+        .to_ipv4_mapped()
+        .map(|ip| self.contains(&IpAddr::V4(ip)))
+        .unwrap_or(false),
+      (IpAddr::V6(_), IpAddr::V4(_)) => false,
+    }
+  }
+
+  /// Parses a net permission entry in CIDR notation, optionally followed by a
+  /// port (eg. `10.0.0.0/8:443`). Returns `None` if the entry is not in CIDR
+  /// notation.
+  pub fn parse_with_port(
+    s: &str,
+  ) -> Option<Result<(IpCidr, Option<u16>), AnyError>> {
+    let (addr, rest) = s.split_once('/')?;
+    let result = match rest.split_once(':') {
+      Some((prefix_len, port)) => match port.parse::<u16>() {
+        Ok(port) => format!("{addr}/{prefix_len}")
+          .parse::<IpCidr>()
+          .map(|cidr| (cidr, Some(port))),
+        Err(_) => Err(invalid_cidr_error(s)),
+      },
+      None => s.parse::<IpCidr>().map(|cidr| (cidr, None)),
+    };
+    Some(result)
+  }
+}
+
+fn invalid_cidr_error(s: &str) -> AnyError {
+  anyhow!("Invalid CIDR range: {s}")
+}
+
+fn prefix_matches(range: &[u8], ip: &[u8], prefix_len: u8) -> bool {
+  let full_bytes = (prefix_len / 8) as usize;
+  let remaining_bits = prefix_len % 8;
+  if range[..full_bytes] != ip[..full_bytes] {
+    return false;
+  }
+  if remaining_bits == 0 {
+    return true;
+  }
+  let mask = 0xffu8 << (8 - remaining_bits);
+  range[full_bytes] & mask == ip[full_bytes] & mask
+}
+
+impl FromStr for IpCidr {
+  type Err = AnyError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (addr, prefix_len) = match s.split_once('/') {
+      Some(parts) => parts,
+      None => return Err(invalid_cidr_error(s)),
+    };
+    let addr = addr
+      .trim_start_matches('[')
+      .trim_end_matches(']')
+      .parse::<IpAddr>()
+      .map_err(|_| invalid_cidr_error(s))?;
+    let prefix_len = prefix_len
+      .parse::<u8>()
+      .map_err(|_| invalid_cidr_error(s))?;
+    let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+    if prefix_len > max_prefix_len {
+      return Err(invalid_cidr_error(s));
+    }
+    Ok(IpCidr { addr, prefix_len })
+  }
+}
+
+impl fmt::Display for IpCidr {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.addr {
+      IpAddr::V4(addr) => write!(f, "{}/{}", addr, self.prefix_len),
+      // brackets keep a following port unambiguous
+      IpAddr::V6(addr) => write!(f, "[{}]/{}", addr, self.prefix_len),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+  }
+
+  #[test]
+  fn test_cidr_contains() {
+    let range = "10.0.0.0/8".parse::<IpCidr>().unwrap();
+    assert!(range.contains(&ip("10.1.2.3")));
+    assert!(!range.contains(&ip("11.0.0.1")));
+    assert!(range.contains(&ip("::ffff:10.0.0.1")));
+
+    let range = "192.168.1.0/24".parse::<IpCidr>().unwrap();
+    assert!(range.contains(&ip("192.168.1.255")));
+    assert!(!range.contains(&ip("192.168.2.1")));
+
+    let range = "172.16.0.0/12".parse::<IpCidr>().unwrap();
+    assert!(range.contains(&ip("172.31.255.255")));
+    assert!(!range.contains(&ip("172.32.0.0")));
+
+    let range = "[fd00::]/8".parse::<IpCidr>().unwrap();
+    assert!(range.contains(&ip("fd12::1")));
+    assert!(!range.contains(&ip("fe80::1")));
+    assert!(!range.contains(&ip("10.0.0.1")));
+
+    let range = "0.0.0.0/0".parse::<IpCidr>().unwrap();
+    assert!(range.contains(&ip("1.2.3.4")));
+  }
+
+  #[test]
+  fn test_cidr_parse() {
+    assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+    assert!("fd00::/129".parse::<IpCidr>().is_err());
+    assert!("deno.land/8".parse::<IpCidr>().is_err());
+    assert!("10.0.0.0".parse::<IpCidr>().is_err());
+    assert_eq!(
+      "fd00::/8".parse::<IpCidr>().unwrap().to_string(),
+      "[fd00::]/8"
+    );
+
+    assert!(IpCidr::parse_with_port("deno.land").is_none());
+    let (cidr, port) =
+      IpCidr::parse_with_port("10.0.0.0/8:443").unwrap().unwrap();
+    assert_eq!(cidr.to_string(), "10.0.0.0/8");
+    assert_eq!(port, Some(443));
+    let (cidr, port) = IpCidr::parse_with_port("[fd00::]/8").unwrap().unwrap();
+    assert_eq!(cidr.to_string(), "[fd00::]/8");
+    assert_eq!(port, None);
+    assert!(IpCidr::parse_with_port("10.0.0.0/8:http").unwrap().is_err());
+  }
+}
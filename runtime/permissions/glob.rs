@@ -0,0 +1,205 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Component;
+use std::path::Path;
+
+/// Returns true if the string contains any glob metacharacters.
+pub fn is_glob_pattern(s: &str) -> bool {
+  s.contains(['*', '?', '['])
+}
+
+/// Returns true if the path contains any glob metacharacters.
+pub fn is_glob_path(path: &Path) -> bool {
+  is_glob_pattern(&path.to_string_lossy())
+}
+
+/// Matches a single string against a wildcard pattern. Supports `*` (any
+/// sequence of characters), `?` (any single character) and bracket
+/// expressions like `[abc]`, `[a-z]` and `[!abc]`.
+pub fn matches_pattern(pattern: &str, text: &str) -> bool {
+  let pattern = pattern.chars().collect::<Vec<_>>();
+  let text = text.chars().collect::<Vec<_>>();
+  matches_chars(&pattern, &text)
+}
+
+fn matches_chars(pattern: &[char], text: &[char]) -> bool {
+  let mut p = 0;
+  let mut t = 0;
+  // position of the last `*` in the pattern and the text position it
+  // was tried against, used for backtracking
+  let mut star: Option<(usize, usize)> = None;
+  while t < text.len() {
+    if p < pattern.len() {
+      match pattern[p] {
+        '*' => {
+          star = Some((p, t));
+          p += 1;
+          continue;
+        }
+        '?' => {
+          p += 1;
+          t += 1;
+          continue;
+        }
+        '[' => {
+          if let Some((matched, len)) = match_bracket(&pattern[p..], text[t]) {
+            if matched {
+              p += len;
+              t += 1;
+              continue;
+            }
+          } else if text[t] == '[' {
+            // unterminated bracket, treat literally
+            p += 1;
+            t += 1;
+            continue;
+          }
+        }
+        c if c == text[t] => {
+          p += 1;
+          t += 1;
+          continue;
+        }
+        _ => {}
+      }
+    }
+    match star {
+      Some((star_p, star_t)) => {
+        p = star_p + 1;
+        t = star_t + 1;
+        star = Some((star_p, star_t + 1));
+      }
+      None => return false,
+    }
+  }
+  pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Matches a bracket expression at the start of `pattern` against `c`.
+/// Returns whether it matched along with the length of the expression,
+/// or `None` if the expression is not terminated.
+fn match_bracket(pattern: &[char], c: char) -> Option<(bool, usize)> {
+  debug_assert_eq!(pattern[0], '[');
+  let mut i = 1;
+  let negated = matches!(pattern.get(i), Some('!') | Some('^'));
+  if negated {
+    i += 1;
+  }
+  let mut matched = false;
+  let mut first = true;
+  while i < pattern.len() {
+    let current = pattern[i];
+    if current == ']' && !first {
+      return Some((matched != negated, i + 1));
+    }
+    first = false;
+    if pattern.get(i + 1) == Some(&'-')
+      && pattern.get(i + 2).map(|c| *c != ']').unwrap_or(false)
+    {
+      let end = pattern[i + 2];
+      if current <= c && c <= end {
+        matched = true;
+      }
+      i += 3;
+    } else {
+      if current == c {
+        matched = true;
+      }
+      i += 1;
+    }
+  }
+  None
+}
+
+/// Returns true if `path` or one of its ancestors is matched by the glob
+/// `pattern`. Both paths are expected to be absolute and normalized. A `**`
+/// component matches any number of path components, including none.
+///
+/// Matching ancestors mirrors the directory semantics of non-glob allowlist
+/// entries, where granting a directory grants everything below it.
+pub fn path_matches_glob(pattern: &Path, path: &Path) -> bool {
+  let pattern = pattern.components().collect::<Vec<_>>();
+  let path = path.components().collect::<Vec<_>>();
+  (0..=path.len()).any(|len| matches_components(&pattern, &path[..len]))
+}
+
+/// Returns true if `path` itself is matched by the glob `pattern`, unlike
+/// [`path_matches_glob`] which also matches the paths below a match.
+pub fn path_is_glob_match(pattern: &Path, path: &Path) -> bool {
+  let pattern = pattern.components().collect::<Vec<_>>();
+  let path = path.components().collect::<Vec<_>>();
+  matches_components(&pattern, &path)
+}
+
+fn matches_components(pattern: &[Component], path: &[Component]) -> bool {
+  match pattern.first() {
+    None => path.is_empty(),
+    Some(Component::Normal(segment)) if segment.to_str() == Some("**") => {
+      let rest = &pattern[1..];
+      (0..=path.len()).any(|skip| matches_components(rest, &path[skip..]))
+    }
+    Some(pattern_component) => match path.first() {
+      None => false,
+      Some(path_component) => {
+        let is_match = match (pattern_component, path_component) {
+          (Component::Normal(p), Component::Normal(s)) => {
+            matches_pattern(&p.to_string_lossy(), &s.to_string_lossy())
+          }
+          (p, s) => p == s,
+        };
+        is_match && matches_components(&pattern[1..], &path[1..])
+      }
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_matches_pattern() {
+    assert!(matches_pattern("*.json", "data.json"));
+    assert!(matches_pattern("app-*", "app-"));
+    assert!(matches_pattern("app-*", "app-123"));
+    assert!(!matches_pattern("app-*", "ap-123"));
+    assert!(matches_pattern("?.txt", "a.txt"));
+    assert!(!matches_pattern("?.txt", "ab.txt"));
+    assert!(matches_pattern("[ab].txt", "b.txt"));
+    assert!(!matches_pattern("[!ab].txt", "b.txt"));
+    assert!(matches_pattern("[a-c]*", "cat"));
+    assert!(!matches_pattern("[a-c]*", "dog"));
+    assert!(matches_pattern("a*b*c", "aXbYbZc"));
+    assert!(!matches_pattern("a*b*c", "aXbYbZ"));
+  }
+
+  #[test]
+  fn test_path_matches_glob() {
+    let pattern = Path::new("/data/**/*.json");
+    assert!(path_matches_glob(pattern, Path::new("/data/a.json")));
+    assert!(path_matches_glob(pattern, Path::new("/data/x/y/a.json")));
+    assert!(!path_matches_glob(pattern, Path::new("/data/x/y/a.txt")));
+    assert!(!path_matches_glob(pattern, Path::new("/other/a.json")));
+
+    let pattern = Path::new("/tmp/app-*");
+    assert!(path_matches_glob(pattern, Path::new("/tmp/app-1")));
+    assert!(path_matches_glob(pattern, Path::new("/tmp/app-1/sub/file")));
+    assert!(!path_matches_glob(pattern, Path::new("/tmp/other")));
+    assert!(!path_matches_glob(pattern, Path::new("/tmp")));
+  }
+
+  #[test]
+  fn test_path_is_glob_match() {
+    let pattern = Path::new("/data/**/*.json");
+    assert!(path_is_glob_match(pattern, Path::new("/data/a.json")));
+    assert!(path_is_glob_match(pattern, Path::new("/data/x/y/a.json")));
+    assert!(!path_is_glob_match(pattern, Path::new("/data/x/y/a.txt")));
+
+    let pattern = Path::new("/tmp/app-*");
+    assert!(path_is_glob_match(pattern, Path::new("/tmp/app-1")));
+    assert!(!path_is_glob_match(
+      pattern,
+      Path::new("/tmp/app-1/sub/file")
+    ));
+  }
+}
@@ -17,22 +17,36 @@ use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use log;
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::hash::Hash;
+use std::net::IpAddr;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::string::ToString;
 use std::sync::Arc;
 
+mod cidr;
+mod glob;
 mod prompter;
+pub use glob::path_is_glob_match;
+pub use glob::path_matches_glob;
 use prompter::permission_prompt;
 use prompter::PromptResponse;
 use prompter::PERMISSION_EMOJI;
 
+pub use cidr::IpCidr;
+pub use glob::is_glob_pattern;
+pub use glob::matches_pattern;
 pub use prompter::set_prompt_callbacks;
+pub use prompter::set_prompter;
+pub use prompter::DenyPrompter;
+pub use prompter::PermissionPrompter;
 pub use prompter::PromptCallback;
+pub use prompter::PromptStackFrame;
 
 static DEBUG_LOG_ENABLED: Lazy<bool> =
   Lazy::new(|| log::log_enabled!(log::Level::Debug));
@@ -231,6 +245,9 @@ pub struct UnaryPermission<T: Eq + Hash> {
   pub global_state: PermissionState,
   pub granted_list: HashSet<T>,
   pub denied_list: HashSet<T>,
+  /// Descriptors that were explicitly denied up front (eg. by a permissions
+  /// policy file). These take precedence over any granted state.
+  pub flag_denied_list: HashSet<T>,
   pub prompt: bool,
 }
 
@@ -247,12 +264,77 @@ impl NetDescriptor {
   fn new<T: AsRef<str>>(host: &&(T, Option<u16>)) -> Self {
     NetDescriptor(host.0.as_ref().to_string(), host.1)
   }
+
+  /// Returns the address range of the descriptor if it's in CIDR notation.
+  fn cidr(&self) -> Option<IpCidr> {
+    if self.0.contains('/') {
+      IpCidr::from_str(&self.0).ok()
+    } else {
+      None
+    }
+  }
+}
+
+/// Checks an IP address against the CIDR entries of a net permission list.
+/// Hostnames aren't resolved here, their addresses are checked when
+/// connecting (see `UnaryPermission::<NetDescriptor>::check_resolved`).
+fn ip_matches_cidr_entries(
+  list: &HashSet<NetDescriptor>,
+  ip: &IpAddr,
+  port: Option<u16>,
+) -> bool {
+  list
+    .iter()
+    .filter(|desc| desc.1.is_none() || desc.1 == port)
+    .filter_map(|desc| desc.cidr())
+    .any(|range| range.contains(ip))
+}
+
+/// Checks an IP address against the CIDR entries of a net permission list,
+/// regardless of the ports of the entries.
+fn ip_matches_any_port_cidr_entries(
+  list: &HashSet<NetDescriptor>,
+  ip: &IpAddr,
+) -> bool {
+  list
+    .iter()
+    .filter_map(|desc| desc.cidr())
+    .any(|range| range.contains(ip))
+}
+
+fn is_ip_address(host: &str) -> bool {
+  host
+    .trim_start_matches('[')
+    .trim_end_matches(']')
+    .parse::<IpAddr>()
+    .is_ok()
+}
+
+/// Checks a host against the CIDR entries of a net permission list if it's
+/// an IP address.
+fn host_matches_cidr_entries(
+  list: &HashSet<NetDescriptor>,
+  host: &str,
+  port: Option<u16>,
+) -> bool {
+  match host
+    .trim_start_matches('[')
+    .trim_end_matches(']')
+    .parse::<IpAddr>()
+  {
+    Ok(ip) => ip_matches_cidr_entries(list, &ip, port),
+    Err(_) => false,
+  }
 }
 
 impl FromStr for NetDescriptor {
   type Err = AnyError;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Some(result) = IpCidr::parse_with_port(s) {
+      let (cidr, port) = result?;
+      return Ok(NetDescriptor(cidr.to_string(), port));
+    }
     let url = url::Url::parse(&format!("http://{s}"))?;
     let hostname = url.host_str().unwrap().to_string();
 
@@ -314,6 +396,171 @@ impl ToString for RunDescriptor {
   }
 }
 
+/// Looks up a command name in the directories of the `PATH` environment
+/// variable, returning the path of the first executable found.
+#[allow(clippy::disallowed_methods)]
+fn resolve_run_name_from_path(name: &str) -> Option<PathBuf> {
+  let paths = std::env::var_os("PATH")?;
+  let candidates: Vec<String> =
+    if cfg!(windows) && Path::new(name).extension().is_none() {
+      let exts =
+        std::env::var("PATHEXT").unwrap_or_else(|_| ".EXE;.CMD;.BAT".into());
+      exts.split(';').map(|ext| format!("{name}{ext}")).collect()
+    } else {
+      vec![name.to_string()]
+    };
+  std::env::split_paths(&paths)
+    .flat_map(|dir| candidates.iter().map(move |c| dir.join(c)))
+    .find(|path| path.is_file())
+}
+
+/// Adds the paths that the command names of a `--deny-run` list resolve to,
+/// so that spawning a denied command through its full path is denied too.
+fn with_resolved_run_names(
+  mut list: HashSet<RunDescriptor>,
+) -> HashSet<RunDescriptor> {
+  let resolved: Vec<RunDescriptor> = list
+    .iter()
+    .filter_map(|desc| match desc {
+      RunDescriptor::Name(name) => {
+        resolve_run_name_from_path(name).map(RunDescriptor::Path)
+      }
+      RunDescriptor::Path(_) => None,
+    })
+    .collect();
+  list.extend(resolved);
+  list
+}
+
+/// Splits an `--allow-run` entry like `git:status` into the command and the
+/// optional argument pattern. On Windows, the colon of a drive letter (eg.
+/// `C:\bin\git.exe`) is not treated as a separator.
+fn split_run_allow_entry(entry: &str) -> (&str, Option<&str>) {
+  let bytes = entry.as_bytes();
+  let has_drive_letter = cfg!(windows)
+    && bytes.len() >= 3
+    && bytes[0].is_ascii_alphabetic()
+    && bytes[1] == b':'
+    && (bytes[2] == b'\\' || bytes[2] == b'/');
+  let search_start = if has_drive_letter { 2 } else { 0 };
+  match entry[search_start..].find(':') {
+    Some(index) => {
+      let index = search_start + index;
+      (&entry[..index], Some(&entry[index + 1..]))
+    }
+    None => (entry, None),
+  }
+}
+
+/// Argument patterns that commands granted through `--allow-run=cmd:args`
+/// are restricted to. Each whitespace separated token of a pattern is matched
+/// against the argument at the same position and may contain wildcards. The
+/// arguments must have exactly as many entries as the pattern, unless its
+/// last token is a plain `*`, which matches any number of remaining
+/// arguments. For example `git:status` only allows spawning `git status`,
+/// while `git:status *` also allows `git status --short`.
+/// Commands without an entry may be spawned with any arguments.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RunArgsAllowlist(HashMap<RunDescriptor, Vec<Vec<String>>>);
+
+impl RunArgsAllowlist {
+  pub fn from_allowlist(allowlist: &Option<Vec<String>>) -> Self {
+    let mut restricted: HashMap<RunDescriptor, Vec<Vec<String>>> =
+      HashMap::new();
+    let mut unrestricted = HashSet::new();
+    for entry in allowlist.iter().flatten() {
+      let (cmd, maybe_args) = split_run_allow_entry(entry);
+      if cmd.is_empty() {
+        continue;
+      }
+      let desc = RunDescriptor::from_str(cmd).unwrap();
+      match maybe_args {
+        Some(args) => restricted
+          .entry(desc)
+          .or_default()
+          .push(args.split_whitespace().map(ToString::to_string).collect()),
+        None => {
+          unrestricted.insert(desc);
+        }
+      }
+    }
+    // a plain entry for the same command lifts any restriction
+    restricted.retain(|desc, _| !unrestricted.contains(desc));
+    Self(restricted)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Checks the arguments a command is spawned with against the argument
+  /// patterns the command was granted with.
+  pub fn check(&self, cmd: &str, args: &[String]) -> Result<(), AnyError> {
+    if self.0.is_empty() {
+      return Ok(());
+    }
+    let desc = RunDescriptor::from_str(cmd).unwrap();
+    match self.0.get(&desc) {
+      Some(patterns)
+        if !patterns.iter().any(|p| args_match_pattern(p, args)) =>
+      {
+        Err(custom_error(
+          "PermissionDenied",
+          format!(
+            "Requires run access to \"{}\" with arguments \"{}\", run again with the --allow-run flag",
+            cmd,
+            args.join(" ")
+          ),
+        ))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Combines the restrictions of a parent with the ones requested for a
+  /// child worker, erroring if the child would be less restricted.
+  fn restrict_child(
+    &self,
+    mut child: RunArgsAllowlist,
+  ) -> Result<RunArgsAllowlist, AnyError> {
+    for (desc, parent_patterns) in &self.0 {
+      match child.0.get(desc) {
+        Some(child_patterns) => {
+          let is_narrower = child_patterns.iter().all(|child_pattern| {
+            parent_patterns
+              .iter()
+              .any(|p| args_match_pattern(p, child_pattern))
+          });
+          if !is_narrower {
+            return Err(escalation_error());
+          }
+        }
+        None => {
+          child.0.insert(desc.clone(), parent_patterns.clone());
+        }
+      }
+    }
+    Ok(child)
+  }
+}
+
+fn args_match_pattern(pattern: &[String], args: &[String]) -> bool {
+  let (pattern, matches_rest) = match pattern.split_last() {
+    Some((last, rest)) if last == "*" => (rest, true),
+    _ => (pattern, false),
+  };
+  let arity_matches = if matches_rest {
+    pattern.len() <= args.len()
+  } else {
+    pattern.len() == args.len()
+  };
+  arity_matches
+    && pattern
+      .iter()
+      .zip(args)
+      .all(|(pattern, arg)| glob::matches_pattern(pattern, arg))
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct SysDescriptor(pub String);
 
@@ -328,12 +575,32 @@ pub fn parse_sys_kind(kind: &str) -> Result<&str, AnyError> {
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct FfiDescriptor(pub PathBuf);
 
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct UnixDescriptor(pub PathBuf);
+
 impl UnaryPermission<ReadDescriptor> {
   pub fn query(&self, path: Option<&Path>) -> PermissionState {
-    if self.global_state == PermissionState::Granted {
+    if self.global_state == PermissionState::Granted
+      && self.flag_denied_list.is_empty()
+    {
       return PermissionState::Granted;
     }
     let path = path.map(|p| resolve_from_cwd(p).unwrap());
+    if let Some(path) = path.as_ref() {
+      if self
+        .flag_denied_list
+        .iter()
+        .any(|path_| path_matches_entry(path, &path_.0))
+      {
+        return PermissionState::Denied;
+      }
+    } else if !self.flag_denied_list.is_empty() {
+      // full access can't be granted while some entries are denied
+      return PermissionState::Denied;
+    }
+    if self.global_state == PermissionState::Granted {
+      return PermissionState::Granted;
+    }
     if self.global_state == PermissionState::Denied
       && match path.as_ref() {
         None => true,
@@ -350,7 +617,7 @@ impl UnaryPermission<ReadDescriptor> {
         Some(path) => self
           .granted_list
           .iter()
-          .any(|path_| path.starts_with(&path_.0)),
+          .any(|path_| path_matches_entry(path, &path_.0)),
       }
     {
       PermissionState::Granted
@@ -517,6 +784,7 @@ impl Default for UnaryPermission<ReadDescriptor> {
       global_state: Default::default(),
       granted_list: Default::default(),
       denied_list: Default::default(),
+      flag_denied_list: Default::default(),
       prompt: false,
     }
   }
@@ -524,10 +792,27 @@ impl Default for UnaryPermission<ReadDescriptor> {
 
 impl UnaryPermission<WriteDescriptor> {
   pub fn query(&self, path: Option<&Path>) -> PermissionState {
-    if self.global_state == PermissionState::Granted {
+    if self.global_state == PermissionState::Granted
+      && self.flag_denied_list.is_empty()
+    {
       return PermissionState::Granted;
     }
     let path = path.map(|p| resolve_from_cwd(p).unwrap());
+    if let Some(path) = path.as_ref() {
+      if self
+        .flag_denied_list
+        .iter()
+        .any(|path_| path_matches_entry(path, &path_.0))
+      {
+        return PermissionState::Denied;
+      }
+    } else if !self.flag_denied_list.is_empty() {
+      // full access can't be granted while some entries are denied
+      return PermissionState::Denied;
+    }
+    if self.global_state == PermissionState::Granted {
+      return PermissionState::Granted;
+    }
     if self.global_state == PermissionState::Denied
       && match path.as_ref() {
         None => true,
@@ -544,7 +829,7 @@ impl UnaryPermission<WriteDescriptor> {
         Some(path) => self
           .granted_list
           .iter()
-          .any(|path_| path.starts_with(&path_.0)),
+          .any(|path_| path_matches_entry(path, &path_.0)),
       }
     {
       PermissionState::Granted
@@ -711,6 +996,7 @@ impl Default for UnaryPermission<WriteDescriptor> {
       global_state: Default::default(),
       granted_list: Default::default(),
       denied_list: Default::default(),
+      flag_denied_list: Default::default(),
       prompt: false,
     }
   }
@@ -721,6 +1007,20 @@ impl UnaryPermission<NetDescriptor> {
     &self,
     host: Option<&(T, Option<u16>)>,
   ) -> PermissionState {
+    if let Some(host) = host {
+      if self.flag_denied_list.iter().any(|host_| {
+        host.0.as_ref() == host_.0 && (host_.1.is_none() || host.1 == host_.1)
+      }) || host_matches_cidr_entries(
+        &self.flag_denied_list,
+        host.0.as_ref(),
+        host.1,
+      ) {
+        return PermissionState::Denied;
+      }
+    } else if !self.flag_denied_list.is_empty() {
+      // full access can't be granted while some entries are denied
+      return PermissionState::Denied;
+    }
     if self.global_state == PermissionState::Denied
       && match host.as_ref() {
         None => true,
@@ -743,6 +1043,11 @@ impl UnaryPermission<NetDescriptor> {
             None,
           )))
             || self.granted_list.contains(&NetDescriptor::new(host))
+            || host_matches_cidr_entries(
+              &self.granted_list,
+              host.0.as_ref(),
+              host.1,
+            )
         }
       }
     {
@@ -761,7 +1066,7 @@ impl UnaryPermission<NetDescriptor> {
       let host = NetDescriptor::new(&host);
       if state == PermissionState::Prompt {
         match permission_prompt(
-          &format!("network access to \"{host}\""),
+          &format!("{} access to \"{host}\"", self.description),
           self.name,
           Some("Deno.permissions.query()"),
           true,
@@ -792,7 +1097,7 @@ impl UnaryPermission<NetDescriptor> {
       if state == PermissionState::Prompt {
         if PromptResponse::Allow
           == permission_prompt(
-            "network access",
+            &format!("{} access", self.description),
             self.name,
             Some("Deno.permissions.query()"),
             true,
@@ -897,6 +1202,107 @@ impl UnaryPermission<NetDescriptor> {
     result
   }
 
+  /// Returns true if the host is a host name that isn't granted by name, but
+  /// that CIDR entries could grant once it's resolved. Such hosts are checked
+  /// against the address they resolve to instead.
+  fn is_checked_when_resolved<T: AsRef<str>>(
+    &self,
+    host: &(T, Option<u16>),
+  ) -> bool {
+    !is_ip_address(host.0.as_ref())
+      && self.query(Some(host)) == PermissionState::Prompt
+      && self.granted_list.iter().any(|desc| {
+        desc.cidr().is_some() && (desc.1.is_none() || desc.1 == host.1)
+      })
+  }
+
+  /// Checks a host before resolving it. A host name that CIDR entries could
+  /// grant passes, it has to be checked with `check_resolved` once it's
+  /// resolved.
+  pub fn check_unresolved<T: AsRef<str>>(
+    &mut self,
+    host: &(T, Option<u16>),
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    if self.is_checked_when_resolved(host) {
+      return Ok(());
+    }
+    self.check(host, api_name)
+  }
+
+  /// Checks the host of a URL before resolving it, see `check_unresolved`.
+  pub fn check_url_unresolved(
+    &mut self,
+    url: &url::Url,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    let hostname = url.host_str().ok_or_else(|| uri_error("Missing host"))?;
+    if self.is_checked_when_resolved(&(hostname, url.port_or_known_default())) {
+      return Ok(());
+    }
+    self.check_url(url, api_name)
+  }
+
+  /// Checks a host right before connecting to an address it resolved to.
+  /// The address is what the CIDR entries are matched against, so that the
+  /// check applies to where the connection actually goes.
+  pub fn check_resolved<T: AsRef<str>>(
+    &mut self,
+    host: &(T, Option<u16>),
+    addr: &SocketAddr,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    let ip = addr.ip();
+    if ip_matches_cidr_entries(&self.flag_denied_list, &ip, host.1) {
+      let (result, _, _) = PermissionState::Denied.check(
+        self.name,
+        api_name,
+        Some(&format!("\"{}\" ({ip})", host.0.as_ref())),
+        false,
+      );
+      return result;
+    }
+    if ip_matches_cidr_entries(&self.granted_list, &ip, host.1)
+      && self.query(Some(host)) != PermissionState::Denied
+    {
+      return Ok(());
+    }
+    self.check(host, api_name)
+  }
+
+  /// Checks an address a host name resolved to where the port of the
+  /// connection isn't known, like in the resolver of an HTTP client. The host
+  /// must have been checked with `check_url_unresolved` before. CIDR entries
+  /// are matched regardless of their port and there's no prompt.
+  pub fn check_resolved_host(
+    &self,
+    hostname: &str,
+    ip: &IpAddr,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    let granted =
+      if ip_matches_any_port_cidr_entries(&self.flag_denied_list, ip) {
+        false
+      } else if self.granted_list.iter().any(|desc| desc.cidr().is_some()) {
+        self.global_state == PermissionState::Granted
+          || self.granted_list.iter().any(|desc| desc.0 == hostname)
+          || ip_matches_any_port_cidr_entries(&self.granted_list, ip)
+      } else {
+        // without CIDR entries, the host was fully checked by name
+        true
+      };
+    if granted {
+      return Ok(());
+    }
+    let (result, _, _) = PermissionState::Denied.check(
+      self.name,
+      api_name,
+      Some(&format!("\"{hostname}\" ({ip})")),
+      false,
+    );
+    result
+  }
+
   pub fn check_all(&mut self) -> Result<(), AnyError> {
     let (result, prompted, _) =
       self
@@ -921,14 +1327,33 @@ impl Default for UnaryPermission<NetDescriptor> {
       global_state: Default::default(),
       granted_list: Default::default(),
       denied_list: Default::default(),
+      flag_denied_list: Default::default(),
       prompt: false,
     }
   }
 }
 
+/// Returns true if the list contains the env var, either by name or by a
+/// glob pattern like `AWS_*`.
+fn env_list_matches(list: &HashSet<EnvDescriptor>, env: &EnvVarName) -> bool {
+  list.contains(&EnvDescriptor::new(env))
+    || list.iter().any(|desc| {
+      glob::is_glob_pattern(desc.0.as_ref())
+        && glob::matches_pattern(desc.0.as_ref(), env.as_ref())
+    })
+}
+
 impl UnaryPermission<EnvDescriptor> {
   pub fn query(&self, env: Option<&str>) -> PermissionState {
     let env = env.map(EnvVarName::new);
+    if let Some(env) = env.as_ref() {
+      if env_list_matches(&self.flag_denied_list, env) {
+        return PermissionState::Denied;
+      }
+    } else if !self.flag_denied_list.is_empty() {
+      // full access can't be granted while some entries are denied
+      return PermissionState::Denied;
+    }
     if self.global_state == PermissionState::Denied
       && match env.as_ref() {
         None => true,
@@ -939,7 +1364,7 @@ impl UnaryPermission<EnvDescriptor> {
     } else if self.global_state == PermissionState::Granted
       || match env.as_ref() {
         None => false,
-        Some(env) => self.granted_list.contains(&EnvDescriptor::new(env)),
+        Some(env) => env_list_matches(&self.granted_list, env),
       }
     {
       PermissionState::Granted
@@ -1062,6 +1487,7 @@ impl Default for UnaryPermission<EnvDescriptor> {
       global_state: Default::default(),
       granted_list: Default::default(),
       denied_list: Default::default(),
+      flag_denied_list: Default::default(),
       prompt: false,
     }
   }
@@ -1069,6 +1495,17 @@ impl Default for UnaryPermission<EnvDescriptor> {
 
 impl UnaryPermission<SysDescriptor> {
   pub fn query(&self, kind: Option<&str>) -> PermissionState {
+    if let Some(kind) = kind {
+      if self
+        .flag_denied_list
+        .contains(&SysDescriptor(kind.to_string()))
+      {
+        return PermissionState::Denied;
+      }
+    } else if !self.flag_denied_list.is_empty() {
+      // full access can't be granted while some entries are denied
+      return PermissionState::Denied;
+    }
     if self.global_state == PermissionState::Denied
       && match kind {
         None => true,
@@ -1201,13 +1638,34 @@ impl Default for UnaryPermission<SysDescriptor> {
       global_state: Default::default(),
       granted_list: Default::default(),
       denied_list: Default::default(),
+      flag_denied_list: Default::default(),
       prompt: false,
     }
   }
 }
 
 impl UnaryPermission<RunDescriptor> {
+  fn is_flag_denied(&self, cmd: &str) -> bool {
+    match RunDescriptor::from_str(cmd).unwrap() {
+      desc @ RunDescriptor::Path(_) => self.flag_denied_list.contains(&desc),
+      desc @ RunDescriptor::Name(_) => {
+        self.flag_denied_list.contains(&desc)
+          || resolve_run_name_from_path(cmd).map_or(false, |path| {
+            self.flag_denied_list.contains(&RunDescriptor::Path(path))
+          })
+      }
+    }
+  }
+
   pub fn query(&self, cmd: Option<&str>) -> PermissionState {
+    if let Some(cmd) = cmd {
+      if self.is_flag_denied(cmd) {
+        return PermissionState::Denied;
+      }
+    } else if !self.flag_denied_list.is_empty() {
+      // full access can't be granted while some entries are denied
+      return PermissionState::Denied;
+    }
     if self.global_state == PermissionState::Denied
       && match cmd {
         None => true,
@@ -1361,6 +1819,7 @@ impl Default for UnaryPermission<RunDescriptor> {
       global_state: Default::default(),
       granted_list: Default::default(),
       denied_list: Default::default(),
+      flag_denied_list: Default::default(),
       prompt: false,
     }
   }
@@ -1369,6 +1828,18 @@ impl Default for UnaryPermission<RunDescriptor> {
 impl UnaryPermission<FfiDescriptor> {
   pub fn query(&self, path: Option<&Path>) -> PermissionState {
     let path = path.map(|p| resolve_from_cwd(p).unwrap());
+    if let Some(path) = path.as_ref() {
+      if self
+        .flag_denied_list
+        .iter()
+        .any(|path_| path.starts_with(&path_.0))
+      {
+        return PermissionState::Denied;
+      }
+    } else if !self.flag_denied_list.is_empty() {
+      // full access can't be granted while some entries are denied
+      return PermissionState::Denied;
+    }
     if self.global_state == PermissionState::Denied
       && match path.as_ref() {
         None => true,
@@ -1531,50 +2002,257 @@ impl Default for UnaryPermission<FfiDescriptor> {
       global_state: Default::default(),
       granted_list: Default::default(),
       denied_list: Default::default(),
+      flag_denied_list: Default::default(),
       prompt: false,
     }
   }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Permissions {
-  pub read: UnaryPermission<ReadDescriptor>,
-  pub write: UnaryPermission<WriteDescriptor>,
-  pub net: UnaryPermission<NetDescriptor>,
-  pub env: UnaryPermission<EnvDescriptor>,
-  pub sys: UnaryPermission<SysDescriptor>,
-  pub run: UnaryPermission<RunDescriptor>,
-  pub ffi: UnaryPermission<FfiDescriptor>,
-  pub hrtime: UnitPermission,
-}
-
-impl Default for Permissions {
-  fn default() -> Self {
-    Self {
-      read: Permissions::new_read(&None, false).unwrap(),
-      write: Permissions::new_write(&None, false).unwrap(),
-      net: Permissions::new_net(&None, false).unwrap(),
-      env: Permissions::new_env(&None, false).unwrap(),
-      sys: Permissions::new_sys(&None, false).unwrap(),
-      run: Permissions::new_run(&None, false).unwrap(),
-      ffi: Permissions::new_ffi(&None, false).unwrap(),
-      hrtime: Permissions::new_hrtime(false),
+impl UnaryPermission<UnixDescriptor> {
+  pub fn query(&self, path: Option<&Path>) -> PermissionState {
+    let path = path.map(|p| resolve_from_cwd(p).unwrap());
+    if let Some(path) = path.as_ref() {
+      if self
+        .flag_denied_list
+        .iter()
+        .any(|path_| path.starts_with(&path_.0))
+      {
+        return PermissionState::Denied;
+      }
+    } else if !self.flag_denied_list.is_empty() {
+      // full access can't be granted while some entries are denied
+      return PermissionState::Denied;
+    }
+    if self.global_state == PermissionState::Denied
+      && match path.as_ref() {
+        None => true,
+        Some(path) => self
+          .denied_list
+          .iter()
+          .any(|path_| path_.0.starts_with(path)),
+      }
+    {
+      PermissionState::Denied
+    } else if self.global_state == PermissionState::Granted
+      || match path.as_ref() {
+        None => false,
+        Some(path) => self
+          .granted_list
+          .iter()
+          .any(|path_| path.starts_with(&path_.0)),
+      }
+    {
+      PermissionState::Granted
+    } else {
+      PermissionState::Prompt
     }
   }
-}
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
-pub struct PermissionsOptions {
-  pub allow_env: Option<Vec<String>>,
-  pub allow_hrtime: bool,
-  pub allow_net: Option<Vec<String>>,
-  pub allow_ffi: Option<Vec<PathBuf>>,
-  pub allow_read: Option<Vec<PathBuf>>,
-  pub allow_run: Option<Vec<String>>,
-  pub allow_sys: Option<Vec<String>>,
-  pub allow_write: Option<Vec<PathBuf>>,
-  pub prompt: bool,
-}
+  pub fn request(&mut self, path: Option<&Path>) -> PermissionState {
+    if let Some(path) = path {
+      let (resolved_path, display_path) = resolved_and_display_path(path);
+      let state = self.query(Some(&resolved_path));
+      if state == PermissionState::Prompt {
+        match permission_prompt(
+          &format!("unix socket access to \"{}\"", display_path.display()),
+          self.name,
+          Some("Deno.permissions.query()"),
+          true,
+        ) {
+          PromptResponse::Allow => {
+            self.granted_list.insert(UnixDescriptor(resolved_path));
+            PermissionState::Granted
+          }
+          PromptResponse::Deny => {
+            self.denied_list.insert(UnixDescriptor(resolved_path));
+            self.global_state = PermissionState::Denied;
+            PermissionState::Denied
+          }
+          PromptResponse::AllowAll => {
+            self.granted_list.clear();
+            self.global_state = PermissionState::Granted;
+            PermissionState::Granted
+          }
+        }
+      } else if state == PermissionState::Granted {
+        self.granted_list.insert(UnixDescriptor(resolved_path));
+        PermissionState::Granted
+      } else {
+        state
+      }
+    } else {
+      let state = self.query(None);
+      if state == PermissionState::Prompt {
+        if PromptResponse::Allow
+          == permission_prompt(
+            "unix socket access",
+            self.name,
+            Some("Deno.permissions.query()"),
+            true,
+          )
+        {
+          self.granted_list.clear();
+          self.global_state = PermissionState::Granted;
+          PermissionState::Granted
+        } else {
+          self.global_state = PermissionState::Denied;
+          PermissionState::Denied
+        }
+      } else {
+        state
+      }
+    }
+  }
+
+  pub fn revoke(&mut self, path: Option<&Path>) -> PermissionState {
+    if let Some(path) = path {
+      let path = resolve_from_cwd(path).unwrap();
+      self
+        .granted_list
+        .retain(|path_| !path.starts_with(&path_.0));
+    } else {
+      self.granted_list.clear();
+    }
+    if self.global_state == PermissionState::Granted {
+      self.global_state = PermissionState::Prompt;
+    }
+    self.query(path)
+  }
+
+  pub fn check(
+    &mut self,
+    path: &Path,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    let (resolved_path, display_path) = resolved_and_display_path(path);
+    let (result, prompted, is_allow_all) =
+      self.query(Some(&resolved_path)).check(
+        self.name,
+        api_name,
+        Some(&format!("\"{}\"", display_path.display())),
+        self.prompt,
+      );
+
+    if prompted {
+      if result.is_ok() {
+        if is_allow_all {
+          self.granted_list.clear();
+          self.global_state = PermissionState::Granted;
+        } else {
+          self.granted_list.insert(UnixDescriptor(resolved_path));
+        }
+      } else {
+        self.denied_list.insert(UnixDescriptor(resolved_path));
+        self.global_state = PermissionState::Denied;
+      }
+    }
+
+    result
+  }
+
+  pub fn check_all(&mut self) -> Result<(), AnyError> {
+    let (result, prompted, _) =
+      self
+        .query(None)
+        .check(self.name, None, Some("all"), self.prompt);
+    if prompted {
+      if result.is_ok() {
+        self.global_state = PermissionState::Granted;
+      } else {
+        self.global_state = PermissionState::Denied;
+      }
+    }
+    result
+  }
+}
+
+impl Default for UnaryPermission<UnixDescriptor> {
+  fn default() -> Self {
+    UnaryPermission::<UnixDescriptor> {
+      name: "unix",
+      description: "connect to or listen on a unix socket",
+      global_state: Default::default(),
+      granted_list: Default::default(),
+      denied_list: Default::default(),
+      flag_denied_list: Default::default(),
+      prompt: false,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Permissions {
+  pub read: UnaryPermission<ReadDescriptor>,
+  pub write: UnaryPermission<WriteDescriptor>,
+  pub net: UnaryPermission<NetDescriptor>,
+  pub udp: UnaryPermission<NetDescriptor>,
+  pub env: UnaryPermission<EnvDescriptor>,
+  pub sys: UnaryPermission<SysDescriptor>,
+  pub run: UnaryPermission<RunDescriptor>,
+  pub run_args: RunArgsAllowlist,
+  pub ffi: UnaryPermission<FfiDescriptor>,
+  pub unix: UnaryPermission<UnixDescriptor>,
+  pub hrtime: UnitPermission,
+}
+
+impl Default for Permissions {
+  fn default() -> Self {
+    Self {
+      read: Permissions::new_read(&None, false).unwrap(),
+      write: Permissions::new_write(&None, false).unwrap(),
+      net: Permissions::new_net(&None, false).unwrap(),
+      udp: Permissions::new_udp(&None, false).unwrap(),
+      env: Permissions::new_env(&None, false).unwrap(),
+      sys: Permissions::new_sys(&None, false).unwrap(),
+      run: Permissions::new_run(&None, false).unwrap(),
+      run_args: Default::default(),
+      ffi: Permissions::new_ffi(&None, false).unwrap(),
+      unix: Permissions::new_unix(&None, false).unwrap(),
+      hrtime: Permissions::new_hrtime(false),
+    }
+  }
+}
+
+/// Environment variable used to pass a narrowed set of permissions, serialized
+/// as `PermissionsOptions`, down to a spawned `deno` subprocess. The
+/// subprocess can't be granted more than the permissions it describes.
+pub const INHERITED_PERMISSIONS_ENV_VAR_NAME: &str =
+  "DENO_INHERITED_PERMISSIONS";
+
+#[derive(Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct PermissionsOptions {
+  pub allow_env: Option<Vec<String>>,
+  pub allow_hrtime: bool,
+  pub allow_net: Option<Vec<String>>,
+  #[serde(default)]
+  pub allow_udp: Option<Vec<String>>,
+  pub allow_ffi: Option<Vec<PathBuf>>,
+  pub allow_read: Option<Vec<PathBuf>>,
+  pub allow_run: Option<Vec<String>>,
+  pub allow_sys: Option<Vec<String>>,
+  pub allow_write: Option<Vec<PathBuf>>,
+  #[serde(default)]
+  pub allow_unix: Option<Vec<PathBuf>>,
+  #[serde(default)]
+  pub deny_env: Option<Vec<String>>,
+  #[serde(default)]
+  pub deny_net: Option<Vec<String>>,
+  #[serde(default)]
+  pub deny_udp: Option<Vec<String>>,
+  #[serde(default)]
+  pub deny_ffi: Option<Vec<PathBuf>>,
+  #[serde(default)]
+  pub deny_read: Option<Vec<PathBuf>>,
+  #[serde(default)]
+  pub deny_run: Option<Vec<String>>,
+  #[serde(default)]
+  pub deny_sys: Option<Vec<String>>,
+  #[serde(default)]
+  pub deny_write: Option<Vec<PathBuf>>,
+  #[serde(default)]
+  pub deny_unix: Option<Vec<PathBuf>>,
+  pub prompt: bool,
+}
 
 impl Permissions {
   pub fn new_read(
@@ -1620,6 +2298,19 @@ impl Permissions {
     })
   }
 
+  /// UDP sockets use the same host descriptors as the net permission, but are
+  /// granted separately from it.
+  pub fn new_udp(
+    state: &Option<Vec<String>>,
+    prompt: bool,
+  ) -> Result<UnaryPermission<NetDescriptor>, AnyError> {
+    Ok(UnaryPermission::<NetDescriptor> {
+      name: "udp",
+      description: "UDP",
+      ..Permissions::new_net(state, prompt)?
+    })
+  }
+
   pub fn new_env(
     state: &Option<Vec<String>>,
     prompt: bool,
@@ -1681,10 +2372,11 @@ impl Permissions {
         .map(|v| {
           v.iter()
             .map(|x| {
-              if x.is_empty() {
+              let (cmd, _) = split_run_allow_entry(x);
+              if cmd.is_empty() {
                 Err(AnyError::msg("Empty path is not allowed"))
               } else {
-                Ok(RunDescriptor::from_str(x).unwrap())
+                Ok(RunDescriptor::from_str(cmd).unwrap())
               }
             })
             .collect()
@@ -1707,6 +2399,18 @@ impl Permissions {
     })
   }
 
+  pub fn new_unix(
+    state: &Option<Vec<PathBuf>>,
+    prompt: bool,
+  ) -> Result<UnaryPermission<UnixDescriptor>, AnyError> {
+    Ok(UnaryPermission::<UnixDescriptor> {
+      global_state: global_state_from_option(state),
+      granted_list: resolve_unix_allowlist(state)?,
+      prompt,
+      ..Default::default()
+    })
+  }
+
   pub fn new_hrtime(state: bool) -> UnitPermission {
     unit_permission_from_flag_bool(
       state,
@@ -1717,16 +2421,39 @@ impl Permissions {
   }
 
   pub fn from_options(opts: &PermissionsOptions) -> Result<Self, AnyError> {
-    Ok(Self {
+    let mut perms = Self {
       read: Permissions::new_read(&opts.allow_read, opts.prompt)?,
       write: Permissions::new_write(&opts.allow_write, opts.prompt)?,
       net: Permissions::new_net(&opts.allow_net, opts.prompt)?,
+      udp: Permissions::new_udp(&opts.allow_udp, opts.prompt)?,
       env: Permissions::new_env(&opts.allow_env, opts.prompt)?,
       sys: Permissions::new_sys(&opts.allow_sys, opts.prompt)?,
       run: Permissions::new_run(&opts.allow_run, opts.prompt)?,
+      run_args: RunArgsAllowlist::from_allowlist(&opts.allow_run),
       ffi: Permissions::new_ffi(&opts.allow_ffi, opts.prompt)?,
+      unix: Permissions::new_unix(&opts.allow_unix, opts.prompt)?,
       hrtime: Permissions::new_hrtime(opts.allow_hrtime),
-    })
+    };
+    perms.read.flag_denied_list =
+      Permissions::new_read(&opts.deny_read, false)?.granted_list;
+    perms.write.flag_denied_list =
+      Permissions::new_write(&opts.deny_write, false)?.granted_list;
+    perms.net.flag_denied_list =
+      Permissions::new_net(&opts.deny_net, false)?.granted_list;
+    perms.udp.flag_denied_list =
+      Permissions::new_udp(&opts.deny_udp, false)?.granted_list;
+    perms.env.flag_denied_list =
+      Permissions::new_env(&opts.deny_env, false)?.granted_list;
+    perms.sys.flag_denied_list =
+      Permissions::new_sys(&opts.deny_sys, false)?.granted_list;
+    perms.run.flag_denied_list = with_resolved_run_names(
+      Permissions::new_run(&opts.deny_run, false)?.granted_list,
+    );
+    perms.ffi.flag_denied_list =
+      Permissions::new_ffi(&opts.deny_ffi, false)?.granted_list;
+    perms.unix.flag_denied_list =
+      Permissions::new_unix(&opts.deny_unix, false)?.granted_list;
+    Ok(perms)
   }
 
   pub fn allow_all() -> Self {
@@ -1734,14 +2461,53 @@ impl Permissions {
       read: Permissions::new_read(&Some(vec![]), false).unwrap(),
       write: Permissions::new_write(&Some(vec![]), false).unwrap(),
       net: Permissions::new_net(&Some(vec![]), false).unwrap(),
+      udp: Permissions::new_udp(&Some(vec![]), false).unwrap(),
       env: Permissions::new_env(&Some(vec![]), false).unwrap(),
       sys: Permissions::new_sys(&Some(vec![]), false).unwrap(),
       run: Permissions::new_run(&Some(vec![]), false).unwrap(),
+      run_args: Default::default(),
       ffi: Permissions::new_ffi(&Some(vec![]), false).unwrap(),
+      unix: Permissions::new_unix(&Some(vec![]), false).unwrap(),
       hrtime: Permissions::new_hrtime(true),
     }
   }
 
+  /// Describes the currently granted permissions as options, so they can be
+  /// passed down to a `deno` subprocess. Permissions that are only
+  /// promptable are not granted.
+  pub fn to_options(&self) -> PermissionsOptions {
+    let run_entries = |desc: &RunDescriptor| match self.run_args.0.get(desc) {
+      Some(patterns) => patterns
+        .iter()
+        .map(|pattern| desc.to_string() + ":" + &pattern.join(" "))
+        .collect(),
+      None => vec![desc.to_string()],
+    };
+    PermissionsOptions {
+      allow_env: granted_entries(&self.env, |d| d.0.as_ref().to_string()),
+      allow_hrtime: self.hrtime.state == PermissionState::Granted,
+      allow_net: granted_entries(&self.net, |d| d.to_string()),
+      allow_udp: granted_entries(&self.udp, |d| d.to_string()),
+      allow_ffi: granted_entries(&self.ffi, |d| d.0.clone()),
+      allow_read: granted_entries(&self.read, |d| d.0.clone()),
+      allow_run: granted_entries(&self.run, run_entries)
+        .map(|entries| entries.into_iter().flatten().collect()),
+      allow_sys: granted_entries(&self.sys, |d| d.0.clone()),
+      allow_write: granted_entries(&self.write, |d| d.0.clone()),
+      allow_unix: granted_entries(&self.unix, |d| d.0.clone()),
+      deny_env: flag_denied_entries(&self.env, |d| d.0.as_ref().to_string()),
+      deny_net: flag_denied_entries(&self.net, |d| d.to_string()),
+      deny_udp: flag_denied_entries(&self.udp, |d| d.to_string()),
+      deny_ffi: flag_denied_entries(&self.ffi, |d| d.0.clone()),
+      deny_read: flag_denied_entries(&self.read, |d| d.0.clone()),
+      deny_run: flag_denied_entries(&self.run, |d| d.to_string()),
+      deny_sys: flag_denied_entries(&self.sys, |d| d.0.clone()),
+      deny_write: flag_denied_entries(&self.write, |d| d.0.clone()),
+      deny_unix: flag_denied_entries(&self.unix, |d| d.0.clone()),
+      prompt: false,
+    }
+  }
+
   /// A helper function that determines if the module specifier is a local or
   /// remote, and performs a read or net check for the specifier.
   pub fn check_specifier(
@@ -1757,6 +2523,8 @@ impl Permissions {
       },
       "data" => Ok(()),
       "blob" => Ok(()),
+      // resolved to a module on the registry, which is checked when fetched
+      "jsr" => Ok(()),
       _ => self.net.check_url(specifier, Some("import()")),
     }
   }
@@ -1840,9 +2608,12 @@ impl PermissionsContainer {
   pub fn check_run(
     &mut self,
     cmd: &str,
+    args: &[String],
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().run.check(cmd, Some(api_name))
+    let mut perms = self.0.lock();
+    perms.run.check(cmd, Some(api_name))?;
+    perms.run_args.check(cmd, args)
   }
 
   #[inline(always)]
@@ -1887,6 +2658,53 @@ impl deno_net::NetPermissions for PermissionsContainer {
     self.0.lock().net.check(host, Some(api_name))
   }
 
+  #[inline(always)]
+  fn check_udp<T: AsRef<str>>(
+    &mut self,
+    host: &(T, Option<u16>),
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().udp.check(host, Some(api_name))
+  }
+
+  #[inline(always)]
+  fn check_net_unresolved<T: AsRef<str>>(
+    &mut self,
+    host: &(T, Option<u16>),
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().net.check_unresolved(host, Some(api_name))
+  }
+
+  #[inline(always)]
+  fn check_udp_unresolved<T: AsRef<str>>(
+    &mut self,
+    host: &(T, Option<u16>),
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().udp.check_unresolved(host, Some(api_name))
+  }
+
+  #[inline(always)]
+  fn check_net_resolved<T: AsRef<str>>(
+    &mut self,
+    host: &(T, Option<u16>),
+    addr: &SocketAddr,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().net.check_resolved(host, addr, Some(api_name))
+  }
+
+  #[inline(always)]
+  fn check_udp_resolved<T: AsRef<str>>(
+    &mut self,
+    host: &(T, Option<u16>),
+    addr: &SocketAddr,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().udp.check_resolved(host, addr, Some(api_name))
+  }
+
   #[inline(always)]
   fn check_read(
     &mut self,
@@ -1904,6 +2722,15 @@ impl deno_net::NetPermissions for PermissionsContainer {
   ) -> Result<(), AnyError> {
     self.0.lock().write.check(path, Some(api_name))
   }
+
+  #[inline(always)]
+  fn check_unix(
+    &mut self,
+    path: &Path,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().unix.check(path, Some(api_name))
+  }
 }
 
 impl deno_fetch::FetchPermissions for PermissionsContainer {
@@ -1916,6 +2743,26 @@ impl deno_fetch::FetchPermissions for PermissionsContainer {
     self.0.lock().net.check_url(url, Some(api_name))
   }
 
+  #[inline(always)]
+  fn check_net_url_unresolved(
+    &mut self,
+    url: &url::Url,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().net.check_url_unresolved(url, Some(api_name))
+  }
+
+  fn resolved_addr_check(&self) -> deno_fetch::ResolvedAddrCheck {
+    let permissions = self.clone();
+    Arc::new(move |hostname, addr| {
+      permissions.0.lock().net.check_resolved_host(
+        hostname,
+        &addr.ip(),
+        Some("fetch()"),
+      )
+    })
+  }
+
   #[inline(always)]
   fn check_read(
     &mut self,
@@ -1945,7 +2792,22 @@ impl deno_websocket::WebSocketPermissions for PermissionsContainer {
     url: &url::Url,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().net.check_url(url, Some(api_name))
+    self.0.lock().net.check_url_unresolved(url, Some(api_name))
+  }
+
+  #[inline(always)]
+  fn check_net_resolved(
+    &mut self,
+    url: &url::Url,
+    addr: &SocketAddr,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    let hostname = url.host_str().ok_or_else(|| uri_error("Missing host"))?;
+    self.0.lock().net.check_resolved(
+      &(hostname, url.port_or_known_default()),
+      addr,
+      Some(api_name),
+    )
   }
 }
 
@@ -2101,6 +2963,33 @@ pub fn resolve_ffi_allowlist(
   }
 }
 
+pub fn resolve_unix_allowlist(
+  allow: &Option<Vec<PathBuf>>,
+) -> Result<HashSet<UnixDescriptor>, AnyError> {
+  if let Some(v) = allow {
+    v.iter()
+      .map(|raw_path| {
+        if raw_path.as_os_str().is_empty() {
+          Err(AnyError::msg("Empty path is not allowed"))
+        } else {
+          resolve_from_cwd(Path::new(&raw_path)).map(UnixDescriptor)
+        }
+      })
+      .collect()
+  } else {
+    Ok(HashSet::new())
+  }
+}
+
+/// Checks a resolved path against a read or write allow or deny list entry.
+/// Plain entries cover the path and everything below it, while entries
+/// containing glob characters (eg. `./data/**/*.json`) are matched as patterns.
+#[inline]
+fn path_matches_entry(path: &Path, entry: &Path) -> bool {
+  path.starts_with(entry)
+    || (glob::is_glob_path(entry) && path_matches_glob(entry, path))
+}
+
 /// Arbitrary helper. Resolves the path from CWD, and also gets a path that
 /// can be displayed without leaking the CWD when not allowed.
 #[inline]
@@ -2110,6 +2999,76 @@ fn resolved_and_display_path(path: &Path) -> (PathBuf, PathBuf) {
   (resolved_path, display_path)
 }
 
+fn granted_entries<T: Eq + Hash, U>(
+  perm: &UnaryPermission<T>,
+  map: impl Fn(&T) -> U,
+) -> Option<Vec<U>> {
+  match perm.global_state {
+    PermissionState::Granted => Some(vec![]),
+    PermissionState::Prompt if !perm.granted_list.is_empty() => {
+      Some(perm.granted_list.iter().map(map).collect())
+    }
+    PermissionState::Prompt | PermissionState::Denied => None,
+  }
+}
+
+fn flag_denied_entries<T: Eq + Hash, U>(
+  perm: &UnaryPermission<T>,
+  map: impl Fn(&T) -> U,
+) -> Option<Vec<U>> {
+  if perm.flag_denied_list.is_empty() {
+    None
+  } else {
+    Some(perm.flag_denied_list.iter().map(map).collect())
+  }
+}
+
+/// Narrows the permissions described by `options` to the ones a parent
+/// process passed down through `INHERITED_PERMISSIONS_ENV_VAR_NAME`. When
+/// `options` grants nothing, all inherited permissions are used. Errors if
+/// `options` grants more than was inherited.
+pub fn narrow_to_inherited_permissions(
+  options: &PermissionsOptions,
+  inherited: &PermissionsOptions,
+) -> Result<PermissionsOptions, AnyError> {
+  let mut parent = Permissions::from_options(&PermissionsOptions {
+    prompt: false,
+    ..inherited.clone()
+  })?;
+  let child_permissions_arg = match ChildPermissionsArg::from_options(options) {
+    arg if arg == ChildPermissionsArg::none() => ChildPermissionsArg::inherit(),
+    arg => arg,
+  };
+  let mut narrowed =
+    create_child_permissions(&mut parent, child_permissions_arg)?.to_options();
+  extend_denied(&mut narrowed.deny_env, &options.deny_env);
+  extend_denied(&mut narrowed.deny_net, &options.deny_net);
+  extend_denied(&mut narrowed.deny_udp, &options.deny_udp);
+  extend_denied(&mut narrowed.deny_ffi, &options.deny_ffi);
+  extend_denied(&mut narrowed.deny_read, &options.deny_read);
+  extend_denied(&mut narrowed.deny_run, &options.deny_run);
+  extend_denied(&mut narrowed.deny_sys, &options.deny_sys);
+  extend_denied(&mut narrowed.deny_write, &options.deny_write);
+  extend_denied(&mut narrowed.deny_unix, &options.deny_unix);
+  // keep the inherited permissions from being changed for subprocesses
+  narrowed
+    .deny_env
+    .get_or_insert_with(Vec::new)
+    .push(INHERITED_PERMISSIONS_ENV_VAR_NAME.to_string());
+  Ok(narrowed)
+}
+
+fn extend_denied<T: Clone>(
+  denied: &mut Option<Vec<T>>,
+  extra: &Option<Vec<T>>,
+) {
+  if let Some(extra) = extra {
+    denied
+      .get_or_insert_with(Vec::new)
+      .extend(extra.iter().cloned());
+  }
+}
+
 fn escalation_error() -> AnyError {
   custom_error(
     "PermissionDenied",
@@ -2117,7 +3076,7 @@ fn escalation_error() -> AnyError {
   )
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ChildUnitPermissionArg {
   Inherit,
   Granted,
@@ -2169,7 +3128,7 @@ impl<'de> Deserialize<'de> for ChildUnitPermissionArg {
   }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ChildUnaryPermissionArg {
   Inherit,
   Granted,
@@ -2237,16 +3196,18 @@ impl<'de> Deserialize<'de> for ChildUnaryPermissionArg {
 }
 
 /// Directly deserializable from JS worker and test permission options.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ChildPermissionsArg {
   env: ChildUnaryPermissionArg,
   hrtime: ChildUnitPermissionArg,
   net: ChildUnaryPermissionArg,
+  udp: ChildUnaryPermissionArg,
   ffi: ChildUnaryPermissionArg,
   read: ChildUnaryPermissionArg,
   run: ChildUnaryPermissionArg,
   sys: ChildUnaryPermissionArg,
   write: ChildUnaryPermissionArg,
+  unix: ChildUnaryPermissionArg,
 }
 
 impl ChildPermissionsArg {
@@ -2255,11 +3216,13 @@ impl ChildPermissionsArg {
       env: ChildUnaryPermissionArg::Inherit,
       hrtime: ChildUnitPermissionArg::Inherit,
       net: ChildUnaryPermissionArg::Inherit,
+      udp: ChildUnaryPermissionArg::Inherit,
       ffi: ChildUnaryPermissionArg::Inherit,
       read: ChildUnaryPermissionArg::Inherit,
       run: ChildUnaryPermissionArg::Inherit,
       sys: ChildUnaryPermissionArg::Inherit,
       write: ChildUnaryPermissionArg::Inherit,
+      unix: ChildUnaryPermissionArg::Inherit,
     }
   }
 
@@ -2268,11 +3231,45 @@ impl ChildPermissionsArg {
       env: ChildUnaryPermissionArg::NotGranted,
       hrtime: ChildUnitPermissionArg::NotGranted,
       net: ChildUnaryPermissionArg::NotGranted,
+      udp: ChildUnaryPermissionArg::NotGranted,
       ffi: ChildUnaryPermissionArg::NotGranted,
       read: ChildUnaryPermissionArg::NotGranted,
       run: ChildUnaryPermissionArg::NotGranted,
       sys: ChildUnaryPermissionArg::NotGranted,
       write: ChildUnaryPermissionArg::NotGranted,
+      unix: ChildUnaryPermissionArg::NotGranted,
+    }
+  }
+  /// Describes the permissions granted by `options`.
+  fn from_options(options: &PermissionsOptions) -> Self {
+    fn unary<T>(
+      list: &Option<Vec<T>>,
+      to_string: impl Fn(&T) -> String,
+    ) -> ChildUnaryPermissionArg {
+      match list {
+        None => ChildUnaryPermissionArg::NotGranted,
+        Some(list) if list.is_empty() => ChildUnaryPermissionArg::Granted,
+        Some(list) => ChildUnaryPermissionArg::GrantedList(
+          list.iter().map(to_string).collect(),
+        ),
+      }
+    }
+    let path_to_string = |path: &PathBuf| path.to_string_lossy().to_string();
+    ChildPermissionsArg {
+      env: unary(&options.allow_env, ToString::to_string),
+      hrtime: if options.allow_hrtime {
+        ChildUnitPermissionArg::Granted
+      } else {
+        ChildUnitPermissionArg::NotGranted
+      },
+      net: unary(&options.allow_net, ToString::to_string),
+      udp: unary(&options.allow_udp, ToString::to_string),
+      ffi: unary(&options.allow_ffi, path_to_string),
+      read: unary(&options.allow_read, path_to_string),
+      run: unary(&options.allow_run, ToString::to_string),
+      sys: unary(&options.allow_sys, ToString::to_string),
+      write: unary(&options.allow_write, path_to_string),
+      unix: unary(&options.allow_unix, path_to_string),
     }
   }
 }
@@ -2333,6 +3330,11 @@ impl<'de> Deserialize<'de> for ChildPermissionsArg {
             child_permissions_arg.net = arg.map_err(|e| {
               de::Error::custom(format!("(deno.permissions.net) {e}"))
             })?;
+          } else if key == "udp" {
+            let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
+            child_permissions_arg.udp = arg.map_err(|e| {
+              de::Error::custom(format!("(deno.permissions.udp) {e}"))
+            })?;
           } else if key == "ffi" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.ffi = arg.map_err(|e| {
@@ -2358,6 +3360,11 @@ impl<'de> Deserialize<'de> for ChildPermissionsArg {
             child_permissions_arg.write = arg.map_err(|e| {
               de::Error::custom(format!("(deno.permissions.write) {e}"))
             })?;
+          } else if key == "unix" {
+            let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
+            child_permissions_arg.unix = arg.map_err(|e| {
+              de::Error::custom(format!("(deno.permissions.unix) {e}"))
+            })?;
           } else {
             return Err(de::Error::custom("unknown permission name"));
           }
@@ -2365,10 +3372,83 @@ impl<'de> Deserialize<'de> for ChildPermissionsArg {
         Ok(child_permissions_arg)
       }
     }
-    deserializer.deserialize_any(ChildPermissionsArgVisitor)
+    deserializer.deserialize_any(ChildPermissionsArgVisitor)
+  }
+}
+
+/// Permissions used for workers that are not explicitly configured through
+/// `deno.permissions`, along with named presets that can be referenced by
+/// passing their name, eg. `new Worker(url, { deno: { permissions: "plugin" } })`.
+/// `preload` lists modules that are evaluated in every worker before the
+/// modules passed through `deno.preload`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WorkerPermissionsConfig {
+  pub default: Option<ChildPermissionsArg>,
+  pub presets: HashMap<String, ChildPermissionsArg>,
+  pub preload: Vec<ModuleSpecifier>,
+}
+
+impl WorkerPermissionsConfig {
+  /// Resolves the permissions for a new worker. `None` means the worker
+  /// should inherit the permissions of its parent.
+  pub fn resolve(
+    &self,
+    arg: Option<WorkerPermissionsArg>,
+  ) -> Result<Option<ChildPermissionsArg>, AnyError> {
+    match arg {
+      Some(WorkerPermissionsArg::Permissions(arg)) => Ok(Some(*arg)),
+      Some(WorkerPermissionsArg::Preset(name)) => {
+        match self.presets.get(&name) {
+          Some(arg) => Ok(Some(arg.clone())),
+          None => Err(type_error(format!(
+            "Unknown worker permissions preset \"{name}\""
+          ))),
+        }
+      }
+      None => Ok(self.default.clone()),
+    }
+  }
+}
+
+/// Permissions passed to a new worker. A string other than `"inherit"` or
+/// `"none"` references a preset from the `WorkerPermissionsConfig`.
+#[derive(Debug, Eq, PartialEq)]
+pub enum WorkerPermissionsArg {
+  Permissions(Box<ChildPermissionsArg>),
+  Preset(String),
+}
+
+impl<'de> Deserialize<'de> for WorkerPermissionsArg {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    match value {
+      serde_json::Value::String(name)
+        if name != "inherit" && name != "none" =>
+      {
+        Ok(WorkerPermissionsArg::Preset(name))
+      }
+      value => serde_json::from_value::<ChildPermissionsArg>(value)
+        .map(|arg| WorkerPermissionsArg::Permissions(Box::new(arg)))
+        .map_err(de::Error::custom),
+    }
   }
 }
 
+/// Checks that all the descriptors of `child_permissions_arg` are valid,
+/// without checking them against any parent permissions.
+pub fn validate_child_permissions(
+  child_permissions_arg: &ChildPermissionsArg,
+) -> Result<(), AnyError> {
+  create_child_permissions(
+    &mut Permissions::allow_all(),
+    child_permissions_arg.clone(),
+  )
+  .map(|_| ())
+}
+
 pub fn create_child_permissions(
   main_perms: &mut Permissions,
   child_permissions_arg: ChildPermissionsArg,
@@ -2399,6 +3479,7 @@ pub fn create_child_permissions(
     }
   }
   worker_perms.env.denied_list = main_perms.env.denied_list.clone();
+  worker_perms.env.flag_denied_list = main_perms.env.flag_denied_list.clone();
   if main_perms.env.global_state == PermissionState::Denied {
     worker_perms.env.global_state = PermissionState::Denied;
   }
@@ -2428,6 +3509,7 @@ pub fn create_child_permissions(
     }
   }
   worker_perms.sys.denied_list = main_perms.sys.denied_list.clone();
+  worker_perms.sys.flag_denied_list = main_perms.sys.flag_denied_list.clone();
   if main_perms.sys.global_state == PermissionState::Denied {
     worker_perms.sys.global_state = PermissionState::Denied;
   }
@@ -2473,10 +3555,41 @@ pub fn create_child_permissions(
     }
   }
   worker_perms.net.denied_list = main_perms.net.denied_list.clone();
+  worker_perms.net.flag_denied_list = main_perms.net.flag_denied_list.clone();
   if main_perms.net.global_state == PermissionState::Denied {
     worker_perms.net.global_state = PermissionState::Denied;
   }
   worker_perms.net.prompt = main_perms.net.prompt;
+  match child_permissions_arg.udp {
+    ChildUnaryPermissionArg::Inherit => {
+      worker_perms.udp = main_perms.udp.clone();
+    }
+    ChildUnaryPermissionArg::Granted => {
+      if main_perms.udp.check_all().is_err() {
+        return Err(escalation_error());
+      }
+      worker_perms.udp.global_state = PermissionState::Granted;
+    }
+    ChildUnaryPermissionArg::NotGranted => {}
+    ChildUnaryPermissionArg::GrantedList(granted_list) => {
+      worker_perms.udp.granted_list =
+        Permissions::new_udp(&Some(granted_list), false)?.granted_list;
+      if !worker_perms
+        .udp
+        .granted_list
+        .iter()
+        .all(|desc| main_perms.udp.check(&(&desc.0, desc.1), None).is_ok())
+      {
+        return Err(escalation_error());
+      }
+    }
+  }
+  worker_perms.udp.denied_list = main_perms.udp.denied_list.clone();
+  worker_perms.udp.flag_denied_list = main_perms.udp.flag_denied_list.clone();
+  if main_perms.udp.global_state == PermissionState::Denied {
+    worker_perms.udp.global_state = PermissionState::Denied;
+  }
+  worker_perms.udp.prompt = main_perms.udp.prompt;
   match child_permissions_arg.ffi {
     ChildUnaryPermissionArg::Inherit => {
       worker_perms.ffi = main_perms.ffi.clone();
@@ -2505,10 +3618,44 @@ pub fn create_child_permissions(
     }
   }
   worker_perms.ffi.denied_list = main_perms.ffi.denied_list.clone();
+  worker_perms.ffi.flag_denied_list = main_perms.ffi.flag_denied_list.clone();
   if main_perms.ffi.global_state == PermissionState::Denied {
     worker_perms.ffi.global_state = PermissionState::Denied;
   }
   worker_perms.ffi.prompt = main_perms.ffi.prompt;
+  match child_permissions_arg.unix {
+    ChildUnaryPermissionArg::Inherit => {
+      worker_perms.unix = main_perms.unix.clone();
+    }
+    ChildUnaryPermissionArg::Granted => {
+      if main_perms.unix.check_all().is_err() {
+        return Err(escalation_error());
+      }
+      worker_perms.unix.global_state = PermissionState::Granted;
+    }
+    ChildUnaryPermissionArg::NotGranted => {}
+    ChildUnaryPermissionArg::GrantedList(granted_list) => {
+      worker_perms.unix.granted_list = Permissions::new_unix(
+        &Some(granted_list.iter().map(PathBuf::from).collect()),
+        false,
+      )?
+      .granted_list;
+      if !worker_perms
+        .unix
+        .granted_list
+        .iter()
+        .all(|desc| main_perms.unix.check(&desc.0, None).is_ok())
+      {
+        return Err(escalation_error());
+      }
+    }
+  }
+  worker_perms.unix.denied_list = main_perms.unix.denied_list.clone();
+  worker_perms.unix.flag_denied_list = main_perms.unix.flag_denied_list.clone();
+  if main_perms.unix.global_state == PermissionState::Denied {
+    worker_perms.unix.global_state = PermissionState::Denied;
+  }
+  worker_perms.unix.prompt = main_perms.unix.prompt;
   match child_permissions_arg.read {
     ChildUnaryPermissionArg::Inherit => {
       worker_perms.read = main_perms.read.clone();
@@ -2537,10 +3684,12 @@ pub fn create_child_permissions(
     }
   }
   worker_perms.read.denied_list = main_perms.read.denied_list.clone();
+  worker_perms.read.flag_denied_list = main_perms.read.flag_denied_list.clone();
   if main_perms.read.global_state == PermissionState::Denied {
     worker_perms.read.global_state = PermissionState::Denied;
   }
   worker_perms.read.prompt = main_perms.read.prompt;
+  worker_perms.run_args = main_perms.run_args.clone();
   match child_permissions_arg.run {
     ChildUnaryPermissionArg::Inherit => {
       worker_perms.run = main_perms.run.clone();
@@ -2553,8 +3702,12 @@ pub fn create_child_permissions(
     }
     ChildUnaryPermissionArg::NotGranted => {}
     ChildUnaryPermissionArg::GrantedList(granted_list) => {
+      let granted_list = Some(granted_list);
+      worker_perms.run_args = main_perms
+        .run_args
+        .restrict_child(RunArgsAllowlist::from_allowlist(&granted_list))?;
       worker_perms.run.granted_list =
-        Permissions::new_run(&Some(granted_list), false)?.granted_list;
+        Permissions::new_run(&granted_list, false)?.granted_list;
       if !worker_perms
         .run
         .granted_list
@@ -2566,6 +3719,7 @@ pub fn create_child_permissions(
     }
   }
   worker_perms.run.denied_list = main_perms.run.denied_list.clone();
+  worker_perms.run.flag_denied_list = main_perms.run.flag_denied_list.clone();
   if main_perms.run.global_state == PermissionState::Denied {
     worker_perms.run.global_state = PermissionState::Denied;
   }
@@ -2598,6 +3752,8 @@ pub fn create_child_permissions(
     }
   }
   worker_perms.write.denied_list = main_perms.write.denied_list.clone();
+  worker_perms.write.flag_denied_list =
+    main_perms.write.flag_denied_list.clone();
   if main_perms.write.global_state == PermissionState::Denied {
     worker_perms.write.global_state = PermissionState::Denied;
   }
@@ -2718,6 +3874,383 @@ mod tests {
     assert!(perms.ffi.check(Some(Path::new("/a/b"))).is_err());
   }
 
+  #[test]
+  fn check_glob_paths() {
+    set_prompter(Box::new(TestPrompter));
+    let allowlist = vec![
+      PathBuf::from("/data/**/*.json"),
+      PathBuf::from("/tmp/app-*"),
+    ];
+
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(allowlist.clone()),
+      allow_write: Some(allowlist),
+      ..Default::default()
+    })
+    .unwrap();
+
+    assert!(perms.read.check(Path::new("/data/a.json"), None).is_ok());
+    assert!(perms
+      .read
+      .check(Path::new("/data/x/y/a.json"), None)
+      .is_ok());
+    assert!(perms
+      .read
+      .check(Path::new("/data/x/y/a.txt"), None)
+      .is_err());
+    assert!(perms.write.check(Path::new("/tmp/app-1"), None).is_ok());
+    assert!(perms.write.check(Path::new("/tmp/app-1/log"), None).is_ok());
+    assert!(perms.write.check(Path::new("/tmp/other"), None).is_err());
+    assert_eq!(
+      perms.read.query(Some(Path::new("/data/b.json"))),
+      PermissionState::Granted
+    );
+    assert_eq!(
+      perms.read.query(Some(Path::new("/data"))),
+      PermissionState::Prompt
+    );
+  }
+
+  #[test]
+  fn check_flag_denied() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![]),
+      allow_net: Some(vec![]),
+      allow_env: Some(vec![]),
+      allow_run: Some(vec![]),
+      deny_read: Some(vec![PathBuf::from("/secrets")]),
+      deny_net: Some(svec!["evil.com", "deno.land:8000"]),
+      deny_env: Some(svec!["AWS_SECRET_ACCESS_KEY"]),
+      deny_run: Some(svec!["curl"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    assert!(perms.read.check(Path::new("/secrets"), None).is_err());
+    assert!(perms.read.check(Path::new("/secrets/key"), None).is_err());
+    assert!(perms.read.check(Path::new("/public"), None).is_ok());
+    assert!(perms.net.check(&("evil.com", Some(443)), None).is_err());
+    assert!(perms.net.check(&("deno.land", Some(8000)), None).is_err());
+    assert!(perms.net.check(&("deno.land", Some(443)), None).is_ok());
+    assert!(perms.env.check("AWS_SECRET_ACCESS_KEY").is_err());
+    assert!(perms.env.check("HOME").is_ok());
+    assert!(perms.run.check("curl", None).is_err());
+    assert!(perms.run.check("git", None).is_ok());
+
+    // denied entries can't be reached through access to everything
+    assert!(perms.read.check_all(None).is_err());
+    assert!(perms.net.check_all().is_err());
+    assert!(perms.env.check_all().is_err());
+    assert!(perms.run.check_all(None).is_err());
+    assert_eq!(perms.env.query(None), PermissionState::Denied);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn check_flag_denied_run_resolves_path() {
+    set_prompter(Box::new(TestPrompter));
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.write("curl", "");
+    let curl_path = temp_dir.path().join("curl");
+    let curl = curl_path.to_str().unwrap();
+    let original_path = std::env::var_os("PATH");
+    std::env::set_var("PATH", temp_dir.path());
+
+    // denying a name also denies the path it resolves to
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_run: Some(vec![]),
+      deny_run: Some(svec!["curl"]),
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(perms.run.check("curl", None).is_err());
+    assert!(perms.run.check(curl, None).is_err());
+    assert!(perms.run.check("git", None).is_ok());
+
+    // denying a path also denies names that resolve to it
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_run: Some(vec![]),
+      deny_run: Some(vec![curl.to_string()]),
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(perms.run.check(curl, None).is_err());
+    assert!(perms.run.check("curl", None).is_err());
+    assert!(perms.run.check("/usr/bin/git", None).is_ok());
+
+    match original_path {
+      Some(path) => std::env::set_var("PATH", path),
+      None => std::env::remove_var("PATH"),
+    }
+  }
+
+  fn to_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(ToString::to_string).collect()
+  }
+
+  #[test]
+  fn check_run_args() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_run: Some(svec![
+        "git:status",
+        "git:log --oneline *",
+        "ls",
+        "cat:*.txt"
+      ]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    assert!(perms.run.check("git", None).is_ok());
+    assert!(perms.run.check("ls", None).is_ok());
+    assert!(perms.run.check("git:status", None).is_err());
+    let run_args = &perms.run_args;
+    assert!(run_args.check("git", &to_args(&["status"])).is_ok());
+    // extra arguments need a trailing `*` in the pattern
+    assert!(run_args.check("git", &to_args(&["status", "-s"])).is_err());
+    assert!(run_args
+      .check("git", &to_args(&["log", "--oneline"]))
+      .is_ok());
+    assert!(run_args
+      .check("git", &to_args(&["log", "--oneline", "-n", "5"]))
+      .is_ok());
+    assert!(run_args.check("git", &to_args(&["log"])).is_err());
+    assert!(run_args.check("git", &to_args(&["push"])).is_err());
+    assert!(run_args.check("git", &[]).is_err());
+    assert!(run_args.check("ls", &to_args(&["-la"])).is_ok());
+    assert!(run_args.check("cat", &to_args(&["notes.txt"])).is_ok());
+    assert!(run_args.check("cat", &to_args(&["/etc/passwd"])).is_err());
+    assert!(run_args
+      .check("cat", &to_args(&["notes.txt", "/etc/passwd"]))
+      .is_err());
+
+    // a plain entry lifts the restriction
+    let perms = Permissions::from_options(&PermissionsOptions {
+      allow_run: Some(svec!["git:status", "git"]),
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(perms.run_args.check("git", &to_args(&["push"])).is_ok());
+  }
+
+  #[test]
+  fn test_narrow_to_inherited_permissions() {
+    set_prompter(Box::new(TestPrompter));
+    let inherited = PermissionsOptions {
+      allow_read: Some(vec![PathBuf::from("/data")]),
+      allow_run: Some(svec!["git:status"]),
+      deny_read: Some(vec![PathBuf::from("/data/secrets")]),
+      ..Default::default()
+    };
+
+    // no permissions requested uses everything that was inherited
+    let narrowed =
+      narrow_to_inherited_permissions(&Default::default(), &inherited).unwrap();
+    let mut perms = Permissions::from_options(&narrowed).unwrap();
+    assert!(perms.read.check(Path::new("/data/a.txt"), None).is_ok());
+    assert!(perms
+      .read
+      .check(Path::new("/data/secrets/key"), None)
+      .is_err());
+    assert!(perms.read.check(Path::new("/other"), None).is_err());
+    assert!(perms.run_args.check("git", &to_args(&["push"])).is_err());
+    assert!(perms.env.check(INHERITED_PERMISSIONS_ENV_VAR_NAME).is_err());
+
+    // narrower permissions are fine
+    let narrowed = narrow_to_inherited_permissions(
+      &PermissionsOptions {
+        allow_read: Some(vec![PathBuf::from("/data/public")]),
+        ..Default::default()
+      },
+      &inherited,
+    )
+    .unwrap();
+    let mut perms = Permissions::from_options(&narrowed).unwrap();
+    assert!(perms.read.check(Path::new("/data/public/a"), None).is_ok());
+    assert!(perms.read.check(Path::new("/data/a.txt"), None).is_err());
+    assert!(perms.run.check("git", None).is_err());
+
+    // but can't escalate
+    assert!(narrow_to_inherited_permissions(
+      &PermissionsOptions {
+        allow_read: Some(vec![]),
+        ..Default::default()
+      },
+      &inherited,
+    )
+    .is_err());
+
+    // argument restrictions are kept
+    let narrowed = narrow_to_inherited_permissions(
+      &PermissionsOptions {
+        allow_run: Some(svec!["git"]),
+        ..Default::default()
+      },
+      &inherited,
+    )
+    .unwrap();
+    let perms = Permissions::from_options(&narrowed).unwrap();
+    assert!(perms.run_args.check("git", &to_args(&["status"])).is_ok());
+    assert!(perms.run_args.check("git", &to_args(&["push"])).is_err());
+  }
+
+  #[test]
+  fn test_check_net_cidr() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["10.0.0.0/8", "192.168.1.0/24:443", "[fd00::]/8"]),
+      deny_net: Some(svec!["10.1.0.0/16"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let domain_tests = vec![
+      ("10.0.0.1", 80, true),
+      ("10.255.1.2", 8080, true),
+      ("10.1.2.3", 80, false),
+      ("11.0.0.1", 80, false),
+      ("192.168.1.20", 443, true),
+      ("192.168.1.20", 80, false),
+      ("192.168.2.20", 443, false),
+      ("[fd12::1]", 80, true),
+      ("[fe80::1]", 80, false),
+    ];
+
+    for (host, port, is_ok) in domain_tests {
+      assert_eq!(
+        is_ok,
+        perms.net.check(&(host, Some(port)), None).is_ok(),
+        "{host}:{port}"
+      );
+    }
+
+    assert!(Permissions::new_net(&Some(svec!["10.0.0.0/33"]), false).is_err());
+  }
+
+  #[test]
+  fn test_check_net_cidr_resolved() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["10.0.0.0/8", "deno.land"]),
+      deny_net: Some(svec!["10.1.0.0/16", "192.168.0.0/16"]),
+      ..Default::default()
+    })
+    .unwrap();
+    let addr = |s: &str| s.parse::<SocketAddr>().unwrap();
+
+    // hostnames aren't resolved to check them against the ranges
+    assert!(perms.net.check(&("internal.test", Some(80)), None).is_err());
+    assert!(perms
+      .net
+      .check_resolved(&("internal.test", Some(80)), &addr("10.0.0.1:80"), None)
+      .is_ok());
+    assert!(perms
+      .net
+      .check_resolved(&("internal.test", Some(80)), &addr("10.1.0.1:80"), None)
+      .is_err());
+    assert!(perms
+      .net
+      .check_resolved(&("internal.test", Some(80)), &addr("1.1.1.1:80"), None)
+      .is_err());
+    // a host allowed by name can't connect to a denied range
+    assert!(perms
+      .net
+      .check_resolved(&("deno.land", Some(443)), &addr("1.1.1.1:443"), None)
+      .is_ok());
+    assert!(perms
+      .net
+      .check_resolved(&("deno.land", Some(443)), &addr("192.168.0.1:443"), None)
+      .is_err());
+
+    // host names that CIDR entries could grant are checked once resolved
+    assert!(perms
+      .net
+      .check_unresolved(&("internal.test", Some(80)), None)
+      .is_ok());
+    assert!(perms
+      .net
+      .check_unresolved(&("deno.land", Some(443)), None)
+      .is_ok());
+    assert!(perms
+      .net
+      .check_unresolved(&("1.1.1.1", Some(80)), None)
+      .is_err());
+    assert!(perms
+      .net
+      .check_url_unresolved(
+        &url::Url::parse("https://internal.test").unwrap(),
+        None
+      )
+      .is_ok());
+
+    let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+    assert!(perms
+      .net
+      .check_resolved_host("internal.test", &ip("10.0.0.1"), None)
+      .is_ok());
+    assert!(perms
+      .net
+      .check_resolved_host("internal.test", &ip("1.1.1.1"), None)
+      .is_err());
+    assert!(perms
+      .net
+      .check_resolved_host("deno.land", &ip("1.1.1.1"), None)
+      .is_ok());
+    assert!(perms
+      .net
+      .check_resolved_host("deno.land", &ip("192.168.0.1"), None)
+      .is_err());
+  }
+
+  #[test]
+  fn test_check_net_unresolved_without_cidr() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["deno.land"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    // without CIDR entries, host names are only checked by name
+    assert!(perms
+      .net
+      .check_unresolved(&("deno.land", Some(443)), None)
+      .is_ok());
+    assert!(perms
+      .net
+      .check_unresolved(&("example.com", Some(443)), None)
+      .is_err());
+    assert!(perms
+      .net
+      .check_resolved_host("deno.land", &"1.1.1.1".parse().unwrap(), None)
+      .is_ok());
+  }
+
+  #[test]
+  fn test_check_env_glob() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_env: Some(svec!["AWS_*", "DENO_*", "HOME"]),
+      deny_env: Some(svec!["AWS_SECRET_*"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    assert!(perms.env.check("AWS_REGION").is_ok());
+    assert!(perms.env.check("DENO_DIR").is_ok());
+    assert!(perms.env.check("HOME").is_ok());
+    assert!(perms.env.check("AWS_SECRET_ACCESS_KEY").is_err());
+    assert!(perms.env.check("PATH").is_err());
+    assert!(perms.env.check("MY_AWS_REGION").is_err());
+    assert_eq!(
+      perms.env.query(Some("AWS_PROFILE")),
+      PermissionState::Granted
+    );
+    assert_eq!(perms.env.query(None), PermissionState::Denied);
+  }
+
   #[test]
   fn test_check_net_with_values() {
     set_prompter(Box::new(TestPrompter));
@@ -2984,11 +4517,20 @@ mod tests {
         ..Permissions::new_ffi(&Some(vec![PathBuf::from("/foo")]), false)
           .unwrap()
       },
+      unix: UnaryPermission {
+        global_state: PermissionState::Prompt,
+        ..Permissions::new_unix(&Some(vec![PathBuf::from("/foo")]), false)
+          .unwrap()
+      },
 
       net: UnaryPermission {
         global_state: PermissionState::Prompt,
         ..Permissions::new_net(&Some(svec!["127.0.0.1:8000"]), false).unwrap()
       },
+      udp: UnaryPermission {
+        global_state: PermissionState::Prompt,
+        ..Permissions::new_udp(&Some(svec!["127.0.0.1:53"]), false).unwrap()
+      },
       env: UnaryPermission {
         global_state: PermissionState::Prompt,
         ..Permissions::new_env(&Some(svec!["HOME"]), false).unwrap()
@@ -3001,6 +4543,7 @@ mod tests {
         global_state: PermissionState::Prompt,
         ..Permissions::new_run(&Some(svec!["deno"]), false).unwrap()
       },
+      run_args: Default::default(),
       hrtime: UnitPermission {
         state: PermissionState::Prompt,
         ..Permissions::new_hrtime(false)
@@ -3023,10 +4566,20 @@ mod tests {
       assert_eq!(perms2.ffi.query(None), PermissionState::Prompt);
       assert_eq!(perms2.ffi.query(Some(Path::new("/foo"))), PermissionState::Granted);
       assert_eq!(perms2.ffi.query(Some(Path::new("/foo/bar"))), PermissionState::Granted);
+      assert_eq!(perms1.unix.query(None), PermissionState::Granted);
+      assert_eq!(perms1.unix.query(Some(Path::new("/foo"))), PermissionState::Granted);
+      assert_eq!(perms2.unix.query(None), PermissionState::Prompt);
+      assert_eq!(perms2.unix.query(Some(Path::new("/foo"))), PermissionState::Granted);
+      assert_eq!(perms2.unix.query(Some(Path::new("/foo/bar"))), PermissionState::Granted);
+      assert_eq!(perms2.unix.query(Some(Path::new("/bar"))), PermissionState::Prompt);
       assert_eq!(perms1.net.query::<&str>(None), PermissionState::Granted);
       assert_eq!(perms1.net.query(Some(&("127.0.0.1", None))), PermissionState::Granted);
       assert_eq!(perms2.net.query::<&str>(None), PermissionState::Prompt);
       assert_eq!(perms2.net.query(Some(&("127.0.0.1", Some(8000)))), PermissionState::Granted);
+      assert_eq!(perms1.udp.query::<&str>(None), PermissionState::Granted);
+      assert_eq!(perms2.udp.query::<&str>(None), PermissionState::Prompt);
+      assert_eq!(perms2.udp.query(Some(&("127.0.0.1", Some(53)))), PermissionState::Granted);
+      assert_eq!(perms2.udp.query(Some(&("127.0.0.1", Some(8000)))), PermissionState::Prompt);
       assert_eq!(perms1.env.query(None), PermissionState::Granted);
       assert_eq!(perms1.env.query(Some("HOME")), PermissionState::Granted);
       assert_eq!(perms2.env.query(None), PermissionState::Prompt);
@@ -3067,6 +4620,11 @@ mod tests {
       prompt_value.set(true);
       assert_eq!(perms.ffi.request(None), PermissionState::Denied);
       prompt_value.set(true);
+      assert_eq!(perms.unix.request(Some(Path::new("/foo"))), PermissionState::Granted);
+      assert_eq!(perms.unix.query(None), PermissionState::Prompt);
+      prompt_value.set(false);
+      assert_eq!(perms.unix.request(Some(Path::new("/foo/bar"))), PermissionState::Granted);
+      prompt_value.set(true);
       assert_eq!(perms.net.request(Some(&("127.0.0.1", None))), PermissionState::Granted);
       prompt_value.set(false);
       assert_eq!(perms.net.request(Some(&("127.0.0.1", Some(8000)))), PermissionState::Granted);
@@ -3120,6 +4678,14 @@ mod tests {
         )
         .unwrap()
       },
+      unix: UnaryPermission {
+        global_state: PermissionState::Prompt,
+        ..Permissions::new_unix(
+          &Some(vec![PathBuf::from("/foo"), PathBuf::from("/foo/baz")]),
+          false,
+        )
+        .unwrap()
+      },
       net: UnaryPermission {
         global_state: PermissionState::Prompt,
         ..Permissions::new_net(
@@ -3128,6 +4694,10 @@ mod tests {
         )
         .unwrap()
       },
+      udp: UnaryPermission {
+        global_state: PermissionState::Prompt,
+        ..Permissions::new_udp(&Some(svec!["127.0.0.1:53"]), false).unwrap()
+      },
       env: UnaryPermission {
         global_state: PermissionState::Prompt,
         ..Permissions::new_env(&Some(svec!["HOME"]), false).unwrap()
@@ -3140,6 +4710,7 @@ mod tests {
         global_state: PermissionState::Prompt,
         ..Permissions::new_run(&Some(svec!["deno"]), false).unwrap()
       },
+      run_args: Default::default(),
       hrtime: UnitPermission {
         state: PermissionState::Denied,
         ..Permissions::new_hrtime(false)
@@ -3156,9 +4727,14 @@ mod tests {
       assert_eq!(perms.ffi.revoke(Some(Path::new("/foo/bar"))), PermissionState::Prompt);
       assert_eq!(perms.ffi.query(Some(Path::new("/foo"))), PermissionState::Prompt);
       assert_eq!(perms.ffi.query(Some(Path::new("/foo/baz"))), PermissionState::Granted);
+      assert_eq!(perms.unix.revoke(Some(Path::new("/foo/bar"))), PermissionState::Prompt);
+      assert_eq!(perms.unix.query(Some(Path::new("/foo"))), PermissionState::Prompt);
+      assert_eq!(perms.unix.query(Some(Path::new("/foo/baz"))), PermissionState::Granted);
       assert_eq!(perms.net.revoke(Some(&("127.0.0.1", Some(9000)))), PermissionState::Prompt);
       assert_eq!(perms.net.query(Some(&("127.0.0.1", None))), PermissionState::Prompt);
       assert_eq!(perms.net.query(Some(&("127.0.0.1", Some(8000)))), PermissionState::Granted);
+      assert_eq!(perms.udp.revoke(Some(&("127.0.0.1", Some(53)))), PermissionState::Prompt);
+      assert_eq!(perms.udp.query(Some(&("127.0.0.1", Some(53)))), PermissionState::Prompt);
       assert_eq!(perms.env.revoke(Some("HOME")), PermissionState::Prompt);
       assert_eq!(perms.env.revoke(Some("hostname")), PermissionState::Prompt);
       assert_eq!(perms.run.revoke(Some("deno")), PermissionState::Prompt);
@@ -3173,10 +4749,13 @@ mod tests {
       read: Permissions::new_read(&None, true).unwrap(),
       write: Permissions::new_write(&None, true).unwrap(),
       net: Permissions::new_net(&None, true).unwrap(),
+      udp: Permissions::new_udp(&None, true).unwrap(),
       env: Permissions::new_env(&None, true).unwrap(),
       sys: Permissions::new_sys(&None, true).unwrap(),
       run: Permissions::new_run(&None, true).unwrap(),
+      run_args: Default::default(),
       ffi: Permissions::new_ffi(&None, true).unwrap(),
+      unix: Permissions::new_unix(&None, true).unwrap(),
       hrtime: Permissions::new_hrtime(false),
     };
 
@@ -3200,6 +4779,12 @@ mod tests {
     assert!(perms.ffi.check(Some(Path::new("/foo"))).is_ok());
     assert!(perms.ffi.check(Some(Path::new("/bar"))).is_err());
 
+    prompt_value.set(true);
+    assert!(perms.unix.check(Path::new("/foo"), None).is_ok());
+    prompt_value.set(false);
+    assert!(perms.unix.check(Path::new("/foo"), None).is_ok());
+    assert!(perms.unix.check(Path::new("/bar"), None).is_err());
+
     prompt_value.set(true);
     assert!(perms.net.check(&("127.0.0.1", Some(8000)), None).is_ok());
     prompt_value.set(false);
@@ -3209,6 +4794,12 @@ mod tests {
     assert!(perms.net.check(&("deno.land", Some(8000)), None).is_err());
     assert!(perms.net.check(&("deno.land", None), None).is_err());
 
+    prompt_value.set(true);
+    assert!(perms.udp.check(&("127.0.0.1", Some(53)), None).is_ok());
+    prompt_value.set(false);
+    assert!(perms.udp.check(&("127.0.0.1", Some(53)), None).is_ok());
+    assert!(perms.udp.check(&("127.0.0.1", Some(54)), None).is_err());
+
     prompt_value.set(true);
     assert!(perms.run.check("cat", None).is_ok());
     prompt_value.set(false);
@@ -3237,10 +4828,13 @@ mod tests {
       read: Permissions::new_read(&None, true).unwrap(),
       write: Permissions::new_write(&None, true).unwrap(),
       net: Permissions::new_net(&None, true).unwrap(),
+      udp: Permissions::new_udp(&None, true).unwrap(),
       env: Permissions::new_env(&None, true).unwrap(),
       sys: Permissions::new_sys(&None, true).unwrap(),
       run: Permissions::new_run(&None, true).unwrap(),
+      run_args: Default::default(),
       ffi: Permissions::new_ffi(&None, true).unwrap(),
+      unix: Permissions::new_unix(&None, true).unwrap(),
       hrtime: Permissions::new_hrtime(false),
     };
 
@@ -3280,6 +4874,12 @@ mod tests {
     assert!(perms.net.check(&("127.0.0.1", Some(8001)), None).is_ok());
     assert!(perms.net.check(&("deno.land", Some(8000)), None).is_ok());
 
+    prompt_value.set(false);
+    assert!(perms.udp.check(&("127.0.0.1", Some(53)), None).is_err());
+    prompt_value.set(true);
+    assert!(perms.udp.check(&("127.0.0.1", Some(53)), None).is_err());
+    assert!(perms.udp.check(&("127.0.0.1", Some(54)), None).is_ok());
+
     prompt_value.set(false);
     assert!(perms.run.check("cat", None).is_err());
     prompt_value.set(true);
@@ -3339,11 +4939,13 @@ mod tests {
         env: ChildUnaryPermissionArg::Inherit,
         hrtime: ChildUnitPermissionArg::Inherit,
         net: ChildUnaryPermissionArg::Inherit,
+        udp: ChildUnaryPermissionArg::Inherit,
         ffi: ChildUnaryPermissionArg::Inherit,
         read: ChildUnaryPermissionArg::Inherit,
         run: ChildUnaryPermissionArg::Inherit,
         sys: ChildUnaryPermissionArg::Inherit,
         write: ChildUnaryPermissionArg::Inherit,
+        unix: ChildUnaryPermissionArg::Inherit,
       }
     );
     assert_eq!(
@@ -3352,11 +4954,13 @@ mod tests {
         env: ChildUnaryPermissionArg::NotGranted,
         hrtime: ChildUnitPermissionArg::NotGranted,
         net: ChildUnaryPermissionArg::NotGranted,
+        udp: ChildUnaryPermissionArg::NotGranted,
         ffi: ChildUnaryPermissionArg::NotGranted,
         read: ChildUnaryPermissionArg::NotGranted,
         run: ChildUnaryPermissionArg::NotGranted,
         sys: ChildUnaryPermissionArg::NotGranted,
         write: ChildUnaryPermissionArg::NotGranted,
+        unix: ChildUnaryPermissionArg::NotGranted,
       }
     );
     assert_eq!(
@@ -3405,21 +5009,25 @@ mod tests {
       serde_json::from_value::<ChildPermissionsArg>(json!({
         "env": true,
         "net": true,
+        "udp": true,
         "ffi": true,
         "read": true,
         "run": true,
         "sys": true,
         "write": true,
+        "unix": true,
       }))
       .unwrap(),
       ChildPermissionsArg {
         env: ChildUnaryPermissionArg::Granted,
         net: ChildUnaryPermissionArg::Granted,
+        udp: ChildUnaryPermissionArg::Granted,
         ffi: ChildUnaryPermissionArg::Granted,
         read: ChildUnaryPermissionArg::Granted,
         run: ChildUnaryPermissionArg::Granted,
         sys: ChildUnaryPermissionArg::Granted,
         write: ChildUnaryPermissionArg::Granted,
+        unix: ChildUnaryPermissionArg::Granted,
         ..ChildPermissionsArg::none()
       }
     );
@@ -3427,21 +5035,25 @@ mod tests {
       serde_json::from_value::<ChildPermissionsArg>(json!({
         "env": false,
         "net": false,
+        "udp": false,
         "ffi": false,
         "read": false,
         "run": false,
         "sys": false,
         "write": false,
+        "unix": false,
       }))
       .unwrap(),
       ChildPermissionsArg {
         env: ChildUnaryPermissionArg::NotGranted,
         net: ChildUnaryPermissionArg::NotGranted,
+        udp: ChildUnaryPermissionArg::NotGranted,
         ffi: ChildUnaryPermissionArg::NotGranted,
         read: ChildUnaryPermissionArg::NotGranted,
         run: ChildUnaryPermissionArg::NotGranted,
         sys: ChildUnaryPermissionArg::NotGranted,
         write: ChildUnaryPermissionArg::NotGranted,
+        unix: ChildUnaryPermissionArg::NotGranted,
         ..ChildPermissionsArg::none()
       }
     );
@@ -3449,16 +5061,19 @@ mod tests {
       serde_json::from_value::<ChildPermissionsArg>(json!({
         "env": ["foo", "bar"],
         "net": ["foo", "bar:8000"],
+        "udp": ["foo:53"],
         "ffi": ["foo", "file:///bar/baz"],
         "read": ["foo", "file:///bar/baz"],
         "run": ["foo", "file:///bar/baz", "./qux"],
         "sys": ["hostname", "osRelease"],
         "write": ["foo", "file:///bar/baz"],
+        "unix": ["/run/app.sock"],
       }))
       .unwrap(),
       ChildPermissionsArg {
         env: ChildUnaryPermissionArg::GrantedList(svec!["foo", "bar"]),
         net: ChildUnaryPermissionArg::GrantedList(svec!["foo", "bar:8000"]),
+        udp: ChildUnaryPermissionArg::GrantedList(svec!["foo:53"]),
         ffi: ChildUnaryPermissionArg::GrantedList(svec![
           "foo",
           "file:///bar/baz"
@@ -3480,11 +5095,69 @@ mod tests {
           "foo",
           "file:///bar/baz"
         ]),
+        unix: ChildUnaryPermissionArg::GrantedList(svec!["/run/app.sock"]),
         ..ChildPermissionsArg::none()
       }
     );
   }
 
+  #[test]
+  fn test_worker_permissions_config() {
+    let config = WorkerPermissionsConfig {
+      default: Some(ChildPermissionsArg::none()),
+      presets: HashMap::from([(
+        "plugin".to_string(),
+        ChildPermissionsArg {
+          read: ChildUnaryPermissionArg::GrantedList(svec!["./plugins"]),
+          ..ChildPermissionsArg::none()
+        },
+      )]),
+      preload: vec![],
+    };
+    assert_eq!(
+      config.resolve(None).unwrap(),
+      Some(ChildPermissionsArg::none())
+    );
+    let arg: WorkerPermissionsArg =
+      serde_json::from_value(json!("plugin")).unwrap();
+    assert_eq!(
+      config.resolve(Some(arg)).unwrap(),
+      Some(ChildPermissionsArg {
+        read: ChildUnaryPermissionArg::GrantedList(svec!["./plugins"]),
+        ..ChildPermissionsArg::none()
+      })
+    );
+    let arg: WorkerPermissionsArg =
+      serde_json::from_value(json!("inherit")).unwrap();
+    assert_eq!(
+      config.resolve(Some(arg)).unwrap(),
+      Some(ChildPermissionsArg::inherit())
+    );
+    let arg: WorkerPermissionsArg =
+      serde_json::from_value(json!("unknown")).unwrap();
+    assert!(config.resolve(Some(arg)).is_err());
+  }
+
+  #[test]
+  fn test_validate_child_permissions() {
+    assert!(validate_child_permissions(&ChildPermissionsArg {
+      net: ChildUnaryPermissionArg::GrantedList(svec!["deno.land:8000"]),
+      read: ChildUnaryPermissionArg::GrantedList(svec!["./plugins"]),
+      ..ChildPermissionsArg::none()
+    })
+    .is_ok());
+    assert!(validate_child_permissions(&ChildPermissionsArg {
+      net: ChildUnaryPermissionArg::GrantedList(svec!["deno.land:port"]),
+      ..ChildPermissionsArg::none()
+    })
+    .is_err());
+    assert!(validate_child_permissions(&ChildPermissionsArg {
+      read: ChildUnaryPermissionArg::GrantedList(svec![""]),
+      ..ChildPermissionsArg::none()
+    })
+    .is_err());
+  }
+
   #[test]
   fn test_create_child_permissions() {
     set_prompter(Box::new(TestPrompter));
@@ -3,6 +3,7 @@
 use crate::colors;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
+use deno_core::v8;
 use once_cell::sync::Lazy;
 use std::fmt::Write;
 
@@ -16,6 +17,12 @@ fn strip_ansi_codes_and_ascii_control(s: &str) -> std::borrow::Cow<str> {
 
 pub const PERMISSION_EMOJI: &str = "⚠️";
 
+/// Maximum number of stack frames displayed in a permission prompt.
+const MAX_PROMPT_STACK_FRAMES: usize = 5;
+
+/// Maximum number of stack frames captured for a permission prompt.
+const PROMPT_STACK_FRAME_LIMIT: usize = 32;
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum PromptResponse {
   Allow,
@@ -60,6 +67,123 @@ pub fn set_prompt_callbacks(
 
 pub type PromptCallback = Box<dyn FnMut() + Send + Sync>;
 
+/// A JavaScript stack frame of the call that requested a permission.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PromptStackFrame {
+  pub function_name: Option<String>,
+  pub file_name: String,
+  pub line_number: usize,
+  pub column_number: usize,
+}
+
+impl std::fmt::Display for PromptStackFrame {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let location = format!(
+      "{}:{}:{}",
+      self.file_name, self.line_number, self.column_number
+    );
+    match &self.function_name {
+      Some(function_name) => write!(f, "{function_name} ({location})"),
+      None => write!(f, "{location}"),
+    }
+  }
+}
+
+/// Captures the JavaScript stack of the API call that triggered a permission
+/// prompt, through the scope of the op checking the permission. Frames of the
+/// runtime's own JavaScript are left out.
+fn capture_prompt_stack() -> Vec<PromptStackFrame> {
+  deno_core::with_current_op_scope(|scope| {
+    let stack = match v8::StackTrace::current_stack_trace(
+      scope,
+      PROMPT_STACK_FRAME_LIMIT,
+    ) {
+      Some(stack) => stack,
+      None => return vec![],
+    };
+    (0..stack.get_frame_count())
+      .filter_map(|i| {
+        let frame = stack.get_frame(scope, i)?;
+        let file_name = frame
+          .get_script_name_or_source_url(scope)?
+          .to_rust_string_lossy(scope);
+        if file_name.starts_with("ext:") {
+          return None;
+        }
+        let function_name = frame
+          .get_function_name(scope)
+          .map(|name| name.to_rust_string_lossy(scope))
+          .filter(|name| !name.is_empty());
+        Some(PromptStackFrame {
+          function_name,
+          file_name,
+          line_number: frame.get_line_number(),
+          column_number: frame.get_column(),
+        })
+      })
+      .collect()
+  })
+  .unwrap_or_default()
+}
+
+/// Returns a display name for the third party module a stack frame belongs
+/// to, if any. npm packages are named after the package while remote modules
+/// are named by their URL.
+fn dependency_name(file_name: &str) -> Option<String> {
+  fn package_name(rest: &str) -> Option<String> {
+    let mut parts = rest.split('/');
+    let first = parts.next().filter(|p| !p.is_empty())?;
+    if first.starts_with('@') {
+      Some(format!("{}/{}", first, parts.next()?))
+    } else {
+      Some(first.to_string())
+    }
+  }
+
+  if file_name.starts_with("http://") || file_name.starts_with("https://") {
+    return Some(file_name.to_string());
+  }
+  let file_name = file_name.replace('\\', "/");
+  if let Some(index) = file_name.find("/registry.npmjs.org/") {
+    let rest = &file_name[index + "/registry.npmjs.org/".len()..];
+    let name = package_name(rest)?;
+    let version = rest[name.len()..].trim_start_matches('/').split('/').next();
+    return Some(match version {
+      Some(version) if !version.is_empty() => format!("npm:{name}@{version}"),
+      _ => format!("npm:{name}"),
+    });
+  }
+  if let Some(index) = file_name.rfind("/node_modules/") {
+    let name = package_name(&file_name[index + "/node_modules/".len()..])?;
+    return Some(format!("npm:{name}"));
+  }
+  None
+}
+
+/// Formats the lines describing where a permission was requested from.
+fn format_stack_lines(stack: &[PromptStackFrame]) -> Vec<String> {
+  let mut lines = Vec::new();
+  if stack.is_empty() {
+    return lines;
+  }
+  if let Some(dependency) =
+    stack.iter().find_map(|f| dependency_name(&f.file_name))
+  {
+    lines.push(format!("Requested by dependency `{dependency}`."));
+  }
+  lines.push("Requested from:".to_string());
+  for frame in stack.iter().take(MAX_PROMPT_STACK_FRAMES) {
+    lines.push(format!("    at {frame}"));
+  }
+  if stack.len() > MAX_PROMPT_STACK_FRAMES {
+    lines.push(format!(
+      "    ... {} more",
+      stack.len() - MAX_PROMPT_STACK_FRAMES
+    ));
+  }
+  lines
+}
+
 pub trait PermissionPrompter: Send + Sync {
   fn prompt(
     &mut self,
@@ -72,6 +196,26 @@ pub trait PermissionPrompter: Send + Sync {
 
 pub struct TtyPrompter;
 
+/// Denies every permission request without prompting, for programs that
+/// can't be granted more permissions than they were started with.
+pub struct DenyPrompter;
+
+impl PermissionPrompter for DenyPrompter {
+  fn prompt(
+    &mut self,
+    _message: &str,
+    _name: &str,
+    _api_name: Option<&str>,
+    _is_unary: bool,
+  ) -> PromptResponse {
+    PromptResponse::Deny
+  }
+}
+
+pub fn set_prompter(prompter: Box<dyn PermissionPrompter>) {
+  *PERMISSION_PROMPTER.lock() = prompter;
+}
+
 impl PermissionPrompter for TtyPrompter {
   fn prompt(
     &mut self,
@@ -83,6 +227,7 @@ impl PermissionPrompter for TtyPrompter {
     if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stderr) {
       return PromptResponse::Deny;
     };
+    let stack = capture_prompt_stack();
 
     #[cfg(unix)]
     fn clear_stdin() -> Result<(), AnyError> {
@@ -215,6 +360,12 @@ impl PermissionPrompter for TtyPrompter {
     let message = strip_ansi_codes_and_ascii_control(message);
     let name = strip_ansi_codes_and_ascii_control(name);
     let api_name = api_name.map(strip_ansi_codes_and_ascii_control);
+    let stack_lines = format_stack_lines(&stack)
+      .into_iter()
+      .map(|line| strip_ansi_codes_and_ascii_control(&line).into_owned())
+      .collect::<Vec<_>>();
+    // number of lines to clear once the prompt is answered
+    let prompt_lines = 3 + usize::from(api_name.is_some()) + stack_lines.len();
 
     // print to stderr so that if stdout is piped this is still displayed.
     let opts: String = if is_unary {
@@ -233,6 +384,9 @@ impl PermissionPrompter for TtyPrompter {
       if let Some(api_name) = api_name.clone() {
         writeln!(&mut output, "├ Requested by `{api_name}` API.").unwrap();
       }
+      for line in &stack_lines {
+        writeln!(&mut output, "├ {}", colors::gray(line)).unwrap();
+      }
       let msg = format!("Run again with --allow-{name} to bypass this prompt.");
       writeln!(&mut output, "├ {}", colors::italic(&msg)).unwrap();
       write!(&mut output, "└ {}", colors::bold("Allow?")).unwrap();
@@ -254,19 +408,19 @@ impl PermissionPrompter for TtyPrompter {
       };
       match ch {
         'y' | 'Y' => {
-          clear_n_lines(if api_name.is_some() { 4 } else { 3 });
+          clear_n_lines(prompt_lines);
           let msg = format!("Granted {message}.");
           eprintln!("✅ {}", colors::bold(&msg));
           break PromptResponse::Allow;
         }
         'n' | 'N' => {
-          clear_n_lines(if api_name.is_some() { 4 } else { 3 });
+          clear_n_lines(prompt_lines);
           let msg = format!("Denied {message}.");
           eprintln!("❌ {}", colors::bold(&msg));
           break PromptResponse::Deny;
         }
         'A' if is_unary => {
-          clear_n_lines(if api_name.is_some() { 4 } else { 3 });
+          clear_n_lines(prompt_lines);
           let msg = format!("Granted all {name} access.");
           eprintln!("✅ {}", colors::bold(&msg));
           break PromptResponse::AllowAll;
@@ -325,7 +479,61 @@ pub mod tests {
     }
   }
 
-  pub fn set_prompter(prompter: Box<dyn PermissionPrompter>) {
-    *PERMISSION_PROMPTER.lock() = prompter;
+  #[test]
+  fn test_deny_prompter() {
+    assert_eq!(
+      DenyPrompter.prompt("read access to \"/foo\"", "read", None, true),
+      PromptResponse::Deny
+    );
+  }
+
+  #[test]
+  fn test_dependency_name() {
+    assert_eq!(
+      dependency_name("https://deno.land/x/oak@v12.0.0/mod.ts"),
+      Some("https://deno.land/x/oak@v12.0.0/mod.ts".to_string())
+    );
+    assert_eq!(
+      dependency_name(
+        "file:///home/user/.cache/deno/npm/registry.npmjs.org/chalk/5.2.0/source/index.js"
+      ),
+      Some("npm:chalk@5.2.0".to_string())
+    );
+    assert_eq!(
+      dependency_name(
+        "file:///home/user/.cache/deno/npm/registry.npmjs.org/@types/node/18.0.0/index.js"
+      ),
+      Some("npm:@types/node@18.0.0".to_string())
+    );
+    assert_eq!(
+      dependency_name("file:///project/node_modules/@scope/pkg/lib/index.js"),
+      Some("npm:@scope/pkg".to_string())
+    );
+    assert_eq!(dependency_name("file:///project/main.ts"), None);
+  }
+
+  #[test]
+  fn test_format_stack_lines() {
+    assert!(format_stack_lines(&[]).is_empty());
+    let frame =
+      |function_name: Option<&str>, file_name: &str| PromptStackFrame {
+        function_name: function_name.map(ToString::to_string),
+        file_name: file_name.to_string(),
+        line_number: 1,
+        column_number: 2,
+      };
+    let stack = vec![
+      frame(Some("readConfig"), "https://deno.land/x/foo/mod.ts"),
+      frame(None, "file:///project/main.ts"),
+    ];
+    assert_eq!(
+      format_stack_lines(&stack),
+      vec![
+        "Requested by dependency `https://deno.land/x/foo/mod.ts`.",
+        "Requested from:",
+        "    at readConfig (https://deno.land/x/foo/mod.ts:1:2)",
+        "    at file:///project/main.ts:1:2",
+      ]
+    );
   }
 }
@@ -0,0 +1,699 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Opt-in OS level sandboxing of the current process, applied in addition to
+//! the permission checks as defense in depth should V8 or an op be
+//! compromised. The sandbox can't be lifted once applied, so permissions
+//! granted later on through prompts are still restricted by it.
+//!
+//! Network access and running subprocesses are restricted everywhere but on
+//! Windows, where only subprocesses are. The file system is restricted to the
+//! paths granted through `--allow-write` and, when it lists paths,
+//! `--allow-read`. Without such a list, reading stays unrestricted, because
+//! the modules of the program may be read from anywhere. Paths that don't
+//! exist yet when the sandbox is applied can't be created.
+//!
+//! On Linux the file system is restricted through Landlock, which only
+//! applies to the calling thread and the threads it spawns from then on, so
+//! the sandbox is applied on the thread of every main worker.
+
+use crate::fs_util::resolve_from_cwd;
+use crate::permissions::PermissionsOptions;
+use deno_core::error::AnyError;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+/// The capabilities the OS sandbox leaves to the process.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SandboxOptions {
+  /// Allow creating internet sockets.
+  pub allow_net: bool,
+  /// Allow executing other programs.
+  pub allow_run: bool,
+  /// The paths that may be read, or `None` to not restrict reading.
+  pub read_paths: Option<Vec<PathBuf>>,
+  /// The paths that may be written, or `None` to not restrict writing.
+  pub write_paths: Option<Vec<PathBuf>>,
+}
+
+impl SandboxOptions {
+  /// Aligns the sandbox with the permissions granted up front.
+  pub fn from_permissions(options: &PermissionsOptions) -> Self {
+    let resolve = |paths: &Vec<PathBuf>| -> Vec<PathBuf> {
+      paths
+        .iter()
+        .filter_map(|path| resolve_from_cwd(path).ok())
+        .collect()
+    };
+    Self {
+      // UDP sockets are internet sockets too
+      allow_net: options.allow_net.is_some() || options.allow_udp.is_some(),
+      allow_run: options.allow_run.is_some(),
+      read_paths: match &options.allow_read {
+        Some(paths) if !paths.is_empty() => Some(resolve(paths)),
+        _ => None,
+      },
+      write_paths: match &options.allow_write {
+        Some(paths) if paths.is_empty() => None,
+        Some(paths) => Some(resolve(paths)),
+        None => Some(Vec::new()),
+      },
+    }
+  }
+
+  /// Keeps a path the runtime itself uses accessible, like its caches.
+  pub fn allow_runtime_path(&mut self, path: PathBuf) {
+    if let Some(read_paths) = &mut self.read_paths {
+      read_paths.push(path.clone());
+    }
+    if let Some(write_paths) = &mut self.write_paths {
+      write_paths.push(path);
+    }
+  }
+}
+
+static SANDBOX_APPLIED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(target_os = "linux")]
+thread_local! {
+  static THREAD_SANDBOX_APPLIED: std::cell::Cell<bool> =
+    std::cell::Cell::new(false);
+}
+
+/// Applies the OS sandbox to the current process and all of its threads, as
+/// well as the parts of it that only apply to the calling thread. Subsequent
+/// calls only apply the latter to threads that weren't sandboxed yet.
+pub fn apply_os_sandbox(options: &SandboxOptions) -> Result<(), AnyError> {
+  if !SANDBOX_APPLIED.swap(true, Ordering::SeqCst) {
+    log::debug!("Applying OS sandbox: {:?}", options);
+    imp::apply(options)?;
+  }
+  #[cfg(target_os = "linux")]
+  if !THREAD_SANDBOX_APPLIED.with(|applied| applied.replace(true)) {
+    imp::apply_to_current_thread(options)?;
+  }
+  Ok(())
+}
+
+/// seccomp-bpf filter and Landlock ruleset, see
+/// `Documentation/userspace-api/seccomp_filter.rst` and
+/// `Documentation/userspace-api/landlock.rst` in the Linux sources.
+#[cfg(target_os = "linux")]
+mod imp {
+  use super::SandboxOptions;
+  use deno_core::anyhow::bail;
+  use deno_core::anyhow::Context;
+  use deno_core::error::AnyError;
+  use std::os::fd::AsRawFd;
+  use std::os::fd::FromRawFd;
+  use std::os::fd::OwnedFd;
+  use std::os::unix::fs::OpenOptionsExt;
+  use std::path::Path;
+
+  // classic BPF instruction fields, see `linux/filter.h`
+  const BPF_LD: u16 = 0x00;
+  const BPF_W: u16 = 0x00;
+  const BPF_ABS: u16 = 0x20;
+  const BPF_JMP: u16 = 0x05;
+  const BPF_JEQ: u16 = 0x10;
+  const BPF_JGE: u16 = 0x30;
+  const BPF_K: u16 = 0x00;
+  const BPF_RET: u16 = 0x06;
+
+  const SECCOMP_SET_MODE_FILTER: libc::c_ulong = 1;
+  const SECCOMP_FILTER_FLAG_TSYNC: libc::c_ulong = 1;
+  const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+  const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+  const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+
+  // offsets into `struct seccomp_data`
+  const NR_OFFSET: u32 = 0;
+  const ARCH_OFFSET: u32 = 4;
+  const ARG0_OFFSET: u32 = 16;
+
+  /// Set on syscall numbers of the x32 ABI, which would otherwise bypass the
+  /// filter on x86_64.
+  const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+  #[cfg(target_arch = "x86_64")]
+  const AUDIT_ARCH: Option<u32> = Some(0xc000_003e);
+  #[cfg(target_arch = "aarch64")]
+  const AUDIT_ARCH: Option<u32> = Some(0xc000_00b7);
+  #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+  const AUDIT_ARCH: Option<u32> = None;
+
+  /// Syscalls Deno never needs, which are commonly used to tamper with other
+  /// processes or the system.
+  const ALWAYS_DENIED_SYSCALLS: &[libc::c_long] = &[
+    libc::SYS_ptrace,
+    libc::SYS_process_vm_readv,
+    libc::SYS_process_vm_writev,
+    libc::SYS_kexec_load,
+    libc::SYS_init_module,
+    libc::SYS_finit_module,
+    libc::SYS_delete_module,
+    libc::SYS_mount,
+    libc::SYS_umount2,
+    libc::SYS_pivot_root,
+    libc::SYS_swapon,
+    libc::SYS_swapoff,
+    libc::SYS_reboot,
+    libc::SYS_bpf,
+    libc::SYS_perf_event_open,
+    libc::SYS_userfaultfd,
+    libc::SYS_add_key,
+    libc::SYS_request_key,
+    libc::SYS_keyctl,
+    // io_uring performs syscalls on behalf of the process which the filter
+    // never sees
+    libc::SYS_io_uring_setup,
+  ];
+
+  const RUN_SYSCALLS: &[libc::c_long] = &[libc::SYS_execve, libc::SYS_execveat];
+
+  const NET_SOCKET_FAMILIES: &[libc::c_int] =
+    &[libc::AF_INET, libc::AF_INET6, libc::AF_PACKET];
+
+  fn stmt(code: u16, k: u32) -> libc::sock_filter {
+    libc::sock_filter {
+      code,
+      jt: 0,
+      jf: 0,
+      k,
+    }
+  }
+
+  fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter { code, jt, jf, k }
+  }
+
+  fn ret_errno(errno: libc::c_int) -> libc::sock_filter {
+    stmt(BPF_RET | BPF_K, SECCOMP_RET_ERRNO | (errno as u32 & 0xffff))
+  }
+
+  fn build_filter(
+    audit_arch: u32,
+    options: &SandboxOptions,
+  ) -> Vec<libc::sock_filter> {
+    let mut filter = vec![
+      stmt(BPF_LD | BPF_W | BPF_ABS, ARCH_OFFSET),
+      // syscall numbers differ between architectures
+      jump(BPF_JMP | BPF_JEQ | BPF_K, audit_arch, 1, 0),
+      stmt(BPF_RET | BPF_K, SECCOMP_RET_KILL_PROCESS),
+      stmt(BPF_LD | BPF_W | BPF_ABS, NR_OFFSET),
+    ];
+    if cfg!(target_arch = "x86_64") {
+      filter.push(jump(BPF_JMP | BPF_JGE | BPF_K, X32_SYSCALL_BIT, 0, 1));
+      filter.push(ret_errno(libc::EPERM));
+    }
+    let mut denied = ALWAYS_DENIED_SYSCALLS.to_vec();
+    if !options.allow_run {
+      denied.extend(RUN_SYSCALLS);
+    }
+    for nr in denied {
+      filter.push(jump(BPF_JMP | BPF_JEQ | BPF_K, nr as u32, 0, 1));
+      filter.push(ret_errno(libc::EPERM));
+    }
+    if !options.allow_net {
+      // skip over the argument checks below unless this is `socket(2)`
+      let skip = 2 + 2 * NET_SOCKET_FAMILIES.len() as u8;
+      filter.push(jump(
+        BPF_JMP | BPF_JEQ | BPF_K,
+        libc::SYS_socket as u32,
+        0,
+        skip,
+      ));
+      filter.push(stmt(BPF_LD | BPF_W | BPF_ABS, ARG0_OFFSET));
+      for family in NET_SOCKET_FAMILIES {
+        filter.push(jump(BPF_JMP | BPF_JEQ | BPF_K, *family as u32, 0, 1));
+        filter.push(ret_errno(libc::EACCES));
+      }
+      filter.push(stmt(BPF_RET | BPF_K, SECCOMP_RET_ALLOW));
+    }
+    filter.push(stmt(BPF_RET | BPF_K, SECCOMP_RET_ALLOW));
+    filter
+  }
+
+  pub fn apply(options: &SandboxOptions) -> Result<(), AnyError> {
+    let audit_arch = match AUDIT_ARCH {
+      Some(audit_arch) => audit_arch,
+      None => bail!("OS sandboxing is not supported on this architecture"),
+    };
+    let mut filter = build_filter(audit_arch, options);
+    let program = libc::sock_fprog {
+      len: filter.len() as libc::c_ushort,
+      filter: filter.as_mut_ptr(),
+    };
+    // SAFETY: `program` points to a valid filter that outlives the calls,
+    // the kernel copies it when installing it.
+    unsafe {
+      // required to install a filter without CAP_SYS_ADMIN
+      if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+        return Err(std::io::Error::last_os_error().into());
+      }
+      // TSYNC applies the filter to all threads of the process
+      if libc::syscall(
+        libc::SYS_seccomp,
+        SECCOMP_SET_MODE_FILTER,
+        SECCOMP_FILTER_FLAG_TSYNC,
+        &program as *const libc::sock_fprog,
+      ) != 0
+      {
+        return Err(std::io::Error::last_os_error().into());
+      }
+    }
+    Ok(())
+  }
+
+  // Landlock constants and structs, see `linux/landlock.h`
+  const LANDLOCK_CREATE_RULESET_VERSION: libc::c_uint = 1 << 0;
+  const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+  const LANDLOCK_ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+  const LANDLOCK_ACCESS_FS_READ_FILE: u64 = 1 << 2;
+  const LANDLOCK_ACCESS_FS_READ_DIR: u64 = 1 << 3;
+  const LANDLOCK_ACCESS_FS_REMOVE_DIR: u64 = 1 << 4;
+  const LANDLOCK_ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+  const LANDLOCK_ACCESS_FS_MAKE_CHAR: u64 = 1 << 6;
+  const LANDLOCK_ACCESS_FS_MAKE_DIR: u64 = 1 << 7;
+  const LANDLOCK_ACCESS_FS_MAKE_REG: u64 = 1 << 8;
+  const LANDLOCK_ACCESS_FS_MAKE_SOCK: u64 = 1 << 9;
+  const LANDLOCK_ACCESS_FS_MAKE_FIFO: u64 = 1 << 10;
+  const LANDLOCK_ACCESS_FS_MAKE_BLOCK: u64 = 1 << 11;
+  const LANDLOCK_ACCESS_FS_MAKE_SYM: u64 = 1 << 12;
+  /// Since ABI version 2.
+  const LANDLOCK_ACCESS_FS_REFER: u64 = 1 << 13;
+  /// Since ABI version 3.
+  const LANDLOCK_ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+
+  #[repr(C)]
+  struct LandlockRulesetAttr {
+    handled_access_fs: u64,
+  }
+
+  #[repr(C, packed)]
+  struct LandlockPathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: libc::c_int,
+  }
+
+  /// Executing programs is left to the seccomp filter.
+  const READ_ACCESS: u64 =
+    LANDLOCK_ACCESS_FS_READ_FILE | LANDLOCK_ACCESS_FS_READ_DIR;
+  const WRITE_ACCESS: u64 = LANDLOCK_ACCESS_FS_WRITE_FILE
+    | LANDLOCK_ACCESS_FS_REMOVE_DIR
+    | LANDLOCK_ACCESS_FS_REMOVE_FILE
+    | LANDLOCK_ACCESS_FS_MAKE_CHAR
+    | LANDLOCK_ACCESS_FS_MAKE_DIR
+    | LANDLOCK_ACCESS_FS_MAKE_REG
+    | LANDLOCK_ACCESS_FS_MAKE_SOCK
+    | LANDLOCK_ACCESS_FS_MAKE_FIFO
+    | LANDLOCK_ACCESS_FS_MAKE_BLOCK
+    | LANDLOCK_ACCESS_FS_MAKE_SYM
+    | LANDLOCK_ACCESS_FS_REFER
+    | LANDLOCK_ACCESS_FS_TRUNCATE;
+  /// The access rights that apply to files, the others only apply to
+  /// directories.
+  const FILE_ACCESS: u64 = LANDLOCK_ACCESS_FS_WRITE_FILE
+    | LANDLOCK_ACCESS_FS_READ_FILE
+    | LANDLOCK_ACCESS_FS_TRUNCATE;
+
+  /// Shared libraries, certificates, name resolution and the process'
+  /// own information, which are read when reading is restricted.
+  const SYSTEM_READ_PATHS: &[&str] = &[
+    "/bin", "/usr", "/lib", "/lib64", "/etc", "/dev", "/proc", "/sys",
+  ];
+  const SYSTEM_WRITE_PATHS: &[&str] = &["/dev/null", "/dev/tty"];
+
+  /// The access rights supported by the running kernel.
+  fn supported_access(abi_version: libc::c_long) -> u64 {
+    let mut access = READ_ACCESS | WRITE_ACCESS;
+    if abi_version < 2 {
+      access &= !LANDLOCK_ACCESS_FS_REFER;
+    }
+    if abi_version < 3 {
+      access &= !LANDLOCK_ACCESS_FS_TRUNCATE;
+    }
+    access
+  }
+
+  /// Restricts the file system access of the calling thread and of the
+  /// threads it spawns from then on.
+  pub fn apply_to_current_thread(
+    options: &SandboxOptions,
+  ) -> Result<(), AnyError> {
+    if options.read_paths.is_none() && options.write_paths.is_none() {
+      return Ok(());
+    }
+    // SAFETY: querying the ABI version doesn't read the attributes
+    let abi_version = unsafe {
+      libc::syscall(
+        libc::SYS_landlock_create_ruleset,
+        std::ptr::null::<LandlockRulesetAttr>(),
+        0usize,
+        LANDLOCK_CREATE_RULESET_VERSION,
+      )
+    };
+    if abi_version < 1 {
+      bail!(
+        "Restricting the file system requires Landlock, which is not available: {}. Grant unrestricted file system access with --allow-read and --allow-write to sandbox the process without it.",
+        std::io::Error::last_os_error()
+      );
+    }
+    let supported_access = supported_access(abi_version);
+    let mut rules: Vec<(&Path, u64)> = Vec::new();
+    if let Some(read_paths) = &options.read_paths {
+      rules.extend(read_paths.iter().map(|path| (path.as_path(), READ_ACCESS)));
+      rules.extend(
+        SYSTEM_READ_PATHS
+          .iter()
+          .map(|path| (Path::new(*path), READ_ACCESS)),
+      );
+    }
+    if let Some(write_paths) = &options.write_paths {
+      rules.extend(
+        write_paths
+          .iter()
+          .map(|path| (path.as_path(), WRITE_ACCESS)),
+      );
+      rules.extend(
+        SYSTEM_WRITE_PATHS
+          .iter()
+          .map(|path| (Path::new(*path), WRITE_ACCESS)),
+      );
+    }
+    let handled_access = rules
+      .iter()
+      .fold(0, |handled, (_, access)| handled | access)
+      & supported_access;
+
+    let attr = LandlockRulesetAttr {
+      handled_access_fs: handled_access,
+    };
+    // SAFETY: `attr` outlives the call and its size is passed along
+    let ruleset_fd = unsafe {
+      libc::syscall(
+        libc::SYS_landlock_create_ruleset,
+        &attr as *const LandlockRulesetAttr,
+        std::mem::size_of::<LandlockRulesetAttr>(),
+        0,
+      )
+    };
+    if ruleset_fd < 0 {
+      return Err(std::io::Error::last_os_error().into());
+    }
+    // SAFETY: the file descriptor was just created and is owned by nothing
+    // else
+    let ruleset = unsafe { OwnedFd::from_raw_fd(ruleset_fd as libc::c_int) };
+    for (path, access) in rules {
+      add_path_rule(&ruleset, path, access & handled_access)?;
+    }
+    // SAFETY: plain syscalls without pointers
+    unsafe {
+      // required to restrict a thread without CAP_SYS_ADMIN
+      if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+        return Err(std::io::Error::last_os_error().into());
+      }
+      if libc::syscall(libc::SYS_landlock_restrict_self, ruleset.as_raw_fd(), 0)
+        != 0
+      {
+        return Err(std::io::Error::last_os_error().into());
+      }
+    }
+    Ok(())
+  }
+
+  /// Allows the access to the file or directory at the path, which is
+  /// skipped when it doesn't exist.
+  #[allow(clippy::disallowed_methods)]
+  fn add_path_rule(
+    ruleset: &OwnedFd,
+    path: &Path,
+    access: u64,
+  ) -> Result<(), AnyError> {
+    let file = match std::fs::OpenOptions::new()
+      .read(true)
+      .custom_flags(libc::O_PATH | libc::O_CLOEXEC)
+      .open(path)
+    {
+      Ok(file) => file,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Failed opening '{}' for the OS sandbox.", path.display())
+        })
+      }
+    };
+    let access = if file.metadata()?.is_dir() {
+      access
+    } else {
+      access & FILE_ACCESS
+    };
+    let attr = LandlockPathBeneathAttr {
+      allowed_access: access,
+      parent_fd: file.as_raw_fd(),
+    };
+    // SAFETY: `attr` outlives the call, the kernel copies it
+    let result = unsafe {
+      libc::syscall(
+        libc::SYS_landlock_add_rule,
+        ruleset.as_raw_fd(),
+        LANDLOCK_RULE_PATH_BENEATH,
+        &attr as *const LandlockPathBeneathAttr,
+        0,
+      )
+    };
+    if result != 0 {
+      return Err(std::io::Error::last_os_error()).with_context(|| {
+        format!("Failed adding '{}' to the OS sandbox.", path.display())
+      });
+    }
+    Ok(())
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_jumps_in_bounds() {
+      for allow_net in [true, false] {
+        for allow_run in [true, false] {
+          let filter = build_filter(
+            0xc000_003e,
+            &SandboxOptions {
+              allow_net,
+              allow_run,
+              ..Default::default()
+            },
+          );
+          for (i, instruction) in filter.iter().enumerate() {
+            if instruction.code & 0x07 == BPF_JMP {
+              let target = i + 1 + instruction.jt.max(instruction.jf) as usize;
+              assert!(target < filter.len());
+            }
+          }
+          let last = filter.last().unwrap();
+          assert_eq!(last.code, BPF_RET | BPF_K);
+          assert_eq!(last.k, SECCOMP_RET_ALLOW);
+        }
+      }
+    }
+
+    #[test]
+    #[allow(clippy::disallowed_methods)]
+    fn restricts_file_system_of_thread() {
+      let allowed_dir = test_util::TempDir::new();
+      allowed_dir.write("allowed.txt", "allowed");
+      let denied_dir = test_util::TempDir::new();
+      denied_dir.write("denied.txt", "denied");
+      let allowed_file = allowed_dir.path().join("allowed.txt");
+      let denied_file = denied_dir.path().join("denied.txt");
+      let options = SandboxOptions {
+        read_paths: Some(vec![allowed_dir.path().to_path_buf()]),
+        write_paths: Some(vec![allowed_dir.path().to_path_buf()]),
+        ..Default::default()
+      };
+      std::thread::spawn(move || {
+        if let Err(err) = apply_to_current_thread(&options) {
+          // eg. when running in a container without Landlock
+          eprintln!("Skipping, Landlock is not available: {err:#}");
+          return;
+        }
+        assert_eq!(std::fs::read_to_string(&allowed_file).unwrap(), "allowed");
+        assert!(std::fs::read_to_string(&denied_file).is_err());
+        assert!(std::fs::write(&allowed_file, "written").is_ok());
+        assert!(std::fs::write(&denied_file, "written").is_err());
+      })
+      .join()
+      .unwrap();
+      // other threads aren't restricted
+      assert_eq!(denied_dir.read_to_string("denied.txt"), "denied");
+    }
+  }
+}
+
+/// Seatbelt profile applied through `sandbox_init(3)`.
+#[cfg(target_os = "macos")]
+mod imp {
+  use super::SandboxOptions;
+  use deno_core::anyhow::bail;
+  use deno_core::error::AnyError;
+  use std::ffi::CStr;
+  use std::ffi::CString;
+  use std::os::raw::c_char;
+  use std::os::raw::c_int;
+  use std::path::Path;
+  use std::path::PathBuf;
+
+  /// Shared libraries, certificates and name resolution, which are read
+  /// when reading is restricted.
+  const SYSTEM_READ_PATHS: &[&str] = &[
+    "/System",
+    "/Library",
+    "/usr",
+    "/bin",
+    "/dev",
+    "/private/etc",
+    "/private/var/db",
+  ];
+  const SYSTEM_WRITE_PATHS: &[&str] = &["/dev/null", "/dev/tty"];
+
+  extern "C" {
+    fn sandbox_init(
+      profile: *const c_char,
+      flags: u64,
+      errorbuf: *mut *mut c_char,
+    ) -> c_int;
+    fn sandbox_free_error(errorbuf: *mut c_char);
+  }
+
+  /// Seatbelt matches the paths with symlinks resolved, eg. `/tmp` is
+  /// `/private/tmp`.
+  #[allow(clippy::disallowed_methods)]
+  fn canonicalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+  }
+
+  fn subpath_filters(paths: &[PathBuf], system_paths: &[&str]) -> String {
+    paths
+      .iter()
+      .map(|path| canonicalize(path))
+      .chain(system_paths.iter().map(PathBuf::from))
+      .map(|path| {
+        let path = path.to_string_lossy();
+        format!(
+          " (subpath \"{}\")",
+          path.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+      })
+      .collect()
+  }
+
+  fn build_profile(options: &SandboxOptions) -> String {
+    let mut profile = String::from("(version 1)\n(allow default)\n");
+    if !options.allow_run {
+      profile.push_str("(deny process-exec)\n");
+    }
+    if !options.allow_net {
+      profile.push_str("(deny network-outbound (remote ip \"*:*\"))\n");
+      profile.push_str("(deny network-bind (local ip \"*:*\"))\n");
+    }
+    // the later rules take precedence
+    if let Some(read_paths) = &options.read_paths {
+      profile.push_str("(deny file-read*)\n");
+      // resolving the allowed paths requires the metadata of their parents
+      profile.push_str("(allow file-read-metadata)\n");
+      profile.push_str(&format!(
+        "(allow file-read*{})\n",
+        subpath_filters(read_paths, SYSTEM_READ_PATHS)
+      ));
+    }
+    if let Some(write_paths) = &options.write_paths {
+      profile.push_str("(deny file-write*)\n");
+      profile.push_str(&format!(
+        "(allow file-write*{})\n",
+        subpath_filters(write_paths, SYSTEM_WRITE_PATHS)
+      ));
+    }
+    profile
+  }
+
+  pub fn apply(options: &SandboxOptions) -> Result<(), AnyError> {
+    let profile = CString::new(build_profile(options)).unwrap();
+    let mut error: *mut c_char = std::ptr::null_mut();
+    // SAFETY: `profile` is a valid C string and `error` is only read and
+    // freed when set by `sandbox_init`.
+    unsafe {
+      if sandbox_init(profile.as_ptr(), 0, &mut error) != 0 {
+        let message = if error.is_null() {
+          "unknown error".to_string()
+        } else {
+          let message = CStr::from_ptr(error).to_string_lossy().to_string();
+          sandbox_free_error(error);
+          message
+        };
+        bail!("Failed applying OS sandbox: {}", message);
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Job object limiting the number of active processes. Windows has no way to
+/// move a running process into an AppContainer, which would be required to
+/// also restrict network and file system access, so only subprocesses are
+/// restricted.
+#[cfg(windows)]
+mod imp {
+  use super::SandboxOptions;
+  use deno_core::error::AnyError;
+  use winapi::shared::minwindef::LPVOID;
+  use winapi::um::jobapi2::AssignProcessToJobObject;
+  use winapi::um::jobapi2::CreateJobObjectW;
+  use winapi::um::jobapi2::SetInformationJobObject;
+  use winapi::um::processthreadsapi::GetCurrentProcess;
+  use winapi::um::winnt::JobObjectExtendedLimitInformation;
+  use winapi::um::winnt::JOBOBJECT_EXTENDED_LIMIT_INFORMATION;
+  use winapi::um::winnt::JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+
+  pub fn apply(options: &SandboxOptions) -> Result<(), AnyError> {
+    if options.allow_run {
+      return Ok(());
+    }
+    // SAFETY: winapi calls, `info` is a zero initialized plain C struct
+    unsafe {
+      let job = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
+      if job.is_null() {
+        return Err(std::io::Error::last_os_error().into());
+      }
+      let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+      info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+      info.BasicLimitInformation.ActiveProcessLimit = 1;
+      if SetInformationJobObject(
+        job,
+        JobObjectExtendedLimitInformation,
+        &mut info as *mut _ as LPVOID,
+        std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+      ) == 0
+      {
+        return Err(std::io::Error::last_os_error().into());
+      }
+      if AssignProcessToJobObject(job, GetCurrentProcess()) == 0 {
+        return Err(std::io::Error::last_os_error().into());
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod imp {
+  use super::SandboxOptions;
+  use deno_core::anyhow::bail;
+  use deno_core::error::AnyError;
+
+  pub fn apply(_options: &SandboxOptions) -> Result<(), AnyError> {
+    bail!("OS sandboxing is not supported on this platform")
+  }
+}
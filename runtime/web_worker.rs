@@ -3,6 +3,7 @@ use crate::colors;
 use crate::inspector_server::InspectorServer;
 use crate::ops;
 use crate::permissions::PermissionsContainer;
+use crate::permissions::WorkerPermissionsConfig;
 use crate::tokio_util::create_and_run_current_thread;
 use crate::worker::FormatJsErrorFn;
 use crate::BootstrapOptions;
@@ -335,10 +336,14 @@ pub struct WebWorkerOptions {
   pub fs: Arc<dyn FileSystem>,
   pub module_loader: Rc<dyn ModuleLoader>,
   pub npm_resolver: Option<Arc<dyn deno_node::NpmResolver>>,
+  /// Custom conditions used when resolving the `exports` and `imports` of
+  /// npm packages, in addition to the default ones.
+  pub node_conditions: Vec<String>,
   pub create_web_worker_cb: Arc<ops::worker_host::CreateWebWorkerCb>,
   pub preload_module_cb: Arc<ops::worker_host::WorkerEventCb>,
   pub pre_execute_module_cb: Arc<ops::worker_host::WorkerEventCb>,
   pub format_js_error_fn: Option<Arc<FormatJsErrorFn>>,
+  pub worker_permissions_config: Arc<WorkerPermissionsConfig>,
   pub source_map_getter: Option<Box<dyn SourceMapGetter>>,
   pub worker_type: WebWorkerType,
   pub maybe_inspector_server: Option<Arc<InspectorServer>>,
@@ -449,6 +454,7 @@ impl WebWorker {
       deno_node::deno_node::init_ops::<PermissionsContainer>(
         options.npm_resolver,
         options.fs,
+        options.node_conditions,
       ),
       // Runtime ops that are always initialized for WebWorkers
       ops::web_worker::deno_web_worker::init_ops(),
@@ -458,6 +464,7 @@ impl WebWorker {
         options.preload_module_cb.clone(),
         options.pre_execute_module_cb.clone(),
         options.format_js_error_fn.clone(),
+        options.worker_permissions_config.clone(),
       ),
       ops::fs_events::deno_fs_events::init_ops(),
       ops::os::deno_os_worker::init_ops(),
@@ -758,6 +765,7 @@ pub fn run_web_worker(
   worker: WebWorker,
   specifier: ModuleSpecifier,
   mut maybe_source_code: Option<String>,
+  preload_modules: Vec<ModuleSpecifier>,
   preload_module_cb: Arc<ops::worker_host::WorkerEventCb>,
   pre_execute_module_cb: Arc<ops::worker_host::WorkerEventCb>,
   format_js_error_fn: Option<Arc<FormatJsErrorFn>>,
@@ -784,6 +792,19 @@ pub fn run_web_worker(
       }
     };
 
+    // Preload modules are evaluated in order before the worker's own code.
+    for preload_module in &preload_modules {
+      if let Err(e) = worker.execute_side_module(preload_module).await {
+        print_worker_error(&e, &name, format_js_error_fn.as_deref());
+        internal_handle
+          .post_event(WorkerControlEvent::TerminalError(e))
+          .expect("Failed to post message to host");
+
+        // Failure to execute script is a terminal error, bye, bye.
+        return Ok(());
+      }
+    }
+
     // Execute provided source code immediately
     let result = if let Some(source_code) = maybe_source_code.take() {
       let r = worker.execute_script(located_script_name!(), source_code.into());
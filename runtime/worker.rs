@@ -41,10 +41,43 @@ use log::debug;
 use crate::inspector_server::InspectorServer;
 use crate::ops;
 use crate::permissions::PermissionsContainer;
+use crate::permissions::WorkerPermissionsConfig;
 use crate::BootstrapOptions;
 
 pub type FormatJsErrorFn = dyn Fn(&JsError) -> String + Sync + Send;
 
+/// Writes a heap snapshot the first time the heap gets close to its limit, to
+/// help diagnosing what filled it up once the process runs out of memory.
+/// Taking the snapshot allocates on the heap itself, so the limit is raised
+/// once and the snapshot is only written from an interrupt, after V8 applied
+/// the raised limit.
+fn add_heap_snapshot_on_oom_callback(js_runtime: &mut JsRuntime) {
+  extern "C" fn write_snapshot(
+    isolate: &mut v8::Isolate,
+    _data: *mut std::ffi::c_void,
+  ) {
+    let path = ops::runtime::default_heap_snapshot_path();
+    eprintln!(
+      "Heap is close to its limit, writing a heap snapshot to {}",
+      path.display()
+    );
+    if let Err(err) = ops::runtime::write_heap_snapshot(isolate, &path) {
+      eprintln!("Failed to write heap snapshot: {err}");
+    }
+  }
+
+  let isolate_handle = js_runtime.v8_isolate().thread_safe_handle();
+  let mut raised = false;
+  js_runtime.add_near_heap_limit_callback(move |current_limit, _| {
+    if raised {
+      return current_limit;
+    }
+    raised = true;
+    isolate_handle.request_interrupt(write_snapshot, std::ptr::null_mut());
+    current_limit * 2
+  });
+}
+
 #[derive(Clone, Default)]
 pub struct ExitCode(Arc<AtomicI32>);
 
@@ -87,6 +120,9 @@ pub struct WorkerOptions {
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
   pub seed: Option<u64>,
+  /// Write a heap snapshot to the current directory once the heap gets close
+  /// to its limit, before running out of memory.
+  pub heap_snapshot_on_oom: bool,
 
   pub fs: Arc<dyn FileSystem>,
   /// Implementation of `ModuleLoader` which will be
@@ -96,11 +132,17 @@ pub struct WorkerOptions {
   /// executed tries to load modules.
   pub module_loader: Rc<dyn ModuleLoader>,
   pub npm_resolver: Option<Arc<dyn deno_node::NpmResolver>>,
+  /// Custom conditions used when resolving the `exports` and `imports` of
+  /// npm packages, in addition to the default ones.
+  pub node_conditions: Vec<String>,
   // Callbacks invoked when creating new instance of WebWorker
   pub create_web_worker_cb: Arc<ops::worker_host::CreateWebWorkerCb>,
   pub web_worker_preload_module_cb: Arc<ops::worker_host::WorkerEventCb>,
   pub web_worker_pre_execute_module_cb: Arc<ops::worker_host::WorkerEventCb>,
   pub format_js_error_fn: Option<Arc<FormatJsErrorFn>>,
+  /// Default permissions and named presets for web workers created by this
+  /// worker.
+  pub worker_permissions_config: Arc<WorkerPermissionsConfig>,
 
   /// Source map reference for errors.
   pub source_map_getter: Option<Box<dyn SourceMapGetter>>,
@@ -153,6 +195,7 @@ impl Default for WorkerOptions {
       fs: Arc::new(deno_fs::RealFs),
       module_loader: Rc::new(FsModuleLoader),
       seed: None,
+      heap_snapshot_on_oom: false,
       unsafely_ignore_certificate_errors: Default::default(),
       should_break_on_first_statement: Default::default(),
       should_wait_for_inspector_session: Default::default(),
@@ -160,6 +203,7 @@ impl Default for WorkerOptions {
       shared_array_buffer_store: Default::default(),
       maybe_inspector_server: Default::default(),
       format_js_error_fn: Default::default(),
+      worker_permissions_config: Default::default(),
       get_error_class_fn: Default::default(),
       origin_storage_dir: Default::default(),
       cache_storage_dir: Default::default(),
@@ -167,6 +211,7 @@ impl Default for WorkerOptions {
       source_map_getter: Default::default(),
       root_cert_store_provider: Default::default(),
       npm_resolver: Default::default(),
+      node_conditions: Default::default(),
       blob_store: Default::default(),
       extensions: Default::default(),
       startup_snapshot: Default::default(),
@@ -274,6 +319,7 @@ impl MainWorker {
       deno_node::deno_node::init_ops::<PermissionsContainer>(
         options.npm_resolver,
         options.fs,
+        options.node_conditions,
       ),
       // Ops from this crate
       ops::runtime::deno_runtime::init_ops(main_module.clone()),
@@ -282,6 +328,7 @@ impl MainWorker {
         options.web_worker_preload_module_cb.clone(),
         options.web_worker_pre_execute_module_cb.clone(),
         options.format_js_error_fn.clone(),
+        options.worker_permissions_config.clone(),
       ),
       ops::fs_events::deno_fs_events::init_ops(),
       ops::os::deno_os::init_ops(exit_code.clone()),
@@ -320,6 +367,10 @@ impl MainWorker {
       ..Default::default()
     });
 
+    if options.heap_snapshot_on_oom {
+      add_heap_snapshot_on_oom_callback(&mut js_runtime);
+    }
+
     if let Some(server) = options.maybe_inspector_server.clone() {
       server.register_inspector(
         main_module.to_string(),
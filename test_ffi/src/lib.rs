@@ -244,6 +244,23 @@ pub extern "C" fn call_stored_function_thread_safe() {
   });
 }
 
+#[no_mangle]
+pub extern "C" fn call_fn_ptr_thread_safe_and_wait(
+  func: Option<extern "C" fn(u8, f64)>,
+) {
+  // Blocks the calling thread until the foreign thread is done, which would
+  // deadlock if the callback waited for the isolate thread.
+  std::thread::spawn(move || {
+    let func = func.unwrap();
+    for i in 0..3 {
+      func(i, i as f64 / 2.0);
+    }
+    println!("Foreign thread done");
+  })
+  .join()
+  .unwrap();
+}
+
 #[no_mangle]
 pub extern "C" fn call_stored_function_thread_safe_and_log() {
   std::thread::spawn(move || {
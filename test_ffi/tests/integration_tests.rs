@@ -128,6 +128,39 @@ fn basic() {
   assert_eq!(stderr, "");
 }
 
+#[test]
+fn nonblocking_callback() {
+  build();
+
+  let output = deno_cmd()
+    .arg("run")
+    .arg("--allow-ffi")
+    .arg("--allow-read")
+    .arg("--unstable")
+    .arg("--quiet")
+    .arg("tests/nonblocking_callback_test.js")
+    .env("NO_COLOR", "1")
+    .output()
+    .unwrap();
+  let stdout = std::str::from_utf8(&output.stdout).unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  if !output.status.success() {
+    println!("stdout {stdout}");
+    println!("stderr {stderr}");
+  }
+  println!("{:?}", output.status);
+  assert!(output.status.success());
+  let expected = "\
+    Invalid UnsafeCallback, nonblocking callbacks must return void\n\
+    Foreign thread done\n\
+    Foreign call returned\n\
+    Callback called with 0 0\n\
+    Callback called with 1 0.5\n\
+    Callback called with 2 1\n";
+  assert_eq!(stdout, expected);
+  assert_eq!(stderr, "");
+}
+
 #[test]
 fn symbol_types() {
   build();
@@ -0,0 +1,43 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+// deno-lint-ignore-file
+
+const targetDir = Deno.execPath().replace(/[^\/\\]+$/, "");
+const [libPrefix, libSuffix] = {
+  darwin: ["lib", "dylib"],
+  linux: ["lib", "so"],
+  windows: ["", "dll"],
+}[Deno.build.os];
+const libPath = `${targetDir}/${libPrefix}test_ffi.${libSuffix}`;
+
+const dylib = Deno.dlopen(libPath, {
+  call_fn_ptr_thread_safe_and_wait: {
+    parameters: ["function"],
+    result: "void",
+  },
+});
+
+try {
+  new Deno.UnsafeCallback(
+    { parameters: [], result: "u8", nonblocking: true },
+    () => 0,
+  );
+} catch (error) {
+  console.log(error.message);
+}
+
+let calls = 0;
+const callback = Deno.UnsafeCallback.threadSafe(
+  { parameters: ["u8", "f64"], result: "void", nonblocking: true },
+  (a, b) => {
+    console.log(`Callback called with ${a} ${b}`);
+    if (++calls === 3) {
+      queueMicrotask(() => {
+        callback.close();
+        dylib.close();
+      });
+    }
+  },
+);
+
+dylib.symbols.call_fn_ptr_thread_safe_and_wait(callback.pointer);
+console.log("Foreign call returned");
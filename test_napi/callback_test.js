@@ -36,3 +36,8 @@ Deno.test("napi callback run with args & recv", function () {
   );
   assertEquals(result, 69);
 });
+
+Deno.test("napi make callback with async context", function () {
+  const result = callback.test_callback_make_callback((a, b) => a + b, [1, 2]);
+  assertEquals(result, 3);
+});
@@ -87,6 +87,53 @@ extern "C" fn test_callback_run_with_recv(
   result
 }
 
+/// `test_callback_make_callback((a, b) => a + b, [1, 2])` => 3, called through
+/// `napi_make_callback` with an async context.
+extern "C" fn test_callback_make_callback(
+  env: napi_env,
+  info: napi_callback_info,
+) -> napi_value {
+  let (args, argc, _) = napi_get_callback_info!(env, info, 2);
+  assert_eq!(argc, 2);
+
+  let mut len = 0;
+  assert_napi_ok!(napi_get_array_length(env, args[1], &mut len));
+
+  let mut argv = Vec::with_capacity(len as usize);
+  for index in 0..len {
+    let mut value: napi_value = ptr::null_mut();
+    assert_napi_ok!(napi_get_element(env, args[1], index, &mut value));
+    argv.push(value);
+  }
+
+  let mut resource: napi_value = ptr::null_mut();
+  assert_napi_ok!(napi_create_object(env, &mut resource));
+  let mut resource_name: napi_value = ptr::null_mut();
+  assert_napi_ok!(napi_create_string_utf8(
+    env,
+    "test".as_ptr() as *const std::os::raw::c_char,
+    4,
+    &mut resource_name,
+  ));
+  let mut context: napi_async_context = ptr::null_mut();
+  assert_napi_ok!(napi_async_init(env, resource, resource_name, &mut context));
+  assert!(!context.is_null());
+
+  let mut result: napi_value = ptr::null_mut();
+  assert_napi_ok!(napi_make_callback(
+    env,
+    context,
+    resource,
+    args[0],
+    argv.len(),
+    argv.as_ptr(),
+    &mut result,
+  ));
+
+  assert_napi_ok!(napi_async_destroy(env, context));
+  result
+}
+
 pub fn init(env: napi_env, exports: napi_value) {
   let properties = &[
     napi_new_property!(env, "test_callback_run", test_callback_run),
@@ -95,6 +142,11 @@ pub fn init(env: napi_env, exports: napi_value) {
       "test_callback_run_with_recv",
       test_callback_run_with_recv
     ),
+    napi_new_property!(
+      env,
+      "test_callback_make_callback",
+      test_callback_make_callback
+    ),
   ];
 
   assert_napi_ok!(napi_define_properties(
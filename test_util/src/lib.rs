@@ -1081,6 +1081,9 @@ async fn main_server(
       );
       Ok(res)
     }
+    (&hyper::Method::POST, path) if path.starts_with("/api/scopes/") => {
+      publish_package(req).await
+    }
     (_, "/echo_accept") => {
       let accept = req.headers().get("accept").map(|v| v.to_str().unwrap());
       let res = Response::new(Body::from(
@@ -1158,6 +1161,50 @@ async fn main_server(
   };
 }
 
+/// Accepts a package uploaded by `deno publish` when it's authenticated with
+/// the test token and its tarball contains exactly the files of its manifest.
+async fn publish_package(
+  req: Request<Body>,
+) -> Result<Response<Body>, hyper::http::Error> {
+  let is_authorized = req
+    .headers()
+    .get("authorization")
+    .map(|value| value.as_bytes() == b"Bearer publish-token")
+    .unwrap_or(false);
+  if !is_authorized {
+    return Response::builder()
+      .status(StatusCode::UNAUTHORIZED)
+      .body(Body::from("Invalid token"));
+  }
+  let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+  let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+  let tarball = base64::decode(body["tarball"].as_str().unwrap()).unwrap();
+  let mut archive =
+    tar::Archive::new(flate2::read::GzDecoder::new(tarball.as_slice()));
+  let mut tarball_paths = archive
+    .entries()
+    .unwrap()
+    .map(|entry| format!("/{}", entry.unwrap().path().unwrap().display()))
+    .collect::<Vec<_>>();
+  tarball_paths.sort();
+  let mut manifest_paths = body["manifest"]["files"]
+    .as_object()
+    .unwrap()
+    .keys()
+    .cloned()
+    .collect::<Vec<_>>();
+  manifest_paths.sort();
+  if tarball_paths != manifest_paths {
+    return Response::builder()
+      .status(StatusCode::BAD_REQUEST)
+      .body(Body::from("The tarball doesn't match the manifest"));
+  }
+  Response::builder()
+    .status(StatusCode::OK)
+    .header("content-type", "application/json")
+    .body(Body::from("{}"))
+}
+
 fn handle_custom_npm_registry_path(
   path: &str,
 ) -> Result<Option<Response<Body>>, anyhow::Error> {